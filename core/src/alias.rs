@@ -0,0 +1,93 @@
+//! Further wire names of a command, for `alias`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(alias = "get_note", alias_client)]
+//! pub fn load_note(id: u64) -> Note {}
+//! ```
+//!
+//! Frontend bundles built before a rename still invoke the old name. Each
+//! alias gets a second backend command with the same body registered under
+//! it, which `bridge_handler!` registers next to the command. With
+//! `alias_client`, the client also gets `#[deprecated]` functions named
+//! after the aliases, calling the current name.
+
+use proc_macro2::Span;
+use syn::ext::IdentExt;
+use syn::{ItemFn, LitStr};
+
+use crate::attrs::BridgeArgs;
+
+/// Reject aliases clashing with the command's own name, and aliases that
+/// can't name client functions with `alias_client`.
+pub fn check_aliases(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let call_site = Span::call_site();
+    if args.alias_client && args.aliases.is_empty() {
+        return Err(syn::Error::new(
+            call_site,
+            "`alias_client` needs at least one `alias`",
+        ));
+    }
+    let command_name = args.command_name(input);
+    for alias in &args.aliases {
+        if *alias == command_name {
+            return Err(syn::Error::new(
+                call_site,
+                format!("`{}` is already the command's name", alias),
+            ));
+        }
+        if args.alias_client && syn::parse_str::<syn::Ident>(alias).is_err() {
+            return Err(syn::Error::new(
+                call_site,
+                format!(
+                    "`alias_client` needs aliases that are identifiers, `{}` isn't",
+                    alias
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Name of the backend command registered under the alias at `index`.
+pub fn alias_command_ident(input: &ItemFn, index: usize) -> syn::Ident {
+    syn::Ident::new(
+        &format!("__tauri_alias_{}_{}", input.sig.ident.unraw(), index),
+        Span::call_site(),
+    )
+}
+
+/// The backend command of the alias at `index`: the function under its
+/// hidden name.
+pub fn alias_command(input: &ItemFn, index: usize) -> ItemFn {
+    let mut alias = input.clone();
+    alias.sig.ident = alias_command_ident(input, index);
+    alias
+}
+
+/// The function and options the deprecated client of `alias` is generated
+/// from: the function named after the alias, sent under the current name.
+pub fn alias_client(input: &ItemFn, args: &BridgeArgs, alias: &str) -> (ItemFn, BridgeArgs) {
+    let mut client = input.clone();
+    client.sig.ident = syn::Ident::new(alias, input.sig.ident.span());
+    client
+        .attrs
+        .retain(|attr| !attr.path().is_ident("deprecated"));
+    let note = LitStr::new(
+        &format!("renamed to `{}`", input.sig.ident.unraw()),
+        Span::call_site(),
+    );
+    client
+        .attrs
+        .push(syn::parse_quote!(#[deprecated(note = #note)]));
+
+    let mut client_args = args.clone();
+    client_args.rename = Some(
+        args.rename
+            .clone()
+            .unwrap_or_else(|| input.sig.ident.unraw().to_string()),
+    );
+    client_args.client_names = Default::default();
+    client_args.aliases = Vec::new();
+    client_args.alias_client = false;
+    (client, client_args)
+}
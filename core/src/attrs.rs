@@ -0,0 +1,1369 @@
+//! Parsing of `#[tauri_bridge(...)]` attribute arguments.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, Expr, ItemFn, Lit, Meta, Path, Token, Type};
+
+use crate::config::CrateConfig;
+use crate::types::{is_byte_vec, raw_bytes_type};
+
+/// Options passed to `#[tauri_bridge(...)]`.
+///
+/// Unset options fall back to the crate-level defaults from
+/// `[package.metadata.tauri-bridge]` and then to the built-in defaults.
+#[derive(Clone, Default)]
+pub struct BridgeArgs {
+    /// Predicate gating the backend half, e.g. `backend_cfg(feature = "backend")`.
+    pub backend_cfg: Option<Meta>,
+    /// Predicate gating the client half, e.g. `client_cfg(feature = "client")`.
+    pub client_cfg: Option<Meta>,
+    /// Module the client is included into with `bridge_include!`,
+    /// e.g. `client_mod = crate::generated`.
+    pub client_mod: Option<Path>,
+    /// `extern`: the command is implemented elsewhere, only generate the client.
+    pub declaration_only: bool,
+    /// Wire command name used instead of the function name.
+    pub rename: Option<String>,
+    /// `namespace = "users"`: prefix of the wire name, as in `users.get`.
+    pub namespace: Option<String>,
+    /// `alias = "old_name"`: further wire names the backend also serves,
+    /// e.g. the name before a rename. May be repeated.
+    pub aliases: Vec<String>,
+    /// `alias_client`: also generate deprecated client functions named after
+    /// the aliases.
+    pub alias_client: bool,
+    /// Command version; versions after 1 suffix the wire name (`greet@2`).
+    pub version: Option<u32>,
+    /// `log_deprecated`: print a notice when a `#[deprecated]` command is invoked.
+    pub log_deprecated: bool,
+    /// `debug_log`: log arguments, responses and durations of calls in
+    /// debug builds.
+    pub debug_log: bool,
+    /// `log_slow`: report calls slower than the threshold of
+    /// `tauri_bridge_runtime::slow`.
+    pub log_slow: bool,
+    /// `non_send`: run the async backend on a blocking thread, so its
+    /// future doesn't need to be `Send`.
+    pub non_send: bool,
+    /// `rebind`: wait for `tauri_bridge_runtime::connection` to be ready
+    /// and re-issue calls cut off by a reconnect.
+    pub rebind: bool,
+    /// `replayable`: client calls can be recorded and replayed with
+    /// `tauri_bridge_runtime::replay`.
+    pub replayable: bool,
+    /// `metrics`: record calls, errors and durations in the registry of
+    /// `tauri_bridge_runtime::metrics`.
+    pub metrics: bool,
+    /// `layers`: run the backend between the hooks of the app's managed
+    /// `tauri_bridge_runtime::layer::Layers`.
+    pub layers: bool,
+    /// `serialize_on = "argument"`: the backend runs calls with the same
+    /// value of `argument` one after the other, in arrival order.
+    pub serialize_on: Option<String>,
+    /// `into_error = Type` or `map_err = "Type::function"`: the error type
+    /// sent instead of the function's and how errors are converted to it.
+    pub error_conversion: Option<ErrorConversion>,
+    /// `raw_response` (or `js_value`): client functions return the response
+    /// as a `wasm_bindgen::JsValue` without deserializing it.
+    pub raw_response: bool,
+    /// `unwrap_envelope`: client functions decode the response envelope
+    /// (e.g. `ApiResponse<T>`) and return its `Result`.
+    pub unwrap_envelope: bool,
+    /// `rejections`: invoke through `crate::try_invoke`, which returns
+    /// rejected invokes as `Err`.
+    pub rejections: bool,
+    /// `translate_errors`: pass the messages of failed calls through the
+    /// translator of `tauri_bridge_runtime::messages`.
+    pub translate_errors: bool,
+    /// `cached`: the plain client functions read through the
+    /// `tauri_bridge_runtime::query` cache, keyed by the wire name.
+    pub cached: bool,
+    /// `cached(swr)`: cached responses are returned right away and refreshed
+    /// in the background (stale-while-revalidate).
+    pub swr: bool,
+    /// `cached(persist)`: responses are also persisted through
+    /// `tauri_bridge_runtime::persist`, surviving reloads of the webview.
+    pub persist: bool,
+    /// `invalidates("get_users", ..)`: query keys whose cached responses are
+    /// dropped after each call.
+    pub invalidates: Vec<String>,
+    /// `sync_cache = "startup"`: the client fetches the response once with
+    /// an `init_<name>` function, and the plain function reads it back
+    /// synchronously.
+    pub startup_cache: bool,
+    /// `bootstrap`: the response is fetched at startup together with those
+    /// of the other commands listed in `bridge_bootstrap!`.
+    pub bootstrap: bool,
+    /// `catch_panic`: respond with backend panics as
+    /// `tauri_bridge_runtime::panic::BridgeError::Panicked`.
+    pub catch_panic: bool,
+    /// `owned_params`: client functions take owned parameters instead of
+    /// borrowing like the backend.
+    pub owned_params: bool,
+    /// `into_params`: client functions take `impl Into<String>` for `String`
+    /// and `impl IntoIterator<Item = impl Into<String>>` for `Vec<String>`.
+    pub into_params: bool,
+    /// `own_args`: the args struct holds owned values and is exported.
+    pub own_args: bool,
+    /// `strict`: reject types that misbehave across IPC.
+    pub strict: bool,
+    /// `by_value`: send `&mut` arguments by value and write the modified
+    /// values returned by the backend back on the client.
+    pub by_value: bool,
+    /// `map_as = "entries"`: send the map response as an array of
+    /// `[key, value]` pairs, keeping non-string keys.
+    pub map_entries: bool,
+    /// `floats = "error" | "null" | "string"`: how non-finite floats in the
+    /// arguments and response are sent.
+    pub floats: Option<FloatPolicy>,
+    /// `json_compatible`: serialize the arguments on the client with
+    /// `serde_wasm_bindgen::Serializer::json_compatible()`. Also set for
+    /// commands sending tagged types of the crate.
+    pub json_compatible: bool,
+    /// `with_options`: also generate `try_<name>_with_options`.
+    pub with_options: bool,
+    /// `signed`: pass arguments and responses through the
+    /// `tauri_bridge_runtime::signing` hooks on the client.
+    pub signed: bool,
+    /// `offline_queue(capacity = .., timeout_ms = ..)`: wait for the Tauri
+    /// transport instead of failing while it's missing.
+    pub offline_queue: Option<OfflineQueue>,
+    /// `circuit_breaker(threshold = .., cooldown_ms = ..)`: fail fast after
+    /// repeated failures until a cooldown elapsed.
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// `priority = "low" | "normal"`: schedule calls on the client so
+    /// low-priority traffic stays out of the way of interactive commands.
+    pub priority: Option<Priority>,
+    /// `incremental`: the backend streams batches over a `Channel<T>`
+    /// parameter; also generate `try_<name>_incremental`.
+    pub incremental: bool,
+    /// `chunked(chunk_size = ..)`: send responses larger than `chunk_size`
+    /// bytes of JSON in chunks over a channel.
+    pub chunked: Option<Chunked>,
+    /// `compress = "gzip"`: compress serialized arguments and responses.
+    pub compress: Option<Compression>,
+    /// `min_size = ..`: smallest JSON payload `compress` compresses.
+    pub min_size: Option<usize>,
+    /// `encrypt`: seal serialized arguments and responses under the key of
+    /// the `tauri_bridge_runtime::encrypt` provider.
+    pub encrypt: bool,
+    /// `raw`: send the response as raw bytes (`tauri::ipc::Response`).
+    pub raw: bool,
+    /// `json_bytes`: send `Vec<u8>` responses as JSON arrays of numbers
+    /// instead of raw bytes.
+    pub json_bytes: bool,
+    /// `max_payload = "1MB"`: largest JSON size of the arguments, checked
+    /// by the client before sending.
+    pub max_payload: Option<usize>,
+    /// `verify_schema`: send the hash of the command's parameters and
+    /// response, for the backend to reject calls from other builds.
+    pub verify_schema: bool,
+    /// `platforms("desktop", ..)`: the only platforms the command exists on.
+    pub platforms: Option<Vec<Platform>>,
+    /// `detect_tauri` or `detect_tauri(fallback = function)`: outside of
+    /// Tauri, fail without invoking, or invoke through `function` instead.
+    pub detect_tauri: Option<TauriDetection>,
+    /// `demo = "function"`: outside of Tauri, the client returns what
+    /// `function` returns for the arguments instead of invoking.
+    pub demo: Option<Path>,
+    /// `client_names(try_fn = .., args = ..)`: names for generated client
+    /// items that would collide with user items.
+    pub client_names: ClientNames,
+    /// `crate_paths(serde = "..", serde_wasm_bindgen = "..")`: paths the
+    /// generated client reaches renamed dependencies under.
+    pub crate_paths: CratePaths,
+    /// `concrete(T = SqliteStore, ..)`: the types a generic service impl
+    /// block is bridged with.
+    pub concrete: Vec<(syn::Ident, Type)>,
+    /// `client_attrs(allow, ..)`: names of the function's attributes that are
+    /// also put on the client functions and args struct.
+    pub client_attrs: Vec<Path>,
+    /// `sidecar = "name"`: run the body in the sidecar registered under
+    /// `name`, which the command forwards calls to.
+    pub sidecar: Option<String>,
+    /// `transaction` or `transaction(undo = function)`: the command can run
+    /// as a step of `bridge_transaction!`, undone by `function` on rollback.
+    pub transaction: Option<TransactionStep>,
+    /// `validate_response = "function"`: the client hands the response to
+    /// `function` and fails with `ResponseError::Invalid` when it rejects it.
+    pub validate_response: Option<Path>,
+    /// `test_harness`: generate a `cfg(test)` dispatch function calling the
+    /// backend with JSON arguments, for `bridge_dispatch!`.
+    pub test_harness: bool,
+    /// `selfcheck`: in debug builds, generate the hidden functions passing
+    /// samples of the argument and response types through both sides, for
+    /// `bridge_selfcheck!`.
+    pub selfcheck: bool,
+    /// `proptest`: generate `cfg(test)` round-trip property tests of the
+    /// args and response types. Needs the `proptest` feature.
+    pub proptest: bool,
+    /// `feature = "name"`: Cargo feature gating both halves and the
+    /// command's `bridge_handler!` entry.
+    pub feature: Option<String>,
+    /// Predicates of `#[cfg(..)]` attributes on the bridged function, which
+    /// gate both halves. Taken from the item, not an option.
+    pub item_cfgs: Vec<Meta>,
+}
+
+/// A platform of the `platforms` option, named like its `target_os`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Platform {
+    Windows,
+    Macos,
+    Linux,
+    Ios,
+    Android,
+}
+
+impl Platform {
+    /// The platforms named by `name`; `desktop` and `mobile` name groups.
+    pub fn from_name(name: &str) -> Option<&'static [Platform]> {
+        match name {
+            "desktop" => Some(&[Platform::Windows, Platform::Macos, Platform::Linux]),
+            "mobile" => Some(&[Platform::Ios, Platform::Android]),
+            "windows" => Some(&[Platform::Windows]),
+            "macos" => Some(&[Platform::Macos]),
+            "linux" => Some(&[Platform::Linux]),
+            "ios" => Some(&[Platform::Ios]),
+            "android" => Some(&[Platform::Android]),
+            _ => None,
+        }
+    }
+
+    /// The `target_os` value of the platform.
+    pub fn target_os(self) -> &'static str {
+        match self {
+            Platform::Windows => "windows",
+            Platform::Macos => "macos",
+            Platform::Linux => "linux",
+            Platform::Ios => "ios",
+            Platform::Android => "android",
+        }
+    }
+}
+
+/// Algorithm of the `compress` option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    Gzip,
+}
+
+/// Encoding of non-finite floats chosen with the `floats` option.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FloatPolicy {
+    /// Fail serializing NaN and infinities.
+    Error,
+    /// Send them as `null`, decoding `null` floats as NaN.
+    Null,
+    /// Send them as `"NaN"`, `"Infinity"` and `"-Infinity"`.
+    String,
+}
+
+impl FloatPolicy {
+    /// Parse a policy name as written in the attribute.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(FloatPolicy::Error),
+            "null" => Some(FloatPolicy::Null),
+            "string" => Some(FloatPolicy::String),
+            _ => None,
+        }
+    }
+}
+
+/// Conversion of command errors to the error type sent to the client.
+#[derive(Clone)]
+pub enum ErrorConversion {
+    /// `into_error = Type`: through `Into<Type>`.
+    Into(Path),
+    /// `map_err = "Type::function"`: through the given function.
+    Map(Path),
+}
+
+impl ErrorConversion {
+    /// The error type sent to the client.
+    pub fn error_type(&self) -> TokenStream2 {
+        let call_site = Span::call_site();
+        match self {
+            ErrorConversion::Into(ty) => quote_spanned! {call_site=> #ty },
+            ErrorConversion::Map(function) => {
+                let mut ty = function.clone();
+                ty.segments.pop();
+                ty.segments.pop_punct();
+                quote_spanned! {call_site=> #ty }
+            }
+        }
+    }
+
+    /// The function converting an error.
+    pub fn function(&self) -> TokenStream2 {
+        let call_site = Span::call_site();
+        match self {
+            ErrorConversion::Into(ty) => {
+                quote_spanned! {call_site=> <_ as ::core::convert::Into<#ty>>::into }
+            }
+            ErrorConversion::Map(function) => quote_spanned! {call_site=> #function },
+        }
+    }
+}
+
+/// Settings of the `chunked` option.
+#[derive(Clone)]
+pub struct Chunked {
+    /// Largest response sent inline, and the size of each chunk.
+    pub chunk_size: usize,
+}
+
+impl Default for Chunked {
+    fn default() -> Self {
+        Chunked {
+            chunk_size: 1_048_576,
+        }
+    }
+}
+
+/// Client scheduling priority of a command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Priority {
+    Low,
+    Normal,
+}
+
+impl Priority {
+    /// Parse a priority name as written in the attribute or metadata.
+    pub fn from_name(name: &str) -> Option<Priority> {
+        match name {
+            "low" => Some(Priority::Low),
+            "normal" => Some(Priority::Normal),
+            _ => None,
+        }
+    }
+}
+
+/// Settings of the `offline_queue` option.
+#[derive(Clone)]
+pub struct OfflineQueue {
+    /// Calls of the command that may wait at once.
+    pub capacity: usize,
+    /// How long a call may wait for the transport.
+    pub timeout_ms: u64,
+}
+
+impl Default for OfflineQueue {
+    fn default() -> Self {
+        OfflineQueue {
+            capacity: 32,
+            timeout_ms: 30_000,
+        }
+    }
+}
+
+/// Settings of the `client_names` option.
+#[derive(Clone, Default)]
+pub struct ClientNames {
+    /// Replaces `try_<name>`, also as the base of its variants
+    /// (`<try_fn>_to`, `<try_fn>_with_options`, `<try_fn>_incremental`).
+    pub try_fn: Option<syn::Ident>,
+    /// Replaces the `<Name>Args` struct.
+    pub args: Option<syn::Ident>,
+}
+
+/// Settings of the `crate_paths` option.
+#[derive(Clone, Default)]
+pub struct CratePaths {
+    /// Replaces `serde`, also in `#[serde(crate = "..")]` of derives.
+    pub serde: Option<Path>,
+    /// Replaces `serde_wasm_bindgen`.
+    pub serde_wasm_bindgen: Option<Path>,
+}
+
+impl CratePaths {
+    /// Parse a path given as a string, e.g. `"my_serde"`.
+    pub fn parse_path(path: &str) -> Option<Path> {
+        syn::parse_str(path).ok()
+    }
+}
+
+/// Settings of the `transaction` option.
+#[derive(Clone, Default)]
+pub struct TransactionStep {
+    /// Async function taking the step's response, awaited when a later
+    /// step of the transaction fails.
+    pub undo: Option<Path>,
+}
+
+/// Settings of the `detect_tauri` option.
+#[derive(Clone, Default)]
+pub struct TauriDetection {
+    /// Async function with the signature of `crate::invoke` (or
+    /// `crate::try_invoke` with `rejections`), called instead of it outside
+    /// of Tauri.
+    pub fallback: Option<Path>,
+}
+
+/// Settings of the `circuit_breaker` option.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    /// Consecutive failures that open the circuit.
+    pub threshold: u32,
+    /// How long the circuit stays open.
+    pub cooldown_ms: u64,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker {
+            threshold: 5,
+            cooldown_ms: 10_000,
+        }
+    }
+}
+
+impl Parse for BridgeArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = BridgeArgs::default();
+        let mut min_size = None;
+
+        while !input.is_empty() {
+            // `extern` is a keyword and can't be parsed as a `Meta`
+            if input.peek(Token![extern]) {
+                input.parse::<Token![extern]>()?;
+                args.declaration_only = true;
+            } else {
+                let meta: Meta = input.parse()?;
+                if meta.path().is_ident("min_size") {
+                    min_size = Some(meta.clone());
+                }
+                args.apply(meta)?;
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        if let Some(min_size) = min_size
+            && args.compress.is_none()
+        {
+            return Err(syn::Error::new_spanned(
+                min_size,
+                "`min_size` only applies together with `compress`",
+            ));
+        }
+
+        Ok(args)
+    }
+}
+
+impl BridgeArgs {
+    /// Apply a single `name`, `name = value` or `name(...)` option.
+    pub fn apply(&mut self, meta: Meta) -> syn::Result<()> {
+        let name = meta
+            .path()
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .unwrap_or_default();
+
+        match name.as_str() {
+            "alias" => {
+                let alias = parse_wire_name(&meta, "aliases")?;
+                self.aliases.push(alias);
+            }
+            "alias_client" => self.alias_client = parse_flag(&meta)?,
+            "backend_cfg" => self.backend_cfg = Some(parse_cfg_predicate(&meta)?),
+            "bootstrap" => self.bootstrap = parse_flag(&meta)?,
+            "by_value" => self.by_value = parse_flag(&meta)?,
+            "cached" => {
+                (self.swr, self.persist) = parse_cached(&meta)?;
+                self.cached = true;
+            }
+            "catch_panic" => self.catch_panic = parse_flag(&meta)?,
+            "chunked" => self.chunked = Some(parse_chunked(&meta)?),
+            "circuit_breaker" => self.circuit_breaker = Some(parse_circuit_breaker(&meta)?),
+            "client_attrs" => self.client_attrs = parse_attr_names(&meta)?,
+            "client_cfg" => self.client_cfg = Some(parse_cfg_predicate(&meta)?),
+            "client_mod" => self.client_mod = Some(parse_path_value(&meta)?),
+            "client_names" => self.client_names = parse_client_names(&meta)?,
+            "concrete" => self.concrete = parse_concrete(&meta)?,
+            "crate_paths" => self.crate_paths = parse_crate_paths(&meta)?,
+            "compress" => {
+                self.compress = match parse_str_value(&meta)?.as_str() {
+                    "gzip" => Some(Compression::Gzip),
+                    _ => return Err(syn::Error::new_spanned(meta, "expected `\"gzip\"`")),
+                };
+            }
+            "debug_log" => self.debug_log = parse_flag(&meta)?,
+            "demo" => {
+                let function = syn::parse_str(&parse_str_value(&meta)?).map_err(|_| {
+                    syn::Error::new_spanned(
+                        &meta,
+                        "expected a function path, e.g. `demo = \"demo_impls::greet\"`",
+                    )
+                })?;
+                self.demo = Some(function);
+            }
+            "detect_tauri" => self.detect_tauri = Some(parse_detect_tauri(&meta)?),
+            "encrypt" => self.encrypt = parse_flag(&meta)?,
+            "feature" => self.feature = Some(parse_str_value(&meta)?),
+            "floats" => {
+                let policy = parse_str_value(&meta)?;
+                self.floats = Some(FloatPolicy::from_name(&policy).ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &meta,
+                        "expected `\"error\"`, `\"null\"` or `\"string\"`",
+                    )
+                })?);
+            }
+            "incremental" => self.incremental = parse_flag(&meta)?,
+            "into_error" | "map_err" => {
+                if self.error_conversion.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "use either `into_error` or `map_err`",
+                    ));
+                }
+                self.error_conversion = Some(parse_error_conversion(&meta)?);
+            }
+            "invalidates" => self.invalidates = parse_query_keys(&meta)?,
+            "into_params" => self.into_params = parse_flag(&meta)?,
+            "raw_response" | "js_value" => self.raw_response = parse_flag(&meta)?,
+            "layers" => self.layers = parse_flag(&meta)?,
+            "map_as" => {
+                self.map_entries = match parse_str_value(&meta)?.as_str() {
+                    "entries" => true,
+                    _ => return Err(syn::Error::new_spanned(meta, "expected `\"entries\"`")),
+                };
+            }
+            "max_payload" => self.max_payload = Some(parse_byte_size(&meta)?),
+            "json_compatible" => self.json_compatible = parse_flag(&meta)?,
+            "verify_schema" => self.verify_schema = parse_flag(&meta)?,
+            "log_deprecated" => self.log_deprecated = parse_flag(&meta)?,
+            "log_slow" => self.log_slow = parse_flag(&meta)?,
+            "metrics" => self.metrics = parse_flag(&meta)?,
+            "min_size" => self.min_size = Some(parse_int_value(&meta)? as usize),
+            "namespace" => {
+                let namespace = parse_str_value(&meta)?;
+                let valid = !namespace.is_empty()
+                    && namespace.split('.').all(|part| {
+                        !part.is_empty()
+                            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    });
+                if !valid {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "expected dot-separated identifiers, e.g. `namespace = \"admin.users\"`",
+                    ));
+                }
+                self.namespace = Some(namespace);
+            }
+            "offline_queue" => self.offline_queue = Some(parse_offline_queue(&meta)?),
+            "own_args" => self.own_args = parse_flag(&meta)?,
+            "owned_params" => self.owned_params = parse_flag(&meta)?,
+            "strict" => self.strict = parse_flag(&meta)?,
+            "sync_cache" => {
+                self.startup_cache = match parse_str_value(&meta)?.as_str() {
+                    "startup" => true,
+                    _ => return Err(syn::Error::new_spanned(meta, "expected `\"startup\"`")),
+                };
+            }
+            "test_harness" => self.test_harness = parse_flag(&meta)?,
+            "transaction" => self.transaction = Some(parse_transaction(&meta)?),
+            "with_options" => self.with_options = parse_flag(&meta)?,
+            "platforms" => self.platforms = Some(parse_platforms(&meta)?),
+            "proptest" => {
+                if !cfg!(feature = "proptest") {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "`proptest` needs the `proptest` feature of tauri-bridge",
+                    ));
+                }
+                self.proptest = parse_flag(&meta)?;
+            }
+            "priority" => {
+                let priority = parse_str_value(&meta)?;
+                self.priority = Some(Priority::from_name(&priority).ok_or_else(|| {
+                    syn::Error::new_spanned(&meta, "expected `\"low\"` or `\"normal\"`")
+                })?);
+            }
+            "json_bytes" => self.json_bytes = parse_flag(&meta)?,
+            "non_send" => self.non_send = parse_flag(&meta)?,
+            "raw" => self.raw = parse_flag(&meta)?,
+            "rebind" => self.rebind = parse_flag(&meta)?,
+            "rejections" => self.rejections = parse_flag(&meta)?,
+            "replayable" => self.replayable = parse_flag(&meta)?,
+            "rename" => self.rename = Some(parse_wire_name(&meta, "command names")?),
+            "selfcheck" => self.selfcheck = parse_flag(&meta)?,
+            "serialize_on" => self.serialize_on = Some(parse_str_value(&meta)?),
+            "sidecar" => self.sidecar = Some(parse_str_value(&meta)?),
+            "signed" => self.signed = parse_flag(&meta)?,
+            "translate_errors" => self.translate_errors = parse_flag(&meta)?,
+            "unwrap_envelope" => self.unwrap_envelope = parse_flag(&meta)?,
+            "validate_response" => {
+                let function = syn::parse_str(&parse_str_value(&meta)?).map_err(|_| {
+                    syn::Error::new_spanned(
+                        &meta,
+                        "expected a function path, e.g. `validate_response = \"check_user\"`",
+                    )
+                })?;
+                self.validate_response = Some(function);
+            }
+            "version" => {
+                let version = parse_int_value(&meta)?;
+                if version == 0 {
+                    return Err(syn::Error::new_spanned(meta, "versions start at 1"));
+                }
+                self.version = Some(version);
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    meta.path(),
+                    format!("unknown tauri_bridge option `{}`", name),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill unset options from the crate-level configuration.
+    pub fn with_defaults(mut self, config: &CrateConfig) -> Self {
+        if self.backend_cfg.is_none() {
+            self.backend_cfg = config.backend_cfg.clone();
+        }
+        if self.client_cfg.is_none() {
+            self.client_cfg = config.client_cfg.clone();
+        }
+        if self.priority.is_none() {
+            self.priority = config.priority;
+        }
+        self.strict |= config.strict;
+        self.with_options |= config.with_options;
+        self.debug_log |= config.debug_log;
+        self.log_slow |= config.log_slow;
+        self.metrics |= config.metrics;
+        self.rejections |= config.rejections;
+        self.translate_errors |= config.translate_errors;
+        self.rebind |= config.rebind;
+        self.into_params |= config.into_params;
+        // `raw` still opts a command into raw bytes
+        self.json_bytes |= config.json_bytes && !self.raw;
+        if self.detect_tauri.is_none() {
+            self.detect_tauri = config.detect_tauri.clone();
+        }
+        if self.crate_paths.serde.is_none() {
+            self.crate_paths.serde = config.crate_paths.serde.clone();
+        }
+        if self.crate_paths.serde_wasm_bindgen.is_none() {
+            self.crate_paths.serde_wasm_bindgen = config.crate_paths.serde_wasm_bindgen.clone();
+        }
+        self
+    }
+
+    /// Path of `serde` in the generated client.
+    pub fn serde_path(&self) -> TokenStream2 {
+        match &self.crate_paths.serde {
+            Some(path) => quote_spanned! {Span::call_site()=> #path },
+            None => quote_spanned! {Span::call_site()=> serde },
+        }
+    }
+
+    /// `#[serde(crate = "..")]` for the client's derives, when `serde` is
+    /// renamed.
+    pub fn serde_crate_attr(&self) -> TokenStream2 {
+        match &self.crate_paths.serde {
+            Some(path) => {
+                let path = quote::ToTokens::to_token_stream(path)
+                    .to_string()
+                    .replace(' ', "");
+                quote_spanned! {Span::call_site()=> #[serde(crate = #path)] }
+            }
+            None => quote_spanned! {Span::call_site()=> },
+        }
+    }
+
+    /// Path of `serde_wasm_bindgen` in the generated client.
+    pub fn serde_wasm_bindgen_path(&self) -> TokenStream2 {
+        match &self.crate_paths.serde_wasm_bindgen {
+            Some(path) => quote_spanned! {Span::call_site()=> #path },
+            None => quote_spanned! {Span::call_site()=> serde_wasm_bindgen },
+        }
+    }
+
+    /// Whether the response is sent as raw bytes: with `raw`, or for
+    /// `Vec<u8>` responses unless `json_bytes` or another option serializes
+    /// them itself.
+    pub fn raw_bytes_response(&self, input: &ItemFn) -> bool {
+        let returns_bytes = raw_bytes_type(&input.sig).is_some_and(is_byte_vec);
+        self.raw
+            || (returns_bytes
+                && !self.json_bytes
+                && self.chunked.is_none()
+                && self.compress.is_none()
+                && !self.encrypt
+                && !self.by_value
+                && self.sidecar.is_none())
+    }
+
+    /// Smallest JSON payload compressed by `compress`.
+    pub fn compress_min_size(&self) -> usize {
+        self.min_size.unwrap_or(65_536)
+    }
+
+    /// The command version, 1 for unversioned commands.
+    pub fn command_version(&self) -> u32 {
+        self.version.unwrap_or(1)
+    }
+
+    /// The command name without the version suffix.
+    pub fn base_command_name(&self, input: &ItemFn) -> String {
+        let name = match &self.rename {
+            Some(rename) => rename.clone(),
+            None => input.sig.ident.unraw().to_string(),
+        };
+        match &self.namespace {
+            Some(namespace) => format!("{}.{}", namespace, name),
+            None => name,
+        }
+    }
+
+    /// The command name used on the wire.
+    ///
+    /// Version 1 keeps the plain name so clients generated before the
+    /// command was versioned keep working.
+    pub fn command_name(&self, input: &ItemFn) -> String {
+        let base = self.base_command_name(input);
+        match self.command_version() {
+            1 => base,
+            version => format!("{}@{}", base, version),
+        }
+    }
+
+    /// The function's attributes named in `client_attrs`, in source order.
+    ///
+    /// `#[deprecated]` is left out, the client functions always get it.
+    pub fn forwarded_client_attrs<'a>(&self, attrs: &'a [Attribute]) -> Vec<&'a Attribute> {
+        attrs
+            .iter()
+            .filter(|attr| !attr.path().is_ident("deprecated"))
+            .filter(|attr| self.client_attrs.iter().any(|name| attr.path() == name))
+            .collect()
+    }
+
+    /// The `#[cfg(...)]` attribute gating the generated backend items.
+    ///
+    /// With `platforms`, the backend is also limited to their `target_os`.
+    pub fn backend_cfg_attr(&self) -> TokenStream2 {
+        let mut predicates = self.backend_predicates();
+        predicates.extend(self.feature_predicate());
+        cfg_all(predicates)
+    }
+
+    /// The `#[cfg(...)]` attribute gating the command's `bridge_handler!`
+    /// entry, which also exists while its `feature` is disabled.
+    pub fn handler_cfg_attr(&self) -> TokenStream2 {
+        cfg_all(self.backend_predicates())
+    }
+
+    /// The `#[cfg(...)]` attribute gating the `test_harness` dispatch
+    /// function: the backend's, in tests or with the `bench`, `fuzz` or
+    /// `remote` feature, and always for `sidecar` commands.
+    pub fn dispatch_cfg_attr(&self) -> TokenStream2 {
+        let call_site = Span::call_site();
+        let mut predicates = self.backend_predicates();
+        predicates.extend(self.feature_predicate());
+        // Benches, fuzz targets and remote backends link the crate without
+        // `cfg(test)`
+        let linked = cfg!(feature = "bench") || cfg!(feature = "fuzz") || cfg!(feature = "remote");
+        if !linked && self.sidecar.is_none() {
+            predicates.push(quote_spanned! {call_site=> test });
+        }
+        cfg_all(predicates)
+    }
+
+    /// The `#[cfg(...)]` attribute gating tests of the types shared by both
+    /// halves.
+    pub fn test_cfg_attr(&self) -> TokenStream2 {
+        let call_site = Span::call_site();
+        let mut predicates: Vec<_> = self
+            .item_cfg_predicates()
+            .chain(self.feature_predicate())
+            .collect();
+        predicates.push(quote_spanned! {call_site=> test });
+        cfg_all(predicates)
+    }
+
+    fn backend_predicates(&self) -> Vec<TokenStream2> {
+        let call_site = Span::call_site();
+        let mut predicates = vec![match &self.backend_cfg {
+            Some(predicate) => quote_spanned! {call_site=> #predicate },
+            None => quote_spanned! {call_site=> not(target_arch = "wasm32") },
+        }];
+        predicates.extend(self.item_cfg_predicates());
+        if let Some(platforms) = &self.platforms {
+            let target_os = platforms.iter().map(|platform| platform.target_os());
+            predicates.push(quote_spanned! {call_site=> any(#(target_os = #target_os),*) });
+        }
+        predicates
+    }
+
+    /// The `#[cfg(...)]` attribute gating the generated client items.
+    pub fn client_cfg_attr(&self) -> TokenStream2 {
+        let call_site = Span::call_site();
+        let mut predicates = vec![match &self.client_cfg {
+            Some(predicate) => quote_spanned! {call_site=> #predicate },
+            None => quote_spanned! {call_site=> target_arch = "wasm32" },
+        }];
+        predicates.extend(self.item_cfg_predicates());
+        predicates.extend(self.feature_predicate());
+        cfg_all(predicates)
+    }
+
+    /// The `#[cfg(...)]` attribute of the bridged function and its
+    /// `feature`, if it has any, for items present on both sides.
+    pub fn item_cfg_attr(&self) -> TokenStream2 {
+        let predicates: Vec<_> = self
+            .item_cfg_predicates()
+            .chain(self.feature_predicate())
+            .collect();
+        if predicates.is_empty() {
+            return TokenStream2::new();
+        }
+        cfg_all(predicates)
+    }
+
+    /// `feature = "name"` of the `feature` option.
+    pub fn feature_predicate(&self) -> Option<TokenStream2> {
+        let call_site = Span::call_site();
+        self.feature
+            .as_ref()
+            .map(|feature| quote_spanned! {call_site=> feature = #feature })
+    }
+
+    fn item_cfg_predicates(&self) -> impl Iterator<Item = TokenStream2> + '_ {
+        let call_site = Span::call_site();
+        self.item_cfgs
+            .iter()
+            .map(move |predicate| quote_spanned! {call_site=> #predicate })
+    }
+}
+
+/// `#[cfg(..)]` requiring all predicates; a single one is used as is.
+fn cfg_all(predicates: Vec<TokenStream2>) -> TokenStream2 {
+    let call_site = Span::call_site();
+    match predicates.as_slice() {
+        [predicate] => quote_spanned! {call_site=> #[cfg(#predicate)] },
+        _ => quote_spanned! {call_site=> #[cfg(all(#(#predicates),*))] },
+    }
+}
+
+/// Remove the `#[cfg(..)]` attributes from the bridged function and return
+/// their predicates.
+pub fn take_cfg_attrs(attrs: &mut Vec<Attribute>) -> syn::Result<Vec<Meta>> {
+    let mut predicates = Vec::new();
+    let mut kept = Vec::new();
+    for attr in attrs.drain(..) {
+        if attr.path().is_ident("cfg") {
+            predicates.push(attr.parse_args::<Meta>()?);
+        } else {
+            kept.push(attr);
+        }
+    }
+    *attrs = kept;
+    Ok(predicates)
+}
+
+/// Parse `name(<predicate>)` into the inner cfg predicate.
+fn parse_cfg_predicate(meta: &Meta) -> syn::Result<Meta> {
+    match meta {
+        Meta::List(list) => list.parse_args::<Meta>(),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            "expected a cfg predicate, e.g. `backend_cfg(feature = \"backend\")`",
+        )),
+    }
+}
+
+/// Parse `client_attrs(allow, clippy::msrv, ..)` into the attribute names.
+fn parse_attr_names(meta: &Meta) -> syn::Result<Vec<Path>> {
+    match meta {
+        Meta::List(list) => Ok(list
+            .parse_args_with(syn::punctuated::Punctuated::<Path, Token![,]>::parse_terminated)?
+            .into_iter()
+            .collect()),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            "expected attribute names, e.g. `client_attrs(allow, doc)`",
+        )),
+    }
+}
+
+/// Parse `name = some::path` into the path.
+fn parse_path_value(meta: &Meta) -> syn::Result<Path> {
+    if let Meta::NameValue(name_value) = meta
+        && let Expr::Path(expr_path) = &name_value.value
+    {
+        return Ok(expr_path.path.clone());
+    }
+    Err(syn::Error::new_spanned(
+        meta,
+        "expected a module path, e.g. `client_mod = crate::generated`",
+    ))
+}
+
+/// Parse `into_error = Type` or `map_err = "Type::function"`.
+fn parse_error_conversion(meta: &Meta) -> syn::Result<ErrorConversion> {
+    if meta.path().is_ident("into_error") {
+        if let Meta::NameValue(name_value) = meta
+            && let Expr::Path(expr_path) = &name_value.value
+        {
+            return Ok(ErrorConversion::Into(expr_path.path.clone()));
+        }
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected an error type, e.g. `into_error = AppError`",
+        ));
+    }
+    let function: Path = syn::parse_str(&parse_str_value(meta)?)
+        .map_err(|_| syn::Error::new_spanned(meta, "expected a function path"))?;
+    if function.segments.len() < 2 {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected a function of the error type, e.g. `map_err = \"AppError::from\"`",
+        ));
+    }
+    Ok(ErrorConversion::Map(function))
+}
+
+/// Parse `name = "value"` into the string value.
+fn parse_str_value(meta: &Meta) -> syn::Result<String> {
+    if let Meta::NameValue(name_value) = meta
+        && let Expr::Lit(expr_lit) = &name_value.value
+        && let Lit::Str(value) = &expr_lit.lit
+    {
+        return Ok(value.value());
+    }
+    Err(syn::Error::new_spanned(
+        meta,
+        "expected a string, e.g. `name = \"value\"`",
+    ))
+}
+
+/// Parse `name = "value"` into a wire name, which the registry turns into
+/// marker identifiers.
+fn parse_wire_name(meta: &Meta, what: &str) -> syn::Result<String> {
+    let name = parse_str_value(meta)?;
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '@'));
+    if !valid {
+        return Err(syn::Error::new_spanned(
+            meta,
+            format!(
+                "{} may only contain letters, digits, `_`, `.` and `@`",
+                what
+            ),
+        ));
+    }
+    Ok(name)
+}
+
+/// Parse `name = 42` into the integer value.
+fn parse_int_value(meta: &Meta) -> syn::Result<u32> {
+    if let Meta::NameValue(name_value) = meta
+        && let Expr::Lit(expr_lit) = &name_value.value
+        && let Lit::Int(value) = &expr_lit.lit
+    {
+        return value.base10_parse();
+    }
+    Err(syn::Error::new_spanned(
+        meta,
+        "expected an integer, e.g. `name = 2`",
+    ))
+}
+
+/// Parse `name = "1MB"` or `name = 1048576` into a number of bytes.
+///
+/// Units are powers of 1024, as in `B`, `KB`, `MB` and `GB`.
+fn parse_byte_size(meta: &Meta) -> syn::Result<usize> {
+    let error = || {
+        syn::Error::new_spanned(
+            meta,
+            "expected a size, e.g. `max_payload = \"1MB\"` or `max_payload = 65536`",
+        )
+    };
+    let Meta::NameValue(name_value) = meta else {
+        return Err(error());
+    };
+    let size = match &name_value.value {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Int(value) => value.base10_parse()?,
+            Lit::Str(value) => {
+                let value = value.value();
+                let value = value.trim();
+                let digits = value
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(value.len());
+                let multiplier: usize = match value[digits..].trim().to_ascii_uppercase().as_str() {
+                    "" | "B" => 1,
+                    "KB" => 1 << 10,
+                    "MB" => 1 << 20,
+                    "GB" => 1 << 30,
+                    _ => return Err(error()),
+                };
+                value[..digits]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|size| size.checked_mul(multiplier))
+                    .ok_or_else(error)?
+            }
+            _ => return Err(error()),
+        },
+        _ => return Err(error()),
+    };
+    if size == 0 {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "the payload limit must be at least 1 byte",
+        ));
+    }
+    Ok(size)
+}
+
+/// Parse `offline_queue` or `offline_queue(capacity = 8, timeout_ms = 5000)`.
+fn parse_offline_queue(meta: &Meta) -> syn::Result<OfflineQueue> {
+    let mut queue = OfflineQueue::default();
+    let Meta::List(list) = meta else {
+        parse_flag(meta)?;
+        return Ok(queue);
+    };
+
+    list.parse_nested_meta(|nested| {
+        let value: syn::LitInt = nested.value()?.parse()?;
+        if nested.path.is_ident("capacity") {
+            queue.capacity = value.base10_parse()?;
+        } else if nested.path.is_ident("timeout_ms") {
+            queue.timeout_ms = value.base10_parse()?;
+        } else {
+            return Err(nested.error("expected `capacity` or `timeout_ms`"));
+        }
+        Ok(())
+    })?;
+
+    if queue.capacity == 0 {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "offline queue capacity must be at least 1",
+        ));
+    }
+    Ok(queue)
+}
+
+/// Parse `circuit_breaker` or `circuit_breaker(threshold = 3, cooldown_ms = 5000)`.
+fn parse_circuit_breaker(meta: &Meta) -> syn::Result<CircuitBreaker> {
+    let mut breaker = CircuitBreaker::default();
+    let Meta::List(list) = meta else {
+        parse_flag(meta)?;
+        return Ok(breaker);
+    };
+
+    list.parse_nested_meta(|nested| {
+        let value: syn::LitInt = nested.value()?.parse()?;
+        if nested.path.is_ident("threshold") {
+            breaker.threshold = value.base10_parse()?;
+        } else if nested.path.is_ident("cooldown_ms") {
+            breaker.cooldown_ms = value.base10_parse()?;
+        } else {
+            return Err(nested.error("expected `threshold` or `cooldown_ms`"));
+        }
+        Ok(())
+    })?;
+
+    if breaker.threshold == 0 {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "circuit breaker threshold must be at least 1",
+        ));
+    }
+    Ok(breaker)
+}
+
+/// Parse `cached`, `cached(swr)`, `cached(persist)` or
+/// `cached(swr, persist)` into whether it's stale-while-revalidate and
+/// whether it's persisted.
+fn parse_cached(meta: &Meta) -> syn::Result<(bool, bool)> {
+    let Meta::List(list) = meta else {
+        return parse_flag(meta).map(|_| (false, false));
+    };
+    let mut swr = false;
+    let mut persist = false;
+    list.parse_nested_meta(|nested| {
+        if nested.path.is_ident("swr") {
+            swr = true;
+            Ok(())
+        } else if nested.path.is_ident("persist") {
+            persist = true;
+            Ok(())
+        } else {
+            Err(nested.error("expected `swr` or `persist`"))
+        }
+    })?;
+    if !swr && !persist {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected `cached(swr)` or `cached(persist)`",
+        ));
+    }
+    Ok((swr, persist))
+}
+
+/// Parse `transaction` or `transaction(undo = delete_note)`.
+fn parse_transaction(meta: &Meta) -> syn::Result<TransactionStep> {
+    let mut step = TransactionStep::default();
+    let Meta::List(list) = meta else {
+        parse_flag(meta)?;
+        return Ok(step);
+    };
+
+    list.parse_nested_meta(|nested| {
+        if nested.path.is_ident("undo") {
+            step.undo = Some(nested.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(nested.error("expected `undo`"))
+        }
+    })?;
+    Ok(step)
+}
+
+/// Parse `detect_tauri` or `detect_tauri(fallback = preview::invoke)`.
+fn parse_detect_tauri(meta: &Meta) -> syn::Result<TauriDetection> {
+    let mut detection = TauriDetection::default();
+    let Meta::List(list) = meta else {
+        parse_flag(meta)?;
+        return Ok(detection);
+    };
+
+    list.parse_nested_meta(|nested| {
+        if nested.path.is_ident("fallback") {
+            detection.fallback = Some(nested.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(nested.error("expected `fallback`"))
+        }
+    })?;
+    Ok(detection)
+}
+
+/// Parse `client_names(try_fn = fetch_checked, args = FetchRequest)`.
+fn parse_client_names(meta: &Meta) -> syn::Result<ClientNames> {
+    let mut names = ClientNames::default();
+    let Meta::List(list) = meta else {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected names, e.g. `client_names(try_fn = fetch_checked, args = FetchRequest)`",
+        ));
+    };
+
+    list.parse_nested_meta(|nested| {
+        let name: syn::Ident = nested.value()?.parse()?;
+        if nested.path.is_ident("try_fn") {
+            names.try_fn = Some(name);
+        } else if nested.path.is_ident("args") {
+            names.args = Some(name);
+        } else {
+            return Err(nested.error("expected `try_fn` or `args`"));
+        }
+        Ok(())
+    })?;
+    Ok(names)
+}
+
+/// Parse `concrete(T = SqliteStore, ..)`.
+fn parse_concrete(meta: &Meta) -> syn::Result<Vec<(syn::Ident, Type)>> {
+    let Meta::List(list) = meta else {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected types, e.g. `concrete(T = SqliteStore)`",
+        ));
+    };
+    let mut concrete = Vec::new();
+    list.parse_nested_meta(|nested| {
+        let param = nested.path.require_ident()?.clone();
+        let ty: Type = nested.value()?.parse()?;
+        concrete.push((param, ty));
+        Ok(())
+    })?;
+    if concrete.is_empty() {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected types, e.g. `concrete(T = SqliteStore)`",
+        ));
+    }
+    Ok(concrete)
+}
+
+/// Parse `crate_paths(serde = "my_serde", serde_wasm_bindgen = "swb")`.
+fn parse_crate_paths(meta: &Meta) -> syn::Result<CratePaths> {
+    let mut paths = CratePaths::default();
+    let Meta::List(list) = meta else {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected paths, e.g. `crate_paths(serde = \"my_serde\")`",
+        ));
+    };
+
+    list.parse_nested_meta(|nested| {
+        let value: syn::LitStr = nested.value()?.parse()?;
+        let path = CratePaths::parse_path(&value.value())
+            .ok_or_else(|| syn::Error::new_spanned(&value, "expected a crate path"))?;
+        if nested.path.is_ident("serde") {
+            paths.serde = Some(path);
+        } else if nested.path.is_ident("serde_wasm_bindgen") {
+            paths.serde_wasm_bindgen = Some(path);
+        } else {
+            return Err(nested.error("expected `serde` or `serde_wasm_bindgen`"));
+        }
+        Ok(())
+    })?;
+    Ok(paths)
+}
+
+/// Parse `chunked` or `chunked(chunk_size = 65536)`.
+fn parse_chunked(meta: &Meta) -> syn::Result<Chunked> {
+    let mut chunked = Chunked::default();
+    let Meta::List(list) = meta else {
+        parse_flag(meta)?;
+        return Ok(chunked);
+    };
+
+    list.parse_nested_meta(|nested| {
+        if nested.path.is_ident("chunk_size") {
+            let value: syn::LitInt = nested.value()?.parse()?;
+            chunked.chunk_size = value.base10_parse()?;
+            Ok(())
+        } else {
+            Err(nested.error("expected `chunk_size`"))
+        }
+    })?;
+
+    // Every chunk must fit the widest UTF-8 character
+    if chunked.chunk_size < 4 {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "chunk size must be at least 4 bytes",
+        ));
+    }
+    Ok(chunked)
+}
+
+/// Parse `platforms("desktop", "android", ..)`, keeping each platform once.
+fn parse_platforms(meta: &Meta) -> syn::Result<Vec<Platform>> {
+    let Meta::List(list) = meta else {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected platforms, e.g. `platforms(\"desktop\")`",
+        ));
+    };
+
+    let names = list
+        .parse_args_with(syn::punctuated::Punctuated::<syn::LitStr, Token![,]>::parse_terminated)?;
+    let mut platforms = Vec::new();
+    for name in &names {
+        let named = Platform::from_name(&name.value()).ok_or_else(|| {
+            syn::Error::new_spanned(
+                name,
+                "expected `\"desktop\"`, `\"mobile\"`, `\"windows\"`, `\"macos\"`, `\"linux\"`, `\"ios\"` or `\"android\"`",
+            )
+        })?;
+        for platform in named {
+            if !platforms.contains(platform) {
+                platforms.push(*platform);
+            }
+        }
+    }
+    if platforms.is_empty() {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected at least one platform",
+        ));
+    }
+    Ok(platforms)
+}
+
+/// Parse `invalidates("get_users", ..)` into the query keys.
+fn parse_query_keys(meta: &Meta) -> syn::Result<Vec<String>> {
+    let Meta::List(list) = meta else {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected query keys, e.g. `invalidates(\"get_users\")`",
+        ));
+    };
+
+    let keys = list
+        .parse_args_with(syn::punctuated::Punctuated::<syn::LitStr, Token![,]>::parse_terminated)?;
+    if let Some(key) = keys.iter().find(|key| key.value().is_empty()) {
+        return Err(syn::Error::new_spanned(key, "query keys can't be empty"));
+    }
+    if keys.is_empty() {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "expected at least one query key",
+        ));
+    }
+    Ok(keys.iter().map(|key| key.value()).collect())
+}
+
+/// Parse a bare `name` flag.
+fn parse_flag(meta: &Meta) -> syn::Result<bool> {
+    match meta {
+        Meta::Path(_) => Ok(true),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            "expected a flag without a value",
+        )),
+    }
+}
+
+/// The `#[deprecated]` attributes of the source function.
+pub fn deprecated_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("deprecated"))
+        .collect()
+}
+
+/// The deprecation of the source function, if any, and its note.
+///
+/// Handles `#[deprecated]`, `#[deprecated = "note"]` and
+/// `#[deprecated(since = "..", note = "note")]`.
+pub fn deprecation_note(attrs: &[Attribute]) -> Option<Option<String>> {
+    let attr = deprecated_attrs(attrs).into_iter().next()?;
+    let note = match &attr.meta {
+        Meta::NameValue(name_value) => match &name_value.value {
+            Expr::Lit(expr_lit) => match &expr_lit.lit {
+                Lit::Str(note) => Some(note.value()),
+                _ => None,
+            },
+            _ => None,
+        },
+        Meta::List(_) => {
+            let mut note = None;
+            let _ = attr.parse_nested_meta(|nested| {
+                if nested.path.is_ident("note") {
+                    note = Some(nested.value()?.parse::<syn::LitStr>()?.value());
+                } else {
+                    // Skip `since = ".."` and friends
+                    let _ = nested.value()?.parse::<Expr>()?;
+                }
+                Ok(())
+            });
+            note
+        }
+        Meta::Path(_) => None,
+    };
+    Some(note)
+}
@@ -0,0 +1,684 @@
+//! Backend code generation for Tauri commands.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{FnArg, ItemFn, Pat, PatType, ReturnType, Type};
+
+use crate::alias::alias_command;
+use crate::attrs::{BridgeArgs, Chunked, deprecation_note};
+use crate::catch_panic::caught_command;
+use crate::entries::{entries_param, entries_type, uses_entries};
+use crate::floats::{floats_param, floats_response_type};
+use crate::inject::{
+    SESSION, backend_params, caller_param, caller_value, is_caller, marked_param, param_attrs,
+    strip_markers,
+};
+use crate::layer::layered_command;
+use crate::metrics::metered;
+use crate::non_send::non_send_command;
+use crate::serialize_on::serialized;
+use crate::session::session_command;
+use crate::sidecar::{sidecar_command, sidecar_impl_name};
+use crate::spec::structure_hash;
+use crate::types::{
+    borrowed_param, get_return_type, is_byte_vec, mutable_param, owned_type, result_types,
+};
+use crate::verify_schema::verified_command;
+
+/// Generate backend code with `#[tauri::command]` attribute.
+///
+/// The generated items are gated on the backend cfg predicate
+/// (`not(target_arch = "wasm32")` unless configured otherwise).
+///
+/// The generated code wraps the function in a module to isolate
+/// the macro exports from `#[tauri::command]`.
+pub fn generate_backend(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let fn_name_str = fn_name.unraw().to_string();
+    let (inputs, bind_label) = backend_params(input);
+    let block = &input.block;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+
+    let backend_cfg = args.backend_cfg_attr();
+    let call_site = Span::call_site();
+
+    // Tauri registers commands under the function name unless renamed
+    let command_name = args.command_name(input);
+    let command_attr = if command_name == fn_name_str {
+        quote_spanned! {call_site=> #[tauri::command] }
+    } else {
+        quote_spanned! {call_site=> #[tauri::command(rename = #command_name)] }
+    };
+
+    // Log use of deprecated commands when asked to
+    let block = match deprecation_note(attrs) {
+        Some(note) if args.log_deprecated => {
+            let message = match note {
+                Some(note) => format!(
+                    "[tauri-bridge] deprecated command `{}` invoked: {}",
+                    command_name, note
+                ),
+                None => format!(
+                    "[tauri-bridge] deprecated command `{}` invoked",
+                    command_name
+                ),
+            };
+            quote_spanned! {call_site=>
+                {
+                    eprintln!(#message);
+                    #block
+                }
+            }
+        }
+        _ => quote_spanned! {call_site=> #block },
+    };
+
+    // Bind the injected caller label or context
+    let block = if caller_param(input).is_some() {
+        quote_spanned! {call_site=>
+            {
+                #bind_label
+                #block
+            }
+        }
+    } else {
+        block
+    };
+
+    // Log handled calls in debug builds when asked to
+    let block = if args.debug_log {
+        debug_logged(input, &command_name, &block)
+    } else {
+        block
+    };
+
+    // Record the call in the metrics registry when asked to
+    let block = if args.metrics {
+        metered(input, &command_name, &block)
+    } else {
+        block
+    };
+
+    // Wait for the turn of the call in its argument's queue when asked to
+    let block = match &args.serialize_on {
+        Some(name) => serialized(input, name, &block),
+        None => block,
+    };
+
+    let session = marked_param(input, SESSION);
+
+    // Respond with panics instead of dropping the call when asked to
+    let (caught, block) = if args.catch_panic {
+        let (caught, block) = caught_command(input, &block);
+        (Some(caught), block)
+    } else {
+        (None, block)
+    };
+    let input = caught.as_ref().unwrap_or(input);
+
+    // Create a unique module name to isolate the tauri::command macro's exports
+    let mod_name = syn::Ident::new(&format!("__tauri_cmd_{}", fn_name_str), call_site);
+
+    let command_fn = |input: &ItemFn, command_attr: &TokenStream2| {
+        if args.compress.is_some() {
+            let codec = PayloadCodec::Gzip {
+                min_size: args.compress_min_size(),
+            };
+            payload_command(input, command_attr, &block, &codec)
+        } else if args.encrypt {
+            let codec = PayloadCodec::Sealed {
+                command: command_name.clone(),
+            };
+            payload_command(input, command_attr, &block, &codec)
+        } else if args.raw_bytes_response(input) {
+            raw_command(input, command_attr, &block)
+        } else if let Some(chunked) = &args.chunked {
+            chunked_command(input, command_attr, &block, chunked)
+        } else if args.by_value && input.sig.inputs.iter().any(is_mutable_arg) {
+            by_value_command(input, command_attr, &block)
+        } else if let Some(sidecar) = &args.sidecar {
+            sidecar_command(input, command_attr, &block, &inputs, sidecar, &command_name)
+        } else if args.non_send {
+            non_send_command(input, command_attr, &block, &inputs)
+        } else if args.verify_schema {
+            let hash = structure_hash(input, &command_name);
+            verified_command(input, command_attr, &block, &inputs, &hash, &command_name)
+        } else if args.layers {
+            layered_command(input, command_attr, &block, &inputs, &command_name)
+        } else if let Some(session) = session {
+            session_command(input, command_attr, &block, &inputs, session, &command_name)
+        } else if uses_entries(input, args) {
+            let wrap_param = |pat_type: &PatType| {
+                let entries = entries_param(pat_type).unwrap_or(false);
+                entries.then(|| (entries_type(&pat_type.ty), false))
+            };
+            let wrap = quote_spanned! {call_site=> tauri_bridge_runtime::entries::Entries };
+            let wrap_response = args
+                .map_entries
+                .then_some((&entries_type as &dyn Fn(&Type) -> TokenStream2, wrap));
+            wrapped_command(input, command_attr, &block, wrap_param, wrap_response)
+        } else if let Some(policy) = args.floats {
+            let wire_type = |ty: &Type| floats_response_type(ty, policy);
+            let wrap = quote_spanned! {call_site=> tauri_bridge_runtime::floats::Floats::new };
+            wrapped_command(
+                input,
+                command_attr,
+                &block,
+                |pat_type| floats_param(pat_type, policy),
+                Some((&wire_type, wrap)),
+            )
+        } else {
+            let vis = &input.vis;
+            let fn_name = &input.sig.ident;
+            let output = &input.sig.output;
+            quote_spanned! {call_site=>
+                #(#attrs)*
+                #command_attr
+                #vis #asyncness fn #fn_name #generics (#inputs) #output #where_clause #block
+            }
+        }
+    };
+    let command = command_fn(input, &command_attr);
+
+    // The sidecar's dispatch function calls the body
+    let sidecar_impl = args.sidecar.as_ref().map(|_| {
+        let impl_name = sidecar_impl_name(input);
+        quote_spanned! {call_site=>
+            #backend_cfg
+            #[doc(hidden)]
+            #vis use #mod_name::#impl_name;
+        }
+    });
+
+    // Each alias is another command with the same body
+    let (aliases, alias_names): (Vec<_>, Vec<_>) = args
+        .aliases
+        .iter()
+        .enumerate()
+        .map(|(index, alias)| {
+            let alias_input = alias_command(input, index);
+            let alias_attr = quote_spanned! {call_site=> #[tauri::command(rename = #alias)] };
+            (command_fn(&alias_input, &alias_attr), alias_input.sig.ident)
+        })
+        .unzip();
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        #[allow(deprecated)]
+        mod #mod_name {
+            use super::*;
+
+            #command
+            #(#aliases)*
+        }
+
+        #backend_cfg
+        #[allow(deprecated)]
+        #vis use #mod_name::#fn_name;
+
+        #sidecar_impl
+
+        #(
+            #backend_cfg
+            #[allow(deprecated)]
+            #[doc(hidden)]
+            #vis use #mod_name::#alias_names;
+        )*
+    }
+}
+
+/// Time the command body and print its response in debug builds.
+fn debug_logged(input: &ItemFn, command_name: &str, block: &TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let output = match &input.sig.output {
+        ReturnType::Default => quote_spanned! {call_site=> () },
+        ReturnType::Type(_, ty) => quote_spanned! {call_site=> #ty },
+    };
+    // Returns in the body must leave the block only
+    let run = if input.sig.asyncness.is_some() {
+        quote_spanned! {call_site=> async #block.await }
+    } else {
+        quote_spanned! {call_site=> (|| -> #output #block)() }
+    };
+    quote_spanned! {call_site=>
+        {
+            #[cfg(debug_assertions)]
+            let __tauri_bridge_started = ::std::time::Instant::now();
+            let __tauri_bridge_response: #output = #run;
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "[tauri-bridge] {} handled in {:.1?}\n{:#?}",
+                #command_name,
+                __tauri_bridge_started.elapsed(),
+                __tauri_bridge_response
+            );
+            __tauri_bridge_response
+        }
+    }
+}
+
+fn is_mutable_arg(arg: &FnArg) -> bool {
+    matches!(arg, FnArg::Typed(pat_type) if mutable_param(&pat_type.ty).is_some())
+}
+
+/// The `by_value` command: takes `&mut` arguments by value and returns
+/// them after the original body ran, as `(result, modified...)`.
+///
+/// The original function is kept as a private inner function.
+fn by_value_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let (fn_name, inner_name) = wrapper_names(input);
+    let inputs = strip_markers(&input.sig.inputs);
+    let output = &input.sig.output;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+    let return_type = get_return_type(&input.sig);
+
+    let mut params = Vec::new();
+    let mut call_args = Vec::new();
+    let mut returned = Vec::new();
+    let mut returned_types = Vec::new();
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            continue;
+        };
+        if is_caller(pat_type) {
+            params.push(quote_spanned! {call_site=> __tauri_bridge_webview: tauri::Webview });
+            call_args.push(caller_value(pat_type));
+            continue;
+        }
+        let ident = &pat_ident.ident;
+        let ty = &pat_type.ty;
+        let param_attrs = param_attrs(pat_type);
+        match mutable_param(ty) {
+            Some(mutable) => {
+                let owned = owned_type(&mutable);
+                params.push(quote_spanned! {call_site=> #(#param_attrs)* mut #ident: #owned });
+                call_args.push(quote_spanned! {call_site=> &mut #ident });
+                returned.push(ident);
+                returned_types.push(owned);
+            }
+            None => {
+                params.push(quote_spanned! {call_site=> #(#param_attrs)* #ident: #ty });
+                call_args.push(quote_spanned! {call_site=> #ident });
+            }
+        }
+    }
+
+    let await_token = asyncness.map(|_| quote_spanned! {call_site=> .await });
+
+    quote_spanned! {call_site=>
+        #asyncness fn #inner_name #generics (#inputs) #output #where_clause #block
+
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (#(#params),*) -> (#return_type, #(#returned_types),*) #where_clause {
+            let result = #inner_name(#(#call_args),*) #await_token;
+            (result, #(#returned),*)
+        }
+    }
+}
+
+/// Parameters of a wrapper command and the arguments forwarding them to
+/// the inner function, with the caller parameter injected.
+fn forward_params(input: &ItemFn) -> (Vec<TokenStream2>, Vec<TokenStream2>) {
+    let call_site = Span::call_site();
+    let mut params = Vec::new();
+    let mut call_args = Vec::new();
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            continue;
+        };
+        if is_caller(pat_type) {
+            params.push(quote_spanned! {call_site=> __tauri_bridge_webview: tauri::Webview });
+            call_args.push(caller_value(pat_type));
+            continue;
+        }
+        let ident = &pat_ident.ident;
+        let ty = &pat_type.ty;
+        let param_attrs = param_attrs(pat_type);
+        params.push(quote_spanned! {call_site=> #(#param_attrs)* #ident: #ty });
+        call_args.push(quote_spanned! {call_site=> #ident });
+    }
+    (params, call_args)
+}
+
+/// Names of a wrapper command and of the inner function it calls.
+///
+/// The command keeps the source identifier, raw or not, with its span so
+/// diagnostics point at user code.
+pub fn wrapper_names(input: &ItemFn) -> (syn::Ident, syn::Ident) {
+    let fn_name = input.sig.ident.clone();
+    let inner_name = syn::Ident::new(
+        &format!("__tauri_bridge_{}", fn_name.unraw()),
+        Span::call_site(),
+    );
+    (fn_name, inner_name)
+}
+
+/// The `raw` command: returns the original function's bytes as a
+/// `tauri::ipc::Response`, which Tauri sends without JSON encoding.
+///
+/// The original function is kept as a private inner function.
+fn raw_command(input: &ItemFn, command_attr: &TokenStream2, block: &TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let (fn_name, inner_name) = wrapper_names(input);
+    let inputs = strip_markers(&input.sig.inputs);
+    let output = &input.sig.output;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+
+    let (params, call_args) = forward_params(input);
+    let await_token = asyncness.map(|_| quote_spanned! {call_site=> .await });
+
+    // `raw_bytes_response` only holds for functions with a return type
+    let ReturnType::Type(_, return_type) = output else {
+        unreachable!("raw byte responses need a return type");
+    };
+    let into_response = |bytes_type: &Type| {
+        if is_byte_vec(bytes_type) {
+            quote_spanned! {call_site=> tauri::ipc::Response::new(bytes) }
+        } else {
+            quote_spanned! {call_site=> tauri::ipc::Response::new(Vec::<u8>::from(bytes)) }
+        }
+    };
+    let (response_type, respond) = match result_types(return_type) {
+        Some((ok, error)) => {
+            let into_response = into_response(ok);
+            (
+                quote_spanned! {call_site=> Result<tauri::ipc::Response, #error> },
+                quote_spanned! {call_site=> result.map(|bytes| #into_response) },
+            )
+        }
+        None => {
+            let into_response = into_response(return_type);
+            (
+                quote_spanned! {call_site=> tauri::ipc::Response },
+                quote_spanned! {call_site=> let bytes = result; #into_response },
+            )
+        }
+    };
+
+    quote_spanned! {call_site=>
+        #asyncness fn #inner_name #generics (#inputs) #output #where_clause #block
+
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (#(#params),*) -> #response_type #where_clause {
+            let result = #inner_name(#(#call_args),*) #await_token;
+            #respond
+        }
+    }
+}
+
+/// The wire type of a wrapped response and the function wrapping it.
+type ResponseWrapper<'a> = (&'a dyn Fn(&Type) -> TokenStream2, TokenStream2);
+
+/// A command taking some parameters and its response in a wrapper type,
+/// for `map_as = "entries"` and `floats`.
+///
+/// `wrap_param` gives the wire type of a wrapped parameter and whether the
+/// function borrows it. The original function is kept as a private inner
+/// function.
+fn wrapped_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    wrap_param: impl Fn(&PatType) -> Option<(TokenStream2, bool)>,
+    wrap_response: Option<ResponseWrapper>,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let (fn_name, inner_name) = wrapper_names(input);
+    let inputs = strip_markers(&input.sig.inputs);
+    let output = &input.sig.output;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+
+    // `forward_params` keeps the parameters with an identifier, in order
+    let (mut params, mut call_args) = forward_params(input);
+    let forwarded = input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+            Pat::Ident(pat_ident) => Some((pat_type, &pat_ident.ident)),
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
+    });
+    for (index, (pat_type, ident)) in forwarded.enumerate() {
+        if let Some((wire_type, borrowed)) = wrap_param(pat_type) {
+            let param_attrs = param_attrs(pat_type);
+            params[index] = quote_spanned! {call_site=> #(#param_attrs)* #ident: #wire_type };
+            call_args[index] = if borrowed {
+                quote_spanned! {call_site=> &#ident.0 }
+            } else {
+                quote_spanned! {call_site=> #ident.0 }
+            };
+        }
+    }
+    let await_token = asyncness.map(|_| quote_spanned! {call_site=> .await });
+
+    let (response_type, respond) = match (output, wrap_response) {
+        (ReturnType::Type(_, return_type), Some((wire_type, wrap))) => {
+            match result_types(return_type) {
+                Some((ok, error)) => {
+                    let ok = wire_type(ok);
+                    (
+                        quote_spanned! {call_site=> -> Result<#ok, #error> },
+                        quote_spanned! {call_site=> result.map(#wrap) },
+                    )
+                }
+                None => {
+                    let wire_type = wire_type(return_type);
+                    (
+                        quote_spanned! {call_site=> -> #wire_type },
+                        quote_spanned! {call_site=> #wrap(result) },
+                    )
+                }
+            }
+        }
+        _ => (
+            quote_spanned! {call_site=> #output },
+            quote_spanned! {call_site=> result },
+        ),
+    };
+
+    quote_spanned! {call_site=>
+        #asyncness fn #inner_name #generics (#inputs) #output #where_clause #block
+
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (#(#params),*) #response_type #where_clause {
+            let result = #inner_name(#(#call_args),*) #await_token;
+            #respond
+        }
+    }
+}
+
+/// The `chunked` command: serializes the original function's response and
+/// returns it inline, or sends it in chunks over an injected channel when
+/// it's larger than the chunk size.
+///
+/// The original function is kept as a private inner function.
+fn chunked_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    chunked: &Chunked,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let (fn_name, inner_name) = wrapper_names(input);
+    let inputs = strip_markers(&input.sig.inputs);
+    let output = &input.sig.output;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+    let chunk_size = chunked.chunk_size;
+
+    let (params, call_args) = forward_params(input);
+    let await_token = asyncness.map(|_| quote_spanned! {call_site=> .await });
+
+    quote_spanned! {call_site=>
+        #asyncness fn #inner_name #generics (#inputs) #output #where_clause #block
+
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (
+            #(#params,)*
+            __tauri_bridge_chunks: tauri::ipc::Channel<String>,
+        ) -> Result<serde_json::Value, String> #where_clause {
+            let result = #inner_name(#(#call_args),*) #await_token;
+            let json = serde_json::to_string(&result)
+                .map_err(|e| format!("Failed to serialize response: {}", e))?;
+            if json.len() <= #chunk_size {
+                return Ok(serde_json::json!({ "inline": json }));
+            }
+
+            // Split on character boundaries; the client concatenates in order
+            let mut rest = json.as_str();
+            let mut count = 0usize;
+            while !rest.is_empty() {
+                let mut end = rest.len().min(#chunk_size);
+                while !rest.is_char_boundary(end) {
+                    end -= 1;
+                }
+                __tauri_bridge_chunks
+                    .send(rest[..end].to_string())
+                    .map_err(|e| format!("Failed to send response chunk: {}", e))?;
+                rest = &rest[end..];
+                count += 1;
+            }
+            Ok(serde_json::json!({ "chunks": count }))
+        }
+    }
+}
+
+/// How a payload command encodes the JSON of its arguments and response.
+enum PayloadCodec {
+    /// `compress`: gzipped from `min_size` bytes up.
+    Gzip { min_size: usize },
+    /// `encrypt`: sealed under the key of `command`.
+    Sealed { command: String },
+}
+
+/// The `compress` and `encrypt` command: takes the arguments and returns
+/// the response as a single payload encoding their JSON.
+///
+/// The original function is kept as a private inner function; shared
+/// references are deserialized into owned fields and passed borrowed.
+fn payload_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    codec: &PayloadCodec,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let (fn_name, inner_name) = wrapper_names(input);
+    let inputs = strip_markers(&input.sig.inputs);
+    let output = &input.sig.output;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+
+    let mut webview_param = None;
+    let mut fields = Vec::new();
+    let mut call_args = Vec::new();
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            continue;
+        };
+        if is_caller(pat_type) {
+            webview_param =
+                Some(quote_spanned! {call_site=> __tauri_bridge_webview: tauri::Webview, });
+            call_args.push(caller_value(pat_type));
+            continue;
+        }
+        let ident = &pat_ident.ident;
+        match borrowed_param(&pat_type.ty) {
+            Some(borrowed) => {
+                let owned = owned_type(&borrowed);
+                fields.push(quote_spanned! {call_site=> #ident: #owned });
+                call_args.push(quote_spanned! {call_site=> &args.#ident });
+            }
+            None => {
+                let ty = &pat_type.ty;
+                fields.push(quote_spanned! {call_site=> #ident: #ty });
+                call_args.push(quote_spanned! {call_site=> args.#ident });
+            }
+        }
+    }
+
+    let await_token = asyncness.map(|_| quote_spanned! {call_site=> .await });
+    let (payload_param, payload_type, decode, encode) = match codec {
+        PayloadCodec::Gzip { min_size } => (
+            quote_spanned! {call_site=> __tauri_bridge_payload },
+            quote_spanned! {call_site=> tauri_bridge_runtime::compress::Payload },
+            quote_spanned! {call_site=>
+                tauri_bridge_runtime::compress::decode(__tauri_bridge_payload)?
+            },
+            quote_spanned! {call_site=> tauri_bridge_runtime::compress::encode(json, #min_size) },
+        ),
+        PayloadCodec::Sealed { command } => (
+            quote_spanned! {call_site=> __tauri_bridge_sealed },
+            quote_spanned! {call_site=> tauri_bridge_runtime::encrypt::Sealed },
+            quote_spanned! {call_site=>
+                tauri_bridge_runtime::encrypt::open(#command, __tauri_bridge_sealed)?
+            },
+            quote_spanned! {call_site=> tauri_bridge_runtime::encrypt::seal(#command, json) },
+        ),
+    };
+
+    quote_spanned! {call_site=>
+        #asyncness fn #inner_name #generics (#inputs) #output #where_clause #block
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct __TauriBridgeArgs {
+            #(#fields),*
+        }
+
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (
+            #webview_param
+            #payload_param: #payload_type,
+        ) -> Result<#payload_type, String> #where_clause {
+            let json = #decode;
+            let args: __TauriBridgeArgs = serde_json::from_str(&json)
+                .map_err(|e| format!("Failed to deserialize arguments: {}", e))?;
+            let result = #inner_name(#(#call_args),*) #await_token;
+            let json = serde_json::to_string(&result)
+                .map_err(|e| format!("Failed to serialize response: {}", e))?;
+            #encode
+        }
+    }
+}
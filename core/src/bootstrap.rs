@@ -0,0 +1,346 @@
+//! Startup values fetched in one invoke with `bridge_bootstrap!`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(bootstrap, cached)]
+//! pub fn list_projects() -> Vec<Project> {}
+//!
+//! #[tauri_bridge(bootstrap, sync_cache = "startup")]
+//! pub fn get_app_version() -> String {}
+//!
+//! tauri_bridge::bridge_bootstrap![list_projects, get_app_version];
+//! ```
+//!
+//! Commands with `bootstrap` get a hidden backend function calling the
+//! command and returning its response as a
+//! `tauri_bridge_runtime::bootstrap::Loaded`, and a hidden client function
+//! decoding such a response and keeping it where the command's client
+//! reads it: the query cache for `cached` commands, the startup values for
+//! `sync_cache = "startup"` ones.
+//!
+//! `bridge_bootstrap!` expands to the backend's `__bridge_bootstrap`
+//! command calling the listed backend functions in order, and the
+//! client's `bridge_bootstrap` handing each response to the matching
+//! client function.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{ItemFn, ReturnType};
+
+use crate::attrs::BridgeArgs;
+use crate::entries::uses_entries;
+use crate::introspect::IntrospectInput;
+use crate::registry::sibling_item_path;
+use crate::types::{get_return_type, result_types};
+
+/// Prefix of the backend function loading a command's response.
+pub const BOOTSTRAP_PREFIX: &str = "__tauri_bridge_bootstrap_";
+
+/// Prefix of the client function keeping a command's response.
+pub const CLIENT_BOOTSTRAP_PREFIX: &str = "__tauri_bridge_client_bootstrap_";
+
+/// Reject commands that can't be called without arguments, whose
+/// response isn't kept anywhere, or whose command or payload differ from
+/// the plain function's.
+pub fn check_bootstrap(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if !args.bootstrap {
+        return Ok(());
+    }
+    if let Some(arg) = input.sig.inputs.first() {
+        return Err(syn::Error::new_spanned(
+            arg,
+            "`bootstrap` commands are called without arguments and can't take parameters",
+        ));
+    }
+    if let Some(param) = input.sig.generics.params.first() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "`bootstrap` commands can't be generic",
+        ));
+    }
+    if !args.cached && !args.startup_cache {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`bootstrap` needs `cached` or `sync_cache = \"startup\"` to keep the response",
+        ));
+    }
+
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`bootstrap` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`bootstrap` can't be combined with `compress`",
+        ),
+        (args.encrypt, "`bootstrap` can't be combined with `encrypt`"),
+        (args.signed, "`bootstrap` can't be combined with `signed`"),
+        (
+            args.raw_bytes_response(input),
+            "`bootstrap` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.raw_response,
+            "`bootstrap` can't be combined with `raw_response`",
+        ),
+        (
+            args.unwrap_envelope,
+            "`bootstrap` can't be combined with `unwrap_envelope`",
+        ),
+        (
+            uses_entries(input, args),
+            "`bootstrap` can't be combined with `map_as`",
+        ),
+        (
+            args.floats.is_some(),
+            "`bootstrap` can't be combined with `floats`",
+        ),
+        (
+            args.catch_panic,
+            "`bootstrap` can't be combined with `catch_panic`",
+        ),
+        (args.layers, "`bootstrap` can't be combined with `layers`"),
+        (
+            args.non_send,
+            "`bootstrap` can't be combined with `non_send`",
+        ),
+        (
+            args.sidecar.is_some(),
+            "`bootstrap` can't be combined with `sidecar`",
+        ),
+        (
+            args.max_payload.is_some(),
+            "`bootstrap` can't be combined with `max_payload`",
+        ),
+        (
+            args.verify_schema,
+            "`bootstrap` can't be combined with `verify_schema`",
+        ),
+        (
+            args.platforms.is_some(),
+            "`bootstrap` can't be combined with `platforms`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// Generate the backend function calling the command for the bundle.
+pub fn generate_bootstrap_backend(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let backend_cfg = args.backend_cfg_attr();
+    let command_name = args.command_name(input);
+    let bootstrap_name = syn::Ident::new(
+        &format!("{}{}", BOOTSTRAP_PREFIX, fn_name.unraw()),
+        call_site,
+    );
+
+    let await_token = input
+        .sig
+        .asyncness
+        .map(|_| quote_spanned! {call_site=> .await });
+    let returns_result = match &input.sig.output {
+        ReturnType::Type(_, ty) => result_types(ty).is_some(),
+        ReturnType::Default => false,
+    };
+    let loaded = if returns_result {
+        quote_spanned! {call_site=>
+            match __tauri_bridge_result {
+                Ok(value) => tauri_bridge_runtime::bootstrap::Loaded::new(#command_name, &value),
+                Err(error) => tauri_bridge_runtime::bootstrap::Loaded::failed(#command_name, &error),
+            }
+        }
+    } else {
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::bootstrap::Loaded::new(#command_name, &__tauri_bridge_result)
+        }
+    };
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        #[doc(hidden)]
+        #[allow(deprecated, dead_code)]
+        #vis async fn #bootstrap_name() -> tauri_bridge_runtime::bootstrap::Loaded {
+            let __tauri_bridge_result = #fn_name() #await_token;
+            #loaded
+        }
+    }
+}
+
+/// Generate the client function keeping the command's response where its
+/// client reads it.
+///
+/// Like a dispatch function, it returns `None` for other commands.
+pub fn generate_bootstrap_client(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let client_cfg = args.client_cfg_attr();
+    let command_name = args.command_name(input);
+    let bootstrap_name = syn::Ident::new(
+        &format!("{}{}", CLIENT_BOOTSTRAP_PREFIX, input.sig.ident.unraw()),
+        call_site,
+    );
+    let swb = args.serde_wasm_bindgen_path();
+
+    // Errors aren't sent, so the value is the `Ok` side of a `Result`
+    let return_type = get_return_type(&input.sig);
+    let (value_type, response) = match &input.sig.output {
+        ReturnType::Type(_, ty) => match result_types(ty) {
+            Some((ok, _)) => (
+                quote_spanned! {call_site=> #ok },
+                quote_spanned! {call_site=> Ok(value) },
+            ),
+            None => (
+                quote_spanned! {call_site=> #ty },
+                quote_spanned! {call_site=> value },
+            ),
+        },
+        ReturnType::Default => (
+            quote_spanned! {call_site=> () },
+            quote_spanned! {call_site=> value },
+        ),
+    };
+    let keep = if args.cached {
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::query::insert::<#return_type>(
+                #command_name,
+                tauri_bridge_runtime::bootstrap::NO_QUERY_ARGS.to_string(),
+                response,
+            );
+        }
+    } else {
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::startup::set::<#return_type>(#command_name, response);
+        }
+    };
+
+    quote_spanned! {call_site=>
+        #client_cfg
+        #[doc(hidden)]
+        #[allow(deprecated, dead_code)]
+        #vis fn #bootstrap_name(
+            command: &str,
+            value: wasm_bindgen::JsValue,
+        ) -> Option<Result<(), String>> {
+            fn keep(value: wasm_bindgen::JsValue) -> Result<(), String> {
+                let value: #value_type = #swb::from_value(value)
+                    .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+                let response: #return_type = #response;
+                #keep
+                Ok(())
+            }
+
+            if command != #command_name {
+                return None;
+            }
+            Some(keep(value))
+        }
+    }
+}
+
+/// Generate the backend's `__bridge_bootstrap` command over the listed
+/// commands and the client's `bridge_bootstrap`.
+pub fn generate_bootstrap(input: &IntrospectInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let backend_cfg = input.bridge_args.backend_cfg_attr();
+    let client_cfg = input.bridge_args.client_cfg_attr();
+
+    let loaders = input.commands.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let loader = sibling_item_path(&command.path, BOOTSTRAP_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            loaded.push(#loader().await);
+        }
+    });
+    let keepers = input.commands.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let keeper = sibling_item_path(&command.path, CLIENT_BOOTSTRAP_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            {
+                if kept.is_none() {
+                    kept = #keeper(&loaded.command, loaded.value.clone());
+                }
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        mod __tauri_bootstrap {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Call the listed commands in order and respond with all of
+            /// their responses.
+            #[tauri::command]
+            pub async fn __bridge_bootstrap() -> tauri_bridge_runtime::bootstrap::Bundle {
+                #[allow(unused_mut)]
+                let mut loaded = Vec::new();
+                #(#loaders)*
+                tauri_bridge_runtime::bootstrap::Bundle { loaded }
+            }
+        }
+
+        #backend_cfg
+        pub use __tauri_bootstrap::__bridge_bootstrap;
+
+        /// Fetch the responses of the listed commands in one invoke and
+        /// keep them where the commands' clients read them.
+        #client_cfg
+        pub async fn bridge_bootstrap()
+        -> Result<tauri_bridge_runtime::bootstrap::Report, String> {
+            #[derive(serde::Deserialize)]
+            struct Loaded {
+                command: String,
+                #[serde(with = "serde_wasm_bindgen::preserve")]
+                value: wasm_bindgen::JsValue,
+                error: Option<String>,
+            }
+
+            #[derive(serde::Deserialize)]
+            struct Bundle {
+                loaded: Vec<Loaded>,
+            }
+
+            // Tauri expects an arguments object even when there are none
+            #[derive(serde::Serialize)]
+            struct NoArgs {}
+            let args = serde_wasm_bindgen::to_value(&NoArgs {})
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            let result = crate::invoke(
+                tauri_bridge_runtime::bootstrap::BOOTSTRAP_COMMAND,
+                args,
+            )
+            .await;
+            let bundle: Bundle = serde_wasm_bindgen::from_value(result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+
+            let mut report = tauri_bridge_runtime::bootstrap::Report::default();
+            for loaded in bundle.loaded {
+                if let Some(error) = loaded.error {
+                    report.fail(&loaded.command, error);
+                    continue;
+                }
+                #[allow(unused_mut)]
+                let mut kept = None;
+                #(#keepers)*
+                match kept {
+                    Some(Ok(())) => report.primed.push(loaded.command),
+                    Some(Err(error)) => report.fail(&loaded.command, error),
+                    None => report.fail(
+                        &loaded.command,
+                        "not listed in the client's `bridge_bootstrap!`",
+                    ),
+                }
+            }
+            Ok(report)
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Shared error enums for `#[bridge_error]`.
+//!
+//! ```rust,ignore
+//! #[bridge_error]
+//! #[derive(Debug, thiserror::Error)]
+//! pub enum NoteError {
+//!     #[error("note {0} not found")]
+//!     NotFound(u64),
+//!     #[bridge_error(code = "E_LOCKED")]
+//!     #[error("note is locked by {by}")]
+//!     Locked { by: String },
+//! }
+//! ```
+//!
+//! The enum is made serializable as `{"code": "not_found", "details": 3}`,
+//! with each variant's code (its snake_case name unless set) as the tag, so
+//! renaming a variant keeps the wire format when its code is pinned. It
+//! also gets `code()`, `CODES` and an `is_<variant>()` helper per variant
+//! for both sides to match on.
+
+use std::collections::HashSet;
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{Fields, ItemEnum, LitStr};
+
+const ATTR: &str = "bridge_error";
+
+/// Generate the serializable enum and its helpers.
+pub fn generate_bridge_error(mut input: ItemEnum) -> syn::Result<TokenStream2> {
+    let call_site = Span::call_site();
+    let mut seen = HashSet::new();
+    let mut codes = Vec::new();
+    let mut patterns = Vec::new();
+    let mut helpers = Vec::new();
+
+    for variant in &mut input.variants {
+        let code = take_code(&mut variant.attrs)?.unwrap_or_else(|| {
+            let name = variant.ident.unraw().to_string().to_case(Case::Snake);
+            LitStr::new(&name, variant.ident.span())
+        });
+        if !seen.insert(code.value()) {
+            return Err(syn::Error::new(
+                code.span(),
+                format!("duplicate error code `{}`", code.value()),
+            ));
+        }
+        variant
+            .attrs
+            .push(syn::parse_quote!(#[serde(rename = #code)]));
+
+        let ident = &variant.ident;
+        let pattern = match &variant.fields {
+            Fields::Named(_) => quote_spanned! {call_site=> Self::#ident { .. } },
+            Fields::Unnamed(_) => quote_spanned! {call_site=> Self::#ident(..) },
+            Fields::Unit => quote_spanned! {call_site=> Self::#ident },
+        };
+        let helper = syn::Ident::new(
+            &format!(
+                "is_{}",
+                variant.ident.unraw().to_string().to_case(Case::Snake)
+            ),
+            variant.ident.span(),
+        );
+        let doc = format!("Whether this is [`Self::{}`].", variant.ident);
+        helpers.push(quote_spanned! {call_site=>
+            #[doc = #doc]
+            pub fn #helper(&self) -> bool {
+                matches!(self, #pattern)
+            }
+        });
+        codes.push(code);
+        patterns.push(pattern);
+    }
+
+    input.attrs.insert(
+        0,
+        syn::parse_quote!(#[derive(serde::Serialize, serde::Deserialize)]),
+    );
+    input.attrs.insert(
+        1,
+        syn::parse_quote!(#[serde(tag = "code", content = "details")]),
+    );
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote_spanned! {call_site=>
+        #input
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Wire codes of the variants, in declaration order.
+            pub const CODES: &'static [&'static str] = &[#(#codes),*];
+
+            /// Stable wire code of the variant.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    #(#patterns => #codes,)*
+                }
+            }
+
+            #(#helpers)*
+        }
+    })
+}
+
+/// Remove the variant's `#[bridge_error(code = "..")]`, returning the code.
+fn take_code(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Option<LitStr>> {
+    let mut code = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(ATTR)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("code") {
+                let value: LitStr = meta.value()?.parse()?;
+                if value.value().is_empty() {
+                    return Err(meta.error("error codes can't be empty"));
+                }
+                code = Some(value);
+                Ok(())
+            } else {
+                Err(meta.error("expected `code = \"..\"`"))
+            }
+        })?;
+    }
+    attrs.retain(|attr| !attr.path().is_ident(ATTR));
+    Ok(code)
+}
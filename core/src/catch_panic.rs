@@ -0,0 +1,89 @@
+//! Panic capture for commands with `catch_panic`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(catch_panic)]
+//! pub fn parse_config(text: String) -> Config {}
+//! ```
+//!
+//! The backend runs the body with `tauri_bridge_runtime::panic::catch` (or
+//! `catch_async`) and responds with a `Caught<T>` instead of `T`, so a panic
+//! reaches the client as `BridgeError::Panicked` rather than dropping the
+//! call. The client decodes `Caught<T>` and returns `Result<T, BridgeError>`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::{ItemFn, ReturnType};
+
+use crate::attrs::BridgeArgs;
+
+/// Reject options that `catch_panic` can't be combined with.
+///
+/// These either generate their own response encoding or type the client
+/// error themselves.
+pub fn check_catch_panic(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`catch_panic` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`catch_panic` can't be combined with `compress`",
+        ),
+        (
+            args.encrypt,
+            "`catch_panic` can't be combined with `encrypt`",
+        ),
+        (
+            args.by_value,
+            "`catch_panic` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`catch_panic` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.test_harness,
+            "`catch_panic` can't be combined with `test_harness`",
+        ),
+        (
+            args.circuit_breaker.is_some(),
+            "`catch_panic` can't be combined with `circuit_breaker`",
+        ),
+        (
+            args.platforms.is_some(),
+            "`catch_panic` can't be combined with `platforms`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// The command with its body run under a panic catcher: `input` returning
+/// `Caught<T>` for its `T`, and the wrapped `block`.
+pub fn caught_command(input: &ItemFn, block: &TokenStream2) -> (ItemFn, TokenStream2) {
+    let call_site = Span::call_site();
+    let output = match &input.sig.output {
+        ReturnType::Default => quote_spanned! {call_site=> () },
+        ReturnType::Type(_, ty) => quote_spanned! {call_site=> #ty },
+    };
+
+    let mut caught = input.clone();
+    caught.sig.output = syn::parse_quote! {
+        -> tauri_bridge_runtime::panic::Caught<#output>
+    };
+
+    // Returns in the body must leave the block only
+    let block = if input.sig.asyncness.is_some() {
+        quote_spanned! {call_site=>
+            { tauri_bridge_runtime::panic::catch_async(async #block).await }
+        }
+    } else {
+        quote_spanned! {call_site=>
+            { tauri_bridge_runtime::panic::catch(|| -> #output #block) }
+        }
+    };
+    (caught, block)
+}
@@ -0,0 +1,1766 @@
+//! WASM client code generation for Tauri command bindings.
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{FnArg, GenericParam, ItemFn, Pat, PatType, Path, ReturnType, Type, Visibility};
+
+use crate::attrs::{BridgeArgs, CircuitBreaker, Platform, Priority, deprecated_attrs};
+use crate::defaults::{default_value, param_default};
+use crate::entries::{entries_param, entries_type};
+use crate::floats::{floats_response_type, floats_with};
+use crate::incremental::batch_type;
+use crate::inject::{SESSION, TARGET_LABEL, has_marker, is_injected, marked_param};
+use crate::prefetch::PREFETCH_PREFIX;
+use crate::spec::{method_spec, schema_hash, structure_hash};
+use crate::types::{
+    BorrowedParam, IntoParam, borrowed_param, generate_try_deserialize_expr, get_return_type,
+    has_reference_type, into_param, is_byte_vec, mutable_param, owned_type, result_types,
+    transform_ref_to_lifetime,
+};
+
+/// Generate client-side code for WASM.
+///
+/// The generated items are gated on the client cfg predicate
+/// (`target_arch = "wasm32"` unless configured otherwise).
+///
+/// The client items are emitted into a hidden `__tauri_client_<name>` module
+/// and re-exported next to the definition, or left for
+/// `bridge_include!` when `client_mod` is set.
+///
+/// This generates:
+/// - An args struct with Serialize/Deserialize derives
+/// - A `try_<name>` async function returning `Result<T, String>`
+///   (`Result<T, BreakerError>` with `circuit_breaker`)
+/// - A `<name>` async function that unwraps the result (same signature as backend),
+///   or with `sync_cache = "startup"` an `init_<name>` async function and a
+///   synchronous `<name>` reading its response
+pub fn generate_client(input: &ItemFn, bridge_args: &BridgeArgs) -> TokenStream2 {
+    // Generated names build on the plain name, `r#move` gives `try_move`
+    let fn_name = input.sig.ident.unraw();
+    let fn_name_str = fn_name.to_string();
+    let vis = &input.vis;
+    let client_cfg = bridge_args.client_cfg_attr();
+    let call_site = Span::call_site();
+    let swb = bridge_args.serde_wasm_bindgen_path();
+
+    // Names visible next to the function carry its span, so a collision
+    // with a user item is reported at the bridged function
+    let name_span = input.sig.ident.span();
+
+    // Generate args struct name (e.g., greet -> GreetArgs)
+    let args_struct_name = bridge_args.client_names.args.clone().unwrap_or_else(|| {
+        syn::Ident::new(
+            &format!("{}Args", fn_name_str.to_case(Case::Pascal)),
+            name_span,
+        )
+    });
+
+    // Name the backend command is registered under
+    let command_name = bridge_args.command_name(input);
+
+    // Generate the hidden module holding the client items
+    let client_mod_name = syn::Ident::new(&format!("__tauri_client_{}", fn_name_str), call_site);
+    let item_vis = nested_visibility(vis);
+
+    // Forward `#[deprecated]` so frontend callers get the warning too
+    let deprecated = deprecated_attrs(&input.attrs);
+    // Attributes selected with `client_attrs(..)` go on every client item
+    let forwarded = bridge_args.forwarded_client_attrs(&input.attrs);
+
+    // Generate client function names
+    let try_fn_name = bridge_args
+        .client_names
+        .try_fn
+        .clone()
+        .unwrap_or_else(|| syn::Ident::new(&format!("try_{}", fn_name), name_span));
+    let fn_name_ident = input.sig.ident.clone();
+
+    // Extract function arguments
+    let args: Vec<_> = input
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| {
+            if let FnArg::Typed(pat_type) = arg {
+                Some(pat_type)
+            } else {
+                None
+            }
+        })
+        // The backend fills in the caller's label and service state itself
+        .filter(|pat_type| !is_injected(pat_type))
+        .collect();
+
+    // The `#[target_label]` argument is set by `try_<name>_to` only
+    let target_param = args
+        .iter()
+        .copied()
+        .find(|pat_type| has_marker(pat_type, TARGET_LABEL));
+    let target_field = target_param.map(arg_name);
+    // The `incremental` channel is created on the client, not passed in
+    let batch_param = args
+        .iter()
+        .copied()
+        .find(|pat_type| bridge_args.incremental && batch_type(&pat_type.ty).is_some());
+    let batch_field = batch_param.map(arg_name);
+    let call_args: Vec<_> = args
+        .iter()
+        .copied()
+        .filter(|pat_type| !has_marker(pat_type, TARGET_LABEL))
+        .filter(|pat_type| Some(*pat_type) != batch_param)
+        .collect();
+
+    // Check if we have any arguments; `chunked` always sends its channel,
+    // `#[session]` its token, `verify_schema` its hash and `compress` an
+    // args object
+    let chunked = bridge_args.chunked.is_some();
+    let compress = bridge_args.compress.is_some();
+    let encrypt = bridge_args.encrypt;
+    let session = marked_param(input, SESSION).is_some();
+    let verify_schema = bridge_args.verify_schema;
+    let has_args = !args.is_empty() || chunked || session || compress || encrypt || verify_schema;
+
+    // Check if any argument has a reference type (needs lifetime);
+    // `own_args` stores shared references by value instead
+    let own_args = bridge_args.own_args;
+    let needs_lifetime = args
+        .iter()
+        .any(|arg| has_reference_type(&arg.ty) && !(own_args && borrowed_param(&arg.ty).is_some()));
+
+    // Generate struct fields with proper lifetime handling
+    let field_vis = if own_args {
+        item_vis.clone()
+    } else {
+        quote_spanned! {call_site=> }
+    };
+    let mut struct_fields: Vec<_> = args
+        .iter()
+        .map(|pat_type| {
+            let pat = arg_name(pat_type);
+            let ty = match borrowed_param(&pat_type.ty) {
+                // Channels are sent as their `__CHANNEL__:<id>` string
+                _ if Some(*pat_type) == batch_param => quote_spanned! {call_site=> String },
+                Some(borrowed) if own_args => owned_type(&borrowed),
+                _ if needs_lifetime => transform_ref_to_lifetime(&pat_type.ty, call_site),
+                _ => {
+                    let ty = &pat_type.ty;
+                    quote_spanned! {call_site=> #ty }
+                }
+            };
+            // `map_as = "entries"` maps are sent as their `[key, value]` pairs,
+            // and `floats` fields with their non-finite floats encoded
+            let with = if entries_param(pat_type).unwrap_or(false) {
+                Some("tauri_bridge_runtime::entries")
+            } else {
+                bridge_args.floats.map(floats_with)
+            };
+            let with = with.map(|with| quote_spanned! {call_site=> #[serde(with = #with)] });
+            quote_spanned! {call_site=> #with #field_vis #pat: #ty }
+        })
+        .collect();
+    if chunked {
+        // Tauri looks up the backend's `__tauri_bridge_chunks` in camelCase
+        struct_fields.push(quote_spanned! {call_site=>
+            #[serde(rename = "tauriBridgeChunks")]
+            #field_vis __tauri_bridge_chunks: String
+        });
+    }
+    if session {
+        // The backend takes the session token in place of the parameter
+        struct_fields.push(quote_spanned! {call_site=>
+            #[serde(rename = "tauriBridgeSession")]
+            #field_vis __tauri_bridge_session: tauri_bridge_runtime::session::Token
+        });
+    }
+    if verify_schema {
+        // The backend checks the hash of the build the client came from
+        struct_fields.push(quote_spanned! {call_site=>
+            #[serde(rename = "tauriBridgeSchema")]
+            #field_vis __tauri_bridge_schema: String
+        });
+    }
+
+    // Generate function parameters and struct field initializers;
+    // `owned_params` takes shared references by value instead
+    let (fn_params, field_inits): (Vec<_>, Vec<_>) = call_args
+        .iter()
+        .map(|pat_type| client_param(pat_type, bridge_args))
+        .unzip();
+    let field_inits: Vec<_> = field_inits.into_iter().flatten().collect();
+
+    // Only borrowed parameters need the lifetime on the client functions
+    let params_need_lifetime = args.iter().any(|arg| {
+        has_reference_type(&arg.ty)
+            && !(bridge_args.owned_params && borrowed_param(&arg.ty).is_some())
+    });
+
+    // `'a` unless the function declares it itself
+    let generics = &input.sig.generics;
+    let where_clause = &generics.where_clause;
+    let declares_a = generics
+        .lifetimes()
+        .any(|param| param.lifetime.ident == "a");
+    let own_lifetime =
+        |needed: bool| (needed && !declares_a).then(|| quote_spanned! {call_site=> 'a });
+
+    // Generate argument forwarding for fn -> try_fn
+    let arg_forwards: Vec<_> = call_args
+        .iter()
+        .filter_map(|pat_type| {
+            if let Pat::Ident(pat_ident) = pat_type.pat.as_ref() {
+                let ident = &pat_ident.ident;
+                Some(quote_spanned! {call_site=> #ident })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Get return type
+    // `raw_response` hands the response over as is,
+    // and `unwrap_envelope` with the envelope's `Result`
+    let envelope = match &input.sig.output {
+        ReturnType::Type(_, ty) if bridge_args.unwrap_envelope => Some(ty),
+        _ => None,
+    };
+    let return_type = if bridge_args.raw_response {
+        quote_spanned! {call_site=> wasm_bindgen::JsValue }
+    } else if let Some(envelope) = envelope {
+        quote_spanned! {call_site=>
+            Result<
+                <#envelope as tauri_bridge_runtime::envelope::Envelope>::Data,
+                <#envelope as tauri_bridge_runtime::envelope::Envelope>::Error,
+            >
+        }
+    } else {
+        get_return_type(&input.sig)
+    };
+    // With `rejections`, the error of a `Result` arrives as the rejection
+    // (unless `catch_panic` wraps it in the response)
+    let settled_result = match &input.sig.output {
+        ReturnType::Type(_, ty)
+            if bridge_args.rejections
+                && !bridge_args.catch_panic
+                && !bridge_args.raw_response
+                && envelope.is_none() =>
+        {
+            result_types(ty)
+        }
+        _ => None,
+    };
+    // `catch_panic` commands respond with their value or panic
+    let wire_type = if bridge_args.catch_panic {
+        quote_spanned! {call_site=> tauri_bridge_runtime::panic::Caught<#return_type> }
+    } else {
+        return_type.clone()
+    };
+    let try_deserialize_expr = if bridge_args.raw_response {
+        quote_spanned! {call_site=> Ok(result) }
+    } else if let Some(envelope) = envelope {
+        let envelope = quote_spanned! {call_site=> #envelope };
+        let decode = generate_try_deserialize_expr(&envelope, &swb, call_site);
+        quote_spanned! {call_site=>
+            (#decode).map(tauri_bridge_runtime::envelope::Envelope::into_result)
+        }
+    } else if bridge_args.by_value && args.iter().any(|arg| mutable_param(&arg.ty).is_some()) {
+        by_value_response(&args, &return_type, &swb)
+    } else if bridge_args.raw_bytes_response(input) {
+        raw_bytes_response(input)
+    } else if chunked {
+        chunked_response(&return_type, &swb)
+    } else if compress {
+        compressed_response(&return_type, &swb)
+    } else if encrypt {
+        sealed_response(&return_type, &command_name, &swb)
+    } else if bridge_args.map_entries {
+        wrapped_response(input, settled_result.is_some(), &swb, &entries_type)
+    } else if let Some(policy) = bridge_args.floats {
+        let wire_type = |ty: &Type| floats_response_type(ty, policy);
+        wrapped_response(input, settled_result.is_some(), &swb, &wire_type)
+    } else if let Some((ok, _)) = settled_result {
+        // The error rejected the invoke, a response is the `Ok` side
+        let ok = quote_spanned! {call_site=> #ok };
+        let decode = generate_try_deserialize_expr(&ok, &swb, call_site);
+        quote_spanned! {call_site=> (#decode).map(Ok) }
+    } else {
+        generate_try_deserialize_expr(&wire_type, &swb, call_site)
+    };
+
+    // Owned args structs are exported so callers can store them
+    let struct_vis = if own_args && has_args {
+        item_vis.clone()
+    } else {
+        quote_spanned! {call_site=> }
+    };
+    let struct_export = if own_args && has_args {
+        quote_spanned! {call_site=> , #args_struct_name }
+    } else {
+        quote_spanned! {call_site=> }
+    };
+
+    // `debug_log` prints the args struct in debug builds
+    let derive_debug = if bridge_args.debug_log {
+        quote_spanned! {call_site=> #[cfg_attr(debug_assertions, derive(Debug))] }
+    } else {
+        quote_spanned! {call_site=> }
+    };
+
+    // The struct takes `'a` for its borrowed fields and the function's
+    // generics its fields use, without bounds; the derives add their own
+    let fields = quote_spanned! {call_site=> #(#struct_fields)* };
+    let mut struct_params: Vec<_> = own_lifetime(needs_lifetime).into_iter().collect();
+    struct_params.extend(generics.params.iter().filter_map(|param| match param {
+        GenericParam::Lifetime(param) => {
+            mentions(fields.clone(), &param.lifetime.ident, true).then(|| {
+                let lifetime = &param.lifetime;
+                quote_spanned! {call_site=> #lifetime }
+            })
+        }
+        GenericParam::Type(param) => mentions(fields.clone(), &param.ident, false).then(|| {
+            let ident = &param.ident;
+            quote_spanned! {call_site=> #ident }
+        }),
+        GenericParam::Const(param) => mentions(fields.clone(), &param.ident, false).then(|| {
+            let ident = &param.ident;
+            let ty = &param.ty;
+            quote_spanned! {call_site=> const #ident: #ty }
+        }),
+    }));
+    let struct_generics = angle_bracketed(&struct_params);
+
+    // Generate the struct definition with appropriate lifetime; Tauri looks
+    // up arguments by their camelCase name
+    let serde = bridge_args.serde_path();
+    let serde_crate = bridge_args.serde_crate_attr();
+    let struct_def = if has_args {
+        if needs_lifetime {
+            quote_spanned! {call_site=>
+                // Only serialized; borrowed fields other than `&str` can't deserialize
+                #(#forwarded)*
+                #derive_debug
+                #[derive(#serde::Serialize)]
+                #serde_crate
+                #[serde(rename_all = "camelCase")]
+                struct #args_struct_name #struct_generics {
+                    #(#struct_fields),*
+                }
+            }
+        } else {
+            quote_spanned! {call_site=>
+                #(#forwarded)*
+                #derive_debug
+                #[derive(#serde::Serialize, #serde::Deserialize)]
+                #serde_crate
+                #[serde(rename_all = "camelCase")]
+                #struct_vis struct #args_struct_name #struct_generics {
+                    #(#struct_fields),*
+                }
+            }
+        }
+    } else {
+        quote_spanned! {call_site=> }
+    };
+
+    // `offline_queue` waits for the transport before sending anything
+    let wait_for_transport = match &bridge_args.offline_queue {
+        Some(queue) => {
+            let capacity = queue.capacity;
+            let timeout_ms = queue.timeout_ms;
+            quote_spanned! {call_site=>
+                if !tauri_bridge_runtime::offline::transport_available() {
+                    tauri_bridge_runtime::offline::wait_for_transport(
+                        #command_name,
+                        #capacity,
+                        #timeout_ms,
+                    )
+                    .await?;
+                }
+            }
+        }
+        None => quote_spanned! {call_site=> },
+    };
+
+    // `priority` takes a turn from the client scheduler
+    let schedule = match bridge_args.priority {
+        Some(priority) => {
+            let priority = match priority {
+                Priority::Low => quote_spanned! {call_site=> Low },
+                Priority::Normal => quote_spanned! {call_site=> Normal },
+            };
+            quote_spanned! {call_site=>
+                let _permit = tauri_bridge_runtime::scheduler::schedule(
+                    tauri_bridge_runtime::scheduler::Priority::#priority,
+                )
+                .await;
+            }
+        }
+        None => quote_spanned! {call_site=> },
+    };
+
+    // `chunked` responses may arrive in pieces over a channel
+    let chunks_setup = if chunked {
+        quote_spanned! {call_site=>
+            let reassembly = tauri_bridge_runtime::chunks::Reassembly::new();
+            let chunk_channel = crate::batch_channel({
+                let reassembly = reassembly.clone();
+                move |chunk| {
+                    if let Ok(chunk) = #swb::from_value::<String>(chunk) {
+                        reassembly.push(chunk);
+                    }
+                }
+            });
+        }
+    } else {
+        quote_spanned! {call_site=> }
+    };
+
+    // `signed` hands the serialized arguments to the app's signer, and goes
+    // through JSON text so maps become plain objects again
+    let sign_args = if bridge_args.signed {
+        quote_spanned! {call_site=>
+            let args = #swb::from_value::<serde_json::Value>(args)
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            let args = tauri_bridge_runtime::signing::sign(#command_name, args)?;
+            let args = serde_json::to_string(&args)
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            let args = js_sys::JSON::parse(&args)
+                .map_err(|_| "Failed to serialize signed arguments".to_string())?;
+        }
+    } else {
+        quote_spanned! {call_site=> }
+    };
+
+    // Serialize the arguments for the invoke call
+    let discard_batches = quote_spanned! {call_site=> crate::batch_channel(|_| {}) };
+    let serialize_with = |target: TokenStream2, channel: &TokenStream2| {
+        let target_init = target_field
+            .as_ref()
+            .map(|field| quote_spanned! {call_site=> #field: #target });
+        let channel_init = batch_field
+            .as_ref()
+            .map(|field| quote_spanned! {call_site=> #field: #channel });
+        let chunks_init =
+            chunked.then(|| quote_spanned! {call_site=> __tauri_bridge_chunks: chunk_channel });
+        let session_init = session.then(|| {
+            quote_spanned! {call_site=>
+                __tauri_bridge_session: tauri_bridge_runtime::session::token(#command_name)
+            }
+        });
+        let schema_init = verify_schema.then(|| {
+            let hash = structure_hash(input, &command_name);
+            quote_spanned! {call_site=> __tauri_bridge_schema: #hash.to_string() }
+        });
+        let inits = field_inits
+            .iter()
+            .chain(target_init.as_ref())
+            .chain(channel_init.as_ref())
+            .chain(chunks_init.as_ref())
+            .chain(session_init.as_ref())
+            .chain(schema_init.as_ref());
+        // `debug_log` keeps the struct around to print it, `max_payload`
+        // to measure it
+        let (bind_args, args_value) = if bridge_args.debug_log || bridge_args.max_payload.is_some()
+        {
+            let log_request = bridge_args.debug_log.then(|| {
+                quote_spanned! {call_site=>
+                    #[cfg(debug_assertions)]
+                    tauri_bridge_runtime::debug_log::request(
+                        #command_name,
+                        Some(&__tauri_bridge_args),
+                    );
+                }
+            });
+            let check_size = bridge_args.max_payload.map(|limit| {
+                quote_spanned! {call_site=>
+                    if let Err(error) = tauri_bridge_runtime::payload::check(
+                        #command_name,
+                        #limit,
+                        &__tauri_bridge_args,
+                    ) {
+                        __tauri_bridge_too_large = Some(error);
+                        return Err(String::new());
+                    }
+                }
+            });
+            (
+                quote_spanned! {call_site=>
+                    let __tauri_bridge_args = #args_struct_name { #(#inits),* };
+                    #log_request
+                    #check_size
+                },
+                quote_spanned! {call_site=> __tauri_bridge_args },
+            )
+        } else {
+            (
+                quote_spanned! {call_site=> },
+                quote_spanned! {call_site=> #args_struct_name { #(#inits),* } },
+            )
+        };
+        let to_value = if compress {
+            let min_size = bridge_args.compress_min_size();
+            quote_spanned! {call_site=>
+                #bind_args
+                let json = serde_json::to_string(&#args_value)
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                let payload = tauri_bridge_runtime::compress::encode(json, #min_size)?;
+                let args = #swb::to_value(
+                    &tauri_bridge_runtime::compress::PayloadArgs { payload },
+                )
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            }
+        } else if encrypt {
+            quote_spanned! {call_site=>
+                #bind_args
+                let json = serde_json::to_string(&#args_value)
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                let sealed = tauri_bridge_runtime::encrypt::seal(#command_name, json)?;
+                let args = #swb::to_value(
+                    &tauri_bridge_runtime::encrypt::SealedArgs { sealed },
+                )
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            }
+        } else if bridge_args.json_compatible {
+            // Maps become plain objects and `None` becomes `null`, which
+            // survive the JSON IPC
+            quote_spanned! {call_site=>
+                #bind_args
+                let args = #serde::Serialize::serialize(
+                    &#args_value,
+                    &#swb::Serializer::json_compatible(),
+                )
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            }
+        } else {
+            quote_spanned! {call_site=>
+                #bind_args
+                let args = #swb::to_value(&#args_value)
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            }
+        };
+        quote_spanned! {call_site=>
+            #wait_for_transport
+            #schedule
+            #chunks_setup
+            #to_value
+            #sign_args
+        }
+    };
+    let serialize_args = if has_args {
+        serialize_with(quote_spanned! {call_site=> None }, &discard_batches)
+    } else {
+        let log_request = bridge_args.debug_log.then(|| {
+            quote_spanned! {call_site=>
+                #[cfg(debug_assertions)]
+                tauri_bridge_runtime::debug_log::request(#command_name, None);
+            }
+        });
+        quote_spanned! {call_site=>
+            #wait_for_transport
+            #schedule
+            #log_request
+            // Tauri expects an arguments object even when there are none
+            #[derive(#serde::Serialize)]
+            #serde_crate
+            struct NoArgs {}
+            let args = #swb::to_value(&NoArgs {})
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            #sign_args
+        }
+    };
+
+    // The function's own generics carry over, e.g. `const N: usize` for a
+    // `Block<N>` argument, and are passed on explicitly when forwarding
+    let mut fn_params_generics: Vec<_> = own_lifetime(params_need_lifetime).into_iter().collect();
+    fn_params_generics.extend(
+        generics
+            .params
+            .iter()
+            .map(|param| quote_spanned! {call_site=> #param }),
+    );
+    let fn_generics = angle_bracketed(&fn_params_generics);
+    let explicit: Vec<_> = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    let turbofish = if explicit.is_empty() {
+        quote_spanned! {call_site=> }
+    } else {
+        quote_spanned! {call_site=> ::<#(#explicit),*> }
+    };
+
+    // `detect_tauri` without a fallback fails outside of Tauri
+    let fallback = bridge_args
+        .detect_tauri
+        .as_ref()
+        .map(|detection| detection.fallback.as_ref());
+    let not_in_tauri = fallback.is_some_and(|fallback| fallback.is_none());
+    // `demo` answers outside of Tauri without any of the guards
+    let demo_call = bridge_args.demo.as_ref().map(|demo| {
+        let call = quote_spanned! {call_site=> #demo #turbofish (#(#arg_forwards),*) };
+        match input.sig.asyncness {
+            Some(_) => quote_spanned! {call_site=> #call.await },
+            None => call,
+        }
+    });
+
+    // `circuit_breaker`, `platforms`, `catch_panic`, `max_payload`,
+    // `validate_response` and `detect_tauri` guard every invoke and type
+    // the error
+    let error_type = match (&bridge_args.circuit_breaker, &bridge_args.platforms) {
+        (Some(_), _) => quote_spanned! {call_site=> tauri_bridge_runtime::breaker::BreakerError },
+        (None, Some(_)) => {
+            quote_spanned! {call_site=> tauri_bridge_runtime::platform::PlatformError }
+        }
+        (None, None) if bridge_args.catch_panic => {
+            quote_spanned! {call_site=> tauri_bridge_runtime::panic::BridgeError }
+        }
+        (None, None) if bridge_args.max_payload.is_some() => {
+            quote_spanned! {call_site=> tauri_bridge_runtime::payload::PayloadError }
+        }
+        (None, None) if bridge_args.validate_response.is_some() => {
+            quote_spanned! {call_site=> tauri_bridge_runtime::validate::ResponseError }
+        }
+        (None, None) if not_in_tauri => {
+            quote_spanned! {call_site=> tauri_bridge_runtime::panic::BridgeError }
+        }
+        (None, None) => quote_spanned! {call_site=> String },
+    };
+    // Validators get the `Ok` side of a `Result`
+    let returns_result = envelope.is_some()
+        || matches!(&input.sig.output, ReturnType::Type(_, ty) if result_types(ty).is_some());
+    let guard_call = |call: TokenStream2| {
+        let call = match (&bridge_args.circuit_breaker, &bridge_args.platforms) {
+            (Some(breaker), _) => circuit_breaker_guard(breaker, &command_name, &return_type, call),
+            (None, Some(platforms)) => platform_guard(platforms, &command_name, &return_type, call),
+            (None, None) if bridge_args.catch_panic => panic_guard(&wire_type, call),
+            (None, None) if bridge_args.max_payload.is_some() => payload_guard(&return_type, call),
+            (None, None) => match &bridge_args.validate_response {
+                Some(validator) => {
+                    validation_guard(validator, &command_name, &return_type, returns_result, call)
+                }
+                None if not_in_tauri => bridge_error_guard(&return_type, call),
+                None => call,
+            },
+        };
+        let call = if not_in_tauri {
+            tauri_guard(&command_name, &error_type, call)
+        } else {
+            call
+        };
+        // Messages are translated last, whatever guarded the call
+        let call = if bridge_args.translate_errors {
+            translating_errors(&command_name, &return_type, &error_type, call)
+        } else {
+            call
+        };
+        match &demo_call {
+            Some(demo_call) => quote_spanned! {call_site=>
+                if !tauri_bridge_runtime::environment::in_tauri() {
+                    return Ok(#demo_call);
+                }
+                #call
+            },
+            None => call,
+        }
+    };
+    // A mutation that returned `Err` or panicked changed nothing to refetch
+    let returns_result = returns_result && !bridge_args.raw_response;
+    let value = if returns_result {
+        quote_spanned! {call_site=> Ok(_) }
+    } else {
+        quote_spanned! {call_site=> _ }
+    };
+    let succeeded = if bridge_args.catch_panic {
+        quote_spanned! {call_site=>
+            matches!(response, Ok(tauri_bridge_runtime::panic::Caught::Value(#value)))
+        }
+    } else if returns_result {
+        quote_spanned! {call_site=> matches!(response, Ok(#value)) }
+    } else {
+        quote_spanned! {call_site=> response.is_ok() }
+    };
+    let finish_response = |response: TokenStream2| {
+        let response = if bridge_args.debug_log {
+            debug_log_response(&command_name, &wire_type, response)
+        } else {
+            response
+        };
+        if bridge_args.invalidates.is_empty() {
+            response
+        } else {
+            invalidating_response(&bridge_args.invalidates, &wire_type, &succeeded, response)
+        }
+    };
+
+    // `signed` responses are checked by the app's verifier before decoding
+    let verify_response = if bridge_args.signed {
+        quote_spanned! {call_site=>
+            let result = #swb::from_value::<serde_json::Value>(result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+            let result = tauri_bridge_runtime::signing::verify(#command_name, result)?;
+            let result = serde_json::to_string(&result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+            let result = js_sys::JSON::parse(&result)
+                .map_err(|_| "Failed to deserialize verified response".to_string())?;
+        }
+    } else {
+        quote_spanned! {call_site=> }
+    };
+    // `log_slow` times the invoke; raw and chunked responses don't arrive
+    // as JSON, so their size isn't measured
+    let slow_report = bridge_args.log_slow.then(|| {
+        slow_call_report(
+            &command_name,
+            !bridge_args.raw_bytes_response(input) && !chunked,
+        )
+    });
+    // Outside of Tauri, `detect_tauri(fallback = ..)` invokes through the
+    // fallback, without the per-call options
+    let fallback_call = fallback
+        .flatten()
+        .map(|fallback| quote_spanned! {call_site=> #fallback(#command_name, args) });
+    let invoke_with = |function: &str, args: TokenStream2, rebind: bool| {
+        let call = invoke_call(
+            function,
+            args,
+            bridge_args.rejections,
+            rebind,
+            fallback_call.as_ref(),
+            settled_result.map(|(_, error)| error),
+            &swb,
+        );
+        let call = if bridge_args.replayable {
+            replayable_call(&command_name, call)
+        } else {
+            call
+        };
+        match &slow_report {
+            Some((started, report)) => {
+                quote_spanned! {call_site=> #started #call #report #verify_response }
+            }
+            None => quote_spanned! {call_site=> #call #verify_response },
+        }
+    };
+    let invoke = invoke_with(
+        "invoke",
+        quote_spanned! {call_site=> #command_name, args },
+        bridge_args.rebind,
+    );
+
+    let mut exported_fns = vec![try_fn_name.clone(), fn_name_ident.clone()];
+
+    // Generate both try_ and regular functions; `cached` keeps their
+    // responses in the query cache
+    let response = finish_response(quote_spanned! {call_site=>
+        #invoke
+        #try_deserialize_expr
+    });
+    let response = if bridge_args.cached {
+        // Persisted responses are versioned by the command's schema hash
+        let version = bridge_args
+            .persist
+            .then(|| schema_hash(&method_spec(input, &command_name).to_string()));
+        cached_response(
+            &command_name,
+            &wire_type,
+            bridge_args.swr,
+            version.as_deref(),
+            response,
+        )
+    } else {
+        response
+    };
+    let try_body = guard_call(quote_spanned! {call_site=>
+        #serialize_args
+        #response
+    });
+    // `sync_cache = "startup"` fetches the response once with `init_<name>`
+    // and reads it back synchronously
+    let plain_fn = if bridge_args.startup_cache {
+        let init_fn_name = syn::Ident::new(&format!("init_{}", fn_name), name_span);
+        let init_fn_str = init_fn_name.to_string();
+        exported_fns.push(init_fn_name.clone());
+        quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #init_fn_name () -> Result<(), #error_type> {
+                let value = #try_fn_name().await?;
+                tauri_bridge_runtime::startup::set(#command_name, value);
+                Ok(())
+            }
+
+            #(#deprecated)* #(#forwarded)*
+            #item_vis fn #fn_name_ident () -> #return_type {
+                tauri_bridge_runtime::startup::expect::<#return_type>(#command_name, #init_fn_str)
+            }
+        }
+    } else {
+        quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #fn_name_ident #fn_generics (#(#fn_params),*) -> #return_type #where_clause {
+                #try_fn_name #turbofish (#(#arg_forwards),*).await.unwrap()
+            }
+        }
+    };
+    let mut client_fns = quote_spanned! {call_site=>
+        #(#deprecated)* #(#forwarded)*
+        #item_vis async fn #try_fn_name #fn_generics (#(#fn_params),*) -> Result<#return_type, #error_type> #where_clause {
+            #try_body
+        }
+
+        #plain_fn
+    };
+
+    // `#[bridge(default)]` parameters add variants leaving them out
+    let defaults: Vec<_> = call_args
+        .iter()
+        .map(|pat_type| {
+            param_default(pat_type)
+                .ok()
+                .flatten()
+                .map(|default| default_value(pat_type, &default))
+        })
+        .collect();
+    if defaults.iter().any(Option::is_some) {
+        let try_defaults_fn_name =
+            syn::Ident::new(&format!("{}_with_defaults", try_fn_name), name_span);
+        let defaults_fn_name = syn::Ident::new(&format!("{}_with_defaults", fn_name), name_span);
+        let required_params: Vec<_> = fn_params
+            .iter()
+            .zip(&defaults)
+            .filter(|(_, default)| default.is_none())
+            .map(|(param, _)| param)
+            .collect();
+        let forwards: Vec<_> = arg_forwards
+            .iter()
+            .zip(&defaults)
+            .map(|(ident, default)| default.as_ref().unwrap_or(ident))
+            .collect();
+        client_fns.extend(quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #try_defaults_fn_name #fn_generics (
+                #(#required_params),*
+            ) -> Result<#return_type, #error_type> #where_clause {
+                #try_fn_name #turbofish (#(#forwards),*).await
+            }
+
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #defaults_fn_name #fn_generics (
+                #(#required_params),*
+            ) -> #return_type #where_clause {
+                #fn_name_ident #turbofish (#(#forwards),*).await
+            }
+        });
+        exported_fns.push(try_defaults_fn_name);
+        exported_fns.push(defaults_fn_name);
+    }
+
+    // `#[target_label]` adds a variant addressing a specific webview
+    if target_param.is_some() {
+        let try_to_fn_name = syn::Ident::new(&format!("{}_to", try_fn_name), name_span);
+        let serialize_args = serialize_with(
+            quote_spanned! {call_site=> Some(webview_label.to_string()) },
+            &discard_batches,
+        );
+        let try_body = guard_call(quote_spanned! {call_site=>
+            #serialize_args
+            #response
+        });
+        client_fns.extend(quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #try_to_fn_name #fn_generics (
+                webview_label: &str,
+                #(#fn_params),*
+            ) -> Result<#return_type, #error_type> #where_clause {
+                #try_body
+            }
+        });
+        exported_fns.push(try_to_fn_name);
+    }
+
+    // `with_options` adds a variant forwarding per-call invoke options
+    if bridge_args.with_options {
+        let try_options_fn_name =
+            syn::Ident::new(&format!("{}_with_options", try_fn_name), name_span);
+        // The options aren't `Clone`, so this variant isn't rebound
+        let invoke_with_options = invoke_with(
+            "invoke_with_options",
+            quote_spanned! {call_site=> #command_name, args, options },
+            false,
+        );
+        let response = finish_response(quote_spanned! {call_site=>
+            #invoke_with_options
+            #try_deserialize_expr
+        });
+        let try_body = guard_call(quote_spanned! {call_site=>
+            #serialize_args
+            #response
+        });
+        client_fns.extend(quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #try_options_fn_name #fn_generics (
+                #(#fn_params,)*
+                options: crate::InvokeOptions,
+            ) -> Result<#return_type, #error_type> #where_clause {
+                #try_body
+            }
+        });
+        exported_fns.push(try_options_fn_name);
+    }
+
+    // `transaction` adds the call as a step of a `bridge_transaction!`,
+    // responding with the `Ok` side of a `Result`
+    if bridge_args.transaction.is_some() {
+        let step_fn_name = syn::Ident::new(&format!("{}_step", fn_name), name_span);
+        let step_type = match &input.sig.output {
+            ReturnType::Type(_, ty) => match result_types(ty) {
+                Some((ok, _)) => quote_spanned! {call_site=> #ok },
+                None => quote_spanned! {call_site=> #ty },
+            },
+            ReturnType::Default => quote_spanned! {call_site=> () },
+        };
+        let args_value = if has_args {
+            let target_init = target_field
+                .as_ref()
+                .map(|field| quote_spanned! {call_site=> #field: None });
+            let inits = field_inits.iter().chain(target_init.as_ref());
+            quote_spanned! {call_site=> #args_struct_name { #(#inits),* } }
+        } else {
+            quote_spanned! {call_site=> serde_json::Value::Null }
+        };
+        client_fns.extend(quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis fn #step_fn_name #fn_generics (
+                #(#fn_params),*
+            ) -> tauri_bridge_runtime::transaction::Call<#step_type> #where_clause {
+                tauri_bridge_runtime::transaction::Call::new(#command_name, &#args_value)
+            }
+        });
+        exported_fns.push(step_fn_name);
+    }
+
+    // `incremental` adds a variant handing each batch to a callback
+    if let Some(batch_param) = batch_param {
+        let try_incremental_fn_name =
+            syn::Ident::new(&format!("{}_incremental", try_fn_name), name_span);
+        let batch_type = batch_type(&batch_param.ty);
+        let serialize_args = serialize_with(
+            quote_spanned! {call_site=> None },
+            &quote_spanned! {call_site=> channel },
+        );
+        let response = finish_response(quote_spanned! {call_site=>
+            #invoke
+            if let Some(error) = batch_error.borrow_mut().take() {
+                return Err(error);
+            }
+            #try_deserialize_expr
+        });
+        let try_body = guard_call(quote_spanned! {call_site=>
+            let batch_error = std::rc::Rc::new(std::cell::RefCell::new(None::<String>));
+            // The channel may outlive the call; once it's done or dropped
+            // (e.g. with its component), batches are no longer handed out
+            let on_batch = std::rc::Rc::new(std::cell::RefCell::new(Some(on_batch)));
+            struct Detach<F>(std::rc::Rc<std::cell::RefCell<Option<F>>>);
+            impl<F> Drop for Detach<F> {
+                fn drop(&mut self) {
+                    self.0.borrow_mut().take();
+                }
+            }
+            let _detach = Detach(on_batch.clone());
+            let channel = crate::batch_channel({
+                let batch_error = batch_error.clone();
+                move |batch| match #swb::from_value::<#batch_type>(batch) {
+                    Ok(batch) => {
+                        if let Some(on_batch) = on_batch.borrow_mut().as_mut() {
+                            on_batch(batch);
+                        }
+                    }
+                    Err(e) => {
+                        batch_error
+                            .borrow_mut()
+                            .get_or_insert_with(|| format!("Failed to deserialize batch: {}", e));
+                    }
+                }
+            });
+            #serialize_args
+            #response
+        });
+        client_fns.extend(quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #try_incremental_fn_name #fn_generics (
+                #(#fn_params,)*
+                on_batch: impl FnMut(#batch_type) + 'static,
+            ) -> Result<#return_type, #error_type> #where_clause {
+                #try_body
+            }
+        });
+        exported_fns.push(try_incremental_fn_name);
+    }
+
+    // `cached` exports the query key for subscribing to invalidations, and
+    // adds a variant filling the cache for `bridge_prefetch_all!`
+    if bridge_args.cached {
+        let query_key_name = syn::Ident::new(
+            &format!("{}_QUERY_KEY", fn_name_str.to_case(Case::UpperSnake)),
+            name_span,
+        );
+        let doc = format!("Query key of the cached `{}` responses.", fn_name_str);
+        client_fns.extend(quote_spanned! {call_site=>
+            #[doc = #doc]
+            #item_vis const #query_key_name: &str = #command_name;
+        });
+        exported_fns.push(query_key_name);
+
+        // `swr` hands responses refreshed in the background to callbacks
+        if bridge_args.swr {
+            let refreshed_fn_name =
+                syn::Ident::new(&format!("on_{}_refreshed", fn_name), name_span);
+            client_fns.extend(quote_spanned! {call_site=>
+                #(#deprecated)* #(#forwarded)*
+                #item_vis fn #refreshed_fn_name #fn_generics (
+                    on_refresh: impl Fn(#wire_type) + 'static,
+                ) -> tauri_bridge_runtime::subscription::Subscription #where_clause {
+                    tauri_bridge_runtime::query::subscribe_refreshed::<#wire_type>(
+                        #command_name,
+                        on_refresh,
+                    )
+                }
+            });
+            exported_fns.push(refreshed_fn_name);
+        }
+
+        let prefetch_fn_name =
+            syn::Ident::new(&format!("{}{}", PREFETCH_PREFIX, fn_name), name_span);
+        client_fns.extend(quote_spanned! {call_site=>
+            #(#deprecated)* #(#forwarded)*
+            #item_vis async fn #prefetch_fn_name #fn_generics (
+                #(#fn_params),*
+            ) -> Result<(), #error_type> #where_clause {
+                #try_fn_name #turbofish (#(#arg_forwards),*).await.map(|_| ())
+            }
+        });
+        exported_fns.push(prefetch_fn_name);
+    }
+
+    // Re-export next to the definition unless the client lives elsewhere
+    let client_reexport = match &bridge_args.client_mod {
+        Some(client_mod) => quote_spanned! {call_site=>
+            // Fail early if the target module does not exist
+            #client_cfg
+            const _: () = {
+                #[allow(unused_imports)]
+                use #client_mod as _;
+            };
+        },
+        None => quote_spanned! {call_site=>
+            #client_cfg
+            #[allow(deprecated)]
+            #vis use #client_mod_name::{#(#exported_fns),* #struct_export};
+        },
+    };
+
+    quote_spanned! {call_site=>
+        #client_cfg
+        #[doc(hidden)]
+        #[allow(deprecated)]
+        #vis mod #client_mod_name {
+            use super::*;
+
+            #struct_def
+            #client_fns
+        }
+
+        #client_reexport
+    }
+}
+
+/// The client function parameter for a backend argument, and the
+/// initializer of its args struct field.
+///
+/// With `owned_params`, `&str` and `&[T]` are taken as `impl AsRef<..>` and
+/// other shared references by value. With `own_args`, borrowed values are
+/// converted to the owned field types. With `into_params`, `String` and
+/// `Vec<String>` are taken through `Into`.
+fn client_param(
+    pat_type: &PatType,
+    bridge_args: &BridgeArgs,
+) -> (TokenStream2, Option<TokenStream2>) {
+    let call_site = Span::call_site();
+    let pat = arg_name(pat_type);
+    let ident = match pat_type.pat.as_ref() {
+        Pat::Ident(pat_ident) => Some(pat_ident.ident.clone()),
+        _ => None,
+    };
+    let owned_params = bridge_args.owned_params;
+    let own_args = bridge_args.own_args;
+
+    // `into_params` converts into the field type before serializing
+    let converted = if bridge_args.into_params {
+        into_param(&pat_type.ty)
+    } else {
+        None
+    };
+    if let (Some(converted), Some(ident)) = (converted, &ident) {
+        let (ty, value) = match converted {
+            IntoParam::String => (
+                quote_spanned! {call_site=> impl Into<String> },
+                quote_spanned! {call_site=> #ident.into() },
+            ),
+            IntoParam::StringVec => (
+                quote_spanned! {call_site=> impl IntoIterator<Item = impl Into<String>> },
+                quote_spanned! {call_site=> #ident.into_iter().map(Into::into).collect() },
+            ),
+        };
+        return (
+            quote_spanned! {call_site=> #pat: #ty },
+            Some(quote_spanned! {call_site=> #ident: #value }),
+        );
+    }
+
+    let borrowed = if owned_params || own_args {
+        borrowed_param(&pat_type.ty)
+    } else {
+        None
+    };
+    let ty = match &borrowed {
+        Some(BorrowedParam::Str) if owned_params => quote_spanned! {call_site=> impl AsRef<str> },
+        Some(BorrowedParam::Slice(elem)) if owned_params => {
+            quote_spanned! {call_site=> impl AsRef<[#elem]> }
+        }
+        Some(BorrowedParam::Ref(elem)) if owned_params => quote_spanned! {call_site=> #elem },
+        _ if has_reference_type(&pat_type.ty) => transform_ref_to_lifetime(&pat_type.ty, call_site),
+        _ => {
+            let ty = &pat_type.ty;
+            quote_spanned! {call_site=> #ty }
+        }
+    };
+    // Converted field value, `None` for the plain `ident` shorthand
+    let value = match (&borrowed, owned_params, own_args) {
+        (Some(BorrowedParam::Str), true, true) => {
+            Some(quote_spanned! {call_site=> #ident.as_ref().to_owned() })
+        }
+        (Some(BorrowedParam::Str), true, false) => {
+            Some(quote_spanned! {call_site=> #ident.as_ref() })
+        }
+        (Some(BorrowedParam::Str), false, _) => {
+            Some(quote_spanned! {call_site=> #ident.to_owned() })
+        }
+        (Some(BorrowedParam::Slice(_)), true, true) => {
+            Some(quote_spanned! {call_site=> #ident.as_ref().to_vec() })
+        }
+        (Some(BorrowedParam::Slice(_)), true, false) => {
+            Some(quote_spanned! {call_site=> #ident.as_ref() })
+        }
+        (Some(BorrowedParam::Slice(_)), false, _) => {
+            Some(quote_spanned! {call_site=> #ident.to_vec() })
+        }
+        (Some(BorrowedParam::Ref(_)), true, true) => None,
+        (Some(BorrowedParam::Ref(_)), true, false) => Some(quote_spanned! {call_site=> &#ident }),
+        (Some(BorrowedParam::Ref(_)), false, _) => {
+            Some(quote_spanned! {call_site=> #ident.clone() })
+        }
+        (None, _, _) => None,
+    };
+    let init = ident.map(|ident| match value {
+        Some(value) => quote_spanned! {call_site=> #ident: #value },
+        None => quote_spanned! {call_site=> #ident },
+    });
+
+    (quote_spanned! {call_site=> #pat: #ty }, init)
+}
+
+/// The binding name of an argument.
+///
+/// `mut` and other binding modes are dropped; the client only forwards the
+/// value. Other patterns are rejected before generation.
+fn arg_name(pat_type: &PatType) -> TokenStream2 {
+    let call_site = Span::call_site();
+    match pat_type.pat.as_ref() {
+        Pat::Ident(pat_ident) => {
+            let ident = &pat_ident.ident;
+            quote_spanned! {call_site=> #ident }
+        }
+        pat => quote_spanned! {call_site=> #pat },
+    }
+}
+
+/// Decode a response sent in a wrapper type, or a `Result` of it, for
+/// `map_as = "entries"` and `floats`.
+fn wrapped_response(
+    input: &ItemFn,
+    settled: bool,
+    swb: &TokenStream2,
+    wire_type: &dyn Fn(&Type) -> TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let ReturnType::Type(_, ty) = &input.sig.output else {
+        return quote_spanned! {call_site=> Ok(()) };
+    };
+    match result_types(ty) {
+        // The error rejected the invoke, a response is the `Ok` side
+        Some((ok, _)) if settled => {
+            let wire = wire_type(ok);
+            let decode = generate_try_deserialize_expr(&wire, swb, call_site);
+            quote_spanned! {call_site=> (#decode).map(|wrapped| Ok(wrapped.0)) }
+        }
+        Some((ok, error)) => {
+            let ok = wire_type(ok);
+            let wire = quote_spanned! {call_site=> Result<#ok, #error> };
+            let decode = generate_try_deserialize_expr(&wire, swb, call_site);
+            quote_spanned! {call_site=> (#decode).map(|result| result.map(|wrapped| wrapped.0)) }
+        }
+        None => {
+            let wire = wire_type(ty);
+            let decode = generate_try_deserialize_expr(&wire, swb, call_site);
+            quote_spanned! {call_site=> (#decode).map(|wrapped| wrapped.0) }
+        }
+    }
+}
+
+/// Decode a `by_value` response, `(result, modified...)`, and write the
+/// modified values back through the `&mut` arguments.
+fn by_value_response(
+    args: &[&PatType],
+    return_type: &TokenStream2,
+    swb: &TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let mut received = Vec::new();
+    let mut received_types = Vec::new();
+    let mut write_backs = Vec::new();
+
+    for pat_type in args {
+        let (Some(mutable), Pat::Ident(pat_ident)) =
+            (mutable_param(&pat_type.ty), pat_type.pat.as_ref())
+        else {
+            continue;
+        };
+        let ident = &pat_ident.ident;
+        let name = ident.unraw().to_string();
+        let value = syn::Ident::new(&format!("__tauri_bridge_{}", name), call_site);
+
+        write_backs.push(match mutable {
+            BorrowedParam::Slice(_) => quote_spanned! {call_site=>
+                if #ident.len() != #value.len() {
+                    return Err(format!(
+                        "`{}` changed length from {} to {}",
+                        #name,
+                        #ident.len(),
+                        #value.len()
+                    ));
+                }
+                #ident.clone_from_slice(&#value);
+            },
+            _ => quote_spanned! {call_site=> *#ident = #value; },
+        });
+        received_types.push(owned_type(&mutable));
+        received.push(value);
+    }
+
+    quote_spanned! {call_site=>
+        let (value, #(#received),*) =
+            #swb::from_value::<(#return_type, #(#received_types),*)>(result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+        #(#write_backs)*
+        Ok(value)
+    }
+}
+
+/// Visibility for items nested one module deeper than the source function.
+///
+/// Client items live in a hidden module and are re-exported with the
+/// original visibility, so relative visibilities need one more `super`.
+pub fn nested_visibility(vis: &Visibility) -> TokenStream2 {
+    let call_site = Span::call_site();
+    match vis {
+        Visibility::Inherited => quote_spanned! {call_site=> pub(super) },
+        Visibility::Restricted(restricted) => {
+            let path = &restricted.path;
+            let first = path
+                .segments
+                .first()
+                .map(|segment| segment.ident.to_string());
+            match first.as_deref() {
+                Some("self") if path.segments.len() == 1 => {
+                    quote_spanned! {call_site=> pub(super) }
+                }
+                Some("super") => quote_spanned! {call_site=> pub(in super::#path) },
+                _ => quote_spanned! {call_site=> #vis },
+            }
+        }
+        Visibility::Public(_) => quote_spanned! {call_site=> #vis },
+    }
+}
+
+/// Bind the response of `crate::<function>` to `result`.
+///
+/// With `rejections`, `crate::try_<function>` returns the rejection of the
+/// promise as `Err`. It's decoded as `error_type`, the error of a `Result`
+/// command, or fails the call with its message.
+///
+/// With `rebind`, the invoke waits for the connection and is made again
+/// with a copy of `args` if the connection goes away before it completes.
+///
+/// With a `fallback` call, `detect_tauri` commands make it instead outside
+/// of Tauri.
+fn invoke_call(
+    function: &str,
+    args: TokenStream2,
+    rejections: bool,
+    rebind: bool,
+    fallback: Option<&TokenStream2>,
+    error_type: Option<&Type>,
+    swb: &TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let function = if rejections {
+        syn::Ident::new(&format!("try_{}", function), call_site)
+    } else {
+        syn::Ident::new(function, call_site)
+    };
+    let invoke = match fallback {
+        Some(fallback) => quote_spanned! {call_site=>
+            async move {
+                if tauri_bridge_runtime::environment::in_tauri() {
+                    crate::#function(#args).await
+                } else {
+                    #fallback.await
+                }
+            }
+        },
+        None => quote_spanned! {call_site=> crate::#function(#args) },
+    };
+    let call = if rebind {
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::connection::rebound(|| {
+                let args = args.clone();
+                #invoke
+            })
+            .await
+        }
+    } else {
+        quote_spanned! {call_site=> #invoke.await }
+    };
+    if !rejections {
+        return quote_spanned! {call_site=>
+            let result = #call;
+        };
+    }
+
+    let message = quote_spanned! {call_site=>
+        Err(error.as_string().unwrap_or_else(|| format!("{:?}", error)))
+    };
+    let rejected = match error_type {
+        Some(error_type) => quote_spanned! {call_site=>
+            match #swb::from_value::<#error_type>(error.clone()) {
+                Ok(error) => Ok(Err(error)),
+                Err(_) => #message,
+            }
+        },
+        None => message,
+    };
+    quote_spanned! {call_site=>
+        let result = match #call {
+            Ok(result) => result,
+            Err(error) => return #rejected,
+        };
+    }
+}
+
+/// Response of a `raw` command: the bytes arrive as an `ArrayBuffer`,
+/// which is copied out without going through JSON.
+fn raw_bytes_response(input: &ItemFn) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let ReturnType::Type(_, return_type) = &input.sig.output else {
+        unreachable!("raw byte responses need a return type");
+    };
+    let from_bytes = |bytes_type: &Type| {
+        if is_byte_vec(bytes_type) {
+            quote_spanned! {call_site=> bytes }
+        } else {
+            quote_spanned! {call_site=> <#bytes_type>::from(bytes) }
+        }
+    };
+    // An `Err` rejects the invoke, so a response is always the `Ok` side
+    let value = match result_types(return_type) {
+        Some((ok, _)) => {
+            let from_bytes = from_bytes(ok);
+            quote_spanned! {call_site=> Ok(#from_bytes) }
+        }
+        None => from_bytes(return_type),
+    };
+    quote_spanned! {call_site=>
+        let bytes = js_sys::Uint8Array::new(&result).to_vec();
+        Ok(#value)
+    }
+}
+
+/// Response of a `chunked` command: reassemble the JSON, then deserialize.
+fn chunked_response(return_type: &TokenStream2, swb: &TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let envelope =
+            #swb::from_value::<tauri_bridge_runtime::chunks::Envelope>(result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+        let json = reassembly.collect(envelope).await;
+        serde_json::from_str::<#return_type>(&json)
+            .map_err(|e| format!("Failed to deserialize response: {}", e))
+    }
+}
+
+/// Response of a `compress` command: decompress the JSON, then deserialize.
+fn compressed_response(return_type: &TokenStream2, swb: &TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let payload =
+            #swb::from_value::<tauri_bridge_runtime::compress::Payload>(result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+        let json = tauri_bridge_runtime::compress::decode(payload)?;
+        serde_json::from_str::<#return_type>(&json)
+            .map_err(|e| format!("Failed to deserialize response: {}", e))
+    }
+}
+
+/// Response of an `encrypt` command: decrypt the JSON, then deserialize.
+fn sealed_response(
+    return_type: &TokenStream2,
+    command_name: &str,
+    swb: &TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let sealed =
+            #swb::from_value::<tauri_bridge_runtime::encrypt::Sealed>(result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+        let json = tauri_bridge_runtime::encrypt::open(#command_name, sealed)?;
+        serde_json::from_str::<#return_type>(&json)
+            .map_err(|e| format!("Failed to deserialize response: {}", e))
+    }
+}
+
+/// Log the response of an invoke with `debug_log`, timed from the invoke
+/// call, in debug builds.
+fn debug_log_response(
+    command_name: &str,
+    return_type: &TokenStream2,
+    response: TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        #[cfg(debug_assertions)]
+        let started_ms = tauri_bridge_runtime::clock::now_ms();
+        let response: Result<#return_type, String> = async { #response }.await;
+        #[cfg(debug_assertions)]
+        tauri_bridge_runtime::debug_log::response(#command_name, started_ms, &response);
+        response
+    }
+}
+
+/// Start timing an invoke with `log_slow`, and report it once answered
+/// when it was slow, measuring the JSON of the arguments and, if
+/// `measure_response`, of the response.
+fn slow_call_report(command_name: &str, measure_response: bool) -> (TokenStream2, TokenStream2) {
+    let call_site = Span::call_site();
+    let response_bytes = if measure_response {
+        quote_spanned! {call_site=> json_len(&result) }
+    } else {
+        quote_spanned! {call_site=> None }
+    };
+    (
+        quote_spanned! {call_site=>
+            let __tauri_bridge_started_ms = tauri_bridge_runtime::clock::now_ms();
+            let __tauri_bridge_sent = args.clone();
+        },
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::slow::check(#command_name, __tauri_bridge_started_ms, || {
+                let json_len = |value: &wasm_bindgen::JsValue| {
+                    js_sys::JSON::stringify(value)
+                        .ok()
+                        .and_then(|json| json.as_string())
+                        .map(|json| json.len())
+                };
+                (json_len(&__tauri_bridge_sent), #response_bytes)
+            });
+        },
+    )
+}
+
+/// Take the response of `call` from `tauri_bridge_runtime::replay` while
+/// it replays, and hand it the call while it records.
+fn replayable_call(command_name: &str, call: TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let __tauri_bridge_json = |value: &wasm_bindgen::JsValue| {
+            js_sys::JSON::stringify(value)
+                .ok()
+                .and_then(|json| json.as_string())
+        };
+        let replayed =
+            tauri_bridge_runtime::replay::replayed(#command_name, || __tauri_bridge_json(&args));
+        let result = match replayed {
+            Some(response) => js_sys::JSON::parse(&response?)
+                .map_err(|_| "Failed to parse replayed response".to_string())?,
+            None => {
+                let __tauri_bridge_recorded_ms = tauri_bridge_runtime::clock::now_ms();
+                let __tauri_bridge_recorded =
+                    tauri_bridge_runtime::replay::is_recording().then(|| args.clone());
+                #call
+                tauri_bridge_runtime::replay::record(#command_name, __tauri_bridge_recorded_ms, || {
+                    (
+                        __tauri_bridge_recorded.as_ref().and_then(__tauri_bridge_json),
+                        __tauri_bridge_json(&result),
+                    )
+                });
+                result
+            }
+        };
+    }
+}
+
+/// Read the response through the query cache, keyed by the serialized
+/// arguments.
+///
+/// With `swr`, cached responses are refreshed on a task started with
+/// `crate::spawn_local`. With a persist `version`, persisted responses are
+/// restored before the read and fetched ones are persisted.
+fn cached_response(
+    command_name: &str,
+    return_type: &TokenStream2,
+    swr: bool,
+    version: Option<&str>,
+    response: TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let (restore, response) = match version {
+        Some(version) => (
+            quote_spanned! {call_site=>
+                tauri_bridge_runtime::persist::restore::<#return_type>(
+                    #command_name,
+                    #version,
+                    &query_args,
+                );
+                let persisted_args = query_args.clone();
+            },
+            quote_spanned! {call_site=>
+                tauri_bridge_runtime::persist::saving(
+                    #command_name,
+                    #version,
+                    persisted_args,
+                    async move { #response },
+                )
+                .await
+            },
+        ),
+        None => (quote_spanned! {call_site=> }, response),
+    };
+    let read = if swr {
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::query::stale_while_revalidate::<#return_type, _>(
+                #command_name,
+                query_args,
+                async move { #response },
+                crate::spawn_local,
+            )
+        }
+    } else {
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::query::cached::<#return_type, _>(
+                #command_name,
+                query_args,
+                async move { #response },
+            )
+        }
+    };
+    quote_spanned! {call_site=>
+        let query_args = js_sys::JSON::stringify(&args)
+            .ok()
+            .and_then(|json| json.as_string())
+            .unwrap_or_default();
+        #restore
+        #read.await
+    }
+}
+
+/// Drop the cached responses of `keys` once the call went through and
+/// `succeeded` holds.
+fn invalidating_response(
+    keys: &[String],
+    return_type: &TokenStream2,
+    succeeded: &TokenStream2,
+    response: TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let response: Result<#return_type, String> = async { #response }.await;
+        if #succeeded {
+            tauri_bridge_runtime::query::invalidate(&[#(#keys),*]);
+        }
+        response
+    }
+}
+
+/// Wrap an invoke in the command's circuit breaker: fail fast while it's
+/// open, and record the outcome otherwise.
+fn circuit_breaker_guard(
+    breaker: &CircuitBreaker,
+    command_name: &str,
+    return_type: &TokenStream2,
+    call: TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let threshold = breaker.threshold;
+    let cooldown_ms = breaker.cooldown_ms;
+    quote_spanned! {call_site=>
+        tauri_bridge_runtime::breaker::check(#command_name, #cooldown_ms)?;
+        let result: Result<#return_type, String> = async { #call }.await;
+        tauri_bridge_runtime::breaker::record(#command_name, #threshold, result)
+    }
+}
+
+/// Fail with the `NotInTauri` error of `error_type` outside of Tauri,
+/// without invoking the backend.
+fn tauri_guard(command_name: &str, error_type: &TokenStream2, call: TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        if !tauri_bridge_runtime::environment::in_tauri() {
+            return Err(<#error_type as tauri_bridge_runtime::environment::NotInTauri>::not_in_tauri(
+                #command_name,
+            ));
+        }
+        #call
+    }
+}
+
+/// Type the failures of `call` as `BridgeError::Failed`, for `detect_tauri`
+/// commands no other option typed the error of.
+fn bridge_error_guard(return_type: &TokenStream2, call: TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let result: Result<#return_type, String> = async { #call }.await;
+        result.map_err(tauri_bridge_runtime::panic::BridgeError::Failed)
+    }
+}
+
+/// Hand the message of a failed call to the app's translator.
+fn translating_errors(
+    command_name: &str,
+    return_type: &TokenStream2,
+    error_type: &TokenStream2,
+    call: TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let result: Result<#return_type, #error_type> = async { #call }.await;
+        result.map_err(|error| {
+            tauri_bridge_runtime::messages::Translate::translate(error, #command_name)
+        })
+    }
+}
+
+/// Turn a panic reported by a `catch_panic` command into its error.
+fn panic_guard(wire_type: &TokenStream2, call: TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        let result: Result<#wire_type, String> = async { #call }.await;
+        tauri_bridge_runtime::panic::unwrap(result)
+    }
+}
+
+/// Fail with a typed error when the arguments are over the `max_payload`
+/// limit, which the serialization of `call` reports.
+fn payload_guard(return_type: &TokenStream2, call: TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    quote_spanned! {call_site=>
+        #[allow(unused_mut)]
+        let mut __tauri_bridge_too_large: Option<tauri_bridge_runtime::payload::PayloadTooLarge> = None;
+        let result: Result<#return_type, String> = async { #call }.await;
+        match __tauri_bridge_too_large {
+            Some(error) => Err(tauri_bridge_runtime::payload::PayloadError::TooLarge(error)),
+            None => result.map_err(tauri_bridge_runtime::payload::PayloadError::Failed),
+        }
+    }
+}
+
+/// Run the response of `call` through the `validate_response` function,
+/// failing with a typed error when it's rejected.
+fn validation_guard(
+    validator: &Path,
+    command_name: &str,
+    return_type: &TokenStream2,
+    returns_result: bool,
+    call: TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let validate = quote_spanned! {call_site=>
+        tauri_bridge_runtime::validate::check(#command_name, value, #validator)
+            .map_err(tauri_bridge_runtime::validate::ResponseError::Invalid)?;
+    };
+    let validate = if returns_result {
+        quote_spanned! {call_site=>
+            if let Ok(value) = &response {
+                #validate
+            }
+        }
+    } else {
+        quote_spanned! {call_site=>
+            let value = &response;
+            #validate
+        }
+    };
+    quote_spanned! {call_site=>
+        let result: Result<#return_type, String> = async { #call }.await;
+        let response = result.map_err(tauri_bridge_runtime::validate::ResponseError::Failed)?;
+        #validate
+        Ok(response)
+    }
+}
+
+/// Fail with a typed error on platforms the command isn't available on,
+/// before anything is sent.
+fn platform_guard(
+    platforms: &[Platform],
+    command_name: &str,
+    return_type: &TokenStream2,
+    call: TokenStream2,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let platforms = platforms.iter().map(|platform| {
+        let variant = syn::Ident::new(&format!("{:?}", platform), call_site);
+        quote_spanned! {call_site=> tauri_bridge_runtime::platform::Platform::#variant }
+    });
+    quote_spanned! {call_site=>
+        tauri_bridge_runtime::platform::check(#command_name, &[#(#platforms),*])?;
+        let result: Result<#return_type, String> = async { #call }.await;
+        result.map_err(tauri_bridge_runtime::platform::PlatformError::Failed)
+    }
+}
+
+/// `<params>`, or nothing without params.
+fn angle_bracketed(params: &[TokenStream2]) -> TokenStream2 {
+    let call_site = Span::call_site();
+    if params.is_empty() {
+        quote_spanned! {call_site=> }
+    } else {
+        quote_spanned! {call_site=> <#(#params),*> }
+    }
+}
+
+/// Whether `tokens` mention `ident`, as a lifetime or as a name.
+fn mentions(tokens: TokenStream2, ident: &syn::Ident, lifetime: bool) -> bool {
+    let mut after_quote = false;
+    tokens.into_iter().any(|tree| {
+        let found = match &tree {
+            TokenTree::Ident(found) => found == ident && after_quote == lifetime,
+            TokenTree::Group(group) => mentions(group.stream(), ident, lifetime),
+            _ => false,
+        };
+        after_quote = matches!(&tree, TokenTree::Punct(punct) if punct.as_char() == '\'');
+        found
+    })
+}
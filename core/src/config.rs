@@ -0,0 +1,274 @@
+//! Crate-level defaults read from `[package.metadata.tauri-bridge]`.
+//!
+//! ```toml
+//! [package.metadata.tauri-bridge]
+//! backend_cfg = 'feature = "backend"'
+//! client_cfg = 'any(target_arch = "wasm32", feature = "native-client")'
+//! manifest_dir = "target/tauri-bridge"
+//! ts_dir = "../frontend/src/bridge"
+//! ts_client = "react-query"
+//! ts_schemas = "zod"
+//! strict = true
+//! priority = "normal"
+//! debug_log = true
+//! log_slow = true
+//! metrics = true
+//! layers = true
+//! rejections = true
+//! translate_errors = true
+//! rebind = true
+//! replayable = true
+//! into_params = true
+//! json_bytes = true
+//! detect_tauri = { fallback = "crate::preview::invoke" }
+//! crate_paths = { serde = "my_serde", serde_wasm_bindgen = "swb" }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use syn::Meta;
+
+use crate::attrs::{CratePaths, Priority, TauriDetection};
+use crate::typescript::{TsClient, TsSchema};
+
+/// Crate-wide defaults applied to every `#[tauri_bridge]` in the crate.
+#[derive(Clone, Default)]
+pub struct CrateConfig {
+    pub backend_cfg: Option<Meta>,
+    pub client_cfg: Option<Meta>,
+    /// Directory for the JSON manifest, relative to the crate root.
+    pub manifest_dir: Option<String>,
+    /// Directory for the TypeScript client, relative to the crate root.
+    pub ts_dir: Option<String>,
+    /// What the TypeScript modules export.
+    pub ts_client: TsClient,
+    /// Validation library of the TypeScript schemas, if any.
+    pub ts_schemas: Option<TsSchema>,
+    /// Apply `strict` to every command.
+    pub strict: bool,
+    /// Apply `with_options` to every command.
+    pub with_options: bool,
+    /// Default `priority` of commands that don't set one.
+    pub priority: Option<Priority>,
+    /// Apply `debug_log` to every command.
+    pub debug_log: bool,
+    /// Apply `log_slow` to every command.
+    pub log_slow: bool,
+    /// Apply `metrics` to every command.
+    pub metrics: bool,
+    /// Apply `layers` to every command whose backend it can wrap.
+    pub layers: bool,
+    /// Apply `rejections` to every command.
+    pub rejections: bool,
+    /// Apply `translate_errors` to every command.
+    pub translate_errors: bool,
+    /// Apply `rebind` to every command.
+    pub rebind: bool,
+    /// Apply `replayable` to every command whose calls it can record.
+    pub replayable: bool,
+    /// Apply `into_params` to every command.
+    pub into_params: bool,
+    /// Apply `json_bytes` to every command not marked `raw`.
+    pub json_bytes: bool,
+    /// Default `detect_tauri` of every command, `true` or a table with its
+    /// `fallback`.
+    pub detect_tauri: Option<TauriDetection>,
+    /// Default `crate_paths` of every command.
+    pub crate_paths: CratePaths,
+}
+
+thread_local! {
+    // Proc macro servers may expand several crates in one process
+    static CONFIG_CACHE: RefCell<HashMap<PathBuf, CrateConfig>> = RefCell::new(HashMap::new());
+}
+
+/// Load the configuration of the crate currently being compiled.
+///
+/// Missing manifests or metadata tables yield the default configuration.
+pub fn load_crate_config() -> Result<CrateConfig, String> {
+    let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") else {
+        return Ok(CrateConfig::default());
+    };
+    let manifest_path = PathBuf::from(manifest_dir).join("Cargo.toml");
+
+    if let Some(config) = CONFIG_CACHE.with(|cache| cache.borrow().get(&manifest_path).cloned()) {
+        return Ok(config);
+    }
+
+    let config = match std::fs::read_to_string(&manifest_path) {
+        Ok(contents) => parse_crate_config(&contents)?,
+        Err(_) => CrateConfig::default(),
+    };
+
+    CONFIG_CACHE.with(|cache| {
+        cache.borrow_mut().insert(manifest_path, config.clone());
+    });
+
+    Ok(config)
+}
+
+/// Parse the `[package.metadata.tauri-bridge]` table out of a manifest.
+pub fn parse_crate_config(manifest: &str) -> Result<CrateConfig, String> {
+    let manifest: toml::Table = manifest
+        .parse()
+        .map_err(|e| format!("Failed to parse Cargo.toml: {}", e))?;
+
+    let Some(table) = manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("tauri-bridge"))
+        .and_then(|table| table.as_table())
+    else {
+        return Ok(CrateConfig::default());
+    };
+
+    Ok(CrateConfig {
+        backend_cfg: parse_cfg_entry(table, "backend_cfg")?,
+        client_cfg: parse_cfg_entry(table, "client_cfg")?,
+        manifest_dir: parse_string_entry(table, "manifest_dir")?,
+        ts_dir: parse_string_entry(table, "ts_dir")?,
+        ts_client: parse_ts_client_entry(table, "ts_client")?,
+        ts_schemas: parse_ts_schemas_entry(table, "ts_schemas")?,
+        strict: parse_bool_entry(table, "strict")?,
+        with_options: parse_bool_entry(table, "with_options")?,
+        priority: parse_priority_entry(table, "priority")?,
+        debug_log: parse_bool_entry(table, "debug_log")?,
+        log_slow: parse_bool_entry(table, "log_slow")?,
+        metrics: parse_bool_entry(table, "metrics")?,
+        layers: parse_bool_entry(table, "layers")?,
+        rejections: parse_bool_entry(table, "rejections")?,
+        translate_errors: parse_bool_entry(table, "translate_errors")?,
+        rebind: parse_bool_entry(table, "rebind")?,
+        replayable: parse_bool_entry(table, "replayable")?,
+        into_params: parse_bool_entry(table, "into_params")?,
+        json_bytes: parse_bool_entry(table, "json_bytes")?,
+        detect_tauri: parse_detect_tauri_entry(table, "detect_tauri")?,
+        crate_paths: parse_crate_paths_entry(table, "crate_paths")?,
+    })
+}
+
+fn parse_cfg_entry(table: &toml::Table, key: &str) -> Result<Option<Meta>, String> {
+    let Some(predicate) = parse_string_entry(table, key)? else {
+        return Ok(None);
+    };
+    syn::parse_str::<Meta>(&predicate)
+        .map(Some)
+        .map_err(|e| format!("Invalid cfg predicate for `{}`: {}", key, e))
+}
+
+fn parse_string_entry(table: &toml::Table, key: &str) -> Result<Option<String>, String> {
+    let Some(value) = table.get(key) else {
+        return Ok(None);
+    };
+    value
+        .as_str()
+        .map(|value| Some(value.to_string()))
+        .ok_or_else(|| format!("tauri-bridge metadata `{}` must be a string", key))
+}
+
+fn parse_ts_client_entry(table: &toml::Table, key: &str) -> Result<TsClient, String> {
+    let Some(client) = parse_string_entry(table, key)? else {
+        return Ok(TsClient::default());
+    };
+    TsClient::from_name(&client).ok_or_else(|| {
+        format!(
+            "tauri-bridge metadata `{}` must be \"plain\" or \"react-query\"",
+            key
+        )
+    })
+}
+
+fn parse_ts_schemas_entry(table: &toml::Table, key: &str) -> Result<Option<TsSchema>, String> {
+    let Some(schemas) = parse_string_entry(table, key)? else {
+        return Ok(None);
+    };
+    TsSchema::from_name(&schemas).map(Some).ok_or_else(|| {
+        format!(
+            "tauri-bridge metadata `{}` must be \"zod\" or \"valibot\"",
+            key
+        )
+    })
+}
+
+fn parse_priority_entry(table: &toml::Table, key: &str) -> Result<Option<Priority>, String> {
+    let Some(priority) = parse_string_entry(table, key)? else {
+        return Ok(None);
+    };
+    Priority::from_name(&priority).map(Some).ok_or_else(|| {
+        format!(
+            "tauri-bridge metadata `{}` must be \"low\" or \"normal\"",
+            key
+        )
+    })
+}
+
+fn parse_crate_paths_entry(table: &toml::Table, key: &str) -> Result<CratePaths, String> {
+    let Some(value) = table.get(key) else {
+        return Ok(CratePaths::default());
+    };
+    let paths = value
+        .as_table()
+        .ok_or_else(|| format!("tauri-bridge metadata `{}` must be a table", key))?;
+    let mut crate_paths = CratePaths::default();
+    for (name, path) in paths {
+        let path = path
+            .as_str()
+            .and_then(CratePaths::parse_path)
+            .ok_or_else(|| format!("tauri-bridge metadata `{}.{}` must be a path", key, name))?;
+        match name.as_str() {
+            "serde" => crate_paths.serde = Some(path),
+            "serde_wasm_bindgen" => crate_paths.serde_wasm_bindgen = Some(path),
+            _ => {
+                return Err(format!(
+                    "tauri-bridge metadata `{}` only takes `serde` and `serde_wasm_bindgen`",
+                    key
+                ));
+            }
+        }
+    }
+    Ok(crate_paths)
+}
+
+fn parse_detect_tauri_entry(
+    table: &toml::Table,
+    key: &str,
+) -> Result<Option<TauriDetection>, String> {
+    let Some(value) = table.get(key) else {
+        return Ok(None);
+    };
+    if let Some(detect) = value.as_bool() {
+        return Ok(detect.then(TauriDetection::default));
+    }
+    let settings = value.as_table().ok_or_else(|| {
+        format!(
+            "tauri-bridge metadata `{}` must be a boolean or a table",
+            key
+        )
+    })?;
+    let mut detection = TauriDetection::default();
+    for (name, fallback) in settings {
+        if name != "fallback" {
+            return Err(format!(
+                "tauri-bridge metadata `{}` only takes `fallback`",
+                key
+            ));
+        }
+        let fallback = fallback
+            .as_str()
+            .and_then(CratePaths::parse_path)
+            .ok_or_else(|| format!("tauri-bridge metadata `{}.{}` must be a path", key, name))?;
+        detection.fallback = Some(fallback);
+    }
+    Ok(Some(detection))
+}
+
+fn parse_bool_entry(table: &toml::Table, key: &str) -> Result<bool, String> {
+    let Some(value) = table.get(key) else {
+        return Ok(false);
+    };
+    value
+        .as_bool()
+        .ok_or_else(|| format!("tauri-bridge metadata `{}` must be a boolean", key))
+}
@@ -0,0 +1,127 @@
+//! Client defaults of parameters marked `#[bridge(default)]`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn search(
+//!     query: String,
+//!     #[bridge(default)] tags: Option<Vec<String>>,
+//!     #[bridge(default = "20")] limit: u32,
+//! ) -> Vec<Note> {}
+//! ```
+//!
+//! Trailing parameters may have a default, `Default::default()` or the
+//! given expression. The client then also gets `try_<name>_with_defaults`
+//! and `<name>_with_defaults`, taking only the other parameters and sending
+//! the defaults for the rest. The backend receives every argument as usual.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::{Expr, FnArg, ItemFn, LitStr, PatType};
+
+use crate::inject::{SESSION, TARGET_LABEL, has_marker, is_caller};
+use crate::types::has_reference_type;
+
+/// Name of the parameter attribute.
+pub const BRIDGE_ATTR: &str = "bridge";
+
+/// Default of a parameter, as written in its attribute.
+pub enum ParamDefault {
+    /// `#[bridge(default)]`: `Default::default()`.
+    Default,
+    /// `#[bridge(default = "expr")]`.
+    Expr(Expr),
+}
+
+/// Parse the parameter's `#[bridge(..)]` attributes.
+pub fn param_default(pat_type: &PatType) -> syn::Result<Option<ParamDefault>> {
+    let mut default = None;
+    for attr in pat_type
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(BRIDGE_ATTR))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("map_as") {
+                // Parsed by `entries::entries_param`
+                let _: LitStr = meta.value()?.parse()?;
+                return Ok(());
+            }
+            if !meta.path.is_ident("default") {
+                return Err(meta
+                    .error("expected `default`, `default = \"expr\"` or `map_as = \"entries\"`"));
+            }
+            default = Some(if meta.input.peek(syn::Token![=]) {
+                let expr: LitStr = meta.value()?.parse()?;
+                ParamDefault::Expr(expr.parse()?)
+            } else {
+                ParamDefault::Default
+            });
+            Ok(())
+        })?;
+    }
+    Ok(default)
+}
+
+/// The expression sending the parameter's default.
+pub fn default_value(pat_type: &PatType, default: &ParamDefault) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let ty = &pat_type.ty;
+    match default {
+        ParamDefault::Default => {
+            quote_spanned! {call_site=> <#ty as ::core::default::Default>::default() }
+        }
+        ParamDefault::Expr(expr) => quote_spanned! {call_site=>
+            {
+                let value: #ty = #expr;
+                value
+            }
+        },
+    }
+}
+
+/// Check that defaults are valid and only on trailing, owned parameters.
+///
+/// The routing and session parameters aren't passed by callers, so they
+/// don't count.
+pub fn check_defaults(input: &ItemFn) -> syn::Result<()> {
+    let mut defaulted = None;
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let default = param_default(pat_type)?;
+        let routing = is_caller(pat_type) || has_marker(pat_type, TARGET_LABEL);
+        let session = has_marker(pat_type, SESSION);
+        match default {
+            Some(_) if routing => {
+                return Err(syn::Error::new_spanned(
+                    pat_type,
+                    "routing parameters can't have a default",
+                ));
+            }
+            Some(_) if session => {
+                return Err(syn::Error::new_spanned(
+                    pat_type,
+                    "`#[session]` parameters can't have a default",
+                ));
+            }
+            Some(_) if has_reference_type(&pat_type.ty) => {
+                return Err(syn::Error::new_spanned(
+                    &pat_type.ty,
+                    "parameters with a default must be owned",
+                ));
+            }
+            Some(_) => defaulted = Some(pat_type),
+            None if routing || session => {}
+            None => {
+                if let Some(defaulted) = defaulted {
+                    return Err(syn::Error::new_spanned(
+                        defaulted,
+                        "only trailing parameters can have a default",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Structured model of what `#[tauri_bridge]` generates for a function, for
+//! tools that would otherwise have to read the expanded tokens.
+//!
+//! ```rust,ignore
+//! let description = tauri_bridge_core::describe(
+//!     quote! { namespace = "users" },
+//!     quote! {
+//!         pub async fn get_user(user_id: u64) -> Result<User, String> { .. }
+//!     },
+//! )?;
+//! assert_eq!(description.command, "users.get_user");
+//! assert_eq!(description.args[0].wire_name, "userId");
+//! ```
+
+use convert_case::{Case, Casing};
+use syn::ext::IdentExt;
+use syn::{FnArg, Item, ItemFn, Pat, ReturnType};
+
+use crate::alias::alias_client;
+use crate::attrs::BridgeArgs;
+use crate::client::generate_client;
+use crate::entries::{entries_param, entries_wire_type};
+use crate::inject::{TARGET_LABEL, has_marker, is_injected};
+use crate::manifest::{doc_comment, type_to_string};
+use crate::types::{owned_value_type, result_types};
+
+/// What `#[tauri_bridge]` generates for a function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BridgeDescription {
+    /// Name of the Rust function.
+    pub name: String,
+    /// Name the backend command is registered and invoked under, e.g.
+    /// `users.get_user@2`.
+    pub command: String,
+    /// Further names the backend command is registered under, with `alias`.
+    pub aliases: Vec<String>,
+    /// Command version, 1 unless set with `version`.
+    pub version: u32,
+    pub is_async: bool,
+    /// Arguments in the invoke payload, in order.
+    pub args: Vec<ArgDescription>,
+    /// Type of a successful response; `()` for commands returning nothing.
+    pub response: String,
+    /// Error type of commands returning a `Result`.
+    pub error: Option<String>,
+    /// Whether the response is sent as raw bytes rather than JSON.
+    pub raw_bytes_response: bool,
+    /// Names of the client items, e.g. `try_get_user`, `get_user` and
+    /// `GetUserArgs`.
+    pub client_items: Vec<String>,
+    pub docs: String,
+}
+
+/// An argument of a bridged command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgDescription {
+    /// Name of the Rust parameter.
+    pub name: String,
+    /// Key of the argument in the invoke payload.
+    pub wire_name: String,
+    /// Type of the parameter, as written.
+    pub ty: String,
+    /// Type the argument is serialized from, with references replaced by
+    /// owned types.
+    pub wire_type: String,
+}
+
+/// Describe a command prepared for expansion.
+pub fn describe_command(input: &ItemFn, args: &BridgeArgs) -> BridgeDescription {
+    // The `#[target_label]` is only sent by the `_to` client variant
+    let arg_descriptions = input
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type)
+                if !is_injected(pat_type) && !has_marker(pat_type, TARGET_LABEL) =>
+            {
+                let name = match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => pat_ident.ident.unraw().to_string(),
+                    _ => return None,
+                };
+                Some(ArgDescription {
+                    wire_name: name.to_case(Case::Camel),
+                    name,
+                    ty: type_to_string(&pat_type.ty),
+                    wire_type: if entries_param(pat_type).unwrap_or(false) {
+                        type_to_string(&entries_wire_type(&pat_type.ty))
+                    } else {
+                        type_to_string(&owned_value_type(&pat_type.ty))
+                    },
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    let (response, error) = match &input.sig.output {
+        ReturnType::Default => ("()".to_string(), None),
+        ReturnType::Type(_, ty) => match result_types(ty) {
+            Some((ok, error)) => (type_to_string(ok), Some(type_to_string(error))),
+            None => (type_to_string(ty), None),
+        },
+    };
+
+    BridgeDescription {
+        name: input.sig.ident.unraw().to_string(),
+        command: args.command_name(input),
+        aliases: args.aliases.clone(),
+        version: args.command_version(),
+        is_async: input.sig.asyncness.is_some(),
+        args: arg_descriptions,
+        response,
+        error,
+        raw_bytes_response: args.raw_bytes_response(input),
+        client_items: client_items(input, args),
+        docs: doc_comment(&input.attrs),
+    }
+}
+
+/// Names of the items in the generated client modules, read back from them
+/// so they always match.
+fn client_items(input: &ItemFn, args: &BridgeArgs) -> Vec<String> {
+    let mut client = generate_client(input, args);
+    if args.alias_client {
+        for alias in &args.aliases {
+            let (alias_input, alias_args) = alias_client(input, args, alias);
+            client.extend(generate_client(&alias_input, &alias_args));
+        }
+    }
+    let Ok(file) = syn::parse2::<syn::File>(client) else {
+        return Vec::new();
+    };
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Mod(module) => module.content.as_ref(),
+            _ => None,
+        })
+        .flat_map(|(_, items)| items)
+        .filter_map(|item| match item {
+            Item::Fn(item) => Some(item.sig.ident.to_string()),
+            Item::Struct(item) => Some(item.ident.to_string()),
+            Item::Const(item) => Some(item.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
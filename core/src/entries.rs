@@ -0,0 +1,210 @@
+//! Maps sent as arrays of `[key, value]` pairs with `map_as = "entries"`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(map_as = "entries")]
+//! pub fn scores(#[bridge(map_as = "entries")] bonus: HashMap<u32, u64>) -> HashMap<u32, u64> {}
+//! ```
+//!
+//! On a parameter, the client's args struct serializes the map through
+//! `tauri_bridge_runtime::entries`, and the backend command takes it as an
+//! `Entries<HashMap<..>>`. On the command, the backend responds with the
+//! `Entries` of the map and the client decodes them back. Either way the
+//! original function is kept as an inner function taking and returning the
+//! plain map.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::{
+    FnArg, GenericArgument, ItemFn, LitStr, PatType, PathArguments, ReturnType, Type, parse_quote,
+};
+
+use crate::attrs::BridgeArgs;
+use crate::defaults::BRIDGE_ATTR;
+use crate::inject::{SESSION, is_injected, marked_param};
+use crate::types::{has_reference_type, result_types};
+
+/// Parse `map_as = "entries"`, the only strategy besides the default.
+pub fn parse_map_as(value: &LitStr) -> syn::Result<()> {
+    if value.value() == "entries" {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            value,
+            "expected `map_as = \"entries\"`",
+        ))
+    }
+}
+
+/// Whether the parameter is marked `#[bridge(map_as = "entries")]`.
+pub fn entries_param(pat_type: &PatType) -> syn::Result<bool> {
+    let mut entries = false;
+    for attr in pat_type
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(BRIDGE_ATTR))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("map_as") {
+                parse_map_as(&meta.value()?.parse()?)?;
+                entries = true;
+            } else if meta.input.peek(syn::Token![=]) {
+                // Other options are parsed where they're used
+                let _: LitStr = meta.value()?.parse()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(entries)
+}
+
+/// Whether the command sends a parameter or its response as entries.
+pub fn uses_entries(input: &ItemFn, args: &BridgeArgs) -> bool {
+    args.map_entries
+        || input.sig.inputs.iter().any(|arg| match arg {
+            FnArg::Typed(pat_type) => entries_param(pat_type).unwrap_or(false),
+            FnArg::Receiver(_) => false,
+        })
+}
+
+/// Reject options with their own wire format, and entries parameters that
+/// aren't owned arguments sent by the client.
+pub fn check_entries(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        if !entries_param(pat_type)? {
+            continue;
+        }
+        if is_injected(pat_type) {
+            return Err(syn::Error::new_spanned(
+                pat_type,
+                "injected parameters aren't sent, so they can't be sent as entries",
+            ));
+        }
+        if has_reference_type(&pat_type.ty) {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "parameters sent as entries must be owned",
+            ));
+        }
+    }
+    if args.map_entries && matches!(input.sig.output, ReturnType::Default) {
+        return Err(syn::Error::new_spanned(
+            &input.sig.ident,
+            "`map_as` on the command sends its map response as entries, but it has no response",
+        ));
+    }
+    if !uses_entries(input, args) {
+        return Ok(());
+    }
+
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`map_as` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`map_as` can't be combined with `compress`",
+        ),
+        (args.encrypt, "`map_as` can't be combined with `encrypt`"),
+        (
+            args.incremental,
+            "`map_as` can't be combined with `incremental`",
+        ),
+        (args.by_value, "`map_as` can't be combined with `by_value`"),
+        (
+            args.raw_bytes_response(input),
+            "`map_as` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.raw_response,
+            "`map_as` can't be combined with `raw_response`",
+        ),
+        (
+            args.unwrap_envelope,
+            "`map_as` can't be combined with `unwrap_envelope`",
+        ),
+        (args.non_send, "`map_as` can't be combined with `non_send`"),
+        (args.layers, "`map_as` can't be combined with `layers`"),
+        (
+            args.sidecar.is_some(),
+            "`map_as` can't be combined with `sidecar`",
+        ),
+        (
+            args.catch_panic,
+            "`map_as` can't be combined with `catch_panic`",
+        ),
+        (
+            args.max_payload.is_some(),
+            "`map_as` can't be combined with `max_payload`",
+        ),
+        (
+            args.test_harness,
+            "`map_as` can't be combined with `test_harness`",
+        ),
+        (
+            args.transaction.is_some(),
+            "`map_as` can't be combined with `transaction`",
+        ),
+        (
+            marked_param(input, SESSION).is_some(),
+            "`map_as` can't be combined with a `#[session]` parameter",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// `Entries<ty>`, the type the map is sent as.
+pub fn entries_type(ty: &Type) -> TokenStream2 {
+    quote_spanned! {Span::call_site()=> tauri_bridge_runtime::entries::Entries<#ty> }
+}
+
+/// The function with its maps sent as entries spelled as `Vec<(K, V)>`,
+/// the shape they have on the wire, for the TypeScript client and the spec.
+pub fn wire_input(input: &ItemFn, args: &BridgeArgs) -> ItemFn {
+    let mut input = input.clone();
+    for arg in input.sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = arg
+            && entries_param(pat_type).unwrap_or(false)
+        {
+            *pat_type.ty = entries_wire_type(&pat_type.ty);
+        }
+    }
+    if args.map_entries
+        && let ReturnType::Type(_, ty) = &mut input.sig.output
+    {
+        let wire = match result_types(ty) {
+            Some((ok, error)) => {
+                let ok = entries_wire_type(ok);
+                parse_quote!(Result<#ok, #error>)
+            }
+            None => entries_wire_type(ty),
+        };
+        **ty = wire;
+    }
+    input
+}
+
+/// `Vec<(K, V)>` for a `HashMap<K, V>` or `BTreeMap<K, V>`, other types as
+/// they are.
+pub fn entries_wire_type(ty: &Type) -> Type {
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+        && (segment.ident == "HashMap" || segment.ident == "BTreeMap")
+        && let PathArguments::AngleBracketed(generics) = &segment.arguments
+    {
+        let mut types = generics.args.iter().filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        });
+        if let (Some(key), Some(value)) = (types.next(), types.next()) {
+            return parse_quote!(Vec<(#key, #value)>);
+        }
+    }
+    ty.clone()
+}
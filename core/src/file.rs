@@ -0,0 +1,230 @@
+//! `#[bridge_file]`: chunked file reads and writes with progress.
+//!
+//! ```rust,ignore
+//! #[bridge_file(chunk_size = 262_144)]
+//! pub fn project_file(path: &str) -> Result<PathBuf, String> {
+//!     Ok(project_dir().join(path))
+//! }
+//! ```
+//!
+//! The annotated function resolves (and validates) the path the client
+//! asked for. The backend gets `project_file_read` and `project_file_write`
+//! commands transferring one chunk per invoke; the client gets
+//! `try_project_file_read` and `try_project_file_write`, which loop over
+//! the chunks and report progress.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, FnArg, ItemFn, Lit, Meta, Token, Type};
+
+use crate::attrs::BridgeArgs;
+use crate::client::nested_visibility;
+
+/// Options of `#[bridge_file(...)]`.
+pub struct FileArgs {
+    /// Bytes transferred per invoke.
+    pub chunk_size: usize,
+    /// Only `backend_cfg` and `client_cfg` apply.
+    pub bridge_args: BridgeArgs,
+}
+
+impl Parse for FileArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = FileArgs {
+            chunk_size: 262_144,
+            bridge_args: BridgeArgs::default(),
+        };
+
+        while !input.is_empty() {
+            let meta: Meta = input.parse()?;
+            if meta.path().is_ident("chunk_size") {
+                args.chunk_size = parse_chunk_size(&meta)?;
+            } else if meta.path().is_ident("backend_cfg") || meta.path().is_ident("client_cfg") {
+                args.bridge_args.apply(meta)?;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    meta.path(),
+                    "expected `chunk_size`, `backend_cfg` or `client_cfg`",
+                ));
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(args)
+    }
+}
+
+/// Parse `chunk_size = 65536`.
+fn parse_chunk_size(meta: &Meta) -> syn::Result<usize> {
+    if let Meta::NameValue(name_value) = meta
+        && let Expr::Lit(expr_lit) = &name_value.value
+        && let Lit::Int(value) = &expr_lit.lit
+    {
+        let chunk_size = value.base10_parse()?;
+        if chunk_size == 0 {
+            return Err(syn::Error::new_spanned(
+                value,
+                "chunk size must be at least 1 byte",
+            ));
+        }
+        return Ok(chunk_size);
+    }
+    Err(syn::Error::new_spanned(
+        meta,
+        "expected `chunk_size = <bytes>`",
+    ))
+}
+
+/// The resolver takes the requested path as its only parameter, a `&str`.
+pub fn check_resolver(input: &ItemFn) -> syn::Result<()> {
+    let mut params = input.sig.inputs.iter();
+    match (params.next(), params.next()) {
+        (Some(FnArg::Typed(pat_type)), None) if is_str_ref(&pat_type.ty) => Ok(()),
+        _ => Err(syn::Error::new_spanned(
+            &input.sig.inputs,
+            "`#[bridge_file]` functions take the requested path as their only parameter, \
+             e.g. `fn project_file(path: &str) -> Result<PathBuf, String>`",
+        )),
+    }
+}
+
+fn is_str_ref(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Reference(reference)
+            if reference.mutability.is_none()
+                && matches!(reference.elem.as_ref(), Type::Path(path) if path.path.is_ident("str"))
+    )
+}
+
+/// Generate the resolver, the backend commands and the client helpers.
+pub fn generate_file(input: &ItemFn, args: &FileArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let item_vis = nested_visibility(vis);
+    let name = input.sig.ident.unraw().to_string();
+    let resolver = &input.sig.ident;
+    let chunk_size = args.chunk_size;
+    let backend_cfg = args.bridge_args.backend_cfg_attr();
+    let client_cfg = args.bridge_args.client_cfg_attr();
+    let await_token = input
+        .sig
+        .asyncness
+        .map(|_| quote_spanned! {call_site=> .await });
+
+    let backend_mod = syn::Ident::new(&format!("__tauri_file_{}", name), call_site);
+    let client_mod = syn::Ident::new(&format!("__tauri_file_client_{}", name), call_site);
+    let read_command = format!("{}_read", name);
+    let write_command = format!("{}_write", name);
+    let read_fn = syn::Ident::new(&read_command, call_site);
+    let write_fn = syn::Ident::new(&write_command, call_site);
+    let try_read_fn = syn::Ident::new(&format!("try_{}", read_command), call_site);
+    let try_write_fn = syn::Ident::new(&format!("try_{}", write_command), call_site);
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        #input
+
+        #backend_cfg
+        mod #backend_mod {
+            use super::*;
+
+            #[tauri::command]
+            #item_vis async fn #read_fn(
+                path: String,
+                offset: u64,
+            ) -> Result<tauri_bridge_runtime::files::FileChunk, String> {
+                let path = #resolver(&path) #await_token .map_err(|e| e.to_string())?;
+                tauri_bridge_runtime::files::read_chunk(&path, offset, #chunk_size)
+            }
+
+            #[tauri::command]
+            #item_vis async fn #write_fn(path: String, offset: u64, data: String) -> Result<(), String> {
+                let path = #resolver(&path) #await_token .map_err(|e| e.to_string())?;
+                tauri_bridge_runtime::files::write_chunk(&path, offset, &data)
+            }
+        }
+
+        #backend_cfg
+        #vis use #backend_mod::{#read_fn, #write_fn};
+
+        #client_cfg
+        mod #client_mod {
+            use super::*;
+
+            #[derive(serde::Serialize)]
+            struct ReadArgs<'a> {
+                path: &'a str,
+                offset: u64,
+            }
+
+            #[derive(serde::Serialize)]
+            struct WriteArgs<'a> {
+                path: &'a str,
+                offset: u64,
+                data: String,
+            }
+
+            /// Read the file in chunks; `on_progress` receives the bytes
+            /// read so far and the file size.
+            #item_vis async fn #try_read_fn(
+                path: &str,
+                mut on_progress: impl FnMut(u64, u64),
+            ) -> Result<Vec<u8>, String> {
+                let mut bytes = Vec::new();
+                loop {
+                    let args = serde_wasm_bindgen::to_value(&ReadArgs {
+                        path,
+                        offset: bytes.len() as u64,
+                    })
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                    let result = crate::invoke(#read_command, args).await;
+                    let chunk =
+                        serde_wasm_bindgen::from_value::<tauri_bridge_runtime::files::FileChunk>(result)
+                            .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+                    let data = tauri_bridge_runtime::files::decode_bytes(&chunk.data)?;
+                    bytes.extend_from_slice(&data);
+                    on_progress(bytes.len() as u64, chunk.size);
+                    if data.is_empty() || bytes.len() as u64 >= chunk.size {
+                        return Ok(bytes);
+                    }
+                }
+            }
+
+            /// Write the file in chunks; `on_progress` receives the bytes
+            /// written so far and the total.
+            #item_vis async fn #try_write_fn(
+                path: &str,
+                data: &[u8],
+                mut on_progress: impl FnMut(u64, u64),
+            ) -> Result<(), String> {
+                let mut offset = 0;
+                // At least one chunk, so empty files are created too
+                loop {
+                    let end = data.len().min(offset + #chunk_size);
+                    let args = serde_wasm_bindgen::to_value(&WriteArgs {
+                        path,
+                        offset: offset as u64,
+                        data: tauri_bridge_runtime::files::encode_bytes(&data[offset..end]),
+                    })
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                    crate::invoke(#write_command, args).await;
+                    offset = end;
+                    on_progress(offset as u64, data.len() as u64);
+                    if offset >= data.len() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        #client_cfg
+        #vis use #client_mod::{#try_read_fn, #try_write_fn};
+    }
+}
@@ -0,0 +1,150 @@
+//! Non-finite floats sent under a policy with `floats = ".."`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(floats = "string")]
+//! pub fn record_samples(samples: Vec<f64>) -> Stats {}
+//! ```
+//!
+//! JSON has no NaN or infinities, so by default they arrive as `null`. With
+//! a policy, the client's args struct serializes its fields through
+//! `tauri_bridge_runtime::floats::{reject, null, string}` and decodes the
+//! response as a `Floats<T, P>`; the backend command takes its parameters
+//! and returns its response as `Floats<T, P>`. The original function is
+//! kept as an inner function taking and returning the plain values.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::{FnArg, ItemFn, PatType, Type};
+
+use crate::attrs::{BridgeArgs, FloatPolicy};
+use crate::entries::uses_entries;
+use crate::inject::{SESSION, is_injected, marked_param};
+use crate::types::{borrowed_param, has_reference_type, owned_type};
+
+/// Reject options with their own wire format, and parameters that can't be
+/// decoded as owned values.
+pub fn check_floats(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if args.floats.is_none() {
+        return Ok(());
+    }
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg
+            && !is_injected(pat_type)
+            && has_reference_type(&pat_type.ty)
+            && borrowed_param(&pat_type.ty).is_none()
+        {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "`floats` commands take owned parameters or `&str`, `&[T]` and `&T`",
+            ));
+        }
+    }
+
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`floats` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`floats` can't be combined with `compress`",
+        ),
+        (args.encrypt, "`floats` can't be combined with `encrypt`"),
+        (
+            args.incremental,
+            "`floats` can't be combined with `incremental`",
+        ),
+        (args.by_value, "`floats` can't be combined with `by_value`"),
+        (
+            args.raw_bytes_response(input),
+            "`floats` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.raw_response,
+            "`floats` can't be combined with `raw_response`",
+        ),
+        (
+            args.unwrap_envelope,
+            "`floats` can't be combined with `unwrap_envelope`",
+        ),
+        (args.non_send, "`floats` can't be combined with `non_send`"),
+        (args.layers, "`floats` can't be combined with `layers`"),
+        (
+            args.sidecar.is_some(),
+            "`floats` can't be combined with `sidecar`",
+        ),
+        (
+            args.catch_panic,
+            "`floats` can't be combined with `catch_panic`",
+        ),
+        (
+            args.max_payload.is_some(),
+            "`floats` can't be combined with `max_payload`",
+        ),
+        (
+            args.test_harness,
+            "`floats` can't be combined with `test_harness`",
+        ),
+        (
+            args.transaction.is_some(),
+            "`floats` can't be combined with `transaction`",
+        ),
+        (
+            uses_entries(input, args),
+            "`floats` can't be combined with `map_as`",
+        ),
+        (
+            marked_param(input, SESSION).is_some(),
+            "`floats` can't be combined with a `#[session]` parameter",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// `Floats<ty, P>`, the type a value is sent as under `policy`.
+pub fn floats_type(ty: &TokenStream2, policy: FloatPolicy) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let marker = match policy {
+        FloatPolicy::Error => quote_spanned! {call_site=> Reject },
+        FloatPolicy::Null => quote_spanned! {call_site=> AsNull },
+        FloatPolicy::String => quote_spanned! {call_site=> AsString },
+    };
+    quote_spanned! {call_site=> tauri_bridge_runtime::floats::Floats<#ty, tauri_bridge_runtime::floats::#marker> }
+}
+
+/// The backend parameter of a sent argument and the expression passing it
+/// on to the inner function; borrowed parameters are decoded owned.
+pub fn floats_param(pat_type: &PatType, policy: FloatPolicy) -> Option<(TokenStream2, bool)> {
+    if is_injected(pat_type) {
+        return None;
+    }
+    let call_site = Span::call_site();
+    Some(match borrowed_param(&pat_type.ty) {
+        Some(borrowed) => (floats_type(&owned_type(&borrowed), policy), true),
+        None => {
+            let ty = &pat_type.ty;
+            (
+                floats_type(&quote_spanned! {call_site=> #ty }, policy),
+                false,
+            )
+        }
+    })
+}
+
+/// Path of the `#[serde(with = "..")]` module of the client's args fields.
+pub fn floats_with(policy: FloatPolicy) -> &'static str {
+    match policy {
+        FloatPolicy::Error => "tauri_bridge_runtime::floats::reject",
+        FloatPolicy::Null => "tauri_bridge_runtime::floats::null",
+        FloatPolicy::String => "tauri_bridge_runtime::floats::string",
+    }
+}
+
+/// `Floats<ty, P>` of a response type.
+pub fn floats_response_type(ty: &Type, policy: FloatPolicy) -> TokenStream2 {
+    let call_site = Span::call_site();
+    floats_type(&quote_spanned! {call_site=> #ty }, policy)
+}
@@ -0,0 +1,278 @@
+//! `#[bridge_form]`: settings-style structs loaded and saved as a whole.
+//!
+//! ```rust,ignore
+//! #[bridge_form(load = read_settings, save = write_settings)]
+//! pub struct Settings {
+//!     pub theme: String,
+//!     pub font_size: u32,
+//! }
+//! ```
+//!
+//! The backend gets `load_settings` and `save_settings` commands calling
+//! the given functions; the client gets a `SettingsForm` handle with a
+//! getter and setter per field and a dirty flag, saving the whole struct
+//! through `save_settings`.
+
+use std::collections::HashSet;
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, Fields, ItemStruct, Meta, Path, Token, Type};
+
+use crate::attrs::BridgeArgs;
+use crate::client::nested_visibility;
+
+/// Methods of the client handle, which fields can't shadow.
+const HANDLE_METHODS: &[&str] = &["load", "reload", "save", "is_dirty", "value", "into_value"];
+
+/// Options of `#[bridge_form(...)]`.
+pub struct FormArgs {
+    /// Backend function returning the stored struct.
+    pub load: Path,
+    /// Backend function storing the struct.
+    pub save: Path,
+    /// Only `backend_cfg` and `client_cfg` apply.
+    pub bridge_args: BridgeArgs,
+}
+
+impl Parse for FormArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut load = None;
+        let mut save = None;
+        let mut bridge_args = BridgeArgs::default();
+
+        while !input.is_empty() {
+            let meta: Meta = input.parse()?;
+            if meta.path().is_ident("load") {
+                load = Some(parse_function(&meta)?);
+            } else if meta.path().is_ident("save") {
+                save = Some(parse_function(&meta)?);
+            } else if meta.path().is_ident("backend_cfg") || meta.path().is_ident("client_cfg") {
+                bridge_args.apply(meta)?;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    meta.path(),
+                    "expected `load`, `save`, `backend_cfg` or `client_cfg`",
+                ));
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        match (load, save) {
+            (Some(load), Some(save)) => Ok(FormArgs {
+                load,
+                save,
+                bridge_args,
+            }),
+            _ => {
+                Err(input.error("`bridge_form` needs `load = <function>` and `save = <function>`"))
+            }
+        }
+    }
+}
+
+/// Parse `load = read_settings`.
+fn parse_function(meta: &Meta) -> syn::Result<Path> {
+    if let Meta::NameValue(name_value) = meta
+        && let Expr::Path(path) = &name_value.value
+    {
+        return Ok(path.path.clone());
+    }
+    Err(syn::Error::new_spanned(
+        meta,
+        "expected `<option> = <function>`",
+    ))
+}
+
+/// Check that the struct has named fields, no generics, and no field
+/// named like a method of the handle.
+pub fn check_form(input: &ItemStruct) -> syn::Result<()> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`#[bridge_form]` structs can't be generic",
+        ));
+    }
+    let Fields::Named(fields) = &input.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.fields,
+            "`#[bridge_form]` structs need named fields",
+        ));
+    };
+
+    let mut methods: HashSet<String> = HANDLE_METHODS.iter().map(|m| m.to_string()).collect();
+    for field in &fields.named {
+        let name = field
+            .ident
+            .as_ref()
+            .expect("named field")
+            .unraw()
+            .to_string();
+        for method in [name.clone(), format!("set_{}", name)] {
+            if !methods.insert(method.clone()) {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("field clashes with the form handle's `{}` method", method),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generate the struct, the backend commands and the client handle.
+pub fn generate_form(mut input: ItemStruct, args: &FormArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    input.attrs.insert(
+        0,
+        syn::parse_quote!(#[derive(serde::Serialize, serde::Deserialize)]),
+    );
+
+    let vis = &input.vis;
+    let item_vis = nested_visibility(vis);
+    let ty = &input.ident;
+    let name = ty.unraw().to_string().to_case(Case::Snake);
+    let load = &args.load;
+    let save = &args.save;
+    let backend_cfg = args.bridge_args.backend_cfg_attr();
+    let client_cfg = args.bridge_args.client_cfg_attr();
+
+    let backend_mod = syn::Ident::new(&format!("__tauri_form_{}", name), call_site);
+    let client_mod = syn::Ident::new(&format!("__tauri_form_client_{}", name), call_site);
+    let load_command = format!("load_{}", name);
+    let save_command = format!("save_{}", name);
+    let load_fn = syn::Ident::new(&load_command, call_site);
+    let save_fn = syn::Ident::new(&save_command, call_site);
+    let handle = syn::Ident::new(&format!("{}Form", ty.unraw()), call_site);
+
+    let Fields::Named(fields) = &input.fields else {
+        unreachable!("checked by check_form");
+    };
+    let accessors = fields.named.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("named field");
+        let field_ty: &Type = &field.ty;
+        let setter = syn::Ident::new(&format!("set_{}", ident.unraw()), call_site);
+        let get_doc = format!("The form's `{}`.", ident.unraw());
+        let set_doc = format!("Change `{}`, marking the form dirty.", ident.unraw());
+        quote_spanned! {call_site=>
+            #[doc = #get_doc]
+            pub fn #ident(&self) -> &#field_ty {
+                &self.value.#ident
+            }
+
+            #[doc = #set_doc]
+            pub fn #setter(&mut self, value: #field_ty) {
+                self.value.#ident = value;
+                self.dirty = true;
+            }
+        }
+    });
+    let handle_doc = format!(
+        "Client handle of a [`{}`] loaded with `{}` and saved with `{}`.",
+        ty, load_command, save_command
+    );
+
+    quote_spanned! {call_site=>
+        #input
+
+        #backend_cfg
+        mod #backend_mod {
+            use super::*;
+
+            #[tauri::command]
+            #item_vis fn #load_fn() -> Result<#ty, String> {
+                #load().map_err(|e| e.to_string())
+            }
+
+            #[tauri::command]
+            #item_vis fn #save_fn(value: #ty) -> Result<(), String> {
+                #save(value).map_err(|e| e.to_string())
+            }
+        }
+
+        #backend_cfg
+        #vis use #backend_mod::{#load_fn, #save_fn};
+
+        #client_cfg
+        mod #client_mod {
+            use super::*;
+
+            #[derive(serde::Serialize)]
+            struct LoadArgs {}
+
+            #[derive(serde::Serialize)]
+            struct SaveArgs<'a> {
+                value: &'a #ty,
+            }
+
+            #[doc = #handle_doc]
+            #item_vis struct #handle {
+                value: #ty,
+                dirty: bool,
+            }
+
+            impl #handle {
+                /// Load the stored value.
+                pub async fn load() -> Result<Self, String> {
+                    Ok(#handle {
+                        value: fetch().await?,
+                        dirty: false,
+                    })
+                }
+
+                /// Replace the value with the stored one, dropping changes.
+                pub async fn reload(&mut self) -> Result<(), String> {
+                    self.value = fetch().await?;
+                    self.dirty = false;
+                    Ok(())
+                }
+
+                /// Store the whole value with one invoke.
+                pub async fn save(&mut self) -> Result<(), String> {
+                    let args = serde_wasm_bindgen::to_value(&SaveArgs { value: &self.value })
+                        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                    let result = crate::invoke(#save_command, args).await;
+                    serde_wasm_bindgen::from_value::<Result<(), String>>(result)
+                        .map_err(|e| format!("Failed to deserialize response: {}", e))??;
+                    self.dirty = false;
+                    Ok(())
+                }
+
+                /// Whether a field changed since the last load or save.
+                pub fn is_dirty(&self) -> bool {
+                    self.dirty
+                }
+
+                /// The current value.
+                pub fn value(&self) -> &#ty {
+                    &self.value
+                }
+
+                /// The current value, saved or not.
+                pub fn into_value(self) -> #ty {
+                    self.value
+                }
+
+                #(#accessors)*
+            }
+
+            async fn fetch() -> Result<#ty, String> {
+                let args = serde_wasm_bindgen::to_value(&LoadArgs {})
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                let result = crate::invoke(#load_command, args).await;
+                serde_wasm_bindgen::from_value::<Result<#ty, String>>(result)
+                    .map_err(|e| format!("Failed to deserialize response: {}", e))?
+            }
+        }
+
+        #client_cfg
+        #vis use #client_mod::#handle;
+    }
+}
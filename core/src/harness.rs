@@ -0,0 +1,418 @@
+//! Native test dispatch of commands with `test_harness`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(test_harness)]
+//! pub fn greet(name: &str) -> String {}
+//!
+//! // In the crate's tests, behind the client's `invoke`
+//! let dispatch = tauri_bridge::bridge_dispatch![greet];
+//! let response = dispatch("greet", serde_json::json!({ "name": "Ada" })).await;
+//! ```
+//!
+//! Under `cfg(test)` each command gets a hidden dispatch function that
+//! decodes the JSON arguments the way Tauri does (camelCase keys, missing
+//! keys as `null`), calls the backend function and encodes its response, so
+//! a natively compiled client can be pointed at the real backend.
+//! `bridge_bench!` times commands through the same functions; the `bench`
+//! feature compiles them outside of tests for that. `bridge_contract!`
+//! sends the calls of recorded sessions through them. `sidecar` commands
+//! always get one, calling their body, for the sidecar binary to serve.
+//! The `fuzz` feature gives one to every command it can, for the targets
+//! of `bridge_fuzz!`.
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, FnArg, ItemFn, Pat, ReturnType, Token};
+
+use crate::attrs::BridgeArgs;
+use crate::inject::{CALLER_LABEL, SESSION, has_marker, is_caller_context, is_injected};
+use crate::registry::{CommandEntry, CommandList, sibling_item_path};
+use crate::sidecar::sidecar_impl_name;
+use crate::types::{borrowed_param, has_reference_type, owned_type, result_types};
+
+/// Prefix of the per-command dispatch function.
+pub const DISPATCH_PREFIX: &str = "__tauri_bridge_dispatch_";
+
+/// Prefix of the constant holding a dispatched command's wire name.
+pub const COMMAND_PREFIX: &str = "__TAURI_BRIDGE_COMMAND_";
+
+/// Reject commands whose backend can't be called without Tauri.
+///
+/// These options take channels, webviews or `tauri::ipc::Response`s, or
+/// change the backend's signature.
+pub fn check_test_harness(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let call_site = Span::call_site();
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`test_harness` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`test_harness` can't be combined with `compress`",
+        ),
+        (
+            args.encrypt,
+            "`test_harness` can't be combined with `encrypt`",
+        ),
+        (
+            args.incremental,
+            "`test_harness` can't be combined with `incremental`",
+        ),
+        (
+            args.by_value,
+            "`test_harness` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`test_harness` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.verify_schema,
+            "`test_harness` can't be combined with `verify_schema`",
+        ),
+        (
+            args.declaration_only,
+            "`test_harness` needs the backend function; it can't be combined with `extern`",
+        ),
+    ];
+    if let Some((_, message)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+        return Err(syn::Error::new(call_site, message));
+    }
+
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg
+            && let Some(marker) = [CALLER_LABEL, SESSION]
+                .into_iter()
+                .find(|marker| has_marker(pat_type, marker))
+        {
+            return Err(syn::Error::new_spanned(
+                pat_type,
+                format!(
+                    "`test_harness` can't call commands taking a `#[{}]`",
+                    marker
+                ),
+            ));
+        }
+        if let FnArg::Typed(pat_type) = arg
+            && is_caller_context(&pat_type.ty)
+        {
+            return Err(syn::Error::new_spanned(
+                pat_type,
+                "`test_harness` can't call commands taking a `CallerContext`",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether the command gets a dispatch function: with `test_harness` or
+/// `sidecar`, and with the `fuzz` feature whenever it could take
+/// `test_harness`.
+pub fn has_dispatch(input: &ItemFn, args: &BridgeArgs) -> bool {
+    if args.test_harness || args.sidecar.is_some() {
+        return true;
+    }
+    // Injected parameters and these options change the backend's signature,
+    // and nested references can't be decoded into owned values
+    let unsupported = input.sig.inputs.iter().any(|arg| {
+        matches!(arg, FnArg::Typed(pat_type) if is_injected(pat_type)
+            || is_caller_context(&pat_type.ty)
+            || (has_reference_type(&pat_type.ty) && borrowed_param(&pat_type.ty).is_none()))
+    });
+    cfg!(feature = "fuzz")
+        && !unsupported
+        && !args.layers
+        && !args.catch_panic
+        && check_test_harness(input, args).is_ok()
+}
+
+/// Generate the `cfg(test)` dispatch function of a command, and the
+/// constant holding its wire name.
+///
+/// The function returns `None` for other commands, and otherwise the
+/// response or the error Tauri would reject the invoke with.
+pub fn generate_dispatch(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let command_name = args.command_name(input);
+    let dispatch_cfg = args.dispatch_cfg_attr();
+    let dispatch_name = syn::Ident::new(
+        &format!("{}{}", DISPATCH_PREFIX, fn_name.unraw()),
+        call_site,
+    );
+    let command_const =
+        syn::Ident::new(&format!("{}{}", COMMAND_PREFIX, fn_name.unraw()), call_site);
+    let (decode_args, call_args) = decoded_args(input, &command_name);
+
+    // Sidecar commands forward calls; their body is a function of its own
+    let target = match &args.sidecar {
+        Some(_) => sidecar_impl_name(input),
+        None => fn_name.clone(),
+    };
+    let await_token = input
+        .sig
+        .asyncness
+        .map(|_| quote_spanned! {call_site=> .await });
+    let encode = |value: TokenStream2| {
+        quote_spanned! {call_site=>
+            ::serde_json::to_value(#value)
+                .map_err(|e| ::serde_json::Value::String(e.to_string()))
+        }
+    };
+    let returns_result = match &input.sig.output {
+        ReturnType::Type(_, ty) => result_types(ty).is_some(),
+        ReturnType::Default => false,
+    };
+    // Tauri rejects the invoke with the error of a `Result`
+    let respond = if returns_result {
+        let ok = encode(quote_spanned! {call_site=> value });
+        let error = encode(quote_spanned! {call_site=> error });
+        quote_spanned! {call_site=>
+            match __tauri_bridge_result {
+                Ok(value) => #ok,
+                Err(error) => Err(#error.unwrap_or_else(|error| error)),
+            }
+        }
+    } else {
+        encode(quote_spanned! {call_site=> __tauri_bridge_result })
+    };
+
+    quote_spanned! {call_site=>
+        #dispatch_cfg
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #vis const #command_const: &str = #command_name;
+
+        #dispatch_cfg
+        #[doc(hidden)]
+        #[allow(deprecated, dead_code)]
+        #vis async fn #dispatch_name(
+            __tauri_bridge_command: &str,
+            __tauri_bridge_args: ::serde_json::Value,
+        ) -> Option<Result<::serde_json::Value, ::serde_json::Value>> {
+            if __tauri_bridge_command != #command_name {
+                return None;
+            }
+            #(#decode_args)*
+            let __tauri_bridge_result = #target(#(#call_args),*) #await_token;
+            Some(#respond)
+        }
+    }
+}
+
+/// Statements decoding the arguments from the JSON object
+/// `__tauri_bridge_args` the way Tauri does, returning a rejection from a
+/// function returning `Option<Result<_, Value>>` when one is invalid, and
+/// the arguments to call the function with.
+pub fn decoded_args(input: &ItemFn, command_name: &str) -> (Vec<TokenStream2>, Vec<TokenStream2>) {
+    let call_site = Span::call_site();
+    let mut decode_args = Vec::new();
+    let mut call_args = Vec::new();
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            continue;
+        };
+        let ident = &pat_ident.ident;
+        // Tauri looks up arguments by their camelCase name
+        let key = ident.unraw().to_string().to_case(Case::Camel);
+        let (ty, call_arg) = match borrowed_param(&pat_type.ty) {
+            Some(borrowed) => (owned_type(&borrowed), quote_spanned! {call_site=> &#ident }),
+            None => {
+                let ty = &pat_type.ty;
+                (
+                    quote_spanned! {call_site=> #ty },
+                    quote_spanned! {call_site=> #ident },
+                )
+            }
+        };
+        decode_args.push(quote_spanned! {call_site=>
+            let #ident: #ty = match ::serde_json::from_value(
+                __tauri_bridge_args.get(#key).cloned().unwrap_or(::serde_json::Value::Null),
+            ) {
+                Ok(value) => value,
+                Err(e) => {
+                    return Some(Err(::serde_json::Value::String(format!(
+                        "invalid args `{}` for command `{}`: {}",
+                        #key, #command_name, e
+                    ))));
+                }
+            };
+        });
+        call_args.push(call_arg);
+    }
+    (decode_args, call_args)
+}
+
+/// Generate an expression evaluating to an async dispatch function over
+/// the listed commands, for `bridge_dispatch!`.
+pub fn generate_dispatch_list(input: &CommandList) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let entries = input.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let dispatch = sibling_item_path(&command.path, DISPATCH_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            {
+                if let Some(response) = #dispatch(command, args.clone()).await {
+                    return response;
+                }
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        {
+            async fn __tauri_bridge_dispatch(
+                command: &str,
+                args: ::serde_json::Value,
+            ) -> Result<::serde_json::Value, ::serde_json::Value> {
+                #(#entries)*
+                Err(::serde_json::Value::String(format!("command {} not found", command)))
+            }
+            __tauri_bridge_dispatch
+        }
+    }
+}
+
+/// Generate a function feeding fuzzer input to the listed commands through
+/// their dispatch functions, for `bridge_fuzz!`.
+pub fn generate_fuzz(input: &CommandList) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let dispatch = generate_dispatch_list(input);
+    let commands = input.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let name = sibling_item_path(&command.path, COMMAND_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            commands.push(#name);
+        }
+    });
+
+    quote_spanned! {call_site=>
+        {
+            fn __tauri_bridge_fuzz(data: &[u8]) {
+                let mut commands: ::std::vec::Vec<&str> = ::std::vec::Vec::new();
+                #(#commands)*
+                ::tauri_bridge_runtime::fuzz::run(data, &commands, #dispatch);
+            }
+            __tauri_bridge_fuzz
+        }
+    }
+}
+
+/// Input of `bridge_contract!`: the recording, then the commands to send
+/// its calls to.
+pub struct ContractInput {
+    pub recording: Expr,
+    pub commands: CommandList,
+}
+
+impl Parse for ContractInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let recording = input.parse()?;
+        input.parse::<Token![;]>()?;
+        Ok(ContractInput {
+            recording,
+            commands: input.parse()?,
+        })
+    }
+}
+
+/// Generate a future sending the calls of a recorded session to the listed
+/// commands through their dispatch functions, for `bridge_contract!`.
+///
+/// It evaluates to `Result<usize, tauri_bridge_runtime::contract::ContractError>`.
+pub fn generate_contract(input: &ContractInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let recording = &input.recording;
+    let dispatch = generate_dispatch_list(&input.commands);
+
+    quote_spanned! {call_site=>
+        async {
+            let recording: &::tauri_bridge_runtime::replay::Recording = &#recording;
+            ::tauri_bridge_runtime::contract::verify(recording, #dispatch).await
+        }
+    }
+}
+
+/// Input of `bridge_bench!`: the iteration count, then the commands with
+/// the JSON arguments to call them with.
+pub struct BenchInput {
+    pub iterations: Expr,
+    pub commands: Punctuated<BenchEntry, Token![,]>,
+}
+
+impl Parse for BenchInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let iterations = input.parse()?;
+        input.parse::<Token![;]>()?;
+        Ok(BenchInput {
+            iterations,
+            commands: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// A listed command and the expression of its arguments, as in
+/// `api::greet => json!({ "name": "Ada" })`.
+pub struct BenchEntry {
+    pub command: CommandEntry,
+    pub args: Expr,
+}
+
+impl Parse for BenchEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let command = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        Ok(BenchEntry {
+            command,
+            args: input.parse()?,
+        })
+    }
+}
+
+/// Generate a future timing each listed command through its dispatch
+/// function, for `bridge_bench!`.
+///
+/// It evaluates to `Result<Vec<BenchReport>, serde_json::Value>`, failing
+/// with the error of the first rejected call.
+pub fn generate_bench(input: &BenchInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let iterations = &input.iterations;
+    let entries = input.commands.iter().map(|entry| {
+        let cfgs = &entry.command.cfgs;
+        let dispatch = sibling_item_path(&entry.command.path, DISPATCH_PREFIX);
+        let command = sibling_item_path(&entry.command.path, COMMAND_PREFIX);
+        let args = &entry.args;
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            {
+                let args: ::serde_json::Value = #args;
+                reports.push(
+                    ::tauri_bridge_runtime::bench::measure(#command, &args, iterations, |args| {
+                        #dispatch(#command, args)
+                    })
+                    .await?,
+                );
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        async {
+            let iterations: u32 = #iterations;
+            let mut reports = ::std::vec::Vec::new();
+            #(#entries)*
+            Ok::<_, ::serde_json::Value>(reports)
+        }
+    }
+}
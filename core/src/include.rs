@@ -0,0 +1,49 @@
+//! `bridge_include!` support for placing client bindings in another module.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Token, Visibility};
+
+use crate::registry::{CommandEntry, sibling_item_path};
+
+/// Input of `bridge_include!`: an optional visibility and command paths.
+///
+/// ```rust,ignore
+/// tauri_bridge::bridge_include!(pub crate::api::greet, crate::api::add);
+/// ```
+pub struct IncludeInput {
+    pub vis: Visibility,
+    pub commands: Punctuated<CommandEntry, Token![,]>,
+}
+
+impl Parse for IncludeInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let vis = input.parse()?;
+        let commands = Punctuated::parse_terminated(input)?;
+        Ok(IncludeInput { vis, commands })
+    }
+}
+
+/// Generate re-exports of the hidden client modules for each listed command.
+///
+/// `crate::api::greet` becomes `use crate::api::__tauri_client_greet::*;`,
+/// bringing `greet` and `try_greet` into the invoking module.
+pub fn generate_include(input: &IncludeInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+
+    let reexports = input.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let path = sibling_item_path(&command.path, "__tauri_client_");
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            #vis use #path::*;
+        }
+    });
+
+    quote_spanned! {call_site=>
+        #(#reexports)*
+    }
+}
@@ -0,0 +1,50 @@
+//! Commands streaming partial results with `incremental`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(incremental)]
+//! pub async fn search(query: String, batches: Channel<Vec<Hit>>) -> Vec<Hit> {}
+//! ```
+//!
+//! The backend sends batches over its `tauri::ipc::Channel` parameter. On
+//! the client the channel is created by `crate::batch_channel` and left out
+//! of the signature; `try_<name>_incremental` takes a callback receiving
+//! each batch instead.
+
+use syn::{FnArg, GenericArgument, ItemFn, PathArguments, Type};
+
+/// The batch type `T` of a `Channel<T>`.
+pub fn batch_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    if segment.ident != "Channel" || args.args.len() != 1 {
+        return None;
+    }
+    match args.args.first() {
+        Some(GenericArgument::Type(inner)) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Incremental commands need exactly one `Channel<T>` parameter.
+pub fn check_incremental(input: &ItemFn) -> syn::Result<()> {
+    let mut channels =
+        input.sig.inputs.iter().filter(
+            |arg| matches!(arg, FnArg::Typed(pat_type) if batch_type(&pat_type.ty).is_some()),
+        );
+    match (channels.next(), channels.next()) {
+        (Some(_), None) => Ok(()),
+        (Some(_), Some(second)) => Err(syn::Error::new_spanned(
+            second,
+            "incremental commands take a single `Channel<T>` parameter",
+        )),
+        (None, _) => Err(syn::Error::new_spanned(
+            &input.sig,
+            "incremental commands need a `tauri::ipc::Channel<T>` parameter to send batches over",
+        )),
+    }
+}
@@ -0,0 +1,231 @@
+//! Window-routing parameters marked with `#[caller_label]` / `#[target_label]`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn focus(#[caller_label] caller: String, #[target_label] target: Option<String>) {}
+//! ```
+//!
+//! A `#[caller_label]` parameter is filled on the backend with the label of
+//! the webview that invoked the command and never sent by the client. A
+//! `#[target_label]` parameter receives the label the client addressed with
+//! `try_<name>_to`, or `None` from the plain client functions. A
+//! `#[session]` parameter receives the validated session, see `session`.
+//!
+//! A parameter of type `CallerContext` is filled with the caller's label
+//! and URL, without a marker:
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn list_notes(ctx: CallerContext) -> Vec<Note> {}
+//! ```
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, FnArg, GenericArgument, ItemFn, PatType, PathArguments, Token, Type};
+
+use crate::defaults::BRIDGE_ATTR;
+
+pub const CALLER_LABEL: &str = "caller_label";
+pub const TARGET_LABEL: &str = "target_label";
+/// Marks the parameter receiving the validated session, see `session`.
+pub const SESSION: &str = "session";
+/// Marks the `tauri::State` parameter of a service method's command, or
+/// the one standing for a `BridgeState`.
+pub const SERVICE_STATE: &str = "__tauri_bridge_state";
+
+/// Whether the parameter carries the `#[<marker>]` attribute.
+pub fn has_marker(pat_type: &PatType, marker: &str) -> bool {
+    pat_type
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident(marker))
+}
+
+/// Whether the backend fills in the parameter itself: the caller's label
+/// or context, the session or a service's state.
+pub fn is_injected(pat_type: &PatType) -> bool {
+    is_caller(pat_type) || has_marker(pat_type, SESSION) || has_marker(pat_type, SERVICE_STATE)
+}
+
+/// Whether the parameter is derived from the invoking webview: the
+/// `#[caller_label]` or the `CallerContext`.
+pub fn is_caller(pat_type: &PatType) -> bool {
+    has_marker(pat_type, CALLER_LABEL) || is_caller_context(&pat_type.ty)
+}
+
+/// Whether the type is `CallerContext`, by any path.
+pub fn is_caller_context(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path.qself.is_none()
+        && type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "CallerContext" && segment.arguments.is_none())
+}
+
+/// The parameter derived from the invoking webview, if any.
+pub fn caller_param(input: &ItemFn) -> Option<&PatType> {
+    input.sig.inputs.iter().find_map(|arg| match arg {
+        FnArg::Typed(pat_type) if is_caller(pat_type) => Some(pat_type),
+        _ => None,
+    })
+}
+
+/// The value of a caller parameter, from the injected webview.
+pub fn caller_value(pat_type: &PatType) -> TokenStream2 {
+    let call_site = Span::call_site();
+    if has_marker(pat_type, CALLER_LABEL) {
+        quote_spanned! {call_site=> __tauri_bridge_webview.label().to_string() }
+    } else {
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::caller::CallerContext {
+                window_label: __tauri_bridge_webview.label().to_string(),
+                url: __tauri_bridge_webview
+                    .url()
+                    .map(|url| url.to_string())
+                    .unwrap_or_default(),
+            }
+        }
+    }
+}
+
+/// The parameter marked `#[<marker>]`, if any.
+pub fn marked_param<'a>(input: &'a ItemFn, marker: &str) -> Option<&'a PatType> {
+    input.sig.inputs.iter().find_map(|arg| match arg {
+        FnArg::Typed(pat_type) if has_marker(pat_type, marker) => Some(pat_type),
+        _ => None,
+    })
+}
+
+/// Whether the attribute is one of the markers or `#[bridge(..)]`,
+/// which only the client reads.
+fn is_marker(attr: &Attribute) -> bool {
+    attr.path().is_ident(CALLER_LABEL)
+        || attr.path().is_ident(TARGET_LABEL)
+        || attr.path().is_ident(SESSION)
+        || attr.path().is_ident(SERVICE_STATE)
+        || attr.path().is_ident(BRIDGE_ATTR)
+}
+
+/// The parameter's own attributes, without the markers.
+pub fn param_attrs(pat_type: &PatType) -> Vec<&Attribute> {
+    pat_type
+        .attrs
+        .iter()
+        .filter(|attr| !is_marker(attr))
+        .collect()
+}
+
+/// The parameters without the markers.
+pub fn strip_markers(inputs: &Punctuated<FnArg, Token![,]>) -> Punctuated<FnArg, Token![,]> {
+    let mut inputs = inputs.clone();
+    for arg in inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = arg {
+            pat_type.attrs.retain(|attr| !is_marker(attr));
+        }
+    }
+    inputs
+}
+
+/// Backend parameters: the caller parameter is replaced by the injected
+/// `tauri::Webview`, from which it's bound at the top of the body.
+pub fn backend_params(input: &ItemFn) -> (Punctuated<FnArg, Token![,]>, TokenStream2) {
+    let call_site = Span::call_site();
+    let mut inputs = strip_markers(&input.sig.inputs);
+    let mut bind_label = quote_spanned! {call_site=> };
+
+    if let Some(caller) = caller_param(input) {
+        let pat = &caller.pat;
+        let ty = &caller.ty;
+        let value = caller_value(caller);
+        bind_label = quote_spanned! {call_site=>
+            let #pat: #ty = #value;
+        };
+        for arg in inputs.iter_mut() {
+            if let FnArg::Typed(pat_type) = arg
+                && pat_type.pat == caller.pat
+            {
+                *arg = syn::parse_quote!(__tauri_bridge_webview: tauri::Webview);
+            }
+        }
+    }
+
+    (inputs, bind_label)
+}
+
+/// Check marker placement and types.
+pub fn check_markers(input: &ItemFn) -> syn::Result<()> {
+    for marker in [CALLER_LABEL, TARGET_LABEL] {
+        let mut marked = input.sig.inputs.iter().filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) if has_marker(pat_type, marker) => Some(pat_type),
+            _ => None,
+        });
+        let (Some(first), second) = (marked.next(), marked.next()) else {
+            continue;
+        };
+        if let Some(second) = second {
+            return Err(syn::Error::new_spanned(
+                second,
+                format!("only one parameter can be `#[{}]`", marker),
+            ));
+        }
+
+        let (valid, expected) = match marker {
+            CALLER_LABEL => (is_string(&first.ty), "String"),
+            _ => (is_option_string(&first.ty), "Option<String>"),
+        };
+        if !valid {
+            return Err(syn::Error::new_spanned(
+                &first.ty,
+                format!("`#[{}]` parameters must be `{}`", marker, expected),
+            ));
+        }
+    }
+
+    // The caller's label and context come from the same injected webview
+    let mut callers = input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) if is_caller(pat_type) => Some(pat_type),
+        _ => None,
+    });
+    if let (Some(_), Some(second)) = (callers.next(), callers.next()) {
+        return Err(syn::Error::new_spanned(
+            second,
+            "only one parameter can take the caller; `CallerContext` holds its label too",
+        ));
+    }
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg
+            && let Type::Reference(type_ref) = pat_type.ty.as_ref()
+            && is_caller_context(&type_ref.elem)
+        {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "`CallerContext` parameters are filled by the backend; take them by value",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn is_string(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("String"))
+}
+
+fn is_option_string(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    segment.ident == "Option"
+        && args.args.len() == 1
+        && matches!(args.args.first(), Some(GenericArgument::Type(inner)) if is_string(inner))
+}
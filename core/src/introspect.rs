@@ -0,0 +1,120 @@
+//! `bridge_introspect![...]`: the startup handshake between both sides.
+//!
+//! ```rust,ignore
+//! tauri_bridge::bridge_introspect![greet, api::get_user];
+//! tauri_bridge::bridge_introspect![client_cfg(feature = "web"); greet];
+//! ```
+//!
+//! Expands to `bridge_manifest()`, the `tauri_bridge_runtime::introspect`
+//! manifest of the listed commands built from their hidden version and spec
+//! constants, the backend's `__bridge_introspect` command serving it, and
+//! the client's `check_bridge()`, comparing it with its own.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::parse::{Parse, ParseStream};
+use syn::{Meta, Token};
+
+use crate::attrs::BridgeArgs;
+use crate::registry::{CommandList, VERSION_PREFIX, sibling_item_path};
+use crate::spec::SPEC_PREFIX;
+
+/// Wire name of the introspection command.
+pub const INTROSPECT_COMMAND: &str = "__bridge_introspect";
+
+/// Input of `bridge_introspect!`: optional cfg options ended by `;`, then
+/// the commands.
+pub struct IntrospectInput {
+    /// Only `backend_cfg` and `client_cfg` apply.
+    pub bridge_args: BridgeArgs,
+    pub commands: CommandList,
+}
+
+impl Parse for IntrospectInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut bridge_args = BridgeArgs::default();
+        // Listed commands are paths, never followed by parentheses
+        if input.peek(syn::Ident) && input.peek2(syn::token::Paren) {
+            loop {
+                let meta: Meta = input.parse()?;
+                if meta.path().is_ident("backend_cfg") || meta.path().is_ident("client_cfg") {
+                    bridge_args.apply(meta)?;
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        meta.path(),
+                        "expected `backend_cfg` or `client_cfg`",
+                    ));
+                }
+                if input.parse::<Option<Token![;]>>()?.is_some() {
+                    break;
+                }
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(IntrospectInput {
+            bridge_args,
+            commands: input.parse()?,
+        })
+    }
+}
+
+/// Generate the manifest function, the command and the client check.
+pub fn generate_introspect(input: &IntrospectInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let backend_cfg = input.bridge_args.backend_cfg_attr();
+    let client_cfg = input.bridge_args.client_cfg_attr();
+    let command = syn::Ident::new(INTROSPECT_COMMAND, call_site);
+
+    let entries = input.commands.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let version = sibling_item_path(&command.path, VERSION_PREFIX);
+        let spec = sibling_item_path(&command.path, SPEC_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            {
+                let (name, version) = #version;
+                commands.push(tauri_bridge_runtime::introspect::CommandInfo::new(
+                    name, version, #spec,
+                ));
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        /// Manifest of the commands this side was compiled with.
+        pub fn bridge_manifest() -> tauri_bridge_runtime::introspect::Manifest {
+            let mut commands = Vec::new();
+            #(#entries)*
+            tauri_bridge_runtime::introspect::Manifest::new(commands)
+        }
+
+        #backend_cfg
+        mod __tauri_introspect {
+            /// Serve the backend's manifest to `check_bridge`.
+            #[tauri::command]
+            pub fn #command() -> tauri_bridge_runtime::introspect::Manifest {
+                super::bridge_manifest()
+            }
+        }
+
+        #backend_cfg
+        pub use __tauri_introspect::#command;
+
+        /// Check that the backend serves the commands of this client.
+        #client_cfg
+        pub async fn check_bridge() -> Result<(), String> {
+            #[derive(serde::Serialize)]
+            struct IntrospectArgs {}
+
+            let args = serde_wasm_bindgen::to_value(&IntrospectArgs {})
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+            let result = crate::invoke(#INTROSPECT_COMMAND, args).await;
+            let backend =
+                serde_wasm_bindgen::from_value::<tauri_bridge_runtime::introspect::Manifest>(result)
+                    .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+            bridge_manifest()
+                .check(&backend)
+                .map_err(|mismatch| mismatch.to_string())
+        }
+    }
+}
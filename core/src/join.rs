@@ -0,0 +1,88 @@
+//! `bridge_join!(...)`: calling several commands concurrently.
+//!
+//! ```rust,ignore
+//! let (user, settings, projects) =
+//!     tauri_bridge::bridge_join!(get_user(1), get_settings(), list_projects()).await?;
+//! ```
+//!
+//! Takes the input of `bridge_prefetch_all!`, and calls the `try_<name>`
+//! client function of each listed command with its arguments. The calls run
+//! concurrently as `tauri_bridge_runtime::join::Joined` futures; once all
+//! are done, their responses are returned as a tuple in the listed order,
+//! or the first error as a `tauri_bridge_runtime::join::JoinError`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+
+use crate::prefetch::PrefetchInput;
+use crate::registry::sibling_item_path;
+
+/// Generate the future calling the listed commands.
+pub fn generate_join(input: &PrefetchInput) -> syn::Result<TokenStream2> {
+    let call_site = Span::call_site();
+    if input.entries.is_empty() {
+        return Err(syn::Error::new(
+            call_site,
+            "`bridge_join!` needs at least one command",
+        ));
+    }
+    // Left out entries would change the shape of the tuple
+    if let Some(cfg) = input
+        .entries
+        .iter()
+        .find_map(|entry| entry.command.cfgs.first())
+    {
+        return Err(syn::Error::new_spanned(
+            cfg,
+            "`bridge_join!` entries can't carry `#[cfg(..)]` attributes",
+        ));
+    }
+
+    let joined: Vec<_> = (0..input.entries.len())
+        .map(|index| syn::Ident::new(&format!("__tauri_bridge_joined_{}", index), call_site))
+        .collect();
+    // Calling the client functions evaluates the arguments right away
+    let calls = input.entries.iter().map(|entry| {
+        let try_fn = sibling_item_path(&entry.command.path, "try_");
+        let args = entry.args.iter();
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::join::Joined::new(#try_fn(#(#args),*))
+        }
+    });
+    let responses = input.entries.iter().zip(&joined).map(|(entry, joined)| {
+        let command = entry
+            .command
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.unraw().to_string())
+            .unwrap_or_default();
+        quote_spanned! {call_site=>
+            #joined
+                .take()
+                .map_err(|error| tauri_bridge_runtime::join::JoinError::new(#command, error))?
+        }
+    });
+
+    Ok(quote_spanned! {call_site=>
+        {
+            let (#(mut #joined,)*) = (#(#calls,)*);
+            async move {
+                ::std::future::poll_fn(|cx| {
+                    // Every call is polled, not just up to the first pending one
+                    let done = [#(#joined.poll(cx)),*];
+                    if done.iter().all(|done| *done) {
+                        ::std::task::Poll::Ready(())
+                    } else {
+                        ::std::task::Poll::Pending
+                    }
+                })
+                .await;
+                ::std::result::Result::Ok::<_, tauri_bridge_runtime::join::JoinError>((
+                    #(#responses,)*
+                ))
+            }
+        }
+    })
+}
@@ -0,0 +1,187 @@
+//! Backend layers around commands with `layers`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(layers)]
+//! pub fn delete_note(id: u64) -> Result<(), String> {}
+//! ```
+//!
+//! The command takes the invoking `tauri::Webview`, looks up the
+//! `tauri_bridge_runtime::layer::Layers` managed by the app and runs the
+//! command between their hooks. It returns `Result<T, InvokeError>` so a
+//! layer can reject the call; a `Result` of the function is flattened into
+//! it, which keeps the response Tauri sends unchanged.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, ItemFn, ReturnType, Token};
+
+use crate::attrs::BridgeArgs;
+use crate::entries::uses_entries;
+use crate::inject::{SESSION, caller_param, marked_param};
+use crate::types::result_types;
+
+/// Reject options whose backend `layers` can't wrap.
+///
+/// These generate their own command signatures around the function.
+pub fn check_layers(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`layers` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`layers` can't be combined with `compress`",
+        ),
+        (args.encrypt, "`layers` can't be combined with `encrypt`"),
+        (args.by_value, "`layers` can't be combined with `by_value`"),
+        (
+            args.raw_bytes_response(input),
+            "`layers` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.test_harness,
+            "`layers` can't be combined with `test_harness`",
+        ),
+        (args.non_send, "`layers` can't be combined with `non_send`"),
+        (
+            args.sidecar.is_some(),
+            "`layers` can't wrap commands running in a `sidecar`",
+        ),
+        (
+            marked_param(input, SESSION).is_some(),
+            "`layers` can't wrap commands taking a `#[session]`",
+        ),
+        (
+            uses_entries(input, args),
+            "`layers` can't be combined with `map_as`",
+        ),
+        (
+            args.floats.is_some(),
+            "`layers` can't be combined with `floats`",
+        ),
+        (
+            args.verify_schema,
+            "`layers` can't be combined with `verify_schema`",
+        ),
+        (
+            args.bootstrap,
+            "`layers` can't be combined with `bootstrap`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// The layered `#[tauri::command]`: runs `block` between the hooks of the
+/// managed layers.
+pub fn layered_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    inputs: &Punctuated<FnArg, Token![,]>,
+    command_name: &str,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+
+    // The caller parameter already injects the webview
+    let mut inputs = inputs.clone();
+    if caller_param(input).is_none() {
+        inputs.insert(0, syn::parse_quote!(__tauri_bridge_webview: tauri::Webview));
+    }
+
+    let Flattened {
+        output,
+        ok_type,
+        failed,
+        respond,
+        run,
+    } = flattened(input, block);
+
+    quote_spanned! {call_site=>
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (#inputs) -> Result<#ok_type, tauri::ipc::InvokeError> #where_clause {
+            let __tauri_bridge_layers = tauri::Manager::try_state::<
+                tauri_bridge_runtime::layer::Layers,
+            >(&__tauri_bridge_webview);
+            let __tauri_bridge_call = tauri_bridge_runtime::layer::enter(
+                __tauri_bridge_layers.as_deref(),
+                tauri_bridge_runtime::layer::Call {
+                    command: #command_name,
+                    webview_label: __tauri_bridge_webview.label(),
+                },
+            )?;
+            let __tauri_bridge_response: #output = #run;
+            __tauri_bridge_call.exit(#failed);
+            #respond
+        }
+    }
+}
+
+/// The parts of a command returning `Result<T, InvokeError>` around the
+/// function's `block`.
+pub struct Flattened {
+    /// Return type of the function.
+    pub output: TokenStream2,
+    /// The `T` responded with.
+    pub ok_type: TokenStream2,
+    /// Whether `__tauri_bridge_response` is an error.
+    pub failed: TokenStream2,
+    /// The command's response, from `__tauri_bridge_response`.
+    pub respond: TokenStream2,
+    /// The block run as an expression.
+    pub run: TokenStream2,
+}
+
+/// Flatten a `Result` of the function into the command's `Result`, which
+/// keeps the response Tauri sends unchanged.
+pub fn flattened(input: &ItemFn, block: &TokenStream2) -> Flattened {
+    let call_site = Span::call_site();
+    let output = match &input.sig.output {
+        ReturnType::Default => quote_spanned! {call_site=> () },
+        ReturnType::Type(_, ty) => quote_spanned! {call_site=> #ty },
+    };
+    let result = match &input.sig.output {
+        ReturnType::Type(_, ty) => result_types(ty),
+        ReturnType::Default => None,
+    };
+    let (ok_type, failed, respond) = match result {
+        Some((ok, _)) => (
+            quote_spanned! {call_site=> #ok },
+            quote_spanned! {call_site=> __tauri_bridge_response.is_err() },
+            quote_spanned! {call_site=>
+                __tauri_bridge_response.map_err(::core::convert::Into::into)
+            },
+        ),
+        None => (
+            output.clone(),
+            quote_spanned! {call_site=> false },
+            quote_spanned! {call_site=> Ok(__tauri_bridge_response) },
+        ),
+    };
+
+    // Returns in the body must leave the block only
+    let run = if input.sig.asyncness.is_some() {
+        quote_spanned! {call_site=> async #block.await }
+    } else {
+        quote_spanned! {call_site=> (|| -> #output #block)() }
+    };
+
+    Flattened {
+        output,
+        ok_type,
+        failed,
+        respond,
+        run,
+    }
+}
@@ -0,0 +1,901 @@
+//! Code generation behind the `tauri-bridge` macros.
+//!
+//! The proc-macro crate only forwards its input to the functions here, so
+//! tools and snapshot tests can expand commands on plain token streams:
+//!
+//! ```rust,ignore
+//! let expanded = tauri_bridge_core::expand(
+//!     quote! {},
+//!     quote! {
+//!         pub fn greet(name: &str) -> String {
+//!             format!("Hello, {}!", name)
+//!         }
+//!     },
+//! );
+//! ```
+//!
+//! Each function returns the expansion, or a `compile_error!` invocation
+//! when the input is invalid. [`describe`] returns a structured model of a
+//! command's expansion instead, for codegen tools and editors.
+
+mod alias;
+mod attrs;
+mod backend;
+mod bootstrap;
+mod bridge_error;
+mod catch_panic;
+mod client;
+mod config;
+mod defaults;
+mod describe;
+mod entries;
+mod file;
+mod floats;
+mod form;
+mod harness;
+mod include;
+mod incremental;
+mod inject;
+mod introspect;
+mod join;
+mod layer;
+mod manifest;
+mod max_payload;
+mod metrics;
+mod namespace;
+mod non_send;
+mod prefetch;
+mod reference;
+mod registry;
+mod replay;
+mod report;
+mod representation;
+mod roundtrip;
+mod selfcheck;
+mod serialize_on;
+mod service;
+mod session;
+mod sidecar;
+mod spec;
+mod startup;
+mod state;
+mod strict;
+mod transaction;
+mod types;
+mod typescript;
+mod validate;
+mod verify_schema;
+
+#[cfg(test)]
+mod tests;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, quote_spanned};
+use syn::parse::{ParseStream, Parser};
+use syn::{FnArg, ForeignItemFn, ItemFn, Pat, ReturnType, Type};
+
+use alias::{alias_client, check_aliases};
+use attrs::{BridgeArgs, take_cfg_attrs};
+use backend::generate_backend;
+use bootstrap::{
+    check_bootstrap, generate_bootstrap, generate_bootstrap_backend, generate_bootstrap_client,
+};
+use bridge_error::generate_bridge_error;
+use catch_panic::check_catch_panic;
+use client::generate_client;
+use config::{CrateConfig, load_crate_config};
+use defaults::check_defaults;
+use describe::describe_command;
+pub use describe::{ArgDescription, BridgeDescription};
+use entries::{check_entries, wire_input};
+use file::{FileArgs, check_resolver, generate_file};
+use floats::check_floats;
+use form::{FormArgs, check_form, generate_form};
+use harness::{
+    BenchInput, ContractInput, check_test_harness, generate_bench, generate_contract,
+    generate_dispatch, generate_dispatch_list, generate_fuzz, has_dispatch,
+};
+use include::{IncludeInput, generate_include};
+use incremental::check_incremental;
+use inject::check_markers;
+use introspect::{IntrospectInput, generate_introspect};
+use join::generate_join;
+use layer::check_layers;
+use manifest::{command_manifest, manifest_dir, write_manifest_entry};
+use max_payload::check_max_payload;
+use namespace::{bridge_module, check_module, module_namespace};
+use non_send::check_non_send;
+use prefetch::{PrefetchInput, generate_prefetch_all};
+use registry::{
+    CommandList, HandlerStep, generate_handler, generate_handler_entry, generate_handler_step,
+    generate_registry_entry, generate_version_map,
+};
+use replay::check_replayable;
+use report::{convert_command_errors, report_errors};
+use representation::{check_representations, load_crate_types};
+use roundtrip::{check_proptest, generate_roundtrip_tests};
+use selfcheck::{
+    check_selfcheck, generate_selfcheck, generate_selfcheck_backend, generate_selfcheck_client,
+};
+use serialize_on::check_serialize_on;
+use service::{check_concrete, check_service, service_commands, service_impl};
+use session::check_session;
+use sidecar::check_sidecar;
+use spec::{generate_spec, generate_spec_const};
+use startup::check_startup_cache;
+use state::{check_bridge_state, lock_bridge_state};
+use strict::check_strict;
+use transaction::{check_transaction, generate_transact, generate_transaction};
+use types::{
+    BorrowedParam, borrowed_param, check_return_type, has_mut_reference, has_reference_type,
+    mutable_param, raw_bytes_type, trait_type,
+};
+use typescript::{command_ts, ts_dir, write_ts_entry};
+use validate::check_validate_response;
+use verify_schema::check_verify_schema;
+
+/// Expand `#[tauri_bridge(<attr>)]` on `item`.
+pub fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args: BridgeArgs = match syn::parse2(attr.clone()) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error(),
+    };
+    // On an impl block, each method taking `&self` becomes a command
+    if let Ok(item) = syn::parse2::<syn::ItemImpl>(item.clone()) {
+        return expand_service(attr, args, item);
+    }
+    // On an inline module, each `pub` function becomes a command namespaced
+    // by the module path
+    if let Ok(item) = syn::parse2::<syn::ItemMod>(item.clone()) {
+        return expand_module(attr, args, item);
+    }
+    let (input, args, config) = match prepare_command(args, item) {
+        Ok(prepared) => prepared,
+        Err(error) => return error.to_compile_error(),
+    };
+    let call_site = Span::call_site();
+
+    let command_name = args.command_name(&input);
+
+    if let Some(dir) = manifest_dir(&config) {
+        let entry = command_manifest(&input, &command_name, args.command_version());
+        if let Err(message) = write_manifest_entry(&dir, &entry) {
+            return syn::Error::new(call_site, message).to_compile_error();
+        }
+    }
+    // Maps sent as entries are described by their `[key, value]` pairs
+    let wire = wire_input(&input, &args);
+    if let Some(dir) = ts_dir(&config)
+        && let Some(module) = command_ts(
+            &wire,
+            &args,
+            &command_name,
+            config.ts_client,
+            config.ts_schemas,
+        )
+        && let Err(message) = write_ts_entry(&dir, &command_name, &module)
+    {
+        return syn::Error::new(call_site, message).to_compile_error();
+    }
+
+    let backend_code = if args.declaration_only {
+        quote_spanned! {call_site=> }
+    } else {
+        let backend = generate_backend(&input, &args);
+        let handler_entry = generate_handler_entry(&input, &args);
+        let dispatch = if has_dispatch(&input, &args) {
+            generate_dispatch(&input, &args)
+        } else {
+            quote_spanned! {call_site=> }
+        };
+        let transact = args
+            .transaction
+            .as_ref()
+            .map(|step| generate_transact(&input, &args, step));
+        let selfcheck = args
+            .selfcheck
+            .then(|| generate_selfcheck_backend(&input, &args));
+        let bootstrap = args
+            .bootstrap
+            .then(|| generate_bootstrap_backend(&input, &args));
+        quote_spanned! {call_site=>
+            #backend #handler_entry #dispatch #transact #selfcheck #bootstrap
+        }
+    };
+    let mut client_code = generate_client(&input, &args);
+    if args.alias_client {
+        for alias in &args.aliases {
+            let (alias_input, alias_args) = alias_client(&input, &args, alias);
+            client_code.extend(generate_client(&alias_input, &alias_args));
+        }
+    }
+    if args.selfcheck {
+        client_code.extend(generate_selfcheck_client(&input, &args));
+    }
+    if args.bootstrap {
+        client_code.extend(generate_bootstrap_client(&input, &args));
+    }
+    let registry_entry = generate_registry_entry(&input, &args);
+    let spec_const = generate_spec_const(&wire, &command_name);
+    let item_cfg = args.item_cfg_attr();
+    let roundtrip_tests = if args.proptest {
+        generate_roundtrip_tests(&input, &args)
+    } else {
+        quote_spanned! {call_site=> }
+    };
+
+    let expanded = quote_spanned! {call_site=>
+        #backend_code
+        #client_code
+        #roundtrip_tests
+        #registry_entry
+        #item_cfg
+        #spec_const
+    };
+
+    expanded
+}
+
+/// Describe what `#[tauri_bridge(<attr>)]` generates for the function
+/// `item`: its command name, arguments and wire types, and client items.
+///
+/// Fails with the error the expansion would report.
+pub fn describe(attr: TokenStream, item: TokenStream) -> syn::Result<BridgeDescription> {
+    let args: BridgeArgs = syn::parse2(attr)?;
+    let (input, args, _) = prepare_command(args, item)?;
+    Ok(describe_command(&input, &args))
+}
+
+/// Parse a bridged function and run the checks every expansion of it
+/// passes, returning it with the options completed from the crate config.
+fn prepare_command(
+    mut args: BridgeArgs,
+    item: TokenStream,
+) -> syn::Result<(ItemFn, BridgeArgs, CrateConfig)> {
+    let parsed = if args.declaration_only {
+        parse_declaration.parse2(item)
+    } else {
+        syn::parse2::<ItemFn>(item)
+    };
+    let mut input = parsed?;
+
+    // `#[cfg]` on the function gates both halves instead of only the backend
+    args.item_cfgs = take_cfg_attrs(&mut input.attrs)?;
+
+    check_arg_patterns(&input)?;
+    check_trait_args(&input)?;
+    check_markers(&input)?;
+    check_defaults(&input)?;
+    check_return_type(&input.sig)?;
+
+    // Errors are converted to the sent type first, then `anyhow` and
+    // `eyre` errors left are sent as serializable reports
+    if let Some(conversion) = &args.error_conversion {
+        convert_command_errors(&mut input, args.declaration_only, conversion)?;
+    }
+    report_errors(&mut input, args.declaration_only);
+
+    let config =
+        load_crate_config().map_err(|message| syn::Error::new(Span::call_site(), message))?;
+    let mut args = args.with_defaults(&config);
+
+    // `BridgeState` parameters are taken as the managed state from here on
+    check_bridge_state(&input, &args)?;
+    lock_bridge_state(&mut input, args.declaration_only);
+
+    // Maps sent as entries keep their keys, so they're checked as pairs
+    if args.strict {
+        check_strict(
+            &wire_input(&input, &args),
+            args.by_value,
+            args.floats.is_some(),
+        )?;
+    }
+    check_mut_args(&input, &args)?;
+    check_payload_options(&input, &args)?;
+    check_session(&input, &args)?;
+    check_sidecar(&input, &args)?;
+    check_serialize_on(&input, &args)?;
+    check_max_payload(&input, &args)?;
+    check_verify_schema(&input, &args)?;
+    check_startup_cache(&input, &args)?;
+    check_bootstrap(&input, &args)?;
+    check_transaction(&input, &args)?;
+    check_entries(&input, &args)?;
+    check_floats(&input, &args)?;
+    check_selfcheck(&input, &args)?;
+    // Tagged types of the crate only survive the JSON IPC as plain objects
+    args.json_compatible |= check_representations(&input, &load_crate_types())?;
+    check_validate_response(&args)?;
+    check_concrete(&input, &args)?;
+    check_aliases(&input, &args)?;
+    if args.incremental {
+        check_incremental(&input)?;
+    }
+    if args.test_harness {
+        check_test_harness(&input, &args)?;
+    }
+    if args.catch_panic {
+        check_catch_panic(&input, &args)?;
+    }
+    if args.non_send && !args.declaration_only {
+        check_non_send(&input, &args)?;
+    }
+    if args.proptest {
+        check_proptest(&input)?;
+    }
+    // The crate-wide `layers` skips commands it can't wrap
+    if args.layers {
+        check_layers(&input, &args)?;
+    }
+    args.layers |= config.layers && check_layers(&input, &args).is_ok();
+    // The crate-wide `replayable` skips commands it can't record
+    if args.replayable {
+        check_replayable(&input, &args)?;
+    }
+    args.replayable |= config.replayable && check_replayable(&input, &args).is_ok();
+
+    Ok((input, args, config))
+}
+
+/// Expand `#[tauri_bridge(<attr>)]` on an impl block: the block for the
+/// backend, and the commands of its methods expanded with the same options.
+fn expand_service(attr: TokenStream, args: BridgeArgs, item: syn::ItemImpl) -> TokenStream {
+    if let Err(error) = check_service(&item, &args) {
+        return error.to_compile_error();
+    }
+    let config = match load_crate_config() {
+        Ok(config) => config,
+        Err(message) => {
+            return syn::Error::new(Span::call_site(), message).to_compile_error();
+        }
+    };
+    let commands: Vec<_> = service_commands(&item, &args)
+        .into_iter()
+        .map(|command| expand(attr.clone(), command.into_token_stream()))
+        .collect();
+    let backend_cfg = args.with_defaults(&config).backend_cfg_attr();
+    let service = service_impl(&item);
+
+    quote_spanned! {Span::call_site()=>
+        #backend_cfg
+        #service
+        #(#commands)*
+    }
+}
+
+/// Expand `#[tauri_bridge(<attr>)]` on an inline module: the module with
+/// its commands expanded in place, namespaced by their module path.
+fn expand_module(attr: TokenStream, args: BridgeArgs, item: syn::ItemMod) -> TokenStream {
+    let namespace = module_namespace(&item, &args);
+    match check_module(&item, &args).and_then(|()| bridge_module(&attr, &namespace, item)) {
+        Ok(item) => item.into_token_stream(),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `#[bridge_file(<attr>)]` on `item`.
+pub fn expand_file(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let (args, input) = match syn::parse2::<FileArgs>(attr)
+        .and_then(|args| Ok((args, syn::parse2::<ItemFn>(item)?)))
+    {
+        Ok(parsed) => parsed,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    if let Err(error) = check_resolver(&input) {
+        return error.to_compile_error();
+    }
+
+    let config = match load_crate_config() {
+        Ok(config) => config,
+        Err(message) => {
+            return syn::Error::new(Span::call_site(), message).to_compile_error();
+        }
+    };
+    let args = FileArgs {
+        chunk_size: args.chunk_size,
+        bridge_args: args.bridge_args.with_defaults(&config),
+    };
+
+    generate_file(&input, &args)
+}
+
+/// Expand `#[bridge_form(<attr>)]` on `item`.
+pub fn expand_form(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let (args, input) = match syn::parse2::<FormArgs>(attr)
+        .and_then(|args| Ok((args, syn::parse2::<syn::ItemStruct>(item)?)))
+    {
+        Ok(parsed) => parsed,
+        Err(error) => return error.to_compile_error(),
+    };
+
+    if let Err(error) = check_form(&input) {
+        return error.to_compile_error();
+    }
+
+    let config = match load_crate_config() {
+        Ok(config) => config,
+        Err(message) => {
+            return syn::Error::new(Span::call_site(), message).to_compile_error();
+        }
+    };
+    let args = FormArgs {
+        bridge_args: args.bridge_args.with_defaults(&config),
+        ..args
+    };
+
+    generate_form(input, &args)
+}
+
+/// Expand `#[bridge_error]` on `item`.
+pub fn expand_error(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new_spanned(attr, "`bridge_error` takes no options").to_compile_error();
+    }
+    match syn::parse2(item).and_then(generate_bridge_error) {
+        Ok(expanded) => expanded,
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_metrics_command!(<input>)`: the `get_bridge_metrics`
+/// command, with `input` as its `#[tauri_bridge]` options.
+pub fn expand_metrics_command(input: TokenStream) -> TokenStream {
+    expand(input, metrics::metrics_command().into_token_stream())
+}
+
+/// Expand `bridge_include!(<input>)`.
+pub fn expand_include(input: TokenStream) -> TokenStream {
+    match syn::parse2::<IncludeInput>(input) {
+        Ok(input) => generate_include(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_spec![<input>]`.
+pub fn expand_spec(input: TokenStream) -> TokenStream {
+    match syn::parse2::<CommandList>(input) {
+        Ok(input) => generate_spec(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_version_map![<input>]`.
+pub fn expand_version_map(input: TokenStream) -> TokenStream {
+    match syn::parse2::<CommandList>(input) {
+        Ok(input) => generate_version_map(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_introspect![<input>]`.
+pub fn expand_introspect(input: TokenStream) -> TokenStream {
+    let input = match syn::parse2::<IntrospectInput>(input) {
+        Ok(input) => input,
+        Err(error) => return error.to_compile_error(),
+    };
+    let config = match load_crate_config() {
+        Ok(config) => config,
+        Err(message) => {
+            return syn::Error::new(Span::call_site(), message).to_compile_error();
+        }
+    };
+    generate_introspect(&IntrospectInput {
+        bridge_args: input.bridge_args.with_defaults(&config),
+        ..input
+    })
+}
+
+/// Expand `bridge_prefetch_all![<commands>]`.
+pub fn expand_prefetch_all(input: TokenStream) -> TokenStream {
+    match syn::parse2::<PrefetchInput>(input) {
+        Ok(input) => generate_prefetch_all(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_join!(<commands>)`, which takes the input of
+/// `bridge_prefetch_all!`.
+pub fn expand_join(input: TokenStream) -> TokenStream {
+    match syn::parse2::<PrefetchInput>(input).and_then(|input| generate_join(&input)) {
+        Ok(tokens) => tokens,
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_transaction![<input>]`, which takes the input of
+/// `bridge_introspect!`.
+pub fn expand_transaction(input: TokenStream) -> TokenStream {
+    let input = match syn::parse2::<IntrospectInput>(input) {
+        Ok(input) => input,
+        Err(error) => return error.to_compile_error(),
+    };
+    let config = match load_crate_config() {
+        Ok(config) => config,
+        Err(message) => {
+            return syn::Error::new(Span::call_site(), message).to_compile_error();
+        }
+    };
+    generate_transaction(&IntrospectInput {
+        bridge_args: input.bridge_args.with_defaults(&config),
+        ..input
+    })
+}
+
+/// Expand `bridge_selfcheck![<input>]`, which takes the input of
+/// `bridge_introspect!`.
+pub fn expand_selfcheck(input: TokenStream) -> TokenStream {
+    let input = match syn::parse2::<IntrospectInput>(input) {
+        Ok(input) => input,
+        Err(error) => return error.to_compile_error(),
+    };
+    let config = match load_crate_config() {
+        Ok(config) => config,
+        Err(message) => {
+            return syn::Error::new(Span::call_site(), message).to_compile_error();
+        }
+    };
+    generate_selfcheck(&IntrospectInput {
+        bridge_args: input.bridge_args.with_defaults(&config),
+        ..input
+    })
+}
+
+/// Expand `bridge_bootstrap![<input>]`, which takes the input of
+/// `bridge_introspect!`.
+pub fn expand_bootstrap(input: TokenStream) -> TokenStream {
+    let input = match syn::parse2::<IntrospectInput>(input) {
+        Ok(input) => input,
+        Err(error) => return error.to_compile_error(),
+    };
+    let config = match load_crate_config() {
+        Ok(config) => config,
+        Err(message) => {
+            return syn::Error::new(Span::call_site(), message).to_compile_error();
+        }
+    };
+    generate_bootstrap(&IntrospectInput {
+        bridge_args: input.bridge_args.with_defaults(&config),
+        ..input
+    })
+}
+
+/// Expand `bridge_dispatch![<input>]`.
+pub fn expand_dispatch(input: TokenStream) -> TokenStream {
+    match syn::parse2::<CommandList>(input) {
+        Ok(input) => generate_dispatch_list(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_bench!(<input>)`.
+pub fn expand_bench(input: TokenStream) -> TokenStream {
+    match syn::parse2::<BenchInput>(input) {
+        Ok(input) => generate_bench(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_contract!(<input>)`.
+pub fn expand_contract(input: TokenStream) -> TokenStream {
+    match syn::parse2::<ContractInput>(input) {
+        Ok(input) => generate_contract(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_fuzz![<input>]`.
+pub fn expand_fuzz(input: TokenStream) -> TokenStream {
+    match syn::parse2::<CommandList>(input) {
+        Ok(input) => generate_fuzz(&input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand `bridge_handler![<input>]`.
+///
+/// The expansion calls the entry macros of the listed commands, which
+/// continue through [`expand_handler_step`].
+pub fn expand_handler(input: TokenStream) -> TokenStream {
+    match syn::parse2::<CommandList>(input) {
+        Ok(input) => generate_handler(input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Expand one step of `bridge_handler!`.
+pub fn expand_handler_step(input: TokenStream) -> TokenStream {
+    match syn::parse2::<HandlerStep>(input) {
+        Ok(input) => generate_handler_step(input),
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Parse a body-less `fn` declaration used with `#[tauri_bridge(extern)]`.
+fn parse_declaration(input: ParseStream) -> syn::Result<ItemFn> {
+    let decl: ForeignItemFn = input.parse()?;
+    Ok(ItemFn {
+        attrs: decl.attrs,
+        vis: decl.vis,
+        sig: decl.sig,
+        block: Box::new(syn::parse_quote!({})),
+    })
+}
+
+/// Reject arguments that aren't plain identifiers.
+///
+/// Arguments are sent as named fields, so patterns like `(x, y): (i32, i32)`
+/// have no name to send them under.
+fn check_arg_patterns(input: &ItemFn) -> syn::Result<()> {
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let is_plain = matches!(
+            pat_type.pat.as_ref(),
+            Pat::Ident(pat_ident) if pat_ident.by_ref.is_none() && pat_ident.subpat.is_none()
+        );
+        if !is_plain {
+            let pat = &pat_type.pat;
+            let ty = &pat_type.ty;
+            return Err(syn::Error::new_spanned(
+                pat,
+                format!(
+                    "tauri_bridge arguments must be plain identifiers; \
+                     bind it by name (e.g. `value: {}`) and destructure it in the body \
+                     with `let {} = value;`",
+                    ty.to_token_stream(),
+                    pat.to_token_stream(),
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reject `impl Trait` and `dyn Trait` arguments.
+///
+/// The backend deserializes each argument into its declared type, and the
+/// client's args struct needs a nameable field type, so both need a
+/// concrete type.
+fn check_trait_args(input: &ItemFn) -> syn::Result<()> {
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let (Pat::Ident(pat_ident), Some(ty)) = (pat_type.pat.as_ref(), trait_type(&pat_type.ty))
+        else {
+            continue;
+        };
+        let reason = match ty {
+            Type::ImplTrait(_) => {
+                "`impl Trait` stands for a type chosen by the caller, which the backend \
+                 can't know when deserializing it"
+            }
+            _ => "a trait object has no type to deserialize the argument into",
+        };
+        return Err(syn::Error::new_spanned(
+            ty,
+            format!(
+                "argument `{}` can't cross IPC as `{}`: {}; take a concrete type \
+                 implementing `Serialize` and `Deserialize` instead",
+                pat_ident.ident,
+                ty.to_token_stream(),
+                reason,
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Reject `&mut` arguments unless `by_value` emulates them.
+///
+/// The backend can't mutate the frontend's memory, so a `&mut` argument
+/// would silently drop the changes.
+fn check_mut_args(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let ty = &pat_type.ty;
+        let message = match mutable_param(ty) {
+            Some(BorrowedParam::Str) if args.by_value => {
+                "`&mut str` can't change length across IPC; take `&mut String` instead"
+            }
+            Some(_) if args.by_value => continue,
+            Some(_) => {
+                "`&mut` arguments can't be mutated across IPC; use \
+                 `#[tauri_bridge(by_value)]` to send the value and write back the \
+                 modified copy, or take the value and return it"
+            }
+            None if has_mut_reference(ty) => {
+                "nested `&mut` references can't be mutated across IPC; take the value and return it"
+            }
+            None => continue,
+        };
+        return Err(syn::Error::new_spanned(ty, message));
+    }
+    Ok(())
+}
+
+/// Reject combinations with the options that take over the payload.
+///
+/// `chunked`, `compress` and `encrypt` serialize the response themselves,
+/// and `compress` and `encrypt` also the arguments, so they can't be
+/// combined with options that change either shape.
+fn check_payload_options(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let call_site = Span::call_site();
+    let conflicts = [
+        (
+            args.chunked.is_some() && args.by_value,
+            "`chunked` can't be combined with `by_value`",
+        ),
+        (
+            args.compress.is_some() && args.by_value,
+            "`compress` can't be combined with `by_value`",
+        ),
+        (
+            args.compress.is_some() && args.chunked.is_some(),
+            "`compress` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some() && args.incremental,
+            "`compress` can't be combined with `incremental`",
+        ),
+        (
+            args.encrypt && args.by_value,
+            "`encrypt` can't be combined with `by_value`",
+        ),
+        (
+            args.encrypt && args.chunked.is_some(),
+            "`encrypt` can't be combined with `chunked`",
+        ),
+        (
+            args.encrypt && args.incremental,
+            "`encrypt` can't be combined with `incremental`",
+        ),
+        (
+            args.encrypt && args.compress.is_some(),
+            "`encrypt` can't be combined with `compress`",
+        ),
+        (
+            args.platforms.is_some() && args.circuit_breaker.is_some(),
+            "`platforms` can't be combined with `circuit_breaker`",
+        ),
+        (
+            args.raw && args.chunked.is_some(),
+            "`raw` can't be combined with `chunked`",
+        ),
+        (
+            args.raw && args.compress.is_some(),
+            "`raw` can't be combined with `compress`",
+        ),
+        (
+            args.raw && args.encrypt,
+            "`raw` can't be combined with `encrypt`",
+        ),
+        (
+            args.raw && args.by_value,
+            "`raw` can't be combined with `by_value`",
+        ),
+        (
+            args.raw && args.json_bytes,
+            "`raw` can't be combined with `json_bytes`",
+        ),
+        (
+            args.raw_response && args.chunked.is_some(),
+            "`raw_response` can't be combined with `chunked`",
+        ),
+        (
+            args.raw_response && args.compress.is_some(),
+            "`raw_response` can't be combined with `compress`",
+        ),
+        (
+            args.raw_response && args.encrypt,
+            "`raw_response` can't be combined with `encrypt`",
+        ),
+        (
+            args.raw_response && args.by_value,
+            "`raw_response` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_response && args.catch_panic,
+            "`raw_response` can't be combined with `catch_panic`",
+        ),
+        (
+            args.cached && args.by_value,
+            "`cached` can't be combined with `by_value`",
+        ),
+        (
+            args.swr && args.catch_panic,
+            "`cached(swr)` can't be combined with `catch_panic`",
+        ),
+        (
+            args.unwrap_envelope && args.chunked.is_some(),
+            "`unwrap_envelope` can't be combined with `chunked`",
+        ),
+        (
+            args.unwrap_envelope && args.compress.is_some(),
+            "`unwrap_envelope` can't be combined with `compress`",
+        ),
+        (
+            args.unwrap_envelope && args.encrypt,
+            "`unwrap_envelope` can't be combined with `encrypt`",
+        ),
+        (
+            args.unwrap_envelope && args.by_value,
+            "`unwrap_envelope` can't be combined with `by_value`",
+        ),
+        (
+            args.unwrap_envelope && args.raw,
+            "`unwrap_envelope` can't be combined with `raw`",
+        ),
+        (
+            args.unwrap_envelope && args.raw_response,
+            "`unwrap_envelope` can't be combined with `raw_response`",
+        ),
+        (
+            args.unwrap_envelope && args.catch_panic,
+            "`unwrap_envelope` can't be combined with `catch_panic`",
+        ),
+        (
+            args.signed && args.chunked.is_some(),
+            "`signed` can't be combined with `chunked`",
+        ),
+        (
+            args.signed && args.incremental,
+            "`signed` can't be combined with `incremental`",
+        ),
+        (
+            args.signed && args.raw_bytes_response(input),
+            "`signed` responses are verified as JSON, so they can't be `raw` bytes, the default for `Vec<u8>`",
+        ),
+    ];
+    if let Some((_, message)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+        return Err(syn::Error::new(call_site, message));
+    }
+
+    if args.unwrap_envelope && matches!(input.sig.output, ReturnType::Default) {
+        return Err(syn::Error::new_spanned(
+            &input.sig,
+            "`unwrap_envelope` needs a function returning an envelope like `ApiResponse<T>`",
+        ));
+    }
+
+    if args.raw && raw_bytes_type(&input.sig).is_none() {
+        return Err(syn::Error::new_spanned(
+            &input.sig,
+            "`raw` needs a return type convertible to and from `Vec<u8>`",
+        ));
+    }
+
+    // Compressed, encrypted and forwarded arguments are deserialized into
+    // owned fields
+    let payload_option = if args.compress.is_some() {
+        Some("compress")
+    } else if args.encrypt {
+        Some("encrypt")
+    } else if args.sidecar.is_some() {
+        Some("sidecar")
+    } else {
+        None
+    };
+    if let Some(option) = payload_option {
+        for arg in &input.sig.inputs {
+            if let FnArg::Typed(pat_type) = arg
+                && has_reference_type(&pat_type.ty)
+                && borrowed_param(&pat_type.ty).is_none()
+            {
+                return Err(syn::Error::new_spanned(
+                    &pat_type.ty,
+                    format!(
+                        "`{}` only supports `&str`, `&[T]` and `&T` references; take the value instead",
+                        option
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+//! Machine-readable JSON manifest of all bridged commands.
+//!
+//! Every `#[tauri_bridge]` expansion adds its command to the commands
+//! expanded so far by the compilation and regenerates
+//! `<dir>/bridge-manifest.json` from them. `<dir>` is `manifest_dir` from
+//! `[package.metadata.tauri-bridge]` (relative to the crate root) or, for
+//! crates with a build script, `$OUT_DIR/tauri-bridge`. The Markdown API
+//! reference is regenerated alongside it.
+//!
+//! rustc runs the macro in a fresh process for every compilation of the
+//! crate, so commands that were deleted or renamed since the last build
+//! drop out of the manifest, and nothing is read back from disk. Files are
+//! written next to their destination and renamed into place, so readers
+//! never see them half-written.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use quote::ToTokens;
+use serde_json::{Value, json};
+use syn::ext::IdentExt;
+use syn::{Expr, FnArg, GenericArgument, ItemFn, Lit, Meta, Pat, PathArguments, ReturnType, Type};
+
+use crate::config::CrateConfig;
+use crate::inject::is_injected;
+use crate::reference::{REFERENCE_FILE, render_reference};
+
+/// File name of the aggregated manifest.
+pub const MANIFEST_FILE: &str = "bridge-manifest.json";
+
+/// Entries of the commands expanded by this compilation, by directory and
+/// command name.
+static BUILD_ENTRIES: Mutex<BTreeMap<PathBuf, BTreeMap<String, Value>>> =
+    Mutex::new(BTreeMap::new());
+
+/// Describe a command as a JSON manifest entry.
+pub fn command_manifest(input: &ItemFn, command: &str, version: u32) -> Value {
+    let args: Vec<Value> = input
+        .sig
+        .inputs
+        .iter()
+        .filter(|arg| !matches!(arg, FnArg::Typed(pat_type) if is_injected(pat_type)))
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => Some(json!({
+                "name": pat_name(&pat_type.pat),
+                "type": type_to_string(&pat_type.ty),
+            })),
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let (returns, error) = match &input.sig.output {
+        ReturnType::Default => ("()".to_string(), None),
+        ReturnType::Type(_, ty) => (type_to_string(ty), result_error_type(ty)),
+    };
+
+    json!({
+        "name": input.sig.ident.unraw().to_string(),
+        "command": command,
+        "version": version,
+        "async": input.sig.asyncness.is_some(),
+        "args": args,
+        "returns": returns,
+        "error": error,
+        "docs": doc_comment(&input.attrs),
+    })
+}
+
+/// Resolve the directory manifests are written to, if any.
+pub fn manifest_dir(config: &CrateConfig) -> Option<PathBuf> {
+    if let Some(dir) = &config.manifest_dir {
+        let crate_root = std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from)?;
+        return Some(crate_root.join(dir));
+    }
+    std::env::var_os("OUT_DIR").map(|out_dir| PathBuf::from(out_dir).join("tauri-bridge"))
+}
+
+/// Record a command's entry and regenerate the aggregated manifest and the
+/// API reference.
+pub fn write_manifest_entry(dir: &Path, entry: &Value) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create manifest directory: {}", e))?;
+
+    // Held while writing, so expansions on other threads don't interleave
+    let mut build = BUILD_ENTRIES.lock().unwrap_or_else(PoisonError::into_inner);
+    let entries = build.entry(dir.to_path_buf()).or_default();
+    let command = entry["command"].as_str().unwrap_or_default();
+    entries.insert(command.to_string(), entry.clone());
+
+    // Sorted by command name
+    let commands: Vec<&Value> = entries.values().collect();
+    let manifest = json!({
+        "crate": std::env::var("CARGO_PKG_NAME").unwrap_or_default(),
+        "version": std::env::var("CARGO_PKG_VERSION").unwrap_or_default(),
+        "commands": commands,
+    });
+    write_atomically(&dir.join(REFERENCE_FILE), &render_reference(&manifest))
+        .map_err(|e| format!("Failed to write API reference: {}", e))?;
+    let manifest = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    write_atomically(&dir.join(MANIFEST_FILE), &manifest)
+        .map_err(|e| format!("Failed to write manifest: {}", e))
+}
+
+/// Write a file through a temporary file renamed into place.
+fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    // Per process, as builds of other targets may write the same file
+    temp.push(format!(".{}.tmp", std::process::id()));
+    let temp = PathBuf::from(temp);
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })
+}
+
+/// Render a type the way it's written in source, e.g. `Vec<&str>`.
+pub fn type_to_string(ty: &Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(" < ", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+        .replace(" ;", ";")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace("[ ", "[")
+        .replace(" ]", "]")
+}
+
+/// The error type of a `Result<T, E>` return type.
+fn result_error_type(ty: &Type) -> Option<String> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.iter().nth(1) {
+        Some(GenericArgument::Type(error)) => Some(type_to_string(error)),
+        _ => None,
+    }
+}
+
+/// Name of an argument pattern, falling back to its source text.
+fn pat_name(pat: &Pat) -> String {
+    match pat {
+        Pat::Ident(pat_ident) => pat_ident.ident.unraw().to_string(),
+        _ => pat.to_token_stream().to_string(),
+    }
+}
+
+/// Collect `///` doc comments into a single string.
+pub fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(name_value) if name_value.path.is_ident("doc") => {
+                match &name_value.value {
+                    Expr::Lit(expr_lit) => match &expr_lit.lit {
+                        Lit::Str(doc) => Some(doc.value().trim().to_string()),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
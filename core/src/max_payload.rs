@@ -0,0 +1,72 @@
+//! Argument size limits with `max_payload = "1MB"`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(max_payload = "1MB")]
+//! pub fn upload_note(title: String, body: String) -> Result<(), String> {}
+//! ```
+//!
+//! The client measures the arguments before sending them and fails with
+//! `tauri_bridge_runtime::payload::PayloadError::TooLarge` instead of
+//! invoking. The limit is the client's only: Tauri reads and decodes the
+//! arguments before any code of the command runs, so a backend check
+//! couldn't keep an oversized payload from being processed, and the
+//! backend is left unchanged.
+
+use proc_macro2::Span;
+use syn::ItemFn;
+
+use crate::attrs::BridgeArgs;
+
+/// Reject options that encode the arguments or type the client error
+/// themselves.
+pub fn check_max_payload(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if args.max_payload.is_none() {
+        return Ok(());
+    }
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`max_payload` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`max_payload` can't be combined with `compress`",
+        ),
+        (
+            args.encrypt,
+            "`max_payload` can't be combined with `encrypt`",
+        ),
+        (
+            args.incremental,
+            "`max_payload` can't be combined with `incremental`",
+        ),
+        (
+            args.by_value,
+            "`max_payload` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`max_payload` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.sidecar.is_some(),
+            "`max_payload` can't be combined with `sidecar`",
+        ),
+        (
+            args.catch_panic,
+            "`max_payload` can't be combined with `catch_panic`",
+        ),
+        (
+            args.circuit_breaker.is_some(),
+            "`max_payload` can't be combined with `circuit_breaker`",
+        ),
+        (
+            args.platforms.is_some(),
+            "`max_payload` can't be combined with `platforms`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
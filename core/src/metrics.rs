@@ -0,0 +1,66 @@
+//! Backend metrics of commands with `metrics`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(metrics)]
+//! pub fn save_note(note: Note) -> Result<(), String> {}
+//!
+//! bridge_metrics_command!();
+//! ```
+//!
+//! The command body is timed and recorded in the registry of
+//! `tauri_bridge_runtime::metrics`, counting an `Err` as an error.
+//! `bridge_metrics_command!` bridges the registry's snapshot as the
+//! `get_bridge_metrics` command.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::{ItemFn, ReturnType};
+
+use crate::types::result_types;
+
+/// Time the command body and record the call.
+pub fn metered(input: &ItemFn, command_name: &str, block: &TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let (output, failed) = match &input.sig.output {
+        ReturnType::Default => (
+            quote_spanned! {call_site=> () },
+            quote_spanned! {call_site=> false },
+        ),
+        ReturnType::Type(_, ty) if result_types(ty).is_some() => (
+            quote_spanned! {call_site=> #ty },
+            quote_spanned! {call_site=> __tauri_bridge_response.is_err() },
+        ),
+        ReturnType::Type(_, ty) => (
+            quote_spanned! {call_site=> #ty },
+            quote_spanned! {call_site=> false },
+        ),
+    };
+    // Returns in the body must leave the block only
+    let run = if input.sig.asyncness.is_some() {
+        quote_spanned! {call_site=> async #block.await }
+    } else {
+        quote_spanned! {call_site=> (|| -> #output #block)() }
+    };
+    quote_spanned! {call_site=>
+        {
+            let __tauri_bridge_started = ::std::time::Instant::now();
+            let __tauri_bridge_response: #output = #run;
+            tauri_bridge_runtime::metrics::record(
+                #command_name,
+                __tauri_bridge_started.elapsed(),
+                #failed,
+            );
+            __tauri_bridge_response
+        }
+    }
+}
+
+/// The `get_bridge_metrics` command, for `#[tauri_bridge(<attr>)]`.
+pub fn metrics_command() -> ItemFn {
+    syn::parse_quote! {
+        /// Per-command metrics of the backend.
+        pub fn get_bridge_metrics() -> tauri_bridge_runtime::metrics::MetricsSnapshot {
+            tauri_bridge_runtime::metrics::bridge_metrics()
+        }
+    }
+}
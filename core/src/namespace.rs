@@ -0,0 +1,145 @@
+//! `#[tauri_bridge]` on inline modules: commands namespaced by their module.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub mod users {
+//!     pub fn get(id: u64) -> User {}
+//!
+//!     #[tauri_bridge(rename = "list")]
+//!     pub fn list_users(limit: u32) -> Vec<User> {}
+//! }
+//! ```
+//!
+//! Each `pub` function, and each function or impl block with its own
+//! `#[tauri_bridge]`, is expanded in place with the module's options and its
+//! own, so the clients are generated inside `pub mod users` as well. The
+//! namespace is the module path below the bridged module, e.g. `users.get`,
+//! or `admin.users.get` for a module `users` nested in it; a `namespace`
+//! given on the bridged module is put in front of it.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{ToTokens, quote_spanned};
+use syn::{Attribute, Item, ItemMod, Meta, Visibility};
+
+use crate::attrs::BridgeArgs;
+
+/// Check that the module is inline and that no option names a single
+/// command.
+pub fn check_module(item: &ItemMod, args: &BridgeArgs) -> syn::Result<()> {
+    if item.content.is_none() {
+        return Err(syn::Error::new_spanned(
+            item,
+            "`tauri_bridge` bridges the functions of inline modules, `mod name { .. }`",
+        ));
+    }
+    let conflicts = [
+        (
+            args.rename.is_some(),
+            "`rename` names a single command; rename the functions of the module instead",
+        ),
+        (
+            !args.aliases.is_empty(),
+            "`alias` names a single command and can't apply to a module",
+        ),
+        (args.declaration_only, "`extern` doesn't apply to modules"),
+    ];
+    if let Some((_, message)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+        return Err(syn::Error::new(Span::call_site(), message));
+    }
+    Ok(())
+}
+
+/// The namespace of the commands directly in the bridged module.
+pub fn module_namespace(item: &ItemMod, args: &BridgeArgs) -> String {
+    match &args.namespace {
+        Some(namespace) => format!("{}.{}", namespace, item.ident),
+        None => item.ident.to_string(),
+    }
+}
+
+/// The module with its commands expanded in place, under `namespace`.
+pub fn bridge_module(
+    attr: &TokenStream2,
+    namespace: &str,
+    mut item: ItemMod,
+) -> syn::Result<ItemMod> {
+    let Some((_, items)) = &mut item.content else {
+        return Ok(item);
+    };
+    for module_item in items.iter_mut() {
+        let expanded = match module_item {
+            Item::Fn(function) => {
+                let own = take_bridge_attr(&mut function.attrs)?;
+                if own.is_none() && !matches!(function.vis, Visibility::Public(_)) {
+                    continue;
+                }
+                let attr = command_attr(attr, own, namespace)?;
+                crate::expand(attr, function.to_token_stream())
+            }
+            Item::Impl(service) => {
+                let Some(own) = take_bridge_attr(&mut service.attrs)? else {
+                    continue;
+                };
+                let attr = command_attr(attr, Some(own), namespace)?;
+                crate::expand(attr, service.to_token_stream())
+            }
+            Item::Mod(module) if module.content.is_some() => {
+                let own = take_bridge_attr(&mut module.attrs)?;
+                if let Some(own) = &own {
+                    check_module(module, &syn::parse2(own.clone())?)?;
+                }
+                let attr = command_attr(attr, own, namespace)?;
+                let nested = format!("{}.{}", namespace, module.ident);
+                bridge_module(&attr, &nested, module.clone())?.into_token_stream()
+            }
+            _ => continue,
+        };
+        *module_item = Item::Verbatim(expanded);
+    }
+    Ok(item)
+}
+
+/// The module's options followed by the item's own and the namespace, which
+/// the item can't set itself.
+fn command_attr(
+    attr: &TokenStream2,
+    own: Option<TokenStream2>,
+    namespace: &str,
+) -> syn::Result<TokenStream2> {
+    let call_site = Span::call_site();
+    let mut options: Vec<TokenStream2> = Vec::new();
+    if !attr.is_empty() {
+        options.push(attr.clone());
+    }
+    if let Some(own) = own.filter(|own| !own.is_empty()) {
+        if syn::parse2::<BridgeArgs>(own.clone())?.namespace.is_some() {
+            return Err(syn::Error::new_spanned(
+                own,
+                "commands of a bridged module are namespaced by their module path",
+            ));
+        }
+        options.push(own);
+    }
+    options.push(quote_spanned! {call_site=> namespace = #namespace });
+    Ok(quote_spanned! {call_site=> #(#options),* })
+}
+
+/// Remove the item's own `#[tauri_bridge]` and return its options.
+fn take_bridge_attr(attrs: &mut Vec<Attribute>) -> syn::Result<Option<TokenStream2>> {
+    let Some(index) = attrs.iter().position(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "tauri_bridge")
+    }) else {
+        return Ok(None);
+    };
+    match attrs.remove(index).meta {
+        Meta::Path(_) => Ok(Some(TokenStream2::new())),
+        Meta::List(list) => Ok(Some(list.tokens)),
+        meta @ Meta::NameValue(_) => Err(syn::Error::new_spanned(
+            meta,
+            "expected `#[tauri_bridge]` or `#[tauri_bridge(..)]`",
+        )),
+    }
+}
@@ -0,0 +1,114 @@
+//! Async commands whose futures aren't `Send`, with `non_send`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(non_send)]
+//! pub async fn render_preview(markdown: String) -> String {
+//!     let cache = Rc::new(RefCell::new(HashMap::new()));
+//!     // ...
+//! }
+//! ```
+//!
+//! Tauri spawns async commands on its multi-threaded runtime, so their
+//! futures must be `Send`. The `non_send` command instead moves its
+//! arguments to a blocking thread and drives the original function there
+//! with `tauri::async_runtime::block_on`; only the arguments and the
+//! response cross threads.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, ItemFn, Pat, Token};
+
+use crate::attrs::BridgeArgs;
+use crate::backend::wrapper_names;
+use crate::types::has_reference_type;
+
+/// Check that the function is async, takes owned arguments and that no
+/// other option generates its own command around it.
+pub fn check_non_send(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if input.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            input.sig.fn_token,
+            "`non_send` only applies to async functions; synchronous commands don't need it",
+        ));
+    }
+    if let Some(FnArg::Typed(pat_type)) = input
+        .sig
+        .inputs
+        .iter()
+        .find(|arg| matches!(arg, FnArg::Typed(pat_type) if has_reference_type(&pat_type.ty)))
+    {
+        return Err(syn::Error::new_spanned(
+            pat_type,
+            "`non_send` commands take owned arguments, which are moved to another thread",
+        ));
+    }
+
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`non_send` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`non_send` can't be combined with `compress`",
+        ),
+        (args.encrypt, "`non_send` can't be combined with `encrypt`"),
+        (
+            args.by_value,
+            "`non_send` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`non_send` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (args.layers, "`non_send` can't be combined with `layers`"),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// The `non_send` command: runs the original function, kept as a private
+/// inner function, on a blocking thread.
+///
+/// `inputs` are the backend parameters; an injected `tauri::Webview` is
+/// moved along and the caller label bound in `block`.
+pub fn non_send_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    inputs: &Punctuated<FnArg, Token![,]>,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let (fn_name, inner_name) = wrapper_names(input);
+    let output = &input.sig.output;
+    let attrs = &input.attrs;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+    let call_args = inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+            Pat::Ident(pat_ident) => Some(&pat_ident.ident),
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
+    });
+    let message = format!("`{}` panicked", fn_name.unraw());
+
+    quote_spanned! {call_site=>
+        async fn #inner_name #generics (#inputs) #output #where_clause #block
+
+        #(#attrs)*
+        #command_attr
+        #vis async fn #fn_name #generics (#inputs) #output #where_clause {
+            tauri::async_runtime::spawn_blocking(move || {
+                tauri::async_runtime::block_on(#inner_name(#(#call_args),*))
+            })
+            .await
+            .expect(#message)
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! `bridge_prefetch_all![...]`: warming the query cache at startup.
+//!
+//! ```rust,ignore
+//! tauri_bridge::bridge_prefetch_all![list_projects, api::get_user(user_id)].await;
+//! ```
+//!
+//! `cached` commands get a `prefetch_<name>` client function, which calls
+//! `try_<name>` so the response lands in the `tauri_bridge_runtime::query`
+//! cache, and drops it. The macro runs the prefetch functions of the
+//! listed commands concurrently, with the arguments given after them, and
+//! ignores their errors; a failed prefetch leaves the read to invoke later.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Token};
+
+use crate::registry::{CommandEntry, sibling_item_path};
+
+/// Prefix of the client function prefetching a `cached` command.
+pub const PREFETCH_PREFIX: &str = "prefetch_";
+
+/// Input of `bridge_prefetch_all!`: the commands, each with its arguments.
+pub struct PrefetchInput {
+    pub entries: Punctuated<PrefetchEntry, Token![,]>,
+}
+
+impl Parse for PrefetchInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(PrefetchInput {
+            entries: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// A listed command, called like `get_user(7)` or, without arguments,
+/// named like `list_projects`.
+pub struct PrefetchEntry {
+    pub command: CommandEntry,
+    pub args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for PrefetchEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let command = input.parse()?;
+        let args = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            Punctuated::parse_terminated(&content)?
+        } else {
+            Punctuated::new()
+        };
+        Ok(PrefetchEntry { command, args })
+    }
+}
+
+/// Generate the future prefetching the listed commands.
+pub fn generate_prefetch_all(input: &PrefetchInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let fetches = input.entries.iter().map(|entry| {
+        let cfgs = &entry.command.cfgs;
+        let prefetch = sibling_item_path(&entry.command.path, PREFETCH_PREFIX);
+        let args = entry.args.iter();
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            fetches.push(::std::boxed::Box::pin(async {
+                let _ = #prefetch(#(#args),*).await;
+            }));
+        }
+    });
+
+    quote_spanned! {call_site=>
+        {
+            #[allow(unused_mut)]
+            let mut fetches: ::std::vec::Vec<tauri_bridge_runtime::query::Prefetch<'_>> =
+                ::std::vec::Vec::new();
+            #(#fetches)*
+            tauri_bridge_runtime::query::prefetch_all(fetches)
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Markdown API reference of all bridged commands, for people calling them
+//! by hand.
+//!
+//! Rendered from the aggregated manifest to `<dir>/bridge-api.md` whenever
+//! [`write_manifest_entry`](crate::manifest::write_manifest_entry)
+//! regenerates it, so it lists the same commands. Each command gets its
+//! signature, docs, error type and an example `invoke` payload, with the
+//! argument names in camelCase as Tauri expects them.
+
+use std::fmt::Write;
+
+use convert_case::{Case, Casing};
+use serde_json::{Value, json};
+use syn::Type;
+
+use crate::typescript::type_generics;
+
+/// File name of the API reference.
+pub const REFERENCE_FILE: &str = "bridge-api.md";
+
+/// Render the API reference of an aggregated manifest.
+pub fn render_reference(manifest: &Value) -> String {
+    let crate_name = manifest["crate"].as_str().unwrap_or_default();
+    let mut reference = format!("# `{}` API reference\n\n", crate_name);
+    if let Some(version) = manifest["version"].as_str().filter(|v| !v.is_empty()) {
+        let _ = writeln!(reference, "Version {}.\n", version);
+    }
+    reference.push_str("<!-- Generated by tauri-bridge; do not edit. -->\n");
+
+    let commands = manifest["commands"]
+        .as_array()
+        .map_or(&[][..], Vec::as_slice);
+    for command in commands {
+        let _ = writeln!(
+            reference,
+            "\n## `{}`\n",
+            command["command"].as_str().unwrap_or_default()
+        );
+        if let Some(docs) = command["docs"].as_str().filter(|docs| !docs.is_empty()) {
+            let _ = writeln!(reference, "{}\n", docs);
+        }
+        let _ = writeln!(reference, "```rust\n{}\n```\n", signature(command));
+        if let Some(error) = command["error"].as_str() {
+            let _ = writeln!(reference, "Errors: `{}`\n", error);
+        }
+
+        // Written by hand to keep the arguments in order
+        let fields: Vec<String> = command["args"]
+            .as_array()
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|arg| {
+                let name = arg["name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_case(Case::Camel);
+                let example = arg["type"]
+                    .as_str()
+                    .and_then(|ty| syn::parse_str::<Type>(ty).ok())
+                    .map_or(Value::Null, |ty| example_value(&ty));
+                format!("  {}: {}", Value::from(name), example)
+            })
+            .collect();
+        let payload = if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}\n}}", fields.join(",\n"))
+        };
+        let _ = writeln!(reference, "Example payload:\n\n```json\n{}\n```", payload);
+    }
+    reference
+}
+
+/// The command's signature as written in Rust, without the injected
+/// parameters.
+fn signature(command: &Value) -> String {
+    let args = command["args"]
+        .as_array()
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .map(|arg| {
+            format!(
+                "{}: {}",
+                arg["name"].as_str().unwrap_or_default(),
+                arg["type"].as_str().unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let asyncness = if command["async"] == true {
+        "async "
+    } else {
+        ""
+    };
+    let name = command["name"].as_str().unwrap_or_default();
+    match command["returns"].as_str() {
+        Some("()") | None => format!("{}fn {}({})", asyncness, name, args),
+        Some(returns) => format!("{}fn {}({}) -> {}", asyncness, name, args, returns),
+    }
+}
+
+/// A placeholder value of a Rust type, as serde encodes it to JSON. Types
+/// the reference doesn't know are shown as `"<Name>"`.
+pub fn example_value(ty: &Type) -> Value {
+    match ty {
+        Type::Reference(type_ref) => example_value(&type_ref.elem),
+        Type::Paren(paren) => example_value(&paren.elem),
+        Type::Slice(slice) => json!([example_value(&slice.elem)]),
+        Type::Array(array) => json!([example_value(&array.elem)]),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Value::Null,
+        Type::Tuple(tuple) => tuple.elems.iter().map(example_value).collect(),
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return Value::Null;
+            };
+            let generics = type_generics(&segment.arguments);
+            let ident = segment.ident.to_string();
+            match (ident.as_str(), generics.as_slice()) {
+                ("String" | "str" | "PathBuf" | "Path", _) => json!("string"),
+                ("char", _) => json!("c"),
+                ("bool", _) => json!(false),
+                (
+                    "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+                    | "u128" | "usize",
+                    _,
+                ) => json!(0),
+                ("f32" | "f64", _) => json!(0.0),
+                ("Option", [_]) => Value::Null,
+                ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [inner]) => {
+                    json!([example_value(inner)])
+                }
+                ("HashMap" | "BTreeMap", [_, value]) => json!({ "key": example_value(value) }),
+                ("Box" | "Rc" | "Arc" | "Cow", [.., inner]) => example_value(inner),
+                ("Value", []) => json!({}),
+                _ => json!(format!("<{}>", ident)),
+            }
+        }
+        _ => Value::Null,
+    }
+}
@@ -0,0 +1,367 @@
+//! Crate-wide command registry: duplicate command-name detection and
+//! per-command metadata collected by list macros like `bridge_version_map!`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, ItemFn, Path, Token, Visibility};
+
+use crate::alias::alias_command_ident;
+use crate::attrs::BridgeArgs;
+use crate::client::nested_visibility;
+
+/// Generate the registry entry for a bridged command.
+///
+/// Every command exports a hidden `macro_rules!` marker named after its
+/// command name. `#[macro_export]` places all markers in the crate root, so
+/// two commands sharing a name fail with "the name `__tauri_bridge_command_<name>`
+/// is defined multiple times" instead of silently shadowing each other in
+/// `tauri::generate_handler!`.
+///
+/// It also emits a hidden `__TAURI_BRIDGE_VERSION_<fn>` constant holding the
+/// command's base name and version for `bridge_version_map!`.
+pub fn generate_registry_entry(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let fn_name = &input.sig.ident;
+    let vis = &input.vis;
+    let call_site = Span::call_site();
+
+    // Use the function's span so the duplicate error points at user code
+    let marker_name = syn::Ident::new(
+        &format!(
+            "__tauri_bridge_command_{}",
+            command_ident_suffix(&args.command_name(input))
+        ),
+        fn_name.span(),
+    );
+
+    // Aliases are claimed like command names
+    let alias_markers = args.aliases.iter().map(|alias| {
+        syn::Ident::new(
+            &format!("__tauri_bridge_command_{}", command_ident_suffix(alias)),
+            fn_name.span(),
+        )
+    });
+
+    let version_const =
+        syn::Ident::new(&format!("{}{}", VERSION_PREFIX, fn_name.unraw()), call_site);
+    let base_name = args.base_command_name(input);
+    let version = args.command_version();
+    let item_cfg = args.item_cfg_attr();
+
+    quote_spanned! {call_site=>
+        #item_cfg
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #marker_name {
+            () => {};
+        }
+
+        #(
+            #item_cfg
+            #[doc(hidden)]
+            #[macro_export]
+            macro_rules! #alias_markers {
+                () => {};
+            }
+        )*
+
+        #item_cfg
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        #vis const #version_const: (&str, u32) = (#base_name, #version);
+    }
+}
+
+/// Generate the command's entry macro for `bridge_handler!`.
+///
+/// `bridge_handler!` can't see whether a command's `feature` is enabled, so
+/// each command exports a hidden `__tauri_bridge_handler_<fn>` macro, defined
+/// and re-exported like the `__cmd__` macros of `#[tauri::command]`. It
+/// hands the list state on to `__bridge_handler_step!`, marked `include` or,
+/// while the feature is disabled, `skip`. `include` lists the commands of
+/// the command's aliases, which are kept with it.
+pub fn generate_handler_entry(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let vis = &input.vis;
+    let call_site = Span::call_site();
+    let handler_cfg = args.handler_cfg_attr();
+    let entry_name = syn::Ident::new(
+        &format!("{}{}", HANDLER_PREFIX, input.sig.ident.unraw()),
+        call_site,
+    );
+    let mod_name = syn::Ident::new(
+        &format!("__tauri_handler_{}", input.sig.ident.unraw()),
+        call_site,
+    );
+    // `#[macro_export]` puts the macro in the crate root, under a name that's
+    // unique like the command name
+    let exported_name = syn::Ident::new(
+        &format!(
+            "{}_{}",
+            HANDLER_PREFIX,
+            command_ident_suffix(&args.command_name(input))
+        ),
+        call_site,
+    );
+    let nested_vis = nested_visibility(vis);
+    let macro_export = match vis {
+        Visibility::Inherited => quote_spanned! {call_site=> },
+        _ => quote_spanned! {call_site=> #[macro_export] },
+    };
+
+    let aliases: Vec<_> = (0..args.aliases.len())
+        .map(|index| alias_command_ident(input, index))
+        .collect();
+    let entry = |cfg: TokenStream2, verdict: &str| {
+        let verdict = match verdict {
+            "include" if !aliases.is_empty() => {
+                quote_spanned! {call_site=> include(#(#aliases),*) }
+            }
+            verdict => {
+                let verdict = syn::Ident::new(verdict, call_site);
+                quote_spanned! {call_site=> #verdict }
+            }
+        };
+        quote_spanned! {call_site=>
+            #handler_cfg
+            #cfg
+            #[doc(hidden)]
+            #macro_export
+            macro_rules! #exported_name {
+                ($($state:tt)*) => {
+                    ::tauri_bridge::__bridge_handler_step! { #verdict $($state)* }
+                };
+            }
+        }
+    };
+    let entries = match args.feature_predicate() {
+        Some(feature) => {
+            let include = entry(quote_spanned! {call_site=> #[cfg(#feature)] }, "include");
+            let skip = entry(quote_spanned! {call_site=> #[cfg(not(#feature))] }, "skip");
+            quote_spanned! {call_site=> #include #skip }
+        }
+        None => entry(TokenStream2::new(), "include"),
+    };
+
+    quote_spanned! {call_site=>
+        #handler_cfg
+        #[doc(hidden)]
+        mod #mod_name {
+            #entries
+
+            #[allow(unused_imports)]
+            #nested_vis use #exported_name as #entry_name;
+        }
+
+        #handler_cfg
+        #[allow(unused_imports)]
+        #vis use #mod_name::#entry_name;
+    }
+}
+
+/// Prefix of the per-command `bridge_handler!` entry macro.
+pub const HANDLER_PREFIX: &str = "__tauri_bridge_handler_";
+
+/// State of `bridge_handler!` between two entry macros: whether to keep
+/// the current command and which alias commands next to it, the commands
+/// kept so far and those left.
+pub struct HandlerStep {
+    pub include: bool,
+    pub aliases: Vec<syn::Ident>,
+    pub kept: TokenStream2,
+    pub current: Option<Path>,
+    pub rest: CommandList,
+}
+
+impl Parse for HandlerStep {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let verdict: syn::Ident = input.parse()?;
+        let mut aliases = Vec::new();
+        if input.peek(syn::token::Paren) {
+            let list;
+            syn::parenthesized!(list in input);
+            aliases = Punctuated::<syn::Ident, Token![,]>::parse_terminated(&list)?
+                .into_iter()
+                .collect();
+        }
+        let kept;
+        syn::bracketed!(kept in input);
+        let current;
+        syn::braced!(current in input);
+        Ok(HandlerStep {
+            include: verdict == "include",
+            aliases,
+            kept: kept.parse()?,
+            current: if current.is_empty() {
+                None
+            } else {
+                Some(current.parse()?)
+            },
+            rest: input.parse()?,
+        })
+    }
+}
+
+/// Generate the start of `bridge_handler!`.
+pub fn generate_handler(input: CommandList) -> TokenStream2 {
+    generate_handler_step(HandlerStep {
+        include: false,
+        aliases: Vec::new(),
+        kept: TokenStream2::new(),
+        current: None,
+        rest: input,
+    })
+}
+
+/// Generate the next step of `bridge_handler!`.
+///
+/// Keeps the current command if its entry macro said so and calls the next
+/// command's entry macro, or `tauri::generate_handler!` with the kept
+/// commands once none are left. Entries under `#[cfg(..)]` may not have an
+/// entry macro and are kept as they are, with their attributes.
+pub fn generate_handler_step(step: HandlerStep) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let HandlerStep {
+        include,
+        aliases,
+        mut kept,
+        current,
+        rest,
+    } = step;
+    if include && let Some(current) = current {
+        kept.extend(quote_spanned! {call_site=> #current, });
+        for alias in aliases {
+            let mut path = current.clone();
+            if let Some(last) = path.segments.last_mut() {
+                last.ident = alias;
+            }
+            kept.extend(quote_spanned! {call_site=> #path, });
+        }
+    }
+
+    let mut rest = rest.commands.into_iter();
+    while let Some(command) = rest.next() {
+        let CommandEntry { cfgs, path } = command;
+        if !cfgs.is_empty() {
+            kept.extend(quote_spanned! {call_site=> #(#cfgs)* #path, });
+            continue;
+        }
+
+        let entry = sibling_item_path(&path, HANDLER_PREFIX);
+        let rest = rest.map(|command| {
+            let CommandEntry { cfgs, path } = command;
+            quote_spanned! {call_site=> #(#cfgs)* #path }
+        });
+        return quote_spanned! {call_site=>
+            #entry! { [#kept] {#path} #(#rest),* }
+        };
+    }
+
+    quote_spanned! {call_site=>
+        ::tauri::generate_handler![#kept]
+    }
+}
+
+/// Prefix of the per-command version constant.
+pub const VERSION_PREFIX: &str = "__TAURI_BRIDGE_VERSION_";
+
+/// Turn a wire command name into an identifier-safe suffix.
+///
+/// `_` is escaped as `__`, so the `_`-codes of `.` and `@` can't collide
+/// with other names: `greet@2` becomes `greet_v2`, `users.get` becomes
+/// `users_dget` and `users__get` becomes `users____get`. `rename` and
+/// `alias` only allow these characters besides letters and digits.
+pub fn command_ident_suffix(command: &str) -> String {
+    command
+        .chars()
+        .map(|c| match c {
+            '_' => "__".to_string(),
+            '.' => "_d".to_string(),
+            '@' => "_v".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// A list of command paths, as passed to `bridge_spec!` and friends.
+pub struct CommandList {
+    pub commands: Punctuated<CommandEntry, Token![,]>,
+}
+
+impl Parse for CommandList {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(CommandList {
+            commands: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// A command path in a list, optionally behind `#[cfg(..)]` attributes.
+///
+/// A `#[cfg]` on a bridged function removes it before `#[tauri_bridge]`
+/// runs, hidden items included, so lists gate its entry the same way.
+pub struct CommandEntry {
+    pub cfgs: Vec<Attribute>,
+    pub path: Path,
+}
+
+impl Parse for CommandEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let cfgs = Attribute::parse_outer(input)?;
+        if let Some(attr) = cfgs.iter().find(|attr| !attr.path().is_ident("cfg")) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "only `#[cfg(..)]` attributes are supported on listed commands",
+            ));
+        }
+        Ok(CommandEntry {
+            cfgs,
+            path: input.parse()?,
+        })
+    }
+}
+
+/// Generate an expression mapping each listed command to its version.
+///
+/// Evaluates to a `BTreeMap<&'static str, u32>` keyed by the base command
+/// name, which frontend and backend can compare during a handshake.
+pub fn generate_version_map(input: &CommandList) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let entries = input.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let version = sibling_item_path(&command.path, VERSION_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            {
+                let (name, version) = #version;
+                // Several implementations may serve one command; report the newest
+                let entry = versions.entry(name).or_insert(version);
+                *entry = (*entry).max(version);
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        {
+            let mut versions = ::std::collections::BTreeMap::<&'static str, u32>::new();
+            #(#entries)*
+            versions
+        }
+    }
+}
+
+/// Path to a generated item living next to a command.
+///
+/// `api::greet` with prefix `__tauri_client_` becomes `api::__tauri_client_greet`,
+/// mirroring how `tauri::generate_handler!` resolves its `__cmd__` items.
+pub fn sibling_item_path(command: &Path, prefix: &str) -> Path {
+    let mut path = command.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.ident = syn::Ident::new(
+            &format!("{}{}", prefix, last.ident.unraw()),
+            last.ident.span(),
+        );
+    }
+    path
+}
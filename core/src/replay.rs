@@ -0,0 +1,37 @@
+//! Recorded and replayed client calls with `replayable`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(replayable)]
+//! pub fn list_projects() -> Vec<Project> {}
+//! ```
+//!
+//! The client hands each call's arguments and response, as JSON, to
+//! `tauri_bridge_runtime::replay` while it records, and takes the response
+//! from it instead of invoking while it replays. The backend doesn't change.
+
+use proc_macro2::Span;
+use syn::ItemFn;
+
+use crate::attrs::BridgeArgs;
+
+/// Reject options whose responses don't arrive as one JSON value.
+pub fn check_replayable(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let conflicts = [
+        (
+            args.raw_bytes_response(input),
+            "`replayable` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.chunked.is_some(),
+            "`replayable` can't be combined with `chunked`",
+        ),
+        (
+            args.incremental,
+            "`replayable` can't be combined with `incremental`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,175 @@
+//! Error conversion of commands before generation.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn load_profile(id: u64) -> anyhow::Result<Profile> {}
+//!
+//! #[tauri_bridge(into_error = AppError)]
+//! pub fn save_profile(profile: Profile) -> Result<(), std::io::Error> {}
+//! ```
+//!
+//! `anyhow` and `eyre` errors aren't serializable, and `into_error` or
+//! `map_err` pick the error type sent instead of the function's. Before
+//! generating anything, such commands are rewritten to return
+//! `Result<T, E>` with the sent error type (`ErrorReport` from
+//! `tauri-bridge-runtime` for `anyhow` and `eyre`), running the original
+//! body as is and converting its error. Every later step sees an ordinary
+//! `Result` command.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::{GenericArgument, ItemFn, PathArguments, ReturnType, Type};
+
+use crate::attrs::ErrorConversion;
+
+/// Crates whose result and error types are reported.
+const REPORT_CRATES: &[&str] = &["anyhow", "eyre", "color_eyre"];
+
+/// Rewrite a command returning an `anyhow` or `eyre` result to return
+/// `Result<T, ErrorReport>`.
+///
+/// With `declaration_only` there's no body, so only the signature changes.
+pub fn report_errors(input: &mut ItemFn, declaration_only: bool) {
+    let ReturnType::Type(_, ty) = &input.sig.output else {
+        return;
+    };
+    let Some(ok) = report_result_ok(ty).cloned() else {
+        return;
+    };
+    let call_site = Span::call_site();
+    convert_errors(
+        input,
+        declaration_only,
+        &ok,
+        &quote_spanned! {call_site=> tauri_bridge_runtime::error::ErrorReport },
+        &quote_spanned! {call_site=> tauri_bridge_runtime::error::ErrorReport::from_report },
+    );
+}
+
+/// Rewrite a command returning a `Result` to return its errors converted
+/// as set with `into_error` or `map_err`.
+pub fn convert_command_errors(
+    input: &mut ItemFn,
+    declaration_only: bool,
+    conversion: &ErrorConversion,
+) -> syn::Result<()> {
+    let ok = match &input.sig.output {
+        ReturnType::Type(_, ty) => result_ok(ty).cloned(),
+        ReturnType::Default => None,
+    };
+    let Some(ok) = ok else {
+        return Err(syn::Error::new_spanned(
+            &input.sig,
+            "`into_error` and `map_err` need a function returning a `Result`",
+        ));
+    };
+    convert_errors(
+        input,
+        declaration_only,
+        &ok,
+        &conversion.error_type(),
+        &conversion.function(),
+    );
+    Ok(())
+}
+
+/// Make `input` return `Result<ok, error_type>`, running the original body
+/// and converting its errors with `convert`.
+fn convert_errors(
+    input: &mut ItemFn,
+    declaration_only: bool,
+    ok: &Type,
+    error_type: &TokenStream2,
+    convert: &TokenStream2,
+) {
+    let call_site = Span::call_site();
+    let output = match &input.sig.output {
+        ReturnType::Default => quote_spanned! {call_site=> () },
+        ReturnType::Type(_, ty) => quote_spanned! {call_site=> #ty },
+    };
+
+    if !declaration_only {
+        let block = &input.block;
+        // Returns in the body must leave the block only
+        let run = if input.sig.asyncness.is_some() {
+            quote_spanned! {call_site=> async #block.await }
+        } else {
+            quote_spanned! {call_site=> (|| -> #output #block)() }
+        };
+        input.block = syn::parse_quote! {
+            {
+                let __tauri_bridge_response: #output = #run;
+                __tauri_bridge_response.map_err(#convert)
+            }
+        };
+    }
+    input.sig.output = syn::parse_quote! {
+        -> Result<#ok, #error_type>
+    };
+}
+
+/// The success type of any `Result`, including aliases like
+/// `io::Result<T>`.
+fn result_ok(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    match args.args.first() {
+        Some(GenericArgument::Type(ok)) => Some(ok),
+        _ => None,
+    }
+}
+
+/// The success type of `anyhow::Result<T>`, `eyre::Result<T>` or a
+/// `Result<T, E>` with their error type.
+///
+/// The crate path must be spelled out: a `Result` imported from them looks
+/// like any other.
+fn report_result_ok(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    let mut types = args.args.iter().map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    match (types.next().flatten(), types.next(), types.next()) {
+        (Some(ok), None, None) if from_report_crate(&type_path.path) => Some(ok),
+        (Some(ok), Some(Some(error)), None) if is_report_error(error) => Some(ok),
+        _ => None,
+    }
+}
+
+/// Whether `anyhow::Error`, `eyre::Report` or similar.
+fn is_report_error(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Error" || segment.ident == "Report")
+        && from_report_crate(&type_path.path)
+}
+
+fn from_report_crate(path: &syn::Path) -> bool {
+    path.segments.len() >= 2
+        && REPORT_CRATES
+            .iter()
+            .any(|name| path.segments[0].ident == name)
+}
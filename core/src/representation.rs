@@ -0,0 +1,510 @@
+//! Tagged and untagged serde representations of the types commands send.
+//!
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(tag = "kind", content = "data")]
+//! pub enum Shape { Circle(Option<f64>), Square(f64) }
+//!
+//! #[tauri_bridge]
+//! pub fn draw(shape: Shape) -> Result<(), String> {}
+//! ```
+//!
+//! Internally and adjacently tagged enums, untagged enums and structs with
+//! `#[serde(flatten)]` fields serialize through maps, or leave the content
+//! of `None` undefined. The default `serde_wasm_bindgen` settings turn maps
+//! into JS `Map`s and `None` into `undefined`, which Tauri's JSON IPC drops,
+//! so such arguments arrive as `{}` or without their content.
+//!
+//! The structs and enums of the crate being compiled are read from its
+//! `src` directory and keyed by their module path, e.g. `events::Event`.
+//! Types are resolved by the path they're written with, and the fields of a
+//! type from its own module first. When a sent parameter reaches a tagged
+//! one, through generic arguments or the fields of other types of the
+//! crate, the client serializes the arguments like `json_compatible` does.
+//! Newtype variants of internally tagged enums holding a type serde can't
+//! tag fail every call, so they're rejected at compile time. Types of other
+//! crates aren't read; commands sending those set `json_compatible`.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use quote::ToTokens;
+use syn::{Attribute, Fields, FnArg, ItemFn, LitStr, ReturnType, Type};
+
+use crate::inject::is_injected;
+
+/// How a type is represented beyond serde's defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tagging {
+    /// `#[serde(tag = "..")]`
+    Internal(String),
+    /// `#[serde(tag = "..", content = "..")]`
+    Adjacent(String, String),
+    /// `#[serde(untagged)]`, on the enum or one of its variants.
+    Untagged,
+    /// A field with `#[serde(flatten)]`.
+    Flatten,
+}
+
+/// A struct or enum of the crate, as far as its serde representation goes.
+#[derive(Clone, Debug, Default)]
+pub struct SerdeType {
+    pub tagging: Option<Tagging>,
+    /// Types of its fields, across every variant.
+    pub fields: Vec<Type>,
+    /// Newtype variants of an internally tagged enum holding a type serde
+    /// can't tag, with that type.
+    pub untaggable: Vec<(String, Type)>,
+}
+
+/// The structs and enums of a crate by module path, e.g. `events::Event`
+/// for `crate::events::Event`.
+pub type CrateTypes = HashMap<String, SerdeType>;
+
+/// A source file's types, as of its modification time and length.
+struct CachedFile {
+    modified: Option<SystemTime>,
+    len: u64,
+    types: CrateTypes,
+}
+
+#[derive(Default)]
+struct CachedCrate {
+    files: HashMap<PathBuf, CachedFile>,
+    types: Rc<CrateTypes>,
+}
+
+thread_local! {
+    // Proc macro servers may expand several crates in one process, and
+    // keep running while the files are edited
+    static TYPES_CACHE: RefCell<HashMap<PathBuf, CachedCrate>> = RefCell::new(HashMap::new());
+}
+
+/// Load the structs and enums of the crate currently being compiled.
+///
+/// Only files added or changed since the last call are parsed again. Files
+/// that can't be read or parsed are skipped; rustc reports them.
+pub fn load_crate_types() -> Rc<CrateTypes> {
+    let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") else {
+        return Rc::default();
+    };
+    let src_dir = PathBuf::from(manifest_dir).join("src");
+
+    let mut paths = Vec::new();
+    source_files(&src_dir, &mut paths);
+
+    TYPES_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let cached = cache.entry(src_dir.clone()).or_default();
+        let mut changed = paths.len() != cached.files.len();
+        let mut files = HashMap::new();
+        for path in paths {
+            let metadata = std::fs::metadata(&path).ok();
+            let modified = metadata
+                .as_ref()
+                .and_then(|metadata| metadata.modified().ok());
+            let len = metadata.map_or(0, |metadata| metadata.len());
+            let file = match cached.files.remove(&path) {
+                Some(file) if file.modified == modified && file.len == len => file,
+                _ => {
+                    changed = true;
+                    CachedFile {
+                        modified,
+                        len,
+                        types: file_types(&src_dir, &path),
+                    }
+                }
+            };
+            files.insert(path, file);
+        }
+        // Files that are gone are left in `cached.files`
+        changed |= !cached.files.is_empty();
+
+        if changed {
+            let mut types = CrateTypes::new();
+            for file in files.values() {
+                for (path, serde_type) in &file.types {
+                    insert_type(&mut types, path.clone(), serde_type.clone());
+                }
+            }
+            cached.types = Rc::new(types);
+        }
+        cached.files = files;
+        cached.types.clone()
+    })
+}
+
+/// The types of a source file, keyed by module path.
+fn file_types(src_dir: &Path, path: &Path) -> CrateTypes {
+    let mut types = CrateTypes::new();
+    if let Ok(source) = std::fs::read_to_string(path)
+        && let Ok(parsed) = syn::parse_file(&source)
+    {
+        collect_types(&parsed.items, &file_module(src_dir, path), &mut types);
+    }
+    types
+}
+
+/// The module a source file defines, e.g. `events::history` for
+/// `src/events/history.rs` or `src/events/history/mod.rs`.
+fn file_module(src_dir: &Path, path: &Path) -> String {
+    let Ok(relative) = path
+        .with_extension("")
+        .strip_prefix(src_dir)
+        .map(Path::to_path_buf)
+    else {
+        return String::new();
+    };
+    let mut segments: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if segments.last().is_some_and(|last| last == "mod")
+        || segments.as_slice() == ["lib"]
+        || segments.as_slice() == ["main"]
+    {
+        segments.pop();
+    }
+    segments.join("::")
+}
+
+/// The `.rs` files under `dir`, recursively.
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            source_files(&path, files);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// Collect the structs and enums of the `items` of `module`, empty for the
+/// crate root, including those of inline modules.
+pub fn collect_types(items: &[syn::Item], module: &str, types: &mut CrateTypes) {
+    let path = |ident: &syn::Ident| match module {
+        "" => ident.to_string(),
+        module => format!("{}::{}", module, ident),
+    };
+    for item in items {
+        let (path, serde_type) = match item {
+            syn::Item::Struct(item) => (path(&item.ident), struct_type(item)),
+            syn::Item::Enum(item) => (path(&item.ident), enum_type(item)),
+            syn::Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    collect_types(items, &path(&item.ident), types);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        insert_type(types, path, serde_type);
+    }
+}
+
+/// Add a type, merging it with one at the same path.
+///
+/// Those are alternatives behind `#[cfg]`s, and none of them is rejected,
+/// as it can't be told which one is compiled.
+fn insert_type(types: &mut CrateTypes, path: String, serde_type: SerdeType) {
+    match types.get_mut(&path) {
+        Some(existing) => {
+            existing.tagging = existing.tagging.take().or(serde_type.tagging);
+            existing.fields.extend(serde_type.fields);
+            existing.untaggable.clear();
+        }
+        None => {
+            types.insert(path, serde_type);
+        }
+    }
+}
+
+/// The types of the crate a path written in `module` may refer to.
+///
+/// Paths from the crate root must match exactly. Others are looked up in
+/// `module` first, then matched against the end of every type's path, as
+/// `use`s aren't followed.
+fn resolve<'a>(
+    path: &syn::Path,
+    module: Option<&str>,
+    types: &'a CrateTypes,
+) -> Vec<(&'a String, &'a SerdeType)> {
+    let mut segments: Vec<String> = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let from_root = segments.first().is_some_and(|first| first == "crate");
+    segments.retain(|segment| !matches!(segment.as_str(), "crate" | "self" | "super"));
+    let written = segments.join("::");
+
+    if from_root {
+        return types.get_key_value(&written).into_iter().collect();
+    }
+    if let Some(module) = module.filter(|module| !module.is_empty())
+        && let Some(found) = types.get_key_value(&format!("{}::{}", module, written))
+    {
+        return vec![found];
+    }
+    let suffix = format!("::{}", written);
+    types
+        .iter()
+        .filter(|(path, _)| **path == written || path.ends_with(&suffix))
+        .collect()
+}
+
+fn struct_type(item: &syn::ItemStruct) -> SerdeType {
+    let flatten = item
+        .fields
+        .iter()
+        .any(|field| serde_flags(&field.attrs).contains(&"flatten".to_string()));
+    SerdeType {
+        tagging: flatten.then_some(Tagging::Flatten),
+        fields: item.fields.iter().map(|field| field.ty.clone()).collect(),
+        untaggable: Vec::new(),
+    }
+}
+
+fn enum_type(item: &syn::ItemEnum) -> SerdeType {
+    let (tag, content) = (
+        serde_value(&item.attrs, "tag"),
+        serde_value(&item.attrs, "content"),
+    );
+    let untagged = serde_flags(&item.attrs).contains(&"untagged".to_string())
+        || item
+            .variants
+            .iter()
+            .any(|variant| serde_flags(&variant.attrs).contains(&"untagged".to_string()));
+    let flatten = item.variants.iter().any(|variant| {
+        variant
+            .fields
+            .iter()
+            .any(|field| serde_flags(&field.attrs).contains(&"flatten".to_string()))
+    });
+    let tagging = match (tag, content) {
+        (Some(tag), Some(content)) => Some(Tagging::Adjacent(tag, content)),
+        (Some(tag), None) => Some(Tagging::Internal(tag)),
+        _ if untagged => Some(Tagging::Untagged),
+        _ if flatten => Some(Tagging::Flatten),
+        _ => None,
+    };
+
+    let untaggable = match tagging {
+        Some(Tagging::Internal(_)) => item
+            .variants
+            .iter()
+            .filter_map(|variant| match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    let field = &fields.unnamed[0];
+                    // Custom serialization may well produce a map
+                    let custom = ["with", "serialize_with"]
+                        .iter()
+                        .any(|key| serde_value(&field.attrs, key).is_some());
+                    (!custom && !taggable(&field.ty))
+                        .then(|| (variant.ident.to_string(), field.ty.clone()))
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    SerdeType {
+        tagging,
+        fields: item
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter().map(|field| field.ty.clone()))
+            .collect(),
+        untaggable,
+    }
+}
+
+/// Types serde can't add a tag to: the newtype variant of an internally
+/// tagged enum must serialize as a map, a struct or a unit.
+const UNTAGGABLE: &[&str] = &[
+    "bool", "char", "str", "String", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16",
+    "i32", "i64", "i128", "isize", "f32", "f64", "Option", "Vec", "VecDeque", "HashSet",
+    "BTreeSet",
+];
+
+fn taggable(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => taggable(&reference.elem),
+        Type::Paren(paren) => taggable(&paren.elem),
+        Type::Group(group) => taggable(&group.elem),
+        Type::Tuple(tuple) => tuple.elems.is_empty(),
+        Type::Array(_) | Type::Slice(_) => false,
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_none_or(|segment| !UNTAGGABLE.contains(&segment.ident.to_string().as_str())),
+        _ => true,
+    }
+}
+
+/// Names of the flags in the `#[serde(..)]` attributes.
+fn serde_flags(attrs: &[Attribute]) -> Vec<String> {
+    let mut flags = Vec::new();
+    for_serde_meta(attrs, |meta| {
+        if meta.input.is_empty() || meta.input.peek(syn::Token![,]) {
+            flags.extend(meta.path.get_ident().map(|ident| ident.to_string()));
+        }
+        Ok(false)
+    });
+    flags
+}
+
+/// The string value of `key` in the `#[serde(..)]` attributes.
+fn serde_value(attrs: &[Attribute], key: &str) -> Option<String> {
+    let mut value = None;
+    for_serde_meta(attrs, |meta| {
+        if meta.path.is_ident(key) && meta.input.peek(syn::Token![=]) {
+            value = Some(meta.value()?.parse::<LitStr>()?.value());
+            return Ok(true);
+        }
+        Ok(false)
+    });
+    value
+}
+
+/// Run `visit` on the entries of the `#[serde(..)]` attributes, skipping
+/// the value of those it didn't consume. Malformed attributes are left to
+/// serde to report.
+fn for_serde_meta(
+    attrs: &[Attribute],
+    mut visit: impl FnMut(&syn::meta::ParseNestedMeta) -> syn::Result<bool>,
+) {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if visit(&meta)? {
+                return Ok(());
+            }
+            if meta.input.peek(syn::Token![=]) {
+                let _: syn::Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _list;
+                syn::parenthesized!(_list in meta.input);
+            }
+            Ok(())
+        });
+    }
+}
+
+/// A tagged type of the crate a type reaches.
+struct Reached<'a> {
+    name: String,
+    serde_type: &'a SerdeType,
+    /// Whether other types of the crate matched the same path.
+    ambiguous: bool,
+}
+
+/// The tagged types of the crate `ty` reaches, through its generic
+/// arguments and the fields of the crate's types.
+fn reached<'a>(ty: &Type, types: &'a CrateTypes) -> Vec<Reached<'a>> {
+    let mut found = Vec::new();
+    let mut seen = HashSet::new();
+    // With the module the type is written in, unknown for the command's own
+    let mut pending: Vec<(Type, Option<String>)> = vec![(ty.clone(), None)];
+    while let Some((ty, module)) = pending.pop() {
+        let within = |ty: Type| (ty, module.clone());
+        match ty {
+            Type::Reference(reference) => pending.push(within(*reference.elem)),
+            Type::Paren(paren) => pending.push(within(*paren.elem)),
+            Type::Group(group) => pending.push(within(*group.elem)),
+            Type::Array(array) => pending.push(within(*array.elem)),
+            Type::Slice(slice) => pending.push(within(*slice.elem)),
+            Type::Tuple(tuple) => pending.extend(tuple.elems.into_iter().map(within)),
+            Type::Path(type_path) => {
+                for segment in &type_path.path.segments {
+                    if let syn::PathArguments::AngleBracketed(generics) = &segment.arguments {
+                        pending.extend(generics.args.iter().filter_map(|arg| match arg {
+                            syn::GenericArgument::Type(ty) => Some(within(ty.clone())),
+                            _ => None,
+                        }));
+                    }
+                }
+                let Some(segment) = type_path.path.segments.last() else {
+                    continue;
+                };
+                let candidates = resolve(&type_path.path, module.as_deref(), types);
+                let ambiguous = candidates.len() > 1;
+                for (path, serde_type) in candidates {
+                    if !seen.insert(path.clone()) {
+                        continue;
+                    }
+                    let type_module = path.rsplit_once("::").map_or("", |(module, _)| module);
+                    pending.extend(
+                        serde_type
+                            .fields
+                            .iter()
+                            .map(|field| (field.clone(), Some(type_module.to_string()))),
+                    );
+                    if serde_type.tagging.is_some() {
+                        found.push(Reached {
+                            name: segment.ident.to_string(),
+                            serde_type,
+                            ambiguous,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Reject internally tagged types the command sends that serde can't tag,
+/// and return whether a sent parameter reaches a tagged type of the crate.
+pub fn check_representations(input: &ItemFn, types: &CrateTypes) -> syn::Result<bool> {
+    let params = input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) if !is_injected(pat_type) => Some((pat_type.ty.as_ref(), true)),
+        _ => None,
+    });
+    let response = match &input.sig.output {
+        ReturnType::Type(_, ty) => Some((ty.as_ref(), false)),
+        ReturnType::Default => None,
+    };
+
+    let mut json_compatible = false;
+    for (ty, sent) in params.chain(response) {
+        let found = reached(ty, types);
+        // Types another one shares the path with may not be the one meant
+        if let Some((name, tag, (variant, held))) = found
+            .iter()
+            .filter(|reached| !reached.ambiguous)
+            .find_map(|reached| {
+                match (
+                    &reached.serde_type.tagging,
+                    reached.serde_type.untaggable.first(),
+                ) {
+                    (Some(Tagging::Internal(tag)), Some(untaggable)) => {
+                        Some((&reached.name, tag, untaggable))
+                    }
+                    _ => None,
+                }
+            })
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "`{}` is internally tagged with `#[serde(tag = \"{}\")]`, so serde can't send its newtype variant `{}` holding `{}`; give the variant named fields or set `content` to tag it adjacently",
+                    name,
+                    tag,
+                    variant,
+                    held.to_token_stream()
+                ),
+            ));
+        }
+        json_compatible |= sent && !found.is_empty();
+    }
+    Ok(json_compatible)
+}
@@ -0,0 +1,141 @@
+//! Round-trip property tests generated with `proptest`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(proptest)]
+//! pub fn save(note: Note, tags: &[String]) -> Result<u64, SaveError> {}
+//! ```
+//!
+//! Under `cfg(test)` the command gets a hidden module of `proptest!` tests
+//! checking that arbitrary arguments, in the shape the client sends them,
+//! and arbitrary responses come back unchanged from `serde_json`. Needs
+//! the `proptest` feature of this crate, and `proptest` and `serde_json` in
+//! the user's tests; the types need `Arbitrary`, `PartialEq` and `Debug`.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{FnArg, ItemFn, Pat, PatType, ReturnType, Type};
+
+use crate::attrs::BridgeArgs;
+use crate::incremental::batch_type;
+use crate::inject::is_injected;
+use crate::types::{borrowed_param, has_reference_type, mutable_param, owned_type, result_types};
+
+/// Reject arguments the tests can't generate owned values for.
+pub fn check_proptest(input: &ItemFn) -> syn::Result<()> {
+    for pat_type in sent_params(input) {
+        if wire_type(&pat_type.ty).is_none() {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "`proptest` needs owned argument types, or `&str`, `&[T]` and `&T`",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Generate the round-trip tests of a command.
+pub fn generate_roundtrip_tests(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let test_cfg = args.test_cfg_attr();
+    let mod_name = syn::Ident::new(
+        &format!("__tauri_proptest_{}", input.sig.ident.unraw()),
+        call_site,
+    );
+
+    let mut fields = Vec::new();
+    let mut strategies = Vec::new();
+    let mut names = Vec::new();
+    for pat_type in sent_params(input) {
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            continue;
+        };
+        let Some(ty) = wire_type(&pat_type.ty) else {
+            continue;
+        };
+        let ident = &pat_ident.ident;
+        fields.push(quote_spanned! {call_site=> #ident: #ty });
+        strategies.push(quote_spanned! {call_site=> #ident in ::proptest::prelude::any::<#ty>() });
+        names.push(ident);
+    }
+
+    // The same shape as the client's args struct, owned
+    let args_test = (!fields.is_empty()).then(|| {
+        quote_spanned! {call_site=>
+            #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct Args {
+                #(#fields),*
+            }
+
+            ::proptest::proptest! {
+                #[test]
+                fn args_round_trip(#(#strategies),*) {
+                    let args = Args { #(#names),* };
+                    let json = ::serde_json::to_string(&args).unwrap();
+                    let decoded: Args = ::serde_json::from_str(&json).unwrap();
+                    ::proptest::prop_assert_eq!(decoded, args);
+                }
+            }
+        }
+    });
+
+    // Raw responses skip JSON; `Result`s send the value or the error
+    let response_types: Vec<&Type> = match &input.sig.output {
+        _ if args.raw_bytes_response(input) => Vec::new(),
+        ReturnType::Default => Vec::new(),
+        ReturnType::Type(_, ty) => match result_types(ty) {
+            Some((ok, error)) => vec![ok, error],
+            None => vec![ty.as_ref()],
+        },
+    };
+    let response_tests = response_types.iter().enumerate().map(|(index, ty)| {
+        let test_name = match (response_types.len(), index) {
+            (1, _) => "response_round_trip",
+            (_, 0) => "ok_response_round_trip",
+            _ => "error_response_round_trip",
+        };
+        let test_name = syn::Ident::new(test_name, call_site);
+        quote_spanned! {call_site=>
+            ::proptest::proptest! {
+                #[test]
+                fn #test_name(response in ::proptest::prelude::any::<#ty>()) {
+                    let json = ::serde_json::to_string(&response).unwrap();
+                    let decoded: #ty = ::serde_json::from_str(&json).unwrap();
+                    ::proptest::prop_assert_eq!(decoded, response);
+                }
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        #test_cfg
+        #[doc(hidden)]
+        mod #mod_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #args_test
+            #(#response_tests)*
+        }
+    }
+}
+
+/// Parameters the client sends: all but the injected ones and channels.
+fn sent_params(input: &ItemFn) -> impl Iterator<Item = &PatType> {
+    input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) if !is_injected(pat_type) && batch_type(&pat_type.ty).is_none() => {
+            Some(pat_type)
+        }
+        _ => None,
+    })
+}
+
+/// The owned type an argument is sent as.
+fn wire_type(ty: &Type) -> Option<TokenStream2> {
+    let call_site = Span::call_site();
+    if let Some(borrowed) = borrowed_param(ty).or_else(|| mutable_param(ty)) {
+        return Some(owned_type(&borrowed));
+    }
+    (!has_reference_type(ty)).then(|| quote_spanned! {call_site=> #ty })
+}
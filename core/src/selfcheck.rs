@@ -0,0 +1,383 @@
+//! Round trips of sample values at startup with `bridge_selfcheck!`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(selfcheck)]
+//! pub fn save_note(note: Note, tags: &[String]) -> Result<NoteId, String> {}
+//!
+//! tauri_bridge::bridge_selfcheck![save_note, list_notes];
+//! ```
+//!
+//! In debug builds, commands with `selfcheck` get a hidden backend function
+//! feeding the `Default` of their response and sent argument types to a
+//! `tauri_bridge_runtime::selfcheck::Checker`, and a hidden client function
+//! serializing the `Default` arguments the way the command's client sends
+//! them and decoding a response sample the way it decodes responses.
+//!
+//! `bridge_selfcheck!` expands to the backend's `__bridge_selfcheck`
+//! command calling the listed backend functions, and the client's
+//! `run_bridge_selfcheck` passing the samples through them both ways. In
+//! release builds both are kept, so registering the command needs no
+//! `cfg`, but check nothing.
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{FnArg, ItemFn, Pat, PatType, ReturnType, Type};
+
+use crate::attrs::BridgeArgs;
+use crate::entries::uses_entries;
+use crate::inject::{is_caller_context, is_injected};
+use crate::introspect::IntrospectInput;
+use crate::registry::sibling_item_path;
+use crate::types::{borrowed_param, has_reference_type, owned_type, result_types};
+
+/// Prefix of the backend function sampling a command.
+pub const SELFCHECK_PREFIX: &str = "__tauri_bridge_selfcheck_";
+
+/// Prefix of the client function passing a command's samples through.
+pub const CLIENT_SELFCHECK_PREFIX: &str = "__tauri_bridge_client_selfcheck_";
+
+/// Reject options whose payload isn't the plain serialization of the
+/// arguments and response, and commands whose samples can't be built.
+pub fn check_selfcheck(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if !args.selfcheck {
+        return Ok(());
+    }
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`selfcheck` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`selfcheck` can't be combined with `compress`",
+        ),
+        (args.encrypt, "`selfcheck` can't be combined with `encrypt`"),
+        (args.signed, "`selfcheck` can't be combined with `signed`"),
+        (
+            args.incremental,
+            "`selfcheck` can't be combined with `incremental`",
+        ),
+        (
+            args.by_value,
+            "`selfcheck` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`selfcheck` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.raw_response,
+            "`selfcheck` can't be combined with `raw_response`",
+        ),
+        (
+            args.unwrap_envelope,
+            "`selfcheck` can't be combined with `unwrap_envelope`",
+        ),
+        (
+            uses_entries(input, args),
+            "`selfcheck` can't be combined with `map_as`",
+        ),
+        (
+            args.floats.is_some(),
+            "`selfcheck` can't be combined with `floats`",
+        ),
+    ];
+    if let Some((_, message)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+        return Err(syn::Error::new(Span::call_site(), message));
+    }
+
+    if let Some(param) = input.sig.generics.params.first() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "`selfcheck` can't build samples of generic commands",
+        ));
+    }
+    for (_, pat_type) in sent_params(input) {
+        if has_reference_type(&pat_type.ty) && borrowed_param(&pat_type.ty).is_none() {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "`selfcheck` needs owned argument types, or `&str`, `&[T]` and `&T`",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Parameters the client sends, with their names.
+fn sent_params(input: &ItemFn) -> impl Iterator<Item = (&syn::Ident, &PatType)> {
+    input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) if !is_injected(pat_type) && !is_caller_context(&pat_type.ty) => {
+            match pat_type.pat.as_ref() {
+                Pat::Ident(pat_ident) => Some((&pat_ident.ident, pat_type)),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// The owned type a parameter is sampled as.
+fn sample_type(ty: &Type) -> TokenStream2 {
+    match borrowed_param(ty) {
+        Some(borrowed) => owned_type(&borrowed),
+        None => quote_spanned! {Span::call_site()=> #ty },
+    }
+}
+
+/// The type of the response sample: the `Ok` type of a `Result`.
+fn response_type(input: &ItemFn) -> TokenStream2 {
+    let call_site = Span::call_site();
+    match &input.sig.output {
+        ReturnType::Type(_, ty) => match result_types(ty) {
+            Some((ok, _)) => quote_spanned! {call_site=> #ok },
+            None => quote_spanned! {call_site=> #ty },
+        },
+        ReturnType::Default => quote_spanned! {call_site=> () },
+    }
+}
+
+/// Generate the debug-only backend function sampling the command.
+pub fn generate_selfcheck_backend(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let backend_cfg = args.backend_cfg_attr();
+    let command_name = args.command_name(input);
+    let selfcheck_name = syn::Ident::new(
+        &format!("{}{}", SELFCHECK_PREFIX, input.sig.ident.unraw()),
+        call_site,
+    );
+    let response = response_type(input);
+    let sent = sent_params(input).map(|(ident, pat_type)| {
+        // Tauri looks up arguments by their camelCase name
+        let key = ident.unraw().to_string().to_case(Case::Camel);
+        let ty = sample_type(&pat_type.ty);
+        quote_spanned! {call_site=>
+            tauri_bridge_runtime::selfcheck::Arg::of::<#ty>(#key)
+        }
+    });
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        #[cfg(debug_assertions)]
+        #[doc(hidden)]
+        #[allow(deprecated, dead_code)]
+        #vis fn #selfcheck_name(checker: &mut tauri_bridge_runtime::selfcheck::Checker) {
+            checker.command::<#response>(#command_name, &[#(#sent),*]);
+        }
+    }
+}
+
+/// Generate the debug-only client function passing the command's samples
+/// through its serialization.
+///
+/// Like a dispatch function, it returns `None` for other commands, and
+/// otherwise the arguments and the JSON of the decoded response sample.
+pub fn generate_selfcheck_client(input: &ItemFn, args: &BridgeArgs) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let client_cfg = args.client_cfg_attr();
+    let command_name = args.command_name(input);
+    let selfcheck_name = syn::Ident::new(
+        &format!("{}{}", CLIENT_SELFCHECK_PREFIX, input.sig.ident.unraw()),
+        call_site,
+    );
+    let serde = args.serde_path();
+    let serde_crate = args.serde_crate_attr();
+    let swb = args.serde_wasm_bindgen_path();
+    let response = response_type(input);
+
+    let (fields, inits): (Vec<_>, Vec<_>) = sent_params(input)
+        .map(|(ident, pat_type)| {
+            let ty = sample_type(&pat_type.ty);
+            (
+                quote_spanned! {call_site=> #ident: #ty },
+                quote_spanned! {call_site=> #ident: ::core::default::Default::default() },
+            )
+        })
+        .unzip();
+    // Serialized like the command's args struct
+    let serialize = if args.json_compatible {
+        quote_spanned! {call_site=>
+            #serde::Serialize::serialize(&args, &#swb::Serializer::json_compatible())
+        }
+    } else {
+        quote_spanned! {call_site=> #swb::to_value(&args) }
+    };
+
+    quote_spanned! {call_site=>
+        #client_cfg
+        #[cfg(debug_assertions)]
+        #[doc(hidden)]
+        #[allow(deprecated, dead_code)]
+        #vis fn #selfcheck_name(
+            command: &str,
+            response: wasm_bindgen::JsValue,
+        ) -> Option<Result<(wasm_bindgen::JsValue, String), String>> {
+            #[derive(#serde::Serialize)]
+            #serde_crate
+            #[serde(rename_all = "camelCase")]
+            struct SelfCheckArgs {
+                #(#fields),*
+            }
+
+            fn echo(
+                args: SelfCheckArgs,
+                response: wasm_bindgen::JsValue,
+            ) -> Result<(wasm_bindgen::JsValue, String), String> {
+                let args = #serialize
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                let response: #response = #swb::from_value(response)
+                    .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+                Ok((args, tauri_bridge_runtime::selfcheck::encode(&response)?))
+            }
+
+            if command != #command_name {
+                return None;
+            }
+            Some(echo(SelfCheckArgs { #(#inits),* }, response))
+        }
+    }
+}
+
+/// Generate the backend's `__bridge_selfcheck` command over the listed
+/// commands and the client's `run_bridge_selfcheck`.
+pub fn generate_selfcheck(input: &IntrospectInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let backend_cfg = input.bridge_args.backend_cfg_attr();
+    let client_cfg = input.bridge_args.client_cfg_attr();
+
+    let samplers = input.commands.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let sampler = sibling_item_path(&command.path, SELFCHECK_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            #sampler(&mut checker);
+        }
+    });
+    let echoes = input.commands.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let echo = sibling_item_path(&command.path, CLIENT_SELFCHECK_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            {
+                if echoed.is_none() {
+                    echoed = #echo(&sample.command, sample.response.clone());
+                }
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        mod __tauri_selfcheck {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Sample the responses of the listed commands, or compare the
+            /// samples the client sent back. Checks nothing in release
+            /// builds.
+            #[tauri::command]
+            pub fn __bridge_selfcheck(
+                echoes: Option<Vec<tauri_bridge_runtime::selfcheck::Echo>>,
+            ) -> tauri_bridge_runtime::selfcheck::SelfCheck {
+                #[allow(unused_mut)]
+                let mut checker = tauri_bridge_runtime::selfcheck::Checker::new(echoes);
+                #[cfg(debug_assertions)]
+                {
+                    #(#samplers)*
+                }
+                checker.finish()
+            }
+        }
+
+        #backend_cfg
+        pub use __tauri_selfcheck::__bridge_selfcheck;
+
+        /// Pass samples of the listed commands' arguments and responses
+        /// through both sides and report those that changed. Checks nothing
+        /// in release builds.
+        #client_cfg
+        pub async fn run_bridge_selfcheck()
+        -> Result<tauri_bridge_runtime::selfcheck::Report, String> {
+            #[cfg(debug_assertions)]
+            {
+                #[derive(serde::Deserialize)]
+                struct Sample {
+                    command: String,
+                    #[serde(with = "serde_wasm_bindgen::preserve")]
+                    response: wasm_bindgen::JsValue,
+                }
+
+                #[derive(serde::Deserialize)]
+                struct Samples {
+                    samples: Vec<Sample>,
+                }
+
+                #[derive(serde::Serialize)]
+                struct Echo {
+                    command: String,
+                    #[serde(with = "serde_wasm_bindgen::preserve")]
+                    args: wasm_bindgen::JsValue,
+                    response: String,
+                }
+
+                #[derive(serde::Serialize)]
+                struct SelfCheckArgs {
+                    echoes: Option<Vec<Echo>>,
+                }
+
+                let args = serde_wasm_bindgen::to_value(&SelfCheckArgs { echoes: None })
+                    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                let result = crate::invoke(
+                    tauri_bridge_runtime::selfcheck::SELFCHECK_COMMAND,
+                    args,
+                )
+                .await;
+                let samples: Samples = serde_wasm_bindgen::from_value(result)
+                    .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+
+                let mut report = tauri_bridge_runtime::selfcheck::Report::default();
+                let mut echoes = Vec::new();
+                for sample in samples.samples {
+                    let mut echoed = None;
+                    #(#echoes)*
+                    match echoed {
+                        Some(Ok((args, response))) => echoes.push(Echo {
+                            command: sample.command,
+                            args,
+                            response,
+                        }),
+                        Some(Err(error)) => report.mismatch(&sample.command, "response", error),
+                        None => report.mismatch(
+                            &sample.command,
+                            "command",
+                            "not listed in the client's `bridge_selfcheck!`",
+                        ),
+                    }
+                }
+
+                let args = serde_wasm_bindgen::to_value(&SelfCheckArgs {
+                    echoes: Some(echoes),
+                })
+                .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+                let result = crate::invoke(
+                    tauri_bridge_runtime::selfcheck::SELFCHECK_COMMAND,
+                    args,
+                )
+                .await;
+                let checked: tauri_bridge_runtime::selfcheck::SelfCheck =
+                    serde_wasm_bindgen::from_value(result)
+                        .map_err(|e| format!("Failed to deserialize response: {}", e))?;
+                report.checked = checked.report.checked;
+                report.mismatches.extend(checked.report.mismatches);
+                Ok(report)
+            }
+            #[cfg(not(debug_assertions))]
+            {
+                Ok(tauri_bridge_runtime::selfcheck::Report::default())
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Sequential calls per resource with `serialize_on = "argument"`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(serialize_on = "project_id")]
+//! pub async fn save_project(project_id: u64, project: Project) -> Result<(), String> {}
+//! ```
+//!
+//! The backend waits for the turn of the call in the queue of the
+//! argument's value from `tauri_bridge_runtime::serial` before running the
+//! body, and passes it on when the body returns. The client doesn't change.
+//! Tauri runs sync commands on the main thread, where waiting would freeze
+//! the UI, so serialized commands must be async.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{FnArg, Ident, ItemFn, Pat};
+
+use crate::attrs::BridgeArgs;
+use crate::inject::is_injected;
+
+/// The parameter the calls are serialized on, which must be sent by the
+/// client.
+pub fn serialized_param<'a>(input: &'a ItemFn, name: &str) -> syn::Result<&'a Ident> {
+    let param = input.sig.inputs.iter().find_map(|arg| match arg {
+        FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+            Pat::Ident(pat_ident) if pat_ident.ident.unraw() == name => {
+                Some((pat_type, &pat_ident.ident))
+            }
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
+    });
+    match param {
+        Some((pat_type, _)) if is_injected(pat_type) => Err(syn::Error::new_spanned(
+            pat_type,
+            "`serialize_on` needs an argument sent by the client",
+        )),
+        Some((_, ident)) => Ok(ident),
+        None => Err(syn::Error::new(
+            Span::call_site(),
+            format!("`serialize_on`: no parameter named `{}`", name),
+        )),
+    }
+}
+
+/// Reject sync commands and arguments the calls can't be serialized on.
+pub fn check_serialize_on(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let Some(name) = &args.serialize_on else {
+        return Ok(());
+    };
+    if input.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            input.sig.fn_token,
+            "`serialize_on` waits for the call's turn, which would block the main thread Tauri \
+             runs sync commands on; make the command `async`",
+        ));
+    }
+    serialized_param(input, name).map(|_| ())
+}
+
+/// Run the command body on the call's turn.
+pub fn serialized(input: &ItemFn, name: &str, block: &TokenStream2) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let param = serialized_param(input, name).expect("checked by `check_serialize_on`");
+    quote_spanned! {call_site=>
+        {
+            let __tauri_bridge_turn = tauri_bridge_runtime::serial::turn(#name, &#param).await;
+            #block
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! `#[tauri_bridge]` on impl blocks: methods of services managed as Tauri
+//! state.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(namespace = "notes")]
+//! impl NoteStore {
+//!     pub fn count(&self, folder: String) -> usize {
+//!         self.notes.lock().unwrap().len()
+//!     }
+//! }
+//!
+//! tauri::Builder::default()
+//!     .manage(NoteStore::default())
+//!     .invoke_handler(tauri_bridge::bridge_handler![count])
+//! ```
+//!
+//! Each `pub` method taking `&self` becomes a command named after it, taking
+//! the service as `tauri::State<Self>` in place of the receiver; the client
+//! functions are generated as for free functions. The impl block itself is
+//! only compiled into the backend.
+//!
+//! Generic impl blocks are bridged with the types given by `concrete`:
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(concrete(S = SqliteStore))]
+//! impl<S: Store> Notes<S> {
+//!     pub fn count(&self) -> usize {}
+//! }
+//! ```
+//!
+//! The commands take `tauri::State<Notes<SqliteStore>>`, with the type
+//! parameters replaced by their types in the signatures as well.
+
+use proc_macro2::{Group, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{ToTokens, quote_spanned};
+use syn::{FnArg, GenericParam, ImplItem, ImplItemFn, ItemFn, ItemImpl, Pat, Type, Visibility};
+
+use crate::attrs::BridgeArgs;
+use crate::inject::{SERVICE_STATE, marked_param, strip_markers};
+
+/// Check that the impl block is an inherent impl whose type parameters are
+/// all given by `concrete`, that the bridged methods take `&self`, and that
+/// no option names a single command.
+pub fn check_service(item: &ItemImpl, args: &BridgeArgs) -> syn::Result<()> {
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "`tauri_bridge` bridges the methods of inherent impl blocks, not trait impls",
+        ));
+    }
+    for param in &item.generics.params {
+        match param {
+            GenericParam::Type(type_param)
+                if args
+                    .concrete
+                    .iter()
+                    .any(|(name, _)| *name == type_param.ident) => {}
+            GenericParam::Type(type_param) => {
+                return Err(syn::Error::new_spanned(
+                    type_param,
+                    format!(
+                        "services are looked up as `tauri::State` of one concrete type; \
+                         give the type of `{0}` with `concrete({0} = ..)`",
+                        type_param.ident
+                    ),
+                ));
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "service impl blocks can only be generic over types, given with `concrete`",
+                ));
+            }
+        }
+    }
+    for (name, _) in &args.concrete {
+        if !item
+            .generics
+            .type_params()
+            .any(|param| param.ident == *name)
+        {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!("`{}` isn't a type parameter of the impl block", name),
+            ));
+        }
+    }
+
+    let conflicts = [
+        (
+            args.rename.is_some(),
+            "`rename` names a single command; use `namespace` for a service's commands",
+        ),
+        (
+            !args.aliases.is_empty(),
+            "`alias` names a single command and can't apply to a service",
+        ),
+        (
+            args.declaration_only,
+            "`extern` doesn't apply to impl blocks",
+        ),
+        (args.non_send, "`non_send` can't look up the service state"),
+        (
+            args.test_harness,
+            "`test_harness` can't call service methods",
+        ),
+    ];
+    if let Some((_, message)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+        return Err(syn::Error::new(Span::call_site(), message));
+    }
+
+    let mut methods = bridged_methods(item).peekable();
+    if methods.peek().is_none() {
+        return Err(syn::Error::new_spanned(
+            &item.self_ty,
+            "no `pub` methods taking `self` to bridge",
+        ));
+    }
+    for method in methods {
+        let Some(FnArg::Receiver(receiver)) = method.sig.inputs.first() else {
+            continue;
+        };
+        let message = match receiver.ty.as_ref() {
+            Type::Reference(type_ref) if type_ref.mutability.is_none() => continue,
+            Type::Reference(_) => {
+                "Tauri shares managed state between calls, so bridged methods take `&self`; \
+                 use interior mutability (e.g. a `Mutex` field) instead of `&mut self`"
+            }
+            _ => "the service stays managed by Tauri, so bridged methods take `&self`",
+        };
+        return Err(syn::Error::new_spanned(receiver, message));
+    }
+    Ok(())
+}
+
+/// Reject `concrete` on a free function; commands of services pass it on.
+pub fn check_concrete(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if args.concrete.is_empty() || marked_param(input, SERVICE_STATE).is_some() {
+        return Ok(());
+    }
+    Err(syn::Error::new_spanned(
+        &input.sig.ident,
+        "`concrete` gives the type parameters of a generic service impl block",
+    ))
+}
+
+/// The impl block for the backend, without the parameter markers only the
+/// commands read.
+pub fn service_impl(item: &ItemImpl) -> ItemImpl {
+    let mut item = item.clone();
+    for impl_item in &mut item.items {
+        if let ImplItem::Fn(method) = impl_item {
+            method.sig.inputs = strip_markers(&method.sig.inputs);
+        }
+    }
+    item
+}
+
+/// The free function standing for each bridged method: the receiver is
+/// replaced by the service state, `Self` by the service type, and the type
+/// parameters by their `concrete` types.
+pub fn service_commands(item: &ItemImpl, args: &BridgeArgs) -> Vec<ItemFn> {
+    let call_site = Span::call_site();
+    let mut replacements: Vec<_> = args
+        .concrete
+        .iter()
+        .map(|(name, ty)| (name.clone(), ty.to_token_stream()))
+        .collect();
+    let self_ty: Type = syn::parse2(replace_idents(
+        item.self_ty.to_token_stream(),
+        &replacements,
+    ))
+    .expect("type stays valid with its parameters replaced");
+    let self_ty = &self_ty;
+    replacements.push((
+        syn::Ident::new("Self", call_site),
+        self_ty.to_token_stream(),
+    ));
+    let marker = syn::Ident::new(SERVICE_STATE, call_site);
+    // `#[cfg]` on the impl block gates the commands as well
+    let cfgs: Vec<_> = item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .collect();
+
+    bridged_methods(item)
+        .map(|method| {
+            let name = &method.sig.ident;
+            let mut sig = method.sig.clone();
+            sig.inputs = sig
+                .inputs
+                .into_iter()
+                .filter(|arg| matches!(arg, FnArg::Typed(_)))
+                .collect();
+            let call_args: Vec<_> = sig
+                .inputs
+                .iter()
+                .filter_map(|arg| match arg {
+                    FnArg::Typed(pat_type) => Some(match pat_type.pat.as_ref() {
+                        Pat::Ident(pat_ident) => pat_ident.ident.to_token_stream(),
+                        pat => pat.to_token_stream(),
+                    }),
+                    FnArg::Receiver(_) => None,
+                })
+                .collect();
+            sig.inputs.insert(
+                0,
+                syn::parse_quote! {
+                    #[#marker] __tauri_bridge_state: tauri::State<'_, #self_ty>
+                },
+            );
+            let sig: syn::Signature =
+                syn::parse2(replace_idents(sig.to_token_stream(), &replacements))
+                    .expect("signature stays valid with `Self` replaced");
+            let await_token = method
+                .sig
+                .asyncness
+                .map(|_| quote_spanned! {call_site=> .await });
+            let attrs = &method.attrs;
+            let vis = &method.vis;
+
+            syn::parse_quote! {
+                #(#cfgs)*
+                #(#attrs)*
+                #vis #sig {
+                    <#self_ty>::#name(&__tauri_bridge_state, #(#call_args),*) #await_token
+                }
+            }
+        })
+        .collect()
+}
+
+/// The `pub` methods with a receiver, which become commands.
+fn bridged_methods(item: &ItemImpl) -> impl Iterator<Item = &ImplItemFn> {
+    item.items.iter().filter_map(|impl_item| match impl_item {
+        ImplItem::Fn(method)
+            if matches!(method.vis, Visibility::Public(_)) && method.sig.receiver().is_some() =>
+        {
+            Some(method)
+        }
+        _ => None,
+    })
+}
+
+/// `tokens` with the identifiers of `replacements` replaced, e.g. `Self`
+/// by the service type.
+fn replace_idents(
+    tokens: TokenStream2,
+    replacements: &[(syn::Ident, TokenStream2)],
+) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .flat_map(|tree| match tree {
+            TokenTree::Ident(ident) => match replacements.iter().find(|(name, _)| *name == ident) {
+                Some((_, replacement)) => replacement.clone(),
+                None => TokenTree::Ident(ident).into(),
+            },
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(
+                    group.delimiter(),
+                    replace_idents(group.stream(), replacements),
+                );
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced).into()
+            }
+            tree => tree.into(),
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+//! Validated sessions passed to `#[session]` parameters.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn delete_note(#[session] session: Session, id: u64) -> Result<(), String> {}
+//! ```
+//!
+//! The client sends the token of the `tauri_bridge_runtime::session`
+//! provider as a hidden `tauriBridgeSession` argument. The command takes
+//! the token and the invoking `tauri::Webview` in place of the parameter,
+//! and fills it in with the managed `SessionValidator<Session>` before
+//! running the function. Like `layers`, it returns `Result<T, InvokeError>`
+//! to reject calls the validator refuses.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, ItemFn, PatType, Token};
+
+use crate::attrs::BridgeArgs;
+use crate::inject::{SESSION, caller_param, has_marker};
+use crate::layer::{Flattened, flattened};
+use crate::types::has_reference_type;
+
+/// Check the `#[session]` parameter and the options it's combined with.
+///
+/// These generate their own command signatures around the function.
+pub fn check_session(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let mut marked = input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) if has_marker(pat_type, SESSION) => Some(pat_type),
+        _ => None,
+    });
+    let Some(session) = marked.next() else {
+        return Ok(());
+    };
+    if let Some(second) = marked.next() {
+        return Err(syn::Error::new_spanned(
+            second,
+            "only one parameter can be `#[session]`",
+        ));
+    }
+    if has_reference_type(&session.ty) {
+        return Err(syn::Error::new_spanned(
+            &session.ty,
+            "`#[session]` parameters take the validated session by value",
+        ));
+    }
+
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`#[session]` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`#[session]` can't be combined with `compress`",
+        ),
+        (
+            args.encrypt,
+            "`#[session]` can't be combined with `encrypt`",
+        ),
+        (
+            args.by_value,
+            "`#[session]` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`#[session]` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.non_send,
+            "`#[session]` can't be combined with `non_send`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// The `#[tauri::command]` validating the session before running `block`.
+pub fn session_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    inputs: &Punctuated<FnArg, Token![,]>,
+    session: &PatType,
+    command_name: &str,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+    let session_pat = &session.pat;
+    let session_type = &session.ty;
+
+    // The token takes the parameter's place; the caller parameter already
+    // injects the webview
+    let mut inputs = inputs.clone();
+    for arg in inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = arg
+            && pat_type.pat == session.pat
+        {
+            *arg = syn::parse_quote!(__tauri_bridge_session: Option<String>);
+        }
+    }
+    if caller_param(input).is_none() {
+        inputs.insert(0, syn::parse_quote!(__tauri_bridge_webview: tauri::Webview));
+    }
+
+    let Flattened {
+        output,
+        ok_type,
+        respond,
+        run,
+        ..
+    } = flattened(input, block);
+
+    quote_spanned! {call_site=>
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (#inputs) -> Result<#ok_type, tauri::ipc::InvokeError> #where_clause {
+            let __tauri_bridge_validator = tauri::Manager::try_state::<
+                tauri_bridge_runtime::session::SessionValidator<#session_type>,
+            >(&__tauri_bridge_webview);
+            let #session_pat: #session_type = tauri_bridge_runtime::session::validate(
+                __tauri_bridge_validator.as_deref(),
+                #command_name,
+                __tauri_bridge_session,
+            )?;
+            let __tauri_bridge_response: #output = #run;
+            #respond
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Commands running in a sidecar process with `sidecar = "name"`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(sidecar = "pdf")]
+//! pub fn render_page(path: String, page: u32) -> Result<Vec<u8>, String> {}
+//! ```
+//!
+//! The function's body moves to a hidden implementation function, which
+//! the sidecar binary serves through `bridge_dispatch!`. The app's command
+//! only forwards the JSON arguments to the sidecar of the managed
+//! `tauri_bridge_runtime::sidecar::SidecarHost` and relays the response,
+//! so the client is unchanged. Like `layers`, it returns
+//! `Result<T, InvokeError>` to reject calls when the sidecar fails.
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, ItemFn, Pat, Token};
+
+use crate::attrs::BridgeArgs;
+use crate::inject::{is_injected, param_attrs};
+use crate::layer::{Flattened, flattened};
+use crate::types::{borrowed_param, owned_type};
+
+/// Prefix of the function holding a sidecar command's body.
+pub const SIDECAR_PREFIX: &str = "__tauri_bridge_sidecar_";
+
+/// The function holding the body of `input`, which the dispatch function
+/// of a sidecar command calls.
+pub fn sidecar_impl_name(input: &ItemFn) -> syn::Ident {
+    syn::Ident::new(
+        &format!("{}{}", SIDECAR_PREFIX, input.sig.ident.unraw()),
+        Span::call_site(),
+    )
+}
+
+/// Reject options and parameters a sidecar can't run.
+///
+/// The sidecar gets the arguments as JSON and has no webview, so only
+/// commands the dispatch function can call are supported.
+pub fn check_sidecar(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let Some(name) = &args.sidecar else {
+        return Ok(());
+    };
+    if name.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`sidecar` needs the name the sidecar is registered under",
+        ));
+    }
+
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`sidecar` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`sidecar` can't be combined with `compress`",
+        ),
+        (args.encrypt, "`sidecar` can't be combined with `encrypt`"),
+        (
+            args.incremental,
+            "`sidecar` can't be combined with `incremental`",
+        ),
+        (args.by_value, "`sidecar` can't be combined with `by_value`"),
+        (args.raw, "`sidecar` can't be combined with `raw`"),
+        (args.non_send, "`sidecar` can't be combined with `non_send`"),
+        (
+            args.layers,
+            "`layers` can't wrap commands running in a `sidecar`",
+        ),
+        (
+            args.catch_panic,
+            "`sidecar` can't be combined with `catch_panic`; a panic only takes down the sidecar",
+        ),
+        (
+            !args.aliases.is_empty(),
+            "`sidecar` can't be combined with `alias`",
+        ),
+        (
+            args.declaration_only,
+            "`sidecar` needs the backend function; it can't be combined with `extern`",
+        ),
+    ];
+    if let Some((_, message)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+        return Err(syn::Error::new(Span::call_site(), message));
+    }
+
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg
+            && is_injected(pat_type)
+        {
+            return Err(syn::Error::new_spanned(
+                pat_type,
+                "`sidecar` commands can't take injected parameters; the sidecar has no webview",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The hidden function running `block` in the sidecar, and the
+/// `#[tauri::command]` forwarding calls to it.
+pub fn sidecar_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    inputs: &Punctuated<FnArg, Token![,]>,
+    sidecar: &str,
+    command_name: &str,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+    let output = &input.sig.output;
+    let impl_name = sidecar_impl_name(input);
+
+    // The arguments are sent on as JSON, so borrowed ones are taken owned
+    let mut params = Vec::new();
+    let mut encode_args = Vec::new();
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            continue;
+        };
+        let ident = &pat_ident.ident;
+        let key = ident.unraw().to_string().to_case(Case::Camel);
+        let ty = match borrowed_param(&pat_type.ty) {
+            Some(borrowed) => owned_type(&borrowed),
+            None => {
+                let ty = &pat_type.ty;
+                quote_spanned! {call_site=> #ty }
+            }
+        };
+        let attrs = param_attrs(pat_type);
+        params.push(quote_spanned! {call_site=> #(#attrs)* #ident: #ty });
+        encode_args.push(quote_spanned! {call_site=>
+            __tauri_bridge_args.insert(
+                #key.to_string(),
+                ::serde_json::to_value(&#ident).map_err(|e| e.to_string())?,
+            );
+        });
+    }
+
+    let Flattened { ok_type, .. } = flattened(input, block);
+
+    quote_spanned! {call_site=>
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #vis #asyncness fn #impl_name #generics (#inputs) #output #where_clause #block
+
+        #(#attrs)*
+        #command_attr
+        #vis async fn #fn_name #generics (
+            __tauri_bridge_webview: tauri::Webview,
+            #(#params),*
+        ) -> Result<#ok_type, tauri::ipc::InvokeError> #where_clause {
+            let mut __tauri_bridge_args = ::serde_json::Map::new();
+            #(#encode_args)*
+            let __tauri_bridge_host = tauri::Manager::try_state::<
+                tauri_bridge_runtime::sidecar::SidecarHost,
+            >(&__tauri_bridge_webview)
+            .ok_or_else(|| {
+                format!(
+                    "`{}` runs in the `{}` sidecar, but no `SidecarHost` is managed",
+                    #command_name, #sidecar
+                )
+            })?;
+            let __tauri_bridge_response = __tauri_bridge_host
+                .call(#sidecar, #command_name, ::serde_json::Value::Object(__tauri_bridge_args))
+                .await
+                .map_err(tauri::ipc::InvokeError::from)?;
+            ::serde_json::from_value(__tauri_bridge_response).map_err(|e| e.to_string().into())
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! OpenRPC-style API description of bridged commands.
+//!
+//! Each command carries a hidden `__TAURI_BRIDGE_SPEC_<name>` constant with
+//! its method description, and `bridge_spec![...]` assembles the constants of
+//! the listed commands into a single OpenRPC document.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use serde_json::{Map, Value, json};
+use syn::ext::IdentExt;
+use syn::{FnArg, GenericArgument, ItemFn, Pat, PathArguments, ReturnType, Type};
+
+use crate::inject::is_injected;
+use crate::manifest::{doc_comment, type_to_string};
+use crate::registry::{CommandList, sibling_item_path};
+
+/// OpenRPC version of the generated document.
+pub const OPENRPC_VERSION: &str = "1.2.6";
+
+/// Prefix of the per-command spec constant.
+pub const SPEC_PREFIX: &str = "__TAURI_BRIDGE_SPEC_";
+
+/// Describe a command as an OpenRPC method object.
+pub fn method_spec(input: &ItemFn, command: &str) -> Value {
+    let params: Vec<Value> = input
+        .sig
+        .inputs
+        .iter()
+        .filter(|arg| !matches!(arg, FnArg::Typed(pat_type) if is_injected(pat_type)))
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => {
+                let name = match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => pat_ident.ident.unraw().to_string(),
+                    _ => return None,
+                };
+                Some(json!({
+                    "name": name,
+                    "required": option_inner(&pat_type.ty).is_none(),
+                    "schema": type_schema(&pat_type.ty),
+                }))
+            }
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let mut method = Map::new();
+    method.insert("name".into(), json!(command));
+    let docs = doc_comment(&input.attrs);
+    if !docs.is_empty() {
+        method.insert("description".into(), json!(docs));
+    }
+    method.insert("params".into(), json!(params));
+
+    match &input.sig.output {
+        ReturnType::Default => {
+            method.insert(
+                "result".into(),
+                json!({ "name": "result", "schema": { "type": "null" } }),
+            );
+        }
+        ReturnType::Type(_, ty) => match result_types(ty) {
+            Some((ok, err)) => {
+                method.insert(
+                    "result".into(),
+                    json!({ "name": "result", "schema": type_schema(ok) }),
+                );
+                method.insert("x-error".into(), type_schema(err));
+            }
+            None => {
+                method.insert(
+                    "result".into(),
+                    json!({ "name": "result", "schema": type_schema(ty) }),
+                );
+            }
+        },
+    }
+
+    Value::Object(method)
+}
+
+/// Generate the hidden spec constant for a command.
+pub fn generate_spec_const(input: &ItemFn, command: &str) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let const_name = syn::Ident::new(
+        &format!("{}{}", SPEC_PREFIX, input.sig.ident.unraw()),
+        call_site,
+    );
+    let spec = method_spec(input, command).to_string();
+
+    quote_spanned! {call_site=>
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        #vis const #const_name: &str = #spec;
+    }
+}
+
+/// Hash of a command's parameters and response, leaving out its docs.
+pub fn structure_hash(input: &ItemFn, command: &str) -> String {
+    let mut method = method_spec(input, command);
+    if let Value::Object(method) = &mut method {
+        method.remove("description");
+    }
+    schema_hash(&method.to_string())
+}
+
+/// FNV-1a hash of a method description, as 16 hex digits; the same as
+/// `tauri_bridge_runtime::introspect::schema_hash`.
+pub fn schema_hash(spec: &str) -> String {
+    let hash = spec.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Generate an expression evaluating to the OpenRPC document as a `String`.
+pub fn generate_spec(input: &CommandList) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let methods = input.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let method = sibling_item_path(&command.path, SPEC_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            methods.push(#method);
+        }
+    });
+
+    quote_spanned! {call_site=>
+        {
+            let mut methods: Vec<&str> = Vec::new();
+            #(#methods)*
+            format!(
+                "{{\"openrpc\":\"{}\",\"info\":{{\"title\":\"{}\",\"version\":\"{}\"}},\"methods\":[{}]}}",
+                #OPENRPC_VERSION,
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION"),
+                methods.join(","),
+            )
+        }
+    }
+}
+
+/// Map a Rust type to a JSON schema, keeping the Rust type as `x-rust-type`.
+pub fn type_schema(ty: &Type) -> Value {
+    let mut schema = match ty {
+        Type::Reference(type_ref) => return type_schema(&type_ref.elem),
+        Type::Paren(paren) => return type_schema(&paren.elem),
+        Type::Slice(slice) => json!({ "type": "array", "items": type_schema(&slice.elem) }),
+        Type::Array(array) => json!({ "type": "array", "items": type_schema(&array.elem) }),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => json!({ "type": "null" }),
+        Type::Tuple(tuple) => json!({
+            "type": "array",
+            "prefixItems": tuple.elems.iter().map(type_schema).collect::<Vec<_>>(),
+        }),
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last();
+            let ident = segment.map(|s| s.ident.to_string()).unwrap_or_default();
+            let generics = segment
+                .map(|s| generic_types(&s.arguments))
+                .unwrap_or_default();
+            match (ident.as_str(), generics.as_slice()) {
+                ("String" | "str" | "char" | "PathBuf" | "Path", _) => json!({ "type": "string" }),
+                ("bool", _) => json!({ "type": "boolean" }),
+                (
+                    "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+                    | "u128" | "usize",
+                    _,
+                ) => json!({ "type": "integer" }),
+                ("f32" | "f64", _) => json!({ "type": "number" }),
+                ("Option", [inner]) => {
+                    json!({ "oneOf": [type_schema(inner), { "type": "null" }] })
+                }
+                ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [inner]) => {
+                    json!({ "type": "array", "items": type_schema(inner) })
+                }
+                ("HashMap" | "BTreeMap", [_, value]) => {
+                    json!({ "type": "object", "additionalProperties": type_schema(value) })
+                }
+                ("Box" | "Rc" | "Arc" | "Cow", [.., inner]) => return type_schema(inner),
+                _ => json!({ "type": "object" }),
+            }
+        }
+        _ => json!({}),
+    };
+
+    if let Value::Object(map) = &mut schema {
+        map.insert("x-rust-type".into(), json!(type_to_string(ty)));
+    }
+    schema
+}
+
+/// Type arguments of a path segment, ignoring lifetimes and consts.
+fn generic_types(arguments: &PathArguments) -> Vec<&Type> {
+    match arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The inner type of an `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    match (
+        segment.ident == "Option",
+        generic_types(&segment.arguments).as_slice(),
+    ) {
+        (true, [inner]) => Some(inner),
+        _ => None,
+    }
+}
+
+/// The `T` and `E` of a `Result<T, E>`.
+fn result_types(ty: &Type) -> Option<(&Type, &Type)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    match (
+        segment.ident == "Result",
+        generic_types(&segment.arguments).as_slice(),
+    ) {
+        (true, [ok, err]) => Some((ok, err)),
+        _ => None,
+    }
+}
@@ -0,0 +1,53 @@
+//! Synchronous getters with `sync_cache = "startup"`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(sync_cache = "startup")]
+//! pub fn get_app_version() -> String {}
+//! ```
+//!
+//! The client gets an `init_<name>` function invoking the command and
+//! keeping the response in `tauri_bridge_runtime::startup`, and the plain
+//! `<name>` becomes a synchronous function returning a clone of it. The
+//! backend is unchanged.
+
+use proc_macro2::Span;
+use syn::{FnArg, ItemFn};
+
+use crate::attrs::BridgeArgs;
+use crate::inject::is_injected;
+
+/// Startup values are fetched once, so the command can't take arguments
+/// and the client's plain function can't be reached through other caches.
+pub fn check_startup_cache(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if !args.startup_cache {
+        return Ok(());
+    }
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg
+            && !is_injected(pat_type)
+        {
+            return Err(syn::Error::new_spanned(
+                pat_type,
+                "`sync_cache` commands are fetched once and can't take parameters",
+            ));
+        }
+    }
+    if let Some(param) = input.sig.generics.type_params().next() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "`sync_cache` commands can't be generic",
+        ));
+    }
+
+    let conflicts = [
+        (args.cached, "`sync_cache` can't be combined with `cached`"),
+        (
+            args.incremental,
+            "`sync_cache` can't be combined with `incremental`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,121 @@
+//! Managed state locked for the body with `BridgeState<T>` parameters.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn add_todo(todos: BridgeState<TodoList>, title: String) -> usize {
+//!     todos.items.push(title);
+//!     todos.items.len()
+//! }
+//! ```
+//!
+//! Before generating anything, the parameter is replaced by the
+//! `tauri::State` of the `BridgeState<T>`, the `Mutex<T>` the app manages,
+//! which is locked at the top of the body and bound to the parameter's name
+//! as `&mut T` until the body returns. Like a service's state, it's marked
+//! injected, so it isn't part of the client signature.
+//!
+//! Only `BridgeState<T>` and `tauri_bridge_runtime::state::BridgeState<T>`
+//! are recognized; a type of the crate named `BridgeState` is taken for the
+//! runtime's when it's written by its bare name.
+
+use proc_macro2::Span;
+use syn::{FnArg, GenericArgument, ItemFn, PathArguments, Type};
+
+use crate::attrs::BridgeArgs;
+use crate::inject::SERVICE_STATE;
+
+/// Whether the type is `BridgeState<T>`, imported or by its path in
+/// `tauri_bridge_runtime`.
+pub fn is_bridge_state(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let mut segments = type_path.path.segments.iter();
+    let Some(segment) = segments.next_back() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    let module: Vec<_> = segments
+        .map(|segment| (segment.ident.to_string(), segment.arguments.is_none()))
+        .collect();
+    let in_runtime = match module.as_slice() {
+        [] => type_path.path.leading_colon.is_none(),
+        [(krate, true), (state, true)] => krate == "tauri_bridge_runtime" && state == "state",
+        _ => false,
+    };
+    type_path.qself.is_none()
+        && in_runtime
+        && segment.ident == "BridgeState"
+        && args.args.len() == 1
+        && matches!(args.args.first(), Some(GenericArgument::Type(_)))
+}
+
+/// Reject commands that can't hold the lock for their body.
+pub fn check_bridge_state(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    let mut states = input.sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) if is_bridge_state(&pat_type.ty) => Some(pat_type),
+        _ => None,
+    });
+    let Some(state) = states.next() else {
+        return Ok(());
+    };
+    if let Some(second) = states.next() {
+        return Err(syn::Error::new_spanned(
+            second,
+            "only one parameter can be a `BridgeState`; keep the data locked together in one type",
+        ));
+    }
+    if input.sig.asyncness.is_some() {
+        return Err(syn::Error::new_spanned(
+            state,
+            "`BridgeState` is locked for the whole body, which async commands would hold \
+             across `.await`s; take a `tauri::State` and lock it between them instead",
+        ));
+    }
+    if args.test_harness {
+        return Err(syn::Error::new_spanned(
+            state,
+            "`test_harness` can't call commands taking a `BridgeState`",
+        ));
+    }
+    Ok(())
+}
+
+/// Replace a `BridgeState<T>` parameter by the managed state, locked at the
+/// top of the body.
+///
+/// With `declaration_only` there's no body, so only the signature changes.
+pub fn lock_bridge_state(input: &mut ItemFn, declaration_only: bool) {
+    let marker = syn::Ident::new(SERVICE_STATE, Span::call_site());
+    let mut locked = None;
+    for arg in input.sig.inputs.iter_mut() {
+        let FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        if !is_bridge_state(&pat_type.ty) {
+            continue;
+        }
+        let (pat, ty) = (pat_type.pat.clone(), pat_type.ty.clone());
+        *arg = syn::parse_quote! {
+            #[#marker] __tauri_bridge_locked_state: tauri::State<'_, #ty>
+        };
+        locked = Some(pat);
+    }
+    let Some(pat) = locked else {
+        return;
+    };
+    if declaration_only {
+        return;
+    }
+    let block = &input.block;
+    // A panic in another call doesn't keep the state locked for good
+    input.block = syn::parse_quote! {{
+        let mut __tauri_bridge_state_guard = __tauri_bridge_locked_state
+            .lock()
+            .unwrap_or_else(::std::sync::PoisonError::into_inner);
+        let #pat = &mut *__tauri_bridge_state_guard;
+        #block
+    }};
+}
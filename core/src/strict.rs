@@ -0,0 +1,118 @@
+//! `strict` mode: reject types known to misbehave across the IPC boundary.
+
+use syn::{FnArg, GenericArgument, ItemFn, PathArguments, ReturnType, Type};
+
+/// Check every argument and the return type of a command.
+///
+/// All offending types are reported at once. `&mut` arguments pass when
+/// `by_value` sends them back explicitly, and floats when the `floats`
+/// policy encodes NaN and infinities.
+pub fn check_strict(input: &ItemFn, by_value: bool, floats: bool) -> syn::Result<()> {
+    let mut errors: Option<syn::Error> = None;
+    let mut report = |error: syn::Error| match &mut errors {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
+
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg {
+            check_type(&pat_type.ty, by_value, floats, &mut report);
+        }
+    }
+    if let ReturnType::Type(_, ty) = &input.sig.output {
+        check_type(ty, false, floats, &mut report);
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+fn check_type(ty: &Type, allow_mut: bool, floats: bool, report: &mut impl FnMut(syn::Error)) {
+    match ty {
+        Type::Ptr(_) => report(syn::Error::new_spanned(
+            ty,
+            "strict: raw pointers can't cross the IPC boundary",
+        )),
+        Type::Reference(type_ref) => {
+            if type_ref.mutability.is_some() && !allow_mut {
+                report(syn::Error::new_spanned(
+                    ty,
+                    "strict: `&mut` arguments can't be mutated across IPC; \
+                     take the value and return the modified copy instead",
+                ));
+            }
+            check_type(&type_ref.elem, false, floats, report);
+        }
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return;
+            };
+            let name = segment.ident.to_string();
+            match name.as_str() {
+                "u128" | "i128" => report(syn::Error::new_spanned(
+                    ty,
+                    "strict: 128-bit integers don't survive the JS boundary; \
+                     send them as strings",
+                )),
+                "f32" | "f64" if !floats => report(syn::Error::new_spanned(
+                    ty,
+                    "strict: NaN and infinities serialize as `null`; \
+                     pick a policy with `floats = \"..\"` or wrap floats in a type with one",
+                )),
+                "HashMap" | "BTreeMap" => {
+                    if let Some(key) = generic_types(&segment.arguments).next()
+                        && !is_string_like(key)
+                    {
+                        report(syn::Error::new_spanned(
+                            key,
+                            "strict: map keys become JS object keys and must be strings; \
+                             send the map with `map_as = \"entries\"` instead",
+                        ));
+                    }
+                }
+                _ => {}
+            }
+            for inner in generic_types(&segment.arguments) {
+                check_type(inner, false, floats, report);
+            }
+        }
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .for_each(|elem| check_type(elem, false, floats, report)),
+        Type::Array(array) => check_type(&array.elem, false, floats, report),
+        Type::Slice(slice) => check_type(&slice.elem, false, floats, report),
+        Type::Paren(paren) => check_type(&paren.elem, false, floats, report),
+        Type::Group(group) => check_type(&group.elem, false, floats, report),
+        _ => {}
+    }
+}
+
+/// The type arguments of `Name<...>`.
+fn generic_types(arguments: &PathArguments) -> impl Iterator<Item = &Type> {
+    let args = match arguments {
+        PathArguments::AngleBracketed(args) => Some(&args.args),
+        _ => None,
+    };
+    args.into_iter().flatten().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// `String`, `&str` or `Cow<str>`.
+fn is_string_like(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(type_ref) => is_string_like(&type_ref.elem),
+        Type::Path(type_path) => match type_path.path.segments.last() {
+            Some(segment) if segment.ident == "Cow" => {
+                generic_types(&segment.arguments).any(is_string_like)
+            }
+            Some(segment) => segment.ident == "String" || segment.ident == "str",
+            None => false,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,7171 @@
+//! Unit tests for the tauri-bridge code generation.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use syn::{ItemFn, ItemImpl, Signature, Type, parse_quote};
+
+use crate::alias::{alias_client, check_aliases};
+use crate::attrs::{
+    BridgeArgs, Compression, FloatPolicy, Platform, Priority, deprecation_note, take_cfg_attrs,
+};
+use crate::backend::generate_backend;
+use crate::bootstrap::{
+    check_bootstrap, generate_bootstrap, generate_bootstrap_backend, generate_bootstrap_client,
+};
+use crate::catch_panic::check_catch_panic;
+use crate::client::generate_client;
+use crate::config::parse_crate_config;
+use crate::defaults::check_defaults;
+use crate::file::{FileArgs, check_resolver, generate_file};
+use crate::form::{FormArgs, check_form, generate_form};
+use crate::harness::{
+    BenchInput, ContractInput, check_test_harness, generate_bench, generate_contract,
+    generate_dispatch, generate_dispatch_list, generate_fuzz, has_dispatch,
+};
+use crate::include::{IncludeInput, generate_include};
+use crate::incremental::check_incremental;
+use crate::introspect::{IntrospectInput, generate_introspect};
+use crate::join::generate_join;
+use crate::layer::check_layers;
+use crate::manifest::{MANIFEST_FILE, command_manifest, type_to_string, write_manifest_entry};
+use crate::max_payload::check_max_payload;
+use crate::non_send::check_non_send;
+use crate::prefetch::{PrefetchInput, generate_prefetch_all};
+use crate::reference::{REFERENCE_FILE, example_value, render_reference};
+use crate::registry::{
+    CommandList, generate_handler, generate_handler_entry, generate_handler_step,
+    generate_registry_entry, generate_version_map,
+};
+use crate::replay::check_replayable;
+use crate::report::{convert_command_errors, report_errors};
+use crate::representation::{CrateTypes, Tagging, check_representations, collect_types};
+use crate::roundtrip::{check_proptest, generate_roundtrip_tests};
+use crate::selfcheck::{
+    check_selfcheck, generate_selfcheck, generate_selfcheck_backend, generate_selfcheck_client,
+};
+use crate::serialize_on::check_serialize_on;
+use crate::service::{check_concrete, check_service, service_commands, service_impl};
+use crate::session::check_session;
+use crate::sidecar::check_sidecar;
+use crate::spec::{
+    generate_spec, generate_spec_const, method_spec, schema_hash, structure_hash, type_schema,
+};
+use crate::startup::check_startup_cache;
+use crate::state::{check_bridge_state, is_bridge_state, lock_bridge_state};
+use crate::strict::check_strict;
+use crate::transaction::{check_transaction, generate_transact, generate_transaction};
+use crate::types::{
+    check_return_type, generate_try_deserialize_expr, get_return_type, has_reference_type,
+    transform_ref_to_lifetime,
+};
+use crate::typescript::{
+    TS_INDEX_FILE, TsClient, TsSchema, command_ts, schema_expr, ts_type, write_ts_entry,
+};
+use crate::validate::check_validate_response;
+use crate::verify_schema::check_verify_schema;
+use crate::{
+    ArgDescription, check_payload_options, check_trait_args, describe, expand, expand_error,
+    expand_metrics_command, expand_spec,
+};
+
+/// Helper to normalize whitespace for comparison
+fn normalize_tokens(tokens: &TokenStream2) -> String {
+    tokens
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Helper to check if generated code contains expected patterns
+fn contains_pattern(generated: &TokenStream2, pattern: &str) -> bool {
+    normalize_tokens(generated).contains(pattern)
+}
+
+// ==================== Basic Function Tests ====================
+
+#[test]
+fn test_basic_string_return() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Backend should have #[tauri::command]
+    assert!(contains_pattern(&backend, "# [tauri :: command]"));
+    // Backend should have the function
+    assert!(contains_pattern(&backend, "pub fn greet"));
+
+    // Client should have GreetArgs struct
+    assert!(contains_pattern(&client, "struct GreetArgs"));
+    // Client should have try_greet (returns Result)
+    assert!(contains_pattern(&client, "async fn try_greet"));
+    // Client should have greet (unwraps result, same sig as backend)
+    assert!(contains_pattern(&client, "async fn greet"));
+    // Client should use lifetime for &str
+    assert!(contains_pattern(&client, "& 'a str"));
+}
+
+#[test]
+fn test_no_args_function() {
+    let input: ItemFn = parse_quote! {
+        pub fn get_version() -> String {
+            "1.0.0".to_string()
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should NOT have args struct (no args)
+    assert!(!contains_pattern(&client, "struct GetVersionArgs"));
+    // Should have both try_ and regular functions
+    assert!(contains_pattern(&client, "async fn try_get_version"));
+    assert!(contains_pattern(&client, "async fn get_version"));
+    // An empty arguments object, so no `serde_json` either
+    assert!(contains_pattern(
+        &client,
+        "let args = serde_wasm_bindgen :: to_value (& NoArgs { })"
+    ));
+    assert!(!contains_pattern(&client, "serde_json"));
+}
+
+#[test]
+fn test_unit_return_type() {
+    let input: ItemFn = parse_quote! {
+        pub fn do_something(value: i32) {
+            println!("{}", value);
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should return Result<(), String> for try_call
+    assert!(contains_pattern(&client, "-> Result < () , String >"));
+    // Should have Ok(()) in deserialization
+    assert!(contains_pattern(&client, "Ok (())"));
+}
+
+// ==================== Multiple Arguments Tests ====================
+
+#[test]
+fn test_multiple_args() {
+    let input: ItemFn = parse_quote! {
+        pub fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have AddArgs struct with both fields
+    assert!(contains_pattern(&client, "struct AddArgs"));
+    assert!(contains_pattern(&client, "a : i32"));
+    assert!(contains_pattern(&client, "b : i32"));
+    // Function should have both params
+    assert!(contains_pattern(&client, "a : i32 , b : i32"));
+}
+
+#[test]
+fn test_mixed_ref_and_owned_args() {
+    let input: ItemFn = parse_quote! {
+        pub fn process(name: &str, count: u32, data: &str) -> String {
+            format!("{}: {} x {}", name, data, count)
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have lifetime on struct
+    assert!(contains_pattern(&client, "struct ProcessArgs < 'a >"));
+    // References should have 'a lifetime
+    assert!(contains_pattern(&client, "name : & 'a str"));
+    assert!(contains_pattern(&client, "data : & 'a str"));
+    // Owned types should remain as-is
+    assert!(contains_pattern(&client, "count : u32"));
+}
+
+#[test]
+fn test_tuple_return_type() {
+    let input: ItemFn = parse_quote! {
+        pub fn stats() -> (String, u32) {
+            (String::new(), 0)
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "async fn try_stats () -> Result < (String , u32) , String >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "serde_wasm_bindgen :: from_value :: < (String , u32) > (result)"
+    ));
+}
+
+#[test]
+fn test_nested_tuple_deserialize_expr() {
+    let return_type = quote::quote!(((i32, i32), Vec<(String, bool)>));
+
+    let expr = generate_try_deserialize_expr(
+        &return_type,
+        &quote::quote!(serde_wasm_bindgen),
+        Span::call_site(),
+    );
+
+    assert!(contains_pattern(
+        &expr,
+        "from_value :: < ((i32 , i32) , Vec < (String , bool) >) > (result)"
+    ));
+
+    // Unit is not a tuple response
+    let unit = generate_try_deserialize_expr(
+        &quote::quote!(()),
+        &quote::quote!(serde_wasm_bindgen),
+        Span::call_site(),
+    );
+    assert!(contains_pattern(&unit, "Ok (())"));
+}
+
+#[test]
+fn test_alias_and_newtype_returns_use_type_deserialize() {
+    for return_type in [
+        quote::quote!(UserId),
+        quote::quote!(Token),
+        quote::quote!(std::string::String),
+    ] {
+        let expr = generate_try_deserialize_expr(
+            &return_type,
+            &quote::quote!(serde_wasm_bindgen),
+            Span::call_site(),
+        );
+        let expected = format!("from_value :: < {} > (result)", return_type);
+        assert!(contains_pattern(&expr, &expected));
+        assert!(!contains_pattern(&expr, "as_string"));
+    }
+}
+
+// ==================== Async Function Tests ====================
+
+#[test]
+fn test_async_function() {
+    let input: ItemFn = parse_quote! {
+        pub async fn fetch_data(url: &str) -> String {
+            // async impl
+            String::new()
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+
+    // Backend should preserve async
+    assert!(contains_pattern(&backend, "pub async fn fetch_data"));
+}
+
+// ==================== Return Type Tests ====================
+
+#[test]
+fn test_bool_return() {
+    let input: ItemFn = parse_quote! {
+        pub fn is_valid(value: i32) -> bool {
+            value > 0
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Decoded through `bool`'s Deserialize impl
+    assert!(contains_pattern(
+        &client,
+        "serde_wasm_bindgen :: from_value :: < bool > (result)"
+    ));
+}
+
+#[test]
+fn test_numeric_return_i32() {
+    let input: ItemFn = parse_quote! {
+        pub fn calculate(x: i32) -> i32 {
+            x * 2
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should use serde_wasm_bindgen for numbers
+    assert!(contains_pattern(
+        &client,
+        "serde_wasm_bindgen :: from_value :: < i32 > (result)"
+    ));
+}
+
+#[test]
+fn test_complex_return_type() {
+    let input: ItemFn = parse_quote! {
+        pub fn get_user(id: u64) -> UserData {
+            UserData::default()
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should use serde_wasm_bindgen for complex types
+    assert!(contains_pattern(
+        &client,
+        "serde_wasm_bindgen :: from_value :: < UserData > (result)"
+    ));
+    // Return type should be Result<UserData, String>
+    assert!(contains_pattern(&client, "-> Result < UserData , String >"));
+}
+
+// ==================== Struct/Enum Argument Tests ====================
+
+#[test]
+fn test_struct_argument() {
+    let input: ItemFn = parse_quote! {
+        pub fn save_user(user: UserData) -> bool {
+            true
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have SaveUserArgs with user field
+    assert!(contains_pattern(&client, "struct SaveUserArgs"));
+    assert!(contains_pattern(&client, "user : UserData"));
+    // No lifetime needed (owned type)
+    assert!(!contains_pattern(&client, "SaveUserArgs < 'a >"));
+}
+
+#[test]
+fn test_struct_reference_argument() {
+    let input: ItemFn = parse_quote! {
+        pub fn validate_user(user: &UserData) -> bool {
+            true
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have lifetime
+    assert!(contains_pattern(&client, "struct ValidateUserArgs < 'a >"));
+    assert!(contains_pattern(&client, "user : & 'a UserData"));
+}
+
+#[test]
+fn test_enum_argument() {
+    let input: ItemFn = parse_quote! {
+        pub fn handle_action(action: Action) -> String {
+            String::new()
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have HandleActionArgs with action field
+    assert!(contains_pattern(&client, "struct HandleActionArgs"));
+    assert!(contains_pattern(&client, "action : Action"));
+}
+
+// ==================== Generic Type Tests ====================
+
+#[test]
+fn test_vec_argument() {
+    let input: ItemFn = parse_quote! {
+        pub fn sum_all(numbers: Vec<i32>) -> i32 {
+            numbers.iter().sum()
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have Vec<i32> in args
+    assert!(contains_pattern(&client, "numbers : Vec < i32 >"));
+}
+
+#[test]
+fn test_option_argument() {
+    let input: ItemFn = parse_quote! {
+        pub fn maybe_greet(name: Option<String>) -> String {
+            name.unwrap_or_default()
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have Option<String> in args
+    assert!(contains_pattern(&client, "name : Option < String >"));
+}
+
+#[test]
+fn test_vec_with_ref_elements() {
+    let input: ItemFn = parse_quote! {
+        pub fn join_strings(strings: &[&str]) -> String {
+            strings.join(", ")
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should have lifetime
+    assert!(contains_pattern(&client, "< 'a >"));
+}
+
+#[test]
+fn test_const_generics_carry_over_to_client() {
+    let input: ItemFn = parse_quote! {
+        pub fn checksum<const N: usize>(block: Block<N>, seed: u32) -> Digest<N>
+        where
+            Block<N>: Checked,
+        {
+            block.digest(seed)
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "struct ChecksumArgs < const N : usize > { block : Block < N > , seed : u32 }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn try_checksum < const N : usize > (block : Block < N > , seed : u32) -> Result < Digest < N > , String > where Block < N > : Checked ,"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "-> Digest < N > where Block < N > : Checked , { try_checksum :: < N > (block , seed) . await . unwrap () }"
+    ));
+}
+
+#[test]
+fn test_lifetimes_carry_over_to_client() {
+    let input: ItemFn = parse_quote! {
+        pub fn label<'b, T: Serialize>(name: &'b str, suffix: &str, tag: T) -> String {
+            format!("{}{}", name, suffix)
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Bounds stay on the functions, the struct only names its parameters
+    assert!(contains_pattern(
+        &client,
+        "struct LabelArgs < 'a , 'b , T > { name : & 'b str , suffix : & 'a str , tag : T }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn try_label < 'a , 'b , T : Serialize > (name : & 'b str , suffix : & 'a str , tag : T)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "try_label :: < T > (name , suffix , tag)"
+    ));
+
+    // A declared `'a` is reused, and unused parameters stay off the struct
+    let input: ItemFn = parse_quote! {
+        pub fn label<'a, const N: usize>(name: &'a str, suffix: &str) -> Digest<N> {}
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(
+        &client,
+        "struct LabelArgs < 'a > { name : & 'a str , suffix : & 'a str }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn try_label < 'a , const N : usize > ("
+    ));
+}
+
+// ==================== Edge Cases ====================
+
+#[test]
+fn test_private_function() {
+    let input: ItemFn = parse_quote! {
+        fn internal_helper(x: i32) -> i32 {
+            x
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should NOT have pub
+    assert!(contains_pattern(&client, "async fn try_internal_helper"));
+    assert!(contains_pattern(&client, "async fn internal_helper"));
+    assert!(!contains_pattern(
+        &client,
+        "pub async fn try_internal_helper"
+    ));
+    assert!(!contains_pattern(&client, "pub async fn internal_helper"));
+}
+
+#[test]
+fn test_function_with_attributes() {
+    let input: ItemFn = parse_quote! {
+        #[doc = "This is a documented function"]
+        #[inline]
+        pub fn documented(x: i32) -> i32 {
+            x
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+
+    // Should preserve attributes
+    assert!(contains_pattern(
+        &backend,
+        "# [doc = \"This is a documented function\"]"
+    ));
+    assert!(contains_pattern(&backend, "# [inline]"));
+}
+
+#[test]
+fn test_snake_case_to_pascal_case() {
+    let input: ItemFn = parse_quote! {
+        pub fn get_user_data(user_id: u64) -> String {
+            String::new()
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should convert get_user_data to GetUserDataArgs
+    assert!(contains_pattern(&client, "struct GetUserDataArgs"));
+}
+
+#[test]
+fn test_mutable_reference() {
+    let input: ItemFn = parse_quote! {
+        pub fn modify_buffer(buf: &mut [u8]) -> usize {
+            buf.len()
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // Should preserve mut
+    assert!(contains_pattern(&client, "& 'a mut"));
+}
+
+#[test]
+fn test_raw_identifier_params() {
+    let input: ItemFn = parse_quote! {
+        pub fn set_kind(r#type: String, r#ref: &str) {}
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn set_kind (r#type : String , r#ref : & str)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "struct SetKindArgs < 'a > { r#type : String , r#ref : & 'a str }"
+    ));
+    assert!(contains_pattern(&client, "SetKindArgs { r#type , r#ref }"));
+}
+
+#[test]
+fn test_raw_identifier_function_names() {
+    let input: ItemFn = parse_quote! {
+        pub async fn r#move(r#async: bool, to: &str) -> String {
+            todo!()
+        }
+    };
+    let args = BridgeArgs::default();
+
+    let backend = generate_backend(&input, &args);
+    let client = generate_client(&input, &args);
+    let registry = generate_registry_entry(&input, &args);
+
+    assert_eq!(args.command_name(&input), "move");
+    assert!(contains_pattern(&backend, "mod __tauri_cmd_move"));
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn r#move (r#async : bool"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "pub use __tauri_cmd_move :: r#move"
+    ));
+    assert!(contains_pattern(&client, "mod __tauri_client_move"));
+    assert!(contains_pattern(&client, "struct MoveArgs"));
+    assert!(contains_pattern(&client, "pub async fn try_move"));
+    assert!(contains_pattern(&client, "pub async fn r#move"));
+    assert!(contains_pattern(
+        &client,
+        "crate :: invoke (\"move\" , args)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub use __tauri_client_move :: { try_move , r#move }"
+    ));
+    assert!(contains_pattern(&registry, "__tauri_bridge_command_move"));
+    assert!(contains_pattern(&registry, "__TAURI_BRIDGE_VERSION_move"));
+    assert!(contains_pattern(
+        &generate_spec_const(&input, "move"),
+        "__TAURI_BRIDGE_SPEC_move"
+    ));
+    assert!(contains_pattern(
+        &generate_version_map(&parse_quote!(api::r#move)),
+        "api :: __TAURI_BRIDGE_VERSION_move"
+    ));
+}
+
+#[test]
+fn test_wrapper_commands_keep_param_names_and_attrs() {
+    let args: BridgeArgs = parse_quote!(chunked);
+    let input: ItemFn = parse_quote! {
+        pub fn lookup(#[allow(unused_mut)] r#type: String, #[target_label] target: Option<String>) -> Vec<String> {
+            todo!()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn lookup (# [allow (unused_mut)] r#type : String , target : Option < String > , __tauri_bridge_chunks"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "__tauri_bridge_lookup (r#type , target)"
+    ));
+}
+
+// ==================== Result Return Type Tests ====================
+
+#[test]
+fn test_result_return_type() {
+    let input: ItemFn = parse_quote! {
+        pub fn fallible_op(x: i32) -> Result<String, Error> {
+            Ok(x.to_string())
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    // The outer wrapper should be Result<Result<String, Error>, String>
+    assert!(contains_pattern(
+        &client,
+        "Result < Result < String , Error > , String >"
+    ));
+}
+
+// ==================== Registry Tests ====================
+
+#[test]
+fn test_registry_entry_exports_command_marker() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let registry = generate_registry_entry(&input, &BridgeArgs::default());
+
+    // Marker is exported to the crate root so duplicates collide
+    assert!(contains_pattern(&registry, "# [macro_export]"));
+    assert!(contains_pattern(
+        &registry,
+        "macro_rules ! __tauri_bridge_command_greet"
+    ));
+}
+
+#[test]
+fn test_registry_entry_uses_function_name() {
+    let input: ItemFn = parse_quote! {
+        async fn fetch_user(id: u64) -> String {
+            String::new()
+        }
+    };
+
+    let registry = generate_registry_entry(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &registry,
+        "__tauri_bridge_command_fetch__user"
+    ));
+}
+
+#[test]
+fn test_registry_markers_are_distinct_per_wire_name() {
+    let input: ItemFn = parse_quote! {
+        pub fn get() {}
+    };
+    let marker = |rename: &str| {
+        let args: BridgeArgs = parse_quote!(rename = #rename);
+        generate_registry_entry(&input, &args).to_string()
+    };
+
+    assert!(marker("users.get").contains("__tauri_bridge_command_users_dget"));
+    assert!(marker("users__get").contains("__tauri_bridge_command_users____get"));
+    assert!(marker("users_dget").contains("__tauri_bridge_command_users__dget "));
+
+    for rename in ["get-user", "", "users get"] {
+        let attr = quote::quote!(rename = #rename);
+        let error = syn::parse2::<BridgeArgs>(attr).err().unwrap();
+        assert!(error.to_string().contains("command names may only contain"));
+    }
+}
+
+// ==================== Cfg Gate Tests ====================
+
+#[test]
+fn test_default_cfg_gates() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &backend,
+        "# [cfg (not (target_arch = \"wasm32\"))]"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "# [cfg (target_arch = \"wasm32\")]"
+    ));
+}
+
+#[test]
+fn test_custom_cfg_gates() {
+    let args: BridgeArgs = parse_quote!(
+        backend_cfg(feature = "backend"),
+        client_cfg(any(target_arch = "wasm32", feature = "native-client"))
+    );
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "# [cfg (feature = \"backend\")]"
+    ));
+    assert!(!contains_pattern(&backend, "target_arch"));
+    assert!(contains_pattern(
+        &client,
+        "# [cfg (any (target_arch = \"wasm32\" , feature = \"native-client\"))]"
+    ));
+}
+
+#[test]
+fn test_unknown_option_is_rejected() {
+    let result = syn::parse_str::<BridgeArgs>("not_an_option");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_cfg_option_requires_predicate() {
+    let result = syn::parse_str::<BridgeArgs>("backend_cfg = \"backend\"");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_crate_config_defaults() {
+    let config = parse_crate_config(
+        r#"
+        [package]
+        name = "app"
+
+        [package.metadata.tauri-bridge]
+        backend_cfg = 'feature = "backend"'
+        "#,
+    )
+    .unwrap();
+
+    let args = BridgeArgs::default().with_defaults(&config);
+    assert!(normalize_tokens(&args.backend_cfg_attr()).contains("feature = \"backend\""));
+    // Client falls back to the built-in default
+    assert!(normalize_tokens(&args.client_cfg_attr()).contains("target_arch = \"wasm32\""));
+}
+
+#[test]
+fn test_attribute_overrides_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        client_cfg = 'feature = "client"'
+        "#,
+    )
+    .unwrap();
+
+    let args: BridgeArgs = parse_quote!(client_cfg(feature = "web"));
+    let args = args.with_defaults(&config);
+    assert!(normalize_tokens(&args.client_cfg_attr()).contains("feature = \"web\""));
+}
+
+#[test]
+fn test_crate_config_without_metadata() {
+    let config = parse_crate_config("[package]\nname = \"app\"\n").unwrap();
+    assert!(config.backend_cfg.is_none());
+    assert!(config.client_cfg.is_none());
+}
+
+#[test]
+fn test_crate_config_invalid_predicate() {
+    let result = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        backend_cfg = 42
+        "#,
+    );
+    assert!(result.is_err());
+}
+
+// ==================== Client Module Tests ====================
+
+#[test]
+fn test_client_reexported_from_hidden_module() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(&client, "pub mod __tauri_client_greet"));
+    assert!(contains_pattern(
+        &client,
+        "pub use __tauri_client_greet :: { try_greet , greet }"
+    ));
+}
+
+#[test]
+fn test_private_client_items_visible_to_parent() {
+    let input: ItemFn = parse_quote! {
+        fn internal_helper(x: i32) -> i32 {
+            x
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "pub (super) async fn try_internal_helper"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "use __tauri_client_internal_helper :: { try_internal_helper , internal_helper }"
+    ));
+}
+
+#[test]
+fn test_client_mod_skips_reexport() {
+    let args: BridgeArgs = parse_quote!(client_mod = crate::generated);
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(&client, "pub mod __tauri_client_greet"));
+    assert!(!contains_pattern(&client, "pub use __tauri_client_greet"));
+    assert!(contains_pattern(&client, "use crate :: generated as _"));
+}
+
+#[test]
+fn test_client_mod_requires_path() {
+    let result = syn::parse_str::<BridgeArgs>("client_mod(generated)");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_bridge_include_reexports_hidden_modules() {
+    let input: IncludeInput = parse_quote!(pub crate::api::greet, shared::add);
+
+    let include = generate_include(&input);
+
+    assert!(contains_pattern(
+        &include,
+        "pub use crate :: api :: __tauri_client_greet :: * ;"
+    ));
+    assert!(contains_pattern(
+        &include,
+        "pub use shared :: __tauri_client_add :: * ;"
+    ));
+}
+
+#[test]
+fn test_bridge_include_default_visibility() {
+    let input: IncludeInput = parse_quote!(crate::api::greet);
+
+    let include = generate_include(&input);
+
+    assert!(!contains_pattern(&include, "pub use"));
+    assert!(contains_pattern(
+        &include,
+        "use crate :: api :: __tauri_client_greet"
+    ));
+}
+
+// ==================== Declaration-Only Tests ====================
+
+#[test]
+fn test_extern_option_parsed() {
+    let args: BridgeArgs = parse_quote!(extern, client_mod = crate::generated);
+    assert!(args.declaration_only);
+    assert!(args.client_mod.is_some());
+
+    let args: BridgeArgs = parse_quote!(client_cfg(feature = "web"));
+    assert!(!args.declaration_only);
+}
+
+#[test]
+fn test_parse_declaration_without_body() {
+    let input = syn::parse::Parser::parse_str(
+        crate::parse_declaration,
+        "pub fn remote_greet(name: &str) -> String;",
+    )
+    .unwrap();
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(&client, "struct RemoteGreetArgs < 'a >"));
+    assert!(contains_pattern(&client, "async fn try_remote_greet"));
+}
+
+#[test]
+fn test_parse_declaration_rejects_body() {
+    let result = syn::parse::Parser::parse_str(
+        crate::parse_declaration,
+        "pub fn remote_greet(name: &str) -> String { String::new() }",
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_destructured_arguments_are_rejected() {
+    let tuple: ItemFn = parse_quote!(
+        pub fn f((x, y): (i32, i32)) -> i32 {
+            x + y
+        }
+    );
+    let wrapper: ItemFn = parse_quote!(
+        pub fn g(Wrapper(id): Wrapper) -> u64 {
+            id
+        }
+    );
+    let by_ref: ItemFn = parse_quote!(
+        pub fn h(ref name: String) {}
+    );
+
+    let error = crate::check_arg_patterns(&tuple).unwrap_err();
+    assert!(error.to_string().contains("must be plain identifiers"));
+    assert!(error.to_string().contains("`value: (i32 , i32)`"));
+    assert!(error.to_string().contains("`let (x , y) = value;`"));
+    assert!(crate::check_arg_patterns(&wrapper).is_err());
+    assert!(crate::check_arg_patterns(&by_ref).is_err());
+}
+
+#[test]
+fn test_mut_argument_is_plain_on_client() {
+    let input: ItemFn = parse_quote! {
+        pub fn push(mut items: Vec<u32>, item: u32) -> Vec<u32> {
+            items.push(item);
+            items
+        }
+    };
+
+    assert!(crate::check_arg_patterns(&input).is_ok());
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "struct PushArgs { items : Vec < u32 > , item : u32 }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn try_push (items : Vec < u32 > , item : u32)"
+    ));
+    assert!(!contains_pattern(&client, "mut items"));
+}
+
+// ==================== Mutable Argument Tests ====================
+
+#[test]
+fn test_mut_args_rejected_without_by_value() {
+    let input: ItemFn = parse_quote!(
+        pub fn fill(buf: &mut [u8]) {}
+    );
+    let nested: ItemFn = parse_quote!(
+        pub fn fill(buf: Option<&mut Vec<u8>>) {}
+    );
+    let args: BridgeArgs = parse_quote!(by_value);
+
+    let error = crate::check_mut_args(&input, &BridgeArgs::default()).unwrap_err();
+    assert!(error.to_string().contains("#[tauri_bridge(by_value)]"));
+    assert!(crate::check_mut_args(&input, &args).is_ok());
+    assert!(crate::check_mut_args(&nested, &args).is_err());
+}
+
+#[test]
+fn test_by_value_rejects_mut_str() {
+    let input: ItemFn = parse_quote!(
+        pub fn shout(text: &mut str) {}
+    );
+    let args: BridgeArgs = parse_quote!(by_value);
+
+    let error = crate::check_mut_args(&input, &args).unwrap_err();
+
+    assert!(error.to_string().contains("`&mut String`"));
+}
+
+#[test]
+fn test_by_value_backend_returns_modified_values() {
+    let args: BridgeArgs = parse_quote!(by_value);
+    let input: ItemFn = parse_quote! {
+        pub fn normalize(buf: &mut [u8], name: &mut String, limit: u8) -> usize {
+            buf.len()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "fn __tauri_bridge_normalize (buf : & mut [u8] , name : & mut String , limit : u8) -> usize"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "# [tauri :: command] pub fn normalize (mut buf : Vec < u8 > , mut name : String , limit : u8) -> (usize , Vec < u8 > , String)"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "let result = __tauri_bridge_normalize (& mut buf , & mut name , limit) ; (result , buf , name)"
+    ));
+}
+
+#[test]
+fn test_by_value_client_writes_back() {
+    let args: BridgeArgs = parse_quote!(by_value);
+    let input: ItemFn = parse_quote! {
+        pub async fn normalize(buf: &mut [u8], name: &mut String) {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "from_value :: < (() , Vec < u8 > , String) > (result)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "buf . clone_from_slice (& __tauri_bridge_buf) ;"
+    ));
+    assert!(contains_pattern(&client, "* name = __tauri_bridge_name ;"));
+}
+
+// ==================== Invoke Options Tests ====================
+
+#[test]
+fn test_with_options_variant() {
+    let args: BridgeArgs = parse_quote!(with_options);
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let client = generate_client(&input, &args);
+    let plain = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "pub async fn try_greet_with_options < 'a > (name : & 'a str , options : crate :: InvokeOptions ,) -> Result < String , String >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "crate :: invoke_with_options (\"greet\" , args , options) . await"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub use __tauri_client_greet :: { try_greet , greet , try_greet_with_options }"
+    ));
+    assert!(!contains_pattern(&plain, "with_options"));
+}
+
+#[test]
+fn test_with_options_from_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        with_options = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(BridgeArgs::default().with_defaults(&config).with_options);
+}
+
+// ==================== Window Routing Tests ====================
+
+#[test]
+fn test_caller_label_injected_on_backend() {
+    let input: ItemFn = parse_quote! {
+        pub fn whoami(#[caller_label] caller: String, verbose: bool) -> String {
+            caller
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn whoami (__tauri_bridge_webview : tauri :: Webview , verbose : bool) -> String { let caller : String = __tauri_bridge_webview . label () . to_string () ;"
+    ));
+    assert!(!contains_pattern(&backend, "caller_label"));
+    // The client never sends the caller label
+    assert!(contains_pattern(
+        &client,
+        "async fn try_whoami (verbose : bool)"
+    ));
+    assert!(!contains_pattern(&client, "caller"));
+}
+
+#[test]
+fn test_target_label_adds_addressed_variant() {
+    let input: ItemFn = parse_quote! {
+        pub fn focus(#[target_label] target: Option<String>, reason: u8) {}
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "struct FocusArgs { target : Option < String > , reason : u8 }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn try_focus (reason : u8)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "FocusArgs { reason , target : None }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn try_focus_to (webview_label : & str , reason : u8)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "FocusArgs { reason , target : Some (webview_label . to_string ()) }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "{ try_focus , focus , try_focus_to }"
+    ));
+}
+
+#[test]
+fn test_routing_marker_validation() {
+    let wrong_type: ItemFn = parse_quote!(
+        fn f(#[caller_label] caller: &str) {}
+    );
+    let twice: ItemFn = parse_quote!(
+        fn f(#[target_label] a: Option<String>, #[target_label] b: Option<String>) {}
+    );
+    let valid: ItemFn = parse_quote!(
+        fn f(#[caller_label] a: String, #[target_label] b: Option<String>) {}
+    );
+
+    let error = crate::inject::check_markers(&wrong_type).unwrap_err();
+    assert!(error.to_string().contains("must be `String`"));
+    assert!(crate::inject::check_markers(&twice).is_err());
+    assert!(crate::inject::check_markers(&valid).is_ok());
+}
+
+#[test]
+fn test_caller_context_injected_on_backend() {
+    let input: ItemFn = parse_quote! {
+        pub fn list_notes(ctx: CallerContext, folder: String) -> Vec<Note> {
+            todo!()
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn list_notes (__tauri_bridge_webview : tauri :: Webview , folder : String) -> Vec < Note > { let ctx : CallerContext = tauri_bridge_runtime :: caller :: CallerContext { window_label : __tauri_bridge_webview . label () . to_string () , url : __tauri_bridge_webview . url () . map (| url | url . to_string ()) . unwrap_or_default () , } ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn try_list_notes (folder : String)"
+    ));
+    assert!(!contains_pattern(&client, "CallerContext"));
+    let description = describe(quote::quote!(), quote::quote!(#input)).unwrap();
+    assert_eq!(description.args.len(), 1);
+}
+
+#[test]
+fn test_caller_context_in_wrapper_commands() {
+    let args: BridgeArgs = parse_quote!(compress = "gzip");
+    let input: ItemFn = parse_quote! {
+        pub async fn export(ctx: tauri_bridge_runtime::caller::CallerContext, rows: Vec<Row>) -> Report {
+            todo!()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn export (__tauri_bridge_webview : tauri :: Webview , __tauri_bridge_payload"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "__tauri_bridge_export (tauri_bridge_runtime :: caller :: CallerContext { window_label : __tauri_bridge_webview . label () . to_string () ,"
+    ));
+}
+
+#[test]
+fn test_caller_context_validation() {
+    let both: ItemFn = parse_quote!(
+        fn f(#[caller_label] label: String, ctx: CallerContext) {}
+    );
+    let borrowed: ItemFn = parse_quote!(
+        fn f(ctx: &CallerContext) {}
+    );
+    let valid: ItemFn = parse_quote!(
+        fn f(ctx: CallerContext, #[target_label] target: Option<String>) {}
+    );
+
+    let error = crate::inject::check_markers(&both).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("only one parameter can take the caller")
+    );
+    assert!(crate::inject::check_markers(&borrowed).is_err());
+    assert!(crate::inject::check_markers(&valid).is_ok());
+    assert!(check_test_harness(&valid, &parse_quote!(test_harness)).is_err());
+}
+
+#[test]
+fn test_manifest_skips_caller_label() {
+    let input: ItemFn = parse_quote!(
+        pub fn whoami(#[caller_label] caller: String, id: u32) {}
+    );
+
+    let entry = command_manifest(&input, "whoami", 1);
+
+    assert_eq!(entry["args"].as_array().unwrap().len(), 1);
+    assert_eq!(entry["args"][0]["name"], "id");
+}
+
+// ==================== Offline Queue Tests ====================
+
+#[test]
+fn test_offline_queue_waits_for_transport() {
+    let args: BridgeArgs = parse_quote!(offline_queue(capacity = 8, timeout_ms = 5000));
+    let input: ItemFn = parse_quote! {
+        pub fn track(event: String) {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "if ! tauri_bridge_runtime :: offline :: transport_available () { tauri_bridge_runtime :: offline :: wait_for_transport (\"track\" , 8usize , 5000u64 ,) . await ? ; }"
+    ));
+    assert!(!contains_pattern(
+        &generate_client(&input, &BridgeArgs::default()),
+        "tauri_bridge_runtime"
+    ));
+}
+
+#[test]
+fn test_offline_queue_options() {
+    let args: BridgeArgs = parse_quote!(offline_queue);
+    let queue = args.offline_queue.unwrap();
+    assert_eq!((queue.capacity, queue.timeout_ms), (32, 30_000));
+
+    assert!(syn::parse_str::<BridgeArgs>("offline_queue(capacity = 0)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("offline_queue(size = 3)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("offline_queue = 3").is_err());
+}
+
+// ==================== Circuit Breaker Tests ====================
+
+#[test]
+fn test_circuit_breaker_guards_invoke() {
+    let args: BridgeArgs = parse_quote!(circuit_breaker(threshold = 3, cooldown_ms = 500));
+    let input: ItemFn = parse_quote! {
+        pub fn sync(force: bool) -> u64 {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < u64 , tauri_bridge_runtime :: breaker :: BreakerError >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: breaker :: check (\"sync\" , 500u64) ? ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: breaker :: record (\"sync\" , 3u32 , result)"
+    ));
+    assert!(!contains_pattern(
+        &generate_client(&input, &BridgeArgs::default()),
+        "tauri_bridge_runtime"
+    ));
+}
+
+#[test]
+fn test_circuit_breaker_options() {
+    let args: BridgeArgs = parse_quote!(circuit_breaker);
+    let breaker = args.circuit_breaker.unwrap();
+    assert_eq!((breaker.threshold, breaker.cooldown_ms), (5, 10_000));
+
+    assert!(syn::parse_str::<BridgeArgs>("circuit_breaker(threshold = 0)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("circuit_breaker(retries = 3)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("circuit_breaker = 3").is_err());
+}
+
+// ==================== Priority Tests ====================
+
+#[test]
+fn test_priority_schedules_calls() {
+    let args: BridgeArgs = parse_quote!(priority = "low");
+    let input: ItemFn = parse_quote! {
+        pub fn prefetch(page: u32) {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let _permit = tauri_bridge_runtime :: scheduler :: schedule (tauri_bridge_runtime :: scheduler :: Priority :: Low ,) . await ; let args ="
+    ));
+    assert!(!contains_pattern(
+        &generate_client(&input, &BridgeArgs::default()),
+        "tauri_bridge_runtime"
+    ));
+}
+
+#[test]
+fn test_priority_options() {
+    let args: BridgeArgs = parse_quote!(priority = "normal");
+    assert_eq!(args.priority, Some(Priority::Normal));
+
+    assert!(syn::parse_str::<BridgeArgs>("priority = \"urgent\"").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("priority").is_err());
+}
+
+#[test]
+fn test_priority_crate_default() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        priority = "normal"
+        "#,
+    )
+    .unwrap();
+
+    let args = BridgeArgs::default().with_defaults(&config);
+    assert_eq!(args.priority, Some(Priority::Normal));
+
+    let args: BridgeArgs = parse_quote!(priority = "low");
+    assert_eq!(args.with_defaults(&config).priority, Some(Priority::Low));
+
+    assert!(parse_crate_config("[package.metadata.tauri-bridge]\npriority = \"high\"\n").is_err());
+}
+
+// ==================== Incremental Tests ====================
+
+#[test]
+fn test_incremental_generates_batch_variant() {
+    let args: BridgeArgs = parse_quote!(incremental);
+    let input: ItemFn = parse_quote! {
+        pub async fn search(query: String, hits: Channel<Vec<Hit>>) -> usize {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(&client, "hits : String"));
+    assert!(contains_pattern(
+        &client,
+        "pub async fn try_search (query : String) -> Result < usize , String >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub async fn try_search_incremental (query : String , on_batch : impl FnMut (Vec < Hit >) + 'static ,)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let _detach = Detach (on_batch . clone ())"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "hits : crate :: batch_channel (| _ | { })"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "serde_wasm_bindgen :: from_value :: < Vec < Hit > > (batch)"
+    ));
+    assert!(contains_pattern(&client, "hits : channel"));
+}
+
+#[test]
+fn test_channel_without_incremental_is_sent_as_is() {
+    let input: ItemFn = parse_quote! {
+        pub fn search(query: String, hits: Channel<Vec<Hit>>) -> usize {}
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(&client, "hits : Channel < Vec < Hit > >"));
+    assert!(!contains_pattern(&client, "try_search_incremental"));
+}
+
+#[test]
+fn test_incremental_needs_one_channel() {
+    let input: ItemFn = parse_quote! {
+        pub fn search(query: String) -> usize {}
+    };
+    assert!(check_incremental(&input).is_err());
+
+    let input: ItemFn = parse_quote! {
+        pub fn search(a: Channel<u32>, b: tauri::ipc::Channel<u32>) -> usize {}
+    };
+    let error = check_incremental(&input).unwrap_err();
+    assert!(error.to_string().contains("single `Channel<T>`"));
+
+    let input: ItemFn = parse_quote! {
+        pub fn search(query: String, hits: tauri::ipc::Channel<Vec<u32>>) -> usize {}
+    };
+    assert!(check_incremental(&input).is_ok());
+}
+
+// ==================== Chunked Response Tests ====================
+
+#[test]
+fn test_chunked_backend_splits_response() {
+    let args: BridgeArgs = parse_quote!(chunked(chunk_size = 4096));
+    let input: ItemFn = parse_quote! {
+        pub fn export(#[caller_label] caller: String, since: u64) -> Vec<String> {
+            vec![]
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "fn __tauri_bridge_export (caller : String , since : u64) -> Vec < String >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "pub fn export (__tauri_bridge_webview : tauri :: Webview , since : u64 , __tauri_bridge_chunks : tauri :: ipc :: Channel < String > ,) -> Result < serde_json :: Value , String >"
+    ));
+    assert!(contains_pattern(&backend, "if json . len () <= 4096usize"));
+    assert!(contains_pattern(
+        &backend,
+        "serde_json :: json ! ({ \"chunks\" : count })"
+    ));
+}
+
+#[test]
+fn test_chunked_client_reassembles_response() {
+    let args: BridgeArgs = parse_quote!(chunked);
+    let input: ItemFn = parse_quote! {
+        pub fn export() -> Vec<String> {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "# [serde (rename = \"tauriBridgeChunks\")] __tauri_bridge_chunks : String"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "ExportArgs { __tauri_bridge_chunks : chunk_channel }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let json = reassembly . collect (envelope) . await ; serde_json :: from_str :: < Vec < String > > (& json)"
+    ));
+}
+
+#[test]
+fn test_chunked_options() {
+    let args: BridgeArgs = parse_quote!(chunked);
+    assert_eq!(args.chunked.unwrap().chunk_size, 1_048_576);
+
+    assert!(syn::parse_str::<BridgeArgs>("chunked(chunk_size = 2)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("chunked(size = 4096)").is_err());
+}
+
+// ==================== Compression Tests ====================
+
+#[test]
+fn test_compress_backend_takes_payload() {
+    let args: BridgeArgs = parse_quote!(compress = "gzip", min_size = 1024);
+    let input: ItemFn = parse_quote! {
+        pub async fn export(title: &str, rows: Vec<Row>) -> Report {
+            todo!()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "struct __TauriBridgeArgs { title : String , rows : Vec < Row > }"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn export (__tauri_bridge_payload : tauri_bridge_runtime :: compress :: Payload ,) -> Result < tauri_bridge_runtime :: compress :: Payload , String >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "__tauri_bridge_export (& args . title , args . rows) . await"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "tauri_bridge_runtime :: compress :: encode (json , 1024usize)"
+    ));
+}
+
+#[test]
+fn test_compress_client_sends_payload() {
+    let args: BridgeArgs = parse_quote!(compress = "gzip");
+    let input: ItemFn = parse_quote! {
+        pub fn export() -> Report {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let json = serde_json :: to_string (& ExportArgs { })"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: compress :: encode (json , 65536usize) ?"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: compress :: decode (payload) ? ; serde_json :: from_str :: < Report > (& json)"
+    ));
+}
+
+#[test]
+fn test_compress_options() {
+    let args: BridgeArgs = parse_quote!(compress = "gzip");
+    assert_eq!(args.compress, Some(Compression::Gzip));
+    assert_eq!(args.compress_min_size(), 65_536);
+
+    assert!(syn::parse_str::<BridgeArgs>("compress = \"brotli\"").is_err());
+    let error = syn::parse_str::<BridgeArgs>("min_size = 1024")
+        .err()
+        .unwrap();
+    assert!(
+        error
+            .to_string()
+            .contains("only applies together with `compress`")
+    );
+    assert!(syn::parse_str::<BridgeArgs>("min_size = 1024, compress = \"gzip\"").is_ok());
+}
+
+// ==================== Encryption Tests ====================
+
+#[test]
+fn test_encrypt_backend_takes_sealed_payload() {
+    let args: BridgeArgs = parse_quote!(encrypt, namespace = "vault");
+    let input: ItemFn = parse_quote! {
+        pub fn unlock(name: &str, pin: u32) -> Secret {
+            todo!()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn unlock (__tauri_bridge_sealed : tauri_bridge_runtime :: encrypt :: Sealed ,) -> Result < tauri_bridge_runtime :: encrypt :: Sealed , String >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "tauri_bridge_runtime :: encrypt :: open (\"vault.unlock\" , __tauri_bridge_sealed) ?"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "tauri_bridge_runtime :: encrypt :: seal (\"vault.unlock\" , json)"
+    ));
+}
+
+#[test]
+fn test_encrypt_client_seals_payload() {
+    let args: BridgeArgs = parse_quote!(encrypt);
+    let input: ItemFn = parse_quote! {
+        pub fn unlock(pin: u32) -> Secret {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let sealed = tauri_bridge_runtime :: encrypt :: seal (\"unlock\" , json) ? ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: encrypt :: open (\"unlock\" , sealed) ? ; serde_json :: from_str :: < Secret > (& json)"
+    ));
+}
+
+#[test]
+fn test_encrypt_conflicts() {
+    let input: ItemFn = parse_quote! {
+        pub fn export(since: u64) -> Vec<u8> { Vec::new() }
+    };
+
+    for attr in [
+        quote::quote!(encrypt, by_value),
+        quote::quote!(encrypt, chunked),
+        quote::quote!(encrypt, compress = "gzip"),
+        quote::quote!(encrypt, raw),
+        quote::quote!(encrypt, raw_response),
+    ] {
+        let args: BridgeArgs = syn::parse2(attr).unwrap();
+        assert!(check_payload_options(&input, &args).is_err());
+    }
+    let args: BridgeArgs = parse_quote!(encrypt);
+    assert!(!args.raw_bytes_response(&input));
+    assert!(check_payload_options(&input, &args).is_ok());
+
+    let input: ItemFn = parse_quote! {
+        pub fn find(query: &mut String) {}
+    };
+    let error = check_payload_options(&input, &args).unwrap_err();
+    assert!(error.to_string().contains("`encrypt` only supports"));
+}
+
+// ==================== Platform Tests ====================
+
+#[test]
+fn test_platforms_gate_backend() {
+    let args: BridgeArgs = parse_quote!(platforms("desktop"));
+    let input: ItemFn = parse_quote! {
+        pub fn open_devtools() {}
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "# [cfg (all (not (target_arch = \"wasm32\") , any (target_os = \"windows\" , target_os = \"macos\" , target_os = \"linux\")))]"
+    ));
+}
+
+#[test]
+fn test_platforms_check_on_client() {
+    let args: BridgeArgs = parse_quote!(platforms("ios", "android"), backend_cfg(feature = "app"));
+    let input: ItemFn = parse_quote! {
+        pub fn scan_qr() -> String {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < String , tauri_bridge_runtime :: platform :: PlatformError >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: platform :: check (\"scan_qr\" , & [tauri_bridge_runtime :: platform :: Platform :: Ios , tauri_bridge_runtime :: platform :: Platform :: Android]) ?"
+    ));
+    assert!(contains_pattern(
+        &args.backend_cfg_attr(),
+        "# [cfg (all (feature = \"app\" , any (target_os = \"ios\" , target_os = \"android\")))]"
+    ));
+}
+
+#[test]
+fn test_platforms_options() {
+    let args: BridgeArgs = parse_quote!(platforms("mobile", "android", "linux"));
+    assert_eq!(
+        args.platforms,
+        Some(vec![Platform::Ios, Platform::Android, Platform::Linux])
+    );
+
+    assert!(syn::parse_str::<BridgeArgs>("platforms(\"web\")").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("platforms()").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("platforms = \"desktop\"").is_err());
+}
+
+// ==================== Tauri Detection Tests ====================
+
+#[test]
+fn test_detect_tauri_fails_outside_tauri() {
+    let args: BridgeArgs = parse_quote!(detect_tauri);
+    let input: ItemFn = parse_quote! {
+        pub fn get_theme() -> String {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < String , tauri_bridge_runtime :: panic :: BridgeError >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "if ! tauri_bridge_runtime :: environment :: in_tauri () { return Err (< tauri_bridge_runtime :: panic :: BridgeError as tauri_bridge_runtime :: environment :: NotInTauri > :: not_in_tauri (\"get_theme\" ,)) ; }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "result . map_err (tauri_bridge_runtime :: panic :: BridgeError :: Failed)"
+    ));
+
+    // Other options keep their error type
+    let args: BridgeArgs = parse_quote!(detect_tauri, platforms("desktop"));
+    let client = generate_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "< tauri_bridge_runtime :: platform :: PlatformError as tauri_bridge_runtime :: environment :: NotInTauri > :: not_in_tauri"
+    ));
+    assert!(!contains_pattern(&client, "BridgeError"));
+
+    let plain = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "in_tauri"));
+}
+
+#[test]
+fn test_detect_tauri_fallback_invokes_instead() {
+    let args: BridgeArgs = parse_quote!(
+        detect_tauri(fallback = crate::preview::invoke),
+        with_options
+    );
+    let input: ItemFn = parse_quote! {
+        pub fn get_theme() -> String {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(&client, "-> Result < String , String >"));
+    assert!(contains_pattern(
+        &client,
+        "let result = async move { if tauri_bridge_runtime :: environment :: in_tauri () { crate :: invoke (\"get_theme\" , args) . await } else { crate :: preview :: invoke (\"get_theme\" , args) . await } } . await ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "crate :: invoke_with_options (\"get_theme\" , args , options) . await } else { crate :: preview :: invoke (\"get_theme\" , args) . await }"
+    ));
+    assert!(!contains_pattern(&client, "NotInTauri"));
+}
+
+#[test]
+fn test_detect_tauri_options() {
+    let args: BridgeArgs = parse_quote!(detect_tauri);
+    assert!(
+        args.detect_tauri
+            .is_some_and(|detection| detection.fallback.is_none())
+    );
+
+    assert!(syn::parse_str::<BridgeArgs>("detect_tauri(fallback)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("detect_tauri(transport = preview)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("detect_tauri = true").is_err());
+
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        detect_tauri = { fallback = "crate::preview::invoke" }
+        "#,
+    )
+    .unwrap();
+    let args = BridgeArgs::default().with_defaults(&config);
+    let fallback = args.detect_tauri.and_then(|detection| detection.fallback);
+    assert!(contains_pattern(
+        &quote::ToTokens::to_token_stream(&fallback.unwrap()),
+        "crate :: preview :: invoke"
+    ));
+
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        detect_tauri = false
+        "#,
+    )
+    .unwrap();
+    assert!(config.detect_tauri.is_none());
+    assert!(
+        parse_crate_config(
+            r#"
+        [package.metadata.tauri-bridge]
+        detect_tauri = { transport = "crate::preview::invoke" }
+        "#,
+        )
+        .is_err()
+    );
+}
+
+// ==================== Demo Tests ====================
+
+#[test]
+fn test_demo_answers_outside_tauri() {
+    let args: BridgeArgs = parse_quote!(demo = "demo_impls::greet", circuit_breaker);
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: String, times: u32) -> String {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "{ if ! tauri_bridge_runtime :: environment :: in_tauri () { return Ok (demo_impls :: greet (name , times)) ; } tauri_bridge_runtime :: breaker :: check"
+    ));
+
+    // Async commands have async demo functions
+    let input: ItemFn = parse_quote! {
+        pub async fn greet(name: String) -> String {}
+    };
+    let client = generate_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "return Ok (demo_impls :: greet (name) . await) ;"
+    ));
+
+    let plain = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "in_tauri"));
+}
+
+#[test]
+fn test_demo_options() {
+    let args: BridgeArgs = parse_quote!(demo = "demo_impls::greet");
+    assert!(contains_pattern(
+        &quote::ToTokens::to_token_stream(&args.demo.unwrap()),
+        "demo_impls :: greet"
+    ));
+
+    assert!(syn::parse_str::<BridgeArgs>("demo").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("demo = \"not a path\"").is_err());
+}
+
+// ==================== Raw Response Tests ====================
+
+#[test]
+fn test_byte_response_is_raw_by_default() {
+    let args = BridgeArgs::default();
+    let input: ItemFn = parse_quote! {
+        pub fn thumbnail(id: u32) -> Result<Vec<u8>, String> {
+            todo!()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+    let client = generate_client(&input, &args);
+
+    assert!(args.raw_bytes_response(&input));
+    assert!(contains_pattern(
+        &backend,
+        "pub fn thumbnail (id : u32) -> Result < tauri :: ipc :: Response , String >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "result . map (| bytes | tauri :: ipc :: Response :: new (bytes))"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let bytes = js_sys :: Uint8Array :: new (& result) . to_vec () ; Ok (Ok (bytes))"
+    ));
+}
+
+#[test]
+fn test_raw_option_converts_bytes() {
+    let args: BridgeArgs = parse_quote!(raw);
+    let input: ItemFn = parse_quote! {
+        pub async fn blob(key: String) -> Blob {
+            todo!()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn blob (key : String) -> tauri :: ipc :: Response"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "tauri :: ipc :: Response :: new (Vec :: < u8 > :: from (bytes))"
+    ));
+    assert!(contains_pattern(&client, "Ok (< Blob > :: from (bytes))"));
+}
+
+#[test]
+fn test_byte_response_stays_json_with_payload_options() {
+    let input: ItemFn = parse_quote! {
+        pub fn dump() -> Vec<u8> {
+            todo!()
+        }
+    };
+
+    let words: ItemFn = parse_quote! {
+        pub fn words() -> Vec<u16> {
+            todo!()
+        }
+    };
+
+    // These options encode the response themselves, so the bytes stay JSON
+    // on purpose instead of falling back silently
+    let options: [BridgeArgs; 6] = [
+        parse_quote!(chunked),
+        parse_quote!(compress = "gzip"),
+        parse_quote!(encrypt),
+        parse_quote!(by_value),
+        parse_quote!(sidecar = "bin"),
+        parse_quote!(json_bytes),
+    ];
+    for args in &options {
+        assert!(!args.raw_bytes_response(&input));
+        assert!(!contains_pattern(
+            &generate_backend(&input, args),
+            "tauri :: ipc :: Response"
+        ));
+        assert!(!contains_pattern(
+            &generate_client(&input, args),
+            "js_sys :: Uint8Array"
+        ));
+    }
+    assert!(!BridgeArgs::default().raw_bytes_response(&words));
+
+    // `json_bytes` keeps the JSON arrays crate-wide, except for `raw`
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        json_bytes = true
+        "#,
+    )
+    .unwrap();
+    assert!(
+        !BridgeArgs::default()
+            .with_defaults(&config)
+            .raw_bytes_response(&input)
+    );
+    let raw: BridgeArgs = parse_quote!(raw);
+    assert!(raw.with_defaults(&config).raw_bytes_response(&input));
+    let error = check_payload_options(&input, &parse_quote!(raw, json_bytes)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`raw` can't be combined with `json_bytes`"
+    );
+}
+
+// ==================== File Transfer Tests ====================
+
+#[test]
+fn test_bridge_file_generates_commands_and_helpers() {
+    let args: FileArgs = parse_quote!(chunk_size = 1024);
+    let input: ItemFn = parse_quote! {
+        pub fn project_file(path: &str) -> Result<PathBuf, String> {
+            Ok(PathBuf::from(path))
+        }
+    };
+
+    let tokens = generate_file(&input, &args);
+
+    assert!(contains_pattern(
+        &tokens,
+        "# [tauri :: command] pub async fn project_file_read (path : String , offset : u64 ,)"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "tauri_bridge_runtime :: files :: read_chunk (& path , offset , 1024usize)"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub use __tauri_file_project_file :: { project_file_read , project_file_write } ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub async fn try_project_file_read (path : & str , mut on_progress : impl FnMut (u64 , u64) ,) -> Result < Vec < u8 > , String >"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "crate :: invoke (\"project_file_write\" , args) . await ;"
+    ));
+}
+
+#[test]
+fn test_bridge_file_awaits_async_resolver() {
+    let args: FileArgs = parse_quote!();
+    let input: ItemFn = parse_quote! {
+        async fn upload(path: &str) -> Result<PathBuf, String> {
+            todo!()
+        }
+    };
+
+    let tokens = generate_file(&input, &args);
+
+    assert!(contains_pattern(
+        &tokens,
+        "let path = upload (& path) . await . map_err"
+    ));
+    assert!(contains_pattern(&tokens, "262144usize"));
+    assert!(contains_pattern(
+        &tokens,
+        "pub (super) async fn try_upload_write"
+    ));
+}
+
+#[test]
+fn test_bridge_file_resolver_signature() {
+    let input: ItemFn = parse_quote! {
+        fn file(path: String) -> Result<PathBuf, String> { todo!() }
+    };
+    assert!(check_resolver(&input).is_err());
+
+    let input: ItemFn = parse_quote! {
+        fn file(root: &str, path: &str) -> Result<PathBuf, String> { todo!() }
+    };
+    assert!(check_resolver(&input).is_err());
+
+    assert!(syn::parse_str::<FileArgs>("chunk_size = 0").is_err());
+    assert!(syn::parse_str::<FileArgs>("strict").is_err());
+    assert!(syn::parse_str::<FileArgs>("client_cfg(feature = \"web\")").is_ok());
+}
+
+// ==================== Form Tests ====================
+
+#[test]
+fn test_bridge_form_generates_commands_and_handle() {
+    let args: FormArgs = parse_quote!(load = store::read, save = store::write);
+    let input: syn::ItemStruct = parse_quote! {
+        pub struct AppSettings {
+            pub theme: String,
+            font_size: u32,
+        }
+    };
+
+    let tokens = generate_form(input, &args);
+
+    assert!(contains_pattern(
+        &tokens,
+        "# [derive (serde :: Serialize , serde :: Deserialize)] pub struct AppSettings"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [tauri :: command] pub fn load_app_settings () -> Result < AppSettings , String > { store :: read () . map_err"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub fn save_app_settings (value : AppSettings) -> Result < () , String > { store :: write (value)"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub use __tauri_form_client_app_settings :: AppSettingsForm ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub fn set_font_size (& mut self , value : u32) { self . value . font_size = value ; self . dirty = true ; }"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "crate :: invoke (\"save_app_settings\" , args) . await ;"
+    ));
+}
+
+#[test]
+fn test_bridge_form_checks() {
+    assert!(syn::parse_str::<FormArgs>("load = read").is_err());
+    assert!(syn::parse_str::<FormArgs>("load = read, save = \"write\"").is_err());
+    assert!(syn::parse_str::<FormArgs>("load = read, save = write, strict").is_err());
+
+    let tuple: syn::ItemStruct = parse_quote!(
+        struct Settings(String);
+    );
+    let generic: syn::ItemStruct = parse_quote!(
+        struct Settings<T> {
+            value: T,
+        }
+    );
+    let clash: syn::ItemStruct = parse_quote!(
+        struct Settings {
+            save: bool,
+        }
+    );
+    let setter_clash: syn::ItemStruct = parse_quote!(
+        struct Settings {
+            x: u8,
+            set_x: u8,
+        }
+    );
+    let valid: syn::ItemStruct = parse_quote!(
+        struct Settings {
+            r#type: u8,
+        }
+    );
+
+    assert!(check_form(&tuple).is_err());
+    assert!(check_form(&generic).is_err());
+    assert!(check_form(&clash).is_err());
+    assert!(check_form(&setter_clash).is_err());
+    assert!(check_form(&valid).is_ok());
+}
+
+// ==================== Strict Mode Tests ====================
+
+#[test]
+fn test_strict_accepts_plain_types() {
+    let input: ItemFn = parse_quote! {
+        pub fn save(name: &str, tags: HashMap<String, Vec<u32>>, ids: &[u64]) -> Option<(i32, bool)> {
+            None
+        }
+    };
+    assert!(check_strict(&input, false, false).is_ok());
+}
+
+#[test]
+fn test_strict_rejects_foot_gun_types() {
+    for input in [
+        parse_quote!(
+            fn f(id: u128) {}
+        ),
+        parse_quote!(
+            fn f() -> Vec<i128> {
+                vec![]
+            }
+        ),
+        parse_quote!(
+            fn f(ratio: f64) {}
+        ),
+        parse_quote!(
+            fn f(counts: HashMap<u32, String>) {}
+        ),
+        parse_quote!(
+            fn f(ptr: *const u8) {}
+        ),
+        parse_quote!(
+            fn f(buf: &mut [u8]) {}
+        ),
+    ] {
+        let input: ItemFn = input;
+        let error = check_strict(&input, false, false).unwrap_err();
+        assert!(error.to_string().starts_with("strict:"));
+    }
+}
+
+#[test]
+fn test_strict_reports_every_offender() {
+    let input: ItemFn = parse_quote!(
+        fn f(a: u128, b: f32) -> *mut u8 {
+            todo!()
+        }
+    );
+
+    let error = check_strict(&input, false, false).unwrap_err();
+
+    assert_eq!(error.into_iter().count(), 3);
+}
+
+#[test]
+fn test_strict_from_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        strict = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(BridgeArgs::default().with_defaults(&config).strict);
+    assert!(parse_crate_config("[package.metadata.tauri-bridge]\nstrict = \"yes\"\n").is_err());
+}
+
+// ==================== Manifest Tests ====================
+
+#[test]
+fn test_manifest_entry_describes_command() {
+    let input: ItemFn = parse_quote! {
+        /// Divide two numbers.
+        /// Fails on zero.
+        pub async fn divide(a: i32, b: &i32) -> Result<i32, String> {
+            Ok(a / b)
+        }
+    };
+
+    let entry = command_manifest(&input, "divide", 1);
+
+    assert_eq!(entry["name"], "divide");
+    assert_eq!(entry["command"], "divide");
+    assert_eq!(entry["async"], true);
+    assert_eq!(entry["args"][0]["name"], "a");
+    assert_eq!(entry["args"][0]["type"], "i32");
+    assert_eq!(entry["args"][1]["type"], "&i32");
+    assert_eq!(entry["returns"], "Result<i32, String>");
+    assert_eq!(entry["error"], "String");
+    assert_eq!(entry["docs"], "Divide two numbers.\nFails on zero.");
+}
+
+#[test]
+fn test_manifest_entry_unit_return() {
+    let input: ItemFn = parse_quote! {
+        pub fn noop() {}
+    };
+
+    let entry = command_manifest(&input, "noop", 1);
+
+    assert_eq!(entry["returns"], "()");
+    assert!(entry["error"].is_null());
+    assert_eq!(entry["args"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_type_to_string_formatting() {
+    let ty: Type = parse_quote!(std::collections::HashMap<&'static str, Vec<(u8, [i32; 3])>>);
+    assert_eq!(
+        type_to_string(&ty),
+        "std::collections::HashMap<&'static str, Vec<(u8, [i32; 3])>>"
+    );
+}
+
+#[test]
+fn test_write_manifest_aggregates_commands() {
+    let dir =
+        std::env::temp_dir().join(format!("tauri-bridge-manifest-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let greet: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String { String::new() }
+    };
+    let add: ItemFn = parse_quote! {
+        pub fn add(a: i32, b: i32) -> i32 { a + b }
+    };
+    write_manifest_entry(&dir, &command_manifest(&greet, "greet", 1)).unwrap();
+    write_manifest_entry(&dir, &command_manifest(&add, "add", 1)).unwrap();
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+    let commands = manifest["commands"].as_array().unwrap();
+    assert_eq!(commands.len(), 2);
+    // Sorted by command name
+    assert_eq!(commands[0]["command"], "add");
+    assert_eq!(commands[1]["command"], "greet");
+
+    let reference = std::fs::read_to_string(dir.join(REFERENCE_FILE)).unwrap();
+    assert!(reference.find("## `add`").unwrap() < reference.find("## `greet`").unwrap());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_write_manifest_drops_commands_of_earlier_builds() {
+    let dir = std::env::temp_dir().join(format!(
+        "tauri-bridge-manifest-stale-test-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    // Left by a build that still had `remove_user`, and a half-written
+    // fragment of the old layout
+    std::fs::create_dir_all(dir.join("commands")).unwrap();
+    std::fs::write(
+        dir.join(MANIFEST_FILE),
+        r#"{"commands": [{"command": "remove_user"}]}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("commands/remove_user.json"), "{\"comm").unwrap();
+
+    let list: ItemFn = parse_quote! {
+        pub fn list_users() -> Vec<String> { Vec::new() }
+    };
+    write_manifest_entry(&dir, &command_manifest(&list, "list_users", 1)).unwrap();
+    // Expanding a command again replaces its entry
+    write_manifest_entry(&dir, &command_manifest(&list, "list_users", 2)).unwrap();
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+    let commands = manifest["commands"].as_array().unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0]["command"], "list_users");
+    assert_eq!(commands[0]["version"], 2);
+    // Temporary files are renamed into place
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|file| file.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, [REFERENCE_FILE, MANIFEST_FILE, "commands"]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_reference_documents_commands() {
+    let input: ItemFn = parse_quote! {
+        /// Rename a note.
+        pub async fn rename_note(
+            note_id: u64,
+            new_title: &str,
+            #[caller_label] caller: String,
+        ) -> Result<Note, NoteError> {
+            todo!()
+        }
+    };
+    let manifest = serde_json::json!({
+        "crate": "notes",
+        "version": "0.3.0",
+        "commands": [command_manifest(&input, "notes.rename_note", 1)],
+    });
+
+    let reference = render_reference(&manifest);
+
+    assert!(reference.starts_with("# `notes` API reference\n\nVersion 0.3.0.\n"));
+    assert!(reference.contains("## `notes.rename_note`\n\nRename a note.\n"));
+    assert!(reference.contains(
+        "```rust\nasync fn rename_note(note_id: u64, new_title: &str) -> Result<Note, NoteError>\n```"
+    ));
+    assert!(reference.contains("Errors: `NoteError`"));
+    assert!(reference.contains("\"noteId\": 0,\n  \"newTitle\": \"string\""));
+    assert!(!reference.contains("caller"));
+}
+
+#[test]
+fn test_reference_example_values() {
+    let cases: Vec<(Type, serde_json::Value)> = vec![
+        (parse_quote!(Option<u8>), serde_json::json!(null)),
+        (
+            parse_quote!(Vec<(bool, f32)>),
+            serde_json::json!([[false, 0.0]]),
+        ),
+        (
+            parse_quote!(HashMap<String, Arc<str>>),
+            serde_json::json!({ "key": "string" }),
+        ),
+        (parse_quote!(Note), serde_json::json!("<Note>")),
+    ];
+    for (ty, expected) in cases {
+        assert_eq!(example_value(&ty), expected);
+    }
+}
+
+#[test]
+fn test_crate_config_manifest_dir() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        manifest_dir = "target/bridge"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.manifest_dir.as_deref(), Some("target/bridge"));
+}
+
+// ==================== TypeScript Tests ====================
+
+#[test]
+fn test_ts_type_mappings() {
+    let cases: Vec<(Type, &str)> = vec![
+        (parse_quote!(&str), "string"),
+        (parse_quote!(u64), "number"),
+        (parse_quote!(bool), "boolean"),
+        (parse_quote!(()), "null"),
+        (parse_quote!(Option<String>), "string | null"),
+        (parse_quote!(Vec<Option<i32>>), "(number | null)[]"),
+        (parse_quote!(&[u8]), "number[]"),
+        (parse_quote!((String, f64)), "[string, number]"),
+        (
+            parse_quote!(HashMap<String, Vec<bool>>),
+            "Record<string, boolean[]>",
+        ),
+        (parse_quote!(Arc<User>), "User"),
+        (parse_quote!(Page<User>), "Page<User>"),
+    ];
+    for (ty, expected) in cases {
+        let mut named = std::collections::BTreeSet::new();
+        assert_eq!(ts_type(&ty, &mut named), expected);
+    }
+
+    let mut named = std::collections::BTreeSet::new();
+    ts_type(&parse_quote!(Vec<Page<User>>), &mut named);
+    assert_eq!(named.into_iter().collect::<Vec<_>>(), ["Page", "User"]);
+}
+
+#[test]
+fn test_plain_ts_client() {
+    let input: ItemFn = parse_quote! {
+        /// Look up a user.
+        pub async fn get_user(user_id: u32, #[caller_label] caller: String) -> Result<User, String> {
+            todo!()
+        }
+    };
+
+    let module = command_ts(
+        &input,
+        &BridgeArgs::default(),
+        "get_user",
+        TsClient::Plain,
+        None,
+    )
+    .unwrap();
+
+    assert!(module.contains("import { invoke } from \"@tauri-apps/api/core\";"));
+    assert!(module.contains("import type { User } from \"../types\";"));
+    assert!(module.contains("/**\n * Look up a user.\n */"));
+    assert!(module.contains(
+        "export function getUser(userId: number): Promise<User> {\n  return invoke(\"get_user\", { userId });\n}"
+    ));
+    assert!(!module.contains("react-query"));
+}
+
+#[test]
+fn test_react_query_ts_client() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: String) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+    let args: BridgeArgs = parse_quote!(namespace = "users");
+
+    let module = command_ts(&input, &args, "users.greet", TsClient::ReactQuery, None).unwrap();
+
+    assert!(module.contains("import { useQuery, type UseQueryOptions }"));
+    assert!(module.contains("return invoke(\"users.greet\", { name });"));
+    assert!(module.contains("export function useGreet(name: string, options?:"));
+    assert!(module.contains("queryKey: [\"users.greet\", name], queryFn: () => greet(name)"));
+}
+
+#[test]
+fn test_react_query_mutation_invalidates_keys() {
+    let input: ItemFn = parse_quote! {
+        pub fn add_member(team: String, user_id: u32) {}
+    };
+    let args: BridgeArgs = parse_quote!(invalidates("list_members", "team_size"));
+
+    let module = command_ts(&input, &args, "add_member", TsClient::ReactQuery, None).unwrap();
+
+    assert!(module.contains("import { useMutation, useQueryClient }"));
+    assert!(
+        module.contains("export function addMember(team: string, userId: number): Promise<null>")
+    );
+    assert!(module.contains(
+        "mutationFn: ({ team, userId }: { team: string; userId: number }) => addMember(team, userId)"
+    ));
+    assert!(module.contains("queryClient.invalidateQueries({ queryKey: [\"list_members\"] });"));
+    assert!(module.contains("queryClient.invalidateQueries({ queryKey: [\"team_size\"] });"));
+    assert!(!module.contains("useQuery("));
+}
+
+#[test]
+fn test_ts_client_skips_rust_only_responses() {
+    let input: ItemFn = parse_quote! {
+        pub fn export_log() -> Vec<u8> { Vec::new() }
+    };
+    let args: BridgeArgs = parse_quote!(compress = "gzip");
+    assert!(command_ts(&input, &args, "export_log", TsClient::Plain, None).is_none());
+
+    let args: BridgeArgs = parse_quote!(catch_panic);
+    assert!(command_ts(&input, &args, "export_log", TsClient::Plain, None).is_none());
+}
+
+#[test]
+fn test_schema_expr_mappings() {
+    let cases: Vec<(Type, &str, &str)> = vec![
+        (parse_quote!(&str), "z.string()", "v.string()"),
+        (
+            parse_quote!(u32),
+            "z.number().int()",
+            "v.pipe(v.number(), v.integer())",
+        ),
+        (
+            parse_quote!(Option<f64>),
+            "z.number().nullable()",
+            "v.nullable(v.number())",
+        ),
+        (
+            parse_quote!(Vec<(bool, ())>),
+            "z.array(z.tuple([z.boolean(), z.null()]))",
+            "v.array(v.tuple([v.boolean(), v.null()]))",
+        ),
+        (
+            parse_quote!(BTreeMap<String, Value>),
+            "z.record(z.string(), z.unknown())",
+            "v.record(v.string(), v.unknown())",
+        ),
+        (
+            parse_quote!(Page<User>),
+            "PageSchema(UserSchema)",
+            "PageSchema(UserSchema)",
+        ),
+    ];
+    for (ty, zod, valibot) in cases {
+        let mut named = std::collections::BTreeSet::new();
+        assert_eq!(schema_expr(&ty, TsSchema::Zod, &mut named), zod);
+        assert_eq!(schema_expr(&ty, TsSchema::Valibot, &mut named), valibot);
+    }
+}
+
+#[test]
+fn test_ts_client_validates_with_schemas() {
+    let input: ItemFn = parse_quote! {
+        pub async fn get_user(user_id: u32) -> Result<User, String> {
+            todo!()
+        }
+    };
+
+    let module = command_ts(
+        &input,
+        &BridgeArgs::default(),
+        "get_user",
+        TsClient::Plain,
+        Some(TsSchema::Zod),
+    )
+    .unwrap();
+
+    assert!(module.contains("import { z } from \"zod\";"));
+    assert!(module.contains("import { UserSchema } from \"../schemas\";"));
+    assert!(module.contains("export const getUserArgs = z.object({ userId: z.number().int() });"));
+    assert!(module.contains("export const getUserResponse = UserSchema;"));
+    assert!(module.contains(
+        "export async function getUser(userId: number): Promise<User> {\n  \
+         const args = getUserArgs.parse({ userId });\n  \
+         return getUserResponse.parse(await invoke(\"get_user\", args));\n}"
+    ));
+
+    let module = command_ts(
+        &input,
+        &BridgeArgs::default(),
+        "get_user",
+        TsClient::Plain,
+        Some(TsSchema::Valibot),
+    )
+    .unwrap();
+
+    assert!(module.contains("import * as v from \"valibot\";"));
+    assert!(module.contains("const args = v.parse(getUserArgs, { userId });"));
+    assert!(module.contains("return v.parse(getUserResponse, await invoke(\"get_user\", args));"));
+}
+
+#[test]
+fn test_write_ts_entry_regenerates_index() {
+    let dir = std::env::temp_dir().join(format!("tauri-bridge-ts-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    write_ts_entry(&dir, "greet", "// greet\n").unwrap();
+    write_ts_entry(&dir, "add", "// add\n").unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join("commands/greet.ts")).unwrap(),
+        "// greet\n"
+    );
+    let index = std::fs::read_to_string(dir.join(TS_INDEX_FILE)).unwrap();
+    assert_eq!(
+        index,
+        "// Generated by tauri-bridge; do not edit.\n\
+         export * from \"./commands/add\";\n\
+         export * from \"./commands/greet\";\n"
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_crate_config_ts_client() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        ts_dir = "../ui/src/bridge"
+        ts_client = "react-query"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.ts_dir.as_deref(), Some("../ui/src/bridge"));
+    assert_eq!(config.ts_client, TsClient::ReactQuery);
+    assert_eq!(config.ts_schemas, None);
+
+    let config = parse_crate_config("[package.metadata.tauri-bridge]\n").unwrap();
+    assert_eq!(config.ts_client, TsClient::Plain);
+    assert!(parse_crate_config("[package.metadata.tauri-bridge]\nts_client = \"vue\"\n").is_err());
+
+    let config =
+        parse_crate_config("[package.metadata.tauri-bridge]\nts_schemas = \"valibot\"\n").unwrap();
+    assert_eq!(config.ts_schemas, Some(TsSchema::Valibot));
+    assert!(parse_crate_config("[package.metadata.tauri-bridge]\nts_schemas = \"yup\"\n").is_err());
+}
+
+// ==================== Versioning Tests ====================
+
+#[test]
+fn test_versioned_command_name() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    assert_eq!(BridgeArgs::default().command_name(&input), "greet");
+
+    let args: BridgeArgs = parse_quote!(version = 1);
+    assert_eq!(args.command_name(&input), "greet");
+
+    let args: BridgeArgs = parse_quote!(version = 2);
+    assert_eq!(args.command_name(&input), "greet@2");
+
+    let args: BridgeArgs = parse_quote!(rename = "hello", version = 3);
+    assert_eq!(args.command_name(&input), "hello@3");
+    assert_eq!(args.base_command_name(&input), "hello");
+}
+
+#[test]
+fn test_version_zero_is_rejected() {
+    assert!(syn::parse_str::<BridgeArgs>("version = 0").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("version = \"2\"").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("rename = hello").is_err());
+}
+
+#[test]
+fn test_versioned_backend_and_client_use_wire_name() {
+    let args: BridgeArgs = parse_quote!(version = 2);
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+    let client = generate_client(&input, &args);
+    let registry = generate_registry_entry(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "# [tauri :: command (rename = \"greet@2\")]"
+    ));
+    // Rust names are unchanged
+    assert!(contains_pattern(&backend, "pub fn greet"));
+    assert!(contains_pattern(
+        &client,
+        "crate :: invoke (\"greet@2\" , args)"
+    ));
+    assert!(contains_pattern(
+        &registry,
+        "__tauri_bridge_command_greet_v2"
+    ));
+    assert!(contains_pattern(
+        &registry,
+        "__TAURI_BRIDGE_VERSION_greet : (& str , u32) = (\"greet\" , 2u32)"
+    ));
+}
+
+#[test]
+fn test_unversioned_backend_keeps_plain_command() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &backend,
+        "# [tauri :: command] pub fn greet"
+    ));
+}
+
+#[test]
+fn test_namespace_prefixes_wire_name() {
+    let args: BridgeArgs = parse_quote!(namespace = "admin.users", rename = "list", version = 2);
+    let input: ItemFn = parse_quote! {
+        pub fn list_users() -> Vec<u64> {
+            vec![]
+        }
+    };
+
+    assert_eq!(args.base_command_name(&input), "admin.users.list");
+    assert_eq!(args.command_name(&input), "admin.users.list@2");
+    assert!(contains_pattern(
+        &generate_backend(&input, &args),
+        "# [tauri :: command (rename = \"admin.users.list@2\")]"
+    ));
+    assert!(contains_pattern(
+        &generate_registry_entry(&input, &args),
+        "macro_rules ! __tauri_bridge_command_admin_dusers_dlist_v2"
+    ));
+
+    for namespace in ["", "users.", "a-b", "users@2"] {
+        let attr = quote::quote!(namespace = #namespace);
+        assert!(syn::parse2::<BridgeArgs>(attr).is_err(), "{}", namespace);
+    }
+}
+
+#[test]
+fn test_module_namespaces_its_commands() {
+    let expanded = normalize_tokens(&expand(
+        quote::quote! { namespace = "admin" },
+        quote::quote! {
+            pub mod users {
+                pub fn get(id: u64) -> String {
+                    format!("user {}", id)
+                }
+
+                #[tauri_bridge(rename = "list", version = 2)]
+                pub fn list_users() -> Vec<u64> {
+                    vec![]
+                }
+
+                fn helper() {}
+
+                pub mod roles {
+                    pub fn count() -> u32 {
+                        0
+                    }
+                }
+            }
+        },
+    ));
+
+    assert!(expanded.starts_with("pub mod users {"));
+    assert!(expanded.contains("# [tauri :: command (rename = \"admin.users.get\")]"));
+    assert!(expanded.contains("# [tauri :: command (rename = \"admin.users.list@2\")]"));
+    assert!(expanded.contains("# [tauri :: command (rename = \"admin.users.roles.count\")]"));
+    assert!(expanded.contains("fn helper () { }"));
+    assert!(!expanded.contains("admin.users.helper"));
+    assert!(!expanded.contains("tauri_bridge (rename"));
+    // The clients stay in the modules of their commands
+    let roles = expanded.find("pub mod roles {").unwrap();
+    assert!(expanded.find("pub async fn try_get (id : u64)").unwrap() < roles);
+    assert!(expanded.find("pub async fn try_count ()").unwrap() > roles);
+
+    let misuse = [
+        (
+            quote::quote! { rename = "users" },
+            quote::quote! { pub mod users {} },
+            "`rename` names a single command",
+        ),
+        (
+            quote::quote! {},
+            quote::quote! { pub mod users; },
+            "inline modules",
+        ),
+        (
+            quote::quote! {},
+            quote::quote! {
+                pub mod users {
+                    #[tauri_bridge(namespace = "people")]
+                    pub fn get() -> u32 {
+                        0
+                    }
+                }
+            },
+            "namespaced by their module path",
+        ),
+        (
+            quote::quote! {},
+            quote::quote! {
+                pub mod users {
+                    #[tauri_bridge(alias = "roles")]
+                    pub mod roles {}
+                }
+            },
+            "`alias` names a single command",
+        ),
+    ];
+    for (attr, item, message) in misuse {
+        let error = normalize_tokens(&expand(attr, item));
+        assert!(error.contains("compile_error"), "{}", error);
+        assert!(error.contains(message), "{}", error);
+    }
+}
+
+#[test]
+fn test_bridge_version_map_collects_commands() {
+    let input: CommandList = parse_quote!(greet, api::add);
+
+    let map = generate_version_map(&input);
+
+    assert!(contains_pattern(&map, "__TAURI_BRIDGE_VERSION_greet"));
+    assert!(contains_pattern(&map, "api :: __TAURI_BRIDGE_VERSION_add"));
+}
+
+#[test]
+fn test_item_cfg_gates_both_halves() {
+    let mut input: ItemFn = parse_quote! {
+        #[cfg(feature = "pro")]
+        pub fn pro_report() -> String {
+            todo!()
+        }
+    };
+    let args = BridgeArgs {
+        item_cfgs: take_cfg_attrs(&mut input.attrs).unwrap(),
+        ..Default::default()
+    };
+
+    let backend = generate_backend(&input, &args);
+    let client = generate_client(&input, &args);
+    let registry = generate_registry_entry(&input, &args);
+
+    assert!(input.attrs.is_empty());
+    assert!(contains_pattern(
+        &backend,
+        "# [cfg (all (not (target_arch = \"wasm32\") , feature = \"pro\"))]"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "# [cfg (all (target_arch = \"wasm32\" , feature = \"pro\"))]"
+    ));
+    assert!(contains_pattern(
+        &registry,
+        "# [cfg (feature = \"pro\")] # [doc (hidden)] # [macro_export]"
+    ));
+    assert!(contains_pattern(
+        &registry,
+        "# [cfg (feature = \"pro\")] # [doc (hidden)] # [allow (non_upper_case_globals)] pub const __TAURI_BRIDGE_VERSION_pro_report"
+    ));
+}
+
+#[test]
+fn test_lists_gate_entries_with_cfg() {
+    let input: CommandList = parse_quote!(
+        greet,
+        #[cfg(feature = "pro")]
+        api::pro_report
+    );
+    let include: IncludeInput = parse_quote!(
+        #[cfg(feature = "pro")]
+        api::pro_report
+    );
+
+    let map = generate_version_map(&input);
+    let spec = generate_spec(&input);
+
+    assert!(contains_pattern(
+        &map,
+        "# [cfg (feature = \"pro\")] { let (name , version) = api :: __TAURI_BRIDGE_VERSION_pro_report ;"
+    ));
+    assert!(contains_pattern(
+        &spec,
+        "# [cfg (feature = \"pro\")] methods . push (api :: __TAURI_BRIDGE_SPEC_pro_report) ;"
+    ));
+    assert!(contains_pattern(
+        &generate_include(&include),
+        "# [cfg (feature = \"pro\")] use api :: __tauri_client_pro_report :: * ;"
+    ));
+    assert!(syn::parse_str::<CommandList>("#[allow(unused)] greet").is_err());
+}
+
+// ==================== Feature Tests ====================
+
+#[test]
+fn test_feature_gates_both_halves() {
+    let args: BridgeArgs = parse_quote!(feature = "experimental-search");
+    let input: ItemFn = parse_quote! {
+        pub fn search(query: &str) -> Vec<String> {
+            vec![query.to_string()]
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+    let client = generate_client(&input, &args);
+    let registry = generate_registry_entry(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "# [cfg (all (not (target_arch = \"wasm32\") , feature = \"experimental-search\"))] # [allow (deprecated)] mod __tauri_cmd_search"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "# [cfg (all (target_arch = \"wasm32\" , feature = \"experimental-search\"))]"
+    ));
+    assert!(contains_pattern(
+        &registry,
+        "# [cfg (feature = \"experimental-search\")] # [doc (hidden)] # [macro_export] macro_rules ! __tauri_bridge_command_search"
+    ));
+    assert!(syn::parse_str::<BridgeArgs>("feature(experimental)").is_err());
+}
+
+#[test]
+fn test_handler_entry_follows_feature() {
+    let input: ItemFn = parse_quote! {
+        pub fn search(query: &str) -> Vec<String> {
+            vec![query.to_string()]
+        }
+    };
+
+    let gated = generate_handler_entry(&input, &parse_quote!(feature = "experimental-search"));
+    let plain = generate_handler_entry(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &gated,
+        "# [cfg (not (target_arch = \"wasm32\"))] # [cfg (feature = \"experimental-search\")] # [doc (hidden)] # [macro_export] macro_rules ! __tauri_bridge_handler__search { ($ ($ state : tt) *) => { :: tauri_bridge :: __bridge_handler_step ! { include $ ($ state) * } } ; }"
+    ));
+    assert!(contains_pattern(
+        &gated,
+        "# [cfg (not (feature = \"experimental-search\"))] # [doc (hidden)] # [macro_export] macro_rules ! __tauri_bridge_handler__search { ($ ($ state : tt) *) => { :: tauri_bridge :: __bridge_handler_step ! { skip $ ($ state) * } } ; }"
+    ));
+    assert!(contains_pattern(
+        &gated,
+        "pub use __tauri_handler_search :: __tauri_bridge_handler_search ;"
+    ));
+    assert!(contains_pattern(&plain, "include"));
+    assert!(!contains_pattern(&plain, "skip"));
+}
+
+#[test]
+fn test_bridge_handler_steps() {
+    let start = generate_handler(parse_quote!(greet, api::search));
+    let step = generate_handler_step(parse_quote!(
+        include [greet,] {api::search}
+        #[cfg(feature = "pro")] api::export,
+        api::report
+    ));
+    let last = generate_handler_step(parse_quote!(skip [greet,] {api::report}));
+
+    assert_eq!(
+        normalize_tokens(&start),
+        "__tauri_bridge_handler_greet ! { [] { greet } api :: search }"
+    );
+    assert_eq!(
+        normalize_tokens(&step),
+        "api :: __tauri_bridge_handler_report ! { [greet , api :: search , # [cfg (feature = \"pro\")] api :: export ,] { api :: report } }"
+    );
+    assert_eq!(
+        normalize_tokens(&last),
+        ":: tauri :: generate_handler ! [greet ,]"
+    );
+}
+
+// ==================== Parameter Default Tests ====================
+
+#[test]
+fn test_defaults_add_short_client_functions() {
+    let input: ItemFn = parse_quote! {
+        pub fn search(
+            query: String,
+            #[bridge(default)] tags: Option<Vec<String>>,
+            #[bridge(default = "20")] limit: u32,
+        ) -> Vec<String> {
+            vec![query]
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+    let backend = generate_backend(&input, &BridgeArgs::default());
+
+    assert!(check_defaults(&input).is_ok());
+    assert!(contains_pattern(
+        &client,
+        "async fn try_search_with_defaults (query : String) -> Result < Vec < String > , String > { try_search (query , < Option < Vec < String > > as :: core :: default :: Default > :: default () , { let value : u32 = 20 ; value }) . await }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "async fn search_with_defaults (query : String) -> Vec < String >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "use __tauri_client_search :: { try_search , search , try_search_with_defaults , search_with_defaults } ;"
+    ));
+    assert!(!contains_pattern(&backend, "bridge"));
+}
+
+#[test]
+fn test_defaults_must_trail_owned_params() {
+    let leading: ItemFn = parse_quote! {
+        pub fn search(#[bridge(default)] limit: u32, query: String) {}
+    };
+    let borrowed: ItemFn = parse_quote! {
+        pub fn search(#[bridge(default)] query: &str) {}
+    };
+    let unknown: ItemFn = parse_quote! {
+        pub fn search(#[bridge(fallback)] limit: u32) {}
+    };
+    let around_label: ItemFn = parse_quote! {
+        pub fn focus(id: u64, #[bridge(default)] raise: bool, #[caller_label] caller: String) {}
+    };
+
+    assert!(check_defaults(&leading).is_err());
+    assert!(check_defaults(&borrowed).is_err());
+    assert!(check_defaults(&unknown).is_err());
+    assert!(check_defaults(&around_label).is_ok());
+}
+
+// ==================== Alias Tests ====================
+
+#[test]
+fn test_alias_registers_second_command() {
+    let args: BridgeArgs = parse_quote!(rename = "load_note", alias = "get_note", alias = "note");
+    let input: ItemFn = parse_quote! {
+        pub fn load_note(id: u64) -> String {
+            id.to_string()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+    let registry = generate_registry_entry(&input, &args);
+    let handler = generate_handler_entry(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "# [tauri :: command (rename = \"get_note\")] pub fn __tauri_alias_load_note_0 (id : u64) -> String"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "# [tauri :: command (rename = \"note\")] pub fn __tauri_alias_load_note_1"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "# [doc (hidden)] pub use __tauri_cmd_load_note :: __tauri_alias_load_note_1 ;"
+    ));
+    assert!(contains_pattern(
+        &registry,
+        "macro_rules ! __tauri_bridge_command_get__note"
+    ));
+    assert!(contains_pattern(
+        &handler,
+        "__bridge_handler_step ! { include (__tauri_alias_load_note_0 , __tauri_alias_load_note_1) $ ($ state) * }"
+    ));
+    assert!(check_aliases(&input, &args).is_ok());
+}
+
+#[test]
+fn test_bridge_handler_keeps_alias_commands() {
+    let step = generate_handler_step(parse_quote!(
+        include(__tauri_alias_load_note_0) [greet,] {api::load_note}
+    ));
+
+    assert_eq!(
+        normalize_tokens(&step),
+        ":: tauri :: generate_handler ! [greet , api :: load_note , api :: __tauri_alias_load_note_0 ,]"
+    );
+}
+
+#[test]
+fn test_alias_client_is_deprecated() {
+    let args: BridgeArgs = parse_quote!(alias = "get_note", alias_client, namespace = "notes");
+    let input: ItemFn = parse_quote! {
+        pub fn load_note(id: u64) -> String {
+            id.to_string()
+        }
+    };
+
+    let (alias_input, alias_args) = alias_client(&input, &args, "get_note");
+    let client = generate_client(&alias_input, &alias_args);
+
+    assert_eq!(alias_input.sig.ident, "get_note");
+    assert_eq!(alias_args.command_name(&alias_input), "notes.load_note");
+    assert!(contains_pattern(
+        &client,
+        "# [deprecated (note = \"renamed to `load_note`\")] pub async fn get_note"
+    ));
+}
+
+#[test]
+fn test_alias_rejections() {
+    let input: ItemFn = parse_quote! {
+        pub fn load_note(id: u64) -> String {
+            id.to_string()
+        }
+    };
+
+    let own_name: BridgeArgs = parse_quote!(alias = "load_note");
+    let dotted_client: BridgeArgs = parse_quote!(alias = "notes.get", alias_client);
+    let no_alias: BridgeArgs = parse_quote!(alias_client);
+
+    assert!(check_aliases(&input, &own_name).is_err());
+    assert!(check_aliases(&input, &dotted_client).is_err());
+    assert!(check_aliases(&input, &no_alias).is_err());
+    assert!(check_aliases(&input, &parse_quote!(alias = "notes.get")).is_ok());
+    for alias in ["", "get-note", "get note"] {
+        let attr = quote::quote!(alias = #alias);
+        assert!(syn::parse2::<BridgeArgs>(attr).is_err(), "{}", alias);
+    }
+}
+
+// ==================== Test Harness Tests ====================
+
+#[test]
+fn test_dispatch_decodes_camel_case_args() {
+    let args: BridgeArgs = parse_quote!(test_harness);
+    let input: ItemFn = parse_quote! {
+        pub async fn find_user(user_id: u64, name_hint: &str) -> Result<User, String> {
+            todo!()
+        }
+    };
+
+    let dispatch = generate_dispatch(&input, &args);
+
+    let cfg = if cfg!(feature = "bench") {
+        "# [cfg (not (target_arch = \"wasm32\"))]"
+    } else {
+        "# [cfg (all (not (target_arch = \"wasm32\") , test))]"
+    };
+    assert!(contains_pattern(
+        &dispatch,
+        &format!(
+            "{} # [doc (hidden)] # [allow (dead_code)] pub const __TAURI_BRIDGE_COMMAND_find_user : & str = \"find_user\" ;",
+            cfg
+        )
+    ));
+    assert!(contains_pattern(
+        &dispatch,
+        &format!(
+            "{} # [doc (hidden)] # [allow (deprecated , dead_code)] pub async fn __tauri_bridge_dispatch_find_user",
+            cfg
+        )
+    ));
+    assert!(contains_pattern(
+        &dispatch,
+        "let user_id : u64 = match :: serde_json :: from_value (__tauri_bridge_args . get (\"userId\")"
+    ));
+    assert!(contains_pattern(
+        &dispatch,
+        "let name_hint : String = match :: serde_json :: from_value (__tauri_bridge_args . get (\"nameHint\")"
+    ));
+    assert!(contains_pattern(
+        &dispatch,
+        "let __tauri_bridge_result = find_user (user_id , & name_hint) . await ;"
+    ));
+    assert!(contains_pattern(&dispatch, "Err (error) => Err ("));
+}
+
+#[test]
+fn test_test_harness_rejects_unsupported_commands() {
+    let input: ItemFn = parse_quote! {
+        pub fn thumbnail(id: u32) -> Vec<u8> {
+            vec![]
+        }
+    };
+    let labelled: ItemFn = parse_quote! {
+        pub fn whoami(#[caller_label] label: String) -> String {
+            label
+        }
+    };
+
+    assert!(check_test_harness(&input, &parse_quote!(test_harness)).is_err());
+    assert!(check_test_harness(&labelled, &parse_quote!(test_harness)).is_err());
+    assert!(check_test_harness(&labelled, &parse_quote!(test_harness, by_value)).is_err());
+    assert!(
+        check_test_harness(
+            &parse_quote!(
+                pub fn ping() {}
+            ),
+            &parse_quote!(test_harness)
+        )
+        .is_ok()
+    );
+}
+
+#[test]
+fn test_dispatch_list_tries_each_command() {
+    let input: CommandList = parse_quote!(
+        greet,
+        #[cfg(feature = "pro")]
+        api::export
+    );
+
+    let list = generate_dispatch_list(&input);
+
+    assert!(contains_pattern(
+        &list,
+        "{ if let Some (response) = __tauri_bridge_dispatch_greet (command , args . clone ()) . await { return response ; } }"
+    ));
+    assert!(contains_pattern(
+        &list,
+        "# [cfg (feature = \"pro\")] { if let Some (response) = api :: __tauri_bridge_dispatch_export"
+    ));
+    assert!(contains_pattern(
+        &list,
+        "Err (:: serde_json :: Value :: String (format ! (\"command {} not found\" , command)))"
+    ));
+}
+
+#[test]
+fn test_bench_measures_each_command() {
+    let input: BenchInput = parse_quote!(
+        100;
+        greet => json!({ "name": "Ada" }),
+        #[cfg(feature = "pro")]
+        api::export => args.clone(),
+    );
+
+    let bench = generate_bench(&input);
+
+    assert!(contains_pattern(&bench, "let iterations : u32 = 100 ;"));
+    assert!(contains_pattern(
+        &bench,
+        "let args : :: serde_json :: Value = json ! ({ \"name\" : \"Ada\" }) ;"
+    ));
+    assert!(contains_pattern(
+        &bench,
+        ":: tauri_bridge_runtime :: bench :: measure (__TAURI_BRIDGE_COMMAND_greet , & args , iterations , | args | { __tauri_bridge_dispatch_greet (__TAURI_BRIDGE_COMMAND_greet , args) }) . await ?"
+    ));
+    assert!(contains_pattern(
+        &bench,
+        "# [cfg (feature = \"pro\")] { let args : :: serde_json :: Value = args . clone () ;"
+    ));
+    assert!(contains_pattern(
+        &bench,
+        "api :: __tauri_bridge_dispatch_export (api :: __TAURI_BRIDGE_COMMAND_export , args)"
+    ));
+}
+
+#[test]
+fn test_contract_replays_recording_through_dispatch() {
+    let input: ContractInput = parse_quote!(
+        fixtures::session();
+        greet,
+        #[cfg(feature = "pro")]
+        api::export,
+    );
+
+    let contract = generate_contract(&input);
+
+    assert!(contains_pattern(
+        &contract,
+        "let recording : & :: tauri_bridge_runtime :: replay :: Recording = & fixtures :: session () ;"
+    ));
+    assert!(contains_pattern(
+        &contract,
+        ":: tauri_bridge_runtime :: contract :: verify (recording , { async fn __tauri_bridge_dispatch ("
+    ));
+    assert!(contains_pattern(
+        &contract,
+        "# [cfg (feature = \"pro\")] { if let Some (response) = api :: __tauri_bridge_dispatch_export"
+    ));
+
+    assert!(syn::parse_str::<ContractInput>("greet, api::export").is_err());
+}
+
+#[test]
+fn test_fuzz_feeds_listed_commands() {
+    let input: CommandList = parse_quote!(
+        greet,
+        #[cfg(feature = "pro")]
+        api::export
+    );
+
+    let fuzz = generate_fuzz(&input);
+
+    assert!(contains_pattern(
+        &fuzz,
+        "fn __tauri_bridge_fuzz (data : & [u8]) {"
+    ));
+    assert!(contains_pattern(
+        &fuzz,
+        "commands . push (__TAURI_BRIDGE_COMMAND_greet) ; # [cfg (feature = \"pro\")] commands . push (api :: __TAURI_BRIDGE_COMMAND_export) ;"
+    ));
+    assert!(contains_pattern(
+        &fuzz,
+        ":: tauri_bridge_runtime :: fuzz :: run (data , & commands , { async fn __tauri_bridge_dispatch"
+    ));
+}
+
+#[test]
+fn test_fuzz_feature_dispatches_every_command_it_can() {
+    let input: ItemFn = parse_quote! { pub fn greet(name: String) -> String { name } };
+    assert!(has_dispatch(&input, &parse_quote!(test_harness)));
+    assert!(has_dispatch(&input, &parse_quote!(sidecar = "text")));
+    assert_eq!(
+        has_dispatch(&input, &BridgeArgs::default()),
+        cfg!(feature = "fuzz")
+    );
+
+    // Commands the dispatch function can't call are left out
+    assert!(!has_dispatch(&input, &parse_quote!(chunked)));
+    assert!(!has_dispatch(&input, &parse_quote!(layers)));
+    let input: ItemFn = parse_quote! { pub fn greet(#[caller_label] caller: String) {} };
+    assert!(!has_dispatch(&input, &BridgeArgs::default()));
+    let input: ItemFn = parse_quote! { pub fn greet(names: Vec<&str>) {} };
+    assert!(!has_dispatch(&input, &BridgeArgs::default()));
+}
+
+// ==================== Debug Log Tests ====================
+
+#[test]
+fn test_debug_log_client_logs_request_and_response() {
+    let args: BridgeArgs = parse_quote!(debug_log);
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: String, times: u32) -> String {
+            name.repeat(times as usize)
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "# [cfg_attr (debug_assertions , derive (Debug))] # [derive (serde :: Serialize , serde :: Deserialize)]"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let __tauri_bridge_args = GreetArgs { name , times } ; # [cfg (debug_assertions)] tauri_bridge_runtime :: debug_log :: request (\"greet\" , Some (& __tauri_bridge_args) ,) ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let response : Result < String , String > = async { let result = crate :: invoke (\"greet\" , args) . await ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "# [cfg (debug_assertions)] tauri_bridge_runtime :: debug_log :: response (\"greet\" , started_ms , & response) ; response"
+    ));
+
+    let plain = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "debug_log"));
+}
+
+#[test]
+fn test_debug_log_backend_times_body() {
+    let args: BridgeArgs = parse_quote!(debug_log, rename = "divide");
+    let sync_fn: ItemFn = parse_quote! {
+        pub fn checked_div(a: i32, b: i32) -> Result<i32, String> {
+            Ok(a / b)
+        }
+    };
+    let async_fn: ItemFn = parse_quote! {
+        pub async fn ping() {}
+    };
+
+    let sync_backend = generate_backend(&sync_fn, &args);
+    let async_backend = generate_backend(&async_fn, &args);
+
+    assert!(contains_pattern(
+        &sync_backend,
+        "let __tauri_bridge_response : Result < i32 , String > = (|| -> Result < i32 , String > { Ok (a / b) }) () ;"
+    ));
+    assert!(contains_pattern(
+        &sync_backend,
+        "eprintln ! (\"[tauri-bridge] {} handled in {:.1?}\\n{:#?}\" , \"divide\" , __tauri_bridge_started . elapsed () , __tauri_bridge_response) ;"
+    ));
+    assert!(contains_pattern(
+        &async_backend,
+        "let __tauri_bridge_response : () = async { } . await ;"
+    ));
+}
+
+#[test]
+fn test_debug_log_from_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        debug_log = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(BridgeArgs::default().with_defaults(&config).debug_log);
+}
+
+// ==================== Error Translation Tests ====================
+
+#[test]
+fn test_translate_errors_client_translates_last() {
+    let args: BridgeArgs = parse_quote!(translate_errors, catch_panic);
+    let input: ItemFn = parse_quote! {
+        pub fn get_user(id: u64) -> Option<String> {
+            None
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let result : Result < Option < String > , tauri_bridge_runtime :: panic :: BridgeError > = async { let result : Result < tauri_bridge_runtime :: panic :: Caught < Option < String > > , String > = async {"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "result . map_err (| error | { tauri_bridge_runtime :: messages :: Translate :: translate (error , \"get_user\") })"
+    ));
+
+    let plain = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "messages :: Translate"));
+}
+
+#[test]
+fn test_translate_errors_from_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        translate_errors = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(
+        BridgeArgs::default()
+            .with_defaults(&config)
+            .translate_errors
+    );
+}
+
+// ==================== Slow Call Tests ====================
+
+#[test]
+fn test_log_slow_client_times_invoke() {
+    let args: BridgeArgs = parse_quote!(log_slow);
+    let input: ItemFn = parse_quote! {
+        pub fn search_notes(query: String) -> Vec<String> {
+            vec![]
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let __tauri_bridge_started_ms = tauri_bridge_runtime :: clock :: now_ms () ; let __tauri_bridge_sent = args . clone () ; let result = crate :: invoke (\"search_notes\" , args) . await ; tauri_bridge_runtime :: slow :: check (\"search_notes\" , __tauri_bridge_started_ms ,"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "(json_len (& __tauri_bridge_sent) , json_len (& result))"
+    ));
+
+    // Raw bytes aren't JSON
+    let raw: ItemFn = parse_quote! {
+        pub fn read_blob(id: u64) -> Vec<u8> {
+            vec![]
+        }
+    };
+    let client = generate_client(&raw, &args);
+    assert!(contains_pattern(
+        &client,
+        "(json_len (& __tauri_bridge_sent) , None)"
+    ));
+
+    let plain = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "slow :: check"));
+}
+
+#[test]
+fn test_log_slow_from_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        log_slow = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(BridgeArgs::default().with_defaults(&config).log_slow);
+}
+
+// ==================== Metrics Tests ====================
+
+#[test]
+fn test_metrics_backend_records_calls() {
+    let args: BridgeArgs = parse_quote!(metrics, rename = "divide");
+    let sync_fn: ItemFn = parse_quote! {
+        pub fn checked_div(a: i32, b: i32) -> Result<i32, String> {
+            Ok(a / b)
+        }
+    };
+    let async_fn: ItemFn = parse_quote! {
+        pub async fn total(values: Vec<u32>) -> u32 {
+            values.iter().sum()
+        }
+    };
+
+    let sync_backend = generate_backend(&sync_fn, &args);
+    let async_backend = generate_backend(&async_fn, &args);
+
+    assert!(contains_pattern(
+        &sync_backend,
+        "let __tauri_bridge_response : Result < i32 , String > = (|| -> Result < i32 , String > { Ok (a / b) }) () ;"
+    ));
+    assert!(contains_pattern(
+        &sync_backend,
+        "tauri_bridge_runtime :: metrics :: record (\"divide\" , __tauri_bridge_started . elapsed () , __tauri_bridge_response . is_err () ,) ;"
+    ));
+    assert!(contains_pattern(
+        &async_backend,
+        "__tauri_bridge_started . elapsed () , false ,) ;"
+    ));
+    let plain = generate_backend(&sync_fn, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "metrics"));
+}
+
+#[test]
+fn test_metrics_from_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        metrics = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(BridgeArgs::default().with_defaults(&config).metrics);
+}
+
+#[test]
+fn test_metrics_command_is_bridged() {
+    let expanded = expand_metrics_command(quote::quote!(rename = "diagnostics.metrics"));
+
+    assert!(contains_pattern(
+        &expanded,
+        "pub fn get_bridge_metrics () -> tauri_bridge_runtime :: metrics :: MetricsSnapshot { tauri_bridge_runtime :: metrics :: bridge_metrics () }"
+    ));
+    assert!(contains_pattern(
+        &expanded,
+        "pub async fn try_get_bridge_metrics ()"
+    ));
+    assert!(contains_pattern(&expanded, "\"diagnostics.metrics\""));
+}
+
+// ==================== Serialize On Tests ====================
+
+#[test]
+fn test_serialized_backend_waits_for_its_turn() {
+    let args: BridgeArgs = parse_quote!(serialize_on = "project_id");
+    let async_fn: ItemFn = parse_quote! {
+        pub async fn save_project(project_id: u64, project: Project) -> Result<(), String> {
+            store(project_id, project).await
+        }
+    };
+    let raw_fn: ItemFn = parse_quote! {
+        pub async fn rename_project(r#project_id: u64, name: String) {
+            rename(project_id, name).await
+        }
+    };
+
+    assert!(check_serialize_on(&async_fn, &args).is_ok());
+    assert!(check_serialize_on(&raw_fn, &args).is_ok());
+    assert!(contains_pattern(
+        &generate_backend(&async_fn, &args),
+        "{ let __tauri_bridge_turn = tauri_bridge_runtime :: serial :: turn (\"project_id\" , & project_id) . await ; { store (project_id , project) . await } }"
+    ));
+    assert!(contains_pattern(
+        &generate_backend(&raw_fn, &args),
+        "let __tauri_bridge_turn = tauri_bridge_runtime :: serial :: turn (\"project_id\" , & r#project_id) . await ;"
+    ));
+    let plain = generate_backend(&async_fn, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "serial"));
+}
+
+#[test]
+fn test_serialize_on_needs_a_sent_argument() {
+    let args: BridgeArgs = parse_quote!(serialize_on = "project_id");
+    let missing: ItemFn = parse_quote! {
+        pub async fn save_project(id: u64) {}
+    };
+    let injected: ItemFn = parse_quote! {
+        pub async fn save_project(#[caller_label] project_id: String) {}
+    };
+
+    let error = check_serialize_on(&missing, &args).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("no parameter named `project_id`")
+    );
+    let error = check_serialize_on(&injected, &args).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("needs an argument sent by the client")
+    );
+}
+
+#[test]
+fn test_serialize_on_rejects_sync_commands() {
+    let args: BridgeArgs = parse_quote!(serialize_on = "project_id");
+    let sync_fn: ItemFn = parse_quote! {
+        pub fn rename_project(project_id: u64, name: String) {}
+    };
+
+    let error = check_serialize_on(&sync_fn, &args).unwrap_err();
+    assert!(error.to_string().contains("make the command `async`"));
+}
+
+// ==================== Layer Tests ====================
+
+#[test]
+fn test_layered_command_runs_between_hooks() {
+    let args: BridgeArgs = parse_quote!(layers, rename = "archive");
+    let input: ItemFn = parse_quote! {
+        pub fn archive_note(id: u64) -> Result<u64, NoteError> {
+            Ok(id)
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "# [tauri :: command (rename = \"archive\")] pub fn archive_note (__tauri_bridge_webview : tauri :: Webview , id : u64) -> Result < u64 , tauri :: ipc :: InvokeError >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "tauri :: Manager :: try_state :: < tauri_bridge_runtime :: layer :: Layers , > (& __tauri_bridge_webview)"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "tauri_bridge_runtime :: layer :: Call { command : \"archive\" , webview_label : __tauri_bridge_webview . label () , } ,) ? ;"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "let __tauri_bridge_response : Result < u64 , NoteError > = (|| -> Result < u64 , NoteError > { Ok (id) }) () ; __tauri_bridge_call . exit (__tauri_bridge_response . is_err ()) ; __tauri_bridge_response . map_err (:: core :: convert :: Into :: into)"
+    ));
+}
+
+#[test]
+fn test_layered_command_wraps_plain_responses() {
+    let args: BridgeArgs = parse_quote!(layers);
+    let input: ItemFn = parse_quote! {
+        pub async fn focus(#[caller_label] caller: String) {}
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    // The webview injected for `#[caller_label]` is reused
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn focus (__tauri_bridge_webview : tauri :: Webview) -> Result < () , tauri :: ipc :: InvokeError >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "let __tauri_bridge_response : () = async { let caller : String = __tauri_bridge_webview . label () . to_string () ; { } } . await ; __tauri_bridge_call . exit (false) ; Ok (__tauri_bridge_response)"
+    ));
+}
+
+#[test]
+fn test_layers_reject_custom_command_shapes() {
+    let input: ItemFn = parse_quote! {
+        pub fn export(items: Vec<u64>) -> Vec<u64> {
+            items
+        }
+    };
+    let compressed: BridgeArgs = parse_quote!(layers, compress = "gzip");
+    let harness: BridgeArgs = parse_quote!(layers, test_harness);
+
+    assert!(check_layers(&input, &compressed).is_err());
+    assert!(check_layers(&input, &harness).is_err());
+    assert!(check_layers(&input, &parse_quote!(layers)).is_ok());
+
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        layers = true
+        "#,
+    )
+    .unwrap();
+    assert!(config.layers);
+}
+
+// ==================== Session Tests ====================
+
+#[test]
+fn test_session_command_validates_token() {
+    let args: BridgeArgs = parse_quote!(namespace = "notes");
+    let input: ItemFn = parse_quote! {
+        pub fn delete_note(#[session] session: UserSession, id: u64) -> Result<u64, String> {
+            Ok(id)
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn delete_note (__tauri_bridge_webview : tauri :: Webview , __tauri_bridge_session : Option < String > , id : u64) -> Result < u64 , tauri :: ipc :: InvokeError >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "tauri :: Manager :: try_state :: < tauri_bridge_runtime :: session :: SessionValidator < UserSession > , > (& __tauri_bridge_webview)"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "let session : UserSession = tauri_bridge_runtime :: session :: validate (__tauri_bridge_validator . as_deref () , \"notes.delete_note\" , __tauri_bridge_session ,) ? ;"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "__tauri_bridge_response . map_err (:: core :: convert :: Into :: into)"
+    ));
+}
+
+#[test]
+fn test_session_command_reuses_caller_webview() {
+    let input: ItemFn = parse_quote! {
+        pub async fn whoami(#[caller_label] caller: String, #[session] session: UserSession) -> String {
+            format!("{}:{}", caller, session.name)
+        }
+    };
+
+    let backend = generate_backend(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn whoami (__tauri_bridge_webview : tauri :: Webview , __tauri_bridge_session : Option < String >) -> Result < String , tauri :: ipc :: InvokeError >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "= async { let caller : String = __tauri_bridge_webview . label () . to_string () ;"
+    ));
+}
+
+#[test]
+fn test_session_client_sends_token() {
+    let input: ItemFn = parse_quote! {
+        pub fn sign_out(#[session] session: UserSession) {}
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "# [serde (rename = \"tauriBridgeSession\")] __tauri_bridge_session : tauri_bridge_runtime :: session :: Token"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "SignOutArgs { __tauri_bridge_session : tauri_bridge_runtime :: session :: token (\"sign_out\") }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub async fn try_sign_out () -> Result < () , String >"
+    ));
+}
+
+#[test]
+fn test_session_checks() {
+    let check = |input: ItemFn, args: BridgeArgs| check_session(&input, &args);
+
+    assert!(
+        check(
+            parse_quote! { pub fn a(#[session] a: UserSession, #[session] b: UserSession) {} },
+            BridgeArgs::default(),
+        )
+        .is_err()
+    );
+    assert!(
+        check(
+            parse_quote! { pub fn a(#[session] session: &UserSession) {} },
+            BridgeArgs::default(),
+        )
+        .is_err()
+    );
+    for args in [
+        parse_quote!(chunked),
+        parse_quote!(compress = "gzip"),
+        parse_quote!(encrypt),
+        parse_quote!(by_value),
+    ] {
+        assert!(
+            check(
+                parse_quote! { pub fn a(#[session] s: UserSession) {} },
+                args
+            )
+            .is_err()
+        );
+    }
+    assert!(
+        check(
+            parse_quote! { pub fn a(#[session] s: UserSession) {} },
+            BridgeArgs::default(),
+        )
+        .is_ok()
+    );
+
+    let input: ItemFn = parse_quote! { pub fn a(#[session] s: UserSession) {} };
+    assert!(check_layers(&input, &parse_quote!(layers)).is_err());
+    assert!(check_test_harness(&input, &parse_quote!(test_harness)).is_err());
+    let input: ItemFn = parse_quote! {
+        pub fn a(#[session] #[bridge(default)] s: UserSession) {}
+    };
+    assert!(check_defaults(&input).is_err());
+}
+
+// ==================== Locked State Tests ====================
+
+#[test]
+fn test_bridge_state_is_locked_for_the_body() {
+    let mut input: ItemFn = parse_quote! {
+        pub fn add_todo(todos: BridgeState<TodoList>, title: String) -> usize {
+            todos.items.push(title);
+            todos.items.len()
+        }
+    };
+
+    assert!(check_bridge_state(&input, &BridgeArgs::default()).is_ok());
+    lock_bridge_state(&mut input, false);
+    let backend = generate_backend(&input, &BridgeArgs::default());
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn add_todo (__tauri_bridge_locked_state : tauri :: State < '_ , BridgeState < TodoList > > , title : String) -> usize"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "let mut __tauri_bridge_state_guard = __tauri_bridge_locked_state . lock () . unwrap_or_else (:: std :: sync :: PoisonError :: into_inner) ; let todos = & mut * __tauri_bridge_state_guard ; { todos . items . push (title) ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub async fn add_todo (title : String) -> usize"
+    ));
+    assert!(!contains_pattern(&client, "BridgeState"));
+}
+
+#[test]
+fn test_bridge_state_declaration_keeps_no_body() {
+    let mut input: ItemFn = parse_quote! {
+        pub fn clear_todos(todos: tauri_bridge_runtime::state::BridgeState<TodoList>) {}
+    };
+
+    lock_bridge_state(&mut input, true);
+
+    assert!(input.block.stmts.is_empty());
+    assert!(contains_pattern(
+        &generate_client(&input, &BridgeArgs::default()),
+        "pub async fn clear_todos ()"
+    ));
+}
+
+#[test]
+fn test_only_the_runtime_bridge_state_is_locked() {
+    let runtime: [Type; 3] = [
+        parse_quote!(BridgeState<TodoList>),
+        parse_quote!(tauri_bridge_runtime::state::BridgeState<TodoList>),
+        parse_quote!(::tauri_bridge_runtime::state::BridgeState<TodoList>),
+    ];
+    let others: [Type; 4] = [
+        parse_quote!(crate::store::BridgeState<TodoList>),
+        parse_quote!(state::BridgeState<TodoList>),
+        parse_quote!(::BridgeState<TodoList>),
+        parse_quote!(BridgeState),
+    ];
+
+    assert!(runtime.iter().all(is_bridge_state));
+    assert!(!others.iter().any(is_bridge_state));
+}
+
+#[test]
+fn test_bridge_state_rejects_commands_holding_it_too_long() {
+    let async_fn: ItemFn = parse_quote! {
+        pub async fn add_todo(todos: BridgeState<TodoList>, title: String) {}
+    };
+    let two_states: ItemFn = parse_quote! {
+        pub fn move_todo(from: BridgeState<TodoList>, to: BridgeState<TodoList>) {}
+    };
+    let harnessed: ItemFn = parse_quote! {
+        pub fn count_todos(todos: BridgeState<TodoList>) -> usize {
+            todos.items.len()
+        }
+    };
+
+    let error = check_bridge_state(&async_fn, &BridgeArgs::default()).unwrap_err();
+    assert!(error.to_string().contains("async commands would hold"));
+    let error = check_bridge_state(&two_states, &BridgeArgs::default()).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("only one parameter can be a `BridgeState`")
+    );
+    let error = check_bridge_state(&harnessed, &parse_quote!(test_harness)).unwrap_err();
+    assert!(error.to_string().contains("`test_harness` can't call"));
+}
+
+// ==================== Sidecar Tests ====================
+
+#[test]
+fn test_sidecar_command_forwards_to_sidecar() {
+    let args: BridgeArgs = parse_quote!(sidecar = "pdf");
+    let input: ItemFn = parse_quote! {
+        pub fn render_page(path: &str, page_number: u32) -> Result<Vec<u8>, String> {
+            Ok(render(path, page_number))
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn __tauri_bridge_sidecar_render_page (path : & str , page_number : u32) -> Result < Vec < u8 > , String > { Ok (render (path , page_number)) }"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn render_page (__tauri_bridge_webview : tauri :: Webview , path : String , page_number : u32) -> Result < Vec < u8 > , tauri :: ipc :: InvokeError >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "__tauri_bridge_args . insert (\"pageNumber\" . to_string () , :: serde_json :: to_value (& page_number)"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        ". call (\"pdf\" , \"render_page\" , :: serde_json :: Value :: Object (__tauri_bridge_args)) . await"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "pub use __tauri_cmd_render_page :: __tauri_bridge_sidecar_render_page ;"
+    ));
+    // Bytes are sent as JSON through the sidecar
+    assert!(!args.raw_bytes_response(&input));
+}
+
+#[test]
+fn test_sidecar_dispatch_calls_body_outside_tests() {
+    let args: BridgeArgs = parse_quote!(sidecar = "pdf");
+    let input: ItemFn = parse_quote! {
+        pub async fn page_count(path: String) -> u32 {
+            count(path).await
+        }
+    };
+
+    let dispatch = generate_dispatch(&input, &args);
+
+    assert!(contains_pattern(
+        &dispatch,
+        "__tauri_bridge_sidecar_page_count (path) . await"
+    ));
+    assert!(!contains_pattern(&dispatch, "test"));
+}
+
+#[test]
+fn test_sidecar_checks() {
+    let check = |input: ItemFn, args: BridgeArgs| check_sidecar(&input, &args);
+    let input: ItemFn = parse_quote! { pub fn a(path: String) -> u32 { 1 } };
+
+    assert!(check(input.clone(), parse_quote!(sidecar = "pdf")).is_ok());
+    assert!(check(input.clone(), parse_quote!(sidecar = "")).is_err());
+    for args in [
+        parse_quote!(sidecar = "pdf", chunked),
+        parse_quote!(sidecar = "pdf", compress = "gzip"),
+        parse_quote!(sidecar = "pdf", encrypt),
+        parse_quote!(sidecar = "pdf", raw),
+        parse_quote!(sidecar = "pdf", layers),
+        parse_quote!(sidecar = "pdf", catch_panic),
+        parse_quote!(sidecar = "pdf", alias = "b"),
+    ] {
+        assert!(check(input.clone(), args).is_err());
+    }
+    assert!(check_layers(&input, &parse_quote!(sidecar = "pdf")).is_err());
+
+    for input in [
+        parse_quote! { pub fn a(#[caller_label] caller: String) {} },
+        parse_quote! { pub fn a(ctx: CallerContext) {} },
+        parse_quote! { pub fn a(#[session] session: UserSession) {} },
+    ] {
+        let error = check(input, parse_quote!(sidecar = "pdf")).unwrap_err();
+        assert!(error.to_string().contains("injected parameters"));
+    }
+
+    let input: ItemFn = parse_quote! { pub fn a(names: &[&str]) {} };
+    let error = check_payload_options(&input, &parse_quote!(sidecar = "pdf")).unwrap_err();
+    assert!(error.to_string().starts_with("`sidecar` only supports"));
+}
+
+// ==================== Payload Limit Tests ====================
+
+#[test]
+fn test_max_payload_parses_sizes() {
+    let limit = |args: BridgeArgs| args.max_payload.unwrap();
+
+    assert_eq!(limit(parse_quote!(max_payload = "1MB")), 1_048_576);
+    assert_eq!(limit(parse_quote!(max_payload = "64 kb")), 65_536);
+    assert_eq!(limit(parse_quote!(max_payload = "512B")), 512);
+    assert_eq!(limit(parse_quote!(max_payload = "2GB")), 2_147_483_648);
+    assert_eq!(limit(parse_quote!(max_payload = 4096)), 4096);
+
+    for attr in [
+        "max_payload = \"1TB\"",
+        "max_payload = \"MB\"",
+        "max_payload = \"0KB\"",
+        "max_payload",
+    ] {
+        assert!(syn::parse_str::<BridgeArgs>(attr).is_err());
+    }
+}
+
+#[test]
+fn test_max_payload_leaves_the_backend_unchanged() {
+    let args: BridgeArgs = parse_quote!(max_payload = "1KB");
+    let input: ItemFn = parse_quote! {
+        pub async fn save_draft(
+            #[caller_label] caller: String,
+            title: &str,
+            body: String,
+        ) -> Result<u64, String> {
+            save(caller, title, body).await
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    // Tauri has decoded the arguments before the command could measure them
+    assert!(contains_pattern(&backend, "-> Result < u64 , String >"));
+    assert!(!contains_pattern(&backend, "payload"));
+}
+
+#[test]
+fn test_max_payload_client_fails_with_payload_error() {
+    let args: BridgeArgs = parse_quote!(max_payload = "1KB");
+    let input: ItemFn = parse_quote! {
+        pub fn save_draft(title: String) -> u64 {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < u64 , tauri_bridge_runtime :: payload :: PayloadError >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: payload :: check (\"save_draft\" , 1024usize , & __tauri_bridge_args ,)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "Some (error) => Err (tauri_bridge_runtime :: payload :: PayloadError :: TooLarge (error))"
+    ));
+}
+
+#[test]
+fn test_max_payload_checks() {
+    let check = |args: BridgeArgs| {
+        let input: ItemFn = parse_quote! { pub fn a(text: String) -> u32 { 1 } };
+        check_max_payload(&input, &args)
+    };
+
+    assert!(check(parse_quote!(max_payload = "1MB")).is_ok());
+    assert!(check(parse_quote!(max_payload = "1MB", extern)).is_ok());
+    for args in [
+        parse_quote!(max_payload = "1MB", chunked),
+        parse_quote!(max_payload = "1MB", compress = "gzip"),
+        parse_quote!(max_payload = "1MB", encrypt),
+        parse_quote!(max_payload = "1MB", catch_panic),
+        parse_quote!(max_payload = "1MB", circuit_breaker),
+        parse_quote!(max_payload = "1MB", platforms("desktop")),
+        parse_quote!(max_payload = "1MB", sidecar = "pdf"),
+    ] {
+        assert!(check(args).is_err());
+    }
+
+    // The backend is left to the options generating its command
+    assert!(check(parse_quote!(max_payload = "1MB", layers)).is_ok());
+    assert!(check(parse_quote!(max_payload = "1MB", test_harness)).is_ok());
+    assert!(check(parse_quote!(max_payload = "1MB", verify_schema)).is_ok());
+    let input: ItemFn = parse_quote! { pub fn a(#[session] session: UserSession, text: String) {} };
+    assert!(check_max_payload(&input, &parse_quote!(max_payload = "1MB")).is_ok());
+    let input: ItemFn = parse_quote! { pub fn a(text: String) {} };
+    assert!(check_layers(&input, &parse_quote!(max_payload = "1MB", layers)).is_ok());
+}
+
+// ==================== Schema Verification Tests ====================
+
+#[test]
+fn test_structure_hash_ignores_docs() {
+    let documented: ItemFn = parse_quote! {
+        /// Save a note.
+        pub fn save_note(note: Note, #[caller_label] caller: String) -> Result<NoteId, String> {}
+    };
+    let plain: ItemFn = parse_quote! {
+        pub fn save_note(note: Note) -> Result<NoteId, String> {}
+    };
+    let changed: ItemFn = parse_quote! {
+        pub fn save_note(note: Note) -> Result<Option<NoteId>, String> {}
+    };
+
+    let hash = structure_hash(&documented, "save_note");
+    assert_eq!(hash.len(), 16);
+    assert_eq!(hash, structure_hash(&plain, "save_note"));
+    assert_ne!(hash, structure_hash(&changed, "save_note"));
+    assert_ne!(hash, structure_hash(&plain, "save_note_v2"));
+}
+
+#[test]
+fn test_verify_schema_backend_checks_sent_hash() {
+    let args: BridgeArgs = parse_quote!(verify_schema);
+    assert!(args.verify_schema);
+    let input: ItemFn = parse_quote! {
+        pub async fn save_note(note: Note) -> Result<NoteId, String> {
+            save(note).await
+        }
+    };
+    let hash = structure_hash(&input, "save_note");
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "(note : Note , __tauri_bridge_schema : Option < String >) -> Result < NoteId , tauri :: ipc :: InvokeError >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        &format!(
+            "tauri_bridge_runtime :: introspect :: verify_schema (\"save_note\" , \"{}\" , __tauri_bridge_schema . as_deref () ,)",
+            hash
+        )
+    ));
+}
+
+#[test]
+fn test_verify_schema_client_sends_hash() {
+    let args: BridgeArgs = parse_quote!(verify_schema);
+    let input: ItemFn = parse_quote! {
+        pub fn list_notes() -> Vec<Note> {}
+    };
+    let hash = structure_hash(&input, "list_notes");
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "# [serde (rename = \"tauriBridgeSchema\")] __tauri_bridge_schema : String"
+    ));
+    assert!(contains_pattern(
+        &client,
+        &format!("__tauri_bridge_schema : \"{}\" . to_string ()", hash)
+    ));
+}
+
+#[test]
+fn test_verify_schema_checks() {
+    let check = |args: BridgeArgs| {
+        let input: ItemFn = parse_quote! { pub fn a(text: String) -> u32 { 1 } };
+        check_verify_schema(&input, &args)
+    };
+
+    assert!(check(parse_quote!(verify_schema)).is_ok());
+    assert!(check(parse_quote!(verify_schema, extern)).is_ok());
+    assert!(check(parse_quote!(verify_schema, alias = "b")).is_ok());
+    for args in [
+        parse_quote!(verify_schema, chunked),
+        parse_quote!(verify_schema, compress = "gzip"),
+        parse_quote!(verify_schema, encrypt),
+        parse_quote!(verify_schema, layers),
+        parse_quote!(verify_schema, test_harness),
+        parse_quote!(verify_schema, catch_panic),
+        parse_quote!(verify_schema, platforms("desktop")),
+        parse_quote!(verify_schema, sidecar = "pdf"),
+        parse_quote!(verify_schema, transaction),
+        parse_quote!(verify_schema, floats = "null"),
+    ] {
+        assert!(check(args).is_err());
+    }
+
+    let input: ItemFn = parse_quote! { pub fn a(#[session] session: UserSession) {} };
+    assert!(check_verify_schema(&input, &parse_quote!(verify_schema)).is_err());
+    // The crate-wide `layers` and the fuzz dispatch skip verified commands
+    let input: ItemFn = parse_quote! { pub fn a(text: String) {} };
+    assert!(check_layers(&input, &parse_quote!(verify_schema)).is_err());
+    assert!(check_test_harness(&input, &parse_quote!(verify_schema)).is_err());
+}
+
+// ==================== Transaction Tests ====================
+
+#[test]
+fn test_transaction_parses_undo_hook() {
+    let args: BridgeArgs = parse_quote!(transaction);
+    assert!(args.transaction.unwrap().undo.is_none());
+
+    let args: BridgeArgs = parse_quote!(transaction(undo = notes::delete_note));
+    let undo = args.transaction.unwrap().undo.unwrap();
+    assert_eq!(quote::quote!(#undo).to_string(), "notes :: delete_note");
+
+    for attr in [
+        "transaction = true",
+        "transaction(redo = a)",
+        "transaction(undo)",
+    ] {
+        assert!(syn::parse_str::<BridgeArgs>(attr).is_err());
+    }
+}
+
+#[test]
+fn test_transaction_step_runs_command_with_undo() {
+    let args: BridgeArgs = parse_quote!(transaction(undo = delete_note));
+    let input: ItemFn = parse_quote! {
+        pub async fn create_note(title: String) -> Result<u64, String> {
+            Ok(1)
+        }
+    };
+
+    let step = args.transaction.as_ref().unwrap();
+    let tokens = generate_transact(&input, &args, step);
+
+    assert!(contains_pattern(
+        &tokens,
+        "pub async fn __tauri_bridge_transact_create_note (__tauri_bridge_command : & str , __tauri_bridge_args : :: serde_json :: Value ,) -> Option < tauri_bridge_runtime :: transaction :: Executed >"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "if __tauri_bridge_command != \"create_note\" { return None ; }"
+    ));
+    assert!(contains_pattern(&tokens, "create_note (title) . await"));
+    assert!(contains_pattern(
+        &tokens,
+        "Some (:: std :: boxed :: Box :: pin (delete_note (__tauri_bridge_value)))"
+    ));
+
+    let client = generate_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "pub fn create_note_step (title : String) -> tauri_bridge_runtime :: transaction :: Call < u64 >"
+    ));
+}
+
+#[test]
+fn test_bridge_transaction_runs_listed_steps() {
+    let input: IntrospectInput = parse_quote!(create_note, notes::tag_note);
+
+    let tokens = generate_transaction(&input);
+
+    assert!(contains_pattern(
+        &tokens,
+        "if let Some (executed) = __tauri_bridge_transact_create_note (command , args . clone ()) . await"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "notes :: __tauri_bridge_transact_tag_note (command , args . clone ())"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [tauri :: command] pub async fn __bridge_transaction (steps : Vec < tauri_bridge_runtime :: transaction :: Step > ,) -> tauri_bridge_runtime :: transaction :: Outcome"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub async fn commit_bridge_transaction (transaction : tauri_bridge_runtime :: transaction :: Transaction ,)"
+    ));
+}
+
+#[test]
+fn test_transaction_checks() {
+    let check = |args: BridgeArgs| {
+        let input: ItemFn = parse_quote! { pub fn a(text: String) -> u32 { 1 } };
+        check_transaction(&input, &args)
+    };
+
+    assert!(check(parse_quote!(transaction)).is_ok());
+    assert!(check(parse_quote!(transaction(undo = b), extern, test_harness)).is_ok());
+    for args in [
+        parse_quote!(transaction, chunked),
+        parse_quote!(transaction, compress = "gzip"),
+        parse_quote!(transaction, encrypt),
+        parse_quote!(transaction, layers),
+        parse_quote!(transaction, catch_panic),
+        parse_quote!(transaction, max_payload = "1MB"),
+        parse_quote!(transaction, sidecar = "pdf"),
+        parse_quote!(transaction, raw_response),
+    ] {
+        assert!(check(args).is_err());
+    }
+
+    let input: ItemFn = parse_quote! { pub fn a(#[caller_label] caller: String) {} };
+    assert!(check_transaction(&input, &parse_quote!(transaction)).is_err());
+}
+
+// ==================== Response Validation Tests ====================
+
+#[test]
+fn test_validate_response_checks_ok_side() {
+    let args: BridgeArgs = parse_quote!(validate_response = "checks::check_user");
+    let input: ItemFn = parse_quote! {
+        pub fn get_user(id: u64) -> Result<User, String> {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < Result < User , String > , tauri_bridge_runtime :: validate :: ResponseError >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "if let Ok (value) = & response { tauri_bridge_runtime :: validate :: check (\"get_user\" , value , checks :: check_user) . map_err (tauri_bridge_runtime :: validate :: ResponseError :: Invalid) ? ; }"
+    ));
+}
+
+#[test]
+fn test_validate_response_checks_plain_value() {
+    let args: BridgeArgs = parse_quote!(validate_response = "check_count");
+    let input: ItemFn = parse_quote! {
+        pub fn count_items() -> i64 {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let value = & response ; tauri_bridge_runtime :: validate :: check (\"count_items\" , value , check_count)"
+    ));
+    // The backend doesn't validate
+    let backend = generate_backend(&input, &args);
+    assert!(!contains_pattern(&backend, "validate"));
+}
+
+#[test]
+fn test_validate_response_checks() {
+    let check = |args: BridgeArgs| check_validate_response(&args);
+
+    assert!(check(parse_quote!(validate_response = "check")).is_ok());
+    assert!(check(parse_quote!(validate_response = "check", extern, cached)).is_ok());
+    for args in [
+        parse_quote!(validate_response = "check", circuit_breaker),
+        parse_quote!(validate_response = "check", platforms("desktop")),
+        parse_quote!(validate_response = "check", catch_panic),
+        parse_quote!(validate_response = "check", max_payload = "1MB"),
+        parse_quote!(validate_response = "check", raw_response),
+    ] {
+        assert!(check(args).is_err());
+    }
+
+    for attr in [
+        "validate_response",
+        "validate_response = check",
+        "validate_response = \"a b\"",
+    ] {
+        assert!(syn::parse_str::<BridgeArgs>(attr).is_err());
+    }
+}
+
+// ==================== Panic Capture Tests ====================
+
+#[test]
+fn test_catch_panic_backend_responds_with_caught() {
+    let args: BridgeArgs = parse_quote!(catch_panic);
+    let input: ItemFn = parse_quote! {
+        pub fn parse_port(text: String) -> u16 {
+            text.parse().unwrap()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub fn parse_port (text : String) -> tauri_bridge_runtime :: panic :: Caught < u16 > { tauri_bridge_runtime :: panic :: catch (|| -> u16 { text . parse () . unwrap () }) }"
+    ));
+
+    let input: ItemFn = parse_quote! {
+        pub async fn reset() {}
+    };
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn reset () -> tauri_bridge_runtime :: panic :: Caught < () > { tauri_bridge_runtime :: panic :: catch_async (async { }) . await }"
+    ));
+}
+
+#[test]
+fn test_catch_panic_client_types_error() {
+    let args: BridgeArgs = parse_quote!(catch_panic);
+    let input: ItemFn = parse_quote! {
+        pub fn parse_port(text: String) -> u16 {
+            text.parse().unwrap()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < u16 , tauri_bridge_runtime :: panic :: BridgeError >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "serde_wasm_bindgen :: from_value :: < tauri_bridge_runtime :: panic :: Caught < u16 > > (result)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: panic :: unwrap (result)"
+    ));
+}
+
+#[test]
+fn test_catch_panic_rejects_custom_responses() {
+    let input: ItemFn = parse_quote! {
+        pub fn export(items: Vec<u64>) -> Vec<u64> {
+            items
+        }
+    };
+    let compressed: BridgeArgs = parse_quote!(catch_panic, compress = "gzip");
+    let breaker: BridgeArgs = parse_quote!(catch_panic, circuit_breaker);
+
+    assert!(check_catch_panic(&input, &compressed).is_err());
+    assert!(check_catch_panic(&input, &breaker).is_err());
+    assert!(check_catch_panic(&input, &parse_quote!(catch_panic, layers)).is_ok());
+}
+
+// ==================== Non-Send Tests ====================
+
+#[test]
+fn test_non_send_command_runs_on_blocking_thread() {
+    let args: BridgeArgs = parse_quote!(non_send);
+    let input: ItemFn = parse_quote! {
+        pub async fn render_preview(markdown: String) -> String {
+            let cache = Rc::new(markdown);
+            cache.to_string()
+        }
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "async fn __tauri_bridge_render_preview (markdown : String) -> String { let cache = Rc :: new (markdown) ; cache . to_string () }"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "# [tauri :: command] pub async fn render_preview (markdown : String) -> String { tauri :: async_runtime :: spawn_blocking (move || { tauri :: async_runtime :: block_on (__tauri_bridge_render_preview (markdown)) }) . await . expect (\"`render_preview` panicked\") }"
+    ));
+}
+
+#[test]
+fn test_non_send_command_moves_injected_webview() {
+    let args: BridgeArgs = parse_quote!(non_send);
+    let input: ItemFn = parse_quote! {
+        pub async fn focus(#[caller_label] caller: String, id: u64) {}
+    };
+
+    let backend = generate_backend(&input, &args);
+
+    assert!(contains_pattern(
+        &backend,
+        "async fn __tauri_bridge_focus (__tauri_bridge_webview : tauri :: Webview , id : u64) { let caller : String = __tauri_bridge_webview . label () . to_string () ; { } }"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "block_on (__tauri_bridge_focus (__tauri_bridge_webview , id))"
+    ));
+}
+
+#[test]
+fn test_non_send_rejects_unsupported_commands() {
+    let args: BridgeArgs = parse_quote!(non_send);
+    let sync: ItemFn = parse_quote! {
+        pub fn count(items: Vec<u64>) -> usize {
+            items.len()
+        }
+    };
+    let borrowed: ItemFn = parse_quote! {
+        pub async fn count(items: &[u64]) -> usize {
+            items.len()
+        }
+    };
+    let owned: ItemFn = parse_quote! {
+        pub async fn export(items: Vec<u64>) -> Vec<u64> {
+            items
+        }
+    };
+
+    assert!(check_non_send(&sync, &args).is_err());
+    assert!(check_non_send(&borrowed, &args).is_err());
+    assert!(check_non_send(&owned, &args).is_ok());
+    assert!(check_non_send(&owned, &parse_quote!(non_send, compress = "gzip")).is_err());
+    assert!(check_non_send(&owned, &parse_quote!(non_send, layers)).is_err());
+    assert!(check_layers(&owned, &parse_quote!(layers, non_send)).is_err());
+}
+
+// ==================== Service Tests ====================
+
+#[test]
+fn test_service_methods_become_state_commands() {
+    let item: ItemImpl = parse_quote! {
+        #[cfg(feature = "notes")]
+        impl NoteStore {
+            /// Notes in a folder.
+            pub fn count(&self, folder: String, #[caller_label] caller: String) -> usize {
+                self.len(&folder)
+            }
+
+            pub async fn reload(&self) -> Result<Self, String> {
+                Ok(self.clone())
+            }
+
+            pub fn new() -> Self {
+                NoteStore::default()
+            }
+
+            fn len(&self, folder: &str) -> usize {
+                0
+            }
+        }
+    };
+
+    let commands: Vec<_> = service_commands(&item, &BridgeArgs::default())
+        .iter()
+        .map(|command| quote::quote!(#command))
+        .collect();
+
+    assert_eq!(commands.len(), 2);
+    assert!(contains_pattern(
+        &commands[0],
+        "# [cfg (feature = \"notes\")] # [doc = r\" Notes in a folder.\"] pub fn count (# [__tauri_bridge_state] __tauri_bridge_state : tauri :: State < '_ , NoteStore > , folder : String , # [caller_label] caller : String) -> usize { < NoteStore > :: count (& __tauri_bridge_state , folder , caller) }"
+    ));
+    assert!(contains_pattern(
+        &commands[1],
+        "pub async fn reload (# [__tauri_bridge_state] __tauri_bridge_state : tauri :: State < '_ , NoteStore >) -> Result < NoteStore , String > { < NoteStore > :: reload (& __tauri_bridge_state ,) . await }"
+    ));
+
+    // The state stays on the backend
+    let backend = generate_backend(
+        &service_commands(&item, &BridgeArgs::default())[0],
+        &BridgeArgs::default(),
+    );
+    assert!(contains_pattern(
+        &backend,
+        "pub fn count (__tauri_bridge_state : tauri :: State < '_ , NoteStore > , folder : String , __tauri_bridge_webview : tauri :: Webview)"
+    ));
+    let client = generate_client(
+        &service_commands(&item, &BridgeArgs::default())[0],
+        &BridgeArgs::default(),
+    );
+    assert!(contains_pattern(
+        &client,
+        "struct CountArgs { folder : String }"
+    ));
+    assert!(!contains_pattern(&client, "__tauri_bridge_state"));
+
+    // Markers only the commands read are dropped from the methods
+    let service = service_impl(&item);
+    assert!(!contains_pattern(&quote::quote!(#service), "caller_label"));
+}
+
+#[test]
+fn test_service_rejects_unsupported_impls() {
+    let args = BridgeArgs::default();
+    let mutable: ItemImpl = parse_quote! {
+        impl Counter {
+            pub fn bump(&mut self) -> u32 {
+                self.0 += 1;
+                self.0
+            }
+        }
+    };
+    let consuming: ItemImpl = parse_quote! {
+        impl Counter {
+            pub fn into_inner(self) -> u32 {
+                self.0
+            }
+        }
+    };
+    let trait_impl: ItemImpl = parse_quote! {
+        impl Default for Counter {
+            fn default() -> Self {
+                Counter(0)
+            }
+        }
+    };
+    let generic: ItemImpl = parse_quote! {
+        impl<T> Store<T> {
+            pub fn len(&self) -> usize {
+                0
+            }
+        }
+    };
+    let private: ItemImpl = parse_quote! {
+        impl Counter {
+            fn get(&self) -> u32 {
+                self.0
+            }
+        }
+    };
+    let counter: ItemImpl = parse_quote! {
+        impl Counter {
+            pub fn get(&self) -> u32 {
+                self.0
+            }
+        }
+    };
+
+    let error = check_service(&mutable, &args).unwrap_err().to_string();
+    assert!(error.contains("instead of `&mut self`"));
+    assert!(check_service(&consuming, &args).is_err());
+    assert!(check_service(&trait_impl, &args).is_err());
+    assert!(check_service(&generic, &args).is_err());
+    assert!(check_service(&private, &args).is_err());
+    assert!(check_service(&counter, &args).is_ok());
+    assert!(check_service(&counter, &parse_quote!(namespace = "counter")).is_ok());
+    assert!(check_service(&counter, &parse_quote!(rename = "get_counter")).is_err());
+    assert!(check_service(&counter, &parse_quote!(non_send)).is_err());
+
+    let expanded = normalize_tokens(&expand(
+        quote::quote! {},
+        quote::quote! {
+            impl Counter {
+                pub fn get(&self) -> u32 {
+                    self.0
+                }
+            }
+        },
+    ));
+    assert!(expanded.contains("# [cfg (not (target_arch = \"wasm32\"))] impl Counter"));
+    assert!(expanded.contains("pub async fn try_get () -> Result < u32 , String >"));
+}
+
+#[test]
+fn test_generic_service_bridged_with_concrete_types() {
+    let args: BridgeArgs = parse_quote!(concrete(S = SqliteStore));
+    let item: ItemImpl = parse_quote! {
+        impl<S: Store> Notes<S> {
+            pub fn find(&self, key: <S as Store>::Key) -> Option<Self> {
+                None
+            }
+        }
+    };
+
+    assert!(check_service(&item, &args).is_ok());
+    let commands = service_commands(&item, &args);
+    assert!(contains_pattern(
+        &quote::quote!(#(#commands)*),
+        "pub fn find (# [__tauri_bridge_state] __tauri_bridge_state : tauri :: State < '_ , Notes < SqliteStore > > , key : < SqliteStore as Store > :: Key) -> Option < Notes < SqliteStore > > { < Notes < SqliteStore > > :: find (& __tauri_bridge_state , key) }"
+    ));
+    assert!(check_concrete(&commands[0], &args).is_ok());
+
+    // Every type parameter needs a type, and only those of the impl block
+    let unnamed: ItemImpl = parse_quote! {
+        impl<S: Store, C> Notes<S, C> {
+            pub fn count(&self) -> usize {
+                0
+            }
+        }
+    };
+    let error = check_service(&unnamed, &args).unwrap_err().to_string();
+    assert!(error.contains("`concrete(C = ..)`"));
+    let stray: BridgeArgs = parse_quote!(concrete(S = SqliteStore, T = u32));
+    assert!(check_service(&item, &stray).is_err());
+    let lifetime: ItemImpl = parse_quote! {
+        impl<'a> Notes<'a> {
+            pub fn count(&self) -> usize {
+                0
+            }
+        }
+    };
+    assert!(check_service(&lifetime, &BridgeArgs::default()).is_err());
+
+    // Free functions have no type parameters to give
+    let free: ItemFn = parse_quote! {
+        pub fn count() -> usize {
+            0
+        }
+    };
+    assert!(check_concrete(&free, &args).is_err());
+    for attr in ["concrete", "concrete()", "concrete(S)"] {
+        assert!(syn::parse_str::<BridgeArgs>(attr).is_err());
+    }
+}
+
+// ==================== Rejection Tests ====================
+
+#[test]
+fn test_rejections_decode_result_sides() {
+    let args: BridgeArgs = parse_quote!(rejections);
+    let input: ItemFn = parse_quote! {
+        pub fn archive(id: u64) -> Result<u64, NoteError> {
+            Ok(id)
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let result = match crate :: try_invoke (\"archive\" , args) . await { Ok (result) => result , Err (error) => return match serde_wasm_bindgen :: from_value :: < NoteError > (error . clone ()) { Ok (error) => Ok (Err (error)) , Err (_) => Err (error . as_string () . unwrap_or_else (|| format ! (\"{:?}\" , error))) , } , } ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "(serde_wasm_bindgen :: from_value :: < u64 > (result) . map_err (| e | format ! (\"Failed to deserialize response: {}\" , e))) . map (Ok)"
+    ));
+}
+
+#[test]
+fn test_rejections_fail_plain_commands() {
+    let args: BridgeArgs = parse_quote!(rejections);
+    let input: ItemFn = parse_quote! {
+        pub fn title(id: u64) -> String {
+            todo!()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "Err (error) => return Err (error . as_string () . unwrap_or_else (|| format ! (\"{:?}\" , error))) ,"
+    ));
+    assert!(!contains_pattern(&client, "crate :: invoke ("));
+
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        rejections = true
+        "#,
+    )
+    .unwrap();
+    assert!(BridgeArgs::default().with_defaults(&config).rejections);
+}
+
+#[test]
+fn test_raw_response_client_returns_response_as_is() {
+    let args: BridgeArgs = parse_quote!(raw_response, rejections);
+    let input: ItemFn = parse_quote! {
+        pub fn chart_series(range: u32) -> Result<Vec<Point>, String> {
+            todo!()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < wasm_bindgen :: JsValue , String >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "Err (error) => return Err (error . as_string () . unwrap_or_else (|| format ! (\"{:?}\" , error))) , } ; Ok (result)"
+    ));
+    assert!(!contains_pattern(&client, "from_value"));
+
+    let conflicting: BridgeArgs = parse_quote!(raw_response, compress = "gzip");
+    assert!(check_payload_options(&input, &conflicting).is_err());
+
+    // The option's earlier name
+    let alias: BridgeArgs = parse_quote!(js_value);
+    assert!(alias.raw_response);
+}
+
+#[test]
+fn test_unwrap_envelope_client_returns_result() {
+    let args: BridgeArgs = parse_quote!(unwrap_envelope, rejections);
+    let input: ItemFn = parse_quote! {
+        pub fn find_member(id: u64) -> ApiResponse<Member> {
+            todo!()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "-> Result < Result < < ApiResponse < Member > as tauri_bridge_runtime :: envelope :: Envelope > :: Data , < ApiResponse < Member > as tauri_bridge_runtime :: envelope :: Envelope > :: Error , > , String >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "serde_wasm_bindgen :: from_value :: < ApiResponse < Member > > (result)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        ". map (tauri_bridge_runtime :: envelope :: Envelope :: into_result)"
+    ));
+
+    let conflicting: BridgeArgs = parse_quote!(unwrap_envelope, catch_panic);
+    let unit: ItemFn = parse_quote! {
+        pub fn ping() {}
+    };
+    assert!(check_payload_options(&input, &conflicting).is_err());
+    assert!(check_payload_options(&unit, &parse_quote!(unwrap_envelope)).is_err());
+}
+
+#[test]
+fn test_cached_client_reads_through_query_cache() {
+    let input: ItemFn = parse_quote! {
+        pub fn list_members(team: u32) -> Vec<String> {
+            vec![]
+        }
+    };
+
+    let client = generate_client(&input, &parse_quote!(cached));
+
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: query :: cached :: < Vec < String > , _ > (\"list_members\" , query_args , async move {"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "const LIST_MEMBERS_QUERY_KEY : & str = \"list_members\" ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "use __tauri_client_list_members :: { try_list_members , list_members , LIST_MEMBERS_QUERY_KEY , prefetch_list_members } ;"
+    ));
+    assert!(check_payload_options(&input, &parse_quote!(cached, by_value)).is_err());
+}
+
+#[test]
+fn test_swr_client_refreshes_in_background() {
+    let args: BridgeArgs = parse_quote!(cached(swr));
+    let input: ItemFn = parse_quote! {
+        pub fn team_name(team: u32) -> String {
+            String::new()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: query :: stale_while_revalidate :: < String , _ > (\"team_name\" , query_args , async move {"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "} , crate :: spawn_local ,) . await"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub fn on_team_name_refreshed (on_refresh : impl Fn (String) + 'static ,) -> tauri_bridge_runtime :: subscription :: Subscription { tauri_bridge_runtime :: query :: subscribe_refreshed :: < String > (\"team_name\" , on_refresh ,) }"
+    ));
+    assert!(!contains_pattern(
+        &generate_client(&input, &parse_quote!(cached)),
+        "on_team_name_refreshed"
+    ));
+
+    assert!(args.cached);
+    for attr in ["cached()", "cached(ttl)", "cached = true"] {
+        assert!(syn::parse_str::<BridgeArgs>(attr).is_err());
+    }
+    assert!(check_payload_options(&input, &parse_quote!(cached(swr), catch_panic)).is_err());
+}
+
+#[test]
+fn test_persisted_client_restores_and_saves_responses() {
+    let args: BridgeArgs = parse_quote!(cached(swr, persist));
+    let input: ItemFn = parse_quote! {
+        pub fn team_name(team: u32) -> String {
+            String::new()
+        }
+    };
+    let version = schema_hash(&method_spec(&input, "team_name").to_string());
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        &format!(
+            "tauri_bridge_runtime :: persist :: restore :: < String > (\"team_name\" , \"{}\" , & query_args ,) ;",
+            version
+        )
+    ));
+    assert!(contains_pattern(
+        &client,
+        &format!(
+            "tauri_bridge_runtime :: persist :: saving (\"team_name\" , \"{}\" , persisted_args , async move {{",
+            version
+        )
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: query :: stale_while_revalidate :: < String , _ >"
+    ));
+    assert!(!contains_pattern(
+        &generate_client(&input, &parse_quote!(cached)),
+        "tauri_bridge_runtime :: persist"
+    ));
+
+    // Same hash as `tauri_bridge_runtime::introspect::schema_hash`
+    assert_eq!(schema_hash(""), "cbf29ce484222325");
+    assert_eq!(schema_hash("a"), "af63dc4c8601ec8c");
+    // The version changes with the command's types
+    let widened: ItemFn = parse_quote! {
+        pub fn team_name(team: u64) -> String {
+            String::new()
+        }
+    };
+    assert_ne!(
+        version,
+        schema_hash(&method_spec(&widened, "team_name").to_string())
+    );
+
+    assert!(args.swr && args.persist && args.cached);
+    let persisted: BridgeArgs = parse_quote!(cached(persist));
+    assert!(persisted.persist && !persisted.swr);
+    assert!(syn::parse_str::<BridgeArgs>("cached(persist = true)").is_err());
+}
+
+#[test]
+fn test_cached_client_prefetches_into_cache() {
+    let input: ItemFn = parse_quote! {
+        pub fn list_members(team: u32) -> Vec<String> {
+            vec![]
+        }
+    };
+
+    let client = generate_client(&input, &parse_quote!(cached));
+
+    assert!(contains_pattern(
+        &client,
+        "pub async fn prefetch_list_members (team : u32) -> Result < () , String > { try_list_members (team) . await . map (| _ | ()) }"
+    ));
+    let client = generate_client(&input, &parse_quote!());
+    assert!(!contains_pattern(&client, "prefetch_list_members"));
+}
+
+#[test]
+fn test_startup_cache_client_reads_fetched_value() {
+    let args: BridgeArgs = parse_quote!(sync_cache = "startup");
+    assert!(args.startup_cache);
+    let input: ItemFn = parse_quote! {
+        pub fn get_app_version() -> String {
+            String::new()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "pub async fn init_get_app_version () -> Result < () , String > { let value = try_get_app_version () . await ? ; tauri_bridge_runtime :: startup :: set (\"get_app_version\" , value) ; Ok (()) }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub fn get_app_version () -> String { tauri_bridge_runtime :: startup :: expect :: < String > (\"get_app_version\" , \"init_get_app_version\") }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub use __tauri_client_get_app_version :: { try_get_app_version , get_app_version , init_get_app_version"
+    ));
+    assert!(!contains_pattern(&client, "async fn get_app_version"));
+
+    assert!(syn::parse_str::<BridgeArgs>("sync_cache = \"lazy\"").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("sync_cache").is_err());
+}
+
+#[test]
+fn test_startup_cache_checks() {
+    let args: BridgeArgs = parse_quote!(sync_cache = "startup");
+    let getter: ItemFn = parse_quote! { pub fn get_app_version() -> String {} };
+    let injected: ItemFn = parse_quote! {
+        pub fn get_window_title(#[caller_label] label: String) -> String {}
+    };
+    let with_param: ItemFn = parse_quote! { pub fn get_setting(key: String) -> String {} };
+    let generic: ItemFn = parse_quote! { pub fn get_default<T: Default>() -> T {} };
+
+    assert!(check_startup_cache(&getter, &args).is_ok());
+    assert!(check_startup_cache(&injected, &args).is_ok());
+    assert!(check_startup_cache(&with_param, &args).is_err());
+    assert!(check_startup_cache(&generic, &args).is_err());
+    assert!(check_startup_cache(&with_param, &parse_quote!()).is_ok());
+    for args in [
+        parse_quote!(sync_cache = "startup", cached),
+        parse_quote!(sync_cache = "startup", incremental),
+    ] {
+        assert!(check_startup_cache(&getter, &args).is_err());
+    }
+}
+
+#[test]
+fn test_prefetch_all_runs_listed_prefetches() {
+    let input: PrefetchInput = parse_quote!(
+        list_projects,
+        api::get_user(user_id, true),
+        #[cfg(feature = "admin")]
+        list_audits(),
+    );
+
+    let tokens = generate_prefetch_all(&input);
+
+    assert!(contains_pattern(
+        &tokens,
+        "fetches . push (:: std :: boxed :: Box :: pin (async { let _ = prefetch_list_projects () . await ; })) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "let _ = api :: prefetch_get_user (user_id , true) . await ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (feature = \"admin\")] fetches . push (:: std :: boxed :: Box :: pin (async { let _ = prefetch_list_audits () . await ; })) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "tauri_bridge_runtime :: query :: prefetch_all (fetches)"
+    ));
+    assert!(syn::parse_str::<PrefetchInput>("#[doc = \"x\"] list_projects").is_err());
+}
+
+#[test]
+fn test_join_calls_listed_commands_concurrently() {
+    let input: PrefetchInput = parse_quote!(api::get_user(user_id, true), list_projects);
+
+    let tokens = generate_join(&input).unwrap();
+
+    assert!(contains_pattern(
+        &tokens,
+        "let (mut __tauri_bridge_joined_0 , mut __tauri_bridge_joined_1 ,) = (tauri_bridge_runtime :: join :: Joined :: new (api :: try_get_user (user_id , true)) , tauri_bridge_runtime :: join :: Joined :: new (try_list_projects ()) ,) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "let done = [__tauri_bridge_joined_0 . poll (cx) , __tauri_bridge_joined_1 . poll (cx)] ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "__tauri_bridge_joined_0 . take () . map_err (| error | tauri_bridge_runtime :: join :: JoinError :: new (\"get_user\" , error)) ?"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "JoinError :: new (\"list_projects\" , error)) ? ,))"
+    ));
+
+    let empty: PrefetchInput = parse_quote!();
+    assert!(generate_join(&empty).is_err());
+    let with_cfg: PrefetchInput = parse_quote!(
+        #[cfg(feature = "admin")]
+        list_audits()
+    );
+    assert!(generate_join(&with_cfg).is_err());
+}
+
+#[test]
+fn test_invalidates_drops_keys_after_calls() {
+    let args: BridgeArgs = parse_quote!(invalidates("list_members", "team_size"));
+    let input: ItemFn = parse_quote! {
+        pub fn add_member(team: u32, name: String) -> u64 {
+            0
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "if response . is_ok () { tauri_bridge_runtime :: query :: invalidate (& [\"list_members\" , \"team_size\"]) ; }"
+    ));
+    assert!(!contains_pattern(&client, "query :: cached"));
+
+    // Mutations returning `Err` or panicking leave the cache alone
+    let fallible: ItemFn = parse_quote! {
+        pub fn remove_member(team: u32, name: String) -> Result<(), String> {
+            Ok(())
+        }
+    };
+    let client = generate_client(&fallible, &args);
+    assert!(contains_pattern(
+        &client,
+        "if matches ! (response , Ok (Ok (_))) {"
+    ));
+    let args: BridgeArgs = parse_quote!(invalidates("list_members"), catch_panic);
+    let client = generate_client(&fallible, &args);
+    assert!(contains_pattern(
+        &client,
+        "if matches ! (response , Ok (tauri_bridge_runtime :: panic :: Caught :: Value (Ok (_)))) {"
+    ));
+    assert!(syn::parse_str::<BridgeArgs>("invalidates()").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("invalidates(\"\")").is_err());
+}
+
+// ==================== Rebind Tests ====================
+
+#[test]
+fn test_rebind_reissues_invoke() {
+    let args: BridgeArgs = parse_quote!(rebind, with_options);
+    let input: ItemFn = parse_quote! {
+        pub fn archive(id: u64) -> u64 {
+            id
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let result = tauri_bridge_runtime :: connection :: rebound (|| { let args = args . clone () ; crate :: invoke (\"archive\" , args) }) . await ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let result = crate :: invoke_with_options (\"archive\" , args , options) . await ;"
+    ));
+}
+
+#[test]
+fn test_rebind_with_rejections() {
+    let args: BridgeArgs = parse_quote!(rebind, rejections);
+    let input: ItemFn = parse_quote! {
+        pub fn archive(id: u64) -> Result<u64, String> {
+            Ok(id)
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let result = match tauri_bridge_runtime :: connection :: rebound (|| { let args = args . clone () ; crate :: try_invoke (\"archive\" , args) }) . await { Ok (result) => result ,"
+    ));
+
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        rebind = true
+        "#,
+    )
+    .unwrap();
+    assert!(BridgeArgs::default().with_defaults(&config).rebind);
+}
+
+// ==================== Replay Tests ====================
+
+#[test]
+fn test_replayable_client_records_and_replays() {
+    let args: BridgeArgs = parse_quote!(replayable);
+    let input: ItemFn = parse_quote! {
+        pub fn list_projects(owner: u64) -> Vec<String> {
+            vec![]
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let replayed = tauri_bridge_runtime :: replay :: replayed (\"list_projects\" ,"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let result = match replayed { Some (response) => js_sys :: JSON :: parse (& response ?)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: replay :: is_recording () . then ("
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let result = crate :: invoke (\"list_projects\" , args) . await ; tauri_bridge_runtime :: replay :: record (\"list_projects\" , __tauri_bridge_recorded_ms ,"
+    ));
+
+    let plain = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&plain, "replay"));
+}
+
+#[test]
+fn test_replayable_needs_json_responses() {
+    let input: ItemFn = parse_quote! {
+        pub fn read_blob(id: u64) -> Vec<u8> {
+            vec![]
+        }
+    };
+    let error = check_replayable(&input, &parse_quote!(replayable)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`replayable` doesn't support `raw` byte responses, the default for `Vec<u8>`"
+    );
+
+    let input: ItemFn = parse_quote! {
+        pub fn export_rows(table: String) -> Vec<String> {
+            vec![]
+        }
+    };
+    assert!(check_replayable(&input, &parse_quote!(replayable, chunked)).is_err());
+    assert!(check_replayable(&input, &parse_quote!(replayable)).is_ok());
+
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        replayable = true
+        "#,
+    )
+    .unwrap();
+    assert!(config.replayable);
+}
+
+// ==================== Signing Tests ====================
+
+#[test]
+fn test_signed_client_goes_through_hooks() {
+    let args: BridgeArgs = parse_quote!(signed, with_options);
+    let input: ItemFn = parse_quote! {
+        pub fn transfer(to: String, cents: u64) -> u64 {
+            cents
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "let args = tauri_bridge_runtime :: signing :: sign (\"transfer\" , args) ? ;"
+    ));
+    // Both the plain and the `with_options` invoke are verified
+    assert_eq!(
+        normalize_tokens(&client)
+            .matches("tauri_bridge_runtime :: signing :: verify (\"transfer\" , result) ?")
+            .count(),
+        2
+    );
+
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&client, "signing"));
+}
+
+#[test]
+fn test_signed_conflicts() {
+    let input: ItemFn = parse_quote! {
+        pub fn export(since: u64) -> Vec<u8> { Vec::new() }
+    };
+
+    for attr in [
+        quote::quote!(signed, chunked),
+        quote::quote!(signed, incremental),
+        quote::quote!(signed, raw),
+    ] {
+        let args: BridgeArgs = syn::parse2(attr).unwrap();
+        assert!(check_payload_options(&input, &args).is_err());
+    }
+    let args: BridgeArgs = parse_quote!(signed, compress = "gzip");
+    assert!(check_payload_options(&input, &args).is_ok());
+}
+
+// ==================== Error Report Tests ====================
+
+#[test]
+fn test_report_errors_rewrites_anyhow_results() {
+    let mut input: ItemFn = parse_quote! {
+        pub fn load(id: u64) -> anyhow::Result<Profile> {
+            fetch(id)
+        }
+    };
+
+    report_errors(&mut input, false);
+
+    let expected: ItemFn = parse_quote! {
+        pub fn load(id: u64) -> Result<Profile, tauri_bridge_runtime::error::ErrorReport> {
+            let __tauri_bridge_response: anyhow::Result<Profile> =
+                (|| -> anyhow::Result<Profile> { fetch(id) })();
+            __tauri_bridge_response.map_err(tauri_bridge_runtime::error::ErrorReport::from_report)
+        }
+    };
+    assert_eq!(
+        quote::quote!(#input).to_string(),
+        quote::quote!(#expected).to_string()
+    );
+}
+
+#[test]
+fn test_report_errors_detects_report_error_types() {
+    let reported = |mut input: ItemFn| {
+        report_errors(&mut input, true);
+        let output = &input.sig.output;
+        quote::quote!(#output).to_string()
+    };
+
+    assert_eq!(
+        reported(parse_quote!(
+            async fn a() -> Result<u8, eyre::Report> {}
+        )),
+        "-> Result < u8 , tauri_bridge_runtime :: error :: ErrorReport >"
+    );
+    assert_eq!(
+        reported(parse_quote!(
+            fn b() -> ::color_eyre::Result<()> {}
+        )),
+        "-> Result < () , tauri_bridge_runtime :: error :: ErrorReport >"
+    );
+    // Only spelled-out paths are recognized
+    assert_eq!(
+        reported(parse_quote!(
+            fn c() -> Result<u8> {}
+        )),
+        "-> Result < u8 >"
+    );
+    assert_eq!(
+        reported(parse_quote!(
+            fn d() -> Result<u8, String> {}
+        )),
+        "-> Result < u8 , String >"
+    );
+    assert_eq!(
+        reported(parse_quote!(
+            fn e() -> anyhow::Result<u8, MyError> {}
+        )),
+        "-> anyhow :: Result < u8 , MyError >"
+    );
+}
+
+#[test]
+fn test_error_conversion_rewrites_result() {
+    let args: BridgeArgs = parse_quote!(map_err = "AppError::internal");
+    let mut input: ItemFn = parse_quote! {
+        pub async fn load(path: String) -> std::io::Result<String> {
+            read(path).await
+        }
+    };
+
+    convert_command_errors(&mut input, false, args.error_conversion.as_ref().unwrap()).unwrap();
+
+    let expected: ItemFn = parse_quote! {
+        pub async fn load(path: String) -> Result<String, AppError> {
+            let __tauri_bridge_response: std::io::Result<String> = async { read(path).await }.await;
+            __tauri_bridge_response.map_err(AppError::internal)
+        }
+    };
+    assert_eq!(
+        quote::quote!(#input).to_string(),
+        quote::quote!(#expected).to_string()
+    );
+
+    let args: BridgeArgs = parse_quote!(into_error = AppError);
+    let mut input: ItemFn = parse_quote! {
+        fn parse(text: String) -> Result<u32, ParseIntError> {}
+    };
+    convert_command_errors(&mut input, true, args.error_conversion.as_ref().unwrap()).unwrap();
+    let output = &input.sig.output;
+    assert_eq!(
+        quote::quote!(#output).to_string(),
+        "-> Result < u32 , AppError >"
+    );
+}
+
+#[test]
+fn test_error_conversion_rejects_invalid_options() {
+    let args: BridgeArgs = parse_quote!(into_error = AppError);
+    let mut input: ItemFn = parse_quote! {
+        fn count() -> u32 { 1 }
+    };
+    assert!(
+        convert_command_errors(&mut input, false, args.error_conversion.as_ref().unwrap()).is_err()
+    );
+
+    assert!(syn::parse2::<BridgeArgs>(quote::quote!(map_err = "into_app_error")).is_err());
+    assert!(
+        syn::parse2::<BridgeArgs>(quote::quote!(
+            into_error = AppError,
+            map_err = "AppError::from"
+        ))
+        .is_err()
+    );
+}
+
+// ==================== Bridge Error Tests ====================
+
+#[test]
+fn test_bridge_error_tags_variants_with_codes() {
+    let expanded = expand_error(
+        quote::quote! {},
+        quote::quote! {
+            pub enum NoteError {
+                NotFound(u64),
+                #[bridge_error(code = "E_LOCKED")]
+                Locked { by: String },
+                Storage,
+            }
+        },
+    );
+
+    assert!(contains_pattern(
+        &expanded,
+        "# [derive (serde :: Serialize , serde :: Deserialize)] # [serde (tag = \"code\" , content = \"details\")] pub enum NoteError { # [serde (rename = \"not_found\")] NotFound (u64) , # [serde (rename = \"E_LOCKED\")] Locked { by : String } , # [serde (rename = \"storage\")] Storage , }"
+    ));
+    assert!(contains_pattern(
+        &expanded,
+        "pub const CODES : & 'static [& 'static str] = & [\"not_found\" , \"E_LOCKED\" , \"storage\"] ;"
+    ));
+    assert!(contains_pattern(
+        &expanded,
+        "match self { Self :: NotFound (..) => \"not_found\" , Self :: Locked { .. } => \"E_LOCKED\" , Self :: Storage => \"storage\" , }"
+    ));
+    assert!(contains_pattern(
+        &expanded,
+        "pub fn is_locked (& self) -> bool { matches ! (self , Self :: Locked { .. }) }"
+    ));
+}
+
+#[test]
+fn test_bridge_error_rejects_invalid_input() {
+    let duplicate = expand_error(
+        quote::quote! {},
+        quote::quote! {
+            enum SyncError {
+                Conflict,
+                #[bridge_error(code = "conflict")]
+                Retry,
+            }
+        },
+    );
+    assert!(
+        duplicate
+            .to_string()
+            .contains("duplicate error code `conflict`")
+    );
+
+    let with_options = expand_error(quote::quote! { codes }, quote::quote! { enum E {} });
+    assert!(with_options.to_string().contains("takes no options"));
+
+    let not_enum = expand_error(quote::quote! {}, quote::quote! { struct E; });
+    assert!(not_enum.to_string().contains("compile_error"));
+}
+
+// ==================== Round Trip Tests ====================
+
+#[test]
+fn test_roundtrip_tests_cover_args_and_response() {
+    let args = BridgeArgs {
+        proptest: true,
+        ..Default::default()
+    };
+    let input: ItemFn = parse_quote! {
+        pub fn save_note(note_title: &str, tags: &[String], #[caller_label] label: String) -> Result<u64, SaveError> {
+            todo!()
+        }
+    };
+
+    let tests = generate_roundtrip_tests(&input, &args);
+
+    assert!(contains_pattern(
+        &tests,
+        "# [cfg (test)] # [doc (hidden)] mod __tauri_proptest_save_note"
+    ));
+    assert!(contains_pattern(
+        &tests,
+        "# [serde (rename_all = \"camelCase\")] struct Args { note_title : String , tags : Vec < String > }"
+    ));
+    assert!(contains_pattern(
+        &tests,
+        "fn args_round_trip (note_title in :: proptest :: prelude :: any :: < String > () , tags in :: proptest :: prelude :: any :: < Vec < String > > ())"
+    ));
+    assert!(contains_pattern(
+        &tests,
+        "fn ok_response_round_trip (response in :: proptest :: prelude :: any :: < u64 > ())"
+    ));
+    assert!(contains_pattern(
+        &tests,
+        "fn error_response_round_trip (response in :: proptest :: prelude :: any :: < SaveError > ())"
+    ));
+    assert!(!contains_pattern(&tests, "label"));
+}
+
+#[test]
+fn test_roundtrip_tests_skip_raw_responses_and_unit() {
+    let args = BridgeArgs {
+        proptest: true,
+        ..Default::default()
+    };
+    let bytes: ItemFn = parse_quote! {
+        pub fn thumbnail(id: u32) -> Vec<u8> {
+            vec![]
+        }
+    };
+    let unit: ItemFn = parse_quote! {
+        pub fn ping() {}
+    };
+
+    assert!(!contains_pattern(
+        &generate_roundtrip_tests(&bytes, &args),
+        "response_round_trip"
+    ));
+    assert!(!contains_pattern(
+        &generate_roundtrip_tests(&unit, &args),
+        "round_trip"
+    ));
+    assert!(
+        check_proptest(&parse_quote! {
+            pub fn lookup(key: Option<&str>) {}
+        })
+        .is_err()
+    );
+}
+
+#[cfg(not(feature = "proptest"))]
+#[test]
+fn test_proptest_needs_feature() {
+    assert!(syn::parse_str::<BridgeArgs>("proptest").is_err());
+}
+
+// ==================== Deprecation Tests ====================
+
+#[test]
+fn test_client_attrs_forwarded_to_client_items() {
+    let args: BridgeArgs = parse_quote!(client_attrs(allow, lint::marker));
+    let input: ItemFn = parse_quote! {
+        #[allow(clippy::too_many_arguments)]
+        #[inline]
+        #[lint::marker]
+        pub fn plot(x: f64, y: f64) {}
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "# [allow (clippy :: too_many_arguments)] # [lint :: marker] # [derive (serde :: Serialize , serde :: Deserialize)] # [serde (rename_all = \"camelCase\")] struct PlotArgs"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "# [allow (clippy :: too_many_arguments)] # [lint :: marker] pub async fn try_plot"
+    ));
+    assert!(!contains_pattern(&client, "inline"));
+    assert!(
+        BridgeArgs::default()
+            .forwarded_client_attrs(&input.attrs)
+            .is_empty()
+    );
+    assert!(syn::parse_str::<BridgeArgs>("client_attrs = allow").is_err());
+}
+
+#[test]
+fn test_client_names_rename_generated_items() {
+    let args: BridgeArgs = parse_quote!(
+        with_options,
+        client_names(try_fn = greet_checked, args = GreetRequest)
+    );
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(&client, "struct GreetRequest"));
+    assert!(contains_pattern(&client, "pub async fn greet_checked"));
+    assert!(contains_pattern(
+        &client,
+        "pub async fn greet_checked_with_options"
+    ));
+    assert!(contains_pattern(&client, "pub async fn greet <"));
+    assert!(!contains_pattern(&client, "GreetArgs"));
+    assert!(!contains_pattern(&client, "try_greet"));
+    assert!(syn::parse_str::<BridgeArgs>("client_names(module = greet_mod)").is_err());
+    assert!(syn::parse_str::<BridgeArgs>("client_names(args = \"GreetRequest\")").is_err());
+}
+
+#[test]
+fn test_deprecated_forwarded_to_client() {
+    let input: ItemFn = parse_quote! {
+        #[deprecated(note = "use greet_v2")]
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let client = generate_client(&input, &BridgeArgs::default());
+
+    assert!(contains_pattern(
+        &client,
+        "# [deprecated (note = \"use greet_v2\")] pub async fn try_greet"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "# [deprecated (note = \"use greet_v2\")] pub async fn greet"
+    ));
+}
+
+#[test]
+fn test_deprecation_note_forms() {
+    let plain: ItemFn = parse_quote!(
+        #[deprecated]
+        fn a() {}
+    );
+    let name_value: ItemFn = parse_quote!(
+        #[deprecated = "gone"]
+        fn a() {}
+    );
+    let list: ItemFn = parse_quote!(
+        #[deprecated(since = "0.2", note = "gone")]
+        fn a() {}
+    );
+    let none: ItemFn = parse_quote!(
+        fn a() {}
+    );
+
+    assert_eq!(deprecation_note(&plain.attrs), Some(None));
+    assert_eq!(
+        deprecation_note(&name_value.attrs),
+        Some(Some("gone".into()))
+    );
+    assert_eq!(deprecation_note(&list.attrs), Some(Some("gone".into())));
+    assert_eq!(deprecation_note(&none.attrs), None);
+}
+
+#[test]
+fn test_log_deprecated_backend_notice() {
+    let input: ItemFn = parse_quote! {
+        #[deprecated = "use greet_v2"]
+        pub fn greet() -> String {
+            "Hello!".to_string()
+        }
+    };
+
+    let quiet = generate_backend(&input, &BridgeArgs::default());
+    let args: BridgeArgs = parse_quote!(log_deprecated);
+    let logged = generate_backend(&input, &args);
+
+    assert!(!contains_pattern(&quiet, "eprintln !"));
+    assert!(contains_pattern(
+        &logged,
+        "eprintln ! (\"[tauri-bridge] deprecated command `greet` invoked: use greet_v2\")"
+    ));
+    assert!(syn::parse_str::<BridgeArgs>("log_deprecated = true").is_err());
+}
+
+// ==================== Owned Params Tests ====================
+
+#[test]
+fn test_owned_params_client_signature() {
+    let args: BridgeArgs = parse_quote!(owned_params);
+    let input: ItemFn = parse_quote! {
+        pub fn tag(label: &str, ids: &[u64], user: &User, count: u32) -> String {
+            String::new()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    // Client functions no longer borrow
+    assert!(contains_pattern(
+        &client,
+        "pub async fn try_tag (label : impl AsRef < str > , ids : impl AsRef < [u64] > , user : User , count : u32)"
+    ));
+    // The args struct still borrows for serialization
+    assert!(contains_pattern(&client, "struct TagArgs < 'a >"));
+    assert!(contains_pattern(
+        &client,
+        "TagArgs { label : label . as_ref () , ids : ids . as_ref () , user : & user , count }"
+    ));
+}
+
+#[test]
+fn test_into_params_client_signature() {
+    let args: BridgeArgs = parse_quote!(into_params);
+    let input: ItemFn = parse_quote! {
+        pub fn create_user(name: String, roles: Vec<String>, note: Option<String>) -> u64 {
+            0
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "async fn try_create_user (name : impl Into < String > , roles : impl IntoIterator < Item = impl Into < String >> , note : Option < String >)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "CreateUserArgs { name : name . into () , roles : roles . into_iter () . map (Into :: into) . collect () , note }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "try_create_user (name , roles , note) . await"
+    ));
+
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        into_params = true
+        "#,
+    )
+    .unwrap();
+    assert!(BridgeArgs::default().with_defaults(&config).into_params);
+}
+
+#[test]
+fn test_owned_params_keeps_nested_references() {
+    let args: BridgeArgs = parse_quote!(owned_params);
+    let input: ItemFn = parse_quote! {
+        pub fn find(name: &str, tags: Vec<&str>) -> u32 {
+            0
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(&client, "async fn try_find < 'a >"));
+    assert!(contains_pattern(&client, "name : impl AsRef < str >"));
+    assert!(contains_pattern(&client, "tags : Vec < & 'a str >"));
+}
+
+#[test]
+fn test_own_args_struct_has_no_lifetime() {
+    let args: BridgeArgs = parse_quote!(own_args);
+    let input: ItemFn = parse_quote! {
+        pub fn tag(label: &str, ids: &[u64], user: &User, count: u32) -> String {
+            String::new()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "pub struct TagArgs { pub label : String , pub ids : Vec < u64 > , pub user : User , pub count : u32 }"
+    ));
+    // Client functions keep the backend signature and convert
+    assert!(contains_pattern(
+        &client,
+        "async fn try_tag < 'a > (label : & 'a str"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "TagArgs { label : label . to_owned () , ids : ids . to_vec () , user : user . clone () , count }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "pub use __tauri_client_tag :: { try_tag , tag , TagArgs }"
+    ));
+}
+
+#[test]
+fn test_own_args_with_owned_params() {
+    let args: BridgeArgs = parse_quote!(own_args, owned_params);
+    let input: ItemFn = parse_quote! {
+        pub fn tag(label: &str, ids: &[u64], user: &User) -> String {
+            String::new()
+        }
+    };
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "TagArgs { label : label . as_ref () . to_owned () , ids : ids . as_ref () . to_vec () , user }"
+    ));
+    assert!(!contains_pattern(&client, "'a"));
+}
+
+// ==================== Crate Path Tests ====================
+
+#[test]
+fn test_crate_paths_rename_serde_dependencies() {
+    let input: ItemFn = parse_quote! {
+        pub fn rename_label(id: u32, name: String) -> Result<String, String> {
+            Ok(name)
+        }
+    };
+    let args: BridgeArgs = parse_quote!(crate_paths(
+        serde = "deps::serde",
+        serde_wasm_bindgen = "swb"
+    ));
+
+    let client = generate_client(&input, &args);
+
+    assert!(contains_pattern(
+        &client,
+        "# [derive (deps :: serde :: Serialize , deps :: serde :: Deserialize)] # [serde (crate = \"deps::serde\")]"
+    ));
+    assert!(contains_pattern(&client, "swb :: to_value (&"));
+    assert!(contains_pattern(&client, "swb :: from_value :: <"));
+    assert!(!contains_pattern(&client, "serde_wasm_bindgen"));
+
+    let default = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(
+        &default,
+        "# [derive (serde :: Serialize , serde :: Deserialize)] # [serde (rename_all"
+    ));
+
+    for attr in [
+        "crate_paths",
+        "crate_paths(serde_json = \"json\")",
+        "crate_paths(serde = my_serde)",
+        "crate_paths(serde = \"not a path\")",
+    ] {
+        assert!(syn::parse_str::<BridgeArgs>(attr).is_err());
+    }
+}
+
+#[test]
+fn test_crate_paths_from_crate_config() {
+    let config = parse_crate_config(
+        r#"
+        [package.metadata.tauri-bridge]
+        crate_paths = { serde = "my_serde", serde_wasm_bindgen = "swb" }
+        "#,
+    )
+    .unwrap();
+
+    let args = BridgeArgs::default().with_defaults(&config);
+    assert_eq!(args.serde_path().to_string(), "my_serde");
+    assert_eq!(args.serde_wasm_bindgen_path().to_string(), "swb");
+
+    // The command's own paths win
+    let args: BridgeArgs = parse_quote!(crate_paths(serde_wasm_bindgen = "own_swb"));
+    let args = args.with_defaults(&config);
+    assert_eq!(args.serde_path().to_string(), "my_serde");
+    assert_eq!(args.serde_wasm_bindgen_path().to_string(), "own_swb");
+
+    for manifest in [
+        "[package.metadata.tauri-bridge]\ncrate_paths = \"my_serde\"\n",
+        "[package.metadata.tauri-bridge]\ncrate_paths = { serde_json = \"json\" }\n",
+        "[package.metadata.tauri-bridge]\ncrate_paths = { serde = 1 }\n",
+    ] {
+        assert!(parse_crate_config(manifest).is_err());
+    }
+}
+
+// ==================== Map Entries Tests ====================
+
+#[test]
+fn test_map_as_entries_sends_pairs() {
+    let input: ItemFn = parse_quote! {
+        pub fn merge_scores(
+            #[bridge(map_as = "entries")] bonus: HashMap<u32, u64>,
+            round: u32,
+        ) -> Result<BTreeMap<u32, u64>, String> {
+            todo!()
+        }
+    };
+    let args: BridgeArgs = parse_quote!(map_as = "entries");
+    assert!(args.map_entries);
+
+    let backend = generate_backend(&input, &args);
+    assert!(contains_pattern(
+        &backend,
+        "fn __tauri_bridge_merge_scores (bonus : HashMap < u32 , u64 > , round : u32 ,)"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "bonus : tauri_bridge_runtime :: entries :: Entries < HashMap < u32 , u64 > > , round : u32"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "-> Result < tauri_bridge_runtime :: entries :: Entries < BTreeMap < u32 , u64 > > , String >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "__tauri_bridge_merge_scores (bonus . 0 , round)"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "result . map (tauri_bridge_runtime :: entries :: Entries)"
+    ));
+
+    let client = generate_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "# [serde (with = \"tauri_bridge_runtime::entries\")] bonus : HashMap < u32 , u64 >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "from_value :: < Result < tauri_bridge_runtime :: entries :: Entries < BTreeMap < u32 , u64 > > , String > >"
+    ));
+
+    // Without the option on the command, only the parameter is wrapped
+    let backend = generate_backend(&input, &BridgeArgs::default());
+    assert!(contains_pattern(
+        &backend,
+        "-> Result < BTreeMap < u32 , u64 > , String >"
+    ));
+    assert!(contains_pattern(&backend, "bonus . 0"));
+
+    let description =
+        describe(quote::quote! { map_as = "entries" }, quote::quote!(#input)).unwrap();
+    assert_eq!(description.args[0].wire_type, "Vec<(u32, u64)>");
+
+    // `strict` accepts integer keys sent as entries
+    assert!(describe(quote::quote! { strict }, quote::quote!(#input)).is_err());
+    assert!(
+        describe(
+            quote::quote! { strict, map_as = "entries" },
+            quote::quote!(#input)
+        )
+        .is_ok()
+    );
+}
+
+#[test]
+fn test_map_as_entries_rejects_misuse() {
+    for (attr, item, message) in [
+        (
+            quote::quote! { map_as = "pairs" },
+            quote::quote! { pub fn scores() -> HashMap<u32, u64> { todo!() } },
+            "expected `\"entries\"`",
+        ),
+        (
+            quote::quote! {},
+            quote::quote! { pub fn scores(#[bridge(map_as = "pairs")] bonus: HashMap<u32, u64>) {} },
+            "expected `map_as = \"entries\"`",
+        ),
+        (
+            quote::quote! { map_as = "entries" },
+            quote::quote! { pub fn reset_scores() {} },
+            "has no response",
+        ),
+        (
+            quote::quote! {},
+            quote::quote! { pub fn scores(#[bridge(map_as = "entries")] bonus: &HashMap<u32, u64>) {} },
+            "must be owned",
+        ),
+        (
+            quote::quote! { map_as = "entries", compress = "gzip" },
+            quote::quote! { pub fn scores() -> HashMap<u32, u64> { todo!() } },
+            "`map_as` can't be combined with `compress`",
+        ),
+        // Named after the option, even when set through its alias
+        (
+            quote::quote! { map_as = "entries", js_value },
+            quote::quote! { pub fn scores() -> HashMap<u32, u64> { todo!() } },
+            "`map_as` can't be combined with `raw_response`",
+        ),
+    ] {
+        let error = describe(attr, item).expect_err("expected an error");
+        assert!(error.to_string().contains(message), "{}", error);
+    }
+
+    // Crate-wide `layers` leaves these commands alone
+    let input: ItemFn = parse_quote! {
+        pub fn scores(#[bridge(map_as = "entries")] bonus: HashMap<u32, u64>) {}
+    };
+    assert!(check_layers(&input, &BridgeArgs::default()).is_err());
+}
+
+// ==================== Float Policy Tests ====================
+
+#[test]
+fn test_floats_policy_wraps_arguments_and_response() {
+    let input: ItemFn = parse_quote! {
+        pub fn record_samples(
+            label: &str,
+            samples: Vec<f64>,
+            #[caller_label] caller: String,
+        ) -> Result<Stats, String> {
+            todo!()
+        }
+    };
+    let args: BridgeArgs = parse_quote!(floats = "string");
+    assert_eq!(args.floats, Some(FloatPolicy::String));
+
+    let backend = generate_backend(&input, &args);
+    assert!(contains_pattern(
+        &backend,
+        "label : tauri_bridge_runtime :: floats :: Floats < String , tauri_bridge_runtime :: floats :: AsString >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "samples : tauri_bridge_runtime :: floats :: Floats < Vec < f64 > , tauri_bridge_runtime :: floats :: AsString >"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "-> Result < tauri_bridge_runtime :: floats :: Floats < Stats , tauri_bridge_runtime :: floats :: AsString > , String >"
+    ));
+    assert!(contains_pattern(&backend, "(& label . 0 , samples . 0 ,"));
+    assert!(contains_pattern(
+        &backend,
+        "result . map (tauri_bridge_runtime :: floats :: Floats :: new)"
+    ));
+
+    let client = generate_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "# [serde (with = \"tauri_bridge_runtime::floats::string\")] samples : Vec < f64 >"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "from_value :: < Result < tauri_bridge_runtime :: floats :: Floats < Stats , tauri_bridge_runtime :: floats :: AsString > , String > >"
+    ));
+
+    for (policy, marker) in [("error", "Reject"), ("null", "AsNull")] {
+        let args: BridgeArgs = syn::parse_str(&format!("floats = \"{}\"", policy)).unwrap();
+        let backend = normalize_tokens(&generate_backend(&input, &args));
+        assert!(backend.contains(&format!("tauri_bridge_runtime :: floats :: {}", marker)));
+    }
+    assert!(syn::parse_str::<BridgeArgs>("floats = \"zero\"").is_err());
+}
+
+#[test]
+fn test_floats_policy_checks() {
+    let input: ItemFn = parse_quote! {
+        pub fn mean(samples: Vec<f64>) -> f64 {
+            todo!()
+        }
+    };
+    // `strict` accepts floats sent under a policy
+    assert!(describe(quote::quote! { strict }, quote::quote!(#input)).is_err());
+    assert!(
+        describe(
+            quote::quote! { strict, floats = "error" },
+            quote::quote!(#input)
+        )
+        .is_ok()
+    );
+
+    for (attr, message) in [
+        (
+            quote::quote! { floats = "null", compress = "gzip" },
+            "`floats` can't be combined with `compress`",
+        ),
+        (
+            quote::quote! { floats = "null", map_as = "entries" },
+            "`floats` can't be combined with `map_as`",
+        ),
+        (
+            quote::quote! { floats = "null", js_value },
+            "`floats` can't be combined with `raw_response`",
+        ),
+    ] {
+        let error = describe(attr, quote::quote!(#input)).expect_err("expected an error");
+        assert!(error.to_string().contains(message), "{}", error);
+    }
+    let args: BridgeArgs = parse_quote!(floats = "string");
+    assert!(check_layers(&input, &args).is_err());
+}
+
+// ==================== Representation Tests ====================
+
+fn crate_types() -> CrateTypes {
+    let file: syn::File = parse_quote! {
+        #[derive(Serialize, Deserialize)]
+        #[serde(tag = "kind", content = "data", rename_all = "camelCase")]
+        pub enum Shape {
+            Circle(Option<f64>),
+            Square { side: f64 },
+        }
+
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct Drawing {
+            pub title: String,
+            pub shapes: Vec<Shape>,
+        }
+
+        pub mod events {
+            #[derive(Serialize)]
+            #[serde(tag = "type")]
+            pub enum Event {
+                Opened(Opened),
+                Closed,
+                Renamed(String),
+            }
+
+            #[derive(Serialize)]
+            pub struct Opened {
+                #[serde(flatten)]
+                pub extra: std::collections::HashMap<String, String>,
+            }
+        }
+
+        #[derive(Serialize)]
+        #[serde(untagged)]
+        pub enum Id {
+            Number(u64),
+            Name(String),
+        }
+
+        pub struct Plain {
+            pub id: u64,
+        }
+    };
+    let mut types = CrateTypes::new();
+    collect_types(&file.items, "", &mut types);
+    types
+}
+
+#[test]
+fn test_representations_of_crate_types() {
+    let types = crate_types();
+    assert_eq!(
+        types["Shape"].tagging,
+        Some(Tagging::Adjacent("kind".to_string(), "data".to_string()))
+    );
+    // Keyed by their module path
+    assert_eq!(
+        types["events::Event"].tagging,
+        Some(Tagging::Internal("type".to_string()))
+    );
+    assert_eq!(types["events::Opened"].tagging, Some(Tagging::Flatten));
+    assert_eq!(types["Id"].tagging, Some(Tagging::Untagged));
+    assert_eq!(types["Drawing"].tagging, None);
+    assert_eq!(types["Plain"].tagging, None);
+    // Only the newtype variant holding a string can't be tagged
+    assert_eq!(types["events::Event"].untaggable.len(), 1);
+    assert_eq!(types["events::Event"].untaggable[0].0, "Renamed");
+}
+
+#[test]
+fn test_tagged_parameters_are_sent_json_compatible() {
+    let types = crate_types();
+    // Reached through the fields of a struct of the crate
+    let input: ItemFn = parse_quote! {
+        pub fn save_drawing(drawing: Drawing) -> Result<(), String> {
+            todo!()
+        }
+    };
+    assert!(check_representations(&input, &types).unwrap());
+
+    let input: ItemFn = parse_quote! {
+        pub fn lookup(ids: Option<Vec<&Id>>) -> Plain {
+            todo!()
+        }
+    };
+    assert!(check_representations(&input, &types).unwrap());
+
+    // Tagged responses are decoded from JSON already
+    let input: ItemFn = parse_quote! {
+        pub fn latest_drawing(id: u64, plain: Plain) -> Drawing {
+            todo!()
+        }
+    };
+    assert!(!check_representations(&input, &types).unwrap());
+
+    let input: ItemFn = parse_quote! {
+        pub fn save_drawing(drawing: Drawing) -> Result<(), String> {
+            todo!()
+        }
+    };
+    let args: BridgeArgs = parse_quote!(json_compatible);
+    assert!(args.json_compatible);
+    let client = generate_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "serde :: Serialize :: serialize (& SaveDrawingArgs { drawing } , & serde_wasm_bindgen :: Serializer :: json_compatible () ,)"
+    ));
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(!contains_pattern(&client, "json_compatible"));
+}
+
+#[test]
+fn test_untaggable_variants_are_rejected() {
+    let types = crate_types();
+    let input: ItemFn = parse_quote! {
+        pub fn emit(events: Vec<Event>) {
+            todo!()
+        }
+    };
+    let error = check_representations(&input, &types).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("`Event` is internally tagged with `#[serde(tag = \"type\")]`, so serde can't send its newtype variant `Renamed` holding `String`")
+    );
+
+    // Responses fail to serialize on the backend all the same
+    let input: ItemFn = parse_quote! {
+        pub fn last_event() -> Option<Event> {
+            todo!()
+        }
+    };
+    assert!(check_representations(&input, &types).is_err());
+
+    // Names defined twice can't be told apart, so they're left alone
+    let mut types = types;
+    let file: syn::File = parse_quote! {
+        pub struct Event {
+            pub name: String,
+        }
+    };
+    collect_types(&file.items, "", &mut types);
+    assert!(check_representations(&input, &types).is_ok());
+    // Unless the path tells them apart
+    let input: ItemFn = parse_quote! {
+        pub fn last_event() -> Option<crate::events::Event> {
+            todo!()
+        }
+    };
+    assert!(check_representations(&input, &types).is_err());
+}
+
+#[test]
+fn test_types_are_resolved_by_path() {
+    let file: syn::File = parse_quote! {
+        pub mod billing {
+            #[derive(Serialize)]
+            #[serde(tag = "state")]
+            pub enum Status {
+                Paid { at: u64 },
+                Due { on: u64 },
+            }
+
+            #[derive(Serialize)]
+            pub struct Invoice {
+                pub status: Status,
+            }
+        }
+
+        pub mod shipping {
+            #[derive(Serialize)]
+            pub enum Status {
+                Sent,
+                Delivered,
+            }
+
+            #[derive(Serialize)]
+            pub struct Parcel {
+                pub status: Status,
+            }
+        }
+    };
+    let mut types = CrateTypes::new();
+    collect_types(&file.items, "", &mut types);
+
+    let sends = |input: ItemFn| check_representations(&input, &types).unwrap();
+    assert!(sends(parse_quote! {
+        pub fn pay(status: billing::Status) {}
+    }));
+    assert!(!sends(parse_quote! {
+        pub fn ship(status: crate::shipping::Status) {}
+    }));
+    // Fields are resolved in the module of their type
+    assert!(sends(parse_quote! {
+        pub fn send_invoice(invoice: Invoice) {}
+    }));
+    assert!(!sends(parse_quote! {
+        pub fn send_parcel(parcel: shipping::Parcel) {}
+    }));
+    // Either one may be meant
+    assert!(sends(parse_quote! {
+        pub fn update(status: Status) {}
+    }));
+}
+
+// ==================== Self-Check Tests ====================
+
+#[test]
+fn test_selfcheck_generates_samplers() {
+    let input: ItemFn = parse_quote! {
+        pub fn save_note(
+            note: Note,
+            tag_ids: &[u32],
+            #[caller_label] caller: String,
+        ) -> Result<NoteId, String> {
+            todo!()
+        }
+    };
+    let args: BridgeArgs = parse_quote!(selfcheck);
+    assert!(args.selfcheck);
+
+    let backend = generate_selfcheck_backend(&input, &args);
+    assert!(contains_pattern(
+        &backend,
+        "fn __tauri_bridge_selfcheck_save_note (checker : & mut tauri_bridge_runtime :: selfcheck :: Checker)"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "checker . command :: < NoteId > (\"save_note\" , & [tauri_bridge_runtime :: selfcheck :: Arg :: of :: < Note > (\"note\") , tauri_bridge_runtime :: selfcheck :: Arg :: of :: < Vec < u32 > > (\"tagIds\")])"
+    ));
+    assert!(contains_pattern(&backend, "# [cfg (debug_assertions)]"));
+
+    let client = generate_selfcheck_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "struct SelfCheckArgs { note : Note , tag_ids : Vec < u32 > }"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let args = serde_wasm_bindgen :: to_value (& args)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let response : NoteId = serde_wasm_bindgen :: from_value (response)"
+    ));
+    assert!(!contains_pattern(&client, "caller"));
+
+    // Sent with the command's serializer settings
+    let args: BridgeArgs = parse_quote!(selfcheck, json_compatible);
+    let client = generate_selfcheck_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "serde :: Serialize :: serialize (& args , & serde_wasm_bindgen :: Serializer :: json_compatible ())"
+    ));
+}
+
+#[test]
+fn test_selfcheck_checks() {
+    let input: ItemFn = parse_quote! {
+        pub fn save_note(note: Note) -> Result<NoteId, String> {
+            todo!()
+        }
+    };
+    let args: BridgeArgs = parse_quote!(selfcheck);
+    assert!(check_selfcheck(&input, &args).is_ok());
+    // Without the option, nothing is checked
+    let args: BridgeArgs = parse_quote!(compress = "gzip");
+    assert!(check_selfcheck(&input, &args).is_ok());
+
+    for options in [
+        "selfcheck, compress = \"gzip\"",
+        "selfcheck, encrypt",
+        "selfcheck, signed",
+        "selfcheck, floats = \"string\"",
+        "selfcheck, map_as = \"entries\"",
+        "selfcheck, raw_response",
+    ] {
+        let args: BridgeArgs = syn::parse_str(options).unwrap();
+        assert!(
+            check_selfcheck(&input, &args).is_err(),
+            "{} should be rejected",
+            options
+        );
+    }
+
+    let args: BridgeArgs = parse_quote!(selfcheck);
+    let input: ItemFn = parse_quote! {
+        pub fn save<T: Store>(store: T) {
+            todo!()
+        }
+    };
+    let error = check_selfcheck(&input, &args).unwrap_err();
+    assert!(error.to_string().contains("generic commands"));
+
+    let input: ItemFn = parse_quote! {
+        pub fn save(note: Option<&Note>) {
+            todo!()
+        }
+    };
+    let error = check_selfcheck(&input, &args).unwrap_err();
+    assert!(error.to_string().contains("needs owned argument types"));
+}
+
+#[test]
+fn test_bridge_selfcheck_lists_commands() {
+    let input: IntrospectInput = parse_quote! {
+        notes::save_note,
+        #[cfg(feature = "tags")]
+        tag_note
+    };
+    let tokens = generate_selfcheck(&input);
+    assert!(contains_pattern(
+        &tokens,
+        "pub fn __bridge_selfcheck (echoes : Option < Vec < tauri_bridge_runtime :: selfcheck :: Echo >> ,) -> tauri_bridge_runtime :: selfcheck :: SelfCheck"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "notes :: __tauri_bridge_selfcheck_save_note (& mut checker) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (feature = \"tags\")] __tauri_bridge_selfcheck_tag_note (& mut checker) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "echoed = notes :: __tauri_bridge_client_selfcheck_save_note (& sample . command , sample . response . clone ()) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub async fn run_bridge_selfcheck ()"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (not (debug_assertions))] { Ok (tauri_bridge_runtime :: selfcheck :: Report :: default ()) }"
+    ));
+}
+
+// ==================== Bootstrap Tests ====================
+
+#[test]
+fn test_bootstrap_loads_response_for_bundle() {
+    let args: BridgeArgs = parse_quote!(bootstrap, sync_cache = "startup");
+    assert!(args.bootstrap);
+    let input: ItemFn = parse_quote! {
+        pub async fn get_quota() -> Result<u32, String> {
+            todo!()
+        }
+    };
+
+    let backend = generate_bootstrap_backend(&input, &args);
+    assert!(contains_pattern(
+        &backend,
+        "pub async fn __tauri_bridge_bootstrap_get_quota () -> tauri_bridge_runtime :: bootstrap :: Loaded { let __tauri_bridge_result = get_quota () . await ;"
+    ));
+    assert!(contains_pattern(
+        &backend,
+        "Err (error) => tauri_bridge_runtime :: bootstrap :: Loaded :: failed (\"get_quota\" , & error) ,"
+    ));
+
+    let client = generate_bootstrap_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "let value : u32 = serde_wasm_bindgen :: from_value (value)"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "let response : Result < u32 , String > = Ok (value) ; tauri_bridge_runtime :: startup :: set :: < Result < u32 , String > > (\"get_quota\" , response) ;"
+    ));
+}
+
+#[test]
+fn test_bootstrap_primes_query_cache() {
+    let args: BridgeArgs = parse_quote!(bootstrap, cached, rename = "projects");
+    let input: ItemFn = parse_quote! {
+        pub fn list_projects() -> Vec<Project> {
+            todo!()
+        }
+    };
+
+    let backend = generate_bootstrap_backend(&input, &args);
+    assert!(contains_pattern(
+        &backend,
+        "tauri_bridge_runtime :: bootstrap :: Loaded :: new (\"projects\" , & __tauri_bridge_result)"
+    ));
+
+    let client = generate_bootstrap_client(&input, &args);
+    assert!(contains_pattern(
+        &client,
+        "tauri_bridge_runtime :: query :: insert :: < Vec < Project > > (\"projects\" , tauri_bridge_runtime :: bootstrap :: NO_QUERY_ARGS . to_string () , response ,) ;"
+    ));
+    assert!(contains_pattern(
+        &client,
+        "if command != \"projects\" { return None ; }"
+    ));
+}
+
+#[test]
+fn test_bootstrap_checks() {
+    let getter: ItemFn = parse_quote! { pub fn list_projects() -> Vec<Project> {} };
+    assert!(check_bootstrap(&getter, &parse_quote!(bootstrap, cached)).is_ok());
+    assert!(check_bootstrap(&getter, &parse_quote!(bootstrap, sync_cache = "startup")).is_ok());
+
+    let error = check_bootstrap(&getter, &parse_quote!(bootstrap)).unwrap_err();
+    assert!(error.to_string().contains("to keep the response"));
+
+    for input in [
+        parse_quote! { pub fn list_projects(team: u32) -> Vec<Project> {} },
+        parse_quote! { pub fn list_projects(#[caller_label] label: String) -> Vec<Project> {} },
+        parse_quote! { pub fn list_projects<T: Store>() -> Vec<Project> {} },
+    ] {
+        assert!(check_bootstrap(&input, &parse_quote!(bootstrap, cached)).is_err());
+    }
+    for options in [
+        "bootstrap, cached, compress = \"gzip\"",
+        "bootstrap, cached, encrypt",
+        "bootstrap, cached, layers",
+        "bootstrap, cached, catch_panic",
+        "bootstrap, cached, floats = \"null\"",
+        "bootstrap, cached, verify_schema",
+        "bootstrap, cached, raw_response",
+    ] {
+        let args: BridgeArgs = syn::parse_str(options).unwrap();
+        assert!(
+            check_bootstrap(&getter, &args).is_err(),
+            "{} should be rejected",
+            options
+        );
+    }
+    // The crate-wide `layers` skips bootstrap commands
+    assert!(check_layers(&getter, &parse_quote!(bootstrap, cached)).is_err());
+}
+
+#[test]
+fn test_bridge_bootstrap_lists_commands() {
+    let input: IntrospectInput = parse_quote! {
+        projects::list_projects,
+        #[cfg(feature = "quota")]
+        get_quota
+    };
+    let tokens = generate_bootstrap(&input);
+    assert!(contains_pattern(
+        &tokens,
+        "pub async fn __bridge_bootstrap () -> tauri_bridge_runtime :: bootstrap :: Bundle"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "loaded . push (projects :: __tauri_bridge_bootstrap_list_projects () . await) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (feature = \"quota\")] loaded . push (__tauri_bridge_bootstrap_get_quota () . await) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "kept = projects :: __tauri_bridge_client_bootstrap_list_projects (& loaded . command , loaded . value . clone ()) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "pub async fn bridge_bootstrap () -> Result < tauri_bridge_runtime :: bootstrap :: Report , String >"
+    ));
+}
+
+// ==================== Spec Tests ====================
+
+#[test]
+fn test_method_spec_params_and_result() {
+    let input: ItemFn = parse_quote! {
+        /// Look up a user.
+        pub fn find_user(id: u64, name: Option<String>) -> Result<User, String> {
+            todo!()
+        }
+    };
+
+    let method = method_spec(&input, "find_user");
+
+    assert_eq!(method["name"], "find_user");
+    assert_eq!(method["description"], "Look up a user.");
+    assert_eq!(method["params"][0]["name"], "id");
+    assert_eq!(method["params"][0]["required"], true);
+    assert_eq!(method["params"][0]["schema"]["type"], "integer");
+    assert_eq!(method["params"][1]["required"], false);
+    assert_eq!(method["result"]["schema"]["x-rust-type"], "User");
+    assert_eq!(method["x-error"]["type"], "string");
+}
+
+#[test]
+fn test_method_spec_unit_result() {
+    let input: ItemFn = parse_quote! {
+        pub fn noop() {}
+    };
+
+    let method = method_spec(&input, "noop");
+
+    assert_eq!(method["result"]["schema"]["type"], "null");
+    assert!(method.get("description").is_none());
+}
+
+#[test]
+fn test_type_schema_collections() {
+    let ty: Type = parse_quote!(Vec<&str>);
+    let schema = type_schema(&ty);
+    assert_eq!(schema["type"], "array");
+    assert_eq!(schema["items"]["type"], "string");
+
+    let ty: Type = parse_quote!(std::collections::HashMap<String, f64>);
+    let schema = type_schema(&ty);
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["additionalProperties"]["type"], "number");
+
+    let ty: Type = parse_quote!((String, bool));
+    let schema = type_schema(&ty);
+    assert_eq!(schema["prefixItems"][1]["type"], "boolean");
+}
+
+#[test]
+fn test_spec_const_generated() {
+    let input: ItemFn = parse_quote! {
+        pub fn greet(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+    };
+
+    let spec = generate_spec_const(&input, "greet");
+
+    assert!(contains_pattern(
+        &spec,
+        "pub const __TAURI_BRIDGE_SPEC_greet : & str"
+    ));
+}
+
+#[test]
+fn test_bridge_spec_collects_commands() {
+    let input: CommandList = parse_quote!(greet, api::add);
+
+    let spec = generate_spec(&input);
+
+    assert!(contains_pattern(
+        &spec,
+        "methods . push (__TAURI_BRIDGE_SPEC_greet) ; methods . push (api :: __TAURI_BRIDGE_SPEC_add) ;"
+    ));
+    assert!(contains_pattern(&spec, "\"1.2.6\""));
+}
+
+// ==================== Introspection Tests ====================
+
+#[test]
+fn test_introspect_builds_manifest_from_constants() {
+    let input: IntrospectInput = parse_quote!(
+        greet,
+        #[cfg(feature = "admin")]
+        api::ban_user
+    );
+
+    let tokens = generate_introspect(&input);
+
+    assert!(contains_pattern(
+        &tokens,
+        "let (name , version) = __TAURI_BRIDGE_VERSION_greet ; commands . push (tauri_bridge_runtime :: introspect :: CommandInfo :: new (name , version , __TAURI_BRIDGE_SPEC_greet ,)) ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (feature = \"admin\")] { let (name , version) = api :: __TAURI_BRIDGE_VERSION_ban_user ;"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [tauri :: command] pub fn __bridge_introspect () -> tauri_bridge_runtime :: introspect :: Manifest"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (target_arch = \"wasm32\")] pub async fn check_bridge () -> Result < () , String >"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "crate :: invoke (\"__bridge_introspect\" , args) . await ;"
+    ));
+}
+
+#[test]
+fn test_introspect_input_options() {
+    let input: IntrospectInput =
+        parse_quote!(client_cfg(feature = "web"), backend_cfg(feature = "app"); greet);
+
+    let tokens = generate_introspect(&input);
+
+    assert_eq!(input.commands.commands.len(), 1);
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (feature = \"web\")] pub async fn check_bridge"
+    ));
+    assert!(contains_pattern(
+        &tokens,
+        "# [cfg (feature = \"app\")] mod __tauri_introspect"
+    ));
+    assert!(syn::parse_str::<IntrospectInput>("strict(); greet").is_err());
+    assert!(syn::parse_str::<IntrospectInput>("client_cfg(feature = \"web\") greet").is_err());
+}
+
+// ==================== Helper Function Tests ====================
+
+#[test]
+fn test_has_reference_type_simple_ref() {
+    let ty: Type = parse_quote!(&str);
+    assert!(has_reference_type(&ty));
+}
+
+#[test]
+fn test_has_reference_type_owned() {
+    let ty: Type = parse_quote!(String);
+    assert!(!has_reference_type(&ty));
+}
+
+#[test]
+fn test_has_reference_type_vec_of_refs() {
+    let ty: Type = parse_quote!(Vec<&str>);
+    assert!(has_reference_type(&ty));
+}
+
+#[test]
+fn test_has_reference_type_tuple_with_ref() {
+    let ty: Type = parse_quote!((i32, &str, bool));
+    assert!(has_reference_type(&ty));
+}
+
+#[test]
+fn test_has_reference_type_array() {
+    let ty: Type = parse_quote!([&str; 3]);
+    assert!(has_reference_type(&ty));
+}
+
+#[test]
+fn test_transform_ref_to_lifetime() {
+    let ty: Type = parse_quote!(&str);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    assert!(normalize_tokens(&transformed).contains("& 'a str"));
+}
+
+#[test]
+fn test_transform_preserves_existing_lifetime() {
+    let ty: Type = parse_quote!(&'static str);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    // Should keep 'static, not replace with 'a
+    assert!(normalize_tokens(&transformed).contains("'static"));
+}
+
+#[test]
+fn test_get_return_type_explicit() {
+    let sig: Signature = parse_quote!(fn foo() -> String);
+    let ret = get_return_type(&sig);
+    assert_eq!(normalize_tokens(&ret), "String");
+}
+
+#[test]
+fn test_get_return_type_unit() {
+    let sig: Signature = parse_quote!(fn foo());
+    let ret = get_return_type(&sig);
+    assert_eq!(normalize_tokens(&ret), "()");
+}
+
+// ==================== Reference Handling Tests ====================
+
+#[test]
+fn test_ref_str() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_str(s: &str) -> String {
+            s.to_string()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesStrArgs < 'a >"));
+    assert!(contains_pattern(&client, "s : & 'a str"));
+}
+
+#[test]
+fn test_ref_string() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_ref_string(s: &String) -> String {
+            s.clone()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(
+        &client,
+        "struct TakesRefStringArgs < 'a >"
+    ));
+    assert!(contains_pattern(&client, "s : & 'a String"));
+}
+
+#[test]
+fn test_ref_slice_u8() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_bytes(data: &[u8]) -> usize {
+            data.len()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesBytesArgs < 'a >"));
+    assert!(contains_pattern(&client, "data : & 'a [u8]"));
+}
+
+#[test]
+fn test_ref_slice_of_refs() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_str_slice(items: &[&str]) -> String {
+            items.join(",")
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesStrSliceArgs < 'a >"));
+    assert!(contains_pattern(&client, "& 'a [& 'a str]"));
+}
+
+#[test]
+fn test_ref_mut_slice() {
+    let input: ItemFn = parse_quote! {
+        pub fn modify_bytes(data: &mut [u8]) -> usize {
+            data.len()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "& 'a mut [u8]"));
+}
+
+#[test]
+fn test_ref_mut_str() {
+    let input: ItemFn = parse_quote! {
+        pub fn modify_str(s: &mut str) -> () {
+            ()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "& 'a mut str"));
+}
+
+#[test]
+fn test_ref_custom_struct() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_user_ref(user: &User) -> String {
+            user.name.clone()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesUserRefArgs < 'a >"));
+    assert!(contains_pattern(&client, "user : & 'a User"));
+}
+
+#[test]
+fn test_ref_with_explicit_lifetime() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_static(s: &'static str) -> String {
+            s.to_string()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    // Should preserve 'static, not replace with 'a
+    assert!(contains_pattern(&client, "& 'static str"));
+}
+
+#[test]
+fn test_option_of_ref() {
+    let input: ItemFn = parse_quote! {
+        pub fn maybe_str(s: Option<&str>) -> String {
+            s.unwrap_or("").to_string()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct MaybeStrArgs < 'a >"));
+    assert!(contains_pattern(&client, "Option < & 'a str >"));
+}
+
+#[test]
+fn test_vec_of_refs() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_vec_refs(items: Vec<&str>) -> String {
+            items.join(",")
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesVecRefsArgs < 'a >"));
+    assert!(contains_pattern(&client, "Vec < & 'a str >"));
+}
+
+#[test]
+fn test_hashmap_with_ref_key() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_map(map: std::collections::HashMap<&str, i32>) -> i32 {
+            0
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "< 'a >"));
+    assert!(contains_pattern(&client, "& 'a str"));
+}
+
+#[test]
+fn test_tuple_with_refs() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_tuple(t: (&str, &str)) -> String {
+            format!("{}{}", t.0, t.1)
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesTupleArgs < 'a >"));
+    assert!(contains_pattern(&client, "(& 'a str , & 'a str)"));
+}
+
+#[test]
+fn test_nested_refs_in_option_vec() {
+    let input: ItemFn = parse_quote! {
+        pub fn complex_refs(data: Option<Vec<&str>>) -> usize {
+            data.map(|v| v.len()).unwrap_or(0)
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct ComplexRefsArgs < 'a >"));
+    assert!(contains_pattern(&client, "Option < Vec < & 'a str > >"));
+}
+
+#[test]
+fn test_array_of_refs() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_array(arr: [&str; 3]) -> String {
+            arr.join("")
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesArrayArgs < 'a >"));
+    assert!(contains_pattern(&client, "[& 'a str ; 3]"));
+}
+
+#[test]
+fn test_ref_to_array() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_ref_array(arr: &[i32; 5]) -> i32 {
+            arr.iter().sum()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "& 'a [i32 ; 5]"));
+}
+
+#[test]
+fn test_double_ref() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_double_ref(s: &&str) -> String {
+            s.to_string()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "& 'a & 'a str"));
+}
+
+#[test]
+fn test_ref_in_result() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_result_ref(r: Result<&str, &str>) -> String {
+            r.unwrap_or("").to_string()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(
+        &client,
+        "struct TakesResultRefArgs < 'a >"
+    ));
+    assert!(contains_pattern(&client, "Result < & 'a str , & 'a str >"));
+}
+
+#[test]
+fn test_multiple_ref_args() {
+    let input: ItemFn = parse_quote! {
+        pub fn concat_all(a: &str, b: &str, c: &str) -> String {
+            format!("{}{}{}", a, b, c)
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct ConcatAllArgs < 'a >"));
+    assert!(contains_pattern(&client, "a : & 'a str"));
+    assert!(contains_pattern(&client, "b : & 'a str"));
+    assert!(contains_pattern(&client, "c : & 'a str"));
+}
+
+#[test]
+fn test_mixed_ref_and_owned_multiple() {
+    let input: ItemFn = parse_quote! {
+        pub fn mixed(name: &str, count: u32, data: Vec<u8>, suffix: &str) -> String {
+            String::new()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct MixedArgs < 'a >"));
+    assert!(contains_pattern(&client, "name : & 'a str"));
+    assert!(contains_pattern(&client, "count : u32"));
+    assert!(contains_pattern(&client, "data : Vec < u8 >"));
+    assert!(contains_pattern(&client, "suffix : & 'a str"));
+}
+
+#[test]
+fn test_cow_str() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_cow(s: std::borrow::Cow<'_, str>) -> String {
+            s.into_owned()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    // Cow has a lifetime, should be detected
+    assert!(contains_pattern(&client, "struct TakesCowArgs"));
+}
+
+#[test]
+fn test_box_of_ref() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_boxed_ref(b: Box<&str>) -> String {
+            b.to_string()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "struct TakesBoxedRefArgs < 'a >"));
+    assert!(contains_pattern(&client, "Box < & 'a str >"));
+}
+
+#[test]
+fn test_ref_path_buf() {
+    let input: ItemFn = parse_quote! {
+        pub fn takes_path(p: &std::path::Path) -> String {
+            p.display().to_string()
+        }
+    };
+    let client = generate_client(&input, &BridgeArgs::default());
+    assert!(contains_pattern(&client, "& 'a std :: path :: Path"));
+}
+
+#[test]
+fn test_has_reference_nested_option_vec() {
+    let ty: Type = parse_quote!(Option<Vec<&str>>);
+    assert!(has_reference_type(&ty));
+}
+
+#[test]
+fn test_has_reference_result_with_ref() {
+    let ty: Type = parse_quote!(Result<&str, String>);
+    assert!(has_reference_type(&ty));
+}
+
+#[test]
+fn test_has_reference_deeply_nested() {
+    let ty: Type = parse_quote!(Option<Result<Vec<&str>, String>>);
+    assert!(has_reference_type(&ty));
+}
+
+#[test]
+fn test_has_reference_no_ref_in_generics() {
+    let ty: Type = parse_quote!(Option<Vec<String>>);
+    assert!(!has_reference_type(&ty));
+}
+
+#[test]
+fn test_transform_nested_option_ref() {
+    let ty: Type = parse_quote!(Option<&str>);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    assert!(normalize_tokens(&transformed).contains("Option < & 'a str >"));
+}
+
+#[test]
+fn test_transform_result_with_refs() {
+    let ty: Type = parse_quote!(Result<&str, &str>);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    let normalized = normalize_tokens(&transformed);
+    assert!(normalized.contains("Result < & 'a str , & 'a str >"));
+}
+
+#[test]
+fn test_transform_vec_of_ref() {
+    let ty: Type = parse_quote!(Vec<&str>);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    assert!(normalize_tokens(&transformed).contains("Vec < & 'a str >"));
+}
+
+#[test]
+fn test_transform_tuple_refs() {
+    let ty: Type = parse_quote!((&str, i32, &str));
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    let normalized = normalize_tokens(&transformed);
+    assert!(normalized.contains("(& 'a str , i32 , & 'a str)"));
+}
+
+#[test]
+fn test_transform_array_of_refs() {
+    let ty: Type = parse_quote!([&str; 2]);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    assert!(normalize_tokens(&transformed).contains("[& 'a str ; 2]"));
+}
+
+#[test]
+fn test_transform_mut_ref() {
+    let ty: Type = parse_quote!(&mut str);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    assert!(normalize_tokens(&transformed).contains("& 'a mut str"));
+}
+
+#[test]
+fn test_transform_double_ref() {
+    let ty: Type = parse_quote!(&&str);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    assert!(normalize_tokens(&transformed).contains("& 'a & 'a str"));
+}
+
+#[test]
+fn test_transform_preserves_static_in_nested() {
+    let ty: Type = parse_quote!(Option<&'static str>);
+    let transformed = transform_ref_to_lifetime(&ty, Span::call_site());
+    assert!(normalize_tokens(&transformed).contains("'static"));
+}
+
+#[test]
+fn test_reference_returns_are_rejected() {
+    let sig: Signature = parse_quote!(fn f(name: &str) -> &str);
+    let error = check_return_type(&sig).unwrap_err().to_string();
+    assert!(error.contains("return `String` instead"));
+
+    let sig: Signature = parse_quote!(fn f() -> Result<Option<&'static [&str]>, &str>);
+    let error = check_return_type(&sig).unwrap_err().to_string();
+    assert!(error.contains("return `Result < Option < Vec < String > > , String >` instead"));
+
+    let sig: Signature = parse_quote!(fn f(items: &[u64]) -> (Vec<u64>, &Note));
+    let error = check_return_type(&sig).unwrap_err().to_string();
+    assert!(error.contains("return `(Vec < u64 > , Note)` instead"));
+
+    assert!(check_return_type(&parse_quote!(fn f(name: &str) -> Cow<'static, str>)).is_ok());
+    assert!(check_return_type(&parse_quote!(fn f(name: &str))).is_ok());
+
+    let expanded = normalize_tokens(&expand(
+        quote::quote! {},
+        quote::quote! {
+            pub fn first_word(text: &str) -> &str {
+                text
+            }
+        },
+    ));
+    assert!(expanded.contains("compile_error"));
+}
+
+#[test]
+fn test_trait_arguments_are_rejected() {
+    let input: ItemFn = parse_quote! {
+        pub fn save(id: u64, value: impl Serialize) {}
+    };
+    let error = check_trait_args(&input).unwrap_err().to_string();
+    assert!(error.contains("argument `value` can't cross IPC as `impl Serialize`"));
+
+    let input: ItemFn = parse_quote! {
+        pub fn save(values: Vec<Box<dyn Any>>) {}
+    };
+    let error = check_trait_args(&input).unwrap_err().to_string();
+    assert!(error.contains("argument `values` can't cross IPC as `dyn Any`"));
+    assert!(error.contains("a trait object has no type"));
+
+    let input: ItemFn = parse_quote! {
+        pub fn save(path: &dyn AsRef<Path>) {}
+    };
+    assert!(check_trait_args(&input).is_err());
+
+    let input: ItemFn = parse_quote! {
+        pub fn save(id: u64, tags: Vec<String>, note: &Note) {}
+    };
+    assert!(check_trait_args(&input).is_ok());
+}
+
+// ==================== Expansion API Tests ====================
+
+#[test]
+fn test_expand_matches_attribute_expansion() {
+    let expanded = normalize_tokens(&expand(
+        quote::quote! { rename = "hello" },
+        quote::quote! {
+            pub fn greet(name: &str) -> String {
+                format!("Hello, {}!", name)
+            }
+        },
+    ));
+
+    assert!(expanded.contains("mod __tauri_cmd_greet"));
+    assert!(expanded.contains("pub async fn try_greet"));
+    assert!(expanded.contains("\"hello\""));
+}
+
+#[test]
+fn test_expand_reports_errors_as_compile_errors() {
+    let expanded = normalize_tokens(&expand(
+        quote::quote! { no_such_option },
+        quote::quote! {
+            pub fn greet() {}
+        },
+    ));
+    assert!(expanded.contains("compile_error"));
+
+    let spec = normalize_tokens(&expand_spec(quote::quote! { greet, }));
+    assert!(!spec.contains("compile_error"));
+    assert!(normalize_tokens(&expand_spec(quote::quote! { 1 })).contains("compile_error"));
+}
+
+#[test]
+fn test_describe_models_the_expansion() {
+    let description = describe(
+        quote::quote! { namespace = "users", version = 2, alias = "fetch_user" },
+        quote::quote! {
+            /// Look up a user.
+            pub async fn get_user(
+                user_id: u64,
+                tags: &[&str],
+                #[caller_label] caller: String,
+            ) -> Result<User, String> {
+                todo!()
+            }
+        },
+    )
+    .unwrap();
+
+    assert_eq!(description.name, "get_user");
+    assert_eq!(description.command, "users.get_user@2");
+    assert_eq!(description.aliases, ["fetch_user"]);
+    assert_eq!(description.version, 2);
+    assert!(description.is_async);
+    assert_eq!(
+        description.args,
+        [
+            ArgDescription {
+                name: "user_id".to_string(),
+                wire_name: "userId".to_string(),
+                ty: "u64".to_string(),
+                wire_type: "u64".to_string(),
+            },
+            ArgDescription {
+                name: "tags".to_string(),
+                wire_name: "tags".to_string(),
+                ty: "&[&str]".to_string(),
+                wire_type: "Vec<String>".to_string(),
+            },
+        ]
+    );
+    assert_eq!(description.response, "User");
+    assert_eq!(description.error.as_deref(), Some("String"));
+    assert!(!description.raw_bytes_response);
+    assert_eq!(
+        description.client_items,
+        ["GetUserArgs", "try_get_user", "get_user"]
+    );
+    assert_eq!(description.docs, "Look up a user.");
+}
+
+#[test]
+fn test_describe_reports_expansion_errors() {
+    let error = describe(
+        quote::quote! {},
+        quote::quote! {
+            pub fn first(items: &[String]) -> &String { &items[0] }
+        },
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("return `String` instead"));
+
+    assert!(
+        describe(
+            quote::quote! { no_such_option },
+            quote::quote! { pub fn f() {} }
+        )
+        .is_err()
+    );
+}
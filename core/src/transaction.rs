@@ -0,0 +1,266 @@
+//! Commands grouped into one invoke with `bridge_transaction!`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(transaction(undo = delete_note))]
+//! pub async fn create_note(title: String) -> Result<NoteId, String> {}
+//!
+//! async fn delete_note(id: NoteId) {}
+//!
+//! tauri_bridge::bridge_transaction![create_note, tag_note];
+//! ```
+//!
+//! Commands with `transaction` get a hidden step function on the backend,
+//! which decodes the JSON arguments like the dispatch function of
+//! `test_harness` and returns the response together with the future of the
+//! `undo` hook, and a `<name>_step` client function recording the call in a
+//! `tauri_bridge_runtime::transaction::Transaction`.
+//!
+//! `bridge_transaction!` expands to the backend's `__bridge_transaction`
+//! command running the steps of the listed commands in order, and the
+//! client's `commit_bridge_transaction` sending a transaction to it.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::{FnArg, ItemFn, ReturnType};
+
+use crate::attrs::{BridgeArgs, TransactionStep};
+use crate::harness::decoded_args;
+use crate::inject::is_injected;
+use crate::introspect::IntrospectInput;
+use crate::registry::sibling_item_path;
+use crate::types::result_types;
+
+/// Prefix of the backend function running a command as a step.
+pub const TRANSACT_PREFIX: &str = "__tauri_bridge_transact_";
+
+/// Reject options whose commands can't run as a step.
+///
+/// Steps are called with JSON arguments and respond with JSON, without a
+/// webview, like the dispatch function of `test_harness`.
+pub fn check_transaction(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if args.transaction.is_none() {
+        return Ok(());
+    }
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`transaction` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`transaction` can't be combined with `compress`",
+        ),
+        (
+            args.encrypt,
+            "`transaction` can't be combined with `encrypt`",
+        ),
+        (
+            args.incremental,
+            "`transaction` can't be combined with `incremental`",
+        ),
+        (
+            args.by_value,
+            "`transaction` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`transaction` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.non_send,
+            "`transaction` can't be combined with `non_send`",
+        ),
+        (args.layers, "`transaction` can't be combined with `layers`"),
+        (
+            args.sidecar.is_some(),
+            "`transaction` can't be combined with `sidecar`",
+        ),
+        (
+            args.catch_panic,
+            "`transaction` can't be combined with `catch_panic`",
+        ),
+        (
+            args.max_payload.is_some(),
+            "`transaction` can't be combined with `max_payload`",
+        ),
+        (
+            args.raw_response,
+            "`transaction` can't be combined with `raw_response`",
+        ),
+        (
+            args.unwrap_envelope,
+            "`transaction` can't be combined with `unwrap_envelope`",
+        ),
+    ];
+    if let Some((_, message)) = conflicts.iter().find(|(conflict, _)| *conflict) {
+        return Err(syn::Error::new(Span::call_site(), message));
+    }
+
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg
+            && is_injected(pat_type)
+        {
+            return Err(syn::Error::new_spanned(
+                pat_type,
+                "`transaction` commands can't take injected parameters; steps run without a webview",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Generate the backend function running the command as a step.
+///
+/// Like a dispatch function, it returns `None` for other commands.
+pub fn generate_transact(
+    input: &ItemFn,
+    args: &BridgeArgs,
+    step: &TransactionStep,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let command_name = args.command_name(input);
+    let backend_cfg = args.backend_cfg_attr();
+    let transact_name = syn::Ident::new(
+        &format!("{}{}", TRANSACT_PREFIX, fn_name.unraw()),
+        call_site,
+    );
+    let (decode_args, call_args) = decoded_args(input, &command_name);
+
+    let await_token = input
+        .sig
+        .asyncness
+        .map(|_| quote_spanned! {call_site=> .await });
+    let returns_result = match &input.sig.output {
+        ReturnType::Type(_, ty) => result_types(ty).is_some(),
+        ReturnType::Default => false,
+    };
+    // The error of a `Result` fails the step
+    let value = if returns_result {
+        quote_spanned! {call_site=>
+            match __tauri_bridge_result {
+                Ok(value) => value,
+                Err(error) => {
+                    return Some(Err(::serde_json::to_value(error)
+                        .unwrap_or_else(|e| ::serde_json::Value::String(e.to_string()))));
+                }
+            }
+        }
+    } else {
+        quote_spanned! {call_site=> __tauri_bridge_result }
+    };
+    let undo = match &step.undo {
+        Some(undo) => quote_spanned! {call_site=>
+            Some(::std::boxed::Box::pin(#undo(__tauri_bridge_value)))
+        },
+        None => quote_spanned! {call_site=>
+            {
+                let _ = __tauri_bridge_value;
+                None
+            }
+        },
+    };
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        #[doc(hidden)]
+        #[allow(deprecated, dead_code)]
+        #vis async fn #transact_name(
+            __tauri_bridge_command: &str,
+            __tauri_bridge_args: ::serde_json::Value,
+        ) -> Option<tauri_bridge_runtime::transaction::Executed> {
+            if __tauri_bridge_command != #command_name {
+                return None;
+            }
+            #(#decode_args)*
+            let __tauri_bridge_result = #fn_name(#(#call_args),*) #await_token;
+            let __tauri_bridge_value = #value;
+            let __tauri_bridge_response = match ::serde_json::to_value(&__tauri_bridge_value) {
+                Ok(response) => response,
+                Err(e) => return Some(Err(::serde_json::Value::String(e.to_string()))),
+            };
+            let __tauri_bridge_undo: Option<tauri_bridge_runtime::transaction::Undo> = #undo;
+            Some(Ok((__tauri_bridge_response, __tauri_bridge_undo)))
+        }
+    }
+}
+
+/// Generate the backend's `__bridge_transaction` command over the listed
+/// commands and the client's `commit_bridge_transaction`.
+pub fn generate_transaction(input: &IntrospectInput) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let backend_cfg = input.bridge_args.backend_cfg_attr();
+    let client_cfg = input.bridge_args.client_cfg_attr();
+
+    let entries = input.commands.commands.iter().map(|command| {
+        let cfgs = &command.cfgs;
+        let transact = sibling_item_path(&command.path, TRANSACT_PREFIX);
+        quote_spanned! {call_site=>
+            #(#cfgs)*
+            {
+                if let Some(executed) = #transact(command, args.clone()).await {
+                    return Some(executed);
+                }
+            }
+        }
+    });
+
+    quote_spanned! {call_site=>
+        #backend_cfg
+        mod __tauri_transaction {
+            #[allow(unused_imports)]
+            use super::*;
+
+            async fn execute(
+                command: &str,
+                args: ::serde_json::Value,
+            ) -> Option<tauri_bridge_runtime::transaction::Executed> {
+                #(#entries)*
+                None
+            }
+
+            /// Run the steps of a transaction in order, undoing those that
+            /// ran once one fails.
+            #[tauri::command]
+            pub async fn __bridge_transaction(
+                steps: Vec<tauri_bridge_runtime::transaction::Step>,
+            ) -> tauri_bridge_runtime::transaction::Outcome {
+                let mut log = tauri_bridge_runtime::transaction::UndoLog::new();
+                for step in steps {
+                    let executed = execute(&step.command, step.args).await;
+                    if let Some(outcome) = log.record(step.command, executed).await {
+                        return outcome;
+                    }
+                }
+                log.commit()
+            }
+        }
+
+        #backend_cfg
+        pub use __tauri_transaction::__bridge_transaction;
+
+        /// Send the steps of `transaction` to the backend as one invoke.
+        #client_cfg
+        pub async fn commit_bridge_transaction(
+            transaction: tauri_bridge_runtime::transaction::Transaction,
+        ) -> Result<
+            tauri_bridge_runtime::transaction::Committed,
+            tauri_bridge_runtime::transaction::TransactionError,
+        > {
+            let args = transaction.into_args()?;
+            // Parsed from JSON text, so the arguments reach Tauri as plain objects
+            let args = js_sys::JSON::parse(&args)
+                .map_err(|_| "Failed to serialize the transaction".to_string())?;
+            let result = crate::invoke(
+                tauri_bridge_runtime::transaction::TRANSACTION_COMMAND,
+                args,
+            )
+            .await;
+            serde_wasm_bindgen::from_value::<tauri_bridge_runtime::transaction::Outcome>(result)
+                .map_err(|e| format!("Failed to deserialize response: {}", e))?
+                .into_committed()
+        }
+    }
+}
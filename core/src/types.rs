@@ -0,0 +1,381 @@
+//! Type analysis utilities for reference detection and lifetime transformation.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{ToTokens, quote_spanned};
+use syn::{GenericArgument, PathArguments, ReturnType, Signature, Type, parse_quote};
+
+/// Check if a type contains any references (including nested in generics).
+pub fn has_reference_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(_) => true,
+        Type::Path(type_path) => {
+            // Check generic arguments for references
+            if let Some(segment) = type_path.path.segments.last()
+                && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+            {
+                return args.args.iter().any(|arg| {
+                    if let syn::GenericArgument::Type(inner_ty) = arg {
+                        has_reference_type(inner_ty)
+                    } else {
+                        false
+                    }
+                });
+            }
+            false
+        }
+        Type::Tuple(tuple) => tuple.elems.iter().any(has_reference_type),
+        Type::Array(array) => has_reference_type(&array.elem),
+        Type::Slice(slice) => has_reference_type(&slice.elem),
+        Type::Paren(paren) => has_reference_type(&paren.elem),
+        _ => false,
+    }
+}
+
+/// Transform reference types to use explicit `'a` lifetime.
+///
+/// This recursively transforms types like `&str` to `&'a str`,
+/// while preserving existing explicit lifetimes like `&'static str`.
+pub fn transform_ref_to_lifetime(ty: &Type, span: Span) -> TokenStream2 {
+    match ty {
+        Type::Reference(type_ref) => {
+            let mutability = &type_ref.mutability;
+            let elem = transform_ref_to_lifetime(&type_ref.elem, span);
+            if type_ref.lifetime.is_some() {
+                // Already has a lifetime, keep it
+                let lifetime = &type_ref.lifetime;
+                quote_spanned! {span=> &#lifetime #mutability #elem }
+            } else {
+                // Add 'a lifetime
+                quote_spanned! {span=> &'a #mutability #elem }
+            }
+        }
+        Type::Path(type_path) => {
+            // Handle generic arguments that might contain references
+            if let Some(segment) = type_path.path.segments.last()
+                && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+            {
+                let path_without_last = type_path
+                    .path
+                    .segments
+                    .iter()
+                    .take(type_path.path.segments.len() - 1);
+                let last_ident = &segment.ident;
+                let transformed_args: Vec<_> = args
+                    .args
+                    .iter()
+                    .map(|arg| {
+                        if let syn::GenericArgument::Type(inner_ty) = arg {
+                            let transformed = transform_ref_to_lifetime(inner_ty, span);
+                            quote_spanned! {span=> #transformed }
+                        } else {
+                            quote_spanned! {span=> #arg }
+                        }
+                    })
+                    .collect();
+
+                let prefix: Vec<_> = path_without_last.collect();
+                if prefix.is_empty() {
+                    return quote_spanned! {span=> #last_ident<#(#transformed_args),*> };
+                } else {
+                    return quote_spanned! {span=> #(#prefix::)* #last_ident<#(#transformed_args),*> };
+                }
+            }
+            quote_spanned! {span=> #type_path }
+        }
+        Type::Tuple(tuple) => {
+            let transformed: Vec<_> = tuple
+                .elems
+                .iter()
+                .map(|elem| transform_ref_to_lifetime(elem, span))
+                .collect();
+            quote_spanned! {span=> (#(#transformed),*) }
+        }
+        Type::Array(array) => {
+            let elem = transform_ref_to_lifetime(&array.elem, span);
+            let len = &array.len;
+            quote_spanned! {span=> [#elem; #len] }
+        }
+        Type::Slice(slice) => {
+            let elem = transform_ref_to_lifetime(&slice.elem, span);
+            quote_spanned! {span=> [#elem] }
+        }
+        Type::Paren(paren) => {
+            let elem = transform_ref_to_lifetime(&paren.elem, span);
+            quote_spanned! {span=> (#elem) }
+        }
+        _ => {
+            quote_spanned! {span=> #ty }
+        }
+    }
+}
+
+/// A top-level reference parameter, shared or mutable.
+pub enum BorrowedParam<'a> {
+    /// `&str` / `&mut str`
+    Str,
+    /// `&[T]` / `&mut [T]`
+    Slice(&'a Type),
+    /// `&T` / `&mut T`
+    Ref(&'a Type),
+}
+
+/// Classify `ty` as a top-level shared reference whose target holds no
+/// further references.
+///
+/// Mutable and nested references aren't classified and keep their
+/// borrowed form on the client.
+pub fn borrowed_param(ty: &Type) -> Option<BorrowedParam<'_>> {
+    let Type::Reference(type_ref) = ty else {
+        return None;
+    };
+    if type_ref.mutability.is_some() || has_reference_type(&type_ref.elem) {
+        return None;
+    }
+    match type_ref.elem.as_ref() {
+        Type::Path(type_path) if type_path.path.is_ident("str") => Some(BorrowedParam::Str),
+        Type::Slice(slice) => Some(BorrowedParam::Slice(&slice.elem)),
+        elem => Some(BorrowedParam::Ref(elem)),
+    }
+}
+
+/// Owned equivalent of a borrowed parameter: `String`, `Vec<T>` or `T`.
+pub fn owned_type(borrowed: &BorrowedParam) -> TokenStream2 {
+    let call_site = Span::call_site();
+    match borrowed {
+        BorrowedParam::Str => quote_spanned! {call_site=> String },
+        BorrowedParam::Slice(elem) => quote_spanned! {call_site=> Vec<#elem> },
+        BorrowedParam::Ref(elem) => quote_spanned! {call_site=> #elem },
+    }
+}
+
+/// Classify `ty` as a top-level mutable reference whose target holds no
+/// further references.
+pub fn mutable_param(ty: &Type) -> Option<BorrowedParam<'_>> {
+    let Type::Reference(type_ref) = ty else {
+        return None;
+    };
+    if type_ref.mutability.is_none() || has_reference_type(&type_ref.elem) {
+        return None;
+    }
+    match type_ref.elem.as_ref() {
+        Type::Path(type_path) if type_path.path.is_ident("str") => Some(BorrowedParam::Str),
+        Type::Slice(slice) => Some(BorrowedParam::Slice(&slice.elem)),
+        elem => Some(BorrowedParam::Ref(elem)),
+    }
+}
+
+/// Check if a type contains a mutable reference anywhere.
+pub fn has_mut_reference(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(type_ref) => {
+            type_ref.mutability.is_some() || has_mut_reference(&type_ref.elem)
+        }
+        Type::Path(type_path) => type_path.path.segments.iter().any(|segment| {
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return false;
+            };
+            args.args.iter().any(|arg| match arg {
+                syn::GenericArgument::Type(inner_ty) => has_mut_reference(inner_ty),
+                _ => false,
+            })
+        }),
+        Type::Tuple(tuple) => tuple.elems.iter().any(has_mut_reference),
+        Type::Array(array) => has_mut_reference(&array.elem),
+        Type::Slice(slice) => has_mut_reference(&slice.elem),
+        Type::Paren(paren) => has_mut_reference(&paren.elem),
+        _ => false,
+    }
+}
+
+/// The first `impl Trait` or `dyn Trait` in a type, nested ones included.
+pub fn trait_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::ImplTrait(_) | Type::TraitObject(_) => Some(ty),
+        Type::Reference(type_ref) => trait_type(&type_ref.elem),
+        Type::Path(type_path) => type_path.path.segments.iter().find_map(|segment| {
+            let PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(inner_ty) => trait_type(inner_ty),
+                _ => None,
+            })
+        }),
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(trait_type),
+        Type::Array(array) => trait_type(&array.elem),
+        Type::Slice(slice) => trait_type(&slice.elem),
+        Type::Paren(paren) => trait_type(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Reject references in the return type.
+///
+/// The client deserializes the response into the return type, which can't
+/// borrow from a response that's gone once decoded; `&'static` included.
+pub fn check_return_type(sig: &Signature) -> syn::Result<()> {
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return Ok(());
+    };
+    if !has_reference_type(ty) {
+        return Ok(());
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        format!(
+            "commands can't return references, since the client deserializes an owned \
+             response; return `{}` instead",
+            owned_value_type(ty).to_token_stream(),
+        ),
+    ))
+}
+
+/// `ty` with its references replaced by owned types: `&str` by `String`,
+/// `&[T]` by `Vec<T>` and `&T` by `T`.
+pub fn owned_value_type(ty: &Type) -> Type {
+    match ty {
+        Type::Reference(type_ref) => match type_ref.elem.as_ref() {
+            Type::Path(type_path) if type_path.path.is_ident("str") => parse_quote!(String),
+            Type::Slice(slice) => {
+                let elem = owned_value_type(&slice.elem);
+                parse_quote!(Vec<#elem>)
+            }
+            elem => owned_value_type(elem),
+        },
+        Type::Path(type_path) => {
+            let mut type_path = type_path.clone();
+            for segment in &mut type_path.path.segments {
+                if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in &mut args.args {
+                        if let GenericArgument::Type(inner_ty) = arg {
+                            *inner_ty = owned_value_type(inner_ty);
+                        }
+                    }
+                }
+            }
+            Type::Path(type_path)
+        }
+        Type::Tuple(tuple) => {
+            let mut tuple = tuple.clone();
+            for elem in &mut tuple.elems {
+                *elem = owned_value_type(elem);
+            }
+            Type::Tuple(tuple)
+        }
+        Type::Array(array) => {
+            let mut array = array.clone();
+            *array.elem = owned_value_type(&array.elem);
+            Type::Array(array)
+        }
+        Type::Paren(paren) => owned_value_type(&paren.elem),
+        _ => ty.clone(),
+    }
+}
+
+/// The `T` and `E` of a `Result<T, E>` type.
+pub fn result_types(ty: &Type) -> Option<(&Type, &Type)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    match (args.args.first(), args.args.iter().nth(1)) {
+        (Some(GenericArgument::Type(ok)), Some(GenericArgument::Type(error))) => Some((ok, error)),
+        _ => None,
+    }
+}
+
+/// Whether the type is `Vec<u8>`.
+pub fn is_byte_vec(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    segment.ident == "Vec"
+        && args.args.len() == 1
+        && matches!(
+            args.args.first(),
+            Some(GenericArgument::Type(Type::Path(elem))) if elem.path.is_ident("u8")
+        )
+}
+
+/// A parameter type `into_params` takes through `Into`.
+pub enum IntoParam {
+    /// `String`, taken as `impl Into<String>`
+    String,
+    /// `Vec<String>`, taken as `impl IntoIterator<Item = impl Into<String>>`
+    StringVec,
+}
+
+/// Classify `ty` as a parameter type `into_params` converts.
+pub fn into_param(ty: &Type) -> Option<IntoParam> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    if type_path.path.is_ident("String") {
+        return Some(IntoParam::String);
+    }
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let is_string_vec = segment.ident == "Vec"
+        && args.args.len() == 1
+        && matches!(
+            args.args.first(),
+            Some(GenericArgument::Type(Type::Path(elem))) if elem.path.is_ident("String")
+        );
+    is_string_vec.then_some(IntoParam::StringVec)
+}
+
+/// The byte type of a raw byte response: the return type, or the `T` of a
+/// `Result<T, E>` return type.
+pub fn raw_bytes_type(sig: &Signature) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return None;
+    };
+    Some(result_types(ty).map_or(ty.as_ref(), |(ok, _)| ok))
+}
+
+/// Extract the return type from a function signature.
+pub fn get_return_type(sig: &Signature) -> TokenStream2 {
+    let call_site = Span::call_site();
+    match &sig.output {
+        ReturnType::Default => quote_spanned! {call_site=> () },
+        ReturnType::Type(_, ty) => quote_spanned! {call_site=> #ty },
+    }
+}
+
+/// Generate deserialize expression that returns Result.
+///
+/// Responses are decoded through the return type's `Deserialize` impl with
+/// the type spelled out, so aliases (`type UserId = u64`), newtypes
+/// (`struct Token(String)`), tuples and fully qualified paths all take the
+/// same path as the type they stand for. Only unit skips decoding.
+/// `serde_wasm_bindgen` is the crate path from `crate_paths`.
+pub fn generate_try_deserialize_expr(
+    return_type: &TokenStream2,
+    serde_wasm_bindgen: &TokenStream2,
+    span: Span,
+) -> TokenStream2 {
+    if return_type.to_string() == "()" {
+        return quote_spanned! {span=>
+            Ok(())
+        };
+    }
+
+    quote_spanned! {span=>
+        #serde_wasm_bindgen::from_value::<#return_type>(result)
+            .map_err(|e| format!("Failed to deserialize response: {}", e))
+    }
+}
@@ -0,0 +1,498 @@
+//! TypeScript clients of bridged commands, for frontends not written in Rust.
+//!
+//! With `ts_dir` set in `[package.metadata.tauri-bridge]`, every
+//! `#[tauri_bridge]` expansion writes a module for its command to
+//! `<ts_dir>/commands/<command>.ts` and regenerates `<ts_dir>/index.ts`
+//! re-exporting all of them. `ts_client` picks what the modules export:
+//!
+//! - `"plain"` (default): a typed wrapper around `invoke`, e.g. `getUser(id)`
+//! - `"react-query"`: the wrapper plus a React Query hook, `useGetUser(id)`.
+//!   Commands with `invalidates(..)` get a mutation hook invalidating those
+//!   query keys instead
+//!
+//! Named types other than the std ones are imported from `<ts_dir>/types.ts`,
+//! e.g. generated by `ts-rs` or `specta`.
+//!
+//! With `ts_schemas = "zod"` (or `"valibot"`), each module also exports
+//! schemas of the arguments and the response, `getUserArgs` and
+//! `getUserResponse`, and the wrapper validates both at runtime. The schema
+//! of a named type `User` is imported as `UserSchema` from
+//! `<ts_dir>/schemas.ts`; generic ones are called with the schemas of their
+//! arguments, `PageSchema(UserSchema)`.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use convert_case::{Case, Casing};
+use syn::ext::IdentExt;
+use syn::{FnArg, GenericArgument, ItemFn, Pat, PathArguments, ReturnType, Type};
+
+use crate::attrs::BridgeArgs;
+use crate::config::CrateConfig;
+use crate::inject::{SESSION, TARGET_LABEL, has_marker, is_injected, marked_param};
+use crate::manifest::doc_comment;
+use crate::types::result_types;
+
+/// File name of the module re-exporting every command.
+pub const TS_INDEX_FILE: &str = "index.ts";
+
+/// What the generated TypeScript modules export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TsClient {
+    /// Typed `invoke` wrappers.
+    #[default]
+    Plain,
+    /// Wrappers plus React Query hooks.
+    ReactQuery,
+}
+
+impl TsClient {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "plain" => Some(TsClient::Plain),
+            "react-query" => Some(TsClient::ReactQuery),
+            _ => None,
+        }
+    }
+}
+
+/// Validation library the schemas are written for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TsSchema {
+    Zod,
+    Valibot,
+}
+
+impl TsSchema {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zod" => Some(TsSchema::Zod),
+            "valibot" => Some(TsSchema::Valibot),
+            _ => None,
+        }
+    }
+
+    fn import(self) -> &'static str {
+        match self {
+            TsSchema::Zod => "import { z } from \"zod\";",
+            TsSchema::Valibot => "import * as v from \"valibot\";",
+        }
+    }
+
+    /// A call of the library's function `name`, e.g. `z.string()`.
+    fn call(self, name: &str, args: &str) -> String {
+        match self {
+            TsSchema::Zod => format!("z.{}({})", name, args),
+            TsSchema::Valibot => format!("v.{}({})", name, args),
+        }
+    }
+
+    fn parse(self, schema: &str, value: &str) -> String {
+        match self {
+            TsSchema::Zod => format!("{}.parse({})", schema, value),
+            TsSchema::Valibot => format!("v.parse({}, {})", schema, value),
+        }
+    }
+}
+
+/// Resolve the directory TypeScript modules are written to, if any.
+pub fn ts_dir(config: &CrateConfig) -> Option<PathBuf> {
+    let dir = config.ts_dir.as_ref()?;
+    let crate_root = std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from)?;
+    Some(crate_root.join(dir))
+}
+
+/// The TypeScript module of a command, or `None` for commands whose
+/// responses only the Rust client decodes (`chunked`, `compress`,
+/// `encrypt`, `by_value`, `catch_panic` and `unwrap_envelope`), or that
+/// take a `#[session]` token only the Rust client sends.
+pub fn command_ts(
+    input: &ItemFn,
+    args: &BridgeArgs,
+    command: &str,
+    client: TsClient,
+    schemas: Option<TsSchema>,
+) -> Option<String> {
+    if args.chunked.is_some()
+        || args.compress.is_some()
+        || args.encrypt
+        || args.by_value
+        || args.catch_panic
+        || args.unwrap_envelope
+        || marked_param(input, SESSION).is_some()
+    {
+        return None;
+    }
+
+    let mut named = BTreeSet::new();
+    let mut named_schemas = BTreeSet::new();
+    // The `#[target_label]` is only set by the Rust client's `_to` variant
+    let params: Vec<(String, String, Option<String>)> = input
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type)
+                if !is_injected(pat_type) && !has_marker(pat_type, TARGET_LABEL) =>
+            {
+                let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+                    return None;
+                };
+                let name = pat_ident.ident.unraw().to_string().to_case(Case::Camel);
+                let schema = schemas.map(|lib| schema_expr(&pat_type.ty, lib, &mut named_schemas));
+                Some((name, ts_type(&pat_type.ty, &mut named), schema))
+            }
+            _ => None,
+        })
+        .collect();
+    let raw = args.raw_bytes_response(input);
+    let response_ty = match &input.sig.output {
+        ReturnType::Default => None,
+        // An `Err` rejects the promise
+        ReturnType::Type(_, ty) => Some(result_types(ty).map_or(ty.as_ref(), |(ok, _)| ok)),
+    };
+    let response = match response_ty {
+        _ if raw => "ArrayBuffer".to_string(),
+        Some(ty) => ts_type(ty, &mut named),
+        None => "null".to_string(),
+    };
+    let response_schema = schemas.map(|lib| match response_ty {
+        _ if raw => match lib {
+            TsSchema::Zod => lib.call("instanceof", "ArrayBuffer"),
+            TsSchema::Valibot => lib.call("instance", "ArrayBuffer"),
+        },
+        Some(ty) => schema_expr(ty, lib, &mut named_schemas),
+        None => lib.call("null", ""),
+    });
+
+    let fn_name = input.sig.ident.unraw().to_string();
+    let ts_fn = fn_name.to_case(Case::Camel);
+    let hook = format!("use{}", fn_name.to_case(Case::Pascal));
+    let param_list = params
+        .iter()
+        .map(|(name, ty, _)| format!("{}: {}", name, ty))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let names = params
+        .iter()
+        .map(|(name, _, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Schemas of `Channel` arguments check the instance, so it's imported
+    // as a value either way
+    let channel_schema = named_schemas.remove("Channel");
+    let uses_channel = named.remove("Channel") || channel_schema;
+
+    let mut module = format!(
+        "// Generated by tauri-bridge from `{}`; do not edit.\n",
+        fn_name
+    );
+    let core_imports = if uses_channel {
+        "Channel, invoke"
+    } else {
+        "invoke"
+    };
+    let _ = writeln!(
+        module,
+        "import {{ {} }} from \"@tauri-apps/api/core\";",
+        core_imports
+    );
+    let mutation = client == TsClient::ReactQuery && !args.invalidates.is_empty();
+    match client {
+        TsClient::ReactQuery if mutation => module
+            .push_str("import { useMutation, useQueryClient } from \"@tanstack/react-query\";\n"),
+        TsClient::ReactQuery => module.push_str(
+            "import { useQuery, type UseQueryOptions } from \"@tanstack/react-query\";\n",
+        ),
+        TsClient::Plain => {}
+    }
+    if let Some(lib) = schemas {
+        let _ = writeln!(module, "{}", lib.import());
+    }
+    if !named.is_empty() {
+        let named = named.into_iter().collect::<Vec<_>>().join(", ");
+        let _ = writeln!(module, "import type {{ {} }} from \"../types\";", named);
+    }
+    if !named_schemas.is_empty() {
+        let named = named_schemas
+            .into_iter()
+            .map(|name| format!("{}Schema", name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(module, "import {{ {} }} from \"../schemas\";", named);
+    }
+    module.push('\n');
+
+    if let (Some(lib), Some(response_schema)) = (schemas, &response_schema) {
+        let fields = params
+            .iter()
+            .filter_map(|(name, _, schema)| Some(format!("{}: {}", name, schema.as_ref()?)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(
+            module,
+            "export const {}Args = {};",
+            ts_fn,
+            lib.call("object", &format!("{{ {} }}", fields))
+        );
+        let _ = writeln!(
+            module,
+            "export const {}Response = {};\n",
+            ts_fn, response_schema
+        );
+    }
+
+    let docs = doc_comment(&input.attrs);
+    if !docs.is_empty() {
+        module.push_str("/**\n");
+        for line in docs.lines() {
+            let _ = writeln!(module, " * {}", line);
+        }
+        module.push_str(" */\n");
+    }
+    match schemas {
+        Some(lib) => {
+            let args = lib.parse(&format!("{}Args", ts_fn), &format!("{{ {} }}", names));
+            let parsed = lib.parse(
+                &format!("{}Response", ts_fn),
+                &format!("await invoke(\"{}\", args)", command),
+            );
+            let _ = writeln!(
+                module,
+                "export async function {}({}): Promise<{}> {{\n  const args = {};\n  return {};\n}}",
+                ts_fn, param_list, response, args, parsed
+            );
+        }
+        None => {
+            let _ = writeln!(
+                module,
+                "export function {}({}): Promise<{}> {{\n  return invoke(\"{}\", {{ {} }});\n}}",
+                ts_fn, param_list, response, command, names
+            );
+        }
+    }
+
+    if mutation {
+        let variables = params
+            .iter()
+            .map(|(name, ty, _)| format!("{}: {}", name, ty))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let invalidations: String = args
+            .invalidates
+            .iter()
+            .map(|key| {
+                format!(
+                    "      queryClient.invalidateQueries({{ queryKey: [\"{}\"] }});\n",
+                    key
+                )
+            })
+            .collect();
+        let _ = write!(
+            module,
+            "\nexport function {hook}() {{\n  const queryClient = useQueryClient();\n  return useMutation({{\n    mutationFn: ({{ {names} }}: {{ {variables} }}) => {ts_fn}({names}),\n    onSuccess: () => {{\n{invalidations}    }},\n  }});\n}}\n",
+        );
+    } else if client == TsClient::ReactQuery {
+        let separator = if params.is_empty() { "" } else { ", " };
+        let key = if params.is_empty() {
+            format!("\"{}\"", command)
+        } else {
+            format!("\"{}\", {}", command, names)
+        };
+        let _ = write!(
+            module,
+            "\nexport function {hook}({param_list}{separator}options?: Omit<UseQueryOptions<{response}>, \"queryKey\" | \"queryFn\">) {{\n  return useQuery({{ queryKey: [{key}], queryFn: () => {ts_fn}({names}), ...options }});\n}}\n",
+        );
+    }
+    Some(module)
+}
+
+/// Write a command's module and regenerate the index re-exporting all of
+/// them.
+pub fn write_ts_entry(dir: &Path, command: &str, module: &str) -> Result<(), String> {
+    let commands_dir = dir.join("commands");
+    std::fs::create_dir_all(&commands_dir)
+        .map_err(|e| format!("Failed to create TypeScript directory: {}", e))?;
+    std::fs::write(commands_dir.join(format!("{}.ts", command)), module)
+        .map_err(|e| format!("Failed to write TypeScript module: {}", e))?;
+
+    let mut commands = Vec::new();
+    let modules = std::fs::read_dir(&commands_dir)
+        .map_err(|e| format!("Failed to read TypeScript directory: {}", e))?;
+    for module in modules.flatten() {
+        let path = module.path();
+        if path.extension().is_some_and(|ext| ext == "ts")
+            && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+        {
+            commands.push(stem.to_string());
+        }
+    }
+    commands.sort();
+
+    let mut index = String::from("// Generated by tauri-bridge; do not edit.\n");
+    for command in commands {
+        let _ = writeln!(index, "export * from \"./commands/{}\";", command);
+    }
+    std::fs::write(dir.join(TS_INDEX_FILE), index)
+        .map_err(|e| format!("Failed to write TypeScript index: {}", e))
+}
+
+/// The TypeScript type of a Rust type, collecting the named types it uses.
+///
+/// Numbers map to `number`, sequences to arrays, maps to `Record`s and
+/// `Option<T>` to `T | null`, as serde encodes them to JSON.
+pub fn ts_type(ty: &Type, named: &mut BTreeSet<String>) -> String {
+    match ty {
+        Type::Reference(type_ref) => ts_type(&type_ref.elem, named),
+        Type::Paren(paren) => ts_type(&paren.elem, named),
+        Type::Slice(slice) => array_type(&slice.elem, named),
+        Type::Array(array) => array_type(&array.elem, named),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => "null".to_string(),
+        Type::Tuple(tuple) => {
+            let elems: Vec<_> = tuple
+                .elems
+                .iter()
+                .map(|elem| ts_type(elem, named))
+                .collect();
+            format!("[{}]", elems.join(", "))
+        }
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return "unknown".to_string();
+            };
+            let ident = segment.ident.to_string();
+            let generics = type_generics(&segment.arguments);
+            match (ident.as_str(), generics.as_slice()) {
+                ("String" | "str" | "char" | "PathBuf" | "Path", _) => "string".to_string(),
+                ("bool", _) => "boolean".to_string(),
+                (
+                    "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+                    | "u128" | "usize" | "f32" | "f64",
+                    _,
+                ) => "number".to_string(),
+                ("Option", [inner]) => format!("{} | null", ts_type(inner, named)),
+                ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [inner]) => array_type(inner, named),
+                ("HashMap" | "BTreeMap", [_, value]) => {
+                    format!("Record<string, {}>", ts_type(value, named))
+                }
+                ("Box" | "Rc" | "Arc" | "Cow", [.., inner]) => ts_type(inner, named),
+                ("Value", []) => "unknown".to_string(),
+                (_, generics) => {
+                    named.insert(ident.clone());
+                    if generics.is_empty() {
+                        ident
+                    } else {
+                        let generics: Vec<_> =
+                            generics.iter().map(|ty| ts_type(ty, named)).collect();
+                        format!("{}<{}>", ident, generics.join(", "))
+                    }
+                }
+            }
+        }
+        _ => "unknown".to_string(),
+    }
+}
+
+/// `T[]`, parenthesized when `T` is a union.
+fn array_type(elem: &Type, named: &mut BTreeSet<String>) -> String {
+    let elem = ts_type(elem, named);
+    if elem.contains(" | ") {
+        format!("({})[]", elem)
+    } else {
+        format!("{}[]", elem)
+    }
+}
+
+/// The schema of a Rust type, collecting the named types whose schemas it
+/// uses.
+pub fn schema_expr(ty: &Type, lib: TsSchema, named: &mut BTreeSet<String>) -> String {
+    match ty {
+        Type::Reference(type_ref) => schema_expr(&type_ref.elem, lib, named),
+        Type::Paren(paren) => schema_expr(&paren.elem, lib, named),
+        Type::Slice(slice) => lib.call("array", &schema_expr(&slice.elem, lib, named)),
+        Type::Array(array) => lib.call("array", &schema_expr(&array.elem, lib, named)),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => lib.call("null", ""),
+        Type::Tuple(tuple) => {
+            let elems: Vec<_> = tuple
+                .elems
+                .iter()
+                .map(|elem| schema_expr(elem, lib, named))
+                .collect();
+            lib.call("tuple", &format!("[{}]", elems.join(", ")))
+        }
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return lib.call("unknown", "");
+            };
+            let ident = segment.ident.to_string();
+            let generics = type_generics(&segment.arguments);
+            match (ident.as_str(), generics.as_slice()) {
+                ("String" | "str" | "char" | "PathBuf" | "Path", _) => lib.call("string", ""),
+                ("bool", _) => lib.call("boolean", ""),
+                ("f32" | "f64", _) => lib.call("number", ""),
+                (
+                    "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+                    | "u128" | "usize",
+                    _,
+                ) => match lib {
+                    TsSchema::Zod => "z.number().int()".to_string(),
+                    TsSchema::Valibot => "v.pipe(v.number(), v.integer())".to_string(),
+                },
+                ("Option", [inner]) => {
+                    let inner = schema_expr(inner, lib, named);
+                    match lib {
+                        TsSchema::Zod => format!("{}.nullable()", inner),
+                        TsSchema::Valibot => lib.call("nullable", &inner),
+                    }
+                }
+                ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [inner]) => {
+                    lib.call("array", &schema_expr(inner, lib, named))
+                }
+                ("HashMap" | "BTreeMap", [_, value]) => {
+                    let key = lib.call("string", "");
+                    let value = schema_expr(value, lib, named);
+                    lib.call("record", &format!("{}, {}", key, value))
+                }
+                ("Box" | "Rc" | "Arc" | "Cow", [.., inner]) => schema_expr(inner, lib, named),
+                ("Value", []) => lib.call("unknown", ""),
+                ("Channel", _) => {
+                    named.insert(ident);
+                    match lib {
+                        TsSchema::Zod => lib.call("instanceof", "Channel"),
+                        TsSchema::Valibot => lib.call("instance", "Channel"),
+                    }
+                }
+                (_, generics) => {
+                    let schema = format!("{}Schema", ident);
+                    named.insert(ident);
+                    if generics.is_empty() {
+                        schema
+                    } else {
+                        let generics: Vec<_> = generics
+                            .iter()
+                            .map(|ty| schema_expr(ty, lib, named))
+                            .collect();
+                        format!("{}({})", schema, generics.join(", "))
+                    }
+                }
+            }
+        }
+        _ => lib.call("unknown", ""),
+    }
+}
+
+/// The type arguments of a path segment.
+pub fn type_generics(arguments: &PathArguments) -> Vec<&Type> {
+    match arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
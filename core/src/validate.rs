@@ -0,0 +1,51 @@
+//! Client-side response validation with `validate_response = "function"`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(validate_response = "check_user")]
+//! pub fn get_user(id: u64) -> Result<User, String> {}
+//!
+//! fn check_user(user: &User) -> Result<(), String> {}
+//! ```
+//!
+//! The client calls the function with a reference to the deserialized
+//! response, or to the `Ok` side of a `Result`, and fails with
+//! `tauri_bridge_runtime::validate::ResponseError::Invalid` when it returns
+//! an error. The backend doesn't change.
+
+use proc_macro2::Span;
+
+use crate::attrs::BridgeArgs;
+
+/// Reject options that type the client error themselves, or hand the
+/// response over without deserializing it.
+pub fn check_validate_response(args: &BridgeArgs) -> syn::Result<()> {
+    if args.validate_response.is_none() {
+        return Ok(());
+    }
+    let conflicts = [
+        (
+            args.circuit_breaker.is_some(),
+            "`validate_response` can't be combined with `circuit_breaker`",
+        ),
+        (
+            args.platforms.is_some(),
+            "`validate_response` can't be combined with `platforms`",
+        ),
+        (
+            args.catch_panic,
+            "`validate_response` can't be combined with `catch_panic`",
+        ),
+        (
+            args.max_payload.is_some(),
+            "`validate_response` can't be combined with `max_payload`",
+        ),
+        (
+            args.raw_response,
+            "`validate_response` can't be combined with `raw_response`",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
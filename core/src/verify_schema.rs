@@ -0,0 +1,149 @@
+//! Per-call schema checks with `verify_schema`.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge(verify_schema)]
+//! pub fn save_note(note: Note) -> Result<NoteId, String> {}
+//! ```
+//!
+//! Both halves embed the hash of the command's parameters and response.
+//! The client sends it as `tauriBridgeSchema`, and the backend rejects a
+//! call carrying another one with a
+//! `tauri_bridge_runtime::introspect::SchemaMismatch` message; like
+//! `layers`, it returns `Result<T, InvokeError>` for that. Calls without a
+//! hash pass, so clients built before the option was added keep working.
+//!
+//! Types are hashed by name: a field changing inside a parameter's type
+//! doesn't change the hash.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, ItemFn, Token, parse_quote};
+
+use crate::attrs::BridgeArgs;
+use crate::entries::uses_entries;
+use crate::inject::{SESSION, marked_param};
+use crate::layer::{Flattened, flattened};
+
+/// Reject options that generate their own command.
+pub fn check_verify_schema(input: &ItemFn, args: &BridgeArgs) -> syn::Result<()> {
+    if !args.verify_schema {
+        return Ok(());
+    }
+    let conflicts = [
+        (
+            args.chunked.is_some(),
+            "`verify_schema` can't be combined with `chunked`",
+        ),
+        (
+            args.compress.is_some(),
+            "`verify_schema` can't be combined with `compress`",
+        ),
+        (
+            args.encrypt,
+            "`verify_schema` can't be combined with `encrypt`",
+        ),
+        (
+            args.incremental,
+            "`verify_schema` can't be combined with `incremental`",
+        ),
+        (
+            args.by_value,
+            "`verify_schema` can't be combined with `by_value`",
+        ),
+        (
+            args.raw_bytes_response(input),
+            "`verify_schema` doesn't support `raw` byte responses, the default for `Vec<u8>`",
+        ),
+        (
+            args.non_send,
+            "`verify_schema` can't be combined with `non_send`",
+        ),
+        (
+            args.test_harness,
+            "`verify_schema` can't be combined with `test_harness`",
+        ),
+        (
+            args.layers,
+            "`verify_schema` can't be combined with `layers`",
+        ),
+        (
+            args.sidecar.is_some(),
+            "`verify_schema` can't be combined with `sidecar`",
+        ),
+        (
+            args.catch_panic,
+            "`verify_schema` can't be combined with `catch_panic`",
+        ),
+        (
+            args.platforms.is_some(),
+            "`verify_schema` can't be combined with `platforms`",
+        ),
+        (
+            args.transaction.is_some(),
+            "`verify_schema` can't be combined with `transaction`",
+        ),
+        (
+            uses_entries(input, args),
+            "`verify_schema` can't be combined with `map_as`",
+        ),
+        (
+            args.floats.is_some(),
+            "`verify_schema` can't be combined with `floats`",
+        ),
+        (
+            marked_param(input, SESSION).is_some(),
+            "`verify_schema` can't be combined with a `#[session]` parameter",
+        ),
+    ];
+    match conflicts.iter().find(|(conflict, _)| *conflict) {
+        Some((_, message)) => Err(syn::Error::new(Span::call_site(), message)),
+        None => Ok(()),
+    }
+}
+
+/// The `#[tauri::command]` rejecting calls sent with a schema hash other
+/// than `hash` before running `block`.
+pub fn verified_command(
+    input: &ItemFn,
+    command_attr: &TokenStream2,
+    block: &TokenStream2,
+    inputs: &Punctuated<FnArg, Token![,]>,
+    hash: &str,
+    command_name: &str,
+) -> TokenStream2 {
+    let call_site = Span::call_site();
+    let vis = &input.vis;
+    let fn_name = &input.sig.ident;
+    let attrs = &input.attrs;
+    let asyncness = &input.sig.asyncness;
+    let generics = &input.sig.generics;
+    let where_clause = &input.sig.generics.where_clause;
+
+    // Tauri looks the hash up as `tauriBridgeSchema`
+    let mut inputs = inputs.clone();
+    inputs.push(parse_quote!(__tauri_bridge_schema: Option<String>));
+
+    let Flattened {
+        output,
+        ok_type,
+        respond,
+        run,
+        ..
+    } = flattened(input, block);
+
+    quote_spanned! {call_site=>
+        #(#attrs)*
+        #command_attr
+        #vis #asyncness fn #fn_name #generics (#inputs) -> Result<#ok_type, tauri::ipc::InvokeError> #where_clause {
+            tauri_bridge_runtime::introspect::verify_schema(
+                #command_name,
+                #hash,
+                __tauri_bridge_schema.as_deref(),
+            )
+            .map_err(|mismatch| tauri::ipc::InvokeError::from(mismatch.to_string()))?;
+            let __tauri_bridge_response: #output = #run;
+            #respond
+        }
+    }
+}
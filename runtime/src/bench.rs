@@ -0,0 +1,97 @@
+//! Timings of commands dispatched natively by `bridge_bench!`.
+//!
+//! Each call is split in three phases: the client serializing the
+//! arguments to JSON, the backend decoding them, running the command and
+//! encoding its response, and the response crossing back as JSON text.
+
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+/// Timings of one command over a number of calls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub command: &'static str,
+    pub iterations: u32,
+    /// Size of the JSON arguments.
+    pub args_bytes: usize,
+    /// Size of the JSON response.
+    pub response_bytes: usize,
+    /// Total time spent serializing arguments.
+    pub serialize: Duration,
+    /// Total time spent in the backend, argument decoding included.
+    pub dispatch: Duration,
+    /// Total time spent passing responses back.
+    pub deserialize: Duration,
+}
+
+impl BenchReport {
+    /// Average time of a whole call.
+    pub fn per_call(&self) -> Duration {
+        (self.serialize + self.dispatch + self.deserialize) / self.iterations.max(1)
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iterations = self.iterations.max(1);
+        write!(
+            f,
+            "{}: {:?}/call (serialize {:?}, dispatch {:?}, deserialize {:?}), {} B args, {} B response",
+            self.command,
+            self.per_call(),
+            self.serialize / iterations,
+            self.dispatch / iterations,
+            self.deserialize / iterations,
+            self.args_bytes,
+            self.response_bytes,
+        )
+    }
+}
+
+/// Call `dispatch` with `args` `iterations` times, timing each phase.
+///
+/// Fails with the error of the first rejected call.
+pub async fn measure<F, Fut>(
+    command: &'static str,
+    args: &Value,
+    iterations: u32,
+    mut dispatch: F,
+) -> Result<BenchReport, Value>
+where
+    F: FnMut(Value) -> Fut,
+    Fut: Future<Output = Option<Result<Value, Value>>>,
+{
+    let mut report = BenchReport {
+        command,
+        iterations,
+        args_bytes: 0,
+        response_bytes: 0,
+        serialize: Duration::ZERO,
+        dispatch: Duration::ZERO,
+        deserialize: Duration::ZERO,
+    };
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let body = serde_json::to_string(args).map_err(|e| Value::String(e.to_string()))?;
+        report.serialize += start.elapsed();
+        report.args_bytes = body.len();
+
+        let start = Instant::now();
+        let args = serde_json::from_str(&body).map_err(|e| Value::String(e.to_string()))?;
+        let response = dispatch(args)
+            .await
+            .unwrap_or_else(|| Err(Value::String(format!("command {} not found", command))))?;
+        report.dispatch += start.elapsed();
+
+        let start = Instant::now();
+        let body = serde_json::to_string(&response).map_err(|e| Value::String(e.to_string()))?;
+        serde_json::from_str::<Value>(&body).map_err(|e| Value::String(e.to_string()))?;
+        report.deserialize += start.elapsed();
+        report.response_bytes = body.len();
+    }
+    Ok(report)
+}
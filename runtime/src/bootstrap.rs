@@ -0,0 +1,121 @@
+//! Startup values of commands generated with `bootstrap`, fetched in one
+//! invoke.
+//!
+//! ```rust,ignore
+//! let report = bridge_bootstrap().await?;
+//! if !report.is_ok() {
+//!     web_sys::console::warn_1(&report.to_string().into());
+//! }
+//! ```
+//!
+//! The backend's `__bridge_bootstrap` command generated by
+//! `bridge_bootstrap!` runs the listed commands in order and responds with
+//! a [`Bundle`] of their responses. The client's `bridge_bootstrap()` keeps
+//! each response where the command's client reads it from: the query cache
+//! of `cached` commands, or the [`startup`](crate::startup) values of
+//! `sync_cache = "startup"` commands.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Wire name of the command fetching the startup values.
+pub const BOOTSTRAP_COMMAND: &str = "__bridge_bootstrap";
+
+/// Query arguments of a cached call without arguments: the JSON of the
+/// empty arguments object.
+pub const NO_QUERY_ARGS: &str = "{}";
+
+/// The response of one listed command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loaded {
+    pub command: String,
+    /// The response, or the `Ok` side of a `Result`; `null` on failure.
+    pub value: Value,
+    /// The command's error, as its message or JSON.
+    pub error: Option<String>,
+}
+
+impl Loaded {
+    /// The response `value` of `command`.
+    pub fn new(command: &str, value: &impl Serialize) -> Self {
+        match serde_json::to_value(value) {
+            Ok(value) => Loaded {
+                command: command.to_string(),
+                value,
+                error: None,
+            },
+            Err(e) => Loaded::failed(command, &format!("Failed to serialize response: {}", e)),
+        }
+    }
+
+    /// The `error` `command` failed with.
+    pub fn failed(command: &str, error: &impl Serialize) -> Self {
+        let error = match serde_json::to_value(error) {
+            Ok(Value::String(message)) => message,
+            Ok(error) => error.to_string(),
+            Err(e) => format!("Failed to serialize error: {}", e),
+        };
+        Loaded {
+            command: command.to_string(),
+            value: Value::Null,
+            error: Some(error),
+        }
+    }
+}
+
+/// The response of the `__bridge_bootstrap` command.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub loaded: Vec<Loaded>,
+}
+
+/// A command whose response wasn't kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failed {
+    pub command: String,
+    pub error: String,
+}
+
+impl fmt::Display for Failed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.command, self.error)
+    }
+}
+
+/// Outcome of `bridge_bootstrap()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Commands whose responses were kept.
+    pub primed: Vec<String>,
+    pub failed: Vec<Failed>,
+}
+
+impl Report {
+    /// Whether every response was kept.
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Record that `command` failed with `error`.
+    pub fn fail(&mut self, command: &str, error: impl Into<String>) {
+        self.failed.push(Failed {
+            command: command.to_string(),
+            error: error.into(),
+        });
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "bootstrap loaded, commands: {}", self.primed.len());
+        }
+        write!(f, "bootstrap incomplete:")?;
+        for failed in &self.failed {
+            write!(f, "\n- {}", failed)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//! Circuit breakers for commands generated with `circuit_breaker`.
+//!
+//! After `threshold` consecutive failures the circuit opens and calls fail
+//! fast with [`BreakerError::Open`] until `cooldown_ms` has passed. The next
+//! call is then let through as a trial: success closes the circuit, failure
+//! opens it again.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::clock::now_ms;
+
+/// Error of a command guarded by a circuit breaker.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BreakerError {
+    /// The circuit is open; the command wasn't invoked.
+    Open {
+        command: &'static str,
+        retry_in_ms: u64,
+    },
+    /// The command was invoked and failed.
+    Failed(String),
+}
+
+impl fmt::Display for BreakerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakerError::Open {
+                command,
+                retry_in_ms,
+            } => write!(
+                f,
+                "Circuit for `{}` is open; retry in {} ms",
+                command, retry_in_ms
+            ),
+            BreakerError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for BreakerError {}
+
+#[derive(Default)]
+struct Circuit {
+    failures: u32,
+    opened_at: Option<f64>,
+}
+
+thread_local! {
+    static CIRCUITS: RefCell<HashMap<&'static str, Circuit>> = RefCell::new(HashMap::new());
+}
+
+/// Fail fast while the circuit of `command` is open.
+pub fn check(command: &'static str, cooldown_ms: u64) -> Result<(), BreakerError> {
+    CIRCUITS.with(|circuits| {
+        let circuits = circuits.borrow();
+        let Some(opened_at) = circuits.get(command).and_then(|circuit| circuit.opened_at) else {
+            return Ok(());
+        };
+        let elapsed = now_ms() - opened_at;
+        if elapsed >= cooldown_ms as f64 {
+            // Cooldown over: let a trial call through
+            return Ok(());
+        }
+        Err(BreakerError::Open {
+            command,
+            retry_in_ms: (cooldown_ms as f64 - elapsed).ceil() as u64,
+        })
+    })
+}
+
+/// Record the outcome of a call and convert its error.
+pub fn record<T>(
+    command: &'static str,
+    threshold: u32,
+    result: Result<T, String>,
+) -> Result<T, BreakerError> {
+    CIRCUITS.with(|circuits| {
+        let mut circuits = circuits.borrow_mut();
+        let circuit = circuits.entry(command).or_default();
+        match &result {
+            Ok(_) => *circuit = Circuit::default(),
+            Err(_) => {
+                circuit.failures += 1;
+                // A failed trial reopens right away
+                if circuit.failures >= threshold || circuit.opened_at.is_some() {
+                    circuit.opened_at = Some(now_ms());
+                }
+            }
+        }
+    });
+    result.map_err(BreakerError::Failed)
+}
+
+/// Whether the circuit of `command` is currently open.
+pub fn is_open(command: &'static str, cooldown_ms: u64) -> bool {
+    check(command, cooldown_ms).is_err()
+}
+
+/// Close the circuit of `command`, e.g. after the user fixed a connection.
+pub fn reset(command: &'static str) {
+    CIRCUITS.with(|circuits| {
+        circuits.borrow_mut().remove(command);
+    });
+}
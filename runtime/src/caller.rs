@@ -0,0 +1,21 @@
+//! Context of the webview invoking a command.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn list_notes(ctx: CallerContext, folder: String) -> Vec<Note> {
+//!     notes::for_tenant(&ctx.window_label, &folder)
+//! }
+//! ```
+//!
+//! A parameter of this type is filled on the backend from the invoking
+//! `tauri::Webview` and left out of the client signature.
+
+/// The webview that invoked a command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallerContext {
+    /// Label of the invoking webview.
+    pub window_label: String,
+    /// URL the webview had loaded when invoking, empty if Tauri couldn't
+    /// read it.
+    pub url: String,
+}
@@ -0,0 +1,89 @@
+//! Reassembly of responses sent in chunks by `chunked` commands.
+//!
+//! The backend serializes the response to JSON. Small responses come back
+//! inline; larger ones are split into chunks sent over a channel, and the
+//! invoke resolves with the chunk count only. Channel messages may still be
+//! in flight at that point, so [`Reassembly::collect`] waits for all of them.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use serde::Deserialize;
+
+/// Response of a `chunked` command.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Envelope {
+    /// The whole JSON response.
+    Inline(String),
+    /// Number of chunks sent over the channel.
+    Chunks(usize),
+}
+
+#[derive(Default)]
+struct Inner {
+    chunks: Vec<String>,
+    waker: Option<Waker>,
+}
+
+/// Chunks received for one call.
+#[derive(Clone, Default)]
+pub struct Reassembly {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl Reassembly {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next chunk, from the channel's message handler.
+    pub fn push(&self, chunk: String) {
+        let waker = {
+            let mut inner = self.inner.borrow_mut();
+            inner.chunks.push(chunk);
+            inner.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// The JSON response, once every chunk arrived.
+    pub fn collect(&self, envelope: Envelope) -> Collect {
+        Collect {
+            inner: self.inner.clone(),
+            envelope: Some(envelope),
+        }
+    }
+}
+
+/// Future returned by [`Reassembly::collect`].
+pub struct Collect {
+    inner: Rc<RefCell<Inner>>,
+    envelope: Option<Envelope>,
+}
+
+impl Future for Collect {
+    type Output = String;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let count = match self.envelope.take() {
+            Some(Envelope::Inline(json)) => return Poll::Ready(json),
+            Some(Envelope::Chunks(count)) => count,
+            None => panic!("`Collect` polled after completion"),
+        };
+
+        let mut inner = self.inner.borrow_mut();
+        if inner.chunks.len() >= count {
+            return Poll::Ready(inner.chunks.concat());
+        }
+        inner.waker = Some(cx.waker().clone());
+        drop(inner);
+        self.envelope = Some(Envelope::Chunks(count));
+        Poll::Pending
+    }
+}
@@ -0,0 +1,103 @@
+//! Monotonic milliseconds for timeouts and cooldowns.
+//!
+//! The timing of the client reads the time from here: circuit breaker
+//! cooldowns, offline queue timeouts, slow call reports and recorded calls.
+//! Tests can install a [`MockClock`] and move time forward by hand instead
+//! of waiting it out:
+//!
+//! ```rust,ignore
+//! let clock = MockClock::install();
+//! // ... trip the circuit of `sync_now` ...
+//! clock.advance(10_000);
+//! assert!(!breaker::is_open("sync_now", 10_000));
+//! clock::reset_clock();
+//! ```
+//!
+//! Like the rest of the client, the clock is per thread.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// A source of the time.
+pub trait Clock {
+    /// Milliseconds since an arbitrary fixed point, never going back.
+    fn now_ms(&self) -> f64;
+}
+
+/// The platform's clock: `Date.now()` in the webview, an [`Instant`]
+/// natively.
+///
+/// [`Instant`]: std::time::Instant
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[cfg(target_arch = "wasm32")]
+    fn now_ms(&self) -> f64 {
+        js_sys::Date::now()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn now_ms(&self) -> f64 {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+/// A clock that only moves when told to, starting at 0.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    // Shared by the clones, so the installed clock can be moved
+    now_ms: Rc<Cell<f64>>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Install a new mock clock on this thread, and return it to move.
+    pub fn install() -> Self {
+        let clock = Self::new();
+        set_clock(clock.clone());
+        clock
+    }
+
+    /// Move the clock forward by `ms`.
+    pub fn advance(&self, ms: u64) {
+        self.now_ms.set(self.now_ms.get() + ms as f64);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_ms(&self) -> f64 {
+        self.now_ms.get()
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Option<Rc<dyn Clock>>> = const { RefCell::new(None) };
+}
+
+/// Read the time from `clock` on this thread, until [`reset_clock`].
+pub fn set_clock(clock: impl Clock + 'static) {
+    CLOCK.with(|current| *current.borrow_mut() = Some(Rc::new(clock)));
+}
+
+/// Go back to the [`SystemClock`].
+pub fn reset_clock() {
+    CLOCK.with(|current| *current.borrow_mut() = None);
+}
+
+/// Milliseconds since an arbitrary fixed point, from the clock set with
+/// [`set_clock`] or else the [`SystemClock`].
+pub fn now_ms() -> f64 {
+    let clock = CLOCK.with(|current| current.borrow().clone());
+    match clock {
+        Some(clock) => clock.now_ms(),
+        None => SystemClock.now_ms(),
+    }
+}
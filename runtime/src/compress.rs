@@ -0,0 +1,66 @@
+//! Payloads of commands generated with `compress = "gzip"`.
+//!
+//! Both sides serialize to JSON first. JSON of at least `min_size` bytes is
+//! gzipped and base64-encoded, smaller JSON is sent as is.
+
+use std::io::{Read, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+/// Arguments or response of a compressed command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payload {
+    /// Whether `data` is gzipped and base64-encoded JSON.
+    pub gzip: bool,
+    pub data: String,
+}
+
+/// Invoke arguments carrying the payload.
+///
+/// Tauri looks up the backend's `__tauri_bridge_payload` in camelCase.
+#[derive(Debug, Clone, Serialize)]
+pub struct PayloadArgs {
+    #[serde(rename = "tauriBridgePayload")]
+    pub payload: Payload,
+}
+
+/// Compress `json` if it's at least `min_size` bytes.
+pub fn encode(json: String, min_size: usize) -> Result<Payload, String> {
+    if json.len() < min_size {
+        return Ok(Payload {
+            gzip: false,
+            data: json,
+        });
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(json.as_bytes())
+        .and_then(|()| encoder.finish())
+        .map(|bytes| Payload {
+            gzip: true,
+            data: STANDARD.encode(bytes),
+        })
+        .map_err(|e| format!("Failed to compress payload: {}", e))
+}
+
+/// The JSON carried by `payload`.
+pub fn decode(payload: Payload) -> Result<String, String> {
+    if !payload.gzip {
+        return Ok(payload.data);
+    }
+
+    let bytes = STANDARD
+        .decode(payload.data)
+        .map_err(|e| format!("Failed to decompress payload: {}", e))?;
+    let mut json = String::new();
+    GzDecoder::new(bytes.as_slice())
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to decompress payload: {}", e))?;
+    Ok(json)
+}
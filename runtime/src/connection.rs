@@ -0,0 +1,208 @@
+//! Connection state of the client, for commands generated with `rebind`.
+//!
+//! When the backend restarts or the webview reloads during development,
+//! calls in flight never get a response. Report the connection going away
+//! with [`disconnected`] and coming back with [`reconnected`], e.g. from the
+//! dev server's reload hooks:
+//!
+//! - [`bridge_ready`] waits until the connection is up
+//! - `rebind` commands wait for it, and re-issue calls that were in flight
+//!   when the connection went away
+//! - [`on_reconnect`] and [`resubscribe`] run listeners again after a
+//!   reconnect
+//!
+//! The connection starts out ready. Like the rest of the client, the state
+//! is per thread.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use crate::subscription::Subscription;
+
+/// State of the connection to the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Ready,
+    /// Gone until [`reconnected`] is called.
+    Reconnecting,
+}
+
+type Listener = Rc<dyn Fn()>;
+
+struct State {
+    state: ConnectionState,
+    /// Bumped on every disconnect, so calls can tell they were cut off
+    epoch: u64,
+    wakers: Vec<Waker>,
+    listeners: Vec<(u64, Listener)>,
+    next_id: u64,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = const {
+        RefCell::new(State {
+            state: ConnectionState::Ready,
+            epoch: 0,
+            wakers: Vec::new(),
+            listeners: Vec::new(),
+            next_id: 0,
+        })
+    };
+}
+
+/// The current connection state.
+pub fn state() -> ConnectionState {
+    STATE.with(|state| state.borrow().state)
+}
+
+/// Report the connection as gone; calls in flight are re-issued once it's
+/// back.
+pub fn disconnected() {
+    let wakers = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.state = ConnectionState::Reconnecting;
+        state.epoch += 1;
+        std::mem::take(&mut state.wakers)
+    });
+    wakers.into_iter().for_each(Waker::wake);
+}
+
+/// Report the connection as back, releasing the waiting calls and running
+/// the [`on_reconnect`] listeners.
+pub fn reconnected() {
+    let (wakers, listeners) = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.state == ConnectionState::Ready {
+            return (Vec::new(), Vec::new());
+        }
+        state.state = ConnectionState::Ready;
+        let listeners: Vec<Listener> = state
+            .listeners
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        (std::mem::take(&mut state.wakers), listeners)
+    });
+    // Outside the borrow; listeners may subscribe again
+    wakers.into_iter().for_each(Waker::wake);
+    for listener in listeners {
+        listener();
+    }
+}
+
+/// Wait until the connection is ready.
+pub fn bridge_ready() -> BridgeReady {
+    BridgeReady
+}
+
+/// Future returned by [`bridge_ready`].
+pub struct BridgeReady;
+
+impl Future for BridgeReady {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match state() {
+            ConnectionState::Ready => Poll::Ready(()),
+            ConnectionState::Reconnecting => {
+                register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Call `on_reconnect` after every reconnect, until the returned
+/// subscription is dropped.
+pub fn on_reconnect(on_reconnect: impl Fn() + 'static) -> Subscription {
+    let id = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.listeners.push((id, Rc::new(on_reconnect)));
+        id
+    });
+    Subscription::new(move || {
+        STATE.with(|state| {
+            state
+                .borrow_mut()
+                .listeners
+                .retain(|(listener, _)| *listener != id);
+        });
+    })
+}
+
+/// Keep the subscription made by `subscribe`, replacing it with a new one
+/// after every reconnect.
+pub fn resubscribe(subscribe: impl Fn() -> Subscription + 'static) -> Subscription {
+    let current = Rc::new(RefCell::new(Some(subscribe())));
+    let reconnect = on_reconnect({
+        let current = current.clone();
+        move || {
+            // Unlisten from the old connection before listening again
+            current.borrow_mut().take();
+            let subscription = subscribe();
+            *current.borrow_mut() = Some(subscription);
+        }
+    });
+    Subscription::new(move || {
+        drop(reconnect);
+        current.borrow_mut().take();
+    })
+}
+
+/// Run the call made by `call` once the connection is ready, and make it
+/// again if the connection goes away before it completes.
+pub async fn rebound<T, F, Fut>(mut call: F) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = T>,
+{
+    loop {
+        bridge_ready().await;
+        let epoch = STATE.with(|state| state.borrow().epoch);
+        let call = Interruptible {
+            call: Box::pin(call()),
+            epoch,
+        };
+        if let Some(response) = call.await {
+            return response;
+        }
+    }
+}
+
+/// A call, resolving to `None` once the connection it was made on is gone.
+struct Interruptible<Fut> {
+    call: Pin<Box<Fut>>,
+    epoch: u64,
+}
+
+impl<Fut: Future> Future for Interruptible<Fut> {
+    type Output = Option<Fut::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if STATE.with(|state| state.borrow().epoch) != self.epoch {
+            return Poll::Ready(None);
+        }
+        match self.call.as_mut().poll(cx) {
+            Poll::Ready(response) => Poll::Ready(Some(response)),
+            Poll::Pending => {
+                register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Wake `waker` on the next connection change.
+fn register(waker: &Waker) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if !state.wakers.iter().any(|known| known.will_wake(waker)) {
+            state.wakers.push(waker.clone());
+        }
+    });
+}
@@ -0,0 +1,152 @@
+//! Contract tests of the backend against recorded sessions, run by
+//! `bridge_contract!`.
+//!
+//! Each call of a [`Recording`] is sent again, with its recorded arguments,
+//! through the dispatch function of the current backend. The call breaks
+//! the contract when it's rejected, or when its response no longer has the
+//! shape of the recorded one:
+//!
+//! - a value of another JSON type than recorded, or a fraction where an
+//!   integer was recorded
+//! - an object without a field the recorded one had; new fields are fine
+//! - an array element unlike the first recorded element
+//!
+//! `null` on either side matches anything, since optional values can't be
+//! told apart from the types they wrap. Values aren't compared, but the
+//! backend should answer like it did while recording, e.g. from the same
+//! fixtures, for enums and optional fields to take the recorded shapes.
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::replay::Recording;
+
+/// A recorded call the current backend answers incompatibly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Index of the call in the recording.
+    pub call: usize,
+    /// The command's wire name.
+    pub command: String,
+    /// Where in the response it was found, e.g. `$.tags[0]`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "call {} of `{}` at {}: {}",
+            self.call, self.command, self.path, self.message
+        )
+    }
+}
+
+/// The violations of a contract test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractError {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} recorded calls broke the contract",
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ContractError {}
+
+/// Send the calls of `recording` through `dispatch`, and return how many
+/// were checked, or every violation found.
+pub async fn verify(
+    recording: &Recording,
+    dispatch: impl AsyncFn(&str, Value) -> Result<Value, Value>,
+) -> Result<usize, ContractError> {
+    let mut violations = Vec::new();
+    for (index, call) in recording.calls.iter().enumerate() {
+        let violation = |path: String, message: String| Violation {
+            call: index,
+            command: call.command.clone(),
+            path,
+            message,
+        };
+        match dispatch(&call.command, call.args.clone()).await {
+            Ok(response) => violations.extend(
+                shape_changes(&call.response, &response)
+                    .into_iter()
+                    .map(|(path, message)| violation(path, message)),
+            ),
+            Err(error) => {
+                let error = error
+                    .as_str()
+                    .map_or_else(|| error.to_string(), str::to_string);
+                violations.push(violation("$".to_string(), format!("rejected: {}", error)));
+            }
+        }
+    }
+    if violations.is_empty() {
+        Ok(recording.calls.len())
+    } else {
+        Err(ContractError { violations })
+    }
+}
+
+/// Where `current` doesn't have the shape of `recorded`, and how, by path.
+pub fn shape_changes(recorded: &Value, current: &Value) -> Vec<(String, String)> {
+    let mut changes = Vec::new();
+    compare(recorded, current, "$".to_string(), &mut changes);
+    changes
+}
+
+fn compare(recorded: &Value, current: &Value, path: String, changes: &mut Vec<(String, String)>) {
+    match (recorded, current) {
+        (Value::Null, _) | (_, Value::Null) => {}
+        (Value::Bool(_), Value::Bool(_)) | (Value::String(_), Value::String(_)) => {}
+        (Value::Number(recorded), Value::Number(current)) => {
+            if !recorded.is_f64() && current.is_f64() {
+                changes.push((path, "expected an integer, got a fraction".to_string()));
+            }
+        }
+        (Value::Array(recorded), Value::Array(current)) => {
+            if let Some(first) = recorded.first() {
+                for (index, element) in current.iter().enumerate() {
+                    compare(first, element, format!("{}[{}]", path, index), changes);
+                }
+            }
+        }
+        (Value::Object(recorded), Value::Object(current)) => {
+            for (key, recorded) in recorded {
+                let field = format!("{}.{}", path, key);
+                match current.get(key) {
+                    Some(current) => compare(recorded, current, field, changes),
+                    None => changes.push((field, "missing field".to_string())),
+                }
+            }
+        }
+        _ => changes.push((
+            path,
+            format!("expected {}, got {}", kind(recorded), kind(current)),
+        )),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
@@ -0,0 +1,41 @@
+//! Console logging of calls made by commands with `debug_log`.
+//!
+//! Generated clients only call into this module in debug builds. Messages
+//! go to `console.debug` in the browser and to stderr natively.
+
+use std::fmt::Debug;
+
+/// Log the arguments a command is invoked with.
+pub fn request(command: &str, args: Option<&dyn Debug>) {
+    match args {
+        Some(args) => write(&format!("[tauri-bridge] -> {}\n{:#?}", command, args)),
+        None => write(&format!("[tauri-bridge] -> {}", command)),
+    }
+}
+
+/// Log the response of a command invoked at `started_ms`, a
+/// [`clock::now_ms`](crate::clock::now_ms) timestamp.
+pub fn response(command: &str, started_ms: f64, response: &dyn Debug) {
+    let elapsed_ms = crate::clock::now_ms() - started_ms;
+    write(&format!(
+        "[tauri-bridge] <- {} ({:.1} ms)\n{:#?}",
+        command, elapsed_ms, response
+    ));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(message: &str) {
+    use js_sys::{Function, JsString, Reflect};
+
+    let Ok(console) = Reflect::get(&js_sys::global(), &JsString::from("console")) else {
+        return;
+    };
+    if let Ok(debug) = Reflect::get(&console, &JsString::from("debug")) {
+        let _ = Function::from(debug).call1(&console, &JsString::from(message));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(message: &str) {
+    eprintln!("{}", message);
+}
@@ -0,0 +1,80 @@
+//! State of the demo implementations of `demo = "function"` commands.
+//!
+//! ```rust,ignore
+//! pub fn add_todo(title: String) -> Vec<Todo> {
+//!     tauri_bridge_runtime::demo::update("todos", |todos: &mut Vec<Todo>| {
+//!         todos.push(Todo::new(title));
+//!         todos.clone()
+//!     })
+//! }
+//! ```
+//!
+//! Outside of Tauri, the clients of such commands call their demo function
+//! instead of invoking the backend. Demo functions keep what the backend
+//! would have stored here, as JSON items in the [`persist`](crate::persist)
+//! store, `localStorage` unless set otherwise, so a demo survives reloads of
+//! the page. Without a store, e.g. in native tests, items are kept in memory.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Prefix of the items demo state is stored under.
+pub const ITEM_PREFIX: &str = "tauri-bridge-demo:";
+
+thread_local! {
+    static MEMORY: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+fn read(item: &str) -> Option<String> {
+    match crate::persist::store() {
+        Some(store) => store.get(item),
+        None => MEMORY.with(|memory| memory.borrow().get(item).cloned()),
+    }
+}
+
+fn write(item: &str, value: String) {
+    match crate::persist::store() {
+        Some(store) => store.set(item, &value),
+        None => MEMORY.with(|memory| {
+            memory.borrow_mut().insert(item.to_string(), value);
+        }),
+    }
+}
+
+/// The value stored under `key`, if any decodes as `T`.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    serde_json::from_str(&read(&format!("{}{}", ITEM_PREFIX, key))?).ok()
+}
+
+/// Store `value` under `key`.
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    if let Ok(value) = serde_json::to_string(value) {
+        write(&format!("{}{}", ITEM_PREFIX, key), value);
+    }
+}
+
+/// Change the value stored under `key`, starting from `T::default()`, and
+/// store it again.
+pub fn update<T, R>(key: &str, change: impl FnOnce(&mut T) -> R) -> R
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let mut value = load(key).unwrap_or_default();
+    let result = change(&mut value);
+    save(key, &value);
+    result
+}
+
+/// Remove the value stored under `key`.
+pub fn remove(key: &str) {
+    let item = format!("{}{}", ITEM_PREFIX, key);
+    match crate::persist::store() {
+        Some(store) => store.remove(&item),
+        None => MEMORY.with(|memory| {
+            memory.borrow_mut().remove(&item);
+        }),
+    }
+}
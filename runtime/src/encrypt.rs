@@ -0,0 +1,115 @@
+//! Payloads of commands generated with `encrypt`.
+//!
+//! Both sides serialize to JSON first, then seal it with ChaCha20-Poly1305
+//! under the key returned by the [`set_key_provider`] hook, so arguments
+//! and responses never show up in plaintext in the webview devtools or IPC
+//! logs. The command name is authenticated with each payload, so a payload
+//! can't be replayed to another command.
+//!
+//! ```rust,ignore
+//! // On both sides, once at startup
+//! tauri_bridge_runtime::encrypt::set_key_provider(|_command| Ok(session_key()));
+//! ```
+//!
+//! The provider gets the command name, so commands can use different keys.
+//! Sealing fails while no provider is set. Unlike the client-only hooks,
+//! the provider is global, as the backend runs commands on many threads.
+
+use std::sync::{Arc, RwLock};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use serde::{Deserialize, Serialize};
+
+/// A 256-bit key.
+pub type Key = [u8; 32];
+
+type KeyProvider = Arc<dyn Fn(&str) -> Result<Key, String> + Send + Sync>;
+
+static KEY_PROVIDER: RwLock<Option<KeyProvider>> = RwLock::new(None);
+
+/// Arguments or response of an encrypted command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sealed {
+    /// Base64-encoded nonce, fresh for every payload.
+    pub nonce: String,
+    /// Base64-encoded ciphertext of the JSON.
+    pub data: String,
+}
+
+/// Invoke arguments carrying the sealed payload.
+///
+/// Tauri looks up the backend's `__tauri_bridge_sealed` in camelCase.
+#[derive(Debug, Clone, Serialize)]
+pub struct SealedArgs {
+    #[serde(rename = "tauriBridgeSealed")]
+    pub sealed: Sealed,
+}
+
+/// Set the hook returning the key of a command.
+pub fn set_key_provider(provider: impl Fn(&str) -> Result<Key, String> + Send + Sync + 'static) {
+    *KEY_PROVIDER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(provider));
+}
+
+/// Remove the key provider.
+pub fn clear_key_provider() {
+    *KEY_PROVIDER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Encrypt the JSON of `command`'s arguments or response.
+pub fn seal(command: &str, json: String) -> Result<Sealed, String> {
+    let cipher = cipher(command)?;
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let data = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: json.as_bytes(),
+                aad: command.as_bytes(),
+            },
+        )
+        .map_err(|_| format!("Failed to encrypt the payload of `{}`", command))?;
+    Ok(Sealed {
+        nonce: STANDARD.encode(nonce),
+        data: STANDARD.encode(data),
+    })
+}
+
+/// Decrypt the JSON sealed for `command`.
+pub fn open(command: &str, sealed: Sealed) -> Result<String, String> {
+    let cipher = cipher(command)?;
+    let failed = || format!("Failed to decrypt the payload of `{}`", command);
+    let nonce = STANDARD.decode(sealed.nonce).map_err(|_| failed())?;
+    if nonce.len() != 12 {
+        return Err(failed());
+    }
+    let data = STANDARD.decode(sealed.data).map_err(|_| failed())?;
+    let json = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &data,
+                aad: command.as_bytes(),
+            },
+        )
+        .map_err(|_| failed())?;
+    String::from_utf8(json).map_err(|_| failed())
+}
+
+fn cipher(command: &str) -> Result<ChaCha20Poly1305, String> {
+    // Cloned out of the lock; the provider may set a provider itself
+    let provider = KEY_PROVIDER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or_else(|| {
+            format!(
+                "`{}` is encrypted, but no key provider is set; call `encrypt::set_key_provider` first",
+                command
+            )
+        })?;
+    let key = provider(command)?;
+    Ok(ChaCha20Poly1305::new(&key.into()))
+}
@@ -0,0 +1,102 @@
+//! Maps sent as arrays of `[key, value]` pairs, for `map_as = "entries"`.
+//!
+//! JSON object keys are strings, so maps with integer or other keys arrive
+//! in JS with their keys coerced, and don't compare equal once decoded
+//! again. Sent as entries, keys keep their type on the wire. Commands wrap
+//! these maps in [`Entries`]; the client's args struct serializes them
+//! through this module with `#[serde(with = "..")]`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+/// A map that can be sent as its entries.
+pub trait EntryMap {
+    type Key;
+    type Value;
+
+    fn entries(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)>;
+    fn from_entries(entries: Vec<(Self::Key, Self::Value)>) -> Self;
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> EntryMap for HashMap<K, V, S> {
+    type Key = K;
+    type Value = V;
+
+    fn entries(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter()
+    }
+
+    fn from_entries(entries: Vec<(K, V)>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl<K: Ord, V> EntryMap for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn entries(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter()
+    }
+
+    fn from_entries(entries: Vec<(K, V)>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+/// Serialize `map` as an array of `[key, value]` pairs.
+pub fn serialize<M, S>(map: &M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    M: EntryMap,
+    M::Key: Serialize,
+    M::Value: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(map.entries())
+}
+
+/// Deserialize a map from an array of `[key, value]` pairs.
+pub fn deserialize<'de, M, D>(deserializer: D) -> Result<M, D::Error>
+where
+    M: EntryMap,
+    M::Key: Deserialize<'de>,
+    M::Value: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Vec::deserialize(deserializer).map(M::from_entries)
+}
+
+/// A map argument or response sent as its entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Entries<M>(pub M);
+
+impl<M> Entries<M> {
+    pub fn into_inner(self) -> M {
+        self.0
+    }
+}
+
+impl<M> Serialize for Entries<M>
+where
+    M: EntryMap,
+    M::Key: Serialize,
+    M::Value: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, M> Deserialize<'de> for Entries<M>
+where
+    M: EntryMap,
+    M::Key: Deserialize<'de>,
+    M::Value: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Entries)
+    }
+}
@@ -0,0 +1,111 @@
+//! Response envelopes of commands generated with `unwrap_envelope`.
+//!
+//! Some apps wrap every response in a struct like [`ApiResponse`], with a
+//! success flag, the data and an error. The client of an `unwrap_envelope`
+//! command decodes the envelope and turns it into a `Result` through
+//! [`Envelope`], which [`ApiResponse`] implements and apps can implement
+//! for their own envelope types.
+
+use std::fmt;
+
+use serde::de::{DeserializeOwned, IntoDeserializer};
+use serde::{Deserialize, Serialize};
+
+/// A response wrapping data or an error.
+pub trait Envelope {
+    /// Data of a successful response.
+    type Data;
+    /// Error of a failed response.
+    type Error;
+
+    /// The data, or the error of a failed response.
+    fn into_result(self) -> Result<Self::Data, Self::Error>;
+}
+
+/// The standard envelope: `{"success": true, "data": ..}` or
+/// `{"success": false, "error": {"code": .., "message": ..}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    #[serde(default = "none", skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
+}
+
+// `#[serde(default)]` would require `T: Default`
+fn none<T>() -> Option<T> {
+    None
+}
+
+impl<T> ApiResponse<T> {
+    /// A successful response with `data`.
+    pub fn ok(data: T) -> Self {
+        ApiResponse {
+            success: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    /// A failed response.
+    pub fn err(code: impl Into<String>, message: impl Into<String>) -> Self {
+        ApiResponse {
+            success: false,
+            data: None,
+            error: Some(ApiError {
+                code: code.into(),
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Envelope for ApiResponse<T> {
+    type Data = T;
+    type Error = ApiError;
+
+    /// A successful response without data holds `()` or `None`; for other
+    /// data types, that's an `invalid_envelope` error.
+    fn into_result(self) -> Result<T, ApiError> {
+        if !self.success {
+            return Err(self.error.unwrap_or_else(|| {
+                ApiError::invalid_envelope("failed response without an error")
+            }));
+        }
+        match self.data {
+            Some(data) => Ok(data),
+            None => {
+                T::deserialize(IntoDeserializer::<serde::de::value::Error>::into_deserializer(()))
+                    .map_err(|_| ApiError::invalid_envelope("successful response without data"))
+            }
+        }
+    }
+}
+
+/// Error of a failed [`ApiResponse`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiError {
+    pub code: String,
+    pub message: String,
+}
+
+impl ApiError {
+    /// Code of errors about the envelope itself.
+    pub const INVALID_ENVELOPE: &'static str = "invalid_envelope";
+
+    fn invalid_envelope(message: &str) -> Self {
+        ApiError {
+            code: Self::INVALID_ENVELOPE.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
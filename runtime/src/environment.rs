@@ -0,0 +1,101 @@
+//! Detection of the Tauri webview, for commands generated with `detect_tauri`.
+//!
+//! Outside of Tauri, e.g. in a web preview of the frontend served by
+//! `trunk serve`, there is no IPC to invoke commands through, and the invoke
+//! shim throws a `ReferenceError` on the first call. Commands generated with
+//! `detect_tauri` check [`in_tauri`] first and fail with the error of
+//! [`NotInTauri`] instead, or call their fallback transport.
+//!
+//! Tauri is detected from the `__TAURI_INTERNALS__` global unless set with
+//! [`set_in_tauri`], e.g. to test the preview build.
+
+use std::cell::Cell;
+
+use crate::breaker::BreakerError;
+use crate::panic::BridgeError;
+use crate::platform::PlatformError;
+use crate::validate::ResponseError;
+
+thread_local! {
+    static OVERRIDE: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Whether the client runs inside Tauri and can invoke commands.
+///
+/// Detected from the webview's globals unless overridden with
+/// [`set_in_tauri`].
+pub fn in_tauri() -> bool {
+    OVERRIDE.with(Cell::get).unwrap_or_else(detect_tauri)
+}
+
+/// Override Tauri detection.
+pub fn set_in_tauri(in_tauri: bool) {
+    OVERRIDE.with(|current| current.set(Some(in_tauri)));
+}
+
+/// Go back to detecting Tauri from the webview's globals.
+pub fn reset_in_tauri() {
+    OVERRIDE.with(|current| current.set(None));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn detect_tauri() -> bool {
+    js_sys::Reflect::has(
+        &js_sys::global(),
+        &js_sys::JsString::from("__TAURI_INTERNALS__"),
+    )
+    .unwrap_or(false)
+}
+
+// Native clients are only built for tests, which mock the invoke
+#[cfg(not(target_arch = "wasm32"))]
+fn detect_tauri() -> bool {
+    true
+}
+
+/// Errors of the `try_` functions of `detect_tauri` commands.
+pub trait NotInTauri {
+    /// The error of calling `command` outside of Tauri; nothing was sent.
+    fn not_in_tauri(command: &'static str) -> Self;
+}
+
+fn message(command: &'static str) -> String {
+    BridgeError::NotInTauri { command }.to_string()
+}
+
+impl NotInTauri for String {
+    fn not_in_tauri(command: &'static str) -> Self {
+        message(command)
+    }
+}
+
+impl NotInTauri for BridgeError {
+    fn not_in_tauri(command: &'static str) -> Self {
+        BridgeError::NotInTauri { command }
+    }
+}
+
+impl NotInTauri for BreakerError {
+    fn not_in_tauri(command: &'static str) -> Self {
+        BreakerError::Failed(message(command))
+    }
+}
+
+impl NotInTauri for PlatformError {
+    fn not_in_tauri(command: &'static str) -> Self {
+        PlatformError::Failed(message(command))
+    }
+}
+
+impl NotInTauri for ResponseError {
+    fn not_in_tauri(command: &'static str) -> Self {
+        ResponseError::Failed(message(command))
+    }
+}
+
+#[cfg(feature = "payload")]
+impl NotInTauri for crate::payload::PayloadError {
+    fn not_in_tauri(command: &'static str) -> Self {
+        crate::payload::PayloadError::Failed(message(command))
+    }
+}
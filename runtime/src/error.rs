@@ -0,0 +1,60 @@
+//! Errors of commands returning `anyhow` or `eyre` results.
+//!
+//! Neither `anyhow::Error` nor `eyre::Report` is serializable, so the
+//! backend sends them as an [`ErrorReport`]: the error's message and the
+//! messages of its sources. Clients receive `Result<T, ErrorReport>`.
+
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A backend error as sent to the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub message: String,
+    /// Messages of the error's sources, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chain: Vec<String>,
+}
+
+impl ErrorReport {
+    /// Report `error` with its sources.
+    pub fn new(error: &(dyn Error + 'static)) -> Self {
+        let mut chain = Vec::new();
+        let mut source = error.source();
+        while let Some(error) = source {
+            chain.push(error.to_string());
+            source = error.source();
+        }
+        ErrorReport {
+            message: error.to_string(),
+            chain,
+        }
+    }
+
+    /// Report an `anyhow::Error`, `eyre::Report` or other boxed error.
+    pub fn from_report<E: AsRef<dyn Error + Send + Sync>>(error: E) -> Self {
+        Self::new(error.as_ref())
+    }
+
+    /// The root cause: the innermost source, or the error itself.
+    pub fn root_cause(&self) -> &str {
+        self.chain.last().unwrap_or(&self.message)
+    }
+}
+
+impl fmt::Display for ErrorReport {
+    /// The message; the alternate flag (`{:#}`) appends the sources.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if f.alternate() {
+            for cause in &self.chain {
+                write!(f, ": {}", cause)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for ErrorReport {}
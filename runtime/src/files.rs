@@ -0,0 +1,73 @@
+//! Chunked file transfer for `#[bridge_file]` commands.
+//!
+//! The backend reads and writes one chunk per invoke; the client loops over
+//! the chunks and reports progress. Chunk data travels base64-encoded, as
+//! byte arrays would inflate several times in JSON.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+/// A chunk read from a file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChunk {
+    /// Base64-encoded bytes; empty at the end of the file.
+    pub data: String,
+    /// Size of the whole file.
+    pub size: u64,
+}
+
+/// Read up to `chunk_size` bytes at `offset`.
+pub fn read_chunk(
+    path: impl AsRef<Path>,
+    offset: u64,
+    chunk_size: usize,
+) -> Result<FileChunk, String> {
+    let path = path.as_ref();
+    let describe = |e: std::io::Error| format!("Failed to read `{}`: {}", path.display(), e);
+
+    let mut file = File::open(path).map_err(describe)?;
+    let size = file.metadata().map_err(describe)?.len();
+    file.seek(SeekFrom::Start(offset)).map_err(describe)?;
+    let mut bytes = Vec::new();
+    file.take(chunk_size as u64)
+        .read_to_end(&mut bytes)
+        .map_err(describe)?;
+
+    Ok(FileChunk {
+        data: encode_bytes(&bytes),
+        size,
+    })
+}
+
+/// Write base64-encoded `data` at `offset`; offset 0 truncates the file.
+pub fn write_chunk(path: impl AsRef<Path>, offset: u64, data: &str) -> Result<(), String> {
+    let path = path.as_ref();
+    let describe = |e: std::io::Error| format!("Failed to write `{}`: {}", path.display(), e);
+
+    let bytes = decode_bytes(data)?;
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(offset == 0)
+        .open(path)
+        .map_err(describe)?;
+    file.seek(SeekFrom::Start(offset)).map_err(describe)?;
+    file.write_all(&bytes).map_err(describe)
+}
+
+/// Encode a chunk for sending.
+pub fn encode_bytes(bytes: &[u8]) -> String {
+    STANDARD.encode(bytes)
+}
+
+/// Decode a received chunk.
+pub fn decode_bytes(data: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(data)
+        .map_err(|e| format!("Failed to decode file chunk: {}", e))
+}
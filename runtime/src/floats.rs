@@ -0,0 +1,862 @@
+//! Non-finite floats of commands generated with `floats = ".."`.
+//!
+//! JSON has no NaN or infinities: `serde_json` writes them as `null`, and
+//! the `null` then fails to decode as a float, or silently stands in for a
+//! value that was never sent. A [`Policy`] decides what happens instead:
+//!
+//! - [`Reject`] fails serializing values holding a non-finite float.
+//! - [`AsNull`] sends them as `null` as before, and decodes `null` floats
+//!   as NaN.
+//! - [`AsString`] sends them as `"NaN"`, `"Infinity"` and `"-Infinity"`,
+//!   and decodes these strings back.
+//!
+//! The policy applies to every float in the value, however deeply nested,
+//! through a serializer and deserializer wrapping the ones in use.
+//! Untagged and flattened types decode from values buffered by serde, which
+//! keep the usual rules. Commands wrap their parameters and response in
+//! [`Floats`]; the client's args struct serializes its fields through the
+//! [`reject`], [`null`] and [`string`] modules with `#[serde(with = "..")]`.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use serde::ser::{self, Serializer};
+use serde::{Deserialize, Serialize};
+
+/// How non-finite floats are sent and decoded.
+pub trait Policy {
+    const NON_FINITE: NonFinite;
+}
+
+/// The encodings of non-finite floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinite {
+    Reject,
+    Null,
+    String,
+}
+
+/// Fail on non-finite floats, `floats = "error"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reject;
+
+/// Send non-finite floats as `null`, `floats = "null"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AsNull;
+
+/// Send non-finite floats as strings, `floats = "string"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AsString;
+
+impl Policy for Reject {
+    const NON_FINITE: NonFinite = NonFinite::Reject;
+}
+
+impl Policy for AsNull {
+    const NON_FINITE: NonFinite = NonFinite::Null;
+}
+
+impl Policy for AsString {
+    const NON_FINITE: NonFinite = NonFinite::String;
+}
+
+/// A value whose floats are sent under the policy `P`.
+pub struct Floats<T, P>(pub T, PhantomData<P>);
+
+impl<T, P> Floats<T, P> {
+    pub fn new(value: T) -> Self {
+        Floats(value, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: fmt::Debug, P> fmt::Debug for Floats<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Floats").field(&self.0).finish()
+    }
+}
+
+impl<T: Clone, P> Clone for Floats<T, P> {
+    fn clone(&self) -> Self {
+        Floats::new(self.0.clone())
+    }
+}
+
+impl<T: PartialEq, P> PartialEq for Floats<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Serialize, P: Policy> Serialize for Floats<T, P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_with(&self.0, serializer, P::NON_FINITE)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, P: Policy> Deserialize<'de> for Floats<T, P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with(deserializer, P::NON_FINITE).map(Floats::new)
+    }
+}
+
+/// Serialize `value` with its non-finite floats encoded as `policy` says.
+pub fn serialize_with<T, S>(value: &T, serializer: S, policy: NonFinite) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + ?Sized,
+    S: Serializer,
+{
+    value.serialize(FloatSerializer {
+        inner: serializer,
+        policy,
+    })
+}
+
+/// Deserialize a value with its non-finite floats decoded as `policy` says.
+pub fn deserialize_with<'de, T, D>(deserializer: D, policy: NonFinite) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(FloatDeserializer {
+        inner: deserializer,
+        policy,
+    })
+}
+
+macro_rules! policy_module {
+    ($name:ident, $policy:ident, $doc:literal) => {
+        #[doc = $doc]
+        pub mod $name {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: Serialize + ?Sized,
+                S: Serializer,
+            {
+                super::serialize_with(value, serializer, super::NonFinite::$policy)
+            }
+
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+            where
+                T: Deserialize<'de>,
+                D: Deserializer<'de>,
+            {
+                super::deserialize_with(deserializer, super::NonFinite::$policy)
+            }
+        }
+    };
+}
+
+policy_module!(
+    reject,
+    Reject,
+    "`#[serde(with = \"..\")]` functions failing on non-finite floats."
+);
+policy_module!(
+    null,
+    Null,
+    "`#[serde(with = \"..\")]` functions sending non-finite floats as `null`."
+);
+policy_module!(
+    string,
+    String,
+    "`#[serde(with = \"..\")]` functions sending non-finite floats as strings."
+);
+
+/// The string sent for a non-finite float.
+fn non_finite_name(value: f64) -> &'static str {
+    if value.is_nan() {
+        "NaN"
+    } else if value > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+// ==================== Serialization ====================
+
+struct FloatSerializer<S> {
+    inner: S,
+    policy: NonFinite,
+}
+
+/// A value serialized under the policy, for elements of compound values.
+struct Wrapped<'a, T: ?Sized> {
+    value: &'a T,
+    policy: NonFinite,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Wrapped<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_with(self.value, serializer, self.policy)
+    }
+}
+
+impl<S> FloatSerializer<S> {
+    fn wrap<'a, T: ?Sized>(&self, value: &'a T) -> Wrapped<'a, T> {
+        Wrapped {
+            value,
+            policy: self.policy,
+        }
+    }
+}
+
+macro_rules! forward_serialize {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, value: $ty) -> Result<S::Ok, S::Error> {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for FloatSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    forward_serialize!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_f32(self, value: f32) -> Result<S::Ok, S::Error> {
+        if value.is_finite() || self.policy == NonFinite::Null {
+            return self.inner.serialize_f32(value);
+        }
+        self.serialize_f64(value as f64)
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<S::Ok, S::Error> {
+        match self.policy {
+            _ if value.is_finite() => self.inner.serialize_f64(value),
+            // Serializers already write non-finite floats as `null`
+            NonFinite::Null => self.inner.serialize_f64(value),
+            NonFinite::Reject => Err(ser::Error::custom(format!(
+                "non-finite float {} can't be sent",
+                value
+            ))),
+            NonFinite::String => self.inner.serialize_str(non_finite_name(value)),
+        }
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let value = self.wrap(value);
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let policy = self.policy;
+        self.inner
+            .serialize_seq(len)
+            .map(|inner| Compound { inner, policy })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let policy = self.policy;
+        self.inner
+            .serialize_tuple(len)
+            .map(|inner| Compound { inner, policy })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let policy = self.policy;
+        self.inner
+            .serialize_tuple_struct(name, len)
+            .map(|inner| Compound { inner, policy })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let policy = self.policy;
+        self.inner
+            .serialize_tuple_variant(name, variant_index, variant, len)
+            .map(|inner| Compound { inner, policy })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let policy = self.policy;
+        self.inner
+            .serialize_map(len)
+            .map(|inner| Compound { inner, policy })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let policy = self.policy;
+        self.inner
+            .serialize_struct(name, len)
+            .map(|inner| Compound { inner, policy })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let policy = self.policy;
+        self.inner
+            .serialize_struct_variant(name, variant_index, variant, len)
+            .map(|inner| Compound { inner, policy })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// A compound value whose elements are serialized under the policy.
+struct Compound<C> {
+    inner: C,
+    policy: NonFinite,
+}
+
+impl<C> Compound<C> {
+    fn wrap<'a, T: ?Sized>(&self, value: &'a T) -> Wrapped<'a, T> {
+        Wrapped {
+            value,
+            policy: self.policy,
+        }
+    }
+}
+
+impl<C: ser::SerializeSeq> ser::SerializeSeq for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTuple> ser::SerializeTuple for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleStruct> ser::SerializeTupleStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleVariant> ser::SerializeTupleVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeMap> ser::SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        let key = self.wrap(key);
+        self.inner.serialize_key(&key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_value(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStruct> ser::SerializeStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStructVariant> ser::SerializeStructVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+// ==================== Deserialization ====================
+
+struct FloatDeserializer<D> {
+    inner: D,
+    policy: NonFinite,
+}
+
+/// Hands the values a visitor gets on under the policy.
+struct FloatVisitor<V> {
+    inner: V,
+    policy: NonFinite,
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                let visitor = FloatVisitor {
+                    inner: visitor,
+                    policy: self.policy,
+                };
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for FloatDeserializer<D> {
+    type Error = D::Error;
+
+    forward_deserialize!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    );
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        match self.policy {
+            NonFinite::Reject => self.inner.deserialize_f64(visitor),
+            // `null` and the strings only show up as what they are
+            policy => self.inner.deserialize_any(NonFiniteVisitor {
+                inner: visitor,
+                policy,
+            }),
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Visits a float sent under the `null` or `string` policy.
+struct NonFiniteVisitor<V> {
+    inner: V,
+    policy: NonFinite,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for NonFiniteVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<V::Value, E> {
+        self.inner.visit_i64(value)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<V::Value, E> {
+        self.inner.visit_u64(value)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<V::Value, E> {
+        self.inner.visit_f64(value)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        match self.policy {
+            NonFinite::Null => self.inner.visit_f64(f64::NAN),
+            _ => self.inner.visit_unit(),
+        }
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.visit_unit()
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<V::Value, E> {
+        let float = match value {
+            "NaN" => f64::NAN,
+            "Infinity" => f64::INFINITY,
+            "-Infinity" => f64::NEG_INFINITY,
+            _ => return self.inner.visit_str(value),
+        };
+        match self.policy {
+            NonFinite::String => self.inner.visit_f64(float),
+            _ => self.inner.visit_str(value),
+        }
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<E: de::Error>(self, value: $ty) -> Result<V::Value, E> {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for FloatVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    forward_visit!(
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    );
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.inner.visit_some(FloatDeserializer {
+            inner: deserializer,
+            policy: self.policy,
+        })
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_unit()
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.inner.visit_newtype_struct(FloatDeserializer {
+            inner: deserializer,
+            policy: self.policy,
+        })
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_seq(FloatAccess {
+            inner: seq,
+            policy: self.policy,
+        })
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_map(FloatAccess {
+            inner: map,
+            policy: self.policy,
+        })
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_enum(FloatAccess {
+            inner: data,
+            policy: self.policy,
+        })
+    }
+}
+
+/// Deserializes the element a seed is given under the policy.
+struct FloatSeed<S> {
+    inner: S,
+    policy: NonFinite,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for FloatSeed<S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.inner.deserialize(FloatDeserializer {
+            inner: deserializer,
+            policy: self.policy,
+        })
+    }
+}
+
+/// Sequences, maps, enums and variants whose elements are deserialized
+/// under the policy.
+struct FloatAccess<A> {
+    inner: A,
+    policy: NonFinite,
+}
+
+impl<A> FloatAccess<A> {
+    fn seed<S>(&self, seed: S) -> FloatSeed<S> {
+        FloatSeed {
+            inner: seed,
+            policy: self.policy,
+        }
+    }
+}
+
+impl<'de, A: de::SeqAccess<'de>> de::SeqAccess<'de> for FloatAccess<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.next_element_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: de::MapAccess<'de>> de::MapAccess<'de> for FloatAccess<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.next_key_seed(seed)
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: de::EnumAccess<'de>> de::EnumAccess<'de> for FloatAccess<A> {
+    type Error = A::Error;
+    type Variant = FloatAccess<A::Variant>;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, Self::Variant), A::Error> {
+        let policy = self.policy;
+        let (value, variant) = self.inner.variant_seed(seed)?;
+        Ok((
+            value,
+            FloatAccess {
+                inner: variant,
+                policy,
+            },
+        ))
+    }
+}
+
+impl<'de, A: de::VariantAccess<'de>> de::VariantAccess<'de> for FloatAccess<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, A::Error> {
+        let visitor = FloatVisitor {
+            inner: visitor,
+            policy: self.policy,
+        };
+        self.inner.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        let visitor = FloatVisitor {
+            inner: visitor,
+            policy: self.policy,
+        };
+        self.inner.struct_variant(fields, visitor)
+    }
+}
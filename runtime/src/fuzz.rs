@@ -0,0 +1,58 @@
+//! Fuzzing of commands dispatched by `bridge_fuzz!`.
+//!
+//! The first byte of the fuzzer's input picks the command, and the rest is
+//! parsed as its JSON arguments. Rejected calls are expected; a panic in
+//! the argument decoding or the command is what the fuzzer reports.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::Thread;
+
+use serde_json::Value;
+
+/// Call one of `commands` through `dispatch` with the arguments in `data`.
+///
+/// Inputs that aren't JSON after the first byte are skipped, so the fuzzer
+/// moves on to ones that reach the commands.
+pub fn run(
+    data: &[u8],
+    commands: &[&str],
+    dispatch: impl AsyncFn(&str, Value) -> Result<Value, Value>,
+) {
+    let Some((&selector, json)) = data.split_first() else {
+        return;
+    };
+    if commands.is_empty() {
+        return;
+    }
+    let Ok(args) = serde_json::from_slice::<Value>(json) else {
+        return;
+    };
+    let command = commands[selector as usize % commands.len()];
+    let _ = block_on(dispatch(command, args));
+}
+
+/// Drive `future` to completion on the current thread.
+///
+/// Fuzz targets have no async runtime; commands waiting on one hang.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(Unpark(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
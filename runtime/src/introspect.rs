@@ -0,0 +1,152 @@
+//! Startup handshake of `bridge_introspect!`.
+//!
+//! Both sides build a [`Manifest`] of the same command list: the backend
+//! serves its own through the `__bridge_introspect` command, and the
+//! client [`check`](Manifest::check)s it against the one it was compiled
+//! with. A frontend bundle built against other commands then fails once,
+//! with the commands that differ, instead of on every affected call.
+//!
+//! Commands generated with `verify_schema` also check on every call: the
+//! client sends the hash of the command's parameters and response, and the
+//! backend rejects calls with another one through [`verify_schema`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A command as compiled into one side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandInfo {
+    /// Base command name, without the version suffix.
+    pub name: String,
+    pub version: u32,
+    /// Hash of the command's parameter and response schema.
+    pub schema_hash: String,
+}
+
+impl CommandInfo {
+    /// The entry of a command with the given OpenRPC method description.
+    pub fn new(name: &str, version: u32, spec: &str) -> Self {
+        CommandInfo {
+            name: name.to_string(),
+            version,
+            schema_hash: schema_hash(spec),
+        }
+    }
+}
+
+/// The commands of one side, sorted by name and version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub commands: Vec<CommandInfo>,
+}
+
+impl Manifest {
+    pub fn new(mut commands: Vec<CommandInfo>) -> Self {
+        commands.sort_by(|a, b| (&a.name, a.version).cmp(&(&b.name, b.version)));
+        Manifest { commands }
+    }
+
+    /// Check that `backend` serves every command of this manifest with
+    /// the same schema. Commands only the backend has are fine.
+    pub fn check(&self, backend: &Manifest) -> Result<(), Mismatch> {
+        let mut mismatch = Mismatch::default();
+        for command in &self.commands {
+            let served = backend
+                .commands
+                .iter()
+                .find(|served| served.name == command.name && served.version == command.version);
+            let label = match command.version {
+                1 => command.name.clone(),
+                version => format!("{}@{}", command.name, version),
+            };
+            match served {
+                None => mismatch.missing.push(label),
+                Some(served) if served.schema_hash != command.schema_hash => {
+                    mismatch.changed.push(label)
+                }
+                Some(_) => {}
+            }
+        }
+        if mismatch.missing.is_empty() && mismatch.changed.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatch)
+        }
+    }
+}
+
+/// How the backend differs from the client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Commands the backend doesn't serve.
+    pub missing: Vec<String>,
+    /// Commands whose parameters or response differ.
+    pub changed: Vec<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frontend and backend were built from different commands")?;
+        if !self.missing.is_empty() {
+            write!(f, "; missing on the backend: {}", self.missing.join(", "))?;
+        }
+        if !self.changed.is_empty() {
+            write!(f, "; changed: {}", self.changed.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// FNV-1a hash of a command's method description, as 16 hex digits.
+///
+/// Stable across builds and platforms, unlike `std`'s hashers.
+pub fn schema_hash(spec: &str) -> String {
+    let hash = spec.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// A call from a client built against another schema of the command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaMismatch {
+    pub command: String,
+    /// Schema hash the client was built with.
+    pub sent: String,
+    /// Schema hash of the backend's command.
+    pub expected: String,
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` was called by a client built against another version of it \
+             (client schema {}, backend schema {}); rebuild the frontend and backend together",
+            self.command, self.sent, self.expected
+        )
+    }
+}
+
+impl std::error::Error for SchemaMismatch {}
+
+/// Check the schema hash a client `sent` for `command` against the
+/// `expected` one. Calls without a hash, from clients built without
+/// `verify_schema`, pass.
+pub fn verify_schema(
+    command: &str,
+    expected: &str,
+    sent: Option<&str>,
+) -> Result<(), SchemaMismatch> {
+    match sent {
+        Some(sent) if sent != expected => Err(SchemaMismatch {
+            command: command.to_string(),
+            sent: sent.to_string(),
+            expected: expected.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
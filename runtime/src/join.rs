@@ -0,0 +1,76 @@
+//! Calls of several commands at once with `bridge_join!`.
+//!
+//! ```rust,ignore
+//! let (user, settings) = tauri_bridge::bridge_join!(get_user(1), get_settings()).await?;
+//! ```
+//!
+//! Each listed call runs as a [`Joined`] future, polled together until all
+//! of them are done. Their errors, whatever the type, become a
+//! [`JoinError`] naming the failed command.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The first failed call of a `bridge_join!`, in the listed order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinError {
+    /// The client function's name, as listed.
+    pub command: &'static str,
+    pub message: String,
+}
+
+impl JoinError {
+    /// The error `command` returned.
+    pub fn new(command: &'static str, error: impl fmt::Display) -> Self {
+        JoinError {
+            command,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` failed: {}", self.command, self.message)
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+/// A call of a `bridge_join!`, keeping its output once done.
+pub enum Joined<F: Future> {
+    Running(Pin<Box<F>>),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> Joined<F> {
+    pub fn new(future: F) -> Self {
+        Joined::Running(Box::pin(future))
+    }
+
+    /// Poll the call if it's still running, and return whether it's done.
+    pub fn poll(&mut self, cx: &mut Context<'_>) -> bool {
+        if let Joined::Running(future) = self {
+            match future.as_mut().poll(cx) {
+                Poll::Ready(output) => *self = Joined::Done(output),
+                Poll::Pending => return false,
+            }
+        }
+        true
+    }
+
+    /// The output of the call.
+    ///
+    /// # Panics
+    ///
+    /// If the call isn't done, or its output was already taken.
+    pub fn take(&mut self) -> F::Output {
+        match std::mem::replace(self, Joined::Taken) {
+            Joined::Done(output) => output,
+            _ => panic!("the joined call isn't done"),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Backend hooks around commands generated with `layers`.
+//!
+//! ```rust,ignore
+//! struct Metrics;
+//!
+//! impl BridgeLayer for Metrics {
+//!     fn after(&self, call: &Call<'_>, outcome: &Outcome) {
+//!         println!("{} took {:?}", call.command, outcome.elapsed);
+//!     }
+//! }
+//!
+//! tauri::Builder::default().manage(Layers::new().with(Metrics))
+//! ```
+//!
+//! Layered commands look up the managed [`Layers`] on every call and run
+//! each layer's [`before`](BridgeLayer::before) in order, then the command,
+//! then each [`after`](BridgeLayer::after) in reverse order. Without
+//! managed `Layers` the command runs as usual.
+
+use std::time::{Duration, Instant};
+
+/// A command call seen by the layers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Call<'a> {
+    /// Wire name of the command.
+    pub command: &'a str,
+    /// Label of the webview that invoked the command.
+    pub webview_label: &'a str,
+}
+
+/// How a command call went.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outcome {
+    pub elapsed: Duration,
+    /// Whether the command returned an error.
+    pub failed: bool,
+}
+
+/// Hooks run around every layered command.
+pub trait BridgeLayer: Send + Sync + 'static {
+    /// Runs before the command; an error rejects the call with the message
+    /// without running the command or later layers.
+    fn before(&self, call: &Call<'_>) -> Result<(), String> {
+        let _ = call;
+        Ok(())
+    }
+
+    /// Runs after the command, unless a layer rejected the call.
+    fn after(&self, call: &Call<'_>, outcome: &Outcome) {
+        let _ = (call, outcome);
+    }
+}
+
+/// The layers of an app, installed as Tauri managed state.
+#[derive(Default)]
+pub struct Layers {
+    layers: Vec<Box<dyn BridgeLayer>>,
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a layer running inside the layers added before it.
+    pub fn with(mut self, layer: impl BridgeLayer) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+}
+
+/// A call that passed the `before` hooks, waiting for its outcome.
+pub struct Entered<'a> {
+    layers: Option<&'a Layers>,
+    call: Call<'a>,
+    started: Instant,
+}
+
+/// Run the `before` hooks of `layers` for `call`.
+pub fn enter<'a>(layers: Option<&'a Layers>, call: Call<'a>) -> Result<Entered<'a>, String> {
+    for layer in layers.into_iter().flat_map(|layers| &layers.layers) {
+        layer.before(&call)?;
+    }
+    Ok(Entered {
+        layers,
+        call,
+        started: Instant::now(),
+    })
+}
+
+impl Entered<'_> {
+    /// Run the `after` hooks with the command's outcome.
+    pub fn exit(self, failed: bool) {
+        let outcome = Outcome {
+            elapsed: self.started.elapsed(),
+            failed,
+        };
+        for layer in self
+            .layers
+            .into_iter()
+            .flat_map(|layers| layers.layers.iter().rev())
+        {
+            layer.after(&self.call, &outcome);
+        }
+    }
+}
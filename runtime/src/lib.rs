@@ -0,0 +1,72 @@
+//! Runtime support for clients generated by `#[tauri_bridge]`.
+//!
+//! The macro crate only generates code; features that need state shared
+//! between calls (queues, breakers, schedulers, the query cache, listener
+//! subscriptions) live here. Add this crate to the frontend, or to the
+//! backend for `compress`, `encrypt`, `layers`, `metrics`, `catch_panic`,
+//! `CallerContext` and `#[session]` parameters, `anyhow`/`eyre` results,
+//! `ApiResponse` envelopes, `remote` backends, `sidecar` commands,
+//! `max_payload` limits, transactions, self-checks, `map_as = "entries"`
+//! and `floats` policies, when using an option that requires it.
+
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "bootstrap")]
+pub mod bootstrap;
+pub mod breaker;
+pub mod caller;
+pub mod chunks;
+pub mod clock;
+#[cfg(feature = "gzip")]
+pub mod compress;
+pub mod connection;
+#[cfg(feature = "contract")]
+pub mod contract;
+pub mod debug_log;
+#[cfg(feature = "demo")]
+pub mod demo;
+#[cfg(feature = "encrypt")]
+pub mod encrypt;
+pub mod entries;
+pub mod envelope;
+pub mod environment;
+pub mod error;
+#[cfg(feature = "files")]
+pub mod files;
+pub mod floats;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod introspect;
+pub mod join;
+pub mod layer;
+pub mod messages;
+pub mod metrics;
+pub mod offline;
+pub mod panic;
+#[cfg(feature = "payload")]
+pub mod payload;
+#[cfg(feature = "persist")]
+pub mod persist;
+pub mod platform;
+#[cfg(feature = "query")]
+pub mod query;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "replay")]
+pub mod replay;
+pub mod scheduler;
+#[cfg(feature = "selfcheck")]
+pub mod selfcheck;
+pub mod serial;
+pub mod session;
+#[cfg(feature = "sidecar")]
+pub mod sidecar;
+#[cfg(feature = "signing")]
+pub mod signing;
+pub mod slow;
+pub mod startup;
+pub mod state;
+pub mod subscription;
+#[cfg(feature = "transaction")]
+pub mod transaction;
+pub mod validate;
@@ -0,0 +1,163 @@
+//! User-facing messages for the failed calls of `translate_errors` commands.
+//!
+//! ```rust,ignore
+//! tauri_bridge_runtime::messages::set_translator(|failure| match failure.kind {
+//!     FailureKind::Response => Some(t!("errors.bad_response", command = failure.command)),
+//!     _ => None,
+//! });
+//! ```
+//!
+//! Generated clients describe failed calls in English, such as
+//! `Failed to deserialize response: ..`. Their `try_` functions hand each
+//! such message to the [`Translate`] impl of their error type, which asks
+//! the translator set with [`set_translator`] for the message to return
+//! instead, before it reaches the UI or the plain function panics with it.
+//! Typed errors, like `BreakerError::Open`, are left for the app to match
+//! on; only their `Failed` messages are translated.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::breaker::BreakerError;
+use crate::panic::BridgeError;
+use crate::platform::PlatformError;
+use crate::validate::ResponseError;
+
+/// Prefix of the messages of arguments that couldn't be serialized.
+pub const ARGUMENTS_FAILED: &str = "Failed to serialize arguments: ";
+
+/// Prefixes of the messages of responses that couldn't be decoded.
+pub const RESPONSE_FAILED: &[&str] = &[
+    "Failed to deserialize response: ",
+    "Failed to deserialize batch: ",
+];
+
+/// What part of a call failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The arguments couldn't be serialized; nothing was sent.
+    Arguments,
+    /// The response, or a batch of it, couldn't be decoded.
+    Response,
+    /// Anything else, e.g. a rejected invoke or an unverified response.
+    Other,
+}
+
+/// A failed call, as handed to the translator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Failure<'a> {
+    /// The command's wire name.
+    pub command: &'a str,
+    pub kind: FailureKind,
+    /// The generated message.
+    pub message: &'a str,
+    /// The message without its prefix: the underlying error.
+    pub detail: &'a str,
+}
+
+impl<'a> Failure<'a> {
+    /// Tell what failed from the generated `message`.
+    pub fn new(command: &'a str, message: &'a str) -> Self {
+        let (kind, detail) = if let Some(detail) = message.strip_prefix(ARGUMENTS_FAILED) {
+            (FailureKind::Arguments, detail)
+        } else if let Some(detail) = RESPONSE_FAILED
+            .iter()
+            .find_map(|prefix| message.strip_prefix(prefix))
+        {
+            (FailureKind::Response, detail)
+        } else {
+            (FailureKind::Other, message)
+        };
+        Failure {
+            command,
+            kind,
+            message,
+            detail,
+        }
+    }
+}
+
+type Translator = Rc<dyn Fn(&Failure<'_>) -> Option<String>>;
+
+thread_local! {
+    static TRANSLATOR: RefCell<Option<Translator>> = const { RefCell::new(None) };
+}
+
+/// Translate the messages of failed calls with `translate`, replacing the
+/// previous translator. Returning `None` keeps the generated message.
+pub fn set_translator(translate: impl Fn(&Failure<'_>) -> Option<String> + 'static) {
+    TRANSLATOR.with(|translator| *translator.borrow_mut() = Some(Rc::new(translate)));
+}
+
+/// Keep the generated messages again.
+pub fn clear_translator() {
+    TRANSLATOR.with(|translator| translator.borrow_mut().take());
+}
+
+/// The message to return for the failed call of `command`.
+pub fn translate(command: &str, message: String) -> String {
+    let translator = TRANSLATOR.with(|translator| translator.borrow().clone());
+    // The translator may set another one
+    translator
+        .and_then(|translate| translate(&Failure::new(command, &message)))
+        .unwrap_or(message)
+}
+
+/// Errors of the `try_` functions whose messages can be translated.
+pub trait Translate {
+    /// Translate the message of a failed call of `command`.
+    fn translate(self, command: &str) -> Self;
+}
+
+impl Translate for String {
+    fn translate(self, command: &str) -> Self {
+        translate(command, self)
+    }
+}
+
+impl Translate for BridgeError {
+    fn translate(self, command: &str) -> Self {
+        match self {
+            BridgeError::Failed(message) => BridgeError::Failed(translate(command, message)),
+            panicked => panicked,
+        }
+    }
+}
+
+impl Translate for BreakerError {
+    fn translate(self, command: &str) -> Self {
+        match self {
+            BreakerError::Failed(message) => BreakerError::Failed(translate(command, message)),
+            open => open,
+        }
+    }
+}
+
+impl Translate for PlatformError {
+    fn translate(self, command: &str) -> Self {
+        match self {
+            PlatformError::Failed(message) => PlatformError::Failed(translate(command, message)),
+            unsupported => unsupported,
+        }
+    }
+}
+
+impl Translate for ResponseError {
+    fn translate(self, command: &str) -> Self {
+        match self {
+            ResponseError::Failed(message) => ResponseError::Failed(translate(command, message)),
+            invalid => invalid,
+        }
+    }
+}
+
+#[cfg(feature = "payload")]
+impl Translate for crate::payload::PayloadError {
+    fn translate(self, command: &str) -> Self {
+        use crate::payload::PayloadError;
+        match self {
+            PayloadError::Failed(message) => PayloadError::Failed(translate(command, message)),
+            too_large => too_large,
+        }
+    }
+}
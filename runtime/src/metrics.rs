@@ -0,0 +1,90 @@
+//! Per-command metrics of commands generated with `metrics`.
+//!
+//! Metered commands [`record`] each call in a process-wide registry:
+//! calls, errors and a duration histogram per wire name. [`bridge_metrics`]
+//! returns a serializable snapshot for a diagnostics page, and
+//! `bridge_metrics_command!()` bridges it as `get_bridge_metrics`.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Upper bounds of the duration buckets, in microseconds.
+pub const BUCKET_BOUNDS_MICROS: [u64; 8] = [
+    1_000, 5_000, 10_000, 50_000, 100_000, 500_000, 1_000_000, 5_000_000,
+];
+
+static REGISTRY: Mutex<BTreeMap<String, CommandMetrics>> = Mutex::new(BTreeMap::new());
+
+/// Metrics of one command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandMetrics {
+    pub calls: u64,
+    /// Calls that returned an `Err`.
+    pub errors: u64,
+    pub total_micros: u64,
+    pub max_micros: u64,
+    /// Calls per duration bucket: `buckets[i]` counts the calls taking at
+    /// most `BUCKET_BOUNDS_MICROS[i]`, the last one the slower calls.
+    pub buckets: Vec<u64>,
+}
+
+impl Default for CommandMetrics {
+    fn default() -> Self {
+        CommandMetrics {
+            calls: 0,
+            errors: 0,
+            total_micros: 0,
+            max_micros: 0,
+            buckets: vec![0; BUCKET_BOUNDS_MICROS.len() + 1],
+        }
+    }
+}
+
+impl CommandMetrics {
+    /// Mean duration of the calls.
+    pub fn mean(&self) -> Duration {
+        match self.calls {
+            0 => Duration::ZERO,
+            calls => Duration::from_micros(self.total_micros / calls),
+        }
+    }
+}
+
+/// Metrics of every command called so far, by wire name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub commands: BTreeMap<String, CommandMetrics>,
+}
+
+/// Record a call of `command`.
+pub fn record(command: &str, elapsed: Duration, failed: bool) {
+    let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+    let bucket = BUCKET_BOUNDS_MICROS
+        .iter()
+        .position(|bound| micros <= *bound)
+        .unwrap_or(BUCKET_BOUNDS_MICROS.len());
+
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let metrics = registry.entry(command.to_string()).or_default();
+    metrics.calls += 1;
+    metrics.errors += u64::from(failed);
+    metrics.total_micros = metrics.total_micros.saturating_add(micros);
+    metrics.max_micros = metrics.max_micros.max(micros);
+    metrics.buckets[bucket] += 1;
+}
+
+/// A snapshot of the metrics.
+pub fn bridge_metrics() -> MetricsSnapshot {
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    MetricsSnapshot {
+        commands: registry.clone(),
+    }
+}
+
+/// Forget every recorded call.
+pub fn reset() {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
@@ -0,0 +1,184 @@
+//! Calls waiting for the Tauri transport.
+//!
+//! In a plain browser, or while the webview reloads, the Tauri globals are
+//! missing and invokes fail. Commands generated with `offline_queue` wait
+//! here instead and go out once the transport is reported back with
+//! [`set_transport_available`] or [`transport_ready`].
+//!
+//! The queue is bounded per command. Waiting calls past their timeout fail
+//! the next time the queue is touched.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use crate::clock::now_ms;
+
+#[derive(Default)]
+struct Slot {
+    result: Option<Result<(), String>>,
+    waker: Option<Waker>,
+}
+
+struct Waiter {
+    command: &'static str,
+    deadline: f64,
+    slot: Rc<RefCell<Slot>>,
+}
+
+#[derive(Default)]
+struct State {
+    available: Option<bool>,
+    waiters: VecDeque<Waiter>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Whether invokes can currently reach the backend.
+///
+/// Detected from the Tauri globals unless overridden with
+/// [`set_transport_available`].
+pub fn transport_available() -> bool {
+    STATE
+        .with(|state| state.borrow().available)
+        .unwrap_or_else(detect_transport)
+}
+
+/// Override transport detection; `true` also releases the waiting calls.
+pub fn set_transport_available(available: bool) {
+    STATE.with(|state| state.borrow_mut().available = Some(available));
+    if available {
+        release(true);
+    }
+}
+
+/// Go back to detecting the transport from the Tauri globals.
+pub fn clear_transport_override() {
+    STATE.with(|state| state.borrow_mut().available = None);
+}
+
+/// Release the waiting calls if the transport is available, e.g. from a
+/// page `load` handler after a webview reload.
+pub fn transport_ready() {
+    if transport_available() {
+        release(true);
+    }
+}
+
+/// Number of calls currently waiting for the transport.
+pub fn queued_calls() -> usize {
+    STATE.with(|state| state.borrow().waiters.len())
+}
+
+/// Wait until the transport is available.
+///
+/// Fails right away when `capacity` calls of `command` are already waiting,
+/// and after `timeout_ms` if the transport doesn't come back in time.
+pub fn wait_for_transport(
+    command: &'static str,
+    capacity: usize,
+    timeout_ms: u64,
+) -> WaitForTransport {
+    release(false);
+
+    let slot = Rc::new(RefCell::new(Slot::default()));
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let queued = state
+            .waiters
+            .iter()
+            .filter(|waiter| waiter.command == command)
+            .count();
+        if queued >= capacity {
+            slot.borrow_mut().result = Some(Err(format!(
+                "Offline queue for `{}` is full ({} calls)",
+                command, capacity
+            )));
+        } else {
+            state.waiters.push_back(Waiter {
+                command,
+                deadline: now_ms() + timeout_ms as f64,
+                slot: slot.clone(),
+            });
+        }
+    });
+
+    WaitForTransport { slot }
+}
+
+/// Future returned by [`wait_for_transport`].
+pub struct WaitForTransport {
+    slot: Rc<RefCell<Slot>>,
+}
+
+impl Future for WaitForTransport {
+    type Output = Result<(), String>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.borrow_mut();
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Resolve expired waiters with a timeout, and the others too when `all`.
+fn release(all: bool) {
+    let now = now_ms();
+    let released: Vec<(Waiter, Result<(), String>)> = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let (released, kept) = state
+            .waiters
+            .drain(..)
+            .partition::<Vec<_>, _>(|waiter| all || waiter.deadline < now);
+        state.waiters = kept.into();
+        released
+            .into_iter()
+            .map(|waiter| {
+                let result = if waiter.deadline < now {
+                    Err(format!(
+                        "Timed out waiting for the Tauri transport to call `{}`",
+                        waiter.command
+                    ))
+                } else {
+                    Ok(())
+                };
+                (waiter, result)
+            })
+            .collect()
+    });
+
+    // Wake outside the borrow; wakers may poll synchronously
+    for (waiter, result) in released {
+        let waker = {
+            let mut slot = waiter.slot.borrow_mut();
+            slot.result = Some(result);
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn detect_transport() -> bool {
+    let key = js_sys::JsString::from("__TAURI_INTERNALS__");
+    js_sys::Reflect::get(&js_sys::global(), &key)
+        .map(|value| !value.is_undefined())
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn detect_transport() -> bool {
+    true
+}
@@ -0,0 +1,104 @@
+//! Panics of commands generated with `catch_panic`.
+//!
+//! The backend runs the command body inside [`catch`] or [`catch_async`]
+//! and responds with a [`Caught`] instead of letting the panic drop the
+//! call, which would leave the client waiting for a response forever. The
+//! client unwraps it into the value or a [`BridgeError::Panicked`].
+
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::pin;
+use std::task::Poll;
+
+use serde::{Deserialize, Serialize};
+
+/// Response of a command with `catch_panic`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Caught<T> {
+    /// The command returned normally.
+    Value(T),
+    /// The command panicked with `message`.
+    Panicked { message: String },
+}
+
+impl<T> Caught<T> {
+    pub fn is_panicked(&self) -> bool {
+        matches!(self, Caught::Panicked { .. })
+    }
+}
+
+/// Run `f`, catching a panic.
+///
+/// The body of a command is assumed to be unwind safe: state it leaves
+/// behind is the app's to recover, as with a panicking Tauri command.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Caught<T> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => Caught::Value(value),
+        Err(payload) => panicked(payload),
+    }
+}
+
+/// Run `future`, catching a panic in any of its polls.
+pub async fn catch_async<F: Future>(future: F) -> Caught<F::Output> {
+    let mut future = pin!(future);
+    std::future::poll_fn(
+        |cx| match catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+            Ok(Poll::Ready(value)) => Poll::Ready(Caught::Value(value)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(panicked(payload)),
+        },
+    )
+    .await
+}
+
+fn panicked<T>(payload: Box<dyn Any + Send>) -> Caught<T> {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "command panicked".to_string(),
+        },
+    };
+    Caught::Panicked { message }
+}
+
+/// Error of a command with `catch_panic` or `detect_tauri`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BridgeError {
+    /// The command panicked on the backend.
+    Panicked { message: String },
+    /// The client doesn't run inside Tauri; nothing was sent.
+    NotInTauri { command: &'static str },
+    /// The call itself failed.
+    Failed(String),
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BridgeError::Panicked { message } => write!(f, "command panicked: {}", message),
+            BridgeError::NotInTauri { command } => {
+                write!(f, "`{}` can't be invoked outside of Tauri", command)
+            }
+            BridgeError::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+impl From<String> for BridgeError {
+    fn from(error: String) -> Self {
+        BridgeError::Failed(error)
+    }
+}
+
+/// Unwrap the response of a command with `catch_panic`.
+pub fn unwrap<T>(response: Result<Caught<T>, String>) -> Result<T, BridgeError> {
+    match response? {
+        Caught::Value(value) => Ok(value),
+        Caught::Panicked { message } => Err(BridgeError::Panicked { message }),
+    }
+}
@@ -0,0 +1,93 @@
+//! Argument size limits of commands generated with `max_payload`.
+//!
+//! The client measures the arguments it's about to send as JSON and fails
+//! with [`PayloadError::TooLarge`] without invoking. It's a client-side
+//! check: the backend doesn't limit what other frontends send, since Tauri
+//! reads and decodes the arguments before the command runs.
+//!
+//! The JSON is counted without being buffered, and serialization stops at
+//! the first write past the limit.
+
+use std::fmt;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+/// Arguments of a call exceeding the command's `max_payload`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayloadTooLarge {
+    pub command: String,
+    /// Bytes counted before giving up, more than `limit`.
+    pub size: usize,
+    pub limit: usize,
+}
+
+impl fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arguments of `{}` exceed the {} byte limit",
+            self.command, self.limit
+        )
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
+/// Error of a command with `max_payload`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayloadError {
+    /// The arguments are over the limit; nothing was sent.
+    TooLarge(PayloadTooLarge),
+    /// The call itself failed.
+    Failed(String),
+}
+
+impl fmt::Display for PayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadError::TooLarge(error) => error.fmt(f),
+            PayloadError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for PayloadError {}
+
+/// Fail when `args` take more than `limit` bytes of JSON.
+pub fn check<T: Serialize + ?Sized>(
+    command: &str,
+    limit: usize,
+    args: &T,
+) -> Result<(), PayloadTooLarge> {
+    let mut counter = Counter { size: 0, limit };
+    // Only the counter fails, once it's past the limit
+    match serde_json::to_writer(&mut counter, args) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(PayloadTooLarge {
+            command: command.to_string(),
+            size: counter.size,
+            limit,
+        }),
+    }
+}
+
+/// Counts written bytes, failing past the limit.
+struct Counter {
+    size: usize,
+    limit: usize,
+}
+
+impl io::Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.size += buf.len();
+        if self.size > self.limit {
+            return Err(io::Error::other("payload too large"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+//! Persistence of `cached(persist)` responses across webview reloads.
+//!
+//! Responses of persisted commands are also written to a [`Store`], the
+//! webview's `localStorage` unless the app sets its own with [`set_store`],
+//! and restored into the query cache on the first read after a reload, so
+//! cold starts don't refetch data that can't have changed.
+//!
+//! Each query key is stored as one item, tagged with the schema hash of the
+//! command's parameters and response (the hash of `bridge_introspect!`
+//! manifests). Responses stored by a build with other types are dropped
+//! instead of decoded. Invalidating a key also removes its item.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::query;
+
+/// Prefix of the items persisted responses are stored under.
+pub const ITEM_PREFIX: &str = "tauri-bridge:";
+
+/// Synchronous key-value storage for persisted responses.
+///
+/// Asynchronous storage such as IndexedDB can back a store that loads it
+/// into memory before the first read and writes changes through.
+pub trait Store {
+    fn get(&self, item: &str) -> Option<String>;
+    fn set(&self, item: &str, value: &str);
+    fn remove(&self, item: &str);
+}
+
+thread_local! {
+    static STORE: RefCell<Option<Rc<dyn Store>>> = const { RefCell::new(None) };
+}
+
+/// Persist responses in `store` instead of `localStorage`.
+pub fn set_store(store: impl Store + 'static) {
+    STORE.with(|slot| *slot.borrow_mut() = Some(Rc::new(store)));
+}
+
+/// Go back to the default store.
+pub fn clear_store() {
+    STORE.with(|slot| *slot.borrow_mut() = None);
+}
+
+pub(crate) fn store() -> Option<Rc<dyn Store>> {
+    STORE
+        .with(|slot| slot.borrow().clone())
+        .or_else(default_store)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn default_store() -> Option<Rc<dyn Store>> {
+    local_storage().map(|_| Rc::new(LocalStorage) as Rc<dyn Store>)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_store() -> Option<Rc<dyn Store>> {
+    None
+}
+
+/// The webview's `localStorage`.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<js_sys::Object> {
+    // Reading `localStorage` throws where storage is disabled
+    let storage =
+        js_sys::Reflect::get(&js_sys::global(), &js_sys::JsString::from("localStorage")).ok()?;
+    (!storage.is_undefined() && !storage.is_null()).then(|| js_sys::Object::from(storage))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_method(storage: &js_sys::Object, name: &str) -> Option<js_sys::Function> {
+    js_sys::Reflect::get(storage, &js_sys::JsString::from(name))
+        .ok()
+        .map(js_sys::Function::from)
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Store for LocalStorage {
+    fn get(&self, item: &str) -> Option<String> {
+        let storage = local_storage()?;
+        storage_method(&storage, "getItem")?
+            .call1(&storage, &js_sys::JsString::from(item))
+            .ok()?
+            .as_string()
+    }
+
+    fn set(&self, item: &str, value: &str) {
+        // Writes fail once the quota is used up; the response stays in memory
+        if let Some(storage) = local_storage()
+            && let Some(set_item) = storage_method(&storage, "setItem")
+        {
+            let _ = set_item.call2(
+                &storage,
+                &js_sys::JsString::from(item),
+                &js_sys::JsString::from(value),
+            );
+        }
+    }
+
+    fn remove(&self, item: &str) {
+        if let Some(storage) = local_storage()
+            && let Some(remove_item) = storage_method(&storage, "removeItem")
+        {
+            let _ = remove_item.call1(&storage, &js_sys::JsString::from(item));
+        }
+    }
+}
+
+/// The persisted responses of a query key.
+#[derive(Serialize, Deserialize)]
+struct Item {
+    /// Schema hash of the command that stored the responses
+    version: String,
+    /// Responses by serialized arguments
+    responses: HashMap<String, Value>,
+}
+
+fn read_item(store: &dyn Store, key: &str, version: &str) -> Option<Item> {
+    let item = format!("{}{}", ITEM_PREFIX, key);
+    let stored: Item = serde_json::from_str(&store.get(&item)?).ok()?;
+    if stored.version != version {
+        store.remove(&item);
+        return None;
+    }
+    Some(stored)
+}
+
+/// The persisted response of `key` for `args`, if stored by a build with
+/// the same `version`.
+pub fn load<T: DeserializeOwned>(key: &str, version: &str, args: &str) -> Option<T> {
+    let store = store()?;
+    let mut item = read_item(store.as_ref(), key, version)?;
+    serde_json::from_value(item.responses.remove(args)?).ok()
+}
+
+/// Persist `response` of `key` for `args`.
+pub fn save<T: Serialize>(key: &str, version: &str, args: String, response: &T) {
+    let Some(store) = store() else {
+        return;
+    };
+    let Ok(response) = serde_json::to_value(response) else {
+        return;
+    };
+    let mut item = read_item(store.as_ref(), key, version).unwrap_or_else(|| Item {
+        version: version.to_string(),
+        responses: HashMap::new(),
+    });
+    item.responses.insert(args, response);
+    if let Ok(value) = serde_json::to_string(&item) {
+        store.set(&format!("{}{}", ITEM_PREFIX, key), &value);
+    }
+}
+
+/// Remove the persisted responses of `keys`.
+pub fn remove(keys: &[&str]) {
+    if let Some(store) = store() {
+        for key in keys {
+            store.remove(&format!("{}{}", ITEM_PREFIX, key));
+        }
+    }
+}
+
+/// Put the persisted response of `key` for `args` into the query cache,
+/// unless it already holds one.
+pub fn restore<T: DeserializeOwned + 'static>(key: &str, version: &str, args: &str) {
+    if query::has_response(key, args) {
+        return;
+    }
+    if let Some(response) = load::<T>(key, version, args) {
+        query::insert(key, args.to_string(), response);
+    }
+}
+
+/// `fetch`, persisting the response once it succeeds.
+pub async fn saving<T, F>(key: &str, version: &str, args: String, fetch: F) -> Result<T, String>
+where
+    T: Serialize,
+    F: Future<Output = Result<T, String>>,
+{
+    let response = fetch.await;
+    if let Ok(response) = &response {
+        save(key, version, args, response);
+    }
+    response
+}
@@ -0,0 +1,147 @@
+//! The platform the app runs on, for commands limited to some platforms.
+//!
+//! The client is always compiled for `wasm32`, so commands generated with
+//! `platforms(..)` check at runtime instead: on an excluded platform they
+//! fail with [`PlatformError::Unsupported`] without invoking the backend,
+//! which doesn't have the command there.
+//!
+//! The platform is detected from the webview's user agent unless set with
+//! [`set_platform`], e.g. from a backend command or an OS plugin.
+
+use std::cell::Cell;
+use std::fmt;
+
+/// An operating system Tauri builds apps for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Windows,
+    Macos,
+    Linux,
+    Ios,
+    Android,
+}
+
+impl Platform {
+    /// The platform's `target_os` name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::Windows => "windows",
+            Platform::Macos => "macos",
+            Platform::Linux => "linux",
+            Platform::Ios => "ios",
+            Platform::Android => "android",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error of a command limited to some platforms.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlatformError {
+    /// The command isn't available on the current platform; nothing was sent.
+    Unsupported {
+        command: &'static str,
+        platform: Platform,
+    },
+    /// The call itself failed.
+    Failed(String),
+}
+
+impl fmt::Display for PlatformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlatformError::Unsupported { command, platform } => {
+                write!(f, "`{}` is not available on {}", command, platform)
+            }
+            PlatformError::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+impl std::error::Error for PlatformError {}
+
+impl From<String> for PlatformError {
+    fn from(error: String) -> Self {
+        PlatformError::Failed(error)
+    }
+}
+
+thread_local! {
+    static OVERRIDE: Cell<Option<Platform>> = const { Cell::new(None) };
+}
+
+/// The current platform, if known.
+///
+/// Detected from the user agent unless overridden with [`set_platform`].
+pub fn current() -> Option<Platform> {
+    OVERRIDE.with(Cell::get).or_else(detect_platform)
+}
+
+/// Override platform detection.
+pub fn set_platform(platform: Platform) {
+    OVERRIDE.with(|current| current.set(Some(platform)));
+}
+
+/// Go back to detecting the platform from the user agent.
+pub fn reset_platform() {
+    OVERRIDE.with(|current| current.set(None));
+}
+
+/// Fail with [`PlatformError::Unsupported`] unless `command` is available
+/// on the current platform. An unknown platform lets the call through.
+pub fn check(command: &'static str, platforms: &[Platform]) -> Result<(), PlatformError> {
+    match current() {
+        Some(platform) if !platforms.contains(&platform) => {
+            Err(PlatformError::Unsupported { command, platform })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The platform named by a user agent string.
+///
+/// Mobile platforms are checked first, their user agents mention the
+/// desktop platforms they derive from.
+pub fn from_user_agent(user_agent: &str) -> Option<Platform> {
+    if user_agent.contains("Android") {
+        Some(Platform::Android)
+    } else if ["iPhone", "iPad", "iPod"]
+        .iter()
+        .any(|device| user_agent.contains(device))
+    {
+        Some(Platform::Ios)
+    } else if user_agent.contains("Windows") {
+        Some(Platform::Windows)
+    } else if user_agent.contains("Macintosh") || user_agent.contains("Mac OS X") {
+        Some(Platform::Macos)
+    } else if user_agent.contains("Linux") {
+        Some(Platform::Linux)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn detect_platform() -> Option<Platform> {
+    let navigator =
+        js_sys::Reflect::get(&js_sys::global(), &js_sys::JsString::from("navigator")).ok()?;
+    let user_agent = js_sys::Reflect::get(&navigator, &js_sys::JsString::from("userAgent")).ok()?;
+    from_user_agent(&user_agent.as_string()?)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn detect_platform() -> Option<Platform> {
+    match std::env::consts::OS {
+        "windows" => Some(Platform::Windows),
+        "macos" => Some(Platform::Macos),
+        "linux" => Some(Platform::Linux),
+        "ios" => Some(Platform::Ios),
+        "android" => Some(Platform::Android),
+        _ => None,
+    }
+}
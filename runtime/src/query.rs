@@ -0,0 +1,275 @@
+//! Query cache of commands generated with `cached` and `invalidates`.
+//!
+//! Responses of `cached` commands are kept per query key (the command's
+//! wire name) and arguments, so repeated reads don't invoke the backend.
+//! Commands with `invalidates("key", ..)` drop the cached responses of those
+//! keys after each call and notify the [`subscribe`]rs, which is where a
+//! frontend framework's hooks refetch:
+//!
+//! ```rust,ignore
+//! let subscription = query::subscribe(GET_USERS_QUERY_KEY, move || refetch.set(true));
+//! ```
+//!
+//! With `cached(swr)`, a cached response is returned right away and
+//! refreshed in the background ([`stale_while_revalidate`]); the commands'
+//! `on_<name>_refreshed` callbacks get the fresh response when it lands.
+//!
+//! `bridge_prefetch_all!` fills the cache ahead of the first read, e.g. at
+//! startup, through [`prefetch_all`].
+//!
+//! Like the rest of the client, the cache is per thread.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::Poll;
+
+use crate::subscription::Subscription;
+
+type Listener = Rc<dyn Fn()>;
+type RefreshListener = Rc<dyn Fn(&dyn Any)>;
+
+#[derive(Default)]
+struct QueryCache {
+    /// Responses by query key, then by serialized arguments
+    entries: HashMap<String, HashMap<String, Rc<dyn Any>>>,
+    listeners: HashMap<String, Vec<(u64, Listener)>>,
+    refresh_listeners: HashMap<String, Vec<(u64, RefreshListener)>>,
+    /// Query keys and arguments with a background refresh in flight
+    refreshing: HashSet<(String, String)>,
+    next_id: u64,
+}
+
+impl QueryCache {
+    fn get<T: Clone + 'static>(&self, key: &str, args: &str) -> Option<T> {
+        self.entries
+            .get(key)
+            .and_then(|responses| responses.get(args))
+            .and_then(|response| response.downcast_ref::<T>())
+            .cloned()
+    }
+
+    fn insert<T: 'static>(&mut self, key: &str, args: String, response: T) {
+        self.entries
+            .entry(key.to_string())
+            .or_default()
+            .insert(args, Rc::new(response));
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<QueryCache> = RefCell::new(QueryCache::default());
+}
+
+/// The cached response for `key` and `args`, or the response of `fetch`,
+/// cached when it succeeds.
+pub async fn cached<T, F>(key: &str, args: String, fetch: F) -> Result<T, String>
+where
+    T: Clone + 'static,
+    F: Future<Output = Result<T, String>>,
+{
+    let hit = CACHE.with(|cache| cache.borrow().get::<T>(key, &args));
+    if let Some(response) = hit {
+        return Ok(response);
+    }
+
+    let response = fetch.await?;
+    CACHE.with(|cache| cache.borrow_mut().insert(key, args, response.clone()));
+    Ok(response)
+}
+
+/// Like [`cached`], but a cached response is returned right away while
+/// `fetch` refreshes it through `spawn`, once at a time per arguments.
+///
+/// A successful refresh replaces the cached response and is handed to the
+/// [`subscribe_refreshed`] callbacks of `key`; a failed one keeps it, and
+/// one invalidated while in flight is dropped.
+pub async fn stale_while_revalidate<T, F>(
+    key: &str,
+    args: String,
+    fetch: F,
+    spawn: impl FnOnce(Pin<Box<dyn Future<Output = ()>>>),
+) -> Result<T, String>
+where
+    T: Clone + 'static,
+    F: Future<Output = Result<T, String>> + 'static,
+{
+    let hit = CACHE.with(|cache| cache.borrow().get::<T>(key, &args));
+    let Some(stale) = hit else {
+        return cached(key, args, fetch).await;
+    };
+
+    let refresh = (key.to_string(), args);
+    let started = CACHE.with(|cache| cache.borrow_mut().refreshing.insert(refresh.clone()));
+    if started {
+        spawn(Box::pin(async move {
+            let fresh = fetch.await;
+            let (key, args) = refresh;
+            let listeners: Vec<RefreshListener> = CACHE.with(|cache| {
+                let mut cache = cache.borrow_mut();
+                // Invalidated while in flight, the response may predate the change
+                let current = cache.refreshing.remove(&(key.clone(), args.clone()));
+                let (true, Ok(fresh)) = (current, &fresh) else {
+                    return Vec::new();
+                };
+                cache.insert(&key, args, fresh.clone());
+                cache
+                    .refresh_listeners
+                    .get(&key)
+                    .into_iter()
+                    .flatten()
+                    .map(|(_, listener)| listener.clone())
+                    .collect()
+            });
+            if let Ok(fresh) = fresh {
+                // Listeners may read or subscribe again
+                for listener in listeners {
+                    listener(&fresh);
+                }
+            }
+        }));
+    }
+    Ok(stale)
+}
+
+/// A prefetch of `bridge_prefetch_all!`, responding through the cache.
+pub type Prefetch<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// Run `fetches` concurrently until they're all done.
+pub async fn prefetch_all(mut fetches: Vec<Prefetch<'_>>) {
+    poll_fn(|cx| {
+        fetches.retain_mut(|fetch| fetch.as_mut().poll(cx).is_pending());
+        if fetches.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// Whether a response for `key` and `args` is cached.
+pub(crate) fn has_response(key: &str, args: &str) -> bool {
+    CACHE.with(|cache| {
+        cache
+            .borrow()
+            .entries
+            .get(key)
+            .is_some_and(|responses| responses.contains_key(args))
+    })
+}
+
+/// Cache `response` for `key` and `args`, e.g. a response fetched by
+/// `bridge_bootstrap!`.
+pub fn insert<T: 'static>(key: &str, args: String, response: T) {
+    CACHE.with(|cache| cache.borrow_mut().insert(key, args, response));
+}
+
+/// Whether a response for `key` is cached, for any arguments.
+pub fn is_cached(key: &str) -> bool {
+    CACHE.with(|cache| {
+        cache
+            .borrow()
+            .entries
+            .get(key)
+            .is_some_and(|responses| !responses.is_empty())
+    })
+}
+
+/// Drop the cached responses of `keys`, persisted ones too, and notify
+/// their subscribers.
+pub fn invalidate(keys: &[&str]) {
+    let listeners: Vec<Listener> = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        for key in keys {
+            cache.entries.remove(*key);
+        }
+        cache
+            .refreshing
+            .retain(|(key, _)| !keys.contains(&key.as_str()));
+        #[cfg(feature = "persist")]
+        crate::persist::remove(keys);
+        keys.iter()
+            .filter_map(|key| cache.listeners.get(*key))
+            .flatten()
+            .map(|(_, listener)| listener.clone())
+            .collect()
+    });
+    // Listeners may read or subscribe again
+    for listener in listeners {
+        listener();
+    }
+}
+
+/// Drop every cached response, without notifying subscribers.
+pub fn clear() {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        cache.entries.clear();
+        cache.refreshing.clear();
+    });
+}
+
+/// Call `on_invalidate` whenever `key` is invalidated, until the returned
+/// subscription is dropped.
+pub fn subscribe(key: &str, on_invalidate: impl Fn() + 'static) -> Subscription {
+    let key = key.to_string();
+    let id = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let id = cache.next_id();
+        cache
+            .listeners
+            .entry(key.clone())
+            .or_default()
+            .push((id, Rc::new(on_invalidate)));
+        id
+    });
+    Subscription::new(move || {
+        CACHE.with(|cache| {
+            if let Some(listeners) = cache.borrow_mut().listeners.get_mut(&key) {
+                listeners.retain(|(listener, _)| *listener != id);
+            }
+        });
+    })
+}
+
+/// Call `on_refresh` with each response of `key` refreshed in the
+/// background by [`stale_while_revalidate`], until the returned
+/// subscription is dropped.
+pub fn subscribe_refreshed<T: Clone + 'static>(
+    key: &str,
+    on_refresh: impl Fn(T) + 'static,
+) -> Subscription {
+    let key = key.to_string();
+    let listener: RefreshListener = Rc::new(move |fresh: &dyn Any| {
+        if let Some(fresh) = fresh.downcast_ref::<T>() {
+            on_refresh(fresh.clone());
+        }
+    });
+    let id = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let id = cache.next_id();
+        cache
+            .refresh_listeners
+            .entry(key.clone())
+            .or_default()
+            .push((id, listener));
+        id
+    });
+    Subscription::new(move || {
+        CACHE.with(|cache| {
+            if let Some(listeners) = cache.borrow_mut().refresh_listeners.get_mut(&key) {
+                listeners.retain(|(listener, _)| *listener != id);
+            }
+        });
+    })
+}
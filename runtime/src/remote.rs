@@ -0,0 +1,197 @@
+//! Remote backends reached over a WebSocket (or any message channel)
+//! instead of Tauri's IPC.
+//!
+//! When the backend runs out of process or on another machine, the client's
+//! `invoke` sends each call as a JSON [`Request`] frame and resolves it with
+//! the matching [`Response`] frame. Commands and arguments are encoded the
+//! same way as for Tauri, so the generated clients don't change:
+//!
+//! ```rust,ignore
+//! // Client, once the socket is open
+//! let socket = web_sys::WebSocket::new("wss://backend.example/bridge")?;
+//! tauri_bridge_runtime::remote::connect({
+//!     let socket = socket.clone();
+//!     move |frame| socket.send_with_str(&frame).map_err(|_| "send failed".to_string())
+//! });
+//! // In the socket's `onmessage`
+//! tauri_bridge_runtime::remote::receive(&text)?;
+//!
+//! // The crate's `try_invoke`
+//! let response = tauri_bridge_runtime::remote::invoke(command, args).await;
+//!
+//! // Backend, for every text frame
+//! let dispatch = tauri_bridge::bridge_dispatch![api::greet, api::save];
+//! let request = tauri_bridge_runtime::remote::decode_request(&text)?;
+//! let result = dispatch(&request.command, request.args).await;
+//! socket.send(tauri_bridge_runtime::remote::encode_response(request.id, result)).await?;
+//! ```
+//!
+//! Calls are matched to responses by id, so the backend may answer them in
+//! any order. Like the rest of the client, the connection is per thread.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A call sent to the remote backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    /// Id the response is matched by.
+    pub id: u64,
+    /// Wire name of the command.
+    pub command: String,
+    /// Arguments as Tauri would receive them, camelCase keys included.
+    pub args: Value,
+}
+
+/// The backend's answer to a [`Request`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub id: u64,
+    /// The response, or the error the invoke is rejected with.
+    pub result: Result<Value, Value>,
+}
+
+type Sender = Rc<dyn Fn(String) -> Result<(), String>>;
+
+#[derive(Default)]
+struct Slot {
+    result: Option<Result<Value, Value>>,
+    waker: Option<Waker>,
+}
+
+#[derive(Default)]
+struct Connection {
+    sender: Option<Sender>,
+    next_id: u64,
+    pending: HashMap<u64, Rc<RefCell<Slot>>>,
+}
+
+thread_local! {
+    static CONNECTION: RefCell<Connection> = RefCell::new(Connection::default());
+}
+
+/// Send calls through `send`, which gets each [`Request`] as a JSON frame.
+pub fn connect(send: impl Fn(String) -> Result<(), String> + 'static) {
+    CONNECTION.with(|connection| connection.borrow_mut().sender = Some(Rc::new(send)));
+}
+
+/// Drop the connection, failing the calls still waiting with `reason`.
+pub fn disconnect(reason: &str) {
+    let pending: Vec<_> = CONNECTION.with(|connection| {
+        let mut connection = connection.borrow_mut();
+        connection.sender = None;
+        connection.pending.drain().map(|(_, slot)| slot).collect()
+    });
+    for slot in pending {
+        resolve(&slot, Err(Value::String(reason.to_string())));
+    }
+}
+
+/// Whether calls currently have a connection to go out on.
+pub fn is_connected() -> bool {
+    CONNECTION.with(|connection| connection.borrow().sender.is_some())
+}
+
+/// Number of calls waiting for their response.
+pub fn pending_calls() -> usize {
+    CONNECTION.with(|connection| connection.borrow().pending.len())
+}
+
+/// Call `command` on the remote backend.
+///
+/// Fails right away while disconnected or when the frame can't be sent.
+pub fn invoke(command: &str, args: Value) -> RemoteCall {
+    let slot = Rc::new(RefCell::new(Slot::default()));
+    let sent = CONNECTION.with(|connection| {
+        let mut connection = connection.borrow_mut();
+        let sender = connection
+            .sender
+            .clone()
+            .ok_or_else(|| format!("Not connected to the remote backend to call `{}`", command))?;
+        let id = connection.next_id;
+        connection.next_id += 1;
+        connection.pending.insert(id, slot.clone());
+        Ok((id, sender))
+    });
+
+    // Sent outside the borrow; the sender may call back into the connection
+    let result = sent.and_then(|(id, sender)| {
+        let request = Request {
+            id,
+            command: command.to_string(),
+            args,
+        };
+        let frame = serde_json::to_string(&request)
+            .map_err(|e| format!("Failed to serialize the call: {}", e));
+        frame.and_then(|frame| sender(frame)).inspect_err(|_| {
+            CONNECTION.with(|connection| connection.borrow_mut().pending.remove(&id));
+        })
+    });
+    if let Err(message) = result {
+        slot.borrow_mut().result = Some(Err(Value::String(message)));
+    }
+    RemoteCall { slot }
+}
+
+/// Resolve the call a [`Response`] frame answers. Responses to unknown
+/// calls, e.g. ones already failed by [`disconnect`], are ignored.
+pub fn receive(frame: &str) -> Result<(), String> {
+    let response: Response = serde_json::from_str(frame)
+        .map_err(|e| format!("Invalid response from the remote backend: {}", e))?;
+    let slot = CONNECTION.with(|connection| connection.borrow_mut().pending.remove(&response.id));
+    if let Some(slot) = slot {
+        resolve(&slot, response.result);
+    }
+    Ok(())
+}
+
+/// Read a [`Request`] frame on the backend.
+pub fn decode_request(frame: &str) -> Result<Request, String> {
+    serde_json::from_str(frame).map_err(|e| format!("Invalid call to the remote backend: {}", e))
+}
+
+/// The [`Response`] frame answering call `id` with `result`, e.g. of a
+/// `bridge_dispatch!` function.
+pub fn encode_response(id: u64, result: Result<Value, Value>) -> String {
+    // A `Value` always serializes
+    serde_json::to_string(&Response { id, result }).unwrap_or_default()
+}
+
+/// Future returned by [`invoke`].
+pub struct RemoteCall {
+    slot: Rc<RefCell<Slot>>,
+}
+
+impl Future for RemoteCall {
+    type Output = Result<Value, Value>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.borrow_mut();
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+fn resolve(slot: &RefCell<Slot>, result: Result<Value, Value>) {
+    // Wake outside the borrow; wakers may poll synchronously
+    let waker = {
+        let mut slot = slot.borrow_mut();
+        slot.result = Some(result);
+        slot.waker.take()
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
@@ -0,0 +1,185 @@
+//! Recording and replay of the calls of `replayable` commands.
+//!
+//! ```rust,ignore
+//! tauri_bridge_runtime::replay::start_recording();
+//! // ... reproduce the bug ...
+//! save_file("session.json", &tauri_bridge_runtime::replay::stop_recording().to_json());
+//!
+//! // Later, e.g. in a UI test, without a backend:
+//! tauri_bridge_runtime::replay::start_replay(Recording::from_json(&saved)?);
+//! ```
+//!
+//! While recording, generated clients add each call that got a response,
+//! with its arguments, response and timing, to the [`Recording`]. Its JSON
+//! can be kept wherever suits the app, a downloaded file or IndexedDB.
+//!
+//! While replaying, clients don't invoke the backend: each call is answered
+//! with the response of the first call of the command with the same
+//! arguments not replayed yet, or else of the first call of the command not
+//! replayed yet, so a recorded session plays back deterministically. A call
+//! with no recorded response left fails.
+
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A call that got a response while recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedCall {
+    /// The command's wire name.
+    pub command: String,
+    pub args: Value,
+    pub response: Value,
+    /// When the call was made, since the recording started.
+    pub at_ms: f64,
+    pub duration_ms: f64,
+}
+
+/// The calls of a recorded session, in the order they were made.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub calls: Vec<RecordedCall>,
+}
+
+impl Recording {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse recording: {}", e))
+    }
+}
+
+enum Mode {
+    Off,
+    Recording {
+        started_ms: f64,
+        recording: Recording,
+    },
+    // Calls not replayed yet
+    Replaying(Vec<Option<RecordedCall>>),
+}
+
+thread_local! {
+    static MODE: RefCell<Mode> = const { RefCell::new(Mode::Off) };
+}
+
+/// Record calls from now on, replacing a recording or replay in progress.
+pub fn start_recording() {
+    let started_ms = crate::clock::now_ms();
+    MODE.with(|mode| {
+        *mode.borrow_mut() = Mode::Recording {
+            started_ms,
+            recording: Recording::default(),
+        }
+    });
+}
+
+/// Stop recording, and return the calls recorded since
+/// [`start_recording`]; empty when not recording.
+pub fn stop_recording() -> Recording {
+    MODE.with(|mode| {
+        let mut mode = mode.borrow_mut();
+        match std::mem::replace(&mut *mode, Mode::Off) {
+            Mode::Recording { recording, .. } => recording,
+            other => {
+                *mode = other;
+                Recording::default()
+            }
+        }
+    })
+}
+
+/// Answer calls with the responses of `recording` from now on, replacing a
+/// recording or replay in progress.
+pub fn start_replay(recording: Recording) {
+    let calls = recording.calls.into_iter().map(Some).collect();
+    MODE.with(|mode| *mode.borrow_mut() = Mode::Replaying(calls));
+}
+
+/// Invoke the backend again.
+pub fn stop_replay() {
+    MODE.with(|mode| {
+        let mut mode = mode.borrow_mut();
+        if matches!(*mode, Mode::Replaying(_)) {
+            *mode = Mode::Off;
+        }
+    });
+}
+
+pub fn is_recording() -> bool {
+    MODE.with(|mode| matches!(*mode.borrow(), Mode::Recording { .. }))
+}
+
+pub fn is_replaying() -> bool {
+    MODE.with(|mode| matches!(*mode.borrow(), Mode::Replaying(_)))
+}
+
+fn parse(json: Option<String>) -> Value {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// The JSON of the recorded response to a call of `command`, when
+/// replaying.
+///
+/// `args` serializes the arguments to JSON, only while replaying.
+pub fn replayed(
+    command: &str,
+    args: impl FnOnce() -> Option<String>,
+) -> Option<Result<String, String>> {
+    MODE.with(|mode| {
+        let mut mode = mode.borrow_mut();
+        let Mode::Replaying(calls) = &mut *mode else {
+            return None;
+        };
+        let args = parse(args());
+        let of_command =
+            |call: &Option<RecordedCall>| call.as_ref().is_some_and(|call| call.command == command);
+        let index = calls
+            .iter()
+            .position(|call| {
+                of_command(call) && call.as_ref().is_some_and(|call| call.args == args)
+            })
+            .or_else(|| calls.iter().position(of_command));
+        Some(match index.and_then(|index| calls[index].take()) {
+            Some(call) => Ok(call.response.to_string()),
+            None => Err(format!("No recorded response left for `{}`", command)),
+        })
+    })
+}
+
+/// Record the call of `command` made at `started_ms`, a
+/// [`clock::now_ms`](crate::clock::now_ms) timestamp, when recording.
+///
+/// `json` serializes the arguments and the response to JSON, only while
+/// recording.
+pub fn record(
+    command: &str,
+    started_ms: f64,
+    json: impl FnOnce() -> (Option<String>, Option<String>),
+) {
+    let duration_ms = crate::clock::now_ms() - started_ms;
+    if !is_recording() {
+        return;
+    }
+    let (args, response) = json();
+    MODE.with(|mode| {
+        if let Mode::Recording {
+            started_ms: recording_started_ms,
+            recording,
+        } = &mut *mode.borrow_mut()
+        {
+            recording.calls.push(RecordedCall {
+                command: command.to_string(),
+                args: parse(args),
+                response: parse(response),
+                at_ms: started_ms - *recording_started_ms,
+                duration_ms,
+            });
+        }
+    });
+}
@@ -0,0 +1,161 @@
+//! Client-side scheduling of low-priority calls.
+//!
+//! Commands generated with `priority = "low"` (bulk prefetch, background
+//! sync) queue here and only go out while no interactive call is in
+//! flight, at most [`set_background_limit`] at a time. Commands with
+//! `priority = "normal"` never wait; they're only counted so low-priority
+//! calls can stay out of their way.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+/// Scheduling priority of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Queued behind interactive calls.
+    Low,
+    /// Interactive; never waits.
+    Normal,
+}
+
+struct State {
+    interactive: usize,
+    background: usize,
+    limit: usize,
+    next_ticket: u64,
+    queue: VecDeque<(u64, Option<Waker>)>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = const {
+        RefCell::new(State {
+            interactive: 0,
+            background: 0,
+            limit: 1,
+            next_ticket: 0,
+            queue: VecDeque::new(),
+        })
+    };
+}
+
+/// Low-priority calls that may be in flight at once (default 1).
+pub fn set_background_limit(limit: usize) {
+    STATE.with(|state| state.borrow_mut().limit = limit.max(1));
+    wake_queue();
+}
+
+/// Number of low-priority calls waiting for their turn.
+pub fn queued_calls() -> usize {
+    STATE.with(|state| state.borrow().queue.len())
+}
+
+/// Wait for the turn of a call; it's in flight until the permit drops.
+pub fn schedule(priority: Priority) -> Schedule {
+    Schedule {
+        priority,
+        ticket: None,
+    }
+}
+
+/// Future returned by [`schedule`].
+pub struct Schedule {
+    priority: Priority,
+    ticket: Option<u64>,
+}
+
+impl Future for Schedule {
+    type Output = Permit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let priority = self.priority;
+        let ready = STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            if priority == Priority::Normal {
+                state.interactive += 1;
+                return true;
+            }
+
+            let ticket = match self.ticket {
+                Some(ticket) => ticket,
+                None => {
+                    let ticket = state.next_ticket;
+                    state.next_ticket += 1;
+                    state.queue.push_back((ticket, None));
+                    self.ticket = Some(ticket);
+                    ticket
+                }
+            };
+
+            let first = state.queue.front().map(|(first, _)| *first) == Some(ticket);
+            if first && state.interactive == 0 && state.background < state.limit {
+                state.queue.pop_front();
+                state.background += 1;
+                return true;
+            }
+
+            if let Some((_, waker)) = state.queue.iter_mut().find(|(queued, _)| *queued == ticket) {
+                *waker = Some(cx.waker().clone());
+            }
+            false
+        });
+
+        if !ready {
+            return Poll::Pending;
+        }
+        self.ticket = None;
+        Poll::Ready(Permit { priority })
+    }
+}
+
+impl Drop for Schedule {
+    fn drop(&mut self) {
+        // A cancelled call gives up its place in the queue
+        if let Some(ticket) = self.ticket {
+            STATE.with(|state| {
+                state
+                    .borrow_mut()
+                    .queue
+                    .retain(|(queued, _)| *queued != ticket)
+            });
+            wake_queue();
+        }
+    }
+}
+
+/// A call in flight; lets the next low-priority call go when dropped.
+pub struct Permit {
+    priority: Priority,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            match self.priority {
+                Priority::Low => state.background -= 1,
+                Priority::Normal => state.interactive -= 1,
+            }
+        });
+        wake_queue();
+    }
+}
+
+/// Let the queued calls check whether it's their turn.
+fn wake_queue() {
+    let wakers: Vec<Waker> = STATE.with(|state| {
+        state
+            .borrow_mut()
+            .queue
+            .iter_mut()
+            .filter_map(|(_, waker)| waker.take())
+            .collect()
+    });
+
+    // Wake outside the borrow; wakers may poll synchronously
+    for waker in wakers {
+        waker.wake();
+    }
+}
@@ -0,0 +1,240 @@
+//! Startup round trips of commands generated with `selfcheck`.
+//!
+//! ```rust,ignore
+//! let report = run_bridge_selfcheck().await?;
+//! if !report.is_ok() {
+//!     web_sys::console::warn_1(&report.to_string().into());
+//! }
+//! ```
+//!
+//! The client invokes the `__bridge_selfcheck` command generated by
+//! `bridge_selfcheck!` twice. First the backend's [`Checker`] returns a
+//! [`Sample`] of each command's response: the JSON of its `Default`. The
+//! client decodes each response sample the way the command's client does,
+//! and sends back its JSON as an [`Echo`] together with the `Default`
+//! arguments, serialized the way the command sends them. Then the backend decodes the
+//! arguments the way Tauri does and compares both with what was sent,
+//! reporting every difference as a [`Mismatch`].
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Wire name of the command running the self-check.
+pub const SELFCHECK_COMMAND: &str = "__bridge_selfcheck";
+
+/// The response sample of a command, sent to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub command: String,
+    pub response: Value,
+}
+
+/// What the client made of a command's samples: the arguments it sends,
+/// and the JSON of the response sample it decoded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Echo {
+    pub command: String,
+    pub args: Value,
+    pub response: String,
+}
+
+/// The response of the `__bridge_selfcheck` command.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SelfCheck {
+    /// Response samples, when no echoes were sent.
+    pub samples: Vec<Sample>,
+    /// The comparison of the echoes, when they were sent.
+    pub report: Report,
+}
+
+/// A sample that didn't survive the round trip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mismatch {
+    pub command: String,
+    /// `argument \`name\``, `response` or `command`.
+    pub part: String,
+    pub problem: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}: {}", self.command, self.part, self.problem)
+    }
+}
+
+/// Outcome of a self-check.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// Commands whose samples were compared.
+    pub checked: Vec<String>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Report {
+    /// Whether every sample came back unchanged.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Record that `part` of `command` didn't survive.
+    pub fn mismatch(&mut self, command: &str, part: &str, problem: impl Into<String>) {
+        self.mismatches.push(Mismatch {
+            command: command.to_string(),
+            part: part.to_string(),
+            problem: problem.into(),
+        });
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(
+                f,
+                "self-check passed, commands checked: {}",
+                self.checked.len()
+            );
+        }
+        write!(f, "self-check failed:")?;
+        for mismatch in &self.mismatches {
+            write!(f, "\n- {}", mismatch)?;
+        }
+        Ok(())
+    }
+}
+
+/// A sent argument of a command: its camelCase name, the JSON of its
+/// `Default`, and how the backend decodes it.
+pub struct Arg {
+    name: &'static str,
+    expected: Result<Value, String>,
+    decode: fn(Value) -> Result<Value, String>,
+}
+
+impl Arg {
+    /// The argument `name` of type `T`.
+    pub fn of<T: Default + Serialize + DeserializeOwned>(name: &'static str) -> Self {
+        Arg {
+            name,
+            expected: sample::<T>(),
+            decode: reencode::<T>,
+        }
+    }
+}
+
+/// The JSON of a response sample the client decoded, for its [`Echo`].
+pub fn encode<T: Serialize>(response: &T) -> Result<String, String> {
+    serde_json::to_string(response).map_err(|e| format!("Failed to serialize response: {}", e))
+}
+
+fn sample<T: Default + Serialize>() -> Result<Value, String> {
+    serde_json::to_value(T::default()).map_err(|e| format!("the sample can't be serialized: {}", e))
+}
+
+/// Decode `value` as a `T` and encode it again, to compare the decoded value.
+fn reencode<T: Serialize + DeserializeOwned>(value: Value) -> Result<Value, String> {
+    let decoded: T =
+        serde_json::from_value(value).map_err(|e| format!("failed to decode: {}", e))?;
+    serde_json::to_value(decoded).map_err(|e| format!("failed to encode: {}", e))
+}
+
+/// The backend half of a self-check: samples responses, or compares the
+/// echoes of the client.
+pub struct Checker {
+    echoes: Option<Vec<Echo>>,
+    check: SelfCheck,
+}
+
+impl Checker {
+    /// Sample responses when `echoes` is `None`, and otherwise compare them.
+    pub fn new(echoes: Option<Vec<Echo>>) -> Self {
+        Checker {
+            echoes,
+            check: SelfCheck::default(),
+        }
+    }
+
+    /// Sample or check `command`, responding with an `R` and taking `args`.
+    ///
+    /// Commands the client didn't echo are skipped; it reports those.
+    pub fn command<R: Default + Serialize>(&mut self, command: &str, args: &[Arg]) {
+        let response = match sample::<R>() {
+            Ok(response) => response,
+            Err(error) => return self.check.report.mismatch(command, "response", error),
+        };
+        let Some(echoes) = &self.echoes else {
+            self.check.samples.push(Sample {
+                command: command.to_string(),
+                response,
+            });
+            return;
+        };
+        let Some(echo) = echoes.iter().find(|echo| echo.command == command) else {
+            return;
+        };
+
+        let report = &mut self.check.report;
+        for arg in args {
+            let part = format!("argument `{}`", arg.name);
+            let expected = match &arg.expected {
+                Ok(expected) => expected,
+                Err(error) => {
+                    report.mismatch(command, &part, error.clone());
+                    continue;
+                }
+            };
+            let sent = echo.args.get(arg.name).cloned().unwrap_or(Value::Null);
+            compare(report, command, &part, expected, (arg.decode)(sent));
+        }
+        let decoded = serde_json::from_str(&echo.response).map_err(|e| e.to_string());
+        compare(report, command, "response", &response, decoded);
+        report.checked.push(command.to_string());
+    }
+
+    /// The response of the `__bridge_selfcheck` command.
+    pub fn finish(self) -> SelfCheck {
+        self.check
+    }
+}
+
+fn compare(
+    report: &mut Report,
+    command: &str,
+    part: &str,
+    expected: &Value,
+    received: Result<Value, String>,
+) {
+    match received {
+        Ok(received) if same(expected, &received) => {}
+        Ok(received) => report.mismatch(
+            command,
+            part,
+            format!("sent `{}`, received `{}`", expected, received),
+        ),
+        Err(error) => report.mismatch(command, part, error),
+    }
+}
+
+/// JSON equality where numbers compare by value: JavaScript sends `0.0`
+/// as `0`.
+fn same(expected: &Value, received: &Value) -> bool {
+    match (expected, received) {
+        (Value::Number(expected), Value::Number(received)) => {
+            expected == received || expected.as_f64() == received.as_f64()
+        }
+        (Value::Array(expected), Value::Array(received)) => {
+            expected.len() == received.len()
+                && expected.iter().zip(received).all(|(e, r)| same(e, r))
+        }
+        (Value::Object(expected), Value::Object(received)) => {
+            expected.len() == received.len()
+                && expected
+                    .iter()
+                    .all(|(key, e)| received.get(key).is_some_and(|r| same(e, r)))
+        }
+        _ => expected == received,
+    }
+}
@@ -0,0 +1,147 @@
+//! Sequential execution of the calls of `serialize_on` commands.
+//!
+//! Calls sharing the value of the named argument take turns: each one waits
+//! for the calls with the same value that arrived before it, so concurrent
+//! writes to one resource run one after the other while other resources
+//! aren't held up. Queues are process-wide and per argument name, so every
+//! command serialized on `project_id` shares the queues of the project ids.
+//! Calls wait without blocking, which is why `serialize_on` commands are
+//! async.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Default)]
+struct Queue {
+    next_ticket: u64,
+    serving: u64,
+    // Tickets of calls that stopped waiting, e.g. cancelled futures
+    abandoned: BTreeSet<u64>,
+    wakers: HashMap<u64, Waker>,
+}
+
+static QUEUES: Mutex<BTreeMap<u64, Queue>> = Mutex::new(BTreeMap::new());
+
+// A panicking command mustn't stall the others
+fn queues() -> MutexGuard<'static, BTreeMap<u64, Queue>> {
+    QUEUES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn queue_key<T: Hash + ?Sized>(param: &str, value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    param.hash(&mut hasher);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn take_ticket(key: u64) -> u64 {
+    let mut queues = queues();
+    let queue = queues.entry(key).or_default();
+    let ticket = queue.next_ticket;
+    queue.next_ticket += 1;
+    ticket
+}
+
+/// Let the call after the one served go, skipping abandoned tickets.
+fn advance(key: u64) {
+    let waker = {
+        let mut queues = queues();
+        let Some(queue) = queues.get_mut(&key) else {
+            return;
+        };
+        queue.serving += 1;
+        while queue.abandoned.remove(&queue.serving) {
+            queue.serving += 1;
+        }
+        let waker = queue.wakers.remove(&queue.serving);
+        if queue.serving == queue.next_ticket {
+            queues.remove(&key);
+        }
+        waker
+    };
+    // Wake outside the lock; wakers may poll synchronously
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// Wait for the turn of a call whose `param` argument is `value`.
+pub fn turn<T: Hash + ?Sized>(param: &str, value: &T) -> WaitForTurn {
+    let key = queue_key(param, value);
+    WaitForTurn {
+        key,
+        ticket: take_ticket(key),
+        served: false,
+    }
+}
+
+/// Number of calls whose `param` argument is `value` running or waiting.
+pub fn queued_calls<T: Hash + ?Sized>(param: &str, value: &T) -> usize {
+    queues().get(&queue_key(param, value)).map_or(0, |queue| {
+        (queue.next_ticket - queue.serving) as usize - queue.abandoned.len()
+    })
+}
+
+/// Future returned by [`turn`].
+pub struct WaitForTurn {
+    key: u64,
+    ticket: u64,
+    served: bool,
+}
+
+impl Future for WaitForTurn {
+    type Output = Turn;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut queues = queues();
+        let Some(queue) = queues.get_mut(&self.key) else {
+            unreachable!("queues are kept while calls wait");
+        };
+        if queue.serving == self.ticket {
+            drop(queues);
+            self.served = true;
+            return Poll::Ready(Turn { key: self.key });
+        }
+        queue.wakers.insert(self.ticket, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitForTurn {
+    fn drop(&mut self) {
+        if self.served {
+            return;
+        }
+        let serving = {
+            let mut queues = queues();
+            let Some(queue) = queues.get_mut(&self.key) else {
+                return;
+            };
+            queue.wakers.remove(&self.ticket);
+            if queue.serving != self.ticket {
+                queue.abandoned.insert(self.ticket);
+            }
+            queue.serving == self.ticket
+        };
+        // Dropped on its turn: pass it on
+        if serving {
+            advance(self.key);
+        }
+    }
+}
+
+/// A call's turn; the next call with the same value goes once it's dropped.
+#[must_use = "the turn ends when dropped"]
+pub struct Turn {
+    key: u64,
+}
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        advance(self.key);
+    }
+}
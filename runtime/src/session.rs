@@ -0,0 +1,96 @@
+//! Sessions of commands taking a `#[session]` parameter.
+//!
+//! ```rust,ignore
+//! // On the client, once at startup
+//! tauri_bridge_runtime::session::set_token_provider(|_command| auth::current_token());
+//!
+//! // On the backend
+//! tauri::Builder::default().manage(SessionValidator::new(|_command, token| {
+//!     sessions::lookup(token).ok_or_else(|| "session expired".to_string())
+//! }))
+//! ```
+//!
+//! The client sends the provider's token with every call as a hidden
+//! argument. The backend command looks up the managed
+//! [`SessionValidator`] of its parameter's type, and rejects the call with
+//! the validator's error, or when the token or validator is missing, before
+//! running the function.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+type TokenProvider = Rc<dyn Fn(&str) -> Option<String>>;
+type Validate<S> = Box<dyn Fn(&str, &str) -> Result<S, String> + Send + Sync>;
+
+thread_local! {
+    static TOKEN_PROVIDER: RefCell<Option<TokenProvider>> = const { RefCell::new(None) };
+}
+
+/// Session token sent with a call; `None` when signed out.
+///
+/// Its `Debug` output leaves out the token, so `debug_log` doesn't print it.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Token(Option<String>);
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Token(..)"),
+            None => f.write_str("Token(None)"),
+        }
+    }
+}
+
+/// Set the hook returning the session token sent with a command. Like the
+/// rest of the client, the provider is per thread.
+pub fn set_token_provider(provider: impl Fn(&str) -> Option<String> + 'static) {
+    TOKEN_PROVIDER.with(|slot| *slot.borrow_mut() = Some(Rc::new(provider)));
+}
+
+/// Remove the token provider.
+pub fn clear_token_provider() {
+    TOKEN_PROVIDER.with(|slot| *slot.borrow_mut() = None);
+}
+
+/// The token to send with `command`, `None` while no provider is set.
+pub fn token(command: &str) -> Token {
+    // Cloned out of the borrow; the provider may set a provider itself
+    let provider = TOKEN_PROVIDER.with(|slot| slot.borrow().clone());
+    Token(provider.and_then(|provider| provider(command)))
+}
+
+/// Turns the token of a call into the session of type `S`, installed as
+/// Tauri managed state.
+pub struct SessionValidator<S> {
+    validate: Validate<S>,
+}
+
+impl<S> SessionValidator<S> {
+    /// Validate with `validate`, which gets the command name and the token.
+    pub fn new(validate: impl Fn(&str, &str) -> Result<S, String> + Send + Sync + 'static) -> Self {
+        Self {
+            validate: Box::new(validate),
+        }
+    }
+}
+
+/// The session of a call to `command`.
+pub fn validate<S>(
+    validator: Option<&SessionValidator<S>>,
+    command: &str,
+    token: Option<String>,
+) -> Result<S, String> {
+    let validator = validator.ok_or_else(|| {
+        format!(
+            "`{}` takes a session, but no `SessionValidator<{}>` is managed",
+            command,
+            std::any::type_name::<S>()
+        )
+    })?;
+    let token = token.ok_or_else(|| format!("`{}` needs a session token", command))?;
+    (validator.validate)(command, &token)
+}
@@ -0,0 +1,265 @@
+//! Commands generated with `sidecar` run in a separate process.
+//!
+//! ```rust,ignore
+//! // In the app, once at startup
+//! tauri::Builder::default().manage(SidecarHost::new().with("pdf", || {
+//!     let exe = std::env::current_exe().unwrap();
+//!     std::process::Command::new(exe.with_file_name("pdf-renderer"))
+//! }))
+//!
+//! // The sidecar binary's `main`
+//! let dispatch = tauri_bridge::bridge_dispatch![api::render_page];
+//! tauri_bridge_runtime::sidecar::serve(dispatch).await?;
+//! ```
+//!
+//! The app's command sends its arguments to the sidecar's stdin as a
+//! [`remote::Request`](crate::remote::Request) line, and relays the
+//! [`remote::Response`](crate::remote::Response) line the sidecar writes to
+//! stdout. Sidecars are started on their first call and restarted on the
+//! next call after they exit; calls still running when a sidecar crashes
+//! are rejected instead of taking the app down with it.
+//!
+//! Stdout carries the frames, so the sidecar should log to stderr. Lines
+//! that aren't frames are skipped.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Write};
+use std::pin::Pin;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use serde_json::Value;
+
+use crate::remote::{Request, Response, decode_request, encode_response};
+
+type Spawn = Box<dyn Fn() -> Command + Send + Sync>;
+
+/// The sidecars commands run in, installed as Tauri managed state.
+#[derive(Default)]
+pub struct SidecarHost {
+    spawners: HashMap<String, Spawn>,
+    processes: Mutex<HashMap<String, Arc<Process>>>,
+}
+
+impl SidecarHost {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run the commands of sidecar `name` in processes started from the
+    /// command `spawn` returns. Stdin and stdout are piped over.
+    pub fn with(mut self, name: &str, spawn: impl Fn() -> Command + Send + Sync + 'static) -> Self {
+        self.spawners.insert(name.to_string(), Box::new(spawn));
+        self
+    }
+
+    /// Call `command` in sidecar `name`, resolving to the response or the
+    /// error the invoke is rejected with.
+    pub fn call(&self, name: &str, command: &str, args: Value) -> SidecarCall {
+        let slot = Arc::new(Mutex::new(Slot::default()));
+        if let Err(message) = self.send(name, command, args, &slot) {
+            lock(&slot).result = Some(Err(Value::String(message)));
+        }
+        SidecarCall { slot }
+    }
+
+    fn send(
+        &self,
+        name: &str,
+        command: &str,
+        args: Value,
+        slot: &Arc<Mutex<Slot>>,
+    ) -> Result<(), String> {
+        let process = self.process(name)?;
+        let id = process.next_id.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut calls = lock(&process.calls);
+            if !calls.running {
+                return Err(format!("The `{}` sidecar exited", name));
+            }
+            calls.pending.insert(id, slot.clone());
+        }
+
+        let request = Request {
+            id,
+            command: command.to_string(),
+            args,
+        };
+        let frame = serde_json::to_string(&request)
+            .map_err(|e| format!("Failed to serialize the call: {}", e))?;
+        let mut stdin = lock(&process.stdin);
+        writeln!(stdin, "{}", frame)
+            .and_then(|_| stdin.flush())
+            .map_err(|e| {
+                lock(&process.calls).pending.remove(&id);
+                format!("Failed to call the `{}` sidecar: {}", name, e)
+            })
+    }
+
+    /// The running process of sidecar `name`, started if it isn't.
+    fn process(&self, name: &str) -> Result<Arc<Process>, String> {
+        let mut processes = lock(&self.processes);
+        if let Some(process) = processes.get(name)
+            && lock(&process.calls).running
+        {
+            return Ok(process.clone());
+        }
+
+        let spawn = self
+            .spawners
+            .get(name)
+            .ok_or_else(|| format!("No sidecar named `{}` is registered", name))?;
+        let process = Process::spawn(name, spawn())
+            .map_err(|e| format!("Failed to start the `{}` sidecar: {}", name, e))?;
+        processes.insert(name.to_string(), process.clone());
+        Ok(process)
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    result: Option<Result<Value, Value>>,
+    waker: Option<Waker>,
+}
+
+struct Calls {
+    running: bool,
+    pending: HashMap<u64, Arc<Mutex<Slot>>>,
+}
+
+struct Process {
+    child: Mutex<Child>,
+    stdin: Mutex<ChildStdin>,
+    next_id: AtomicU64,
+    calls: Arc<Mutex<Calls>>,
+}
+
+impl Process {
+    fn spawn(name: &str, mut command: Command) -> io::Result<Arc<Process>> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(io::Error::other("stdin and stdout must be piped"));
+        };
+
+        let calls = Arc::new(Mutex::new(Calls {
+            running: true,
+            pending: HashMap::new(),
+        }));
+        let reader_calls = calls.clone();
+        let name = name.to_string();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                let Ok(response) = serde_json::from_str::<Response>(&line) else {
+                    continue;
+                };
+                let slot = lock(&reader_calls).pending.remove(&response.id);
+                if let Some(slot) = slot {
+                    resolve(&slot, response.result);
+                }
+            }
+            // Fail the calls left once the sidecar is gone
+            let pending: Vec<_> = {
+                let mut calls = lock(&reader_calls);
+                calls.running = false;
+                calls.pending.drain().map(|(_, slot)| slot).collect()
+            };
+            for slot in pending {
+                resolve(
+                    &slot,
+                    Err(Value::String(format!("The `{}` sidecar exited", name))),
+                );
+            }
+        });
+
+        Ok(Arc::new(Process {
+            child: Mutex::new(child),
+            stdin: Mutex::new(stdin),
+            next_id: AtomicU64::new(0),
+            calls,
+        }))
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let mut child = lock(&self.child);
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// Future returned by [`SidecarHost::call`].
+pub struct SidecarCall {
+    slot: Arc<Mutex<Slot>>,
+}
+
+impl Future for SidecarCall {
+    type Output = Result<Value, Value>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = lock(&self.slot);
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Answer the calls the app writes to stdin, one at a time, until it
+/// closes it. Takes the function of `bridge_dispatch!`.
+pub async fn serve(dispatch: impl AsyncFn(&str, Value) -> Result<Value, Value>) -> io::Result<()> {
+    serve_io(io::stdin().lock(), io::stdout().lock(), dispatch).await
+}
+
+/// Like [`serve`], reading calls from `input` and writing responses to
+/// `output`.
+pub async fn serve_io(
+    input: impl BufRead,
+    mut output: impl Write,
+    dispatch: impl AsyncFn(&str, Value) -> Result<Value, Value>,
+) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match decode_request(&line) {
+            Ok(request) => request,
+            Err(message) => {
+                eprintln!("[tauri-bridge] {}", message);
+                continue;
+            }
+        };
+        let result = dispatch(&request.command, request.args).await;
+        writeln!(output, "{}", encode_response(request.id, result))?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+fn resolve(slot: &Mutex<Slot>, result: Result<Value, Value>) {
+    // Wake outside the lock; wakers may poll synchronously
+    let waker = {
+        let mut slot = lock(slot);
+        slot.result = Some(result);
+        slot.waker.take()
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
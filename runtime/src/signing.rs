@@ -0,0 +1,79 @@
+//! Signing of payloads, for commands generated with `signed`.
+//!
+//! Under Tauri's isolation pattern, the isolation iframe can check every
+//! payload before it reaches the backend. Commands generated with `signed`
+//! pass their arguments through the [`set_signer`] hook before invoking,
+//! and their responses through the [`set_verifier`] hook before decoding
+//! them, so an app can sign or encrypt what it sends and check what it
+//! gets back:
+//!
+//! ```rust,ignore
+//! tauri_bridge_runtime::signing::set_signer(|command, args| {
+//!     let signature = sign(command, &args.to_string());
+//!     Ok(serde_json::json!({ "args": args, "signature": signature }))
+//! });
+//! ```
+//!
+//! Hooks get the command name and the JSON value, and return the value to
+//! send or decode; an `Err` fails the call. A `signed` command fails while
+//! its hook is missing, rather than sending unsigned payloads. Rejections
+//! aren't verified. Like the rest of the client, the hooks are per thread.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde_json::Value;
+
+type Hook = Rc<dyn Fn(&str, Value) -> Result<Value, String>>;
+
+#[derive(Default)]
+struct Hooks {
+    signer: Option<Hook>,
+    verifier: Option<Hook>,
+}
+
+thread_local! {
+    static HOOKS: RefCell<Hooks> = RefCell::new(Hooks::default());
+}
+
+/// Set the hook turning the arguments of a `signed` command into the
+/// payload sent.
+pub fn set_signer(signer: impl Fn(&str, Value) -> Result<Value, String> + 'static) {
+    HOOKS.with(|hooks| hooks.borrow_mut().signer = Some(Rc::new(signer)));
+}
+
+/// Set the hook checking the response of a `signed` command, returning
+/// the value to decode.
+pub fn set_verifier(verifier: impl Fn(&str, Value) -> Result<Value, String> + 'static) {
+    HOOKS.with(|hooks| hooks.borrow_mut().verifier = Some(Rc::new(verifier)));
+}
+
+/// Remove both hooks.
+pub fn clear_hooks() {
+    HOOKS.with(|hooks| *hooks.borrow_mut() = Hooks::default());
+}
+
+/// Pass the arguments of `command` through the signer.
+pub fn sign(command: &str, args: Value) -> Result<Value, String> {
+    // Cloned out of the borrow; the hook may set hooks itself
+    let signer = HOOKS.with(|hooks| hooks.borrow().signer.clone());
+    match signer {
+        Some(signer) => signer(command, args),
+        None => Err(format!(
+            "`{}` is signed, but no signer is set; call `signing::set_signer` first",
+            command
+        )),
+    }
+}
+
+/// Pass the response of `command` through the verifier.
+pub fn verify(command: &str, response: Value) -> Result<Value, String> {
+    let verifier = HOOKS.with(|hooks| hooks.borrow().verifier.clone());
+    match verifier {
+        Some(verifier) => verifier(command, response),
+        None => Err(format!(
+            "`{}` is signed, but no verifier is set; call `signing::set_verifier` first",
+            command
+        )),
+    }
+}
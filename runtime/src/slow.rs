@@ -0,0 +1,120 @@
+//! Reports of slow calls made by commands with `log_slow`.
+//!
+//! Generated clients time each invoke and, when it took longer than the
+//! threshold (200 ms unless set with [`set_threshold_ms`]), report a
+//! [`SlowCall`] with the command, the duration and the JSON size of the
+//! arguments and response. By default the report is logged as one
+//! `key=value` line, to `console.warn` in the browser and to stderr
+//! natively; [`on_slow_call`] hands it to the app instead, e.g. to forward
+//! it to a crash reporter.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+/// Threshold of the reports, unless set otherwise.
+pub const DEFAULT_THRESHOLD_MS: f64 = 200.0;
+
+/// A call that took longer than the threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowCall {
+    /// The command's wire name.
+    pub command: String,
+    pub duration_ms: f64,
+    /// Length of the arguments' JSON, when it could be measured.
+    pub args_bytes: Option<usize>,
+    /// Length of the response's JSON, when it could be measured.
+    pub response_bytes: Option<usize>,
+}
+
+impl fmt::Display for SlowCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "slow command command={} duration_ms={:.1}",
+            self.command, self.duration_ms
+        )?;
+        if let Some(bytes) = self.args_bytes {
+            write!(f, " args_bytes={}", bytes)?;
+        }
+        if let Some(bytes) = self.response_bytes {
+            write!(f, " response_bytes={}", bytes)?;
+        }
+        Ok(())
+    }
+}
+
+type Handler = Rc<dyn Fn(&SlowCall)>;
+
+thread_local! {
+    static THRESHOLD_MS: Cell<f64> = const { Cell::new(DEFAULT_THRESHOLD_MS) };
+    static HANDLER: RefCell<Option<Handler>> = const { RefCell::new(None) };
+}
+
+/// Report calls taking longer than `threshold_ms` from now on.
+pub fn set_threshold_ms(threshold_ms: f64) {
+    THRESHOLD_MS.with(|threshold| threshold.set(threshold_ms));
+}
+
+/// The current threshold of the reports.
+pub fn threshold_ms() -> f64 {
+    THRESHOLD_MS.with(Cell::get)
+}
+
+/// Hand reports to `handler` instead of logging them, replacing the
+/// previous handler.
+pub fn on_slow_call(handler: impl Fn(&SlowCall) + 'static) {
+    HANDLER.with(|current| *current.borrow_mut() = Some(Rc::new(handler)));
+}
+
+/// Log reports again.
+pub fn clear_handler() {
+    HANDLER.with(|current| current.borrow_mut().take());
+}
+
+/// Report the call of `command` invoked at `started_ms`, a
+/// [`clock::now_ms`](crate::clock::now_ms) timestamp, if it was slow.
+///
+/// `sizes` measures the arguments and the response, only for slow calls.
+pub fn check(
+    command: &str,
+    started_ms: f64,
+    sizes: impl FnOnce() -> (Option<usize>, Option<usize>),
+) {
+    let duration_ms = crate::clock::now_ms() - started_ms;
+    if duration_ms <= threshold_ms() {
+        return;
+    }
+    let (args_bytes, response_bytes) = sizes();
+    let call = SlowCall {
+        command: command.to_string(),
+        duration_ms,
+        args_bytes,
+        response_bytes,
+    };
+    // The handler may set another one
+    match HANDLER.with(|handler| handler.borrow().clone()) {
+        Some(handler) => handler(&call),
+        None => write(&format!("[tauri-bridge] {}", call)),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(message: &str) {
+    use js_sys::{Function, JsString, Reflect};
+
+    let Ok(console) = Reflect::get(&js_sys::global(), &JsString::from("console")) else {
+        return;
+    };
+    if let Ok(warn) = Reflect::get(&console, &JsString::from("warn")) {
+        let _ = Function::from(warn).call1(&console, &JsString::from(message));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(message: &str) {
+    eprintln!("{}", message);
+}
@@ -0,0 +1,59 @@
+//! Values of commands generated with `sync_cache = "startup"`.
+//!
+//! ```rust,ignore
+//! init_get_app_version().await?;
+//! let version = get_app_version();
+//! ```
+//!
+//! The `init_<name>` client function invokes the command once and
+//! [`set`]s the response; the synchronous `<name>` reads it back with
+//! [`get`]. Like the query cache, the values are per thread.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    static VALUES: RefCell<HashMap<String, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Keep `value` as the response of `command`, replacing an earlier one.
+pub fn set<T: 'static>(command: &str, value: T) {
+    VALUES.with(|values| {
+        values
+            .borrow_mut()
+            .insert(command.to_string(), Box::new(value));
+    });
+}
+
+/// The response kept for `command`, if it was fetched as a `T`.
+pub fn get<T: Clone + 'static>(command: &str) -> Option<T> {
+    VALUES.with(|values| {
+        values
+            .borrow()
+            .get(command)
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    })
+}
+
+/// Whether `command`'s response was fetched.
+pub fn is_set(command: &str) -> bool {
+    VALUES.with(|values| values.borrow().contains_key(command))
+}
+
+/// The response kept for `command`, panicking when its `init_<name>`
+/// function wasn't awaited first.
+pub fn expect<T: Clone + 'static>(command: &str, init_fn: &str) -> T {
+    get(command).unwrap_or_else(|| {
+        panic!(
+            "`{}` was read before its value was fetched; await `{}()` at startup",
+            command, init_fn
+        )
+    })
+}
+
+/// Forget every response, e.g. to fetch them again after reconnecting.
+pub fn clear() {
+    VALUES.with(|values| values.borrow_mut().clear());
+}
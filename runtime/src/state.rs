@@ -0,0 +1,18 @@
+//! Managed state locked for the body of a command.
+//!
+//! ```rust,ignore
+//! #[tauri_bridge]
+//! pub fn add_todo(todos: BridgeState<TodoList>, title: String) -> usize {
+//!     todos.items.push(title);
+//!     todos.items.len()
+//! }
+//!
+//! tauri::Builder::default().manage(BridgeState::new(TodoList::default()))
+//! ```
+//!
+//! A `BridgeState<T>` parameter is filled on the backend from the managed
+//! `BridgeState<T>`, locked until the body returns, so the body gets
+//! `&mut T`. It's left out of the client signature.
+
+/// The mutex the app manages for `BridgeState<T>` parameters.
+pub type BridgeState<T> = std::sync::Mutex<T>;
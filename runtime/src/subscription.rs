@@ -0,0 +1,94 @@
+//! Listener lifetimes on the client.
+//!
+//! A [`Subscription`] runs its cleanup, e.g. removing a listener, when
+//! dropped, so a listener can't outlive its owner by accident. A [`Scope`]
+//! ties subscriptions and callbacks to a component: keep it in the
+//! component's state and drop it on unmount (e.g. from Leptos'
+//! `on_cleanup` or a Yew component's `destroy`), and everything bound to it
+//! stops.
+//!
+//! ```rust,ignore
+//! let scope = Scope::new();
+//! scope.add(query::subscribe(LIST_MEMBERS_QUERY_KEY, refetch));
+//! try_search_incremental(query, scope.bind(move |batch| rows.extend(batch))).await;
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// Handle of a listener; removes it when dropped.
+#[must_use = "dropping a subscription removes its listener right away"]
+pub struct Subscription {
+    unlisten: Option<Box<dyn FnOnce()>>,
+}
+
+impl Subscription {
+    /// A subscription running `unlisten` when dropped.
+    pub fn new(unlisten: impl FnOnce() + 'static) -> Self {
+        Subscription {
+            unlisten: Some(Box::new(unlisten)),
+        }
+    }
+
+    /// Keep the listener for the rest of the program.
+    pub fn forget(mut self) {
+        self.unlisten = None;
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(unlisten) = self.unlisten.take() {
+            unlisten();
+        }
+    }
+}
+
+/// Subscriptions and callbacks living as long as a component.
+///
+/// Dropping the scope drops its subscriptions and turns the callbacks
+/// from [`Scope::bind`] into no-ops.
+pub struct Scope {
+    alive: Rc<Cell<bool>>,
+    subscriptions: RefCell<Vec<Subscription>>,
+}
+
+impl Scope {
+    pub fn new() -> Self {
+        Scope {
+            alive: Rc::new(Cell::new(true)),
+            subscriptions: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Keep `subscription` until the scope is dropped.
+    pub fn add(&self, subscription: Subscription) {
+        self.subscriptions.borrow_mut().push(subscription);
+    }
+
+    /// `callback`, called only while the scope is alive.
+    pub fn bind<T>(&self, mut callback: impl FnMut(T) + 'static) -> impl FnMut(T) + 'static {
+        let alive = self.alive.clone();
+        move |value| {
+            if alive.get() {
+                callback(value);
+            }
+        }
+    }
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        self.alive.set(false);
+        // Unlisten in subscription order
+        for subscription in self.subscriptions.get_mut().drain(..) {
+            drop(subscription);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Grouped calls of commands generated with `transaction`.
+//!
+//! ```rust,ignore
+//! let mut transaction = Transaction::new();
+//! let note = transaction.add(api::create_note_step("Groceries".to_string()));
+//! let tag = transaction.add(api::tag_note_step(7, "home".to_string()));
+//!
+//! let mut committed = commit_bridge_transaction(transaction).await?;
+//! let note: NoteId = committed.take(note)?;
+//! ```
+//!
+//! The client records [`Call`]s in a [`Transaction`] and sends them as the
+//! [`Step`]s of a single invoke of the `__bridge_transaction` command
+//! generated by `bridge_transaction!`. The backend runs them in order with
+//! an [`UndoLog`]: once a step fails, the undo hooks of the steps that ran
+//! are awaited in reverse and the transaction is [`Outcome::RolledBack`].
+//! The failing step's own hook doesn't run, as it didn't complete.
+
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Wire name of the command running transactions.
+pub const TRANSACTION_COMMAND: &str = "__bridge_transaction";
+
+/// A call of a transaction: the command's wire name and JSON arguments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    pub command: String,
+    pub args: Value,
+}
+
+/// The response of the `__bridge_transaction` command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    /// Every step ran; their responses, in order.
+    Committed(Vec<Value>),
+    /// Step `step` failed with `error` and the steps before it were undone.
+    RolledBack {
+        step: usize,
+        command: String,
+        error: Value,
+    },
+}
+
+impl Outcome {
+    /// The responses of a committed transaction.
+    pub fn into_committed(self) -> Result<Committed, TransactionError> {
+        match self {
+            Outcome::Committed(responses) => Ok(Committed {
+                responses: responses.into_iter().map(Some).collect(),
+            }),
+            Outcome::RolledBack {
+                step,
+                command,
+                error,
+            } => Err(TransactionError::RolledBack {
+                step,
+                command,
+                error,
+            }),
+        }
+    }
+}
+
+/// Undoes a step that ran; started only on a rollback.
+pub type Undo = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A step that ran: its JSON response and the undo hook of its command.
+pub type Executed = Result<(Value, Option<Undo>), Value>;
+
+/// The steps a backend transaction ran so far.
+#[derive(Default)]
+pub struct UndoLog {
+    responses: Vec<Value>,
+    undos: Vec<Option<Undo>>,
+}
+
+impl UndoLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the step running `command`, which wasn't found when
+    /// `executed` is `None`. On failure the steps recorded before are
+    /// undone, and the rolled back outcome is returned.
+    pub async fn record(&mut self, command: String, executed: Option<Executed>) -> Option<Outcome> {
+        let executed = executed.unwrap_or_else(|| {
+            Err(Value::String(format!(
+                "command {} can't run in a transaction",
+                command
+            )))
+        });
+        match executed {
+            Ok((response, undo)) => {
+                self.responses.push(response);
+                self.undos.push(undo);
+                None
+            }
+            Err(error) => {
+                let step = self.responses.len();
+                for undo in self.undos.drain(..).rev().flatten() {
+                    undo.await;
+                }
+                Some(Outcome::RolledBack {
+                    step,
+                    command,
+                    error,
+                })
+            }
+        }
+    }
+
+    /// The outcome once every step ran.
+    pub fn commit(self) -> Outcome {
+        Outcome::Committed(self.responses)
+    }
+}
+
+/// A call of a command, returned by its `<name>_step` client function.
+pub struct Call<T> {
+    step: Result<Step, String>,
+    response: PhantomData<fn() -> T>,
+}
+
+impl<T> Call<T> {
+    /// The call of `command` with `args`, serialized right away.
+    pub fn new(command: &str, args: &impl Serialize) -> Self {
+        let step = serde_json::to_value(args)
+            .map(|args| Step {
+                command: command.to_string(),
+                args,
+            })
+            .map_err(|e| format!("Failed to serialize arguments: {}", e));
+        Call {
+            step,
+            response: PhantomData,
+        }
+    }
+}
+
+/// Calls recorded on the client, committed all at once.
+#[derive(Default)]
+pub struct Transaction {
+    steps: Vec<Result<Step, String>>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `call` as the next step, returning the handle of its response.
+    pub fn add<T>(&mut self, call: Call<T>) -> Pending<T> {
+        self.steps.push(call.step);
+        Pending {
+            index: self.steps.len() - 1,
+            response: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The JSON arguments of the `__bridge_transaction` command.
+    pub fn into_args(self) -> Result<String, TransactionError> {
+        #[derive(Serialize)]
+        struct TransactionArgs {
+            steps: Vec<Step>,
+        }
+
+        let steps = self
+            .steps
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(TransactionError::Failed)?;
+        serde_json::to_string(&TransactionArgs { steps })
+            .map_err(|e| TransactionError::Failed(format!("Failed to serialize arguments: {}", e)))
+    }
+}
+
+/// The response of a step, taken from the [`Committed`] transaction.
+pub struct Pending<T> {
+    index: usize,
+    response: PhantomData<fn() -> T>,
+}
+
+impl<T> Pending<T> {
+    /// Position of the step in the transaction.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// The responses of a committed transaction.
+#[derive(Debug)]
+pub struct Committed {
+    responses: Vec<Option<Value>>,
+}
+
+impl Committed {
+    /// The response of the step `pending` was returned for.
+    pub fn take<T: DeserializeOwned>(
+        &mut self,
+        pending: Pending<T>,
+    ) -> Result<T, TransactionError> {
+        let response = self
+            .responses
+            .get_mut(pending.index)
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                TransactionError::Failed(format!("No response for step {}", pending.index))
+            })?;
+        serde_json::from_value(response)
+            .map_err(|e| TransactionError::Failed(format!("Failed to deserialize response: {}", e)))
+    }
+}
+
+/// Error of committing a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionError {
+    /// Step `step` failed with `error`; the steps before it were undone.
+    RolledBack {
+        step: usize,
+        command: String,
+        error: Value,
+    },
+    /// The transaction itself failed.
+    Failed(String),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::RolledBack {
+                step,
+                command,
+                error,
+            } => {
+                let error = match error {
+                    Value::String(message) => message.clone(),
+                    error => error.to_string(),
+                };
+                write!(
+                    f,
+                    "transaction rolled back at step {} (`{}`): {}",
+                    step, command, error
+                )
+            }
+            TransactionError::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+impl From<String> for TransactionError {
+    fn from(error: String) -> Self {
+        TransactionError::Failed(error)
+    }
+}
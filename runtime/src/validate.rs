@@ -0,0 +1,60 @@
+//! Response validation of commands generated with `validate_response`.
+//!
+//! The client hands the deserialized response (the `Ok` side of a
+//! `Result`) to the app's validator before returning it. A response the
+//! validator rejects fails the call with [`ResponseError::Invalid`], so
+//! impossible data stops at the boundary instead of reaching UI state.
+
+use std::fmt;
+
+/// A response the command's validator rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidResponse {
+    pub command: String,
+    /// The validator's error, as text.
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid response from `{}`: {}",
+            self.command, self.reason
+        )
+    }
+}
+
+impl std::error::Error for InvalidResponse {}
+
+/// Error of a command with `validate_response`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseError {
+    /// The response arrived but the validator rejected it.
+    Invalid(InvalidResponse),
+    /// The call itself failed.
+    Failed(String),
+}
+
+impl fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseError::Invalid(error) => error.fmt(f),
+            ResponseError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ResponseError {}
+
+/// Run `validator` on the response `value` of `command`.
+pub fn check<T: ?Sized, E: fmt::Display>(
+    command: &str,
+    value: &T,
+    validator: impl FnOnce(&T) -> Result<(), E>,
+) -> Result<(), InvalidResponse> {
+    validator(value).map_err(|error| InvalidResponse {
+        command: command.to_string(),
+        reason: error.to_string(),
+    })
+}
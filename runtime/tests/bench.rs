@@ -0,0 +1,45 @@
+//! Tests for command timings.
+
+#![cfg(feature = "bench")]
+
+use serde_json::{Value, json};
+use tauri_bridge_runtime::bench::measure;
+
+#[tokio::test]
+async fn test_measure_dispatches_each_iteration() {
+    let mut calls = 0;
+    let report = measure("add", &json!({ "a": 1, "b": 2 }), 5, |args| {
+        calls += 1;
+        async move {
+            Some(Ok(json!(
+                args["a"].as_i64().unwrap() + args["b"].as_i64().unwrap()
+            )))
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(calls, 5);
+    assert_eq!(report.command, "add");
+    assert_eq!(report.iterations, 5);
+    assert_eq!(report.args_bytes, r#"{"a":1,"b":2}"#.len());
+    assert_eq!(report.response_bytes, 1);
+    assert!(report.to_string().starts_with("add: "));
+}
+
+#[tokio::test]
+async fn test_measure_stops_at_rejected_call() {
+    let result = measure("fail", &Value::Null, 3, |_| async {
+        Some(Err(json!("boom")))
+    })
+    .await;
+
+    assert_eq!(result, Err(json!("boom")));
+}
+
+#[tokio::test]
+async fn test_measure_reports_unknown_command() {
+    let result = measure("missing", &Value::Null, 1, |_| async { None }).await;
+
+    assert_eq!(result, Err(json!("command missing not found")));
+}
@@ -0,0 +1,59 @@
+//! Tests for the startup values of `bridge_bootstrap!`.
+
+#![cfg(feature = "bootstrap")]
+
+use serde_json::json;
+use tauri_bridge_runtime::bootstrap::{Bundle, Failed, Loaded, Report};
+
+#[test]
+fn test_loaded_keeps_value_or_error() {
+    let loaded = Loaded::new("get_app_version", &"1.2.0");
+    assert_eq!(loaded.value, json!("1.2.0"));
+    assert_eq!(loaded.error, None);
+
+    let failed = Loaded::failed("get_user", &"not signed in");
+    assert_eq!(failed.value, json!(null));
+    assert_eq!(failed.error.as_deref(), Some("not signed in"));
+
+    let failed = Loaded::failed("get_user", &json!({ "code": 401 }));
+    assert_eq!(failed.error.as_deref(), Some(r#"{"code":401}"#));
+}
+
+#[test]
+fn test_bundle_round_trips() {
+    let bundle = Bundle {
+        loaded: vec![
+            Loaded::new("list_projects", &vec!["a", "b"]),
+            Loaded::failed("get_user", &"offline"),
+        ],
+    };
+
+    let json = serde_json::to_value(&bundle).unwrap();
+    assert_eq!(json["loaded"][0]["command"], "list_projects");
+    assert_eq!(json["loaded"][1]["error"], "offline");
+    assert_eq!(serde_json::from_value::<Bundle>(json).unwrap(), bundle);
+}
+
+#[test]
+fn test_report_lists_failed_commands() {
+    let mut report = Report {
+        primed: vec!["list_projects".to_string()],
+        ..Report::default()
+    };
+    assert!(report.is_ok());
+    assert_eq!(report.to_string(), "bootstrap loaded, commands: 1");
+
+    report.fail("get_user", "offline");
+    assert!(!report.is_ok());
+    assert_eq!(
+        report.failed,
+        [Failed {
+            command: "get_user".to_string(),
+            error: "offline".to_string(),
+        }]
+    );
+    assert_eq!(
+        report.to_string(),
+        "bootstrap incomplete:\n- `get_user`: offline"
+    );
+}
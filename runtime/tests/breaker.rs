@@ -0,0 +1,73 @@
+//! Tests for the circuit breaker.
+
+use std::time::Duration;
+
+use tauri_bridge_runtime::breaker::{BreakerError, check, is_open, record, reset};
+
+fn fail(command: &'static str) {
+    let result = record::<()>(command, 3, Err("down".to_string()));
+    assert_eq!(result, Err(BreakerError::Failed("down".to_string())));
+}
+
+#[test]
+fn test_opens_after_threshold() {
+    fail("sync");
+    fail("sync");
+    assert!(check("sync", 60_000).is_ok());
+
+    fail("sync");
+
+    match check("sync", 60_000) {
+        Err(BreakerError::Open {
+            command,
+            retry_in_ms,
+        }) => {
+            assert_eq!(command, "sync");
+            assert!(retry_in_ms > 59_000);
+        }
+        other => panic!("expected an open circuit, got {:?}", other),
+    }
+    reset("sync");
+    assert!(!is_open("sync", 60_000));
+}
+
+#[test]
+fn test_success_resets_failures() {
+    fail("save");
+    fail("save");
+    assert_eq!(record("save", 3, Ok(1)), Ok(1));
+    fail("save");
+
+    assert!(!is_open("save", 60_000));
+}
+
+#[test]
+fn test_trial_after_cooldown() {
+    for _ in 0..3 {
+        fail("fetch");
+    }
+    assert!(is_open("fetch", 5));
+
+    std::thread::sleep(Duration::from_millis(10));
+    assert!(check("fetch", 5).is_ok());
+
+    // A failed trial reopens, a successful one closes
+    fail("fetch");
+    assert!(is_open("fetch", 5));
+    std::thread::sleep(Duration::from_millis(10));
+    assert_eq!(record("fetch", 3, Ok(())), Ok(()));
+    assert!(!is_open("fetch", 5));
+}
+
+#[test]
+fn test_open_error_message() {
+    let error = BreakerError::Open {
+        command: "sync",
+        retry_in_ms: 250,
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "Circuit for `sync` is open; retry in 250 ms"
+    );
+}
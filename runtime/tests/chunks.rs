@@ -0,0 +1,42 @@
+//! Tests for chunked response reassembly.
+
+use tauri_bridge_runtime::chunks::{Envelope, Reassembly};
+
+#[tokio::test]
+async fn test_inline_response() {
+    let reassembly = Reassembly::new();
+
+    let json = reassembly.collect(Envelope::Inline("[1,2]".into())).await;
+
+    assert_eq!(json, "[1,2]");
+}
+
+#[tokio::test]
+async fn test_waits_for_late_chunks() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            let reassembly = Reassembly::new();
+            reassembly.push("{\"a\":".into());
+
+            let pending = tokio::task::spawn_local({
+                let reassembly = reassembly.clone();
+                async move { reassembly.collect(Envelope::Chunks(2)).await }
+            });
+            tokio::task::yield_now().await;
+            assert!(!pending.is_finished());
+
+            reassembly.push("1}".into());
+            assert_eq!(pending.await.unwrap(), "{\"a\":1}");
+        })
+        .await;
+}
+
+#[test]
+fn test_envelope_wire_format() {
+    let inline: Envelope = serde_json::from_str(r#"{"inline":"null"}"#).unwrap();
+    let chunks: Envelope = serde_json::from_str(r#"{"chunks":3}"#).unwrap();
+
+    assert_eq!(inline, Envelope::Inline("null".into()));
+    assert_eq!(chunks, Envelope::Chunks(3));
+}
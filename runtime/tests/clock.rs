@@ -0,0 +1,89 @@
+//! Tests for the injectable clock.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use tauri_bridge_runtime::breaker::{check, is_open, record};
+use tauri_bridge_runtime::clock::{Clock, MockClock, now_ms, reset_clock, set_clock};
+use tauri_bridge_runtime::offline::{
+    clear_transport_override, set_transport_available, wait_for_transport,
+};
+use tauri_bridge_runtime::slow::{check as check_slow, clear_handler, on_slow_call};
+
+#[test]
+fn test_mock_clock_only_moves_when_advanced() {
+    let clock = MockClock::install();
+    assert_eq!(now_ms(), 0.0);
+
+    clock.advance(1_500);
+    assert_eq!(now_ms(), 1_500.0);
+    assert_eq!(clock.now_ms(), 1_500.0);
+
+    reset_clock();
+    let system = now_ms();
+    assert!(now_ms() >= system);
+}
+
+#[test]
+fn test_custom_clocks_can_be_set() {
+    struct Frozen;
+
+    impl Clock for Frozen {
+        fn now_ms(&self) -> f64 {
+            42.0
+        }
+    }
+
+    set_clock(Frozen);
+    assert_eq!(now_ms(), 42.0);
+    reset_clock();
+}
+
+#[test]
+fn test_breaker_cooldown_follows_the_clock() {
+    let clock = MockClock::install();
+    for _ in 0..3 {
+        let _ = record::<()>("upload", 3, Err("down".to_string()));
+    }
+
+    clock.advance(59_999);
+    assert!(is_open("upload", 60_000));
+    clock.advance(1);
+    assert!(check("upload", 60_000).is_ok());
+
+    assert_eq!(record("upload", 3, Ok(())), Ok(()));
+    reset_clock();
+}
+
+#[tokio::test]
+async fn test_offline_timeouts_follow_the_clock() {
+    let clock = MockClock::install();
+    set_transport_available(false);
+
+    let expired = wait_for_transport("export", 4, 10_000);
+    clock.advance(10_001);
+    set_transport_available(true);
+
+    let error = expired.await.unwrap_err();
+    assert!(error.contains("Timed out waiting for the Tauri transport to call `export`"));
+    clear_transport_override();
+    reset_clock();
+}
+
+#[test]
+fn test_slow_calls_are_measured_by_the_clock() {
+    let clock = MockClock::install();
+    let durations = Rc::new(RefCell::new(Vec::new()));
+    on_slow_call({
+        let durations = durations.clone();
+        move |call| durations.borrow_mut().push(call.duration_ms)
+    });
+
+    let started_ms = now_ms();
+    clock.advance(750);
+    check_slow("render_report", started_ms, || (None, None));
+
+    assert_eq!(*durations.borrow(), vec![750.0]);
+    clear_handler();
+    reset_clock();
+}
@@ -0,0 +1,59 @@
+//! Tests for compressed payloads.
+
+#![cfg(feature = "gzip")]
+
+use tauri_bridge_runtime::compress::{Payload, PayloadArgs, decode, encode};
+
+#[test]
+fn test_small_payload_stays_plain() {
+    let payload = encode("[1,2,3]".into(), 64).unwrap();
+
+    assert_eq!(
+        payload,
+        Payload {
+            gzip: false,
+            data: "[1,2,3]".into()
+        }
+    );
+    assert_eq!(decode(payload).unwrap(), "[1,2,3]");
+}
+
+#[test]
+fn test_large_payload_round_trips() {
+    let json = serde_json::to_string(&vec!["log line"; 1000]).unwrap();
+
+    let payload = encode(json.clone(), 64).unwrap();
+
+    assert!(payload.gzip);
+    assert!(payload.data.len() < json.len() / 10);
+    assert_eq!(decode(payload).unwrap(), json);
+}
+
+#[test]
+fn test_corrupt_payload_fails() {
+    let payload = Payload {
+        gzip: true,
+        data: "not base64!".into(),
+    };
+
+    assert!(
+        decode(payload)
+            .unwrap_err()
+            .starts_with("Failed to decompress payload")
+    );
+}
+
+#[test]
+fn test_payload_args_key() {
+    let args = PayloadArgs {
+        payload: Payload {
+            gzip: false,
+            data: "{}".into(),
+        },
+    };
+
+    assert_eq!(
+        serde_json::to_value(args).unwrap(),
+        serde_json::json!({ "tauriBridgePayload": { "gzip": false, "data": "{}" } })
+    );
+}
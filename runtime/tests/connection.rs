@@ -0,0 +1,98 @@
+//! Tests for the connection state.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use tauri_bridge_runtime::connection::{
+    ConnectionState, bridge_ready, disconnected, on_reconnect, rebound, reconnected, resubscribe,
+    state,
+};
+use tauri_bridge_runtime::subscription::Subscription;
+
+#[tokio::test]
+async fn test_bridge_ready_waits_for_reconnect() {
+    assert_eq!(state(), ConnectionState::Ready);
+    bridge_ready().await;
+
+    disconnected();
+    let released = Cell::new(false);
+    tokio::join!(
+        async {
+            bridge_ready().await;
+            released.set(true);
+        },
+        async {
+            assert!(!released.get());
+            reconnected();
+        }
+    );
+
+    assert!(released.get());
+    assert_eq!(state(), ConnectionState::Ready);
+}
+
+#[tokio::test]
+async fn test_rebound_reissues_calls_cut_off() {
+    let attempts = Cell::new(0);
+    let call = || {
+        attempts.set(attempts.get() + 1);
+        let attempt = attempts.get();
+        async move {
+            if attempt == 1 {
+                // Lost with the old connection
+                std::future::pending::<()>().await;
+            }
+            attempt
+        }
+    };
+
+    let (response, ()) = tokio::join!(rebound(call), async {
+        tokio::task::yield_now().await;
+        disconnected();
+        reconnected();
+    });
+
+    assert_eq!(response, 2);
+}
+
+#[test]
+fn test_on_reconnect_runs_until_dropped() {
+    let calls = Rc::new(Cell::new(0));
+    let subscription = on_reconnect({
+        let calls = calls.clone();
+        move || calls.set(calls.get() + 1)
+    });
+
+    reconnected(); // Already ready: no reconnect
+    disconnected();
+    reconnected();
+    drop(subscription);
+    disconnected();
+    reconnected();
+
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn test_resubscribe_replaces_subscription() {
+    let subscribed = Rc::new(Cell::new(0));
+    let unlistened = Rc::new(Cell::new(0));
+    let subscription = resubscribe({
+        let (subscribed, unlistened) = (subscribed.clone(), unlistened.clone());
+        move || {
+            subscribed.set(subscribed.get() + 1);
+            let unlistened = unlistened.clone();
+            Subscription::new(move || unlistened.set(unlistened.get() + 1))
+        }
+    });
+    assert_eq!((subscribed.get(), unlistened.get()), (1, 0));
+
+    disconnected();
+    reconnected();
+    assert_eq!((subscribed.get(), unlistened.get()), (2, 1));
+
+    drop(subscription);
+    disconnected();
+    reconnected();
+    assert_eq!((subscribed.get(), unlistened.get()), (2, 2));
+}
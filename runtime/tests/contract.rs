@@ -0,0 +1,76 @@
+//! Tests for the contract checks of recorded responses.
+
+#![cfg(feature = "contract")]
+
+use serde_json::{Value, json};
+use tauri_bridge_runtime::contract::{ContractError, Violation, shape_changes, verify};
+use tauri_bridge_runtime::replay::{RecordedCall, Recording};
+
+#[test]
+fn test_additive_changes_keep_the_shape() {
+    let recorded = json!({ "id": 1, "tags": ["a"], "owner": null, "score": 2.5 });
+    let current =
+        json!({ "id": 7, "tags": [], "owner": { "name": "Ada" }, "score": 3, "new": true });
+
+    assert!(shape_changes(&recorded, &current).is_empty());
+}
+
+#[test]
+fn test_breaking_changes_are_found_by_path() {
+    let recorded = json!({ "id": 1, "tags": ["a"], "owner": { "name": "Ada" } });
+    let current = json!({ "id": 1.5, "tags": ["b", 2], "owner": {} });
+
+    assert_eq!(
+        shape_changes(&recorded, &current),
+        [
+            (
+                "$.id".to_string(),
+                "expected an integer, got a fraction".to_string()
+            ),
+            ("$.owner.name".to_string(), "missing field".to_string()),
+            (
+                "$.tags[1]".to_string(),
+                "expected a string, got a number".to_string()
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_verify_reports_every_violation() {
+    let recording = Recording {
+        calls: ["get_user", "get_user"]
+            .into_iter()
+            .enumerate()
+            .map(|(id, command)| RecordedCall {
+                command: command.to_string(),
+                args: json!({ "id": id }),
+                response: json!({ "name": "Ada" }),
+                at_ms: 0.0,
+                duration_ms: 1.0,
+            })
+            .collect(),
+    };
+    let dispatch = async |_: &str, args: Value| match args["id"].as_u64() {
+        Some(0) => Ok(json!({ "name": "Ada" })),
+        _ => Err(json!("user not found")),
+    };
+
+    let error = verify(&recording, dispatch).await.unwrap_err();
+
+    assert_eq!(
+        error,
+        ContractError {
+            violations: vec![Violation {
+                call: 1,
+                command: "get_user".to_string(),
+                path: "$".to_string(),
+                message: "rejected: user not found".to_string(),
+            }],
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "1 recorded calls broke the contract\n  call 1 of `get_user` at $: rejected: user not found"
+    );
+}
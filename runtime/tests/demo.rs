@@ -0,0 +1,38 @@
+//! Tests for the state of demo implementations.
+
+#![cfg(feature = "demo")]
+
+use tauri_bridge_runtime::demo::{load, remove, save, update};
+
+#[test]
+fn test_demo_state_round_trips() {
+    assert_eq!(load::<Vec<String>>("tags"), None);
+
+    save("tags", &vec!["work".to_string()]);
+    let count = update("tags", |tags: &mut Vec<String>| {
+        tags.push("home".to_string());
+        tags.len()
+    });
+
+    assert_eq!(count, 2);
+    assert_eq!(
+        load::<Vec<String>>("tags"),
+        Some(vec!["work".to_string(), "home".to_string()])
+    );
+    // Values of another type read as missing
+    assert_eq!(load::<u32>("tags"), None);
+
+    remove("tags");
+    assert_eq!(load::<Vec<String>>("tags"), None);
+}
+
+#[test]
+fn test_update_starts_from_default() {
+    let total = update("visits", |visits: &mut u32| {
+        *visits += 1;
+        *visits
+    });
+
+    assert_eq!(total, 1);
+    assert_eq!(load::<u32>("visits"), Some(1));
+}
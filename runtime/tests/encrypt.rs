@@ -0,0 +1,46 @@
+//! Tests for the payload encryption.
+
+#![cfg(feature = "encrypt")]
+
+use tauri_bridge_runtime::encrypt::{clear_key_provider, open, seal, set_key_provider};
+
+// The provider is global, so everything runs in one test
+#[test]
+fn test_seal_and_open() {
+    clear_key_provider();
+    assert!(
+        seal("greet", "{}".to_string())
+            .unwrap_err()
+            .contains("no key provider")
+    );
+
+    set_key_provider(|command| match command {
+        "admin" => Err("no key for `admin`".to_string()),
+        _ => Ok([1; 32]),
+    });
+
+    let json = r#"{"name":"Ada"}"#.to_string();
+    let sealed = seal("greet", json.clone()).unwrap();
+    assert!(!sealed.data.contains("Ada"));
+    assert_eq!(open("greet", sealed.clone()).unwrap(), json);
+
+    // Fresh nonce for every payload
+    assert_ne!(seal("greet", json.clone()).unwrap().nonce, sealed.nonce);
+
+    // Bound to the command it was sealed for
+    assert!(open("farewell", sealed.clone()).is_err());
+
+    let mut tampered = sealed.clone();
+    tampered.data.replace_range(0..4, "AAAA");
+    assert!(open("greet", tampered).is_err());
+
+    assert_eq!(
+        seal("admin", json).unwrap_err(),
+        "no key for `admin`".to_string()
+    );
+
+    set_key_provider(|_| Ok([2; 32]));
+    assert!(open("greet", sealed).is_err());
+
+    clear_key_provider();
+}
@@ -0,0 +1,42 @@
+//! Tests for maps sent as `[key, value]` pairs.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use tauri_bridge_runtime::entries::Entries;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScoreArgs {
+    #[serde(with = "tauri_bridge_runtime::entries")]
+    bonus: HashMap<u32, u64>,
+    round: u32,
+}
+
+#[test]
+fn test_entries_keep_integer_keys() {
+    let scores = Entries(BTreeMap::from([(1_u32, 10_u64), (7, 80)]));
+    let json = serde_json::to_value(&scores).unwrap();
+
+    assert_eq!(json, serde_json::json!([[1, 10], [7, 80]]));
+    let decoded: Entries<BTreeMap<u32, u64>> = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, scores);
+}
+
+#[test]
+fn test_entries_fields_round_trip() {
+    let args = ScoreArgs {
+        bonus: HashMap::from([(3, 4)]),
+        round: 2,
+    };
+    let json = serde_json::to_value(&args).unwrap();
+
+    assert_eq!(json, serde_json::json!({ "bonus": [[3, 4]], "round": 2 }));
+    assert_eq!(serde_json::from_value::<ScoreArgs>(json).unwrap(), args);
+}
+
+#[test]
+fn test_entries_reject_objects() {
+    let object = serde_json::json!({ "1": 10 });
+    assert!(serde_json::from_value::<Entries<HashMap<u32, u64>>>(object).is_err());
+}
@@ -0,0 +1,57 @@
+//! Tests for response envelopes.
+
+use tauri_bridge_runtime::envelope::{ApiError, ApiResponse, Envelope};
+
+#[test]
+fn test_api_response_wire_format() {
+    let ok = serde_json::to_value(ApiResponse::ok(3)).unwrap();
+    let err = serde_json::to_value(ApiResponse::<u32>::err("not_found", "no user 3")).unwrap();
+
+    assert_eq!(ok, serde_json::json!({ "success": true, "data": 3 }));
+    assert_eq!(
+        err,
+        serde_json::json!({
+            "success": false,
+            "error": { "code": "not_found", "message": "no user 3" }
+        })
+    );
+}
+
+#[test]
+fn test_into_result_unwraps_data_or_error() {
+    assert_eq!(
+        ApiResponse::ok("Bob".to_string()).into_result(),
+        Ok("Bob".to_string())
+    );
+
+    let error = ApiResponse::<u32>::err("not_found", "no user 3")
+        .into_result()
+        .unwrap_err();
+    assert_eq!(error.code, "not_found");
+    assert_eq!(error.to_string(), "not_found: no user 3");
+}
+
+#[test]
+fn test_success_without_data() {
+    let unit: ApiResponse<()> = serde_json::from_str(r#"{"success": true}"#).unwrap();
+    let optional: ApiResponse<Option<u32>> =
+        serde_json::from_str(r#"{"success": true, "data": null}"#).unwrap();
+    let missing: ApiResponse<u32> = serde_json::from_str(r#"{"success": true}"#).unwrap();
+
+    assert_eq!(unit.into_result(), Ok(()));
+    assert_eq!(optional.into_result(), Ok(None));
+    assert_eq!(
+        missing.into_result().unwrap_err().code,
+        ApiError::INVALID_ENVELOPE
+    );
+}
+
+#[test]
+fn test_failure_without_error() {
+    let failed: ApiResponse<u32> = serde_json::from_str(r#"{"success": false}"#).unwrap();
+
+    assert_eq!(
+        failed.into_result().unwrap_err().code,
+        ApiError::INVALID_ENVELOPE
+    );
+}
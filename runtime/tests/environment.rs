@@ -0,0 +1,35 @@
+//! Tests for the detection of the Tauri webview.
+
+use tauri_bridge_runtime::breaker::BreakerError;
+use tauri_bridge_runtime::environment::{NotInTauri, in_tauri, reset_in_tauri, set_in_tauri};
+use tauri_bridge_runtime::panic::BridgeError;
+
+#[test]
+fn test_detection_can_be_overridden() {
+    // Native clients only run in tests, against a mocked invoke
+    assert!(in_tauri());
+
+    set_in_tauri(false);
+    assert!(!in_tauri());
+
+    reset_in_tauri();
+    assert!(in_tauri());
+}
+
+#[test]
+fn test_not_in_tauri_errors_name_the_command() {
+    assert_eq!(
+        BridgeError::not_in_tauri("get_theme"),
+        BridgeError::NotInTauri {
+            command: "get_theme"
+        }
+    );
+    assert_eq!(
+        String::not_in_tauri("get_theme"),
+        "`get_theme` can't be invoked outside of Tauri"
+    );
+    assert_eq!(
+        BreakerError::not_in_tauri("get_theme"),
+        BreakerError::Failed("`get_theme` can't be invoked outside of Tauri".to_string())
+    );
+}
@@ -0,0 +1,50 @@
+//! Tests for chunked file transfer.
+
+#![cfg(feature = "files")]
+
+use tauri_bridge_runtime::files::{decode_bytes, encode_bytes, read_chunk, write_chunk};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!(
+        "tauri-bridge-files-{}-{}",
+        std::process::id(),
+        name
+    ))
+}
+
+#[test]
+fn test_write_then_read_in_chunks() {
+    let path = temp_path("round-trip");
+    write_chunk(&path, 0, &encode_bytes(b"hello ")).unwrap();
+    write_chunk(&path, 6, &encode_bytes(b"world")).unwrap();
+
+    let first = read_chunk(&path, 0, 4).unwrap();
+    let rest = read_chunk(&path, 4, 64).unwrap();
+    let end = read_chunk(&path, 11, 64).unwrap();
+
+    assert_eq!(first.size, 11);
+    assert_eq!(decode_bytes(&first.data).unwrap(), b"hell");
+    assert_eq!(decode_bytes(&rest.data).unwrap(), b"o world");
+    assert!(end.data.is_empty());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_write_at_zero_truncates() {
+    let path = temp_path("truncate");
+    write_chunk(&path, 0, &encode_bytes(b"a long line")).unwrap();
+    write_chunk(&path, 0, &encode_bytes(b"short")).unwrap();
+
+    assert_eq!(std::fs::read(&path).unwrap(), b"short");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_missing_file_error_names_path() {
+    let path = temp_path("missing");
+
+    let error = read_chunk(&path, 0, 16).unwrap_err();
+
+    assert!(error.starts_with("Failed to read `"));
+    assert!(error.contains("missing"));
+}
@@ -0,0 +1,109 @@
+//! Tests for non-finite float policies.
+
+use serde::{Deserialize, Serialize};
+use tauri_bridge_runtime::floats::{AsNull, AsString, Floats, Reject};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sample {
+    label: String,
+    values: Vec<f64>,
+    peak: Option<f32>,
+    #[serde(with = "tauri_bridge_runtime::floats::string")]
+    mean: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Reading {
+    Missing,
+    Value(f64),
+    Range { low: f64, high: f64 },
+}
+
+fn sample() -> Sample {
+    Sample {
+        label: "cpu".to_string(),
+        values: vec![1.5, f64::NAN, f64::INFINITY],
+        peak: Some(f32::NEG_INFINITY),
+        mean: f64::NAN,
+    }
+}
+
+#[test]
+fn test_string_policy_round_trips_non_finite_floats() {
+    let json = serde_json::to_value(Floats::<_, AsString>::new(sample())).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "label": "cpu",
+            "values": [1.5, "NaN", "Infinity"],
+            "peak": "-Infinity",
+            "mean": "NaN",
+        })
+    );
+
+    let decoded: Floats<Sample, AsString> = serde_json::from_value(json).unwrap();
+    let decoded = decoded.into_inner();
+    assert_eq!(decoded.values[0], 1.5);
+    assert!(decoded.values[1].is_nan());
+    assert_eq!(decoded.values[2], f64::INFINITY);
+    assert_eq!(decoded.peak, Some(f32::NEG_INFINITY));
+    assert!(decoded.mean.is_nan());
+}
+
+#[test]
+fn test_string_policy_reaches_enum_variants() {
+    let readings = vec![
+        Reading::Missing,
+        Reading::Value(f64::NEG_INFINITY),
+        Reading::Range {
+            low: 0.5,
+            high: f64::INFINITY,
+        },
+    ];
+    let json = serde_json::to_value(Floats::<_, AsString>::new(&readings)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            "Missing",
+            { "Value": "-Infinity" },
+            { "Range": { "low": 0.5, "high": "Infinity" } },
+        ])
+    );
+
+    let decoded: Floats<Vec<Reading>, AsString> = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.0, readings);
+}
+
+#[test]
+fn test_reject_policy_fails_on_non_finite_floats() {
+    let error = serde_json::to_value(Floats::<_, Reject>::new(vec![1.0, f64::NAN])).unwrap_err();
+    assert!(error.to_string().contains("non-finite float NaN"));
+
+    let finite = serde_json::to_value(Floats::<_, Reject>::new(vec![1.0, 2.5])).unwrap();
+    assert_eq!(finite, serde_json::json!([1.0, 2.5]));
+}
+
+#[test]
+fn test_null_policy_decodes_null_as_nan() {
+    let json = serde_json::to_value(Floats::<_, AsNull>::new(vec![f64::NAN, 2.0])).unwrap();
+    assert_eq!(json, serde_json::json!([null, 2.0]));
+
+    let decoded: Floats<Vec<f64>, AsNull> = serde_json::from_value(json).unwrap();
+    assert!(decoded.0[0].is_nan());
+    assert_eq!(decoded.0[1], 2.0);
+
+    // Optional floats stay `None`
+    let decoded: Floats<Option<f64>, AsNull> =
+        serde_json::from_value(serde_json::Value::Null).unwrap();
+    assert_eq!(decoded.0, None);
+}
+
+#[test]
+fn test_policies_leave_strings_alone() {
+    let json = serde_json::json!(["NaN", 1.0]);
+    let decoded: Floats<(String, f64), AsString> = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.0, ("NaN".to_string(), 1.0));
+
+    let error = serde_json::from_value::<Floats<f64, AsNull>>(serde_json::json!("NaN"));
+    assert!(error.is_err());
+}
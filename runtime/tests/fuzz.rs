@@ -0,0 +1,83 @@
+//! Tests for the fuzz target driver.
+
+#![cfg(feature = "fuzz")]
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tauri_bridge_runtime::fuzz::{block_on, run};
+
+#[test]
+fn test_first_byte_picks_the_command() {
+    let calls = RefCell::new(Vec::new());
+    let dispatch = async |command: &str, args: Value| {
+        calls.borrow_mut().push((command.to_string(), args));
+        Err(Value::Null)
+    };
+
+    run(b"\x00{\"name\":\"Ada\"}", &["greet", "save"], dispatch);
+    run(b"\x03[1,2]", &["greet", "save"], dispatch);
+    // Skipped: no selector, arguments that aren't JSON, no commands
+    run(b"", &["greet"], dispatch);
+    run(b"\x00{\"name\"", &["greet"], dispatch);
+    run(b"\x00null", &[], dispatch);
+
+    assert_eq!(
+        calls.into_inner(),
+        [
+            ("greet".to_string(), json!({ "name": "Ada" })),
+            ("save".to_string(), json!([1, 2])),
+        ]
+    );
+}
+
+#[test]
+fn test_panics_reach_the_fuzzer() {
+    let dispatch = async |_: &str, args: Value| -> Result<Value, Value> {
+        let divisor = args.as_u64().unwrap();
+        Ok(json!(10 / divisor))
+    };
+
+    run(b"\x005", &["divide"], dispatch);
+    let panicked = std::panic::catch_unwind(|| run(b"\x000", &["divide"], dispatch));
+    assert!(panicked.is_err());
+}
+
+/// Ready once a thread it starts has woken it.
+#[derive(Default)]
+struct WokenByThread {
+    done: Arc<AtomicBool>,
+    started: bool,
+}
+
+impl Future for WokenByThread {
+    type Output = u32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        if self.done.load(Ordering::SeqCst) {
+            return Poll::Ready(7);
+        }
+        if !self.started {
+            self.started = true;
+            let done = self.done.clone();
+            let waker = cx.waker().clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(5));
+                done.store(true, Ordering::SeqCst);
+                waker.wake();
+            });
+        }
+        Poll::Pending
+    }
+}
+
+#[test]
+fn test_block_on_waits_for_wakes() {
+    assert_eq!(block_on(WokenByThread::default()), 7);
+}
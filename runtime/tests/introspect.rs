@@ -0,0 +1,87 @@
+//! Tests for the introspection handshake.
+
+use tauri_bridge_runtime::introspect::{
+    CommandInfo, Manifest, Mismatch, SchemaMismatch, schema_hash, verify_schema,
+};
+
+fn manifest(commands: &[(&str, u32, &str)]) -> Manifest {
+    Manifest::new(
+        commands
+            .iter()
+            .map(|(name, version, spec)| CommandInfo::new(name, *version, spec))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_schema_hash_is_stable() {
+    assert_eq!(schema_hash(""), "cbf29ce484222325");
+    assert_eq!(schema_hash("a"), "af63dc4c8601ec8c");
+    assert_ne!(schema_hash(r#"{"x":1}"#), schema_hash(r#"{"x":2}"#));
+}
+
+#[test]
+fn test_manifest_sorts_commands() {
+    let manifest = manifest(&[("shout", 2, "{}"), ("greet", 1, "{}"), ("shout", 1, "{}")]);
+
+    let order: Vec<_> = manifest
+        .commands
+        .iter()
+        .map(|command| (command.name.as_str(), command.version))
+        .collect();
+    assert_eq!(order, [("greet", 1), ("shout", 1), ("shout", 2)]);
+}
+
+#[test]
+fn test_check_accepts_backend_with_extra_commands() {
+    let client = manifest(&[("greet", 1, "{}")]);
+    let backend = manifest(&[("greet", 1, "{}"), ("admin", 1, "{}")]);
+
+    assert_eq!(client.check(&backend), Ok(()));
+}
+
+#[test]
+fn test_check_reports_missing_and_changed() {
+    let client = manifest(&[("greet", 1, "{}"), ("shout", 2, "{}"), ("add", 1, "{}")]);
+    let backend = manifest(&[("greet", 1, "{\"changed\":true}"), ("shout", 1, "{}")]);
+
+    let mismatch = client.check(&backend).unwrap_err();
+
+    assert_eq!(
+        mismatch,
+        Mismatch {
+            missing: vec!["add".to_string(), "shout@2".to_string()],
+            changed: vec!["greet".to_string()],
+        }
+    );
+    assert_eq!(
+        mismatch.to_string(),
+        "frontend and backend were built from different commands; \
+         missing on the backend: add, shout@2; changed: greet"
+    );
+}
+
+#[test]
+fn test_verify_schema_accepts_matching_and_missing_hashes() {
+    assert_eq!(verify_schema("greet", "0011", Some("0011")), Ok(()));
+    assert_eq!(verify_schema("greet", "0011", None), Ok(()));
+}
+
+#[test]
+fn test_verify_schema_rejects_other_hashes() {
+    let mismatch = verify_schema("greet", "0011", Some("0022")).unwrap_err();
+
+    assert_eq!(
+        mismatch,
+        SchemaMismatch {
+            command: "greet".to_string(),
+            sent: "0022".to_string(),
+            expected: "0011".to_string(),
+        }
+    );
+    assert_eq!(
+        mismatch.to_string(),
+        "`greet` was called by a client built against another version of it \
+         (client schema 0022, backend schema 0011); rebuild the frontend and backend together"
+    );
+}
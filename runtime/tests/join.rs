@@ -0,0 +1,45 @@
+//! Tests for the calls of `bridge_join!`.
+
+use std::future::poll_fn;
+use std::time::Duration;
+
+use tauri_bridge_runtime::join::{JoinError, Joined};
+
+#[tokio::test]
+async fn test_joined_calls_run_concurrently() {
+    let mut slow = Joined::new(async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        "slow"
+    });
+    let mut fast = Joined::new(async { 7 });
+
+    let mut fast_first = false;
+    poll_fn(|cx| {
+        let done = [slow.poll(cx), fast.poll(cx)];
+        fast_first |= done == [false, true];
+        if done.iter().all(|done| *done) {
+            std::task::Poll::Ready(())
+        } else {
+            std::task::Poll::Pending
+        }
+    })
+    .await;
+
+    assert!(fast_first);
+    assert_eq!((slow.take(), fast.take()), ("slow", 7));
+}
+
+#[test]
+#[should_panic(expected = "the joined call isn't done")]
+fn test_take_panics_while_running() {
+    Joined::new(async { 1 }).take();
+}
+
+#[test]
+fn test_join_error_names_the_command() {
+    let error = JoinError::new("get_user", "user 4 not found");
+
+    assert_eq!(error.command, "get_user");
+    assert_eq!(error.message, "user 4 not found");
+    assert_eq!(error.to_string(), "`get_user` failed: user 4 not found");
+}
@@ -0,0 +1,93 @@
+//! Tests for backend layers.
+
+use std::sync::{Arc, Mutex};
+
+use tauri_bridge_runtime::layer::{BridgeLayer, Call, Layers, Outcome, enter};
+
+struct Recorder {
+    name: &'static str,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl BridgeLayer for Recorder {
+    fn before(&self, call: &Call<'_>) -> Result<(), String> {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{} before {}", self.name, call.command));
+        Ok(())
+    }
+
+    fn after(&self, call: &Call<'_>, outcome: &Outcome) {
+        self.events.lock().unwrap().push(format!(
+            "{} after {} failed={}",
+            self.name, call.command, outcome.failed
+        ));
+    }
+}
+
+struct DenyAll;
+
+impl BridgeLayer for DenyAll {
+    fn before(&self, call: &Call<'_>) -> Result<(), String> {
+        Err(format!(
+            "`{}` denied for {}",
+            call.command, call.webview_label
+        ))
+    }
+}
+
+fn call() -> Call<'static> {
+    Call {
+        command: "save",
+        webview_label: "main",
+    }
+}
+
+#[test]
+fn test_layers_nest_around_the_command() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let layers = Layers::new()
+        .with(Recorder {
+            name: "outer",
+            events: events.clone(),
+        })
+        .with(Recorder {
+            name: "inner",
+            events: events.clone(),
+        });
+
+    let entered = enter(Some(&layers), call()).unwrap();
+    events.lock().unwrap().push("command".to_string());
+    entered.exit(true);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "outer before save",
+            "inner before save",
+            "command",
+            "inner after save failed=true",
+            "outer after save failed=true",
+        ]
+    );
+}
+
+#[test]
+fn test_rejecting_layer_stops_the_call() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let layers = Layers::new().with(DenyAll).with(Recorder {
+        name: "metrics",
+        events: events.clone(),
+    });
+
+    let result = enter(Some(&layers), call());
+
+    assert_eq!(result.err(), Some("`save` denied for main".to_string()));
+    assert!(events.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_missing_layers_let_calls_through() {
+    enter(None, call()).unwrap().exit(false);
+}
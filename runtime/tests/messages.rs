@@ -0,0 +1,79 @@
+//! Tests for the translated messages of `translate_errors` commands.
+
+use tauri_bridge_runtime::breaker::BreakerError;
+use tauri_bridge_runtime::messages::{
+    Failure, FailureKind, Translate, clear_translator, set_translator, translate,
+};
+use tauri_bridge_runtime::panic::BridgeError;
+
+#[test]
+fn test_failure_tells_what_failed() {
+    let failure = Failure::new("get_user", "Failed to deserialize response: invalid type");
+    assert_eq!(failure.kind, FailureKind::Response);
+    assert_eq!(failure.detail, "invalid type");
+
+    let failure = Failure::new(
+        "get_user",
+        "Failed to serialize arguments: key must be a string",
+    );
+    assert_eq!(failure.kind, FailureKind::Arguments);
+    assert_eq!(failure.detail, "key must be a string");
+
+    let failure = Failure::new("get_user", "invalid signature");
+    assert_eq!(failure.kind, FailureKind::Other);
+    assert_eq!(failure.detail, "invalid signature");
+}
+
+#[test]
+fn test_translator_replaces_messages() {
+    clear_translator();
+    let message = "Failed to deserialize response: invalid type".to_string();
+    assert_eq!(translate("get_user", message.clone()), message);
+
+    set_translator(|failure| match failure.kind {
+        FailureKind::Response => Some(format!("Antwort von {} unlesbar", failure.command)),
+        _ => None,
+    });
+    assert_eq!(
+        translate("get_user", message.clone()),
+        "Antwort von get_user unlesbar"
+    );
+    // Kept when the translator has nothing better
+    assert_eq!(
+        translate("get_user", "invalid signature".to_string()),
+        "invalid signature"
+    );
+
+    clear_translator();
+    assert_eq!(translate("get_user", message.clone()), message);
+}
+
+#[test]
+fn test_typed_errors_keep_their_variants() {
+    set_translator(|failure| Some(format!("{} failed", failure.command)));
+
+    assert_eq!(
+        BridgeError::Failed("Failed to deserialize response: eof".to_string())
+            .translate("parse_port"),
+        BridgeError::Failed("parse_port failed".to_string())
+    );
+    assert_eq!(
+        BridgeError::Panicked {
+            message: "not a port".to_string()
+        }
+        .translate("parse_port"),
+        BridgeError::Panicked {
+            message: "not a port".to_string()
+        }
+    );
+    assert!(matches!(
+        BreakerError::Open {
+            command: "sync_now",
+            retry_in_ms: 10
+        }
+        .translate("sync_now"),
+        BreakerError::Open { .. }
+    ));
+
+    clear_translator();
+}
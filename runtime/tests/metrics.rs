@@ -0,0 +1,47 @@
+//! Tests for the metrics registry.
+
+use std::time::Duration;
+
+use tauri_bridge_runtime::metrics::{BUCKET_BOUNDS_MICROS, bridge_metrics, record};
+
+// The registry is process-wide, so each test records its own commands
+
+#[test]
+fn test_record_counts_calls_and_errors() {
+    record("metrics_counts", Duration::from_millis(2), false);
+    record("metrics_counts", Duration::from_millis(4), true);
+
+    let metrics = &bridge_metrics().commands["metrics_counts"];
+
+    assert_eq!(metrics.calls, 2);
+    assert_eq!(metrics.errors, 1);
+    assert_eq!(metrics.total_micros, 6_000);
+    assert_eq!(metrics.max_micros, 4_000);
+    assert_eq!(metrics.mean(), Duration::from_millis(3));
+}
+
+#[test]
+fn test_record_fills_duration_buckets() {
+    record("metrics_buckets", Duration::from_micros(1_000), false);
+    record("metrics_buckets", Duration::from_millis(20), false);
+    record("metrics_buckets", Duration::from_secs(60), false);
+
+    let buckets = &bridge_metrics().commands["metrics_buckets"].buckets;
+
+    assert_eq!(buckets.len(), BUCKET_BOUNDS_MICROS.len() + 1);
+    assert_eq!(buckets[0], 1);
+    assert_eq!(buckets[3], 1);
+    assert_eq!(buckets[BUCKET_BOUNDS_MICROS.len()], 1);
+    assert_eq!(buckets.iter().sum::<u64>(), 3);
+}
+
+#[test]
+fn test_snapshot_serializes() {
+    record("metrics_serialized", Duration::from_millis(1), false);
+
+    let snapshot = bridge_metrics();
+    let json = serde_json::to_value(&snapshot).unwrap();
+
+    assert_eq!(json["commands"]["metrics_serialized"]["calls"], 1);
+    assert_eq!(serde_json::from_value::<_>(json).ok(), Some(snapshot));
+}
@@ -0,0 +1,58 @@
+//! Tests for the offline queue.
+
+use std::time::Duration;
+
+use tauri_bridge_runtime::offline::{
+    clear_transport_override, queued_calls, set_transport_available, transport_available,
+    wait_for_transport,
+};
+
+#[tokio::test]
+async fn test_waits_until_transport_is_back() {
+    set_transport_available(false);
+    assert!(!transport_available());
+
+    let waiting = tokio::task::LocalSet::new();
+    let handle = waiting.spawn_local(wait_for_transport("log", 4, 60_000));
+    waiting
+        .run_until(async {
+            tokio::task::yield_now().await;
+            assert_eq!(queued_calls(), 1);
+
+            set_transport_available(true);
+            assert!(handle.await.unwrap().is_ok());
+        })
+        .await;
+
+    assert_eq!(queued_calls(), 0);
+    clear_transport_override();
+}
+
+#[tokio::test]
+async fn test_queue_is_bounded_per_command() {
+    set_transport_available(false);
+
+    let _first = wait_for_transport("track", 1, 60_000);
+    let _other = wait_for_transport("log", 1, 60_000);
+    let full = wait_for_transport("track", 1, 60_000).await;
+
+    assert!(
+        full.unwrap_err()
+            .contains("Offline queue for `track` is full")
+    );
+    set_transport_available(true);
+    clear_transport_override();
+}
+
+#[tokio::test]
+async fn test_expired_calls_time_out() {
+    set_transport_available(false);
+
+    let expired = wait_for_transport("sync", 4, 1);
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    set_transport_available(true);
+
+    let error = expired.await.unwrap_err();
+    assert!(error.contains("Timed out waiting for the Tauri transport to call `sync`"));
+    clear_transport_override();
+}
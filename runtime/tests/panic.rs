@@ -0,0 +1,67 @@
+//! Tests for panic capture.
+
+use tauri_bridge_runtime::panic::{BridgeError, Caught, catch, catch_async, unwrap};
+
+#[test]
+fn test_catch_returns_value_or_panic_message() {
+    assert_eq!(catch(|| 1 + 1), Caught::Value(2));
+    assert_eq!(
+        catch(|| -> u8 { panic!("static message") }),
+        Caught::Panicked {
+            message: "static message".to_string()
+        }
+    );
+    assert_eq!(
+        catch(|| -> u8 { panic!("formatted {}", 42) }),
+        Caught::Panicked {
+            message: "formatted 42".to_string()
+        }
+    );
+    assert_eq!(
+        catch(|| -> u8 { std::panic::panic_any(7) }),
+        Caught::Panicked {
+            message: "command panicked".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_catch_async_catches_panics_after_await() {
+    assert_eq!(catch_async(async { 5 }).await, Caught::Value(5));
+
+    let caught = catch_async(async {
+        tokio::task::yield_now().await;
+        panic!("after yield");
+    })
+    .await;
+    assert_eq!(
+        caught,
+        Caught::<()>::Panicked {
+            message: "after yield".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_unwrap_maps_panics_and_failures() {
+    assert_eq!(unwrap(Ok(Caught::Value(3))), Ok(3));
+    assert_eq!(
+        unwrap::<u8>(Ok(Caught::Panicked {
+            message: "boom".to_string()
+        })),
+        Err(BridgeError::Panicked {
+            message: "boom".to_string()
+        })
+    );
+    assert_eq!(
+        unwrap::<u8>(Err("offline".to_string())),
+        Err(BridgeError::Failed("offline".to_string()))
+    );
+    assert_eq!(
+        BridgeError::Panicked {
+            message: "boom".to_string()
+        }
+        .to_string(),
+        "command panicked: boom"
+    );
+}
@@ -0,0 +1,68 @@
+//! Tests for `max_payload` size checks.
+
+#![cfg(feature = "payload")]
+
+use serde::Serialize;
+use tauri_bridge_runtime::payload::{PayloadError, PayloadTooLarge, check};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentArgs {
+    thread_id: u32,
+    text: String,
+}
+
+#[test]
+fn test_arguments_within_limit_pass() {
+    let args = CommentArgs {
+        thread_id: 1,
+        text: "hi".to_string(),
+    };
+    let size = serde_json::to_string(&args).unwrap().len();
+
+    assert_eq!(check("post_comment", size, &args), Ok(()));
+    assert!(check("post_comment", size - 1, &args).is_err());
+}
+
+#[test]
+fn test_counting_stops_past_limit() {
+    let args = CommentArgs {
+        thread_id: 1,
+        text: "a".repeat(100),
+    };
+    let error = check("post_comment", 64, &args).unwrap_err();
+    assert_eq!(error.command, "post_comment");
+    assert_eq!(error.limit, 64);
+    assert!(error.size > 64);
+
+    let samples = vec![0u8; 1_000_000];
+    let error = check("upload_samples", 64, &samples).unwrap_err();
+    assert!(error.size > 64 && error.size < 1_000);
+}
+
+#[test]
+fn test_errors_display_and_round_trip() {
+    let too_large = PayloadTooLarge {
+        command: "post_comment".to_string(),
+        size: 70,
+        limit: 64,
+    };
+
+    assert_eq!(
+        too_large.to_string(),
+        "arguments of `post_comment` exceed the 64 byte limit"
+    );
+    let json = serde_json::to_value(&too_large).unwrap();
+    assert_eq!(
+        serde_json::from_value::<PayloadTooLarge>(json).unwrap(),
+        too_large
+    );
+    assert_eq!(
+        PayloadError::TooLarge(too_large.clone()).to_string(),
+        too_large.to_string()
+    );
+    assert_eq!(
+        PayloadError::Failed("offline".to_string()).to_string(),
+        "offline"
+    );
+}
@@ -0,0 +1,129 @@
+//! Tests for persisted query responses.
+
+#![cfg(feature = "persist")]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use tauri_bridge_runtime::persist::{
+    ITEM_PREFIX, Store, clear_store, load, remove, restore, save, saving, set_store,
+};
+use tauri_bridge_runtime::query::{cached, clear, invalidate, is_cached};
+
+#[derive(Clone, Default)]
+struct MemoryStore(Rc<RefCell<HashMap<String, String>>>);
+
+impl Store for MemoryStore {
+    fn get(&self, item: &str) -> Option<String> {
+        self.0.borrow().get(item).cloned()
+    }
+
+    fn set(&self, item: &str, value: &str) {
+        self.0
+            .borrow_mut()
+            .insert(item.to_string(), value.to_string());
+    }
+
+    fn remove(&self, item: &str) {
+        self.0.borrow_mut().remove(item);
+    }
+}
+
+#[test]
+fn test_saved_responses_load_by_args() {
+    let store = MemoryStore::default();
+    set_store(store.clone());
+
+    save("get_users", "v1", "1".into(), &vec!["Ada"]);
+    save("get_users", "v1", "2".into(), &vec!["Bob"]);
+
+    assert_eq!(
+        load::<Vec<String>>("get_users", "v1", "1"),
+        Some(vec!["Ada".into()])
+    );
+    assert_eq!(
+        load::<Vec<String>>("get_users", "v1", "2"),
+        Some(vec!["Bob".into()])
+    );
+    assert_eq!(load::<Vec<String>>("get_users", "v1", "3"), None);
+    assert_eq!(load::<Vec<String>>("get_teams", "v1", "1"), None);
+    // Responses that don't decode as the type are skipped
+    assert_eq!(load::<u32>("get_users", "v1", "1"), None);
+    assert!(store.get(&format!("{}get_users", ITEM_PREFIX)).is_some());
+    clear_store();
+}
+
+#[test]
+fn test_other_versions_are_dropped() {
+    let store = MemoryStore::default();
+    set_store(store.clone());
+
+    save("get_users", "v1", "1".into(), &1);
+    assert_eq!(load::<u32>("get_users", "v2", "1"), None);
+    assert!(store.get(&format!("{}get_users", ITEM_PREFIX)).is_none());
+
+    // Saving with a new version starts over
+    save("get_users", "v1", "1".into(), &1);
+    save("get_users", "v2", "2".into(), &2);
+    assert_eq!(load::<u32>("get_users", "v2", "1"), None);
+    assert_eq!(load::<u32>("get_users", "v2", "2"), Some(2));
+    clear_store();
+}
+
+#[tokio::test]
+async fn test_restore_seeds_the_query_cache() {
+    clear();
+    set_store(MemoryStore::default());
+
+    let fetch = saving("get_users", "v1", "1".into(), async {
+        Ok::<_, String>(7_u32)
+    });
+    assert_eq!(cached("get_users", "1".into(), fetch).await, Ok(7));
+
+    clear();
+    restore::<u32>("get_users", "v1", "1");
+    assert!(is_cached("get_users"));
+    assert_eq!(
+        cached("get_users", "1".into(), async { Ok::<_, String>(9_u32) }).await,
+        Ok(7)
+    );
+
+    // Memory responses win over persisted ones
+    save("get_users", "v1", "1".into(), &8_u32);
+    restore::<u32>("get_users", "v1", "1");
+    assert_eq!(
+        cached("get_users", "1".into(), async { Ok::<_, String>(9_u32) }).await,
+        Ok(7)
+    );
+    clear_store();
+}
+
+#[tokio::test]
+async fn test_failed_fetches_and_invalidation_are_not_persisted() {
+    clear();
+    let store = MemoryStore::default();
+    set_store(store.clone());
+
+    let fetch = saving("get_users", "v1", "1".into(), async {
+        Err::<u32, _>("offline".to_string())
+    });
+    assert!(fetch.await.is_err());
+    assert_eq!(load::<u32>("get_users", "v1", "1"), None);
+
+    save("get_users", "v1", "1".into(), &1_u32);
+    save("get_teams", "v1", "1".into(), &1_u32);
+    invalidate(&["get_users"]);
+    assert_eq!(load::<u32>("get_users", "v1", "1"), None);
+    assert_eq!(load::<u32>("get_teams", "v1", "1"), Some(1));
+    remove(&["get_teams"]);
+    assert!(store.0.borrow().is_empty());
+    clear_store();
+}
+
+#[test]
+fn test_nothing_is_persisted_without_a_store() {
+    clear_store();
+    save("get_users", "v1", "1".into(), &1_u32);
+    assert_eq!(load::<u32>("get_users", "v1", "1"), None);
+}
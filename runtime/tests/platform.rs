@@ -0,0 +1,60 @@
+//! Tests for platform checks.
+
+use tauri_bridge_runtime::platform::{
+    Platform, PlatformError, check, current, from_user_agent, reset_platform, set_platform,
+};
+
+#[test]
+fn test_check_rejects_excluded_platform() {
+    set_platform(Platform::Ios);
+
+    assert_eq!(
+        check("open_devtools", &[Platform::Windows, Platform::Macos]),
+        Err(PlatformError::Unsupported {
+            command: "open_devtools",
+            platform: Platform::Ios,
+        })
+    );
+    assert!(check("scan_qr", &[Platform::Ios, Platform::Android]).is_ok());
+    reset_platform();
+}
+
+#[test]
+fn test_override_and_reset() {
+    set_platform(Platform::Android);
+    assert_eq!(current(), Some(Platform::Android));
+
+    reset_platform();
+    assert_eq!(current().map(Platform::name), Some(std::env::consts::OS));
+}
+
+#[test]
+fn test_platform_from_user_agent() {
+    let cases = [
+        (
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36",
+            Some(Platform::Android),
+        ),
+        (
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15",
+            Some(Platform::Ios),
+        ),
+        (
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 Edg/120.0",
+            Some(Platform::Windows),
+        ),
+        (
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15",
+            Some(Platform::Macos),
+        ),
+        (
+            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/605.1.15",
+            Some(Platform::Linux),
+        ),
+        ("curl/8.0", None),
+    ];
+
+    for (user_agent, platform) in cases {
+        assert_eq!(from_user_agent(user_agent), platform, "{}", user_agent);
+    }
+}
@@ -0,0 +1,162 @@
+//! Tests for the query cache.
+
+#![cfg(feature = "query")]
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use tauri_bridge_runtime::query::{
+    Prefetch, cached, clear, invalidate, is_cached, prefetch_all, stale_while_revalidate,
+    subscribe, subscribe_refreshed,
+};
+
+/// A refresh started by `stale_while_revalidate`
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+#[tokio::test]
+async fn test_cached_fetches_once_per_args() {
+    clear();
+    let fetches = Rc::new(Cell::new(0));
+    let fetch = |value: u32| {
+        let fetches = fetches.clone();
+        async move {
+            fetches.set(fetches.get() + 1);
+            Ok::<_, String>(value)
+        }
+    };
+
+    assert_eq!(cached("get_users", "1".into(), fetch(1)).await, Ok(1));
+    assert_eq!(cached("get_users", "1".into(), fetch(9)).await, Ok(1));
+    assert_eq!(cached("get_users", "2".into(), fetch(2)).await, Ok(2));
+    assert_eq!(fetches.get(), 2);
+    assert!(is_cached("get_users"));
+}
+
+#[tokio::test]
+async fn test_errors_are_not_cached() {
+    clear();
+
+    assert_eq!(
+        cached::<u32, _>("get_user", "7".into(), async { Err("offline".to_string()) }).await,
+        Err("offline".to_string())
+    );
+    assert!(!is_cached("get_user"));
+    assert_eq!(cached("get_user", "7".into(), async { Ok(7) }).await, Ok(7));
+}
+
+#[tokio::test]
+async fn test_invalidate_drops_responses_and_notifies() {
+    clear();
+    let notified = Rc::new(Cell::new(0));
+    let subscription = subscribe("get_users", {
+        let notified = notified.clone();
+        move || notified.set(notified.get() + 1)
+    });
+
+    cached("get_users", "null".into(), async { Ok(vec![1]) })
+        .await
+        .unwrap();
+    invalidate(&["get_users", "get_teams"]);
+
+    assert!(!is_cached("get_users"));
+    assert_eq!(notified.get(), 1);
+    assert_eq!(
+        cached("get_users", "null".into(), async { Ok(vec![1, 2]) }).await,
+        Ok(vec![1, 2])
+    );
+
+    drop(subscription);
+    invalidate(&["get_users"]);
+    assert_eq!(notified.get(), 1);
+}
+
+#[tokio::test]
+async fn test_prefetch_all_runs_fetches_concurrently() {
+    clear();
+    let ready = Rc::new(Cell::new(false));
+
+    // The first prefetch only finishes once the second one ran
+    let waiting: Prefetch<'_> = Box::pin(async {
+        while !ready.get() {
+            tokio::task::yield_now().await;
+        }
+        cached("get_teams", "null".into(), async { Ok(2) })
+            .await
+            .unwrap();
+    });
+    let unblocking: Prefetch<'_> = Box::pin(async {
+        cached("get_users", "null".into(), async { Ok(1) })
+            .await
+            .unwrap();
+        ready.set(true);
+    });
+    prefetch_all(vec![waiting, unblocking]).await;
+
+    assert!(is_cached("get_teams"));
+    assert!(is_cached("get_users"));
+    prefetch_all(Vec::new()).await;
+}
+
+#[tokio::test]
+async fn test_stale_while_revalidate_refreshes_in_background() {
+    clear();
+    let spawned: Rc<RefCell<Vec<Task>>> = Rc::default();
+    let spawn = |task| spawned.borrow_mut().push(task);
+    let refreshed = Rc::new(RefCell::new(Vec::new()));
+    let _subscription = subscribe_refreshed("get_team", {
+        let refreshed = refreshed.clone();
+        move |team: String| refreshed.borrow_mut().push(team)
+    });
+    let fetch = |team: &'static str| async move { Ok::<_, String>(team.to_string()) };
+
+    let read = stale_while_revalidate("get_team", "1".into(), fetch("red"), spawn).await;
+    assert_eq!(read, Ok("red".to_string()));
+    assert!(spawned.borrow().is_empty());
+
+    let read = stale_while_revalidate("get_team", "1".into(), fetch("blue"), spawn).await;
+    assert_eq!(read, Ok("red".to_string()));
+    // Only one refresh per arguments at a time
+    let read = stale_while_revalidate("get_team", "1".into(), fetch("green"), spawn).await;
+    assert_eq!(read, Ok("red".to_string()));
+    let tasks = std::mem::take(&mut *spawned.borrow_mut());
+    assert_eq!(tasks.len(), 1);
+    for task in tasks {
+        task.await;
+    }
+
+    assert_eq!(*refreshed.borrow(), ["blue"]);
+    assert_eq!(
+        cached("get_team", "1".into(), fetch("unused")).await,
+        Ok("blue".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_failed_refresh_keeps_stale_response() {
+    clear();
+    let spawned: Rc<RefCell<Vec<Task>>> = Rc::default();
+    let spawn = |task| spawned.borrow_mut().push(task);
+
+    cached("get_count", "null".into(), async { Ok(1) })
+        .await
+        .unwrap();
+    let read = stale_while_revalidate(
+        "get_count",
+        "null".into(),
+        async { Err::<i32, _>("offline".to_string()) },
+        spawn,
+    )
+    .await;
+    assert_eq!(read, Ok(1));
+    let tasks = std::mem::take(&mut *spawned.borrow_mut());
+    for task in tasks {
+        task.await;
+    }
+
+    assert_eq!(
+        cached("get_count", "null".into(), async { Ok(2) }).await,
+        Ok(1)
+    );
+}
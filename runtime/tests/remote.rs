@@ -0,0 +1,107 @@
+//! Tests for the remote backend transport.
+
+#![cfg(feature = "remote")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde_json::{Value, json};
+use tauri_bridge_runtime::remote::{
+    connect, decode_request, disconnect, encode_response, invoke, is_connected, pending_calls,
+    receive,
+};
+
+/// Connect to a backend that buffers the frames it's sent.
+fn connect_buffered() -> Rc<RefCell<Vec<String>>> {
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    connect({
+        let sent = sent.clone();
+        move |frame| {
+            sent.borrow_mut().push(frame);
+            Ok(())
+        }
+    });
+    sent
+}
+
+#[tokio::test]
+async fn test_responses_resolve_calls_by_id() {
+    let sent = connect_buffered();
+    assert!(is_connected());
+
+    let greet = invoke("greet", json!({ "name": "Ferris" }));
+    let add = invoke("add", json!({ "a": 1, "b": 2 }));
+    assert_eq!(pending_calls(), 2);
+
+    let requests: Vec<_> = sent
+        .borrow()
+        .iter()
+        .map(|frame| decode_request(frame).unwrap())
+        .collect();
+    assert_eq!(requests[0].command, "greet");
+    assert_eq!(requests[0].args, json!({ "name": "Ferris" }));
+    assert_eq!(requests[1].command, "add");
+
+    // Answered out of order
+    receive(&encode_response(requests[1].id, Ok(json!(3)))).unwrap();
+    receive(&encode_response(
+        requests[0].id,
+        Err(Value::String("Ferris is busy".to_string())),
+    ))
+    .unwrap();
+
+    assert_eq!(add.await, Ok(json!(3)));
+    assert_eq!(
+        greet.await,
+        Err(Value::String("Ferris is busy".to_string()))
+    );
+    assert_eq!(pending_calls(), 0);
+    disconnect("done");
+}
+
+#[tokio::test]
+async fn test_disconnect_fails_pending_calls() {
+    let _sent = connect_buffered();
+    let waiting = invoke("sync", Value::Null);
+
+    disconnect("socket closed");
+    assert!(!is_connected());
+    assert_eq!(
+        waiting.await,
+        Err(Value::String("socket closed".to_string()))
+    );
+
+    let error = invoke("sync", Value::Null).await.unwrap_err();
+    assert!(
+        error
+            .as_str()
+            .unwrap()
+            .contains("Not connected to the remote backend to call `sync`")
+    );
+    // Late responses are ignored
+    receive(&encode_response(0, Ok(Value::Null))).unwrap();
+}
+
+#[tokio::test]
+async fn test_send_failures_reject_the_call() {
+    connect(|_| Err("socket is closing".to_string()));
+
+    let error = invoke("greet", Value::Null).await.unwrap_err();
+    assert_eq!(error, Value::String("socket is closing".to_string()));
+    assert_eq!(pending_calls(), 0);
+    disconnect("done");
+}
+
+#[test]
+fn test_invalid_frames_are_rejected() {
+    assert!(
+        receive("not json")
+            .unwrap_err()
+            .contains("Invalid response from the remote backend")
+    );
+    assert!(
+        decode_request(r#"{"id":1}"#)
+            .unwrap_err()
+            .contains("Invalid call to the remote backend")
+    );
+}
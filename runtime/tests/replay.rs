@@ -0,0 +1,71 @@
+//! Tests for recorded and replayed calls.
+
+#![cfg(feature = "replay")]
+
+use serde_json::json;
+use tauri_bridge_runtime::clock::now_ms;
+use tauri_bridge_runtime::replay::{
+    Recording, is_recording, is_replaying, record, replayed, start_recording, start_replay,
+    stop_recording, stop_replay,
+};
+
+fn json_of(value: serde_json::Value) -> Option<String> {
+    Some(value.to_string())
+}
+
+#[test]
+fn test_calls_are_recorded_only_while_recording() {
+    record("get_user", now_ms(), || panic!("nothing is recorded"));
+    assert_eq!(stop_recording(), Recording::default());
+
+    start_recording();
+    assert!(is_recording());
+    record("get_user", now_ms() - 12.0, || {
+        (
+            json_of(json!({ "id": 4 })),
+            json_of(json!({ "name": "Ada" })),
+        )
+    });
+    let recording = stop_recording();
+
+    assert!(!is_recording());
+    assert_eq!(recording.calls.len(), 1);
+    let call = &recording.calls[0];
+    assert_eq!(call.command, "get_user");
+    assert_eq!(call.args, json!({ "id": 4 }));
+    assert_eq!(call.response, json!({ "name": "Ada" }));
+    assert!(call.duration_ms >= 12.0);
+}
+
+#[test]
+fn test_replay_prefers_calls_with_the_same_arguments() {
+    start_recording();
+    for (id, name) in [(1, "Ada"), (2, "Grace")] {
+        record("get_user", now_ms(), || {
+            (json_of(json!({ "id": id })), json_of(json!(name)))
+        });
+    }
+    let recording = Recording::from_json(&stop_recording().to_json()).unwrap();
+
+    assert_eq!(replayed("get_user", || panic!("not replaying")), None);
+    start_replay(recording);
+    assert!(is_replaying());
+
+    let replay = |id: u32| replayed("get_user", || json_of(json!({ "id": id })));
+    assert_eq!(replay(2), Some(Ok("\"Grace\"".to_string())));
+    // Other arguments get the next call of the command
+    assert_eq!(replay(7), Some(Ok("\"Ada\"".to_string())));
+    assert_eq!(
+        replay(1),
+        Some(Err("No recorded response left for `get_user`".to_string()))
+    );
+
+    stop_replay();
+    assert!(!is_replaying());
+}
+
+#[test]
+fn test_invalid_recordings_are_rejected() {
+    let error = Recording::from_json("[]").unwrap_err();
+    assert!(error.starts_with("Failed to parse recording: "));
+}
@@ -0,0 +1,82 @@
+//! Tests for the low-priority call scheduler.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use tauri_bridge_runtime::scheduler::{Priority, queued_calls, schedule, set_background_limit};
+
+#[tokio::test]
+async fn test_low_priority_waits_for_interactive_calls() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            let order = Rc::new(RefCell::new(Vec::new()));
+
+            let interactive = schedule(Priority::Normal).await;
+            let background = tokio::task::spawn_local({
+                let order = order.clone();
+                async move {
+                    let _permit = schedule(Priority::Low).await;
+                    order.borrow_mut().push("low");
+                }
+            });
+            tokio::task::yield_now().await;
+            assert!(order.borrow().is_empty());
+            assert_eq!(queued_calls(), 1);
+
+            // Interactive calls still go out right away
+            let _other = schedule(Priority::Normal).await;
+            order.borrow_mut().push("normal");
+            drop(_other);
+
+            drop(interactive);
+            background.await.unwrap();
+            assert_eq!(*order.borrow(), ["normal", "low"]);
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_background_limit_runs_in_order() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            set_background_limit(1);
+            let order = Rc::new(RefCell::new(Vec::new()));
+
+            let first = schedule(Priority::Low).await;
+            let queued: Vec<_> = (1..=3)
+                .map(|n| {
+                    let order = order.clone();
+                    tokio::task::spawn_local(async move {
+                        let _permit = schedule(Priority::Low).await;
+                        order.borrow_mut().push(n);
+                        tokio::task::yield_now().await;
+                    })
+                })
+                .collect();
+            tokio::task::yield_now().await;
+            assert_eq!(queued_calls(), 3);
+
+            drop(first);
+            for task in queued {
+                task.await.unwrap();
+            }
+            assert_eq!(*order.borrow(), [1, 2, 3]);
+            assert_eq!(queued_calls(), 0);
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_cancelled_call_leaves_queue() {
+    let blocker = schedule(Priority::Normal).await;
+    let waiting =
+        tokio::time::timeout(std::time::Duration::from_millis(5), schedule(Priority::Low)).await;
+
+    assert!(waiting.is_err());
+    assert_eq!(queued_calls(), 0);
+    drop(blocker);
+
+    let _permit = schedule(Priority::Low).await;
+}
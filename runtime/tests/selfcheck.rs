@@ -0,0 +1,108 @@
+//! Tests for the backend half of startup self-checks.
+
+#![cfg(feature = "selfcheck")]
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri_bridge_runtime::selfcheck::{Arg, Checker, Echo, Mismatch, Report};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Draft {
+    title: String,
+    word_count: f64,
+    tags: HashMap<String, u32>,
+}
+
+fn sample(checker: &mut Checker) {
+    checker.command::<Draft>(
+        "save_draft",
+        &[Arg::of::<Draft>("draft"), Arg::of::<u32>("revision")],
+    );
+    checker.command::<()>("discard_draft", &[Arg::of::<String>("id")]);
+}
+
+#[test]
+fn test_samples_are_defaults_of_the_response_types() {
+    let mut checker = Checker::new(None);
+    sample(&mut checker);
+    let check = checker.finish();
+
+    assert_eq!(check.samples.len(), 2);
+    assert_eq!(check.samples[0].command, "save_draft");
+    assert_eq!(
+        check.samples[0].response,
+        json!({ "title": "", "wordCount": 0.0, "tags": {} })
+    );
+    assert_eq!(check.samples[1].response, json!(null));
+    assert!(check.report.is_ok());
+}
+
+#[test]
+fn test_unchanged_echoes_pass() {
+    // JavaScript numbers lose the fraction of `0.0`
+    let echoes = vec![Echo {
+        command: "save_draft".to_string(),
+        args: json!({
+            "draft": { "title": "", "wordCount": 0, "tags": {} },
+            "revision": 0,
+        }),
+        response: r#"{"title":"","wordCount":0,"tags":{}}"#.to_string(),
+    }];
+    let mut checker = Checker::new(Some(echoes));
+    sample(&mut checker);
+    let check = checker.finish();
+
+    assert!(check.samples.is_empty());
+    // Commands without an echo are reported by the client
+    assert_eq!(check.report.checked, ["save_draft"]);
+    assert!(check.report.is_ok(), "{}", check.report);
+}
+
+#[test]
+fn test_changed_echoes_are_mismatches() {
+    let echoes = vec![Echo {
+        command: "save_draft".to_string(),
+        // The map arrived as `{}` only by chance; the title went missing
+        args: json!({ "draft": { "wordCount": 0, "tags": {} } }),
+        response: r#"{"title":"","wordCount":0}"#.to_string(),
+    }];
+    let mut checker = Checker::new(Some(echoes));
+    sample(&mut checker);
+    let report = checker.finish().report;
+
+    assert_eq!(report.mismatches.len(), 3);
+    assert_eq!(report.mismatches[0].part, "argument `draft`");
+    assert!(
+        report.mismatches[0]
+            .problem
+            .starts_with("failed to decode: missing field `title`")
+    );
+    assert_eq!(report.mismatches[1].part, "argument `revision`");
+    assert_eq!(
+        report.mismatches[2],
+        Mismatch {
+            command: "save_draft".to_string(),
+            part: "response".to_string(),
+            problem: r#"sent `{"tags":{},"title":"","wordCount":0.0}`, received `{"title":"","wordCount":0}`"#
+                .to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_report_display() {
+    let mut report = Report {
+        checked: vec!["save_draft".to_string()],
+        mismatches: Vec::new(),
+    };
+    assert_eq!(report.to_string(), "self-check passed, commands checked: 1");
+
+    report.mismatch("save_draft", "response", "sent `1`, received `2`");
+    assert_eq!(
+        report.to_string(),
+        "self-check failed:\n- `save_draft` response: sent `1`, received `2`"
+    );
+}
@@ -0,0 +1,83 @@
+//! Tests for the queues of `serialize_on` commands.
+
+use std::sync::{Arc, Mutex};
+
+use tauri_bridge_runtime::serial::{queued_calls, turn};
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[tokio::test]
+async fn test_turns_follow_arrival_order() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let call = |label: &'static str, project: u64| {
+        let order = order.clone();
+        let waiting = turn("project_id", &project);
+        async move {
+            let _turn = waiting.await;
+            order.lock().unwrap().push(label);
+            tokio::task::yield_now().await;
+            order.lock().unwrap().push(label);
+        }
+    };
+
+    let first = call("first", 1);
+    let second = call("second", 1);
+    assert_eq!(queued_calls("project_id", &1u64), 2);
+    tokio::join!(second, first);
+
+    assert_eq!(
+        *order.lock().unwrap(),
+        vec!["first", "first", "second", "second"]
+    );
+    assert_eq!(queued_calls("project_id", &1u64), 0);
+}
+
+#[tokio::test]
+async fn test_other_values_dont_wait() {
+    let held = turn("folder_id", "inbox").await;
+    let other = turn("folder_id", "archive").await;
+    // Same value, but another argument's queue
+    let renamed = turn("label_id", "inbox").await;
+
+    assert_eq!(queued_calls("folder_id", "inbox"), 1);
+    drop((held, other, renamed));
+    assert_eq!(queued_calls("folder_id", "inbox"), 0);
+}
+
+#[tokio::test]
+async fn test_cancelled_calls_give_up_their_turn() {
+    let held = turn("document_id", &3u32).await;
+    let cancelled = turn("document_id", &3u32);
+    let next = turn("document_id", &3u32);
+    drop(cancelled);
+    drop(held);
+
+    let _turn = next.await;
+    assert_eq!(queued_calls("document_id", &3u32), 1);
+}
+
+// Calls on Tauri's async runtime wake each other from any worker
+#[test]
+fn test_turns_are_passed_across_threads() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let held = block_on(turn("account_id", "alice"));
+
+    let waiting = turn("account_id", "alice");
+    let waiter = std::thread::spawn({
+        let order = order.clone();
+        move || {
+            let _turn = block_on(waiting);
+            order.lock().unwrap().push("waiter");
+        }
+    });
+    order.lock().unwrap().push("holder");
+    drop(held);
+    waiter.join().unwrap();
+
+    assert_eq!(*order.lock().unwrap(), vec!["holder", "waiter"]);
+}
@@ -0,0 +1,61 @@
+//! Tests for the session tokens and validators.
+
+use tauri_bridge_runtime::session::{
+    SessionValidator, clear_token_provider, set_token_provider, token, validate,
+};
+
+#[derive(Debug, PartialEq)]
+struct Session {
+    user: String,
+}
+
+#[test]
+fn test_token_comes_from_provider() {
+    clear_token_provider();
+    assert_eq!(
+        serde_json::to_value(token("greet")).unwrap(),
+        serde_json::Value::Null
+    );
+
+    set_token_provider(|command| (command != "sign_in").then(|| "secret".to_string()));
+    assert_eq!(serde_json::to_value(token("greet")).unwrap(), "secret");
+    assert_eq!(
+        serde_json::to_value(token("sign_in")).unwrap(),
+        serde_json::Value::Null
+    );
+    // Kept out of debug logs
+    assert_eq!(format!("{:?}", token("greet")), "Token(..)");
+
+    clear_token_provider();
+}
+
+#[test]
+fn test_validate_turns_tokens_into_sessions() {
+    let validator = SessionValidator::new(|command, token| match token {
+        "ada" => Ok(Session {
+            user: format!("{}:{}", token, command),
+        }),
+        _ => Err("session expired".to_string()),
+    });
+
+    assert_eq!(
+        validate(Some(&validator), "greet", Some("ada".to_string())),
+        Ok(Session {
+            user: "ada:greet".to_string()
+        })
+    );
+    assert_eq!(
+        validate(Some(&validator), "greet", Some("bob".to_string())),
+        Err("session expired".to_string())
+    );
+    assert!(
+        validate(Some(&validator), "greet", None)
+            .unwrap_err()
+            .contains("needs a session token")
+    );
+    assert!(
+        validate::<Session>(None, "greet", Some("ada".to_string()))
+            .unwrap_err()
+            .contains("no `SessionValidator<")
+    );
+}
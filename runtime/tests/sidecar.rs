@@ -0,0 +1,115 @@
+//! Tests for sidecar processes, served by this test binary itself.
+
+#![cfg(feature = "sidecar")]
+
+use std::io::Cursor;
+use std::process::Command;
+
+use serde_json::{Value, json};
+use tauri_bridge_runtime::remote::{Request, Response, encode_response};
+use tauri_bridge_runtime::sidecar::{SidecarHost, serve, serve_io};
+
+const SIDECAR_ENV: &str = "TAURI_BRIDGE_TEST_SIDECAR";
+
+async fn dispatch(command: &str, args: Value) -> Result<Value, Value> {
+    match command {
+        "add" => Ok(json!(
+            args["a"].as_i64().unwrap() + args["b"].as_i64().unwrap()
+        )),
+        "crash" => std::process::exit(3),
+        _ => Err(Value::String(format!("command {} not found", command))),
+    }
+}
+
+/// The sidecar's `main` when started by the tests below.
+#[tokio::test]
+async fn sidecar_main() {
+    if std::env::var_os(SIDECAR_ENV).is_some() {
+        serve(dispatch).await.unwrap();
+    }
+}
+
+fn host() -> SidecarHost {
+    SidecarHost::new().with("math", || {
+        let mut command = Command::new(std::env::current_exe().unwrap());
+        command
+            .args(["sidecar_main", "--exact", "--nocapture", "--quiet"])
+            .env(SIDECAR_ENV, "1");
+        command
+    })
+}
+
+#[tokio::test]
+async fn test_calls_run_in_sidecar() {
+    let host = host();
+
+    let sum = host.call("math", "add", json!({ "a": 2, "b": 3 }));
+    let missing = host.call("math", "divide", Value::Null);
+
+    assert_eq!(sum.await, Ok(json!(5)));
+    assert_eq!(
+        missing.await,
+        Err(Value::String("command divide not found".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_crashed_sidecar_is_restarted() {
+    let host = host();
+
+    let crashed = host.call("math", "crash", Value::Null).await;
+    assert_eq!(
+        crashed,
+        Err(Value::String("The `math` sidecar exited".to_string()))
+    );
+
+    let sum = host.call("math", "add", json!({ "a": 1, "b": 1 })).await;
+    assert_eq!(sum, Ok(json!(2)));
+}
+
+#[tokio::test]
+async fn test_unknown_sidecar_is_rejected() {
+    let error = host().call("pdf", "render", Value::Null).await.unwrap_err();
+    assert_eq!(error, "No sidecar named `pdf` is registered");
+}
+
+#[tokio::test]
+async fn test_serve_answers_each_request_line() {
+    let request = |id, a| {
+        serde_json::to_string(&Request {
+            id,
+            command: "add".to_string(),
+            args: json!({ "a": a, "b": 1 }),
+        })
+        .unwrap()
+    };
+    let input = format!("{}\nnot a call\n\n{}\n", request(7, 1), request(8, 2));
+    let mut output = Vec::new();
+
+    serve_io(Cursor::new(input), &mut output, dispatch)
+        .await
+        .unwrap();
+
+    let responses: Vec<Response> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        responses,
+        [
+            Response {
+                id: 7,
+                result: Ok(json!(2)),
+            },
+            Response {
+                id: 8,
+                result: Ok(json!(3)),
+            },
+        ]
+    );
+    assert_eq!(
+        encode_response(7, Ok(json!(2))),
+        r#"{"id":7,"result":{"Ok":2}}"#
+    );
+}
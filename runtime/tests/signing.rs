@@ -0,0 +1,45 @@
+//! Tests for the payload signing hooks.
+
+#![cfg(feature = "signing")]
+
+use serde_json::json;
+use tauri_bridge_runtime::signing::{clear_hooks, set_signer, set_verifier, sign, verify};
+
+#[test]
+fn test_hooks_transform_payloads() {
+    set_signer(|command, args| Ok(json!({ "args": args, "signature": command.len() })));
+    set_verifier(|command, response| {
+        if response["signature"] == command.len() {
+            Ok(response["value"].clone())
+        } else {
+            Err("bad signature".to_string())
+        }
+    });
+
+    assert_eq!(
+        sign("greet", json!({ "name": "Ada" })).unwrap(),
+        json!({ "args": { "name": "Ada" }, "signature": 5 })
+    );
+    assert_eq!(
+        verify("greet", json!({ "value": "Hello", "signature": 5 })).unwrap(),
+        json!("Hello")
+    );
+    assert_eq!(
+        verify("greet", json!({ "value": "Hello", "signature": 1 })).unwrap_err(),
+        "bad signature"
+    );
+
+    clear_hooks();
+}
+
+#[test]
+fn test_missing_hooks_fail_closed() {
+    clear_hooks();
+
+    assert!(sign("greet", json!({})).unwrap_err().contains("no signer"));
+    assert!(
+        verify("greet", json!(null))
+            .unwrap_err()
+            .contains("no verifier")
+    );
+}
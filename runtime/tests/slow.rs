@@ -0,0 +1,62 @@
+//! Tests for the reports of slow calls.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use tauri_bridge_runtime::clock::now_ms;
+use tauri_bridge_runtime::slow::{
+    DEFAULT_THRESHOLD_MS, SlowCall, check, clear_handler, on_slow_call, set_threshold_ms,
+    threshold_ms,
+};
+
+#[test]
+fn test_only_slow_calls_are_reported() {
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    on_slow_call({
+        let reports = reports.clone();
+        move |call| reports.borrow_mut().push(call.clone())
+    });
+    assert_eq!(threshold_ms(), DEFAULT_THRESHOLD_MS);
+
+    check("get_user", now_ms(), || {
+        panic!("fast calls aren't measured")
+    });
+    assert!(reports.borrow().is_empty());
+
+    check("search_notes", now_ms() - 450.0, || (Some(18), None));
+    let report = reports.borrow_mut().pop().unwrap();
+    assert_eq!(report.command, "search_notes");
+    assert!(report.duration_ms >= 450.0);
+    assert_eq!((report.args_bytes, report.response_bytes), (Some(18), None));
+
+    set_threshold_ms(1000.0);
+    check("search_notes", now_ms() - 450.0, || (None, None));
+    assert!(reports.borrow().is_empty());
+
+    set_threshold_ms(DEFAULT_THRESHOLD_MS);
+    clear_handler();
+}
+
+#[test]
+fn test_slow_call_is_logged_as_key_values() {
+    let call = SlowCall {
+        command: "search_notes".to_string(),
+        duration_ms: 812.44,
+        args_bytes: Some(18),
+        response_bytes: None,
+    };
+
+    assert_eq!(
+        call.to_string(),
+        "slow command command=search_notes duration_ms=812.4 args_bytes=18"
+    );
+    assert_eq!(
+        serde_json::to_value(&call).unwrap(),
+        serde_json::json!({
+            "command": "search_notes",
+            "durationMs": 812.44,
+            "argsBytes": 18,
+            "responseBytes": null,
+        })
+    );
+}
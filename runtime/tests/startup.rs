@@ -0,0 +1,31 @@
+//! Tests for the values of `sync_cache = "startup"` commands.
+
+use tauri_bridge_runtime::startup::{clear, expect, get, is_set, set};
+
+#[test]
+fn test_values_are_kept_per_command() {
+    clear();
+    assert!(!is_set("get_app_version"));
+    assert_eq!(get::<String>("get_app_version"), None);
+
+    set("get_app_version", "1.2.0".to_string());
+    set("get_max_upload", 64_u32);
+    assert!(is_set("get_app_version"));
+    assert_eq!(get::<String>("get_app_version").as_deref(), Some("1.2.0"));
+    assert_eq!(expect::<u32>("get_max_upload", "init_get_max_upload"), 64);
+    // Another type than the one fetched reads as missing
+    assert_eq!(get::<u64>("get_max_upload"), None);
+
+    set("get_app_version", "1.3.0".to_string());
+    assert_eq!(get::<String>("get_app_version").as_deref(), Some("1.3.0"));
+
+    clear();
+    assert!(!is_set("get_max_upload"));
+}
+
+#[test]
+#[should_panic(expected = "await `init_get_locale()` at startup")]
+fn test_expect_panics_before_init() {
+    clear();
+    expect::<String>("get_locale", "init_get_locale");
+}
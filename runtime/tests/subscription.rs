@@ -0,0 +1,60 @@
+//! Tests for subscriptions and scopes.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use tauri_bridge_runtime::subscription::{Scope, Subscription};
+
+fn counting_subscription(unlistened: &Rc<Cell<u32>>) -> Subscription {
+    let unlistened = unlistened.clone();
+    Subscription::new(move || unlistened.set(unlistened.get() + 1))
+}
+
+#[test]
+fn test_subscription_unlistens_on_drop() {
+    let unlistened = Rc::new(Cell::new(0));
+
+    let subscription = counting_subscription(&unlistened);
+    assert_eq!(unlistened.get(), 0);
+    drop(subscription);
+
+    assert_eq!(unlistened.get(), 1);
+}
+
+#[test]
+fn test_forget_keeps_listener() {
+    let unlistened = Rc::new(Cell::new(0));
+
+    counting_subscription(&unlistened).forget();
+
+    assert_eq!(unlistened.get(), 0);
+}
+
+#[test]
+fn test_scope_drops_subscriptions() {
+    let unlistened = Rc::new(Cell::new(0));
+    let scope = Scope::new();
+    scope.add(counting_subscription(&unlistened));
+    scope.add(counting_subscription(&unlistened));
+
+    assert_eq!(unlistened.get(), 0);
+    drop(scope);
+
+    assert_eq!(unlistened.get(), 2);
+}
+
+#[test]
+fn test_scope_bind_stops_after_drop() {
+    let calls = Rc::new(Cell::new(0));
+    let scope = Scope::new();
+    let mut callback = scope.bind({
+        let calls = calls.clone();
+        move |n: u32| calls.set(calls.get() + n)
+    });
+
+    callback(1);
+    drop(scope);
+    callback(2);
+
+    assert_eq!(calls.get(), 1);
+}
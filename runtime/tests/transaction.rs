@@ -0,0 +1,104 @@
+//! Tests for transactions of grouped commands.
+
+#![cfg(feature = "transaction")]
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Value, json};
+use tauri_bridge_runtime::transaction::{
+    Call, Executed, Outcome, Transaction, TransactionError, Undo, UndoLog,
+};
+
+fn undo(log: &Arc<Mutex<Vec<&'static str>>>, name: &'static str) -> Option<Undo> {
+    let log = log.clone();
+    Some(Box::pin(async move { log.lock().unwrap().push(name) }))
+}
+
+#[tokio::test]
+async fn test_undo_log_commits_responses_in_order() {
+    let mut log = UndoLog::new();
+    let undone = Arc::new(Mutex::new(Vec::new()));
+
+    assert!(
+        log.record("a".to_string(), Some(Ok((json!(1), undo(&undone, "a")))))
+            .await
+            .is_none()
+    );
+    assert!(
+        log.record("b".to_string(), Some(Ok((json!("two"), None))))
+            .await
+            .is_none()
+    );
+
+    assert_eq!(
+        log.commit(),
+        Outcome::Committed(vec![json!(1), json!("two")])
+    );
+    assert!(undone.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_step_undoes_earlier_steps_in_reverse() {
+    let mut log = UndoLog::new();
+    let undone = Arc::new(Mutex::new(Vec::new()));
+    let ran = |name| -> Option<Executed> { Some(Ok((Value::Null, undo(&undone, name)))) };
+
+    assert!(log.record("a".to_string(), ran("a")).await.is_none());
+    assert!(log.record("b".to_string(), ran("b")).await.is_none());
+    let outcome = log
+        .record("c".to_string(), Some(Err(json!("disk full"))))
+        .await;
+
+    assert_eq!(
+        outcome,
+        Some(Outcome::RolledBack {
+            step: 2,
+            command: "c".to_string(),
+            error: json!("disk full"),
+        })
+    );
+    assert_eq!(*undone.lock().unwrap(), ["b", "a"]);
+}
+
+#[tokio::test]
+async fn test_unknown_command_rolls_back() {
+    let outcome = UndoLog::new().record("greet".to_string(), None).await;
+
+    assert_eq!(
+        outcome,
+        Some(Outcome::RolledBack {
+            step: 0,
+            command: "greet".to_string(),
+            error: json!("command greet can't run in a transaction"),
+        })
+    );
+}
+
+#[test]
+fn test_transaction_sends_steps_and_hands_out_responses() {
+    let mut transaction = Transaction::new();
+    let count = transaction.add(Call::<u32>::new("count", &json!({ "tag": "home" })));
+    let name = transaction.add(Call::<String>::new("name", &Value::Null));
+    assert_eq!(transaction.len(), 2);
+    assert_eq!(name.index(), 1);
+
+    let args: Value = serde_json::from_str(&transaction.into_args().unwrap()).unwrap();
+    assert_eq!(
+        args,
+        json!({
+            "steps": [
+                { "command": "count", "args": { "tag": "home" } },
+                { "command": "name", "args": null },
+            ]
+        })
+    );
+
+    let mut committed = Outcome::Committed(vec![json!(3), json!(false)])
+        .into_committed()
+        .unwrap();
+    assert!(matches!(
+        committed.take(name),
+        Err(TransactionError::Failed(message)) if message.starts_with("Failed to deserialize response")
+    ));
+    assert_eq!(committed.take(count), Ok(3));
+}
@@ -0,0 +1,46 @@
+//! Tests for response validation.
+
+use tauri_bridge_runtime::validate::{InvalidResponse, ResponseError, check};
+
+fn positive(count: &i64) -> Result<(), String> {
+    if *count < 0 {
+        return Err(format!("{} is negative", count));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check_passes_accepted_responses() {
+    assert_eq!(check("count_items", &3, positive), Ok(()));
+}
+
+#[test]
+fn test_check_reports_rejected_responses() {
+    let error = check("count_items", &-1, positive).unwrap_err();
+
+    assert_eq!(
+        error,
+        InvalidResponse {
+            command: "count_items".to_string(),
+            reason: "-1 is negative".to_string(),
+        }
+    );
+    assert_eq!(
+        ResponseError::Invalid(error).to_string(),
+        "invalid response from `count_items`: -1 is negative"
+    );
+}
+
+#[test]
+fn test_check_takes_unsized_responses() {
+    let not_empty = |id: &str| {
+        if id.is_empty() {
+            Err("empty id")
+        } else {
+            Ok(())
+        }
+    };
+
+    assert!(check("get_id", "", not_empty).is_err());
+    assert!(check("get_id", "u-1", not_empty).is_ok());
+}
@@ -18,21 +18,11 @@
 //!   - A `GreetArgs` struct with Serialize/Deserialize
 //!   - `try_greet` async function that returns `Result<T, String>`
 //!   - `greet` async function with same signature as backend (unwraps result)
-
-mod backend;
-mod client;
-mod types;
-
-#[cfg(test)]
-mod tests;
+//!
+//! The code generation lives in `tauri-bridge-core`, which can expand
+//! commands outside of the compiler, e.g. for snapshot tests.
 
 use proc_macro::TokenStream;
-use proc_macro2::Span;
-use quote::quote_spanned;
-use syn::{ItemFn, parse_macro_input};
-
-use backend::generate_backend;
-use client::generate_client;
 
 /// Macro that generates both backend Tauri command and WASM client bindings.
 ///
@@ -51,19 +41,834 @@ use client::generate_client;
 ///   - A `GreetArgs` struct
 ///   - `try_greet` async function that returns `Result<T, String>`
 ///   - `greet` async function with same signature as backend (unwraps result)
+///
+/// # Options
+///
+/// - `backend_cfg(<predicate>)`: cfg predicate gating the backend half
+///   (default: `not(target_arch = "wasm32")`)
+/// - `client_cfg(<predicate>)`: cfg predicate gating the client half
+///   (default: `target_arch = "wasm32"`)
+/// - `client_attrs(<name>, ..)`: also put the function's attributes with
+///   these names (e.g. `allow`, `doc`) on the client functions and args struct
+/// - `client_names(try_fn = <ident>, args = <Ident>)`: rename `try_<name>`
+///   (and its `_to`, `_with_options` and `_incremental` variants) or the
+///   `<Name>Args` struct when they collide with items next to the function
+/// - `crate_paths(serde = "<path>", serde_wasm_bindgen = "<path>")`: reach
+///   renamed dependencies under these paths in the generated client
+/// - `client_mod = <path>`: don't re-export the client next to the definition;
+///   the module at `<path>` includes it with [`bridge_include!`]
+/// - `rename = "<name>"`: register the command under a different wire name
+/// - `version = <n>`: version the command; versions after 1 are registered
+///   as `<name>@<n>`, so an older implementation can keep serving old clients
+/// - `owned_params`: client functions take `impl AsRef<str>`,
+///   `impl AsRef<[T]>` and `T` instead of `&str`, `&[T]` and `&T`
+/// - `into_params`: client functions take `impl Into<String>` for `String`
+///   and `impl IntoIterator<Item = impl Into<String>>` for `Vec<String>`
+///   parameters, converting before serializing
+/// - `own_args`: the args struct holds `String`, `Vec<T>` and `T` instead of
+///   borrowing, and is exported next to the client functions
+/// - `strict`: reject types that misbehave across IPC (`u128`, floats,
+///   maps with non-string keys, raw pointers, `&mut` arguments)
+/// - `by_value`: allow `&mut` arguments by sending them by value; the
+///   backend returns the modified values and the client writes them back
+/// - `map_as = "entries"`: the `HashMap` or `BTreeMap` response is sent as
+///   an array of `[key, value]` pairs, keeping non-string keys; needs
+///   `tauri-bridge-runtime` on both sides
+/// - `floats = "error" | "null" | "string"`: how NaN and infinities in the
+///   arguments and response are sent; `error` fails serializing them,
+///   `null` sends `null` and decodes `null` floats as NaN, `string` sends
+///   `"NaN"`, `"Infinity"` and `"-Infinity"`; needs `tauri-bridge-runtime`
+///   on both sides
+/// - `json_compatible`: the client serializes the arguments with
+///   `serde_wasm_bindgen::Serializer::json_compatible()`, sending maps as
+///   objects and `None` as `null`; set on its own for commands sending
+///   tagged types of the crate (see below)
+/// - `with_options`: also generate `try_<name>_with_options(.., options)`,
+///   which calls `crate::invoke_with_options` with a `crate::InvokeOptions`
+/// - `offline_queue` / `offline_queue(capacity = <n>, timeout_ms = <ms>)`:
+///   while the Tauri transport is missing, calls wait for it (bounded, with
+///   a timeout) instead of failing; needs `tauri-bridge-runtime`
+/// - `rebind`: calls wait for `tauri_bridge_runtime::connection::bridge_ready()`
+///   and are made again when the connection is reported gone while they're
+///   in flight; the `_with_options` variant isn't rebound
+/// - `circuit_breaker` / `circuit_breaker(threshold = <n>, cooldown_ms = <ms>)`:
+///   after `threshold` consecutive failures (default 5), calls fail fast with
+///   `BreakerError::Open` until `cooldown_ms` (default 10s) elapsed; the
+///   `try_` functions return `Result<T, BreakerError>`; needs
+///   `tauri-bridge-runtime`
+/// - `priority = "low" | "normal"`: low-priority calls are queued on the
+///   client and only sent while no `normal` call is in flight; needs
+///   `tauri-bridge-runtime`
+/// - `incremental`: the backend sends partial results over its
+///   `tauri::ipc::Channel<T>` parameter; the client creates the channel with
+///   `crate::batch_channel` and gains `try_<name>_incremental(.., on_batch)`
+/// - `chunked` / `chunked(chunk_size = <bytes>)`: responses whose JSON is
+///   larger than `chunk_size` (default 1 MiB) are sent in chunks over a
+///   channel and reassembled by the client; needs `serde_json` on the
+///   backend and `tauri-bridge-runtime` plus `crate::batch_channel` on the
+///   client
+/// - `compress = "gzip"`, optionally with `min_size = <bytes>`: arguments
+///   and responses are sent as JSON payloads, gzipped from `min_size`
+///   (default 64 KiB) up; needs `serde_json` and `tauri-bridge-runtime` with
+///   the `gzip` feature on both sides
+/// - `signed`: the client passes the arguments through the app's
+///   `tauri_bridge_runtime::signing` signer before invoking and the response
+///   through its verifier before decoding, failing while either is missing;
+///   needs `serde_json`, `js-sys` and `tauri-bridge-runtime` with the
+///   `signing` feature on the client
+/// - `encrypt`: arguments and responses are sent as JSON sealed with
+///   ChaCha20-Poly1305 under the key of the app's
+///   `tauri_bridge_runtime::encrypt` provider, failing while it's missing;
+///   needs `serde_json` and `tauri-bridge-runtime` with the `encrypt`
+///   feature on both sides
+/// - `raw`: the response is sent as a `tauri::ipc::Response`, skipping JSON;
+///   on by default for `Vec<u8>` (or `Result<Vec<u8>, E>`) responses, other
+///   types must convert to and from `Vec<u8>`; the client needs `js-sys`
+/// - `json_bytes`: send `Vec<u8>` responses as JSON arrays of numbers, as
+///   before raw byte responses
+/// - `max_payload = "1MB"`: the largest JSON size of the arguments, in `B`,
+///   `KB`, `MB` or `GB` (powers of 1024) or plain bytes; the client fails
+///   with `PayloadError::TooLarge` from `tauri-bridge-runtime` without
+///   invoking. Client-side only, the backend is unchanged; the client needs
+///   `tauri-bridge-runtime` with the `payload` feature
+/// - `verify_schema`: the client sends a hash of the command's parameters
+///   and response, and the backend rejects calls from clients built against
+///   another version of the command with a `SchemaMismatch` message from
+///   `tauri_bridge_runtime::introspect`
+/// - `platforms("desktop", ..)`: only compile the backend for these platforms
+///   (`desktop`, `mobile`, `windows`, `macos`, `linux`, `ios`, `android`); on
+///   the others the client fails with `PlatformError::Unsupported` from
+///   `tauri-bridge-runtime` without invoking
+/// - `detect_tauri` or `detect_tauri(fallback = function)`: outside of Tauri,
+///   e.g. in a web preview, the `try_` functions fail with
+///   `BridgeError::NotInTauri` from `tauri-bridge-runtime` (or the `Failed`
+///   variant of another option's error) without invoking, or call `function`,
+///   which has the signature of `crate::invoke`, instead
+/// - `demo = "function"`: outside of Tauri, the client returns what
+///   `function`, taking the client's arguments, returns for them instead of
+///   invoking; `tauri_bridge_runtime::demo` keeps its state in `localStorage`
+/// - `replayable`: client calls are recorded while
+///   `tauri_bridge_runtime::replay` records, and answered with the recorded
+///   responses instead of invoking while it replays; needs `js-sys` and
+///   `tauri-bridge-runtime` with the `replay` feature, and JSON responses
+/// - `sidecar = "name"`: run the body in the sidecar process registered
+///   under `name` with the managed `tauri_bridge_runtime::sidecar::SidecarHost`;
+///   the app's command forwards the JSON arguments and relays the response,
+///   and the sidecar binary serves the body with [`bridge_dispatch!`]; needs
+///   `serde_json` and `tauri-bridge-runtime` with the `sidecar` feature
+/// - `transaction` or `transaction(undo = function)`: the command can run
+///   as a step of [`bridge_transaction!`], recorded on the client with
+///   `<name>_step(..)`; once a later step fails, the async `function` is
+///   awaited with the step's response to undo it
+/// - `selfcheck`: in debug builds, samples of the argument and response
+///   types (their `Default`) can be passed through both sides at startup
+///   with [`bridge_selfcheck!`]
+/// - `validate_response = "function"`: the client calls `function` with a
+///   reference to the response (the `Ok` side of a `Result`) and fails
+///   with `tauri_bridge_runtime::validate::ResponseError::Invalid` when it
+///   returns an error
+/// - `test_harness`: under `cfg(test)`, also generate a dispatch function
+///   calling the backend with JSON arguments like Tauri, for
+///   [`bridge_dispatch!`] and [`bridge_bench!`]; needs `serde_json` in tests,
+///   and the `bench`, `fuzz` or `remote` feature outside of them
+/// - `proptest`: under `cfg(test)`, generate property tests checking that
+///   the arguments and response survive a `serde_json` round trip; needs the
+///   `proptest` feature, plus `proptest` and `serde_json` in tests
+/// - `feature = "<name>"`: only compile the command with this Cargo feature
+///   enabled; [`bridge_handler!`] leaves it out of the handler otherwise
+/// - `log_deprecated`: print a notice on the backend whenever a
+///   `#[deprecated]` command is invoked (the attribute itself is always
+///   forwarded to the client functions)
+/// - `layers`: run the backend between the hooks of the
+///   `tauri_bridge_runtime::layer::Layers` the app manages, which may reject
+///   the call; can't be combined with options generating their own command
+///   signature (`chunked`, `compress`, `by_value`, `raw` byte responses,
+///   `non_send`, `test_harness`)
+/// - `serialize_on = "argument"`: the backend runs calls with the same
+///   value of `argument` one after the other, in arrival order, e.g. writes
+///   to one project; the argument must be `Hash`, and the command `async`
+///   so calls wait without blocking
+/// - `non_send`: run an async backend on a blocking thread, so its future
+///   doesn't need to be `Send` (e.g. holding an `Rc` across an `.await`);
+///   the arguments must be owned
+/// - `into_error = Type`: send errors as `Type`, converted with `Into`; the
+///   function returns any `Result` (e.g. `io::Result<T>`) and both sides
+///   see `Result<T, Type>`
+/// - `map_err = "Type::function"`: like `into_error`, converting with the
+///   given function of the error type
+/// - `namespace = "users"`: prefix the wire name, as in `users.get`;
+///   dot-separated identifiers
+/// - `alias = "old_name"`: also serve the command under another wire name,
+///   e.g. its name before a rename; may be repeated. The alias commands are
+///   registered by `bridge_handler!`
+/// - `alias_client`: also generate `#[deprecated]` client functions named
+///   after the aliases, calling the current name
+/// - `raw_response`: client functions return the response as a
+///   `wasm_bindgen::JsValue` instead of deserializing it, e.g. to hand it to
+///   a JS library; `js_value` is accepted as an alias
+/// - `cached`: the plain client functions keep responses in the
+///   `tauri_bridge_runtime::query` cache (feature `query`), keyed by the wire
+///   name exported as `<NAME>_QUERY_KEY` and the arguments;
+///   `prefetch_<name>(..)` fills the cache without returning the response,
+///   also for [`bridge_prefetch_all!`]; with `cached(swr)`, cached
+///   responses are returned right away and refreshed on a task started
+///   with `crate::spawn_local`, and `on_<name>_refreshed(callback)` gets
+///   each refreshed response; with `cached(persist)` (feature `persist`),
+///   responses are also kept in `localStorage`, versioned by the command's
+///   schema hash, and restored after a reload
+/// - `invalidates("key", ..)`: drop the cached responses of these query keys
+///   after each successful call and notify their `query::subscribe`rs; calls
+///   returning `Err` or panicking with `catch_panic` leave them cached
+/// - `sync_cache = "startup"`: for commands without parameters;
+///   `init_<name>()` fetches the response once into
+///   `tauri_bridge_runtime::startup`, and the plain `<name>()` is a
+///   synchronous function returning a clone of it, panicking before the
+///   init function was awaited
+/// - `bootstrap`: for `cached` and `sync_cache = "startup"` commands without
+///   parameters; the response is fetched together with those of the other
+///   commands listed in [`bridge_bootstrap!`]
+/// - `unwrap_envelope`: client functions decode a response envelope such as
+///   `tauri_bridge_runtime::envelope::ApiResponse<T>` and return its
+///   `Result` through the runtime's `Envelope` trait
+/// - `rejections`: invoke through `crate::try_invoke` (declared with
+///   `#[wasm_bindgen(catch)]`, returning `Result<JsValue, JsValue>`) and
+///   decode a rejected invoke as the error of a `Result` command, or fail
+///   with its message
+/// - `translate_errors`: `try_` functions hand the message of a failed
+///   call to the translator set with
+///   `tauri_bridge_runtime::messages::set_translator`, which can replace it
+///   with a user-facing one, also before the plain function panics with it
+/// - `catch_panic`: catch panics in the backend and report them to the
+///   client as `tauri_bridge_runtime::panic::BridgeError::Panicked`, the
+///   error type of the `try_` functions; the Rust function then returns
+///   `Caught<T>`
+/// - `debug_log`: in debug builds, log each call's arguments, response and
+///   duration (client: browser console via `tauri-bridge-runtime`, backend:
+///   stderr); the args and response types need `Debug`
+/// - `log_slow`: report client calls slower than the threshold of
+///   `tauri_bridge_runtime::slow` (200 ms unless set), with the command, the
+///   duration and the JSON size of the arguments and response
+/// - `metrics`: count the backend's calls and errors and record their
+///   durations in the `tauri_bridge_runtime::metrics` registry; see
+///   [`bridge_metrics_command!`]
+/// - `extern`: the command is implemented elsewhere (e.g. a separate backend
+///   crate); takes a body-less declaration and only generates the client
+///
+/// Crate-wide defaults for these can be set in `Cargo.toml`:
+///
+/// ```toml
+/// [package.metadata.tauri-bridge]
+/// backend_cfg = 'feature = "backend"'
+/// client_cfg = 'feature = "client"'
+/// strict = true
+/// ```
+///
+/// # Window routing
+///
+/// A `#[caller_label] name: String` parameter receives the label of the
+/// calling webview on the backend and is not part of the client signature.
+/// A `#[target_label] name: Option<String>` parameter adds a
+/// `try_<name>_to(webview_label, ..)` client function addressing a webview;
+/// the plain client functions send `None`. A parameter of type
+/// `tauri_bridge_runtime::caller::CallerContext` receives the calling
+/// webview's label and URL instead of a `#[caller_label]`.
+///
+/// # Locked state
+///
+/// A `tauri_bridge_runtime::state::BridgeState<T>` parameter of a sync
+/// command is filled from the managed `BridgeState<T>`, a `Mutex<T>`,
+/// locked until the body returns; the body gets `&mut T`, and the client
+/// signature leaves it out. Other paths to a `BridgeState` aren't touched,
+/// but the bare name is reserved for this one.
+///
+/// # Sessions
+///
+/// A `#[session] name: T` parameter receives the session the managed
+/// `tauri_bridge_runtime::session::SessionValidator<T>` returns for the
+/// token the client sends, and is not part of the client signature; calls
+/// without a valid token are rejected before the function runs.
+///
+/// # Parameter defaults
+///
+/// Trailing parameters marked `#[bridge(default)]` or
+/// `#[bridge(default = "expr")]` add `try_<name>_with_defaults` and
+/// `<name>_with_defaults` client functions leaving them out, which send
+/// `Default::default()` or the expression instead. Their types must be owned.
+///
+/// # Map entries
+///
+/// A `HashMap` or `BTreeMap` parameter marked `#[bridge(map_as = "entries")]`
+/// is sent as an array of `[key, value]` pairs instead of a JSON object, so
+/// integer and other non-string keys arrive intact. The backend command
+/// takes it as a `tauri_bridge_runtime::entries::Entries<..>` and passes the
+/// map on to the function.
+///
+/// # Tagged enums
+///
+/// Tagged and untagged enums and structs with `#[serde(flatten)]` fields
+/// serialize through maps or leave `None` content undefined, which the
+/// default `serde_wasm_bindgen` settings turn into values Tauri's JSON IPC
+/// drops. The structs and enums in the crate's `src` directory are looked
+/// up by name: commands whose parameters reach such a type are sent with
+/// `json_compatible`, and internally tagged enums with a newtype variant
+/// serde can't tag, like `Renamed(String)`, are rejected. Types of other
+/// crates aren't seen; set `json_compatible` for those.
+///
+/// # anyhow and eyre results
+///
+/// Commands returning `anyhow::Result<T>`, `eyre::Result<T>` or a `Result`
+/// with their error type send the error as a
+/// `tauri_bridge_runtime::error::ErrorReport` (message and source chain);
+/// both the backend function and the client return
+/// `Result<T, ErrorReport>`. The crate path must be spelled out.
+///
+/// # Services
+///
+/// On an inherent impl block, each `pub` method taking `&self` becomes a
+/// command named after it. Its backend looks the service up as
+/// `tauri::State<Self>`, so register it with `.manage()`. The options apply
+/// to every method, except `rename` and `alias`, which name a single
+/// command. The impl block itself is only compiled into the backend.
+/// Generic impl blocks name the type each parameter is registered with,
+/// e.g. `concrete(S = SqliteStore)` on `impl<S: Store> Notes<S>`.
+///
+/// # Modules
+///
+/// On an inline module, each `pub` function becomes a command namespaced by
+/// the module path, as in `users.get`, and its client is generated inside
+/// the module as well. Functions and impl blocks with their own
+/// `#[tauri_bridge(..)]` are bridged with the module's options and theirs;
+/// `namespace` on the module is put in front of the module path.
+///
+/// # Manifest
+///
+/// Each command is also recorded in a JSON manifest (`bridge-manifest.json`)
+/// and a Markdown API reference (`bridge-api.md`), written to
+/// `manifest_dir` from the metadata table, or to `$OUT_DIR/tauri-bridge` for
+/// crates with a build script. With `ts_dir`
+/// set, a TypeScript module (`ts_client = "plain"` or `"react-query"`) is
+/// written there as well, validating with Zod or valibot schemas given
+/// `ts_schemas = "zod"` or `"valibot"`.
+#[proc_macro_attribute]
+pub fn tauri_bridge(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand(attr.into(), item.into()).into()
+}
+
+/// Generate chunked file read/write commands and client helpers.
+///
+/// The annotated function resolves the path requested by the client (and
+/// should reject anything outside the allowed directories). This generates
+/// the `<name>_read` and `<name>_write` backend commands, to be registered
+/// like any other command, and the `try_<name>_read(path, on_progress)` and
+/// `try_<name>_write(path, data, on_progress)` client helpers.
+///
+/// Options: `chunk_size = <bytes>` (default 256 KiB), `backend_cfg(...)`,
+/// `client_cfg(...)`. Both sides need `tauri-bridge-runtime` with the
+/// `files` feature.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[bridge_file(chunk_size = 65536)]
+/// pub fn project_file(path: &str) -> Result<PathBuf, String> {
+///     let path = project_dir().join(path);
+///     path.starts_with(project_dir())
+///         .then_some(path)
+///         .ok_or_else(|| "path outside the project".to_string())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn bridge_file(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_file(attr.into(), item.into()).into()
+}
+
+/// Generate load/save commands and a client form handle for a struct.
+///
+/// `load` and `save` name backend functions, `fn() -> Result<T, E>` and
+/// `fn(T) -> Result<(), E>` with `E: Display`, which read and store the
+/// struct. This generates the `load_<name>` and `save_<name>` backend
+/// commands, to be registered like any other command, and a
+/// `<Struct>Form` client handle:
+///
+/// - `load()` / `reload()`: fetch the stored value
+/// - `<field>()` / `set_<field>(value)`: read or change a field
+/// - `is_dirty()`: whether a field changed since the last load or save
+/// - `save()`: store the whole struct with one invoke
+///
+/// The struct derives `Serialize` and `Deserialize` (don't derive them
+/// yourself) and needs named fields. Options: `backend_cfg(...)`,
+/// `client_cfg(...)`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[bridge_form(load = read_settings, save = write_settings)]
+/// pub struct Settings {
+///     pub theme: String,
+///     pub font_size: u32,
+/// }
+///
+/// // Frontend
+/// let mut form = SettingsForm::load().await?;
+/// form.set_font_size(16);
+/// if form.is_dirty() {
+///     form.save().await?;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn bridge_form(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_form(attr.into(), item.into()).into()
+}
+
+/// Make an error enum shared by the backend and the client.
+///
+/// The enum derives `Serialize` and `Deserialize` (don't derive them
+/// yourself) and is sent as `{"code": "<code>", "details": <fields>}`.
+/// Each variant's code is its snake_case name, or the one set with
+/// `#[bridge_error(code = "..")]`; codes must be unique. The enum also gets:
+///
+/// - `code()`: the code of the variant
+/// - `CODES`: every code, in declaration order
+/// - `is_<variant>()`: whether it's that variant, e.g. `is_not_found()`
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[bridge_error]
+/// #[derive(Debug, thiserror::Error)]
+/// pub enum NoteError {
+///     #[error("note {0} not found")]
+///     NotFound(u64),
+///     #[bridge_error(code = "E_LOCKED")]
+///     #[error("note is locked by {by}")]
+///     Locked { by: String },
+/// }
+///
+/// #[tauri_bridge]
+/// pub fn open_note(id: u64) -> Result<Note, NoteError> { /* ... */ }
+/// ```
 #[proc_macro_attribute]
-pub fn tauri_bridge(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
+pub fn bridge_error(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_error(attr.into(), item.into()).into()
+}
+
+/// Bridge the metrics registry as the `get_bridge_metrics` command.
+///
+/// Commands generated with `metrics` (or `metrics = true` in the crate's
+/// `[package.metadata.tauri-bridge]`) record their calls in
+/// `tauri_bridge_runtime::metrics`. This expands to a `#[tauri_bridge]`
+/// command returning `tauri_bridge_runtime::metrics::bridge_metrics()`, a
+/// `MetricsSnapshot` with the calls, errors and a duration histogram per
+/// command. The input is passed on as `#[tauri_bridge]` options.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// bridge_metrics_command!(backend_cfg(feature = "diagnostics"));
+///
+/// // Frontend
+/// let snapshot = try_get_bridge_metrics().await?;
+/// ```
+#[proc_macro]
+pub fn bridge_metrics_command(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_metrics_command(input.into()).into()
+}
+
+/// Include the client bindings of commands into the current module.
+///
+/// Used together with `#[tauri_bridge(client_mod = ...)]` so the client
+/// functions live in a different module (or crate) than the backend ones.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // src/api.rs
+/// #[tauri_bridge(client_mod = crate::generated)]
+/// pub fn greet(name: &str) -> String {
+///     format!("Hello, {}!", name)
+/// }
+///
+/// // src/generated.rs
+/// tauri_bridge::bridge_include!(pub crate::api::greet);
+/// ```
+#[proc_macro]
+pub fn bridge_include(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_include(input.into()).into()
+}
+
+/// Build an OpenRPC document describing the listed commands.
+///
+/// Expands to an expression evaluating to the document as a JSON `String`.
+/// Entries may carry `#[cfg(..)]` attributes for conditionally compiled
+/// commands.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// pub fn bridge_spec() -> String {
+///     tauri_bridge::bridge_spec![greet, api::get_user]
+/// }
+/// ```
+#[proc_macro]
+pub fn bridge_spec(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_spec(input.into()).into()
+}
+
+/// Map the listed commands to their versions.
+///
+/// Expands to a `BTreeMap<&'static str, u32>` keyed by base command name
+/// (unversioned commands report version 1). Compare the backend's map with
+/// the frontend's during startup to detect a stale bundle. Entries may
+/// carry `#[cfg(..)]` attributes like in [`bridge_spec!`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// pub fn bridge_version_map() -> BTreeMap<&'static str, u32> {
+///     tauri_bridge::bridge_version_map![greet, api::get_user]
+/// }
+/// ```
+#[proc_macro]
+pub fn bridge_version_map(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_version_map(input.into()).into()
+}
+
+/// Generate a startup handshake checking that both sides were built from
+/// the same commands.
+///
+/// Expands to:
+///
+/// - `bridge_manifest()`: the `tauri_bridge_runtime::introspect::Manifest`
+///   of the listed commands, with their versions and schema hashes
+/// - on the backend, the `__bridge_introspect` command returning it, to be
+///   registered like any other command
+/// - on the client, `check_bridge()`, which fetches the backend's manifest
+///   and fails with the missing and changed commands
+///
+/// Invoke it in a module both sides compile. `backend_cfg(..)` and
+/// `client_cfg(..)` may come first, followed by `;`. Entries may carry
+/// `#[cfg(..)]` attributes like in [`bridge_spec!`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// tauri_bridge::bridge_introspect![greet, api::get_user];
+///
+/// // Backend
+/// tauri::generate_handler![greet, api::get_user, __bridge_introspect]
+///
+/// // Frontend, at startup
+/// if let Err(message) = check_bridge().await {
+///     show_fatal_error(&format!("Please reload the app: {}", message));
+/// }
+/// ```
+#[proc_macro]
+pub fn bridge_introspect(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_introspect(input.into()).into()
+}
+
+/// Run calls of the listed `transaction` commands as one invoke, all or
+/// nothing.
+///
+/// Expands to:
+///
+/// - on the backend, the `__bridge_transaction` command, to be registered
+///   like any other command; it runs the steps in order and, once one
+///   fails, awaits the `undo` hooks of those that ran in reverse
+/// - on the client, `commit_bridge_transaction(transaction)`, which sends
+///   the calls recorded in a `tauri_bridge_runtime::transaction::Transaction`
+///   and returns their responses, or `TransactionError::RolledBack` with the
+///   failed step
+///
+/// Invoke it in a module both sides compile; it takes the same input as
+/// [`bridge_introspect!`]. Both sides need `serde_json` and
+/// `tauri-bridge-runtime` with the `transaction` feature, and the client
+/// needs `js-sys`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[tauri_bridge(transaction(undo = delete_note))]
+/// pub async fn create_note(title: String) -> Result<NoteId, String> {
+///     // ...
+/// }
+///
+/// tauri_bridge::bridge_transaction![create_note, tag_note];
+///
+/// // Frontend
+/// let mut transaction = Transaction::new();
+/// let note = transaction.add(create_note_step("Groceries".to_string()));
+/// transaction.add(tag_note_step(7, "home".to_string()));
+/// let note_id = commit_bridge_transaction(transaction).await?.take(note)?;
+/// ```
+#[proc_macro]
+pub fn bridge_transaction(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_transaction(input.into()).into()
+}
+
+/// Check at startup that the listed `selfcheck` commands' arguments and
+/// responses survive the trip between client and backend.
+///
+/// Expands to:
+///
+/// - on the backend, the `__bridge_selfcheck` command, to be registered
+///   like any other command
+/// - on the client, `run_bridge_selfcheck()`, which fetches the `Default`
+///   of each command's response from the backend and decodes it like the
+///   command's client does, then sends it back with the `Default` of the
+///   arguments serialized like the command sends them. The backend decodes
+///   the arguments like Tauri does, and every value that didn't come back
+///   the same is a `Mismatch` in the returned
+///   `tauri_bridge_runtime::selfcheck::Report`
+///
+/// The checks only run in debug builds; release builds keep both items,
+/// which check nothing. The error types of `Result`s aren't sampled.
+///
+/// Invoke it in a module both sides compile; it takes the same input as
+/// [`bridge_introspect!`]. The sampled types need `Default`, and response
+/// types `Serialize` on the client too. Both sides need
+/// `tauri-bridge-runtime` with the `selfcheck` feature.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[tauri_bridge(selfcheck)]
+/// pub fn save_note(note: Note, tags: &[String]) -> Result<NoteId, String> {
+///     // ...
+/// }
+///
+/// tauri_bridge::bridge_selfcheck![save_note, list_notes];
+///
+/// // Frontend, at startup
+/// let report = run_bridge_selfcheck().await?;
+/// if !report.is_ok() {
+///     web_sys::console::warn_1(&report.to_string().into());
+/// }
+/// ```
+#[proc_macro]
+pub fn bridge_selfcheck(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_selfcheck(input.into()).into()
+}
+
+/// Fetch the responses of the listed `bootstrap` commands at startup in
+/// one invoke.
+///
+/// Expands to:
+///
+/// - on the backend, the `__bridge_bootstrap` command, to be registered
+///   like any other command. It calls the listed commands in order and
+///   responds with all of their responses
+/// - on the client, `bridge_bootstrap()`, which keeps each response where
+///   the command's client reads it: the query cache for `cached` commands,
+///   so their first read doesn't invoke, and the startup value for
+///   `sync_cache = "startup"` commands, as their `init_<name>()` would. It
+///   returns a `tauri_bridge_runtime::bootstrap::Report` naming the
+///   commands that failed; their reads invoke as usual
+///
+/// Invoke it in a module both sides compile; it takes the same input as
+/// [`bridge_introspect!`]. Both sides need `tauri-bridge-runtime` with the
+/// `bootstrap` feature, and the client needs `js-sys`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[tauri_bridge(bootstrap, cached)]
+/// pub fn list_projects() -> Vec<Project> {
+///     // ...
+/// }
+///
+/// #[tauri_bridge(bootstrap, sync_cache = "startup")]
+/// pub fn get_app_version() -> String {
+///     // ...
+/// }
+///
+/// tauri_bridge::bridge_bootstrap![list_projects, get_app_version];
+///
+/// // Frontend, before mounting the app
+/// let report = bridge_bootstrap().await?;
+/// let version = get_app_version();
+/// ```
+#[proc_macro]
+pub fn bridge_bootstrap(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_bootstrap(input.into()).into()
+}
+
+/// Fill the query cache with the responses of the listed `cached`
+/// commands, e.g. at startup before the first navigation.
+///
+/// Each entry calls the command's `prefetch_<name>` client function, with
+/// the arguments in parentheses or none when only named. Expands to a
+/// future running the prefetches concurrently; errors are ignored, so a
+/// read whose prefetch failed invokes as usual. Entries may carry
+/// `#[cfg(..)]` attributes like in [`bridge_spec!`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// tauri_bridge::bridge_prefetch_all![
+///     list_projects,
+///     api::get_user(user_id),
+///     #[cfg(feature = "admin")]
+///     api::list_audits(0, 50),
+/// ]
+/// .await;
+/// ```
+#[proc_macro]
+pub fn bridge_prefetch_all(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_prefetch_all(input.into()).into()
+}
 
-    let backend_code = generate_backend(&input);
-    let client_code = generate_client(&input);
+/// Call the listed commands concurrently and return their responses as a
+/// tuple.
+///
+/// Each entry calls the command's `try_<name>` client function, with the
+/// arguments in parentheses or none when only named, like in
+/// [`bridge_prefetch_all!`]. Expands to a future resolving to
+/// `Result<(T1, T2, ..), tauri_bridge_runtime::join::JoinError>` once every
+/// call is done. Commands returning a `Result` respond with it, as their
+/// `try_` functions do; the error is the first failed call's in the listed order,
+/// with the command's name and the error's `Display` message, so the error
+/// types of the commands need `Display`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (user, settings, projects) =
+///     tauri_bridge::bridge_join!(get_user(1), get_settings(), list_projects()).await?;
+/// ```
+#[proc_macro]
+pub fn bridge_join(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_join(input.into()).into()
+}
 
-    let call_site = Span::call_site();
+/// Dispatch JSON invokes to the listed `test_harness` commands.
+///
+/// Expands to an async function taking the command name and its JSON
+/// arguments, and returning the JSON response or the error the invoke is
+/// rejected with. Back the client's `invoke` with it in tests to run
+/// natively compiled client functions against the real backend, or on a
+/// server answering `tauri_bridge_runtime::remote` frames with the `remote`
+/// feature. Entries may carry `#[cfg(..)]` attributes like in
+/// [`bridge_spec!`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[cfg(test)]
+/// pub async fn invoke(command: &str, args: JsValue) -> JsValue {
+///     let dispatch = tauri_bridge::bridge_dispatch![api::greet, api::add];
+///     JsValue(dispatch(command, args.0).await.unwrap())
+/// }
+/// ```
+#[proc_macro]
+pub fn bridge_dispatch(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_dispatch(input.into()).into()
+}
 
-    let expanded = quote_spanned! {call_site=>
-        #backend_code
-        #client_code
-    };
+/// Time the listed `test_harness` commands through their dispatch
+/// functions.
+///
+/// Takes the number of calls per command, then each command with an
+/// expression evaluating to its JSON arguments. Expands to a future of
+/// `Result<Vec<tauri_bridge_runtime::bench::BenchReport>, serde_json::Value>`,
+/// which fails with the error of the first rejected call. Outside of tests,
+/// the dispatch functions need the `bench` feature. Entries may carry
+/// `#[cfg(..)]` attributes like in [`bridge_spec!`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // benches/ipc.rs
+/// let reports = tauri_bridge::bridge_bench!(1000;
+///     app::greet => serde_json::json!({ "name": "Ferris" }),
+///     app::search => serde_json::json!({ "query": "tauri", "limit": 50 }),
+/// )
+/// .await
+/// .unwrap();
+/// for report in reports {
+///     println!("{}", report);
+/// }
+/// ```
+#[proc_macro]
+pub fn bridge_bench(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_bench(input.into()).into()
+}
+
+/// Check the listed `test_harness` commands against the calls of a recorded
+/// session.
+///
+/// Takes an expression evaluating to a
+/// `tauri_bridge_runtime::replay::Recording`, e.g. one recorded from
+/// `replayable` commands and kept as a fixture, then the commands. Each
+/// recorded call is sent with its arguments through the command's dispatch
+/// function, and breaks the contract when it's rejected or its response no
+/// longer has the shape of the recorded one: other JSON types, or missing
+/// fields. Expands to a future of
+/// `Result<usize, tauri_bridge_runtime::contract::ContractError>`, with the
+/// number of calls checked or every violation. Needs `serde_json` and
+/// `tauri-bridge-runtime` with the `contract` feature. Entries may carry
+/// `#[cfg(..)]` attributes like in [`bridge_spec!`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[tokio::test]
+/// async fn test_frontend_contract() {
+///     let recording = Recording::from_json(include_str!("fixtures/session.json")).unwrap();
+///     tauri_bridge::bridge_contract!(recording; app::greet, app::list_notes)
+///         .await
+///         .unwrap();
+/// }
+/// ```
+#[proc_macro]
+pub fn bridge_contract(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_contract(input.into()).into()
+}
+
+/// Fuzz the argument decoding and bodies of the listed commands.
+///
+/// Expands to a `fn(&[u8])` for a `cargo-fuzz` target: the first byte of
+/// the input picks the command, and the rest is sent as its JSON
+/// arguments through the command's dispatch function. Rejected calls are
+/// ignored, so only panics are reported. The `fuzz` feature gives every
+/// command that could take `test_harness` a dispatch function; the target
+/// needs `serde_json` and `tauri-bridge-runtime` with the `fuzz` feature.
+/// Entries may carry `#[cfg(..)]` attributes like in [`bridge_spec!`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // fuzz/fuzz_targets/commands.rs
+/// #![no_main]
+///
+/// libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+///     tauri_bridge::bridge_fuzz![my_app::greet, my_app::import_notes](data)
+/// });
+/// ```
+#[proc_macro]
+pub fn bridge_fuzz(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_fuzz(input.into()).into()
+}
+
+/// Build the invoke handler of the listed commands.
+///
+/// Expands to `tauri::generate_handler!` with the listed commands, leaving
+/// out those whose `feature` is disabled. Entries may carry `#[cfg(..)]`
+/// attributes like in [`bridge_spec!`]; those are passed on as they are.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// tauri::Builder::default()
+///     .invoke_handler(tauri_bridge::bridge_handler![greet, api::search])
+/// ```
+#[proc_macro]
+pub fn bridge_handler(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_handler(input.into()).into()
+}
 
-    TokenStream::from(expanded)
+#[doc(hidden)]
+#[proc_macro]
+pub fn __bridge_handler_step(input: TokenStream) -> TokenStream {
+    tauri_bridge_core::expand_handler_step(input.into()).into()
 }
@@ -6,6 +6,8 @@
 
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::VecDeque;
+use tauri_bridge::{bridge_error, bridge_file, bridge_form, tauri_bridge};
 
 #[derive(Debug, Clone)]
 pub struct InvokeCall {
@@ -16,6 +18,9 @@ pub struct InvokeCall {
 struct MockState {
     calls: Vec<InvokeCall>,
     next_response: Option<serde_json::Value>,
+    queued_responses: VecDeque<serde_json::Value>,
+    next_batches: Vec<serde_json::Value>,
+    next_rejection: Option<serde_json::Value>,
 }
 
 impl MockState {
@@ -23,6 +28,9 @@ impl MockState {
         Self {
             calls: Vec::new(),
             next_response: None,
+            queued_responses: VecDeque::new(),
+            next_batches: Vec::new(),
+            next_rejection: None,
         }
     }
 }
@@ -38,6 +46,36 @@ fn set_mock_response<T: Serialize>(value: T) {
     });
 }
 
+/// Responses for the next invokes, in order
+fn set_mock_responses<T: Serialize>(values: Vec<T>) {
+    let values = values
+        .into_iter()
+        .map(|value| serde_json::to_value(value).unwrap())
+        .collect();
+    MOCK_STATE.with(|state| {
+        state.borrow_mut().queued_responses = values;
+    });
+}
+
+/// Rejects the next `try_invoke` with `value`, like a command's `Err`
+fn set_mock_rejection<T: Serialize>(value: T) {
+    let json = serde_json::to_value(value).unwrap();
+    MOCK_STATE.with(|state| {
+        state.borrow_mut().next_rejection = Some(json);
+    });
+}
+
+/// Batches the next invoke sends over its channel before responding
+fn set_mock_batches<T: Serialize>(batches: Vec<T>) {
+    let batches = batches
+        .into_iter()
+        .map(|batch| serde_json::to_value(batch).unwrap())
+        .collect();
+    MOCK_STATE.with(|state| {
+        state.borrow_mut().next_batches = batches;
+    });
+}
+
 fn get_invoke_calls() -> Vec<InvokeCall> {
     MOCK_STATE.with(|state| state.borrow().calls.clone())
 }
@@ -47,6 +85,9 @@ fn clear_mock_state() {
         let mut s = state.borrow_mut();
         s.calls.clear();
         s.next_response = None;
+        s.queued_responses.clear();
+        s.next_batches.clear();
+        s.next_rejection = None;
     });
 }
 
@@ -79,6 +120,69 @@ mod serde_wasm_bindgen {
     pub fn from_value<T: for<'de> Deserialize<'de>>(value: JsValue) -> Result<T, String> {
         serde_json::from_value(value.0).map_err(|e| e.to_string())
     }
+
+    /// Embeds a `JsValue` as it is
+    pub mod preserve {
+        use super::*;
+
+        pub fn serialize<S: serde::Serializer>(
+            value: &JsValue,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            value.0.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<JsValue, D::Error> {
+            serde_json::Value::deserialize(deserializer).map(JsValue)
+        }
+    }
+}
+
+mod wasm_bindgen {
+    pub use super::JsValue;
+}
+
+// Dependencies renamed in the manifest, for `crate_paths`
+mod renamed_serde {
+    pub use serde::*;
+}
+
+mod renamed_swb {
+    pub use super::serde_wasm_bindgen::*;
+}
+
+mod js_sys {
+    use super::JsValue;
+
+    /// Stands in for the `ArrayBuffer` of a raw response: a JSON byte array
+    pub struct Uint8Array(Vec<u8>);
+
+    impl Uint8Array {
+        pub fn new(value: &JsValue) -> Self {
+            Self(serde_json::from_value(value.0.clone()).unwrap_or_default())
+        }
+
+        pub fn to_vec(&self) -> Vec<u8> {
+            self.0.clone()
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub mod JSON {
+        use super::JsValue;
+
+        pub fn stringify(value: &JsValue) -> Result<JsValue, JsValue> {
+            Ok(JsValue(serde_json::Value::String(value.0.to_string())))
+        }
+
+        pub fn parse(text: &str) -> Result<JsValue, JsValue> {
+            serde_json::from_str(text)
+                .map(JsValue)
+                .map_err(|e| JsValue(serde_json::Value::String(e.to_string())))
+        }
+    }
 }
 
 pub async fn invoke(command: &str, args: JsValue) -> JsValue {
@@ -87,14 +191,114 @@ pub async fn invoke(command: &str, args: JsValue) -> JsValue {
         args: args.0.clone(),
     };
 
-    MOCK_STATE.with(|state| {
+    let (response, batches) = MOCK_STATE.with(|state| {
         let mut s = state.borrow_mut();
         s.calls.push(call);
-        let response = s.next_response.take().unwrap_or(serde_json::Value::Null);
-        JsValue(response)
+        let response = s
+            .queued_responses
+            .pop_front()
+            .or_else(|| s.next_response.take())
+            .unwrap_or(serde_json::Value::Null);
+        (JsValue(response), std::mem::take(&mut s.next_batches))
+    });
+
+    // Deliver batches to the channel passed in the args, if any
+    let channel = args
+        .0
+        .as_object()
+        .into_iter()
+        .flat_map(|fields| fields.values())
+        .find_map(|value| {
+            value
+                .as_str()?
+                .strip_prefix("__CHANNEL__:")?
+                .parse::<usize>()
+                .ok()
+        });
+    if let Some(id) = channel {
+        CHANNELS.with(|channels| {
+            let mut channels = channels.borrow_mut();
+            for batch in batches {
+                (channels[id])(JsValue(batch));
+            }
+        });
+    }
+
+    response
+}
+
+/// Catch-aware `invoke`, returning a mocked rejection as `Err`
+pub async fn try_invoke(command: &str, args: JsValue) -> Result<JsValue, JsValue> {
+    let rejection = MOCK_STATE.with(|state| state.borrow_mut().next_rejection.take());
+    match rejection {
+        Some(rejection) => {
+            let _ = invoke(command, args).await;
+            Err(JsValue(rejection))
+        }
+        None => Ok(invoke(command, args).await),
+    }
+}
+
+type BatchHandler = Box<dyn FnMut(JsValue)>;
+
+thread_local! {
+    static CHANNELS: RefCell<Vec<BatchHandler>> = RefCell::new(Vec::new());
+}
+
+/// Registers the handler like `new Channel()` and returns its wire form
+pub fn batch_channel(on_message: impl FnMut(JsValue) + 'static) -> String {
+    CHANNELS.with(|channels| {
+        let mut channels = channels.borrow_mut();
+        channels.push(Box::new(on_message));
+        format!("__CHANNEL__:{}", channels.len() - 1)
     })
 }
 
+type Task = std::pin::Pin<Box<dyn std::future::Future<Output = ()>>>;
+
+thread_local! {
+    static SPAWNED: RefCell<Vec<Task>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Queues the task like `wasm_bindgen_futures::spawn_local`
+pub fn spawn_local<F: std::future::Future<Output = ()> + 'static>(future: F) {
+    SPAWNED.with(|spawned| spawned.borrow_mut().push(Box::pin(future)));
+}
+
+/// Runs the tasks queued by `spawn_local`, returning how many ran
+async fn run_spawned() -> usize {
+    let tasks = SPAWNED.with(|spawned| std::mem::take(&mut *spawned.borrow_mut()));
+    let count = tasks.len();
+    for task in tasks {
+        task.await;
+    }
+    count
+}
+
+/// Per-call options, recorded by the mock
+#[derive(Debug, Clone, Default)]
+pub struct InvokeOptions {
+    pub headers: Vec<(String, String)>,
+}
+
+thread_local! {
+    static LAST_OPTIONS: RefCell<Option<InvokeOptions>> = const { RefCell::new(None) };
+}
+
+pub async fn invoke_with_options(command: &str, args: JsValue, options: InvokeOptions) -> JsValue {
+    LAST_OPTIONS.with(|last| *last.borrow_mut() = Some(options));
+    invoke(command, args).await
+}
+
+pub async fn try_invoke_with_options(
+    command: &str,
+    args: JsValue,
+    options: InvokeOptions,
+) -> Result<JsValue, JsValue> {
+    LAST_OPTIONS.with(|last| *last.borrow_mut() = Some(options));
+    try_invoke(command, args).await
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct User {
     pub id: u64,
@@ -245,6 +449,585 @@ pub async fn call_process_items(items: Vec<String>) -> u64 {
     try_call_process_items(items).await.unwrap()
 }
 
+// Macro-generated client compiled natively via `client_cfg`, included into
+// another module via `client_mod` so it doesn't clash with the backend fn
+
+#[tauri_bridge(client_cfg(not(target_arch = "wasm32")), client_mod = crate::generated)]
+pub fn shout(message: &str) -> String {
+    message.to_uppercase()
+}
+
+pub mod generated {
+    tauri_bridge::bridge_include!(pub crate::shout);
+}
+
+// Declaration-only command: implemented in another backend crate
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn remote_status(user_id: u64) -> Status;
+
+#[tauri_bridge(extern, version = 2, client_cfg(not(target_arch = "wasm32")))]
+pub fn remote_lookup(query: String) -> Vec<u64>;
+
+// Calls waiting out a backend restart
+
+#[tauri_bridge(extern, rebind, client_cfg(not(target_arch = "wasm32")))]
+pub fn sync_drafts(limit: u32) -> u32;
+
+// Startup handshake over the remote commands
+
+tauri_bridge::bridge_introspect![
+    backend_cfg(any()),
+    client_cfg(not(target_arch = "wasm32"));
+    remote_status,
+    remote_lookup,
+];
+
+// Commands recorded into transactions
+
+#[tauri_bridge(extern, transaction, client_cfg(not(target_arch = "wasm32")))]
+pub fn open_ticket(title: &str) -> Result<u64, String>;
+
+#[tauri_bridge(extern, transaction, client_cfg(not(target_arch = "wasm32")))]
+pub fn assign_ticket(ticket: u64, assignee: String);
+
+tauri_bridge::bridge_transaction![
+    backend_cfg(any()),
+    client_cfg(not(target_arch = "wasm32"));
+    open_ticket,
+    assign_ticket,
+];
+
+// Commands whose samples are checked at startup
+
+#[tauri_bridge(extern, selfcheck, client_cfg(not(target_arch = "wasm32")))]
+pub fn file_report(title: &str, page_count: u32) -> Result<Vec<u64>, String>;
+
+tauri_bridge::bridge_selfcheck![
+    backend_cfg(any()),
+    client_cfg(not(target_arch = "wasm32"));
+    file_report,
+];
+
+// Startup values fetched in one invoke
+
+#[tauri_bridge(extern, bootstrap, cached, client_cfg(not(target_arch = "wasm32")))]
+pub fn list_boards() -> Vec<String>;
+
+#[tauri_bridge(
+    extern,
+    bootstrap,
+    sync_cache = "startup",
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn get_board_limit() -> Result<u32, String>;
+
+#[tauri_bridge(extern, bootstrap, cached, client_cfg(not(target_arch = "wasm32")))]
+pub fn get_board_owner() -> String;
+
+tauri_bridge::bridge_bootstrap![
+    backend_cfg(any()),
+    client_cfg(not(target_arch = "wasm32"));
+    list_boards,
+    get_board_limit,
+    get_board_owner,
+];
+
+// Client taking owned parameters so futures don't borrow the caller
+
+#[tauri_bridge(extern, owned_params, client_cfg(not(target_arch = "wasm32")))]
+pub fn tag_user(label: &str, ids: &[u64], user: &User) -> String;
+
+// Client with an owned, exported args struct for building request queues
+
+#[tauri_bridge(extern, own_args, client_cfg(not(target_arch = "wasm32")))]
+pub fn rename_user(user_id: u64, name: &str) -> User;
+
+// Tuple responses
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn name_and_age(user_id: u64) -> (String, u32);
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn bounds() -> ((i32, i32), (i32, i32));
+
+// Alias and newtype responses
+
+pub type UserId = u64;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Token(String);
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn next_user_id() -> UserId;
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn issue_token(user_id: UserId) -> Token;
+
+// Generic functions
+
+/// A block of `N` bytes.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Block<const N: usize>(Vec<u8>);
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn pad_block<'b, const N: usize>(block: Block<N>, label: &'b str, fill: &u8) -> Block<N>;
+
+// Methods of a service managed as Tauri state
+
+pub struct Library;
+
+#[tauri_bridge(
+    namespace = "library",
+    backend_cfg(any()),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+impl Library {
+    pub fn lend(&self, title: &str, days: u32) -> bool {
+        unreachable!("{} {}", title, days)
+    }
+}
+
+// `&mut` arguments sent by value and written back
+
+#[tauri_bridge(extern, by_value, client_cfg(not(target_arch = "wasm32")))]
+pub fn scramble(bytes: &mut [u8], label: &mut String, seed: u8) -> u32;
+
+// Variant forwarding per-call invoke options
+
+#[tauri_bridge(extern, with_options, client_cfg(not(target_arch = "wasm32")))]
+pub fn upload(name: &str, data: Vec<u8>) -> u64;
+
+// Window routing: the caller label is injected on the backend, the target
+// label is only sent by the `_to` variant
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn open_panel(
+    #[caller_label] caller: String,
+    #[target_label] target: Option<String>,
+    panel: &str,
+) -> bool;
+
+// Telemetry call waiting for the transport while it's missing
+
+#[tauri_bridge(
+    extern,
+    offline_queue(capacity = 2),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn track_event(name: &str);
+
+// Sync call failing fast once the backend keeps failing
+
+#[tauri_bridge(
+    extern,
+    circuit_breaker(threshold = 2, cooldown_ms = 60_000),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn sync_now(force: bool) -> u64;
+
+// Prefetch queued behind interactive calls
+
+#[tauri_bridge(extern, priority = "low", client_cfg(not(target_arch = "wasm32")))]
+pub fn prefetch_page(page: u32);
+
+// Export returning its response in chunks when large
+
+#[tauri_bridge(extern, chunked, client_cfg(not(target_arch = "wasm32")))]
+pub fn export_log(since: u64) -> Vec<String>;
+
+// Export gzipping large payloads
+
+#[tauri_bridge(
+    extern,
+    compress = "gzip",
+    min_size = 64,
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn export_report(title: &str, rows: Vec<String>) -> Vec<String>;
+
+// Payloads signed and verified by the app for the isolation pattern
+
+#[tauri_bridge(extern, signed, client_cfg(not(target_arch = "wasm32")))]
+pub fn transfer_funds(to: &str, cents: u64) -> u64;
+
+// Payloads sealed under a key shared with the backend
+
+#[tauri_bridge(extern, encrypt, client_cfg(not(target_arch = "wasm32")))]
+pub fn unlock_vault(name: &str, pin: u32) -> Vec<String>;
+
+// Commands sending the session token for the backend to validate
+
+pub struct AccountSession;
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn close_account(#[session] session: AccountSession, reason: String) -> bool;
+
+// Raw identifier parameters are sent under their plain names
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn set_kind(r#type: &str, r#ref: u32) -> bool;
+
+// Keyword names keep their raw identifier, generated names use the plain one
+
+#[tauri_bridge(extern, with_options, client_cfg(not(target_arch = "wasm32")))]
+pub fn r#move(r#async: bool, to: &str) -> String;
+
+// Lint attributes also apply to the client functions
+
+#[tauri_bridge(extern, client_attrs(allow), client_cfg(not(target_arch = "wasm32")))]
+#[allow(clippy::too_many_arguments)]
+pub fn draw_rect(x: f64, y: f64, width: f64, height: f64, r: u8, g: u8, b: u8, alpha: f64);
+
+// Generated names renamed around user items
+
+pub struct LookupArgs;
+
+pub fn try_lookup() -> &'static str {
+    "user item"
+}
+
+#[tauri_bridge(
+    extern,
+    client_names(try_fn = lookup_checked, args = LookupRequest),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn lookup(key: &str) -> String;
+
+// Calls logged in debug builds
+
+#[tauri_bridge(extern, debug_log, client_cfg(not(target_arch = "wasm32")))]
+pub fn find_users(name_prefix: &str, limit: u32) -> Result<Vec<User>, String>;
+
+#[tauri_bridge(extern, debug_log, client_cfg(not(target_arch = "wasm32")))]
+pub fn user_count() -> u64;
+
+// Commands responding with their panics
+
+#[tauri_bridge(extern, catch_panic, client_cfg(not(target_arch = "wasm32")))]
+pub fn parse_port(text: &str) -> u16;
+
+#[tauri_bridge(extern, catch_panic, client_cfg(not(target_arch = "wasm32")))]
+pub fn reset_cache();
+
+// Commands reporting slow calls
+
+#[tauri_bridge(extern, log_slow, client_cfg(not(target_arch = "wasm32")))]
+pub fn rebuild_index(full: bool) -> Vec<u32>;
+
+// Commands whose failure messages are translated
+
+#[tauri_bridge(extern, translate_errors, client_cfg(not(target_arch = "wasm32")))]
+pub fn get_locale_name(code: &str) -> String;
+
+// Commands returning anyhow results
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn read_setting(key: &str) -> anyhow::Result<u32>;
+
+// Commands failing with a shared error enum
+
+#[bridge_error]
+#[derive(Debug, PartialEq)]
+pub enum NoteError {
+    NotFound(u64),
+    #[bridge_error(code = "E_LOCKED")]
+    Locked {
+        by: String,
+    },
+}
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn open_note(id: u64) -> Result<String, NoteError>;
+
+// Commands converting their errors
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct AppError {
+    pub kind: String,
+}
+
+#[tauri_bridge(
+    extern,
+    into_error = AppError,
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn parse_limit(text: &str) -> Result<u32, std::num::ParseIntError>;
+
+// Commands whose errors reject the invoke
+
+#[tauri_bridge(extern, rejections, client_cfg(not(target_arch = "wasm32")))]
+pub fn archive_note(id: u64) -> Result<u64, NoteError>;
+
+#[tauri_bridge(
+    extern,
+    rejections,
+    with_options,
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn note_title(id: u64) -> String;
+
+// Commands handing the response to JS as is
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[tauri_bridge(
+    extern,
+    raw_response,
+    debug_log,
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn chart_series(range: u32) -> Vec<Point>;
+
+// Commands grouped in a namespace
+
+pub mod users {
+    use super::*;
+
+    #[tauri_bridge(extern, namespace = "users", client_cfg(not(target_arch = "wasm32")))]
+    pub fn get(id: u64) -> String;
+}
+
+// Commands still served under their names before a rename
+
+#[tauri_bridge(
+    extern,
+    alias = "fetch_note",
+    alias_client,
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn load_note(id: u64) -> String;
+
+// Commands with trailing default parameters
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn search_notes(
+    query: String,
+    #[bridge(default)] tags: Option<Vec<String>>,
+    #[bridge(default = "20")] limit: u32,
+) -> Vec<u64>;
+
+// Commands taking their strings through `Into`
+
+#[tauri_bridge(extern, into_params, client_cfg(not(target_arch = "wasm32")))]
+pub fn invite_member(name: String, roles: Vec<String>) -> u64;
+
+// Commands responding with an envelope
+
+#[tauri_bridge(extern, unwrap_envelope, client_cfg(not(target_arch = "wasm32")))]
+pub fn find_member(id: u64) -> tauri_bridge_runtime::envelope::ApiResponse<String>;
+
+// Cached reads and the mutations invalidating them
+
+#[tauri_bridge(extern, cached, client_cfg(not(target_arch = "wasm32")))]
+pub fn list_members(team: u32) -> Vec<String>;
+
+#[tauri_bridge(
+    extern,
+    invalidates("list_members"),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn add_member(team: u32, name: String) -> u64;
+
+#[tauri_bridge(
+    extern,
+    invalidates("list_members"),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn remove_member(team: u32, name: String) -> Result<(), String>;
+
+#[tauri_bridge(extern, cached(swr), client_cfg(not(target_arch = "wasm32")))]
+pub fn team_name(team: u32) -> String;
+
+#[tauri_bridge(extern, cached(persist), client_cfg(not(target_arch = "wasm32")))]
+pub fn team_motto(team: u32) -> String;
+
+// Const-like getters fetched once at startup
+
+#[tauri_bridge(
+    extern,
+    sync_cache = "startup",
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn get_build_channel() -> String;
+
+// Commands reaching serde under renamed paths
+
+#[tauri_bridge(
+    extern,
+    crate_paths(serde = "renamed_serde", serde_wasm_bindgen = "renamed_swb"),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn rename_label(id: u32, name: String) -> String;
+
+// Commands sending maps with integer keys as entries
+
+#[tauri_bridge(extern, map_as = "entries", client_cfg(not(target_arch = "wasm32")))]
+pub fn merge_scores(
+    #[bridge(map_as = "entries")] bonus: std::collections::HashMap<u32, u64>,
+    round: u32,
+) -> std::collections::BTreeMap<u32, u64>;
+
+// Commands sending NaN and infinities as strings
+
+#[tauri_bridge(extern, floats = "string", client_cfg(not(target_arch = "wasm32")))]
+pub fn smooth_readings(readings: Vec<f64>, window: u32) -> Vec<f64>;
+
+// Commands limited to some platforms
+
+#[tauri_bridge(
+    extern,
+    platforms("ios", "android"),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn scan_qr_code() -> String;
+
+#[tauri_bridge(extern, platforms("desktop"), client_cfg(not(target_arch = "wasm32")))]
+pub fn open_devtools(detached: bool);
+
+// Commands checking that they run inside Tauri
+
+#[tauri_bridge(extern, detect_tauri, client_cfg(not(target_arch = "wasm32")))]
+pub fn get_window_theme() -> String;
+
+#[tauri_bridge(
+    extern,
+    detect_tauri(fallback = crate::preview_invoke),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn get_app_version() -> String;
+
+// Commands answered by demo functions outside of Tauri
+
+#[tauri_bridge(
+    extern,
+    demo = "demo_impls::add_bookmark",
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn add_bookmark(url: String) -> Vec<String>;
+
+mod demo_impls {
+    pub fn add_bookmark(url: String) -> Vec<String> {
+        tauri_bridge_runtime::demo::update("bookmarks", |bookmarks: &mut Vec<String>| {
+            bookmarks.push(url);
+            bookmarks.clone()
+        })
+    }
+}
+
+// Commands whose calls can be recorded and replayed
+
+#[tauri_bridge(extern, replayable, client_cfg(not(target_arch = "wasm32")))]
+pub fn list_recent_files(limit: u32) -> Vec<String>;
+
+/// Stand-in for `invoke` in a web preview of the frontend
+pub async fn preview_invoke(command: &str, _args: JsValue) -> JsValue {
+    JsValue(serde_json::json!(format!("preview of {}", command)))
+}
+
+// Commands limiting the size of their arguments
+
+#[tauri_bridge(extern, max_payload = "64B", client_cfg(not(target_arch = "wasm32")))]
+pub fn post_comment(thread: u32, text: String) -> u64;
+
+// Commands sending their schema hash for the backend to verify
+
+#[tauri_bridge(extern, verify_schema, client_cfg(not(target_arch = "wasm32")))]
+pub fn archive_thread(thread: u32) -> bool;
+
+#[tauri_bridge(extern, verify_schema, client_cfg(not(target_arch = "wasm32")))]
+pub fn server_build() -> String;
+
+// Commands rejecting impossible responses on the client
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Stock {
+    pub sku: String,
+    pub count: i32,
+}
+
+fn check_stock(stock: &Stock) -> Result<(), String> {
+    if stock.count < 0 {
+        return Err(format!("negative count for {}", stock.sku));
+    }
+    Ok(())
+}
+
+fn check_page(page: &u32) -> Result<(), &'static str> {
+    if *page == 0 {
+        return Err("pages start at 1");
+    }
+    Ok(())
+}
+
+#[tauri_bridge(
+    extern,
+    validate_response = "check_stock",
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn get_stock(sku: &str) -> Result<Stock, String>;
+
+#[tauri_bridge(
+    extern,
+    validate_response = "check_page",
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn last_page() -> u32;
+
+// Raw byte responses
+
+#[tauri_bridge(extern, client_cfg(not(target_arch = "wasm32")))]
+pub fn thumbnail(id: u32) -> Vec<u8>;
+
+#[derive(Debug, PartialEq)]
+pub struct Blob(Vec<u8>);
+
+impl From<Vec<u8>> for Blob {
+    fn from(bytes: Vec<u8>) -> Self {
+        Blob(bytes)
+    }
+}
+
+#[tauri_bridge(extern, raw, client_cfg(not(target_arch = "wasm32")))]
+pub fn read_blob(key: &str) -> Result<Blob, String>;
+
+// File transfer in chunks of 4 bytes
+
+#[bridge_file(
+    chunk_size = 4,
+    backend_cfg(any()),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+pub fn project_file(path: &str) -> Result<std::path::PathBuf, String> {
+    Ok(std::path::PathBuf::from(path))
+}
+
+// Settings screen bound to one save command
+
+#[bridge_form(
+    load = read_settings,
+    save = write_settings,
+    backend_cfg(any()),
+    client_cfg(not(target_arch = "wasm32"))
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub theme: String,
+    pub font_size: u32,
+}
+
+// Search streaming matches as they're found
+
+#[tauri_bridge(extern, incremental, client_cfg(not(target_arch = "wasm32")))]
+pub fn find_files(pattern: &str, found: Channel<Vec<String>>) -> usize;
+
+#[tauri_bridge(extern, priority = "normal", client_cfg(not(target_arch = "wasm32")))]
+pub fn search(query: &str) -> Vec<String>;
+
 // Tests
 
 #[tokio::test]
@@ -605,3 +1388,1795 @@ async fn test_enum_response_deserialization() {
         assert_eq!(result.unwrap(), status);
     }
 }
+
+#[tokio::test]
+async fn test_included_client_invokes_backend_command() {
+    clear_mock_state();
+    set_mock_response("HELLO");
+
+    let result = generated::try_shout("hello").await;
+
+    assert_eq!(result.unwrap(), "HELLO");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "shout");
+    assert_eq!(call.args["message"], "hello");
+}
+
+#[tokio::test]
+async fn test_included_client_coexists_with_backend() {
+    clear_mock_state();
+    set_mock_response("HI");
+
+    // Backend function keeps its name at the definition site
+    assert_eq!(shout("hi"), "HI");
+    assert_eq!(generated::shout("hi").await, "HI");
+}
+
+#[tokio::test]
+async fn test_extern_declaration_generates_client() {
+    clear_mock_state();
+    set_mock_response(Status::Inactive);
+
+    let result = try_remote_status(7).await;
+
+    assert_eq!(result.unwrap(), Status::Inactive);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "remote_status");
+    assert_eq!(call.args["userId"], 7);
+}
+
+#[tokio::test]
+async fn test_versioned_client_invokes_suffixed_command() {
+    clear_mock_state();
+    set_mock_response(vec![1u64, 2]);
+
+    let result = try_remote_lookup("ali".to_string()).await;
+
+    assert_eq!(result.unwrap(), vec![1, 2]);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "remote_lookup@2");
+}
+
+#[tokio::test]
+async fn test_owned_params_client_accepts_owned_values() {
+    clear_mock_state();
+    set_mock_response("tagged");
+
+    let label = String::from("admin");
+    let user = User {
+        id: 3,
+        name: "Ana".to_string(),
+        email: None,
+    };
+    // The future owns its arguments and can outlive the caller's values
+    let future = try_tag_user(label, vec![1u64, 2], user);
+
+    assert_eq!(future.await.unwrap(), "tagged");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "tag_user");
+    assert_eq!(call.args["label"], "admin");
+    assert_eq!(call.args["ids"], serde_json::json!([1, 2]));
+    assert_eq!(call.args["user"]["name"], "Ana");
+}
+
+#[tokio::test]
+async fn test_own_args_struct_can_be_stored() {
+    clear_mock_state();
+
+    // Queue requests without holding borrows
+    let queue: Vec<RenameUserArgs> = ["Ana", "Bo"]
+        .iter()
+        .enumerate()
+        .map(|(index, name)| RenameUserArgs {
+            user_id: index as u64,
+            name: name.to_string(),
+        })
+        .collect();
+    assert_eq!(queue[1].name, "Bo");
+
+    set_mock_response(User {
+        id: 1,
+        name: "Bo".to_string(),
+        email: None,
+    });
+    let user = try_rename_user(1, &queue[1].name).await.unwrap();
+
+    assert_eq!(user.name, "Bo");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "rename_user");
+    assert_eq!(call.args["name"], "Bo");
+}
+
+#[tokio::test]
+async fn test_tuple_response_deserialization() {
+    clear_mock_state();
+    set_mock_response(("Ana".to_string(), 31u32));
+
+    let result = try_name_and_age(3).await;
+
+    assert_eq!(result.unwrap(), ("Ana".to_string(), 31));
+}
+
+#[tokio::test]
+async fn test_nested_tuple_response_deserialization() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!([[0, 1], [10, 20]]));
+
+    assert_eq!(bounds().await, ((0, 1), (10, 20)));
+    // Commands without parameters send an empty object, not `null`
+    assert_eq!(get_last_invoke_call().unwrap().args, serde_json::json!({}));
+}
+
+#[tokio::test]
+async fn test_tuple_response_wrong_length() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!(["Ana"]));
+
+    let result = try_name_and_age(3).await;
+
+    assert!(
+        result
+            .unwrap_err()
+            .contains("Failed to deserialize response")
+    );
+}
+
+#[tokio::test]
+async fn test_alias_response_deserialization() {
+    clear_mock_state();
+    set_mock_response(42u64);
+
+    assert_eq!(try_next_user_id().await.unwrap(), 42);
+}
+
+#[tokio::test]
+async fn test_newtype_response_deserialization() {
+    clear_mock_state();
+    set_mock_response("secret");
+
+    assert_eq!(issue_token(7).await, Token("secret".to_string()));
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.args["userId"], 7);
+}
+
+#[tokio::test]
+async fn test_generic_client_keeps_const_generics() {
+    clear_mock_state();
+    set_mock_response([1u8, 0, 0, 0]);
+
+    let padded: Block<4> = pad_block(Block(vec![1]), "header", &0).await;
+
+    assert_eq!(padded, Block(vec![1, 0, 0, 0]));
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.args["block"], serde_json::json!([1]));
+    assert_eq!(call.args["label"], "header");
+}
+
+#[tokio::test]
+async fn test_service_client_leaves_out_state() {
+    clear_mock_state();
+    set_mock_response(true);
+
+    assert!(lend("Dune", 14).await);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "library.lend");
+    assert_eq!(
+        call.args,
+        serde_json::json!({ "title": "Dune", "days": 14 })
+    );
+}
+
+#[tokio::test]
+async fn test_by_value_client_writes_back_modified_args() {
+    clear_mock_state();
+    set_mock_response((3u32, vec![9u8, 8, 7], "scrambled".to_string()));
+
+    let mut bytes = [1u8, 2, 3];
+    let mut label = "plain".to_string();
+    let result = try_scramble(&mut bytes, &mut label, 5).await;
+
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(bytes, [9, 8, 7]);
+    assert_eq!(label, "scrambled");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.args["bytes"], serde_json::json!([1, 2, 3]));
+    assert_eq!(call.args["label"], "plain");
+}
+
+#[tokio::test]
+async fn test_by_value_client_rejects_resized_slice() {
+    clear_mock_state();
+    set_mock_response((0u32, vec![1u8], String::new()));
+
+    let mut bytes = [1u8, 2, 3];
+    let mut label = String::new();
+    let result = try_scramble(&mut bytes, &mut label, 5).await;
+
+    assert!(
+        result
+            .unwrap_err()
+            .contains("`bytes` changed length from 3 to 1")
+    );
+    assert_eq!(bytes, [1, 2, 3]);
+}
+
+#[tokio::test]
+async fn test_with_options_variant_forwards_options() {
+    clear_mock_state();
+    set_mock_response(11u64);
+
+    let options = InvokeOptions {
+        headers: vec![("x-upload".to_string(), "1".to_string())],
+    };
+    let result = try_upload_with_options("a.png", vec![1, 2], options).await;
+
+    assert_eq!(result.unwrap(), 11);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "upload");
+    assert_eq!(call.args["name"], "a.png");
+    let options = LAST_OPTIONS.with(|last| last.borrow().clone()).unwrap();
+    assert_eq!(options.headers[0].0, "x-upload");
+
+    // The plain variants are still generated
+    set_mock_response(12u64);
+    assert_eq!(upload("b.png", vec![]).await, 12);
+}
+
+#[tokio::test]
+async fn test_target_label_routing() {
+    clear_mock_state();
+    set_mock_response(true);
+
+    assert!(try_open_panel_to("settings", "audio").await.unwrap());
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "open_panel");
+    assert_eq!(call.args["target"], "settings");
+    assert_eq!(call.args["panel"], "audio");
+    assert!(call.args.get("caller").is_none());
+
+    set_mock_response(true);
+    assert!(open_panel("audio").await);
+    let call = get_last_invoke_call().unwrap();
+    assert!(call.args["target"].is_null());
+}
+
+#[tokio::test]
+async fn test_offline_queue_replays_when_transport_returns() {
+    use tauri_bridge_runtime::offline::{clear_transport_override, set_transport_available};
+
+    clear_mock_state();
+    set_transport_available(false);
+
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            let pending = tokio::task::spawn_local(try_track_event("opened"));
+            tokio::task::yield_now().await;
+            assert!(get_invoke_calls().is_empty());
+
+            set_transport_available(true);
+            assert!(pending.await.unwrap().is_ok());
+        })
+        .await;
+
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "track_event");
+    assert_eq!(call.args["name"], "opened");
+    clear_transport_override();
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_fails_fast_when_open() {
+    use tauri_bridge_runtime::breaker::{BreakerError, reset};
+
+    clear_mock_state();
+
+    // Null responses don't deserialize into `u64`
+    for _ in 0..2 {
+        let result = try_sync_now(true).await;
+        assert!(matches!(result, Err(BreakerError::Failed(_))));
+    }
+
+    let result = try_sync_now(true).await;
+    assert!(matches!(
+        result,
+        Err(BreakerError::Open {
+            command: "sync_now",
+            ..
+        })
+    ));
+    assert_eq!(get_invoke_calls().len(), 2);
+
+    reset("sync_now");
+    set_mock_response(7u64);
+    assert_eq!(try_sync_now(false).await, Ok(7));
+    assert_eq!(get_last_invoke_call().unwrap().args["force"], false);
+}
+
+#[tokio::test]
+async fn test_low_priority_call_waits_for_interactive_call() {
+    use tauri_bridge_runtime::scheduler::{Priority, queued_calls, schedule};
+
+    clear_mock_state();
+
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            // Stands in for an interactive call still in flight
+            let interactive = schedule(Priority::Normal).await;
+
+            let pending = tokio::task::spawn_local(try_prefetch_page(2));
+            tokio::task::yield_now().await;
+            assert_eq!(queued_calls(), 1);
+
+            set_mock_response(vec!["a"]);
+            assert_eq!(search("a").await, ["a"]);
+            assert_eq!(get_invoke_calls().len(), 1);
+
+            drop(interactive);
+            assert!(pending.await.unwrap().is_ok());
+        })
+        .await;
+
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "prefetch_page");
+    assert_eq!(call.args["page"], 2);
+}
+
+#[tokio::test]
+async fn test_rebind_waits_for_reconnect() {
+    use tauri_bridge_runtime::connection::{disconnected, reconnected};
+
+    clear_mock_state();
+    set_mock_response(3);
+    disconnected();
+
+    let (synced, ()) = tokio::join!(try_sync_drafts(10), async {
+        assert!(get_invoke_calls().is_empty());
+        reconnected();
+    });
+
+    assert_eq!(synced, Ok(3));
+    assert_eq!(get_last_invoke_call().unwrap().args["limit"], 10);
+}
+
+#[tokio::test]
+async fn test_transaction_commits_steps_in_one_invoke() {
+    use serde_json::json;
+    use tauri_bridge_runtime::transaction::{Outcome, Transaction};
+
+    clear_mock_state();
+    set_mock_response(Outcome::Committed(vec![json!(12), json!(null)]));
+
+    let mut transaction = Transaction::new();
+    let ticket = transaction.add(open_ticket_step("Broken build"));
+    let assigned = transaction.add(assign_ticket_step(12, "ada".to_string()));
+    let mut committed = commit_bridge_transaction(transaction).await.unwrap();
+
+    assert_eq!(committed.take(ticket), Ok(12));
+    assert_eq!(committed.take(assigned), Ok(()));
+    let calls = get_invoke_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].command, "__bridge_transaction");
+    assert_eq!(
+        calls[0].args,
+        json!({
+            "steps": [
+                { "command": "open_ticket", "args": { "title": "Broken build" } },
+                { "command": "assign_ticket", "args": { "ticket": 12, "assignee": "ada" } },
+            ]
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_rolled_back_transaction_reports_failed_step() {
+    use serde_json::json;
+    use tauri_bridge_runtime::transaction::{Outcome, Transaction, TransactionError};
+
+    clear_mock_state();
+    set_mock_response(Outcome::RolledBack {
+        step: 1,
+        command: "assign_ticket".to_string(),
+        error: json!("unknown user"),
+    });
+
+    let mut transaction = Transaction::new();
+    transaction.add(open_ticket_step("Broken build"));
+    transaction.add(assign_ticket_step(12, "nobody".to_string()));
+    let error = commit_bridge_transaction(transaction).await.unwrap_err();
+
+    assert_eq!(
+        error,
+        TransactionError::RolledBack {
+            step: 1,
+            command: "assign_ticket".to_string(),
+            error: json!("unknown user"),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "transaction rolled back at step 1 (`assign_ticket`): unknown user"
+    );
+}
+
+#[tokio::test]
+async fn test_selfcheck_echoes_samples_to_backend() {
+    use serde_json::json;
+    use tauri_bridge_runtime::selfcheck::{Mismatch, Report, Sample, SelfCheck};
+
+    clear_mock_state();
+    set_mock_responses(vec![
+        SelfCheck {
+            samples: vec![
+                Sample {
+                    command: "file_report".to_string(),
+                    response: json!([]),
+                },
+                Sample {
+                    command: "archive_report".to_string(),
+                    response: json!(null),
+                },
+            ],
+            report: Report::default(),
+        },
+        SelfCheck {
+            samples: Vec::new(),
+            report: Report {
+                checked: vec!["file_report".to_string()],
+                mismatches: Vec::new(),
+            },
+        },
+    ]);
+
+    let report = run_bridge_selfcheck().await.unwrap();
+
+    assert_eq!(report.checked, ["file_report"]);
+    assert_eq!(
+        report.mismatches,
+        [Mismatch {
+            command: "archive_report".to_string(),
+            part: "command".to_string(),
+            problem: "not listed in the client's `bridge_selfcheck!`".to_string(),
+        }]
+    );
+    let calls = get_invoke_calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].command, "__bridge_selfcheck");
+    assert_eq!(calls[0].args, json!({ "echoes": null }));
+    assert_eq!(
+        calls[1].args,
+        json!({
+            "echoes": [{
+                "command": "file_report",
+                "args": { "title": "", "pageCount": 0 },
+                "response": "[]",
+            }]
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_bootstrap_primes_caches_in_one_invoke() {
+    use tauri_bridge_runtime::bootstrap::{Bundle, Failed, Loaded};
+    use tauri_bridge_runtime::{query, startup};
+
+    clear_mock_state();
+    query::clear();
+    startup::clear();
+    set_mock_response(Bundle {
+        loaded: vec![
+            Loaded::new("list_boards", &vec!["Todo", "Done"]),
+            Loaded::new("get_board_limit", &12),
+            Loaded::failed("get_board_owner", &"offline"),
+            Loaded::new("get_board_theme", &"dark"),
+        ],
+    });
+
+    let report = bridge_bootstrap().await.unwrap();
+
+    assert_eq!(report.primed, ["list_boards", "get_board_limit"]);
+    assert_eq!(
+        report.failed,
+        [
+            Failed {
+                command: "get_board_owner".to_string(),
+                error: "offline".to_string(),
+            },
+            Failed {
+                command: "get_board_theme".to_string(),
+                error: "not listed in the client's `bridge_bootstrap!`".to_string(),
+            },
+        ]
+    );
+    let calls = get_invoke_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].command, "__bridge_bootstrap");
+
+    // Primed reads don't invoke, failed ones do
+    assert_eq!(list_boards().await, ["Todo", "Done"]);
+    assert_eq!(get_board_limit(), Ok(12));
+    assert_eq!(get_invoke_calls().len(), 1);
+    assert!(!query::is_cached(GET_BOARD_OWNER_QUERY_KEY));
+}
+
+#[tokio::test]
+async fn test_bootstrap_reports_undecodable_response() {
+    use tauri_bridge_runtime::bootstrap::{Bundle, Loaded};
+    use tauri_bridge_runtime::query;
+
+    clear_mock_state();
+    query::clear();
+    set_mock_response(Bundle {
+        loaded: vec![Loaded::new("list_boards", &7)],
+    });
+
+    let report = bridge_bootstrap().await.unwrap();
+
+    assert!(report.primed.is_empty());
+    assert_eq!(report.failed[0].command, "list_boards");
+    assert!(
+        report.failed[0]
+            .error
+            .starts_with("Failed to deserialize response")
+    );
+    assert!(!query::is_cached(LIST_BOARDS_QUERY_KEY));
+}
+
+#[tokio::test]
+async fn test_selfcheck_reports_undecodable_response_sample() {
+    use serde_json::json;
+    use tauri_bridge_runtime::selfcheck::{Report, Sample, SelfCheck};
+
+    clear_mock_state();
+    set_mock_responses(vec![
+        SelfCheck {
+            samples: vec![Sample {
+                command: "file_report".to_string(),
+                response: json!({ "ids": [] }),
+            }],
+            report: Report::default(),
+        },
+        SelfCheck::default(),
+    ]);
+
+    let report = run_bridge_selfcheck().await.unwrap();
+
+    assert!(!report.is_ok());
+    assert_eq!(report.mismatches[0].part, "response");
+    assert!(
+        report.mismatches[0]
+            .problem
+            .starts_with("Failed to deserialize response")
+    );
+    assert_eq!(get_invoke_calls()[1].args, json!({ "echoes": [] }));
+}
+
+#[tokio::test]
+async fn test_check_bridge_accepts_matching_backend() {
+    clear_mock_state();
+    set_mock_response(bridge_manifest());
+
+    assert_eq!(check_bridge().await, Ok(()));
+    assert_eq!(
+        get_last_invoke_call().unwrap().command,
+        "__bridge_introspect"
+    );
+}
+
+#[tokio::test]
+async fn test_check_bridge_reports_stale_backend() {
+    use tauri_bridge_runtime::introspect::{CommandInfo, Manifest};
+
+    clear_mock_state();
+    // An older backend: `remote_lookup` at version 1, `remote_status` changed
+    set_mock_response(Manifest::new(vec![
+        CommandInfo::new("remote_lookup", 1, "{}"),
+        CommandInfo::new("remote_status", 1, "{}"),
+    ]));
+
+    let message = check_bridge().await.unwrap_err();
+
+    assert!(message.contains("missing on the backend: remote_lookup@2"));
+    assert!(message.contains("changed: remote_status"));
+}
+
+#[tokio::test]
+async fn test_form_tracks_changes_and_saves_once() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "Ok": { "theme": "dark", "font_size": 14 } }));
+
+    let mut form = SettingsForm::load().await.unwrap();
+    assert_eq!(form.theme(), "dark");
+    assert!(!form.is_dirty());
+
+    form.set_font_size(16);
+    form.set_theme("light".into());
+    assert!(form.is_dirty());
+
+    set_mock_response(serde_json::json!({ "Ok": null }));
+    form.save().await.unwrap();
+
+    assert!(!form.is_dirty());
+    let calls = get_invoke_calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].command, "load_settings");
+    assert_eq!(calls[1].command, "save_settings");
+    assert_eq!(
+        calls[1].args["value"],
+        serde_json::json!({ "theme": "light", "font_size": 16 })
+    );
+}
+
+#[tokio::test]
+async fn test_form_keeps_changes_when_save_fails() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "Ok": { "theme": "dark", "font_size": 14 } }));
+    let mut form = SettingsForm::load().await.unwrap();
+    form.set_font_size(0);
+
+    set_mock_response(serde_json::json!({ "Err": "font size must be positive" }));
+    let result = form.save().await;
+
+    assert_eq!(result.unwrap_err(), "font size must be positive");
+    assert!(form.is_dirty());
+    assert_eq!(*form.font_size(), 0);
+}
+
+#[tokio::test]
+async fn test_incremental_hands_batches_to_callback() {
+    clear_mock_state();
+    set_mock_batches(vec![vec!["a.rs"], vec!["b.rs", "c.rs"]]);
+    set_mock_response(3);
+
+    let found = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let result = try_find_files_incremental("*.rs", {
+        let found = found.clone();
+        move |batch: Vec<String>| found.borrow_mut().extend(batch)
+    })
+    .await;
+
+    assert_eq!(result, Ok(3));
+    assert_eq!(*found.borrow(), ["a.rs", "b.rs", "c.rs"]);
+
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "find_files");
+    assert_eq!(call.args["pattern"], "*.rs");
+    assert!(
+        call.args["found"]
+            .as_str()
+            .unwrap()
+            .starts_with("__CHANNEL__:")
+    );
+}
+
+#[tokio::test]
+async fn test_incremental_detaches_callback_after_call() {
+    clear_mock_state();
+    set_mock_response(0);
+
+    let found = std::rc::Rc::new(RefCell::new(Vec::new()));
+    try_find_files_incremental("*.rs", {
+        let found = found.clone();
+        move |batch: Vec<String>| found.borrow_mut().extend(batch)
+    })
+    .await
+    .unwrap();
+
+    // A batch arriving late, e.g. after the component went away
+    let call = get_last_invoke_call().unwrap();
+    let id: usize = call.args["found"].as_str().unwrap()["__CHANNEL__:".len()..]
+        .parse()
+        .unwrap();
+    CHANNELS.with(|channels| (channels.borrow_mut()[id])(JsValue(serde_json::json!(["late.rs"]))));
+
+    assert!(found.borrow().is_empty());
+}
+
+#[tokio::test]
+async fn test_incremental_plain_call_discards_batches() {
+    clear_mock_state();
+    set_mock_batches(vec![vec!["a.rs"]]);
+    set_mock_response(1);
+
+    assert_eq!(find_files("*.rs").await, 1);
+}
+
+#[tokio::test]
+async fn test_incremental_reports_malformed_batch() {
+    clear_mock_state();
+    set_mock_batches(vec![serde_json::json!(42)]);
+    set_mock_response(1);
+
+    let result = try_find_files_incremental("*.rs", |_| {}).await;
+
+    assert!(
+        result
+            .unwrap_err()
+            .starts_with("Failed to deserialize batch")
+    );
+}
+
+#[tokio::test]
+async fn test_chunked_response_is_reassembled() {
+    clear_mock_state();
+    set_mock_batches(vec!["[\"a\",\"b", "\",\"c\"]"]);
+    set_mock_response(serde_json::json!({ "chunks": 2 }));
+
+    let result = try_export_log(7).await;
+
+    assert_eq!(result, Ok(vec!["a".to_string(), "b".into(), "c".into()]));
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "export_log");
+    assert_eq!(call.args["since"], 7);
+    assert!(
+        call.args["tauriBridgeChunks"]
+            .as_str()
+            .unwrap()
+            .starts_with("__CHANNEL__:")
+    );
+}
+
+#[tokio::test]
+async fn test_chunked_inline_response() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "inline": "[\"only\"]" }));
+
+    assert_eq!(export_log(0).await, ["only"]);
+}
+
+#[tokio::test]
+async fn test_compressed_args_and_response() {
+    use tauri_bridge_runtime::compress::{Payload, decode, encode};
+
+    clear_mock_state();
+    let rows = vec!["row".to_string(); 50];
+    let response = encode(serde_json::to_string(&rows).unwrap(), 64).unwrap();
+    assert!(response.gzip);
+    set_mock_response(response);
+
+    let result = try_export_report("weekly", rows.clone()).await;
+
+    assert_eq!(result, Ok(rows.clone()));
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "export_report");
+    let payload: Payload = serde_json::from_value(call.args["tauriBridgePayload"].clone()).unwrap();
+    assert!(payload.gzip);
+    let args: serde_json::Value = serde_json::from_str(&decode(payload).unwrap()).unwrap();
+    assert_eq!(args["title"], "weekly");
+    assert_eq!(args["rows"].as_array().unwrap().len(), 50);
+}
+
+#[tokio::test]
+async fn test_compress_keeps_small_payloads_plain() {
+    use tauri_bridge_runtime::compress::Payload;
+
+    clear_mock_state();
+    set_mock_response(Payload {
+        gzip: false,
+        data: "[]".into(),
+    });
+
+    assert!(export_report("empty", vec![]).await.is_empty());
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.args["tauriBridgePayload"]["gzip"], false);
+}
+
+#[tokio::test]
+async fn test_signed_payloads_go_through_hooks() {
+    use tauri_bridge_runtime::signing::{clear_hooks, set_signer, set_verifier};
+
+    clear_mock_state();
+    set_signer(|command, args| Ok(serde_json::json!({ "command": command, "signed": args })));
+    set_verifier(|_, response| match response["signature"].as_str() {
+        Some("valid") => Ok(response["balance"].clone()),
+        _ => Err("invalid signature".to_string()),
+    });
+
+    set_mock_response(serde_json::json!({ "balance": 900, "signature": "valid" }));
+    assert_eq!(transfer_funds("savings", 100).await, 900);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(
+        call.args,
+        serde_json::json!({
+            "command": "transfer_funds",
+            "signed": { "to": "savings", "cents": 100 },
+        })
+    );
+
+    set_mock_response(serde_json::json!({ "balance": 1_000_000, "signature": "forged" }));
+    assert_eq!(
+        try_transfer_funds("savings", 100).await,
+        Err("invalid signature".to_string())
+    );
+
+    clear_hooks();
+    assert!(
+        try_transfer_funds("savings", 100)
+            .await
+            .unwrap_err()
+            .contains("no signer")
+    );
+}
+
+#[tokio::test]
+async fn test_encrypted_args_and_response() {
+    use tauri_bridge_runtime::encrypt::{Sealed, clear_key_provider, open, seal, set_key_provider};
+
+    clear_mock_state();
+    set_key_provider(|_| Ok([7; 32]));
+    let secrets = vec!["api-key".to_string()];
+    set_mock_response(seal("unlock_vault", serde_json::to_string(&secrets).unwrap()).unwrap());
+
+    assert_eq!(try_unlock_vault("main", 1234).await, Ok(secrets.clone()));
+    let call = get_last_invoke_call().unwrap();
+    assert!(!call.args.to_string().contains("main"));
+    let sealed: Sealed = serde_json::from_value(call.args["tauriBridgeSealed"].clone()).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&open("unlock_vault", sealed).unwrap()).unwrap(),
+        serde_json::json!({ "name": "main", "pin": 1234 })
+    );
+
+    // Sealed for another command
+    set_mock_response(seal("lock_vault", "[]".to_string()).unwrap());
+    assert!(
+        try_unlock_vault("main", 1234)
+            .await
+            .unwrap_err()
+            .contains("Failed to decrypt")
+    );
+
+    clear_key_provider();
+    assert!(
+        try_unlock_vault("main", 1234)
+            .await
+            .unwrap_err()
+            .contains("no key provider")
+    );
+}
+
+#[tokio::test]
+async fn test_session_token_sent_with_args() {
+    use tauri_bridge_runtime::session::{clear_token_provider, set_token_provider};
+
+    clear_mock_state();
+    set_mock_response(true);
+    set_token_provider(|command| Some(format!("token-for-{}", command)));
+
+    assert!(close_account("moving".to_string()).await);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(
+        call.args,
+        serde_json::json!({ "reason": "moving", "tauriBridgeSession": "token-for-close_account" })
+    );
+
+    // Signed out, the backend rejects the call
+    clear_token_provider();
+    set_mock_response(true);
+    assert!(close_account("moving".to_string()).await);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.args["tauriBridgeSession"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn test_raw_identifier_params_use_plain_keys() {
+    clear_mock_state();
+    set_mock_response(true);
+
+    assert!(set_kind("folder", 3).await);
+
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.args, serde_json::json!({ "type": "folder", "ref": 3 }));
+}
+
+#[tokio::test]
+async fn test_raw_identifier_command_names() {
+    clear_mock_state();
+    set_mock_response("moved");
+
+    assert_eq!(r#move(true, "archive").await, "moved");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "move");
+    assert_eq!(call.args["async"], true);
+
+    set_mock_response("moved");
+    assert_eq!(
+        try_move_with_options(false, "inbox", InvokeOptions::default()).await,
+        Ok("moved".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_client_attrs_command_sends_all_args() {
+    clear_mock_state();
+
+    draw_rect(1.0, 2.0, 3.0, 4.0, 255, 0, 0, 0.5).await;
+
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "draw_rect");
+    assert_eq!(call.args["r"], 255);
+    assert_eq!(call.args["alpha"], 0.5);
+}
+
+#[tokio::test]
+async fn test_client_names_avoid_user_items() {
+    clear_mock_state();
+    set_mock_response("found");
+
+    assert_eq!(lookup_checked("k").await, Ok("found".to_string()));
+    assert_eq!(try_lookup(), "user item");
+    let _ = LookupArgs;
+
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "lookup");
+    assert_eq!(call.args["key"], "k");
+}
+
+#[tokio::test]
+async fn test_debug_log_client_sends_and_decodes_as_usual() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "Ok": [] }));
+
+    assert_eq!(try_find_users("al", 5).await, Ok(Ok(vec![])));
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "find_users");
+    assert_eq!(
+        call.args,
+        serde_json::json!({ "namePrefix": "al", "limit": 5 })
+    );
+
+    set_mock_response("not a number");
+    let result = try_user_count().await;
+    assert!(
+        result
+            .unwrap_err()
+            .starts_with("Failed to deserialize response")
+    );
+}
+
+#[tokio::test]
+async fn test_catch_panic_client_reports_panics() {
+    use tauri_bridge_runtime::panic::BridgeError;
+
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "Value": 8080 }));
+
+    assert_eq!(try_parse_port("8080").await, Ok(8080));
+    assert_eq!(get_last_invoke_call().unwrap().args["text"], "8080");
+
+    set_mock_response(serde_json::json!({ "Panicked": { "message": "not a port" } }));
+    assert_eq!(
+        try_parse_port("http").await,
+        Err(BridgeError::Panicked {
+            message: "not a port".to_string()
+        })
+    );
+
+    set_mock_response(serde_json::json!({ "Value": null }));
+    assert_eq!(try_reset_cache().await, Ok(()));
+
+    set_mock_response(8080);
+    assert!(matches!(
+        try_parse_port("8080").await,
+        Err(BridgeError::Failed(error)) if error.starts_with("Failed to deserialize response")
+    ));
+}
+
+#[tokio::test]
+async fn test_join_returns_responses_in_listed_order() {
+    clear_mock_state();
+    set_mock_responses(vec![
+        serde_json::json!({ "Ok": [] }),
+        serde_json::json!(12),
+        serde_json::json!({ "Value": 8080 }),
+    ]);
+
+    let prefix = "al".to_string();
+    let joined = tauri_bridge::bridge_join!(find_users(&prefix, 5), user_count, parse_port("8080"));
+    assert_eq!(joined.await, Ok((Ok(vec![]), 12, 8080)));
+    let commands: Vec<_> = get_invoke_calls()
+        .into_iter()
+        .map(|call| call.command)
+        .collect();
+    assert_eq!(commands, ["find_users", "user_count", "parse_port"]);
+}
+
+#[tokio::test]
+async fn test_join_unifies_errors() {
+    use tauri_bridge_runtime::join::JoinError;
+
+    clear_mock_state();
+    set_mock_responses(vec![
+        serde_json::json!(12),
+        serde_json::json!({ "Panicked": { "message": "not a port" } }),
+    ]);
+
+    let result = tauri_bridge::bridge_join!(user_count(), parse_port("http")).await;
+    assert_eq!(
+        result,
+        Err(JoinError {
+            command: "parse_port",
+            message: "command panicked: not a port".to_string(),
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_log_slow_reports_calls_over_threshold() {
+    use tauri_bridge_runtime::slow;
+
+    clear_mock_state();
+    let reports = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    slow::on_slow_call({
+        let reports = reports.clone();
+        move |call| reports.borrow_mut().push(call.clone())
+    });
+
+    set_mock_response(vec![1, 2]);
+    assert_eq!(rebuild_index(true).await, [1, 2]);
+    assert!(reports.borrow().is_empty());
+
+    // Every call is slow without a threshold
+    slow::set_threshold_ms(-1.0);
+    set_mock_response(vec![1, 2, 3]);
+    assert_eq!(rebuild_index(false).await, [1, 2, 3]);
+    let report = reports.borrow_mut().pop().unwrap();
+    assert_eq!(report.command, "rebuild_index");
+    assert_eq!(report.args_bytes, Some(r#"{"full":false}"#.len()));
+    assert_eq!(report.response_bytes, Some("[1,2,3]".len()));
+
+    slow::set_threshold_ms(slow::DEFAULT_THRESHOLD_MS);
+    slow::clear_handler();
+}
+
+#[tokio::test]
+async fn test_translate_errors_client_translates_messages() {
+    use tauri_bridge_runtime::messages::{self, FailureKind};
+
+    clear_mock_state();
+    messages::set_translator(|failure| {
+        (failure.kind == FailureKind::Response)
+            .then(|| format!("Antwort von `{}` unlesbar", failure.command))
+    });
+
+    set_mock_response("Deutsch");
+    assert_eq!(try_get_locale_name("de").await, Ok("Deutsch".to_string()));
+    set_mock_response(5);
+    assert_eq!(
+        try_get_locale_name("de").await,
+        Err("Antwort von `get_locale_name` unlesbar".to_string())
+    );
+
+    messages::clear_translator();
+    set_mock_response(5);
+    assert!(
+        try_get_locale_name("de")
+            .await
+            .unwrap_err()
+            .starts_with("Failed to deserialize response")
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "Antwort von `get_locale_name` unlesbar")]
+async fn test_translate_errors_plain_client_panics_translated() {
+    clear_mock_state();
+    tauri_bridge_runtime::messages::set_translator(|failure| {
+        Some(format!("Antwort von `{}` unlesbar", failure.command))
+    });
+    set_mock_response(5);
+
+    get_locale_name("de").await;
+}
+
+#[tokio::test]
+async fn test_anyhow_client_decodes_error_report() {
+    use tauri_bridge_runtime::error::ErrorReport;
+
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "Ok": 80 }));
+    assert_eq!(try_read_setting("port=80").await, Ok(Ok(80)));
+
+    set_mock_response(serde_json::json!({
+        "Err": { "message": "invalid port", "chain": ["invalid digit found in string"] }
+    }));
+    let report = read_setting("port=http").await.unwrap_err();
+    assert_eq!(
+        report,
+        ErrorReport {
+            message: "invalid port".to_string(),
+            chain: vec!["invalid digit found in string".to_string()],
+        }
+    );
+    assert_eq!(report.root_cause(), "invalid digit found in string");
+}
+
+#[tokio::test]
+async fn test_bridge_error_client_matches_on_codes() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!({
+        "Err": { "code": "E_LOCKED", "details": { "by": "sam" } }
+    }));
+
+    let error = open_note(1).await.unwrap_err();
+    assert!(error.is_locked());
+    assert_eq!(error.code(), "E_LOCKED");
+    assert_eq!(
+        error,
+        NoteError::Locked {
+            by: "sam".to_string()
+        }
+    );
+
+    set_mock_response(serde_json::json!({ "Err": { "code": "not_found", "details": 7 } }));
+    assert!(open_note(7).await.unwrap_err().is_not_found());
+}
+
+#[tokio::test]
+async fn test_error_conversion_client_decodes_sent_error() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "Err": { "kind": "invalid_input" } }));
+
+    assert_eq!(
+        parse_limit("many").await,
+        Err(AppError {
+            kind: "invalid_input".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_rejections_decode_error_side() {
+    clear_mock_state();
+    set_mock_response(7);
+    assert_eq!(try_archive_note(7).await, Ok(Ok(7)));
+
+    set_mock_rejection(serde_json::json!({ "code": "not_found", "details": 8 }));
+    assert_eq!(try_archive_note(8).await, Ok(Err(NoteError::NotFound(8))));
+    assert_eq!(get_last_invoke_call().unwrap().args["id"], 8);
+
+    // Rejections that aren't the command's error fail the call
+    set_mock_rejection("invalid args `id` for command `archive_note`");
+    assert_eq!(
+        try_archive_note(9).await,
+        Err("invalid args `id` for command `archive_note`".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_rejections_fail_plain_commands() {
+    clear_mock_state();
+    set_mock_response("Groceries");
+    assert_eq!(
+        try_note_title_with_options(1, InvokeOptions::default()).await,
+        Ok("Groceries".to_string())
+    );
+
+    set_mock_rejection("command note_title not found");
+    assert_eq!(
+        try_note_title(2).await,
+        Err("command note_title not found".to_string())
+    );
+
+    set_mock_rejection(serde_json::json!({ "reason": "denied" }));
+    let error = try_note_title(3).await.unwrap_err();
+    assert!(error.contains("denied"));
+}
+
+#[tokio::test]
+async fn test_raw_response_client_skips_deserialization() {
+    clear_mock_state();
+    // Not a `Vec<Point>`, passed through untouched
+    let series = serde_json::json!({ "labels": ["a"], "values": [1.5] });
+    set_mock_response(&series);
+
+    let response: JsValue = chart_series(30).await;
+    assert_eq!(response.0, series);
+    assert_eq!(get_last_invoke_call().unwrap().args["range"], 30);
+}
+
+#[tokio::test]
+async fn test_namespaced_client_prefixes_command() {
+    clear_mock_state();
+    set_mock_response("Sam");
+
+    assert_eq!(users::get(3).await, "Sam");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "users.get");
+    assert_eq!(call.args["id"], 3);
+}
+
+#[tokio::test]
+async fn test_into_params_convert_before_sending() {
+    clear_mock_state();
+    set_mock_response(7);
+
+    assert_eq!(invite_member("Bob", ["admin", "editor"]).await, 7);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(
+        call.args,
+        serde_json::json!({ "name": "Bob", "roles": ["admin", "editor"] })
+    );
+}
+
+#[tokio::test]
+async fn test_unwrap_envelope_returns_data_or_error() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!({ "success": true, "data": "Ada" }));
+    assert_eq!(find_member(1).await, Ok("Ada".to_string()));
+
+    set_mock_response(serde_json::json!({
+        "success": false,
+        "error": { "code": "not_found", "message": "no member 2" }
+    }));
+    let error = find_member(2).await.unwrap_err();
+    assert_eq!(error.code, "not_found");
+    assert_eq!(error.message, "no member 2");
+}
+
+#[tokio::test]
+async fn test_cached_reads_until_invalidated() {
+    use tauri_bridge_runtime::query;
+
+    clear_mock_state();
+    query::clear();
+    let invalidated = std::rc::Rc::new(std::cell::Cell::new(false));
+    let _subscription = query::subscribe(LIST_MEMBERS_QUERY_KEY, {
+        let invalidated = invalidated.clone();
+        move || invalidated.set(true)
+    });
+
+    set_mock_responses(vec![vec!["Ada"], vec!["Bob"]]);
+    assert_eq!(list_members(1).await, ["Ada"]);
+    assert_eq!(list_members(1).await, ["Ada"]);
+    assert_eq!(list_members(2).await, ["Bob"]);
+    assert_eq!(get_invoke_calls().len(), 2);
+
+    set_mock_responses(vec![serde_json::json!(3), serde_json::json!(["Ada", "Cy"])]);
+    assert_eq!(add_member(1, "Cy".to_string()).await, 3);
+    assert!(invalidated.get());
+    assert_eq!(list_members(1).await, ["Ada", "Cy"]);
+    assert_eq!(get_invoke_calls().len(), 4);
+}
+
+#[tokio::test]
+async fn test_failed_mutation_keeps_cache() {
+    use tauri_bridge_runtime::query;
+
+    clear_mock_state();
+    query::clear();
+    let invalidations = std::rc::Rc::new(std::cell::Cell::new(0));
+    let _subscription = query::subscribe(LIST_MEMBERS_QUERY_KEY, {
+        let invalidations = invalidations.clone();
+        move || invalidations.set(invalidations.get() + 1)
+    });
+
+    set_mock_response(vec!["Ada"]);
+    assert_eq!(list_members(5).await, ["Ada"]);
+
+    set_mock_response(serde_json::json!({ "Err": "not a member" }));
+    assert_eq!(
+        remove_member(5, "Bob".to_string()).await,
+        Err("not a member".to_string())
+    );
+    assert_eq!(invalidations.get(), 0);
+    assert!(query::is_cached(LIST_MEMBERS_QUERY_KEY));
+
+    set_mock_response(serde_json::json!({ "Ok": null }));
+    assert_eq!(remove_member(5, "Ada".to_string()).await, Ok(()));
+    assert_eq!(invalidations.get(), 1);
+    assert!(!query::is_cached(LIST_MEMBERS_QUERY_KEY));
+}
+
+#[tokio::test]
+async fn test_startup_cache_reads_synchronously_after_init() {
+    use tauri_bridge_runtime::startup;
+
+    clear_mock_state();
+    startup::clear();
+    assert!(!startup::is_set("get_build_channel"));
+
+    set_mock_responses(vec!["beta", "stable"]);
+    assert_eq!(init_get_build_channel().await, Ok(()));
+    assert_eq!(get_build_channel(), "beta");
+    assert_eq!(get_build_channel(), "beta");
+    assert_eq!(get_invoke_calls().len(), 1);
+
+    // Initializing again fetches a fresh value
+    assert_eq!(init_get_build_channel().await, Ok(()));
+    assert_eq!(get_build_channel(), "stable");
+    assert_eq!(get_invoke_calls().len(), 2);
+}
+
+#[tokio::test]
+async fn test_swr_returns_stale_response_and_refreshes() {
+    use tauri_bridge_runtime::query;
+
+    clear_mock_state();
+    query::clear();
+    let refreshed = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let _subscription = on_team_name_refreshed({
+        let refreshed = refreshed.clone();
+        move |name| refreshed.borrow_mut().push(name)
+    });
+    set_mock_responses(vec!["Red", "Blue", "Green"]);
+
+    assert_eq!(team_name(1).await, "Red");
+    assert_eq!(run_spawned().await, 0);
+
+    // Stale reads refresh once in the background
+    assert_eq!(team_name(1).await, "Red");
+    assert_eq!(team_name(1).await, "Red");
+    assert_eq!(get_invoke_calls().len(), 1);
+    assert_eq!(run_spawned().await, 1);
+    assert_eq!(get_invoke_calls().len(), 2);
+    assert_eq!(*refreshed.borrow(), ["Blue"]);
+
+    assert_eq!(team_name(1).await, "Blue");
+    query::invalidate(&[TEAM_NAME_QUERY_KEY]);
+    // The refresh started before the invalidation is dropped
+    assert_eq!(run_spawned().await, 1);
+    assert_eq!(*refreshed.borrow(), ["Blue"]);
+    assert!(!query::is_cached(TEAM_NAME_QUERY_KEY));
+}
+
+#[tokio::test]
+async fn test_renamed_crate_paths() {
+    clear_mock_state();
+    set_mock_response("Urgent");
+
+    assert_eq!(rename_label(3, "Urgent".to_string()).await, "Urgent");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "rename_label");
+    assert_eq!(call.args, serde_json::json!({"id": 3, "name": "Urgent"}));
+}
+
+#[tokio::test]
+async fn test_maps_sent_as_entries() {
+    clear_mock_state();
+    set_mock_response(vec![(1_u32, 10_u64), (7, 80)]);
+
+    let bonus = std::collections::HashMap::from([(7_u32, 70_u64)]);
+    let scores = merge_scores(bonus, 2).await;
+    assert_eq!(
+        scores,
+        std::collections::BTreeMap::from([(1_u32, 10_u64), (7, 80)])
+    );
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "merge_scores");
+    assert_eq!(
+        call.args,
+        serde_json::json!({"bonus": [[7, 70]], "round": 2})
+    );
+}
+
+#[tokio::test]
+async fn test_non_finite_floats_sent_as_strings() {
+    clear_mock_state();
+    set_mock_response(serde_json::json!(["NaN", 1.5, "-Infinity"]));
+
+    let smoothed = smooth_readings(vec![1.0, f64::INFINITY], 3).await;
+    assert!(smoothed[0].is_nan());
+    assert_eq!(smoothed[1..], [1.5, f64::NEG_INFINITY]);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(
+        call.args,
+        serde_json::json!({"readings": [1.0, "Infinity"], "window": 3})
+    );
+}
+
+/// Keeps persisted responses in memory, standing in for `localStorage`
+#[derive(Clone, Default)]
+struct MemoryStore(std::rc::Rc<RefCell<std::collections::HashMap<String, String>>>);
+
+impl tauri_bridge_runtime::persist::Store for MemoryStore {
+    fn get(&self, item: &str) -> Option<String> {
+        self.0.borrow().get(item).cloned()
+    }
+
+    fn set(&self, item: &str, value: &str) {
+        self.0
+            .borrow_mut()
+            .insert(item.to_string(), value.to_string());
+    }
+
+    fn remove(&self, item: &str) {
+        self.0.borrow_mut().remove(item);
+    }
+}
+
+#[tokio::test]
+async fn test_persisted_responses_survive_reloads() {
+    use tauri_bridge_runtime::persist::{self, Store};
+    use tauri_bridge_runtime::query;
+
+    clear_mock_state();
+    query::clear();
+    let store = MemoryStore::default();
+    persist::set_store(store.clone());
+    let item = format!("{}{}", persist::ITEM_PREFIX, TEAM_MOTTO_QUERY_KEY);
+    set_mock_responses(vec!["Onward", "Upward", "Forward"]);
+
+    assert_eq!(team_motto(1).await, "Onward");
+    assert!(store.get(&item).is_some());
+
+    // A reload empties the memory cache; the persisted response is restored
+    query::clear();
+    assert_eq!(team_motto(1).await, "Onward");
+    assert_eq!(get_invoke_calls().len(), 1);
+    assert_eq!(team_motto(2).await, "Upward");
+    assert_eq!(get_invoke_calls().len(), 2);
+
+    // Responses persisted by a build with other types are refetched
+    let stale = store
+        .get(&item)
+        .unwrap()
+        .replacen("\"version\":\"", "\"version\":\"0", 1);
+    store.set(&item, &stale);
+    query::clear();
+    assert_eq!(team_motto(1).await, "Forward");
+    assert_eq!(get_invoke_calls().len(), 3);
+
+    query::invalidate(&[TEAM_MOTTO_QUERY_KEY]);
+    assert!(store.get(&item).is_none());
+    persist::clear_store();
+}
+
+#[tokio::test]
+async fn test_prefetch_fills_the_cache() {
+    use tauri_bridge_runtime::query;
+
+    clear_mock_state();
+    query::clear();
+    set_mock_responses(vec![vec!["Ada"], vec!["Bob"]]);
+
+    assert_eq!(prefetch_list_members(1).await, Ok(()));
+    assert!(query::is_cached(LIST_MEMBERS_QUERY_KEY));
+    assert_eq!(list_members(1).await, ["Ada"]);
+    assert_eq!(get_invoke_calls().len(), 1);
+
+    let team = 2;
+    tauri_bridge::bridge_prefetch_all![list_members(team), list_members(1)].await;
+    assert_eq!(list_members(team).await, ["Bob"]);
+    // Already cached teams aren't fetched again
+    assert_eq!(get_invoke_calls().len(), 2);
+}
+
+#[tokio::test]
+async fn test_prefetch_all_ignores_failures() {
+    use tauri_bridge_runtime::query;
+
+    clear_mock_state();
+    query::clear();
+    set_mock_response("not a list");
+
+    tauri_bridge::bridge_prefetch_all![list_members(3)].await;
+    assert!(!query::is_cached(LIST_MEMBERS_QUERY_KEY));
+    assert_eq!(get_invoke_calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_defaults_fill_left_out_params() {
+    clear_mock_state();
+    set_mock_response(vec![1, 2]);
+
+    assert_eq!(
+        search_notes_with_defaults("todo".to_string()).await,
+        vec![1, 2]
+    );
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "search_notes");
+    assert_eq!(
+        call.args,
+        serde_json::json!({ "query": "todo", "tags": null, "limit": 20 })
+    );
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn test_alias_client_invokes_current_name() {
+    clear_mock_state();
+    set_mock_response("note 3");
+
+    assert_eq!(fetch_note(3).await, "note 3");
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "load_note");
+    assert_eq!(call.args["id"], 3);
+}
+
+#[tokio::test]
+async fn test_platform_excluded_command_fails_without_invoke() {
+    use tauri_bridge_runtime::platform::{Platform, PlatformError, reset_platform, set_platform};
+
+    clear_mock_state();
+    set_platform(Platform::Macos);
+
+    assert_eq!(
+        try_scan_qr_code().await,
+        Err(PlatformError::Unsupported {
+            command: "scan_qr_code",
+            platform: Platform::Macos,
+        })
+    );
+    assert!(get_invoke_calls().is_empty());
+
+    try_open_devtools(true).await.unwrap();
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "open_devtools");
+    assert_eq!(call.args["detached"], true);
+    reset_platform();
+}
+
+#[tokio::test]
+async fn test_command_fails_without_invoke_outside_tauri() {
+    use tauri_bridge_runtime::environment::{reset_in_tauri, set_in_tauri};
+    use tauri_bridge_runtime::panic::BridgeError;
+
+    clear_mock_state();
+    set_in_tauri(false);
+
+    let error = try_get_window_theme().await.unwrap_err();
+    assert_eq!(
+        error,
+        BridgeError::NotInTauri {
+            command: "get_window_theme"
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "`get_window_theme` can't be invoked outside of Tauri"
+    );
+    assert!(get_invoke_calls().is_empty());
+
+    reset_in_tauri();
+    set_mock_response("dark");
+    assert_eq!(try_get_window_theme().await, Ok("dark".to_string()));
+    assert_eq!(get_invoke_calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_command_uses_fallback_outside_tauri() {
+    use tauri_bridge_runtime::environment::{reset_in_tauri, set_in_tauri};
+
+    clear_mock_state();
+    set_in_tauri(false);
+
+    assert_eq!(get_app_version().await, "preview of get_app_version");
+    assert!(get_invoke_calls().is_empty());
+
+    reset_in_tauri();
+    set_mock_response("2.4.0");
+    assert_eq!(get_app_version().await, "2.4.0");
+    assert_eq!(get_invoke_calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_demo_function_answers_outside_tauri() {
+    use tauri_bridge_runtime::environment::{reset_in_tauri, set_in_tauri};
+
+    clear_mock_state();
+    set_in_tauri(false);
+
+    assert_eq!(add_bookmark("a.dev".to_string()).await, vec!["a.dev"]);
+    assert_eq!(
+        add_bookmark("b.dev".to_string()).await,
+        vec!["a.dev", "b.dev"]
+    );
+    assert!(get_invoke_calls().is_empty());
+    tauri_bridge_runtime::demo::remove("bookmarks");
+
+    reset_in_tauri();
+    set_mock_response(vec!["c.dev"]);
+    assert_eq!(add_bookmark("c.dev".to_string()).await, vec!["c.dev"]);
+    assert_eq!(get_invoke_calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_recorded_calls_replay_without_invoke() {
+    use tauri_bridge_runtime::replay::{
+        start_recording, start_replay, stop_recording, stop_replay,
+    };
+
+    clear_mock_state();
+    start_recording();
+    set_mock_responses(vec![vec!["a.txt"], vec!["a.txt", "b.txt"]]);
+    assert_eq!(list_recent_files(1).await, vec!["a.txt"]);
+    assert_eq!(list_recent_files(2).await, vec!["a.txt", "b.txt"]);
+    let recording = stop_recording();
+
+    assert_eq!(recording.calls.len(), 2);
+    assert_eq!(recording.calls[1].command, "list_recent_files");
+    assert_eq!(recording.calls[1].args, serde_json::json!({ "limit": 2 }));
+    assert_eq!(
+        recording.calls[1].response,
+        serde_json::json!(["a.txt", "b.txt"])
+    );
+
+    // Calls are answered by their arguments, whatever the order
+    clear_mock_state();
+    start_replay(recording);
+    assert_eq!(list_recent_files(2).await, vec!["a.txt", "b.txt"]);
+    assert_eq!(list_recent_files(1).await, vec!["a.txt"]);
+    assert_eq!(
+        try_list_recent_files(1).await,
+        Err("No recorded response left for `list_recent_files`".to_string())
+    );
+    assert!(get_invoke_calls().is_empty());
+    stop_replay();
+}
+
+#[tokio::test]
+async fn test_oversized_arguments_fail_without_invoke() {
+    use tauri_bridge_runtime::payload::{PayloadError, PayloadTooLarge};
+
+    clear_mock_state();
+    set_mock_response(9);
+
+    assert_eq!(try_post_comment(1, "nice".to_string()).await, Ok(9));
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "post_comment");
+    assert_eq!(call.args["text"], "nice");
+
+    clear_mock_state();
+    let error = try_post_comment(1, "a".repeat(100)).await.unwrap_err();
+    assert!(matches!(
+        error,
+        PayloadError::TooLarge(PayloadTooLarge { ref command, size, limit: 64 })
+            if command == "post_comment" && size > 64
+    ));
+    assert!(get_invoke_calls().is_empty());
+}
+
+#[tokio::test]
+async fn test_verify_schema_sends_hash() {
+    clear_mock_state();
+    set_mock_response(true);
+
+    assert!(archive_thread(4).await);
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.args["thread"], 4);
+    let hash = call.args["tauriBridgeSchema"].as_str().unwrap().to_string();
+    assert_eq!(hash.len(), 16);
+    assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+
+    // Commands without parameters still send an args object for the hash
+    set_mock_response("1.4.0");
+    assert_eq!(server_build().await, "1.4.0");
+    let call = get_last_invoke_call().unwrap();
+    assert_ne!(call.args["tauriBridgeSchema"].as_str(), Some(hash.as_str()));
+}
+
+#[tokio::test]
+async fn test_validated_response_rejects_impossible_data() {
+    use tauri_bridge_runtime::validate::{InvalidResponse, ResponseError};
+
+    clear_mock_state();
+    let stock = Stock {
+        sku: "A-1".to_string(),
+        count: 3,
+    };
+    set_mock_response(Ok::<_, String>(stock.clone()));
+    assert_eq!(try_get_stock("A-1").await, Ok(Ok(stock)));
+
+    set_mock_response(Ok::<_, String>(Stock {
+        sku: "A-1".to_string(),
+        count: -2,
+    }));
+    let error = try_get_stock("A-1").await.unwrap_err();
+    assert_eq!(
+        error,
+        ResponseError::Invalid(InvalidResponse {
+            command: "get_stock".to_string(),
+            reason: "negative count for A-1".to_string(),
+        })
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid response from `get_stock`: negative count for A-1"
+    );
+
+    // Errors of the command aren't validated
+    set_mock_response(Err::<Stock, _>("unknown sku".to_string()));
+    assert_eq!(
+        try_get_stock("B-2").await,
+        Ok(Err("unknown sku".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_validated_response_without_result() {
+    use tauri_bridge_runtime::validate::ResponseError;
+
+    clear_mock_state();
+    set_mock_response(4);
+    assert_eq!(try_last_page().await, Ok(4));
+
+    set_mock_response(0);
+    assert!(matches!(
+        try_last_page().await,
+        Err(ResponseError::Invalid(error)) if error.reason == "pages start at 1"
+    ));
+
+    set_mock_response("four");
+    assert!(matches!(
+        try_last_page().await,
+        Err(ResponseError::Failed(message)) if message.starts_with("Failed to deserialize response")
+    ));
+}
+
+#[tokio::test]
+async fn test_byte_response_is_read_raw() {
+    clear_mock_state();
+    set_mock_response(vec![137u8, 80, 78, 71]);
+
+    assert_eq!(try_thumbnail(3).await, Ok(vec![137, 80, 78, 71]));
+    let call = get_last_invoke_call().unwrap();
+    assert_eq!(call.command, "thumbnail");
+    assert_eq!(call.args["id"], 3);
+}
+
+#[tokio::test]
+async fn test_raw_response_converts_bytes() {
+    clear_mock_state();
+    set_mock_response(vec![1u8, 2, 3]);
+
+    assert_eq!(read_blob("avatar").await, Ok(Blob(vec![1, 2, 3])));
+}
+
+#[tokio::test]
+async fn test_file_read_in_chunks() {
+    use tauri_bridge_runtime::files::{FileChunk, encode_bytes};
+
+    clear_mock_state();
+    let chunk = |bytes: &[u8]| FileChunk {
+        data: encode_bytes(bytes),
+        size: 6,
+    };
+    set_mock_responses(vec![chunk(b"abcd"), chunk(b"ef")]);
+
+    let mut progress = Vec::new();
+    let bytes =
+        try_project_file_read("notes.txt", |done, total| progress.push((done, total))).await;
+
+    assert_eq!(bytes.unwrap(), b"abcdef");
+    assert_eq!(progress, [(4, 6), (6, 6)]);
+    let calls = get_invoke_calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].command, "project_file_read");
+    assert_eq!(calls[1].args["path"], "notes.txt");
+    assert_eq!(calls[1].args["offset"], 4);
+}
+
+#[tokio::test]
+async fn test_file_write_in_chunks() {
+    use tauri_bridge_runtime::files::decode_bytes;
+
+    clear_mock_state();
+
+    let mut progress = Vec::new();
+    let result = try_project_file_write("notes.txt", b"abcdef", |done, total| {
+        progress.push((done, total))
+    })
+    .await;
+
+    assert!(result.is_ok());
+    assert_eq!(progress, [(4, 6), (6, 6)]);
+    let calls = get_invoke_calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].command, "project_file_write");
+    assert_eq!(calls[1].args["offset"], 4);
+    let data = calls[1].args["data"].as_str().unwrap();
+    assert_eq!(decode_bytes(data).unwrap(), b"ef");
+}
+
+#[tokio::test]
+async fn test_file_write_empty_creates_file() {
+    clear_mock_state();
+
+    assert!(
+        try_project_file_write("empty.txt", &[], |_, _| {})
+            .await
+            .is_ok()
+    );
+    assert_eq!(get_invoke_calls().len(), 1);
+}
@@ -1,6 +1,6 @@
 //! Macro expansion tests - verifies generated code compiles correctly.
 
-use tauri_bridge::tauri_bridge;
+use tauri_bridge::{bridge_error, bridge_file, bridge_form, bridge_metrics_command, tauri_bridge};
 
 #[tauri_bridge]
 pub fn greet(name: &str) -> String {
@@ -54,10 +54,993 @@ pub fn concat(a: &str, b: &str) -> String {
     format!("{}{}", a, b)
 }
 
+/// Original implementation, kept for frontends built before version 2
+#[tauri_bridge(rename = "shout")]
+pub fn shout_v1(text: &str) -> String {
+    text.to_uppercase()
+}
+
+#[tauri_bridge(version = 2)]
+pub fn shout(text: &str, times: usize) -> String {
+    text.to_uppercase().repeat(times)
+}
+
+#[tauri_bridge(strict)]
+pub fn tally(words: Vec<String>) -> std::collections::HashMap<String, u32> {
+    let mut counts = std::collections::HashMap::new();
+    for word in words {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[tauri_bridge(by_value)]
+pub fn double_all(values: &mut [i32], label: &mut String) -> usize {
+    values.iter_mut().for_each(|value| *value *= 2);
+    label.push('!');
+    values.len()
+}
+
+/// Backend sees which webview called it
+#[tauri_bridge]
+pub fn greet_caller(
+    #[caller_label] caller: String,
+    #[target_label] target: Option<String>,
+) -> String {
+    format!("{} -> {:?}", caller, target)
+}
+
+/// Streams the even numbers in batches
+#[tauri_bridge(incremental)]
+pub fn find_even(limit: u32, batches: tauri::ipc::Channel<Vec<u32>>) -> usize {
+    let evens: Vec<u32> = (0..limit).filter(|n| n % 2 == 0).collect();
+    for chunk in evens.chunks(2) {
+        let _ = batches.send(chunk.to_vec());
+    }
+    evens.len()
+}
+
+/// Large responses go out in chunks
+#[tauri_bridge(chunked(chunk_size = 64))]
+pub fn export_log(lines: u32) -> Vec<String> {
+    (0..lines).map(|n| format!("line {}", n)).collect()
+}
+
+/// Payloads over a few bytes are gzipped
+#[tauri_bridge(compress = "gzip", min_size = 16)]
+pub fn repeat_line(line: &str, times: usize) -> Vec<String> {
+    vec![line.to_string(); times]
+}
+
+/// Only compiled for the platform the tests run on
+#[tauri_bridge(platforms("desktop"))]
+pub fn window_count() -> usize {
+    1
+}
+
+/// Raw identifier parameters survive the wrapper command
+#[tauri_bridge(chunked)]
+pub fn kinds(r#type: &str, #[allow(unused_mut)] mut r#ref: u32) -> Vec<String> {
+    r#ref += 1;
+    vec![format!("{}:{}", r#type, r#ref)]
+}
+
+/// Keyword function names need raw identifiers
+#[tauri_bridge]
+pub fn r#match(pattern: &str, r#type: &str) -> bool {
+    r#type.contains(pattern)
+}
+
+/// Byte responses skip JSON
+#[tauri_bridge]
+pub fn checksum_bytes(data: String) -> Result<Vec<u8>, String> {
+    if data.is_empty() {
+        return Err("nothing to checksum".to_string());
+    }
+    Ok(data.bytes().map(|b| b.wrapping_mul(31)).collect())
+}
+
+/// Files under the temp directory
+#[bridge_file(chunk_size = 4)]
+pub fn temp_file(path: &str) -> Result<std::path::PathBuf, String> {
+    if path.contains("..") {
+        return Err("path outside the temp directory".to_string());
+    }
+    Ok(std::env::temp_dir().join(path))
+}
+
+/// Settings kept in memory
+#[bridge_form(load = read_editor_settings, save = write_editor_settings)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorSettings {
+    pub tab_width: u8,
+    pub wrap: bool,
+}
+
+thread_local! {
+    static EDITOR_SETTINGS: std::cell::RefCell<EditorSettings> =
+        const { std::cell::RefCell::new(EditorSettings { tab_width: 4, wrap: false }) };
+}
+
+fn read_editor_settings() -> Result<EditorSettings, String> {
+    Ok(EDITOR_SETTINGS.with(|settings| settings.borrow().clone()))
+}
+
+fn write_editor_settings(value: EditorSettings) -> Result<(), String> {
+    if value.tab_width == 0 {
+        return Err("tab width must be at least 1".to_string());
+    }
+    EDITOR_SETTINGS.with(|settings| *settings.borrow_mut() = value);
+    Ok(())
+}
+
+#[tauri_bridge]
+pub fn push_item(mut items: Vec<i32>, item: i32) -> Vec<i32> {
+    items.push(item);
+    items
+}
+
+#[deprecated(note = "use `add` instead")]
+#[tauri_bridge(log_deprecated)]
+pub fn plus(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[tauri_bridge(debug_log)]
+pub fn checked_div(a: i32, b: i32) -> Result<i32, String> {
+    if b == 0 {
+        return Err("division by zero".to_string());
+    }
+    let quotient = a.checked_div(b).ok_or("overflow")?;
+    Ok(quotient)
+}
+
+#[tauri_bridge(debug_log)]
+pub async fn ping() {}
+
+#[tauri_bridge(metrics)]
+pub fn parse_ratio(ratio: String) -> Result<f64, String> {
+    let (a, b) = ratio.split_once(':').ok_or("expected `a:b`")?;
+    let a: f64 = a.parse().map_err(|_| "invalid numerator")?;
+    let b: f64 = b.parse().map_err(|_| "invalid denominator")?;
+    Ok(a / b)
+}
+
+bridge_metrics_command!();
+
+#[tauri_bridge(layers)]
+pub fn archive_note(id: u64) -> Result<u64, String> {
+    if id == 0 {
+        return Err("no such note".to_string());
+    }
+    Ok(id)
+}
+
+#[tauri_bridge(layers)]
+pub fn append_tag(mut tags: Vec<String>, tag: String) -> Vec<String> {
+    tags.push(tag);
+    tags
+}
+
+#[tauri_bridge(layers, rename = "layered_focus")]
+pub async fn focus_note(#[caller_label] caller: String, id: u64) -> String {
+    format!("{}:{}", caller, id)
+}
+
+#[tauri_bridge(catch_panic)]
+pub fn parse_port(text: String) -> u16 {
+    if text.is_empty() {
+        return 0;
+    }
+    text.parse().expect("not a port")
+}
+
+#[tauri_bridge(catch_panic)]
+pub async fn reset_cache(keys: Vec<String>) {
+    assert!(!keys.is_empty(), "no keys to reset");
+}
+
+/// A service managed as Tauri state
+#[derive(Default)]
+pub struct Counter {
+    count: std::sync::atomic::AtomicU32,
+}
+
+#[tauri_bridge(namespace = "counter")]
+impl Counter {
+    /// Add to the count
+    pub fn bump(&self, by: u32) -> u32 {
+        self.count
+            .fetch_add(by, std::sync::atomic::Ordering::SeqCst)
+            + by
+    }
+
+    pub async fn current(&self) -> Result<u32, String> {
+        Ok(self.count.load(std::sync::atomic::Ordering::SeqCst))
+    }
+
+    pub fn reset(&self, #[caller_label] caller: String) -> String {
+        self.count.store(0, std::sync::atomic::Ordering::SeqCst);
+        caller
+    }
+}
+
+/// Storage a generic service is bridged with
+pub trait Tally: Send + Sync + 'static {
+    fn total(&self) -> u64;
+}
+
+#[derive(Default)]
+pub struct FixedTally;
+
+impl Tally for FixedTally {
+    fn total(&self) -> u64 {
+        7
+    }
+}
+
+pub struct Ledger<T> {
+    tally: T,
+}
+
+#[tauri_bridge(concrete(T = FixedTally))]
+impl<T: Tally> Ledger<T> {
+    pub fn ledger_total(&self, scale: u64) -> u64 {
+        self.tally.total() * scale
+    }
+}
+
+#[tauri_bridge(non_send)]
+pub async fn render_preview(markdown: String) -> String {
+    let lines = std::rc::Rc::new(markdown.lines().count());
+    std::future::ready(()).await;
+    format!("{} lines", lines)
+}
+
+#[tauri_bridge(catch_panic, layers, debug_log)]
+pub fn checked_rename(#[caller_label] caller: String, name: String) -> Result<String, String> {
+    if name.is_empty() {
+        return Err(format!("empty name from {}", caller));
+    }
+    Ok(name.to_uppercase())
+}
+
+#[tauri_bridge(max_payload = "64B")]
+pub async fn save_comment(thread: u32, text: &str) -> Result<usize, String> {
+    if text.is_empty() {
+        return Err(format!("empty comment in {}", thread));
+    }
+    Ok(text.len())
+}
+
+#[tauri_bridge(verify_schema)]
+pub fn pin_comment(thread: u32, pinned: bool) -> Result<bool, String> {
+    if thread == 0 {
+        return Err("no thread 0".to_string());
+    }
+    Ok(pinned)
+}
+
+#[tauri_bridge]
+pub fn tenant_note(ctx: tauri_bridge_runtime::caller::CallerContext, id: u64) -> String {
+    format!("{}/{}", ctx.window_label, id)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserSession {
+    pub user: String,
+}
+
+#[tauri_bridge]
+pub fn rename_account(#[session] session: UserSession, name: String) -> Result<String, String> {
+    if name.is_empty() {
+        return Err(format!("{} needs a name", session.user));
+    }
+    Ok(name)
+}
+
+#[tauri_bridge(catch_panic)]
+pub async fn account_owner(
+    #[caller_label] caller: String,
+    #[session] session: UserSession,
+) -> String {
+    format!("{}@{}", session.user, caller)
+}
+
+#[tauri_bridge]
+pub fn read_setting(key: String) -> anyhow::Result<u32> {
+    use anyhow::Context;
+
+    let value: u32 = key
+        .strip_prefix("port=")
+        .context("not a port setting")?
+        .parse()
+        .context("invalid port")?;
+    if value == 0 {
+        anyhow::bail!("port 0 is reserved");
+    }
+    Ok(value)
+}
+
+#[tauri_bridge]
+pub async fn sync_settings(count: u32) -> Result<u32, anyhow::Error> {
+    if count == 0 {
+        return Err(anyhow::anyhow!("nothing to sync"));
+    }
+    Ok(count)
+}
+
+#[bridge_error]
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum NoteError {
+    #[error("note {0} not found")]
+    NotFound(u64),
+    #[bridge_error(code = "E_LOCKED")]
+    #[error("note is locked by {by}")]
+    Locked { by: String },
+    #[error("storage failed")]
+    Storage,
+}
+
+#[tauri_bridge]
+pub fn open_note(id: u64) -> Result<String, NoteError> {
+    match id {
+        0 => Err(NoteError::NotFound(id)),
+        1 => Err(NoteError::Locked {
+            by: "sam".to_string(),
+        }),
+        _ => Ok(format!("note {}", id)),
+    }
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AppError {
+    pub kind: String,
+    pub message: String,
+}
+
+impl AppError {
+    pub fn internal(error: impl std::fmt::Display) -> Self {
+        AppError {
+            kind: "internal".to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<std::num::ParseIntError> for AppError {
+    fn from(error: std::num::ParseIntError) -> Self {
+        AppError {
+            kind: "invalid_input".to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+#[tauri_bridge(into_error = AppError)]
+pub fn parse_limit(text: String) -> Result<u32, std::num::ParseIntError> {
+    let limit = text.trim().parse()?;
+    Ok(limit)
+}
+
+#[tauri_bridge(map_err = "AppError::internal")]
+pub async fn load_config(path: String) -> std::io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+/// Compiled out on both sides
+#[tauri_bridge]
+#[cfg(any())]
+pub fn never_compiled() -> String {
+    unreachable!()
+}
+
+/// Behind a Cargo feature this crate doesn't have
+#[allow(unexpected_cfgs)]
+mod experimental {
+    use tauri_bridge::tauri_bridge;
+
+    #[tauri_bridge(feature = "experimental-search")]
+    pub fn search(query: &str) -> Vec<String> {
+        vec![query.to_string()]
+    }
+}
+
+/// Commands grouped under `users.` on the wire
+pub mod users {
+    use tauri_bridge::tauri_bridge;
+
+    #[tauri_bridge(namespace = "users")]
+    pub fn get(id: u64) -> String {
+        format!("user {}", id)
+    }
+
+    #[tauri_bridge(namespace = "users", rename = "list", version = 2)]
+    pub fn list_users(limit: u32) -> Vec<u64> {
+        (0..limit as u64).collect()
+    }
+}
+
+/// Commands namespaced by their module path, `admin.roles.count`
+#[tauri_bridge]
+pub mod admin {
+    pub mod roles {
+        pub fn count(user: u64) -> u32 {
+            user as u32 % 3
+        }
+    }
+}
+
+/// Renamed from `fetch_note`, which older frontends still invoke
+#[tauri_bridge(alias = "fetch_note", alias = "notes.fetch")]
+pub fn load_note(id: u64) -> String {
+    format!("note {}", id)
+}
+
+/// Callers may leave out the trailing parameters
+#[tauri_bridge]
+pub fn search_notes(
+    query: String,
+    #[bridge(default)] tags: Option<Vec<String>>,
+    #[bridge(default = "20")] limit: u32,
+) -> Vec<String> {
+    let tags = tags.unwrap_or_default();
+    vec![format!("{} {:?} {}", query, tags, limit)]
+}
+
+/// Integer keys survive the trip as `[key, value]` pairs
+#[tauri_bridge(map_as = "entries")]
+pub fn merge_tallies(
+    #[bridge(map_as = "entries")] tallies: std::collections::HashMap<u32, u64>,
+    bonus: u64,
+) -> std::collections::BTreeMap<u32, u64> {
+    tallies
+        .into_iter()
+        .map(|(id, count)| (id, count + bonus))
+        .collect()
+}
+
+/// Arguments sent as plain JSON objects
+#[tauri_bridge(json_compatible)]
+pub fn tag_fields(fields: std::collections::HashMap<String, Option<String>>) -> usize {
+    fields.values().flatten().count()
+}
+
+/// Non-finite samples travel as strings
+#[tauri_bridge(floats = "string")]
+pub fn scale_samples(samples: Vec<f64>, factor: f64) -> Vec<f64> {
+    samples.iter().map(|sample| sample * factor).collect()
+}
+
+#[allow(unexpected_cfgs)]
+pub fn bridge_version_map() -> std::collections::BTreeMap<&'static str, u32> {
+    tauri_bridge::bridge_version_map![
+        greet,
+        shout_v1,
+        shout,
+        #[cfg(any())]
+        never_compiled,
+        #[cfg(feature = "experimental-search")]
+        experimental::search,
+        users::get,
+        users::list_users,
+        admin::roles::count
+    ]
+}
+
+pub fn bridge_spec() -> String {
+    tauri_bridge::bridge_spec![
+        greet,
+        add,
+        divide,
+        #[cfg(any())]
+        never_compiled
+    ]
+}
+
+/// Ids of the drafts undone by rolled back transactions
+static DISCARDED_DRAFTS: std::sync::Mutex<Vec<u64>> = std::sync::Mutex::new(Vec::new());
+
+#[tauri_bridge(transaction(undo = discard_draft))]
+pub async fn create_draft(title: String) -> Result<u64, String> {
+    if title.is_empty() {
+        return Err("drafts need a title".to_string());
+    }
+    Ok(title.len() as u64)
+}
+
+async fn discard_draft(id: u64) {
+    DISCARDED_DRAFTS.lock().unwrap().push(id);
+}
+
+#[tauri_bridge(transaction)]
+pub fn label_draft(draft_id: u64, label: &str) -> String {
+    format!("{}#{}", draft_id, label)
+}
+
+tauri_bridge::bridge_transaction![create_draft, label_draft];
+
+/// Samples of these types pass through both sides at startup
+#[tauri_bridge(selfcheck)]
+pub fn rename_draft(draft_id: u64, title: &str) -> Result<String, String> {
+    Ok(format!("{}: {}", draft_id, title))
+}
+
+tauri_bridge::bridge_selfcheck![rename_draft];
+
+/// Fetched with the other startup values in one invoke
+#[tauri_bridge(bootstrap, cached)]
+pub async fn list_draft_labels() -> Vec<String> {
+    vec!["inbox".to_string(), "later".to_string()]
+}
+
+#[tauri_bridge(bootstrap, sync_cache = "startup")]
+pub fn get_draft_quota() -> Result<u32, String> {
+    Err("no quota".to_string())
+}
+
+tauri_bridge::bridge_bootstrap![list_draft_labels, get_draft_quota];
+
+tauri_bridge::bridge_introspect![
+    greet,
+    shout,
+    #[cfg(any())]
+    never_compiled
+];
+
 #[cfg(not(target_arch = "wasm32"))]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bridge_spec_document() {
+        let spec: serde_json::Value = serde_json::from_str(&bridge_spec()).unwrap();
+
+        assert_eq!(spec["openrpc"], "1.2.6");
+        assert_eq!(spec["info"]["title"], "tauri-bridge");
+        let methods = spec["methods"].as_array().unwrap();
+        assert_eq!(methods.len(), 3);
+        assert_eq!(methods[0]["name"], "greet");
+        assert_eq!(methods[1]["params"][1]["name"], "b");
+        assert_eq!(methods[2]["result"]["schema"]["type"], "integer");
+        assert_eq!(methods[2]["x-error"]["type"], "string");
+    }
+
+    #[test]
+    fn test_bridge_selfcheck_command() {
+        use tauri_bridge_runtime::selfcheck::Echo;
+
+        let check = __bridge_selfcheck(None);
+        assert_eq!(check.samples.len(), 1);
+        assert_eq!(check.samples[0].command, "rename_draft");
+        assert_eq!(check.samples[0].response, serde_json::json!(""));
+
+        let check = __bridge_selfcheck(Some(vec![Echo {
+            command: "rename_draft".to_string(),
+            args: serde_json::json!({ "draftId": 0, "title": "" }),
+            response: "\"\"".to_string(),
+        }]));
+        assert_eq!(check.report.checked, ["rename_draft"]);
+        assert!(check.report.is_ok());
+
+        // A client sending snake_case keys
+        let check = __bridge_selfcheck(Some(vec![Echo {
+            command: "rename_draft".to_string(),
+            args: serde_json::json!({ "draft_id": 0, "title": "" }),
+            response: "\"\"".to_string(),
+        }]));
+        assert_eq!(check.report.mismatches.len(), 1);
+        assert_eq!(check.report.mismatches[0].part, "argument `draftId`");
+    }
+
+    #[tokio::test]
+    async fn test_bridge_bootstrap_command() {
+        use tauri_bridge_runtime::bootstrap::Loaded;
+
+        let bundle = __bridge_bootstrap().await;
+
+        assert_eq!(
+            bundle.loaded,
+            [
+                Loaded::new("list_draft_labels", &["inbox", "later"]),
+                Loaded::failed("get_draft_quota", &"no quota"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bridge_introspect_command() {
+        let manifest = __bridge_introspect();
+
+        assert_eq!(manifest, bridge_manifest());
+        assert_eq!(manifest.commands.len(), 2);
+        assert_eq!(manifest.commands[0].name, "greet");
+        assert_eq!(manifest.commands[1].name, "shout");
+        assert_eq!(manifest.commands[1].version, 2);
+        assert_eq!(manifest.check(&manifest), Ok(()));
+    }
+
+    #[test]
+    fn test_bridge_version_map() {
+        let versions = bridge_version_map();
+
+        assert_eq!(versions.len(), 5);
+        assert_eq!(versions["greet"], 1);
+        assert_eq!(versions["shout"], 2);
+        assert_eq!(versions["users.get"], 1);
+        assert_eq!(versions["users.list"], 2);
+        assert_eq!(users::get(7), "user 7");
+        assert_eq!(versions["admin.roles.count"], 1);
+        assert_eq!(admin::roles::count(7), 1);
+    }
+
+    #[test]
+    fn test_default_markers_leave_the_backend() {
+        assert_eq!(search_notes("todo".to_string(), None, 5), ["todo [] 5"]);
+    }
+
+    #[test]
+    fn test_map_entries_commands_take_and_return_pairs() {
+        use tauri_bridge_runtime::entries::Entries;
+
+        let tallies = Entries(std::collections::HashMap::from([(3, 4), (9, 0)]));
+        let merged = merge_tallies(tallies, 1);
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::json!([[3, 5], [9, 1]])
+        );
+        let args: Entries<std::collections::HashMap<u32, u64>> =
+            serde_json::from_value(serde_json::json!([[3, 4]])).unwrap();
+        assert_eq!(merge_tallies(args, 0).0[&3], 4);
+    }
+
+    #[test]
+    fn test_floats_commands_encode_non_finite_values() {
+        let args: tauri_bridge_runtime::floats::Floats<
+            Vec<f64>,
+            tauri_bridge_runtime::floats::AsString,
+        > = serde_json::from_value(serde_json::json!([1.0, "Infinity"])).unwrap();
+        let factor = serde_json::from_value(serde_json::json!(-2.0)).unwrap();
+        let scaled = scale_samples(args, factor);
+        assert_eq!(
+            serde_json::to_value(&scaled).unwrap(),
+            serde_json::json!([-2.0, "-Infinity"])
+        );
+    }
+
+    #[test]
+    fn test_json_compatible_backend_is_unchanged() {
+        let fields = serde_json::from_value(serde_json::json!({ "a": "x", "b": null })).unwrap();
+        assert_eq!(tag_fields(fields), 1);
+    }
+
+    #[test]
+    fn test_alias_commands_run_the_body() {
+        assert_eq!(load_note(4), "note 4");
+        // The hidden commands registered under the aliases
+        assert_eq!(__tauri_alias_load_note_0(4), "note 4");
+        assert_eq!(__tauri_alias_load_note_1(4), "note 4");
+    }
+
+    #[test]
+    fn test_versioned_functions_keep_rust_names() {
+        assert_eq!(shout_v1("hey"), "HEY");
+        assert_eq!(shout("hey", 2), "HEYHEY");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_command_still_callable() {
+        assert_eq!(plus(2, 3), 5);
+    }
+
+    #[tokio::test]
+    async fn test_debug_log_command_keeps_returns() {
+        assert_eq!(checked_div(7, 2), Ok(3));
+        assert_eq!(checked_div(1, 0), Err("division by zero".to_string()));
+        assert_eq!(checked_div(i32::MIN, -1), Err("overflow".to_string()));
+        ping().await;
+    }
+
+    #[test]
+    fn test_metered_command_is_recorded() {
+        assert_eq!(parse_ratio("3:4".to_string()), Ok(0.75));
+        assert!(parse_ratio("3".to_string()).is_err());
+
+        let snapshot = get_bridge_metrics();
+        let metrics = &snapshot.commands["parse_ratio"];
+        assert_eq!(metrics.calls, 2);
+        assert_eq!(metrics.errors, 1);
+        assert_eq!(metrics.buckets.iter().sum::<u64>(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic_command_responds_with_panics() {
+        use tauri_bridge_runtime::panic::Caught;
+
+        assert_eq!(parse_port("8080".to_string()), Caught::Value(8080));
+        assert_eq!(parse_port(String::new()), Caught::Value(0));
+        assert!(parse_port("http".to_string()).is_panicked());
+
+        assert_eq!(reset_cache(vec!["a".to_string()]).await, Caught::Value(()));
+        assert_eq!(
+            reset_cache(vec![]).await,
+            Caught::Panicked {
+                message: "no keys to reset".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_service_methods_become_state_commands() {
+        let counter = Counter::default();
+        assert_eq!(counter.bump(2), 2);
+
+        let _: fn(tauri::State<'_, Counter>, u32) -> u32 = bump;
+        let _: fn(tauri::State<'_, Counter>, tauri::Webview) -> String = reset;
+        let _ = current;
+    }
+
+    #[test]
+    fn test_generic_service_commands_use_concrete_types() {
+        let ledger = Ledger { tally: FixedTally };
+        assert_eq!(ledger.ledger_total(2), 14);
+
+        let _: fn(tauri::State<'_, Ledger<FixedTally>>, u64) -> u64 = ledger_total;
+    }
+
+    #[tokio::test]
+    async fn test_max_payload_leaves_the_backend_unchanged() {
+        assert_eq!(save_comment(1, "nice").await, Ok(4));
+        assert!(save_comment(1, "").await.is_err());
+        // Only the client measures the arguments
+        assert_eq!(save_comment(1, &"a".repeat(100)).await, Ok(100));
+    }
+
+    #[test]
+    fn test_verify_schema_command_rejects_other_builds() {
+        assert!(matches!(pin_comment(1, true, None), Ok(true)));
+        assert!(pin_comment(0, true, None).is_err());
+
+        let error = pin_comment(1, true, Some("0000000000000000".to_string())).unwrap_err();
+        let message = error.0.as_str().unwrap();
+        assert!(message.starts_with(
+            "`pin_comment` was called by a client built against another version of it"
+        ));
+    }
+
+    #[test]
+    fn test_caller_context_filled_from_webview() {
+        let _: fn(tauri::Webview, u64) -> String = tenant_note;
+    }
+
+    #[test]
+    fn test_session_commands_take_token() {
+        let _: fn(
+            tauri::Webview,
+            Option<String>,
+            String,
+        ) -> Result<String, tauri::ipc::InvokeError> = rename_account;
+        let _ = account_owner;
+    }
+
+    #[tokio::test]
+    async fn test_non_send_command_is_send() {
+        fn assert_send<T: Send>(future: T) -> T {
+            future
+        }
+
+        let preview = assert_send(render_preview("a\nb".to_string())).await;
+        assert_eq!(preview, "2 lines");
+    }
+
+    #[tokio::test]
+    async fn test_transaction_runs_steps_and_rolls_back() {
+        use serde_json::json;
+        use tauri_bridge_runtime::transaction::{Outcome, Step};
+
+        fn assert_send<T: Send>(future: T) -> T {
+            future
+        }
+        let step = |command: &str, args| Step {
+            command: command.to_string(),
+            args,
+        };
+
+        let outcome = assert_send(__bridge_transaction(vec![
+            step("create_draft", json!({ "title": "Groceries" })),
+            step("label_draft", json!({ "draftId": 9, "label": "home" })),
+        ]))
+        .await;
+        assert_eq!(outcome, Outcome::Committed(vec![json!(9), json!("9#home")]));
+        assert!(DISCARDED_DRAFTS.lock().unwrap().is_empty());
+
+        let outcome = __bridge_transaction(vec![
+            step("create_draft", json!({ "title": "Todo" })),
+            step("label_draft", json!({ "draftId": 4, "label": "work" })),
+            step("create_draft", json!({ "title": "Groceries" })),
+            step("create_draft", json!({ "title": "" })),
+            step("label_draft", json!({ "draftId": 0, "label": "never" })),
+        ])
+        .await;
+        assert_eq!(
+            outcome,
+            Outcome::RolledBack {
+                step: 3,
+                command: "create_draft".to_string(),
+                error: json!("drafts need a title"),
+            }
+        );
+        // Undone in reverse, skipping steps without a hook
+        assert_eq!(*DISCARDED_DRAFTS.lock().unwrap(), [9, 4]);
+
+        let outcome = __bridge_transaction(vec![step("greet", json!({ "name": "Ada" }))]).await;
+        assert_eq!(
+            outcome,
+            Outcome::RolledBack {
+                step: 0,
+                command: "greet".to_string(),
+                error: json!("command greet can't run in a transaction"),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_anyhow_command_reports_error_chain() {
+        use tauri_bridge_runtime::error::ErrorReport;
+
+        assert_eq!(read_setting("port=80".to_string()), Ok(80));
+
+        let report = read_setting("port=http".to_string()).unwrap_err();
+        assert_eq!(report.message, "invalid port");
+        assert_eq!(report.chain, vec!["invalid digit found in string"]);
+        assert_eq!(
+            format!("{:#}", report),
+            "invalid port: invalid digit found in string"
+        );
+        assert_eq!(
+            read_setting("port=0".to_string()),
+            Err(ErrorReport {
+                message: "port 0 is reserved".to_string(),
+                chain: vec![],
+            })
+        );
+
+        assert_eq!(sync_settings(2).await, Ok(2));
+        assert_eq!(
+            sync_settings(0).await.unwrap_err().to_string(),
+            "nothing to sync"
+        );
+    }
+
+    #[test]
+    fn test_bridge_error_serializes_with_codes() {
+        let not_found = open_note(0).unwrap_err();
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_locked());
+        assert_eq!(not_found.code(), "not_found");
+        assert_eq!(not_found.to_string(), "note 0 not found");
+        assert_eq!(
+            serde_json::to_value(&not_found).unwrap(),
+            serde_json::json!({ "code": "not_found", "details": 0 })
+        );
+
+        let locked = open_note(1).unwrap_err();
+        assert_eq!(locked.code(), "E_LOCKED");
+        assert_eq!(
+            serde_json::to_value(&locked).unwrap(),
+            serde_json::json!({ "code": "E_LOCKED", "details": { "by": "sam" } })
+        );
+
+        let storage: NoteError = serde_json::from_str(r#"{"code":"storage"}"#).unwrap();
+        assert_eq!(storage, NoteError::Storage);
+        assert!(storage.is_storage());
+        assert_eq!(NoteError::CODES, ["not_found", "E_LOCKED", "storage"]);
+    }
+
+    #[tokio::test]
+    async fn test_error_conversion_commands_send_app_errors() {
+        assert_eq!(parse_limit(" 25 ".to_string()), Ok(25));
+        assert_eq!(
+            parse_limit("many".to_string()).unwrap_err().kind,
+            "invalid_input"
+        );
+
+        let error = load_config("/nonexistent/tauri-bridge.toml".to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind, "internal");
+    }
+
+    #[test]
+    fn test_strict_command_compiles() {
+        assert_eq!(tally(vec!["a".into(), "a".into()])["a"], 2);
+    }
+
+    #[test]
+    fn test_by_value_command_returns_modified_args() {
+        let (len, values, label) = double_all(vec![1, 2], "hi".to_string());
+
+        assert_eq!(len, 2);
+        assert_eq!(values, vec![2, 4]);
+        assert_eq!(label, "hi!");
+    }
+
+    #[test]
+    fn test_compressed_command_round_trips() {
+        use tauri_bridge_runtime::compress::{Payload, decode};
+
+        let args = Payload {
+            gzip: false,
+            data: r#"{"line":"log","times":100}"#.into(),
+        };
+
+        let response = repeat_line(args).unwrap();
+
+        assert!(response.gzip);
+        let lines: Vec<String> = serde_json::from_str(&decode(response).unwrap()).unwrap();
+        assert_eq!(lines, vec!["log"; 100]);
+    }
+
+    #[test]
+    fn test_raw_identifier_command() {
+        assert!(r#match("ab", "cabd"));
+    }
+
+    #[test]
+    fn test_platform_command_exists_on_desktop() {
+        assert_eq!(window_count(), 1);
+    }
+
+    #[test]
+    fn test_byte_command_returns_raw_response() {
+        let _response: tauri::ipc::Response = checksum_bytes("ab".into()).unwrap();
+
+        assert_eq!(
+            checksum_bytes(String::new()).err().unwrap(),
+            "nothing to checksum"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_commands_transfer_chunks() {
+        use tauri_bridge_runtime::files::{decode_bytes, encode_bytes};
+
+        let name = format!("tauri-bridge-expansion-{}.txt", std::process::id());
+        temp_file_write(name.clone(), 0, encode_bytes(b"hello"))
+            .await
+            .unwrap();
+
+        let chunk = temp_file_read(name.clone(), 4).await.unwrap();
+
+        assert_eq!(chunk.size, 5);
+        assert_eq!(decode_bytes(&chunk.data).unwrap(), b"o");
+        assert!(temp_file_read("../etc/passwd".into(), 0).await.is_err());
+        std::fs::remove_file(temp_file(&name).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_form_commands_load_and_save() {
+        let mut settings = load_editor_settings().unwrap();
+        settings.wrap = true;
+        save_editor_settings(settings.clone()).unwrap();
+
+        assert_eq!(load_editor_settings().unwrap(), settings);
+        assert_eq!(
+            save_editor_settings(EditorSettings {
+                tab_width: 0,
+                wrap: true
+            })
+            .unwrap_err(),
+            "tab width must be at least 1"
+        );
+    }
+
+    #[test]
+    fn test_mut_argument_binding() {
+        assert_eq!(push_item(vec![1], 2), vec![1, 2]);
+    }
+
     #[test]
     fn test_greet_function_exists() {
         assert_eq!(greet("World"), "Hello, World!");
@@ -6,6 +6,7 @@
 
 use serde::{Deserialize, Serialize};
 use tauri_bridge::tauri_bridge;
+use tauri_bridge_runtime::state::BridgeState;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct User {
@@ -307,6 +308,104 @@ fn test_special_characters_handling() {
     );
 }
 
+/// Behind a Cargo feature this crate doesn't have
+#[allow(unexpected_cfgs)]
+mod experimental {
+    use tauri_bridge::tauri_bridge;
+
+    #[tauri_bridge(feature = "experimental-search")]
+    pub fn search(query: &str) -> Vec<String> {
+        vec![query.to_string()]
+    }
+}
+
+/// Runs between the hooks of the managed layers
+#[tauri_bridge(layers)]
+pub fn archive_note(id: u64) -> Result<u64, String> {
+    Ok(id)
+}
+
+#[tauri_bridge(layers)]
+pub async fn ping_layers() {}
+
+static PROJECT_LINES: std::sync::Mutex<std::collections::BTreeMap<u64, Vec<String>>> =
+    std::sync::Mutex::new(std::collections::BTreeMap::new());
+
+/// Appends of one project run one after the other
+#[tauri_bridge(serialize_on = "project_id")]
+pub async fn append_line(project_id: u64, line: String) -> usize {
+    let mut lines = PROJECT_LINES
+        .lock()
+        .unwrap()
+        .get(&project_id)
+        .cloned()
+        .unwrap_or_default();
+    // Another append saving in between would be lost without the queue
+    tokio::task::yield_now().await;
+    lines.push(line);
+    let count = lines.len();
+    PROJECT_LINES.lock().unwrap().insert(project_id, lines);
+    count
+}
+
+#[tauri_bridge(serialize_on = "project_id")]
+pub async fn project_lines(project_id: u64) -> Vec<String> {
+    PROJECT_LINES
+        .lock()
+        .unwrap()
+        .get(&project_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_serialized_calls_run_in_order() {
+    let counts = tokio::join!(
+        append_line(7, "first".to_string()),
+        append_line(7, "second".to_string()),
+        append_line(8, "other".to_string()),
+        append_line(7, "third".to_string()),
+    );
+
+    assert_eq!(counts, (1, 2, 1, 3));
+    assert_eq!(project_lines(7).await, vec!["first", "second", "third"]);
+    assert_eq!(project_lines(8).await, vec!["other"]);
+}
+
+#[derive(Default)]
+pub struct TodoList {
+    pub items: Vec<String>,
+}
+
+/// Locks the managed `BridgeState<TodoList>` for the body
+#[tauri_bridge]
+pub fn add_todo(todos: BridgeState<TodoList>, title: String) -> usize {
+    todos.items.push(title);
+    todos.items.len()
+}
+
+/// Scoped to the invoking window
+#[tauri_bridge]
+pub fn tenant_name(ctx: tauri_bridge_runtime::caller::CallerContext) -> String {
+    ctx.window_label
+}
+
+pub struct UserSession {
+    pub user: String,
+}
+
+/// Takes the session of the managed `SessionValidator<UserSession>`
+#[tauri_bridge]
+pub fn whoami(#[session] session: UserSession) -> String {
+    session.user
+}
+
+/// Also served as `get_greeting`, its name before a rename
+#[tauri_bridge(alias = "get_greeting")]
+pub fn greeting(name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
 #[test]
 fn test_commands_can_be_registered() {
     fn accepts_handler<F: Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static>(
@@ -327,5 +426,30 @@ fn test_commands_can_be_registered() {
         noop,
         async_greet,
         async_fetch_user,
+        archive_note,
+        ping_layers,
+        append_line,
+        project_lines,
+        add_todo,
+        whoami,
+        tenant_name,
+    ]);
+}
+
+#[test]
+fn test_bridge_handler_leaves_out_disabled_features() {
+    fn accepts_handler<F: Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static>(
+        _: F,
+    ) {
+    }
+
+    // `experimental::search` has no backend without its feature
+    accepts_handler(tauri_bridge::bridge_handler![
+        greet,
+        add_numbers,
+        greeting,
+        experimental::search,
+        #[cfg(not(target_os = "none"))]
+        get_user,
     ]);
 }
@@ -0,0 +1,286 @@
+//! Round trips from the generated client functions, compiled natively,
+//! through `bridge_dispatch!` to the real backend functions.
+//!
+//! Run with: cargo test --test test_harness
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Note {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub pinned: Option<bool>,
+}
+
+pub mod api {
+    use super::Note;
+    #[allow(unused_imports)]
+    use crate::serde_wasm_bindgen;
+    use tauri_bridge::tauri_bridge;
+
+    #[tauri_bridge(test_harness, client_mod = crate::client, client_cfg(test))]
+    pub fn create_note(title: &str, tag_list: &[String], pinned: Option<bool>) -> Note {
+        Note {
+            title: title.to_string(),
+            tags: tag_list.to_vec(),
+            pinned,
+        }
+    }
+
+    #[tauri_bridge(
+        test_harness,
+        rename = "note_count",
+        client_mod = crate::client,
+        client_cfg(test)
+    )]
+    pub async fn count_notes(notes: Vec<Note>) -> usize {
+        notes.len()
+    }
+
+    #[tauri_bridge(test_harness, client_mod = crate::client, client_cfg(test))]
+    pub fn parse_id(input: &str) -> Result<u32, String> {
+        input.parse().map_err(|_| format!("not an id: {}", input))
+    }
+
+    // The app's command forwards to the sidecar; dispatch runs the body
+    #[tauri_bridge(sidecar = "text", client_mod = crate::client, client_cfg(test))]
+    pub fn word_count(text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+}
+
+pub mod client {
+    tauri_bridge::bridge_include!(
+        pub crate::api::create_note,
+        crate::api::count_notes,
+        crate::api::word_count,
+    );
+}
+
+#[derive(Debug, Clone)]
+pub struct JsValue(serde_json::Value);
+
+mod serde_wasm_bindgen {
+    use super::*;
+
+    pub fn to_value<T: Serialize>(value: &T) -> Result<JsValue, String> {
+        serde_json::to_value(value)
+            .map(JsValue)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn from_value<T: for<'de> Deserialize<'de>>(value: JsValue) -> Result<T, String> {
+        serde_json::from_value(value.0).map_err(|e| e.to_string())
+    }
+}
+
+pub async fn invoke(command: &str, args: JsValue) -> JsValue {
+    let dispatch = tauri_bridge::bridge_dispatch![
+        api::create_note,
+        api::count_notes,
+        api::parse_id,
+        api::word_count
+    ];
+    match dispatch(command, args.0).await {
+        Ok(response) => JsValue(response),
+        Err(error) => panic!("invoke of `{}` rejected: {}", command, error),
+    }
+}
+
+#[tokio::test]
+async fn test_client_round_trips_through_backend() {
+    let tags = vec!["work".to_string(), "urgent".to_string()];
+
+    let note = client::try_create_note("Plan", &tags, Some(true))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        note,
+        Note {
+            title: "Plan".to_string(),
+            tags,
+            pinned: Some(true),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_renamed_async_command_round_trips() {
+    let note = client::create_note("Plan", &[], None).await;
+
+    assert_eq!(client::count_notes(vec![note.clone(), note]).await, 2);
+}
+
+#[tokio::test]
+async fn test_sidecar_command_dispatches_to_body() {
+    assert_eq!(client::word_count("ferris the crab").await, 3);
+    assert_eq!(api::__tauri_bridge_sidecar_word_count("a b"), 2);
+}
+
+#[tokio::test]
+async fn test_dispatch_decodes_like_tauri() {
+    let dispatch = tauri_bridge::bridge_dispatch![api::create_note, api::parse_id];
+
+    // Arguments are looked up in camelCase and missing options are `None`
+    let note = dispatch(
+        "create_note",
+        serde_json::json!({ "title": "Plan", "tagList": ["a"] }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(note["tags"], serde_json::json!(["a"]));
+    assert_eq!(note["pinned"], serde_json::Value::Null);
+
+    assert_eq!(
+        dispatch("create_note", serde_json::json!({ "title": "Plan" })).await,
+        Err(serde_json::json!(
+            "invalid args `tagList` for command `create_note`: invalid type: null, expected a sequence"
+        ))
+    );
+    assert_eq!(
+        dispatch("parse_id", serde_json::json!({ "input": "42" })).await,
+        Ok(serde_json::json!(42))
+    );
+    assert_eq!(
+        dispatch("parse_id", serde_json::json!({ "input": "x" })).await,
+        Err(serde_json::json!("not an id: x"))
+    );
+    assert_eq!(
+        dispatch("count_notes", serde_json::Value::Null).await,
+        Err(serde_json::json!("command count_notes not found"))
+    );
+}
+
+#[tokio::test]
+async fn test_bench_times_each_command() {
+    let title = "Plan";
+    let reports = tauri_bridge::bridge_bench!(3;
+        api::create_note => serde_json::json!({ "title": title, "tagList": ["a"] }),
+        api::parse_id => serde_json::json!({ "input": "42" }),
+    )
+    .await
+    .unwrap();
+
+    let commands: Vec<_> = reports.iter().map(|report| report.command).collect();
+    assert_eq!(commands, ["create_note", "parse_id"]);
+    assert!(reports.iter().all(|report| report.iterations == 3));
+    assert_eq!(reports[1].args_bytes, r#"{"input":"42"}"#.len());
+    assert_eq!(reports[1].response_bytes, 2);
+
+    let rejected =
+        tauri_bridge::bridge_bench!(1; api::parse_id => serde_json::json!({ "input": "x" })).await;
+    assert_eq!(rejected, Err(serde_json::json!("not an id: x")));
+}
+
+#[tokio::test]
+async fn test_contract_checks_recorded_calls() {
+    use tauri_bridge_runtime::replay::Recording;
+
+    let session = |calls: serde_json::Value| {
+        Recording::from_json(&serde_json::json!({ "calls": calls }).to_string()).unwrap()
+    };
+    let call = |command: &str, args: serde_json::Value, response: serde_json::Value| {
+        serde_json::json!({
+            "command": command,
+            "args": args,
+            "response": response,
+            "atMs": 0.0,
+            "durationMs": 1.0,
+        })
+    };
+
+    let recorded = session(serde_json::json!([
+        call(
+            "create_note",
+            serde_json::json!({ "title": "Plan", "tagList": ["a"] }),
+            serde_json::json!({ "title": "Plan", "tags": ["a"], "pinned": null }),
+        ),
+        call(
+            "parse_id",
+            serde_json::json!({ "input": "42" }),
+            serde_json::json!(42)
+        ),
+    ]));
+    let checked = tauri_bridge::bridge_contract!(recorded; api::create_note, api::parse_id)
+        .await
+        .unwrap();
+    assert_eq!(checked, 2);
+
+    // Recorded by a frontend expecting other shapes
+    let outdated = session(serde_json::json!([
+        call(
+            "create_note",
+            serde_json::json!({ "title": "Plan", "tagList": [] }),
+            serde_json::json!({ "title": "Plan", "tags": [], "archived": false }),
+        ),
+        call(
+            "parse_id",
+            serde_json::json!({ "input": "42" }),
+            serde_json::json!("42")
+        ),
+        call(
+            "parse_id",
+            serde_json::json!({ "input": "x" }),
+            serde_json::json!(0)
+        ),
+    ]));
+    let error = tauri_bridge::bridge_contract!(outdated; api::create_note, api::parse_id)
+        .await
+        .unwrap_err();
+    let violations: Vec<_> = error.violations.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        violations,
+        [
+            "call 0 of `create_note` at $.archived: missing field",
+            "call 1 of `parse_id` at $: expected a string, got a number",
+            "call 2 of `parse_id` at $: rejected: not an id: x",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_remote_backend_serves_frames() {
+    use tauri_bridge_runtime::remote;
+
+    let frames = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    remote::connect({
+        let frames = frames.clone();
+        move |frame| {
+            frames.borrow_mut().push(frame);
+            Ok(())
+        }
+    });
+    let parsed = remote::invoke("parse_id", serde_json::json!({ "input": "42" }));
+    let rejected = remote::invoke("parse_id", serde_json::json!({ "input": "x" }));
+
+    // The backend's side of the socket
+    let dispatch = tauri_bridge::bridge_dispatch![api::parse_id];
+    for frame in frames.take() {
+        let request = remote::decode_request(&frame).unwrap();
+        let result = dispatch(&request.command, request.args).await;
+        remote::receive(&remote::encode_response(request.id, result)).unwrap();
+    }
+
+    assert_eq!(parsed.await, Ok(serde_json::json!(42)));
+    assert_eq!(rejected.await, Err(serde_json::json!("not an id: x")));
+    remote::disconnect("done");
+}
+
+#[test]
+fn test_fuzz_target_survives_arbitrary_input() {
+    let fuzz = tauri_bridge::bridge_fuzz![api::create_note, api::parse_id, api::word_count];
+
+    for data in [
+        &b""[..],
+        b"\x00{\"title\":\"Plan\",\"tagList\":[\"a\"]}",
+        b"\x01{\"input\":\"42\"}",
+        b"\x04{\"input\":[]}",
+        b"\x02{\"text\":null}",
+        b"\x05not json",
+    ] {
+        fuzz(data);
+    }
+}